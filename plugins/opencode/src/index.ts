@@ -11,7 +11,8 @@
  * 开发模式：设置 AXON_DEV=true 启用详细日志
  */
 
-import type { Plugin, Hooks } from '@opencode-ai/plugin';
+import { tool, type Plugin, type Hooks, type ToolDefinition } from '@opencode-ai/plugin';
+import type { ZodTypeAny as ZodType } from 'zod';
 import path from 'path';
 import fs from 'fs';
 
@@ -21,10 +22,63 @@ import fs from 'fs';
 
 interface AxonEndpoints {
   baseUrl: string;
+  health: string;
   events: string;
   config: string;
   agents: string;
   orchestrations: string;
+  consent: string;
+  tools: string;
+  context: string;
+  sessions: string;
+  endpointEvents: string;
+}
+
+/** Axon 中设置的会话级 Agent 参数覆盖 */
+interface AxonAgentOverride {
+  /** provider/model 格式 */
+  model?: string;
+  temperature?: number;
+  top_p?: number;
+  tools?: Record<string, boolean>;
+}
+
+/** Axon 推送的当前服务端点 */
+interface AxonEndpointInfo {
+  /** OpenCode 服务地址，服务未运行时为空 */
+  opencodeEndpoint?: string;
+  pluginApiPort: number;
+}
+
+/** Axon 中定义的自定义工具（参数为 JSON Schema） */
+interface AxonToolSpec {
+  name: string;
+  description: string;
+  parameters: JsonSchema;
+}
+
+interface JsonSchema {
+  type?: string | string[];
+  description?: string;
+  enum?: unknown[];
+  items?: JsonSchema;
+  properties?: Record<string, JsonSchema>;
+  required?: string[];
+}
+
+interface ToolExecutionResult {
+  output: string;
+  truncated: boolean;
+  durationMs: number;
+}
+
+/** 用户在 Axon 中固定到上下文的文件 */
+interface PinnedFileContent {
+  path: string;
+  content?: string;
+  size: number;
+  truncated: boolean;
+  error?: string;
 }
 
 interface AxonAgentConfig {
@@ -98,11 +152,23 @@ interface OrchestrationGroup {
   delegationRuleset: DelegationRuleset;
 }
 
+/** 工具执行确认结果 */
+interface ToolConsentResult {
+  allowed: boolean;
+  /** error：无法完成确认（未连接、请求失败或被限流），按拒绝处理 */
+  outcome: 'allowed' | 'denied' | 'timed_out' | 'error';
+  action?: 'file_delete' | 'command_exec';
+}
+
 interface AxonBridgeConfig {
   port: number;
   devMode: boolean;
   agents: Record<string, AxonAgentConfig>;
   disabledAgents: string[];
+  /** 后端当前的 Plugin API 版本（旧版后端没有此字段） */
+  api_version?: number;
+  /** 后端仍兼容的最低 Plugin API 版本 */
+  min_api_version?: number;
 }
 
 interface CommandFrontmatter {
@@ -127,6 +193,22 @@ const DEV_MODE = process.env.AXON_DEV === 'true' || process.env.NODE_ENV === 'de
 const AXON_PORT = parseInt(process.env.AXON_BRIDGE_PORT || '23517', 10);
 const AXON_AGENTS_DIR = process.env.AXON_AGENTS_DIR || '';
 
+/** 插件使用的 Plugin API 版本，需与 Axon 后端支持的版本范围匹配 */
+const PLUGIN_API_VERSION = 1;
+const API_VERSION_HEADER = 'X-Axon-Plugin-Api-Version';
+
+/** 等待用户确认的请求超时（略大于后端的 120 秒确认超时） */
+const CONSENT_TIMEOUT_MS = 130_000;
+
+/** 需要用户确认的工具（与后端 consent::classify_tool 一致） */
+const CONSENT_GATED_TOOLS = new Set(['bash']);
+
+/** 自定义工具执行超时（后端脚本工具最长 300 秒，加上最长 120 秒的用户确认） */
+const TOOL_TIMEOUT_MS = 430_000;
+
+/** 端点事件流断开后的重连间隔 */
+const ENDPOINT_RECONNECT_MS = 5_000;
+
 // ============================================================================
 // 日志模块
 // ============================================================================
@@ -169,12 +251,19 @@ function createLogger(client: PluginClient): Logger {
 
 function buildEndpoints(port: number): AxonEndpoints {
   const baseUrl = `http://127.0.0.1:${port}`;
+  const apiUrl = `${baseUrl}/api/plugin/v${PLUGIN_API_VERSION}`;
   return {
     baseUrl,
-    events: `${baseUrl}/api/plugin/events`,
-    config: `${baseUrl}/api/plugin/config`,
-    agents: `${baseUrl}/api/plugin/agents`,
-    orchestrations: `${baseUrl}/api/plugin/orchestrations`,
+    health: `${apiUrl}/health`,
+    events: `${apiUrl}/events`,
+    config: `${apiUrl}/config`,
+    agents: `${apiUrl}/agents`,
+    orchestrations: `${apiUrl}/orchestrations`,
+    consent: `${apiUrl}/consent`,
+    tools: `${apiUrl}/tools`,
+    context: `${apiUrl}/context`,
+    sessions: `${apiUrl}/sessions`,
+    endpointEvents: `${apiUrl}/endpoint/events`,
   };
 }
 
@@ -188,8 +277,11 @@ function createFetchWithTimeout(logger: Logger) {
     const timeoutId = setTimeout(() => controller.abort(), timeout);
 
     try {
+      const headers = new Headers(options?.headers);
+      headers.set(API_VERSION_HEADER, String(PLUGIN_API_VERSION));
       const response = await fetch(url, {
         ...options,
+        headers,
         signal: controller.signal,
       });
       clearTimeout(timeoutId);
@@ -344,6 +436,7 @@ class AxonBridgeClient {
   private connected = false;
   private config: AxonBridgeConfig | null = null;
   private orchestrations: OrchestrationGroup[] = [];
+  private opencodeEndpoint: string | null = null;
   private logger: Logger;
   private fetchWithTimeout: ReturnType<typeof createFetchWithTimeout>;
 
@@ -356,10 +449,13 @@ class AxonBridgeClient {
   async checkConnection(): Promise<boolean> {
     try {
       const response = await this.fetchWithTimeout(
-        `${this.endpoints.baseUrl}/api/plugin/health`,
+        this.endpoints.health,
         { method: 'GET' },
         2000
       );
+      if (response && !response.ok) {
+        await this.reportVersionMismatch(response);
+      }
       this.connected = response?.ok ?? false;
       this.logger.info(`Axon 后端连接状态: ${this.connected ? '已连接' : '未连接'}`);
       return this.connected;
@@ -382,7 +478,17 @@ class AxonBridgeClient {
     try {
       const response = await this.fetchWithTimeout(this.endpoints.config);
       if (response?.ok) {
-        this.config = await response.json();
+        const config: AxonBridgeConfig = await response.json();
+        const minVersion = config.min_api_version ?? 1;
+        const maxVersion = config.api_version ?? 1;
+        if (PLUGIN_API_VERSION < minVersion || PLUGIN_API_VERSION > maxVersion) {
+          this.logger.error(
+            `Plugin API 版本不匹配：插件使用 v${PLUGIN_API_VERSION}，Axon 支持 v${minVersion} - v${maxVersion}`
+          );
+          this.connected = false;
+          return this.getDefaultConfig();
+        }
+        this.config = config;
         this.logger.info('获取 Axon 配置成功', this.config);
         return this.config;
       }
@@ -452,6 +558,231 @@ class AxonBridgeClient {
     return [];
   }
 
+  /**
+   * 请求执行工具
+   *
+   * 破坏性工具调用会阻塞到用户在 Axon 中确认。需要确认的工具在无法完成确认时
+   * （未连接、请求失败、超时或被限流）一律拒绝；其他工具不经过确认
+   */
+  async requestToolConsent(input: {
+    tool: string;
+    args: unknown;
+    sessionId: string;
+    directory: string;
+  }): Promise<ToolConsentResult> {
+    if (!CONSENT_GATED_TOOLS.has(input.tool)) {
+      return { allowed: true, outcome: 'allowed' };
+    }
+    const failed: ToolConsentResult = { allowed: false, outcome: 'error' };
+    if (!this.connected) {
+      this.logger.warn('未连接 Axon，拒绝需要确认的工具调用', { tool: input.tool });
+      return failed;
+    }
+
+    const response = await this.fetchWithTimeout(
+      this.endpoints.consent,
+      {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(input),
+      },
+      CONSENT_TIMEOUT_MS
+    );
+    if (!response?.ok) {
+      this.logger.warn('请求工具执行确认失败，已拒绝', {
+        tool: input.tool,
+        status: response?.status,
+      });
+      return failed;
+    }
+
+    try {
+      const body = (await response.json()) as { data?: ToolConsentResult };
+      return body.data ?? failed;
+    } catch (error) {
+      this.logger.warn('解析工具执行确认结果失败，已拒绝', { tool: input.tool, error });
+      return failed;
+    }
+  }
+
+  async getTools(): Promise<AxonToolSpec[]> {
+    if (!this.connected) {
+      return [];
+    }
+
+    try {
+      const response = await this.fetchWithTimeout(this.endpoints.tools);
+      if (response?.ok) {
+        const body = (await response.json()) as { data?: AxonToolSpec[]; error?: string };
+        if (body.error) {
+          this.logger.warn('获取自定义工具失败', body.error);
+        }
+        return body.data ?? [];
+      }
+    } catch (error) {
+      this.logger.error('获取自定义工具失败', error);
+    }
+
+    return [];
+  }
+
+  /**
+   * 执行自定义工具，实际执行由 Axon 完成
+   *
+   * Node / Python 脚本工具会先等待用户在 Axon 中确认，`directory` 用于匹配"始终允许"规则
+   */
+  async executeTool(
+    name: string,
+    args: unknown,
+    sessionId: string,
+    directory: string
+  ): Promise<string> {
+    const response = await this.fetchWithTimeout(
+      `${this.endpoints.tools}/${encodeURIComponent(name)}/execute`,
+      {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ args, sessionId, directory }),
+      },
+      TOOL_TIMEOUT_MS
+    );
+    if (!response) {
+      throw new Error(`无法连接 Axon 执行工具 ${name}`);
+    }
+
+    const body = (await response.json().catch(() => null)) as {
+      data?: ToolExecutionResult;
+      error?: string;
+    } | null;
+    if (!response.ok || !body?.data) {
+      throw new Error(body?.error ?? `工具 ${name} 执行失败（HTTP ${response.status}）`);
+    }
+    return body.data.truncated ? `${body.data.output}\n[输出过长，已截断]` : body.data.output;
+  }
+
+  /**
+   * 获取项目中固定到上下文的文件内容（大小已由 Axon 限制）
+   */
+  async getPinnedContext(directory: string): Promise<PinnedFileContent[]> {
+    if (!this.connected) {
+      return [];
+    }
+
+    try {
+      const response = await this.fetchWithTimeout(
+        `${this.endpoints.context}?project=${encodeURIComponent(directory)}`
+      );
+      if (response?.ok) {
+        const body = (await response.json()) as {
+          data?: { files: PinnedFileContent[] };
+          error?: string;
+        };
+        if (body.error) {
+          this.logger.warn('获取固定文件失败', body.error);
+        }
+        return body.data?.files ?? [];
+      }
+    } catch (error) {
+      this.logger.error('获取固定文件失败', error);
+    }
+
+    return [];
+  }
+
+  /**
+   * 记录版本不匹配错误
+   *
+   * 后端拒绝插件声明的版本时返回 400；不支持带版本号路径的旧版后端返回 404
+   */
+  private async reportVersionMismatch(
+    response: Awaited<ReturnType<typeof fetch>>
+  ): Promise<void> {
+    if (response.status === 400) {
+      const body = (await response.json().catch(() => null)) as { error?: string } | null;
+      this.logger.error(body?.error ?? 'Plugin API 版本不匹配');
+    } else if (response.status === 404 && !response.headers.has(API_VERSION_HEADER)) {
+      this.logger.error(
+        `Plugin API 版本不匹配：当前 Axon 不支持 v${PLUGIN_API_VERSION} 接口，请更新 Axon`
+      );
+    }
+  }
+
+  /** 获取会话中指定 Agent 的参数覆盖 */
+  async getAgentOverride(sessionId: string, agent: string): Promise<AxonAgentOverride | null> {
+    if (!this.connected) return null;
+    const response = await this.fetchWithTimeout(
+      `${this.endpoints.sessions}/${encodeURIComponent(sessionId)}/agent-overrides`,
+      { method: 'GET' },
+      2000
+    );
+    if (!response?.ok) return null;
+    const body = (await response.json().catch(() => null)) as {
+      data?: Record<string, AxonAgentOverride>;
+    } | null;
+    return body?.data?.[agent] ?? null;
+  }
+
+  /**
+   * 订阅 Axon 推送的端点变化
+   *
+   * OpenCode 换端口重启或 Plugin API 端口变化后，环境变量中的端口已过期，
+   * 按推送的端点更新请求地址。连接断开后定时重连
+   */
+  watchEndpoint(): void {
+    const connect = async (): Promise<void> => {
+      try {
+        const response = await fetch(this.endpoints.endpointEvents, {
+          headers: { [API_VERSION_HEADER]: String(PLUGIN_API_VERSION) },
+        });
+        if (!response.ok || !response.body) return;
+
+        const decoder = new TextDecoder();
+        let buffer = '';
+        for await (const chunk of response.body) {
+          buffer += decoder.decode(chunk as Uint8Array, { stream: true });
+          let boundary: number;
+          while ((boundary = buffer.indexOf('\n\n')) !== -1) {
+            const message = buffer.slice(0, boundary);
+            buffer = buffer.slice(boundary + 2);
+            const data = message
+              .split('\n')
+              .filter((line) => line.startsWith('data:'))
+              .map((line) => line.slice(5).trim())
+              .join('');
+            if (data) this.applyEndpoint(JSON.parse(data) as AxonEndpointInfo);
+          }
+        }
+      } catch (error) {
+        this.logger.debug('端点事件流断开', { error: (error as Error).message });
+      }
+    };
+
+    const loop = async (): Promise<void> => {
+      for (;;) {
+        await connect();
+        await new Promise((resolve) => setTimeout(resolve, ENDPOINT_RECONNECT_MS));
+      }
+    };
+    loop().catch(() => {});
+  }
+
+  private applyEndpoint(endpoint: AxonEndpointInfo): void {
+    this.opencodeEndpoint = endpoint.opencodeEndpoint ?? null;
+    const current = new URL(this.endpoints.baseUrl).port;
+    if (endpoint.pluginApiPort && String(endpoint.pluginApiPort) !== current) {
+      this.logger.info('Plugin API 端口已变化', {
+        from: current,
+        to: endpoint.pluginApiPort,
+      });
+      this.endpoints = buildEndpoints(endpoint.pluginApiPort);
+    }
+  }
+
+  /** Axon 最近推送的 OpenCode 服务地址 */
+  getOpencodeEndpoint(): string | null {
+    return this.opencodeEndpoint;
+  }
+
   getCachedOrchestrations(): OrchestrationGroup[] {
     return this.orchestrations;
   }
@@ -466,6 +797,81 @@ class AxonBridgeClient {
   }
 }
 
+// ============================================================================
+// 自定义工具
+// ============================================================================
+
+/**
+ * 将 JSON Schema 转换为 zod 类型（只支持常用子集，其余按任意值处理）
+ */
+function jsonSchemaToZod(schema: JsonSchema): ZodType {
+  const z = tool.schema;
+  const type = Array.isArray(schema.type) ? schema.type[0] : schema.type;
+  let result: ZodType;
+
+  if (schema.enum?.length && schema.enum.every((v) => typeof v === 'string')) {
+    result = z.enum(schema.enum as [string, ...string[]]);
+  } else {
+    switch (type) {
+      case 'string':
+        result = z.string();
+        break;
+      case 'number':
+        result = z.number();
+        break;
+      case 'integer':
+        result = z.number().int();
+        break;
+      case 'boolean':
+        result = z.boolean();
+        break;
+      case 'array':
+        result = z.array(schema.items ? jsonSchemaToZod(schema.items) : z.any());
+        break;
+      case 'object':
+        result = z.object(jsonSchemaToShape(schema)).passthrough();
+        break;
+      default:
+        result = z.any();
+    }
+  }
+
+  return schema.description ? result.describe(schema.description) : result;
+}
+
+function jsonSchemaToShape(schema: JsonSchema): Record<string, ZodType> {
+  const required = new Set(schema.required ?? []);
+  const shape: Record<string, ZodType> = {};
+  for (const [key, property] of Object.entries(schema.properties ?? {})) {
+    const field = jsonSchemaToZod(property);
+    shape[key] = required.has(key) ? field : field.optional();
+  }
+  return shape;
+}
+
+/**
+ * 将 Axon 中定义的工具包装为 OpenCode 工具
+ */
+function createAxonTools(
+  specs: AxonToolSpec[],
+  client: AxonBridgeClient,
+  logger: Logger,
+  directory: string
+): Record<string, ToolDefinition> {
+  const tools: Record<string, ToolDefinition> = {};
+  for (const spec of specs) {
+    tools[spec.name] = tool({
+      description: spec.description,
+      args: jsonSchemaToShape(spec.parameters),
+      async execute(args, context) {
+        logger.debug('执行自定义工具', { tool: spec.name, sessionID: context.sessionID });
+        return client.executeTool(spec.name, args, context.sessionID, directory);
+      },
+    });
+  }
+  return tools;
+}
+
 // ============================================================================
 // 编排指令生成器
 // ============================================================================
@@ -622,6 +1028,25 @@ function formatTrigger(trigger: SubagentTrigger): string {
   }
 }
 
+/**
+ * 把固定文件整理为一段系统提示，没有可用内容时返回 null
+ */
+function formatPinnedContext(files: PinnedFileContent[], directory: string): string | null {
+  const sections = files
+    .filter((file) => file.content !== undefined)
+    .map((file) => {
+      const relative = file.path.startsWith(directory)
+        ? file.path.slice(directory.length).replace(/^[\\/]/, '')
+        : file.path;
+      const note = file.truncated ? '\n[文件过长，已截断]' : '';
+      return `<file path="${relative}">\n${file.content}\n</file>${note}`;
+    });
+  if (sections.length === 0) {
+    return null;
+  }
+  return ['以下是用户固定的项目文件，回答时优先参考：', ...sections].join('\n\n');
+}
+
 // ============================================================================
 // 插件主体
 // ============================================================================
@@ -644,6 +1069,7 @@ const AxonBridgePlugin: Plugin = async (ctx) => {
   if (!connected) {
     logger.warn('Axon 后端未运行，部分功能将不可用');
   }
+  client.watchEndpoint();
 
   const config = await client.fetchConfig();
   const filesystemAgents = loadAgentsFromDirectory(AXON_AGENTS_DIR, logger);
@@ -654,6 +1080,12 @@ const AxonBridgePlugin: Plugin = async (ctx) => {
   // 加载编排组配置
   await client.getOrchestrations();
 
+  // 注册 Axon 中定义的自定义工具（修改后需重启 OpenCode）
+  const axonTools = createAxonTools(await client.getTools(), client, logger, ctx.directory);
+  if (Object.keys(axonTools).length > 0) {
+    logger.info('已注册自定义工具', Object.keys(axonTools));
+  }
+
   // 会话状态跟踪
   const sessionStates = new Map<
     string,
@@ -664,6 +1096,9 @@ const AxonBridgePlugin: Plugin = async (ctx) => {
   >();
 
   const hooks: Hooks = {
+    // 自定义工具：执行转发给 Axon
+    tool: axonTools,
+
     // 配置钩子：注入自定义 Agent 和命令
     config: async (inputConfig) => {
       logger.debug('处理配置', { hasAgentConfig: !!inputConfig.agent });
@@ -763,8 +1198,8 @@ const AxonBridgePlugin: Plugin = async (ctx) => {
       }
     },
 
-    // 消息钩子：记录当前 agent
-    'chat.message': async (input, _output) => {
+    // 消息钩子：记录当前 agent，应用会话级模型和工具覆盖
+    'chat.message': async (input, output) => {
       logger.debug('处理消息', {
         sessionID: input.sessionID,
         agent: input.agent,
@@ -776,10 +1211,43 @@ const AxonBridgePlugin: Plugin = async (ctx) => {
       if (state && input.agent) {
         state.agent = input.agent;
       }
+
+      const override = input.agent
+        ? await client.getAgentOverride(input.sessionID, input.agent)
+        : null;
+      if (override) {
+        const message = output.message as {
+          model?: { providerID: string; modelID: string };
+          tools?: Record<string, boolean>;
+        };
+        const [providerID, ...modelParts] = override.model?.split('/') ?? [];
+        if (providerID && modelParts.length > 0) {
+          message.model = { providerID, modelID: modelParts.join('/') };
+        }
+        if (override.tools) {
+          message.tools = { ...message.tools, ...override.tools };
+        }
+        logger.debug('应用会话级 Agent 覆盖', { sessionID: input.sessionID, agent: input.agent });
+      }
+    },
+
+    // 模型参数钩子：应用会话级温度和 Top P 覆盖
+    'chat.params': async (input, output) => {
+      const override = await client.getAgentOverride(input.sessionID, input.agent);
+      if (override?.temperature !== undefined) {
+        output.temperature = override.temperature;
+      } else if (override?.top_p !== undefined) {
+        output.topP = override.top_p;
+      }
     },
 
-    // System Prompt 转换钩子：注入编排指令
+    // System Prompt 转换钩子：注入固定文件和编排指令
     'experimental.chat.system.transform': async (input, output) => {
+      const pinned = formatPinnedContext(await client.getPinnedContext(ctx.directory), ctx.directory);
+      if (pinned) {
+        output.system.push(pinned);
+      }
+
       const state = sessionStates.get(input.sessionID);
       const currentAgent = state?.agent;
 
@@ -802,11 +1270,29 @@ const AxonBridgePlugin: Plugin = async (ctx) => {
     },
 
     // 工具执行前钩子
-    'tool.execute.before': async (input, _output) => {
+    'tool.execute.before': async (input, output) => {
       logger.debug('工具执行前', {
         tool: input.tool,
         sessionID: input.sessionID,
       });
+
+      // 破坏性操作需要用户在 Axon 中确认
+      const consent = await client.requestToolConsent({
+        tool: input.tool,
+        args: output.args,
+        sessionId: input.sessionID,
+        directory: ctx.directory,
+      });
+      if (!consent.allowed) {
+        logger.info('工具调用被拒绝', { tool: input.tool, outcome: consent.outcome });
+        const reasons: Record<ToolConsentResult['outcome'], string> = {
+          allowed: '',
+          denied: '用户拒绝了该操作',
+          timed_out: '等待用户确认超时，操作已取消',
+          error: '无法向 Axon 请求确认，操作已取消',
+        };
+        throw new Error(reasons[consent.outcome]);
+      }
     },
 
     // 工具执行后钩子
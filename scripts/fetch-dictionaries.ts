@@ -0,0 +1,52 @@
+/**
+ * 下载 Hunspell 英文词典，打包进应用作为拼写检查的默认词典（Windows 没有系统词典）
+ *
+ * 输出: src-tauri/resources/dictionaries/en_US.aff、en_US.dic
+ * 使用: bun run build:dictionaries
+ *
+ * 下载失败时保留已有词典；没有已有词典时以失败退出
+ */
+
+import { existsSync, mkdirSync } from 'fs';
+import path from 'path';
+
+const DICTIONARY_BASE_URL = 'https://raw.githubusercontent.com/LibreOffice/dictionaries/master/en';
+const DICTIONARIES = ['en_US.aff', 'en_US.dic'];
+const OUTPUT_DIR = path.join(import.meta.dir, '../src-tauri/resources/dictionaries');
+
+const log = {
+  info: (msg: string) => console.log(`\x1b[36m[dictionaries]\x1b[0m ${msg}`),
+  warn: (msg: string) => console.log(`\x1b[33m[dictionaries]\x1b[0m ${msg}`),
+  error: (msg: string) => console.log(`\x1b[31m[dictionaries]\x1b[0m ${msg}`),
+};
+
+async function download(file: string): Promise<void> {
+  const outputPath = path.join(OUTPUT_DIR, file);
+  try {
+    const response = await fetch(`${DICTIONARY_BASE_URL}/${file}`, {
+      signal: AbortSignal.timeout(30_000),
+    });
+    if (!response.ok) {
+      throw new Error(`HTTP ${response.status}`);
+    }
+    const content = await response.arrayBuffer();
+    if (content.byteLength === 0) {
+      throw new Error('文件为空');
+    }
+    await Bun.write(outputPath, content);
+    log.info(`已写入 ${file}: ${(content.byteLength / 1024).toFixed(1)} KB`);
+  } catch (error) {
+    const message = error instanceof Error ? error.message : String(error);
+    if (existsSync(outputPath)) {
+      log.warn(`下载 ${file} 失败 (${message})，保留已有文件`);
+      return;
+    }
+    log.error(`下载 ${file} 失败: ${message}`);
+    process.exit(1);
+  }
+}
+
+mkdirSync(OUTPUT_DIR, { recursive: true });
+for (const file of DICTIONARIES) {
+  await download(file);
+}
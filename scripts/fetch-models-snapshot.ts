@@ -0,0 +1,57 @@
+/**
+ * 下载 models.dev 注册表快照并压缩，作为离线环境的初始注册表打包进应用
+ *
+ * 输出: src-tauri/resources/models_registry.json.gz
+ * 使用: bun run build:models-snapshot
+ *
+ * 下载失败时保留已有快照；没有已有快照时以失败退出
+ */
+
+import { existsSync, mkdirSync } from 'fs';
+import path from 'path';
+
+const REGISTRY_URL = 'https://models.dev/api.json';
+const OUTPUT_DIR = path.join(import.meta.dir, '../src-tauri/resources');
+const OUTPUT_PATH = path.join(OUTPUT_DIR, 'models_registry.json.gz');
+
+const log = {
+  info: (msg: string) => console.log(`\x1b[36m[models]\x1b[0m ${msg}`),
+  warn: (msg: string) => console.log(`\x1b[33m[models]\x1b[0m ${msg}`),
+  error: (msg: string) => console.log(`\x1b[31m[models]\x1b[0m ${msg}`),
+};
+
+async function main(): Promise<void> {
+  try {
+    const response = await fetch(REGISTRY_URL, { signal: AbortSignal.timeout(30_000) });
+    if (!response.ok) {
+      throw new Error(`HTTP ${response.status}`);
+    }
+
+    const text = await response.text();
+    const data = JSON.parse(text) as Record<string, { models?: Record<string, unknown> }>;
+    const providers = Object.keys(data).length;
+    if (providers === 0) {
+      throw new Error('注册表数据为空');
+    }
+    const models = Object.values(data).reduce(
+      (sum, provider) => sum + Object.keys(provider.models ?? {}).length,
+      0,
+    );
+
+    mkdirSync(OUTPUT_DIR, { recursive: true });
+    const compressed = Bun.gzipSync(new TextEncoder().encode(text), { level: 9 });
+    await Bun.write(OUTPUT_PATH, compressed);
+
+    log.info(`已写入快照: ${providers} 个 provider, ${models} 个模型, ${(compressed.length / 1024).toFixed(1)} KB`);
+  } catch (error) {
+    const message = error instanceof Error ? error.message : String(error);
+    if (existsSync(OUTPUT_PATH)) {
+      log.warn(`下载注册表失败 (${message})，保留已有快照`);
+      return;
+    }
+    log.error(`下载注册表失败: ${message}`);
+    process.exit(1);
+  }
+}
+
+await main();
@@ -0,0 +1,330 @@
+//! Agent 活动时间线
+//!
+//! 插件转发的 `message.part.updated` 事件中，工具调用的 part 会随执行进度多次发送
+//! （pending → running → completed / error）。这里按调用 ID 把同一次调用的事件合并为
+//! 一条时间线记录，每个会话保留最近的记录，记录变化时推送给前端，
+//! 用于展示「Agent 正在做什么」。会话删除后丢弃对应记录。
+
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tracing::warn;
+
+/// 活动记录新增或变化事件
+pub const EVENT_ACTIVITY_UPDATED: &str = "activity:updated";
+
+/// 每个会话保留的记录数
+const MAX_ENTRIES_PER_SESSION: usize = 200;
+
+/// 保留记录的会话数（超出时丢弃最久没有活动的会话）
+const MAX_SESSIONS: usize = 50;
+
+/// 摘要的最大字符数
+const MAX_SUMMARY_CHARS: usize = 160;
+
+/// 用作摘要的工具参数（按优先级）
+const SUMMARY_ARGS: &[&str] = &[
+    "description",
+    "command",
+    "filePath",
+    "path",
+    "pattern",
+    "url",
+    "query",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityStatus {
+    Pending,
+    Running,
+    Completed,
+    Error,
+}
+
+/// 一次工具调用
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntry {
+    /// 工具调用 ID
+    pub id: String,
+    pub session_id: String,
+    pub message_id: String,
+    pub tool: String,
+    pub status: ActivityStatus,
+    /// 工具给出的标题，没有时取自参数（命令、文件路径等）
+    pub summary: Option<String>,
+    pub error: Option<String>,
+    /// Unix 毫秒
+    pub started_at: Option<i64>,
+    pub ended_at: Option<i64>,
+    pub duration_ms: Option<i64>,
+}
+
+/// `message.part.updated` 中的工具 part（仅解析需要的字段）
+#[derive(Debug, Deserialize)]
+struct ToolPart {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(rename = "callID")]
+    call_id: Option<String>,
+    #[serde(rename = "sessionID")]
+    session_id: String,
+    #[serde(rename = "messageID")]
+    message_id: String,
+    #[serde(default)]
+    tool: String,
+    state: Option<ToolState>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolState {
+    status: ActivityStatus,
+    #[serde(default)]
+    input: Value,
+    title: Option<String>,
+    error: Option<String>,
+    #[serde(default)]
+    time: ToolTime,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ToolTime {
+    start: Option<i64>,
+    end: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+struct Sessions {
+    entries: HashMap<String, VecDeque<ActivityEntry>>,
+    /// 最近有活动的在后
+    order: VecDeque<String>,
+}
+
+/// 活动时间线
+#[derive(Debug, Default)]
+pub struct ActivityFeed {
+    sessions: Mutex<Sessions>,
+    app_handle: RwLock<Option<AppHandle>>,
+}
+
+impl ActivityFeed {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// 设置事件发送句柄
+    pub fn initialize(&self, handle: AppHandle) {
+        *self.app_handle.write() = Some(handle);
+    }
+
+    /// 处理插件转发的事件
+    pub fn record_event(&self, event_type: &str, properties: Option<&Value>) {
+        match event_type {
+            "message.part.updated" => {
+                let Some(part) = properties.and_then(|p| p.get("part")) else {
+                    return;
+                };
+                if let Some(entry) = self.apply_part(part) {
+                    self.emit(entry);
+                }
+            }
+            "session.deleted" => {
+                let session_id = properties
+                    .and_then(|p| p.pointer("/info/id"))
+                    .and_then(Value::as_str);
+                if let Some(session_id) = session_id {
+                    self.clear(session_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 会话的记录（按开始顺序），`limit` 为空时返回全部保留的记录
+    pub fn session_activity(&self, session_id: &str, limit: Option<usize>) -> Vec<ActivityEntry> {
+        let sessions = self.sessions.lock();
+        let Some(entries) = sessions.entries.get(session_id) else {
+            return Vec::new();
+        };
+        let skip = limit.map_or(0, |limit| entries.len().saturating_sub(limit));
+        entries.iter().skip(skip).cloned().collect()
+    }
+
+    pub fn clear(&self, session_id: &str) {
+        let mut sessions = self.sessions.lock();
+        sessions.entries.remove(session_id);
+        sessions.order.retain(|id| id != session_id);
+    }
+
+    /// 合并工具 part，记录有变化时返回新的记录
+    fn apply_part(&self, part: &Value) -> Option<ActivityEntry> {
+        let part: ToolPart = serde_json::from_value(part.clone()).ok()?;
+        if part.kind != "tool" {
+            return None;
+        }
+        let call_id = part.call_id?;
+        let state = part.state?;
+
+        let mut sessions = self.sessions.lock();
+        sessions.touch(&part.session_id);
+        let entries = sessions.entries.entry(part.session_id.clone()).or_default();
+        let existing = entries.iter().rposition(|e| e.id == call_id);
+        let index = match existing {
+            Some(index) => index,
+            None => {
+                if entries.len() >= MAX_ENTRIES_PER_SESSION {
+                    entries.pop_front();
+                }
+                entries.push_back(ActivityEntry {
+                    id: call_id,
+                    session_id: part.session_id,
+                    message_id: part.message_id,
+                    tool: part.tool.clone(),
+                    status: state.status,
+                    summary: None,
+                    error: None,
+                    started_at: None,
+                    ended_at: None,
+                    duration_ms: None,
+                });
+                entries.len() - 1
+            }
+        };
+
+        let entry = &mut entries[index];
+        let previous = entry.clone();
+        entry.status = state.status;
+        if let Some(summary) = state
+            .title
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| summarize_input(&state.input))
+        {
+            entry.summary = Some(truncate(summary.trim()));
+        }
+        entry.error = state.error.map(|e| truncate(e.trim()));
+        entry.started_at = state.time.start.or(entry.started_at);
+        entry.ended_at = state.time.end.or(entry.ended_at);
+        entry.duration_ms = entry
+            .started_at
+            .zip(entry.ended_at)
+            .map(|(start, end)| end - start);
+
+        (existing.is_none() || *entry != previous).then(|| entry.clone())
+    }
+
+    fn emit(&self, entry: ActivityEntry) {
+        if let Some(handle) = self.app_handle.read().as_ref() {
+            if let Err(e) = handle.emit(EVENT_ACTIVITY_UPDATED, entry) {
+                warn!("发送活动事件失败: {}", e);
+            }
+        }
+    }
+}
+
+impl Sessions {
+    /// 标记会话有新活动，超出会话数上限时丢弃最久没有活动的会话
+    fn touch(&mut self, session_id: &str) {
+        if self.order.back().is_some_and(|id| id == session_id) {
+            return;
+        }
+        self.order.retain(|id| id != session_id);
+        self.order.push_back(session_id.to_string());
+        while self.order.len() > MAX_SESSIONS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+fn summarize_input(input: &Value) -> Option<String> {
+    SUMMARY_ARGS
+        .iter()
+        .filter_map(|key| input.get(*key).and_then(Value::as_str))
+        .find(|value| !value.trim().is_empty())
+        .map(str::to_string)
+}
+
+fn truncate(text: &str) -> String {
+    match text.char_indices().nth(MAX_SUMMARY_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn part(call_id: &str, state: Value) -> Value {
+        json!({
+            "part": {
+                "type": "tool",
+                "callID": call_id,
+                "sessionID": "ses_1",
+                "messageID": "msg_1",
+                "tool": "bash",
+                "state": state,
+            }
+        })
+    }
+
+    #[test]
+    fn correlates_tool_call_updates() {
+        let feed = ActivityFeed::default();
+        let pending = part("call_1", json!({ "status": "pending", "input": {} }));
+        let running = part(
+            "call_1",
+            json!({ "status": "running", "input": { "command": "cargo test" }, "time": { "start": 1000 } }),
+        );
+        let completed = part(
+            "call_1",
+            json!({ "status": "completed", "input": { "command": "cargo test" }, "title": "Run tests",
+                    "output": "ok", "time": { "start": 1000, "end": 3500 } }),
+        );
+        assert!(feed.apply_part(&pending["part"]).is_some());
+        assert!(feed.apply_part(&running["part"]).is_some());
+        // 重复的更新不再推送
+        assert!(feed.apply_part(&running["part"]).is_none());
+        let entry = feed.apply_part(&completed["part"]).unwrap();
+        assert_eq!(entry.status, ActivityStatus::Completed);
+        assert_eq!(entry.summary.as_deref(), Some("Run tests"));
+        assert_eq!(entry.duration_ms, Some(2500));
+
+        let entries = feed.session_activity("ses_1", None);
+        assert_eq!(entries.len(), 1);
+
+        // 文本 part 不计入
+        assert!(feed
+            .apply_part(&json!({ "type": "text", "sessionID": "ses_1", "messageID": "msg_1" }))
+            .is_none());
+
+        feed.record_event(
+            "session.deleted",
+            Some(&json!({ "info": { "id": "ses_1" } })),
+        );
+        assert!(feed.session_activity("ses_1", None).is_empty());
+    }
+
+    #[test]
+    fn keeps_recent_entries() {
+        let feed = ActivityFeed::default();
+        for i in 0..MAX_ENTRIES_PER_SESSION + 5 {
+            let event = part(
+                &format!("call_{}", i),
+                json!({ "status": "running", "input": {} }),
+            );
+            feed.record_event("message.part.updated", Some(&event));
+        }
+        let entries = feed.session_activity("ses_1", None);
+        assert_eq!(entries.len(), MAX_ENTRIES_PER_SESSION);
+        assert_eq!(entries[0].id, "call_5");
+        let last = feed.session_activity("ses_1", Some(2));
+        assert_eq!(last[1].id, format!("call_{}", MAX_ENTRIES_PER_SESSION + 4));
+    }
+}
@@ -0,0 +1,321 @@
+//! 原生应用菜单
+//!
+//! macOS 菜单栏（应用 / 文件 / 编辑 / 显示 / Agents / 帮助）。菜单项使用快捷键映射中的
+//! 操作 ID 和快捷键，选择后通过 [`EVENT_MENU_ACTION`] 交给前端，与界面按钮和快捷键
+//! 走同一处理逻辑。最近项目由前端同步（项目列表保存在前端），Agents 从配置目录读取，
+//! 两者或快捷键映射变化时重建菜单。
+//!
+//! 其他平台的主窗口使用自绘标题栏，不安装菜单栏。
+
+use crate::commands::collect_agents;
+use crate::keymap::{self, KeymapEntry};
+use crate::state::AppState;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+use tracing::{debug, warn};
+
+/// 菜单操作事件（主窗口接收），payload 为 [`MenuAction`]
+pub const EVENT_MENU_ACTION: &str = "menu:action";
+
+/// 菜单项 ID 前缀，用于区分托盘和右键菜单的事件
+const MENU_ID_PREFIX: &str = "app-menu:";
+
+/// 打开最近项目，参数为项目目录
+const ACTION_OPEN_RECENT: &str = "project.openRecent";
+
+/// 使用 Agent 新建会话，参数为 Agent 名称（opencode 按名称注册 Agent）
+const ACTION_OPEN_AGENT: &str = "agent.open";
+
+/// 菜单中最多显示的最近项目数
+const MAX_RECENT_PROJECTS: usize = 10;
+
+/// 菜单中最多显示的 Agent 数
+const MAX_AGENTS: usize = 20;
+
+/// 前端同步的最近项目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentProject {
+    pub name: String,
+    pub directory: String,
+}
+
+/// 交给前端执行的菜单操作
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MenuAction {
+    /// 操作 ID（与快捷键映射一致，另有 project.openRecent、agent.open 等菜单专用操作）
+    pub action: String,
+    /// 操作参数（项目目录、Agent 名称）
+    pub argument: Option<String>,
+}
+
+/// 应用菜单状态
+#[derive(Debug, Default)]
+pub struct AppMenu {
+    recent_projects: Mutex<Vec<RecentProject>>,
+    /// 菜单已安装（仅 macOS）
+    installed: AtomicBool,
+}
+
+impl AppMenu {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// 安装菜单栏
+    pub fn install(&self, app: &AppHandle) -> tauri::Result<()> {
+        if !cfg!(target_os = "macos") {
+            return Ok(());
+        }
+        app.set_menu(self.build(app)?)?;
+        self.installed.store(true, Ordering::SeqCst);
+        debug!("应用菜单已安装");
+        Ok(())
+    }
+
+    /// 快捷键、项目或 Agent 变化后重建菜单
+    pub fn refresh(&self, app: &AppHandle) {
+        if !self.installed.load(Ordering::SeqCst) {
+            return;
+        }
+        if let Err(e) = self.build(app).and_then(|menu| app.set_menu(menu)) {
+            warn!("重建应用菜单失败: {}", e);
+        }
+    }
+
+    /// 替换最近项目列表（按最近使用排序）
+    pub fn set_recent_projects(&self, app: &AppHandle, mut projects: Vec<RecentProject>) {
+        projects.retain(|p| !p.directory.trim().is_empty());
+        projects.truncate(MAX_RECENT_PROJECTS);
+        {
+            let mut current = self.recent_projects.lock();
+            if *current == projects {
+                return;
+            }
+            *current = projects;
+        }
+        self.refresh(app);
+    }
+
+    /// 处理菜单事件，忽略非应用菜单的菜单项
+    pub fn handle_menu_event(&self, app: &AppHandle, event: &MenuEvent) {
+        let Some(action) = parse_menu_id(event.id().as_ref()) else {
+            return;
+        };
+        crate::tray::show_main_window(app);
+        if let Err(e) = app.emit_to("main", EVENT_MENU_ACTION, &action) {
+            warn!("发送菜单操作事件失败: {}", e);
+        }
+    }
+
+    fn build(&self, app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+        let state: tauri::State<'_, AppState> = app.state();
+        let entries = keymap::resolve(&state.settings.get_keymap(), &state.settings.get_hotkeys());
+        let item = |action: &str, text: &str| {
+            MenuItem::with_id(
+                app,
+                menu_id(action, None),
+                text,
+                true,
+                accelerator(&entries, action),
+            )
+        };
+
+        let app_submenu = Submenu::with_items(
+            app,
+            "Axon",
+            true,
+            &[
+                &PredefinedMenuItem::about(app, Some("关于 Axon"), None)?,
+                &PredefinedMenuItem::separator(app)?,
+                &item("settings.open", "设置...")?,
+                &PredefinedMenuItem::separator(app)?,
+                &PredefinedMenuItem::services(app, None)?,
+                &PredefinedMenuItem::separator(app)?,
+                &PredefinedMenuItem::hide(app, None)?,
+                &PredefinedMenuItem::hide_others(app, None)?,
+                &PredefinedMenuItem::show_all(app, None)?,
+                &PredefinedMenuItem::separator(app)?,
+                &PredefinedMenuItem::quit(app, None)?,
+            ],
+        )?;
+
+        let recent = Submenu::new(app, "最近项目", true)?;
+        let projects = self.recent_projects.lock().clone();
+        if projects.is_empty() {
+            recent.append(&MenuItem::new(app, "无最近项目", false, None::<&str>)?)?;
+        }
+        for project in &projects {
+            recent.append(&MenuItem::with_id(
+                app,
+                menu_id(ACTION_OPEN_RECENT, Some(&project.directory)),
+                &project.name,
+                true,
+                None::<&str>,
+            )?)?;
+        }
+        let file = Submenu::with_items(
+            app,
+            "文件",
+            true,
+            &[
+                &item("session.new", "新建会话")?,
+                &PredefinedMenuItem::separator(app)?,
+                &item("project.open", "打开项目...")?,
+                &recent,
+                &PredefinedMenuItem::separator(app)?,
+                &item("tab.close", "关闭标签页")?,
+            ],
+        )?;
+
+        let edit = Submenu::with_items(
+            app,
+            "编辑",
+            true,
+            &[
+                &PredefinedMenuItem::undo(app, None)?,
+                &PredefinedMenuItem::redo(app, None)?,
+                &PredefinedMenuItem::separator(app)?,
+                &PredefinedMenuItem::cut(app, None)?,
+                &PredefinedMenuItem::copy(app, None)?,
+                &PredefinedMenuItem::paste(app, None)?,
+                &PredefinedMenuItem::select_all(app, None)?,
+            ],
+        )?;
+
+        let view = Submenu::with_items(
+            app,
+            "显示",
+            true,
+            &[
+                &item("view.toggleSidebar", "显示 / 隐藏侧边栏")?,
+                &item("view.toggleTerminal", "显示 / 隐藏终端")?,
+                &PredefinedMenuItem::separator(app)?,
+                &PredefinedMenuItem::fullscreen(app, None)?,
+            ],
+        )?;
+
+        let agents = Submenu::with_items(
+            app,
+            "Agents",
+            true,
+            &[
+                &item("agent.new", "新建 Agent...")?,
+                &item("agent.manage", "管理 Agents")?,
+                &PredefinedMenuItem::separator(app)?,
+            ],
+        )?;
+        let summaries = collect_agents(app, &state).unwrap_or_else(|e| {
+            warn!("读取 Agent 列表失败: {}", e);
+            Vec::new()
+        });
+        if summaries.is_empty() {
+            agents.append(&MenuItem::new(
+                app,
+                "没有已保存的 Agent",
+                false,
+                None::<&str>,
+            )?)?;
+        }
+        for agent in summaries.iter().take(MAX_AGENTS) {
+            agents.append(&MenuItem::with_id(
+                app,
+                menu_id(ACTION_OPEN_AGENT, Some(&agent.name)),
+                &agent.name,
+                true,
+                None::<&str>,
+            )?)?;
+        }
+
+        let help = Submenu::with_items(
+            app,
+            "帮助",
+            true,
+            &[
+                &item("help.keymap", "快捷键")?,
+                &item("help.checkUpdates", "检查更新")?,
+            ],
+        )?;
+
+        Menu::with_items(app, &[&app_submenu, &file, &edit, &view, &agents, &help])
+    }
+}
+
+/// 操作当前绑定的快捷键
+fn accelerator<'a>(entries: &'a [KeymapEntry], action: &str) -> Option<&'a str> {
+    entries
+        .iter()
+        .find(|e| e.action == action)
+        .and_then(|e| e.accelerator.as_deref())
+}
+
+/// 菜单项 ID：`app-menu:<操作>` 或 `app-menu:<操作>:<参数>`（操作 ID 不含冒号）
+fn menu_id(action: &str, argument: Option<&str>) -> String {
+    match argument {
+        Some(argument) => format!("{}{}:{}", MENU_ID_PREFIX, action, argument),
+        None => format!("{}{}", MENU_ID_PREFIX, action),
+    }
+}
+
+fn parse_menu_id(id: &str) -> Option<MenuAction> {
+    let rest = id.strip_prefix(MENU_ID_PREFIX)?;
+    let (action, argument) = match rest.split_once(':') {
+        Some((action, argument)) => (action, Some(argument.to_string())),
+        None => (rest, None),
+    };
+    (!action.is_empty()).then(|| MenuAction {
+        action: action.to_string(),
+        argument,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn menu_ids_round_trip() {
+        let id = menu_id(ACTION_OPEN_RECENT, Some("C:\\work\\axon"));
+        assert_eq!(
+            parse_menu_id(&id),
+            Some(MenuAction {
+                action: ACTION_OPEN_RECENT.to_string(),
+                argument: Some("C:\\work\\axon".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_menu_id(&menu_id("session.new", None)),
+            Some(MenuAction {
+                action: "session.new".to_string(),
+                argument: None,
+            })
+        );
+        assert_eq!(parse_menu_id("file-context:open"), None);
+        assert_eq!(parse_menu_id("show"), None);
+        assert_eq!(parse_menu_id(MENU_ID_PREFIX), None);
+    }
+
+    #[test]
+    fn menu_items_use_current_bindings() {
+        let overrides = std::collections::BTreeMap::from([
+            (
+                "session.new".to_string(),
+                Some("CmdOrCtrl+Shift+N".to_string()),
+            ),
+            ("tab.close".to_string(), None),
+        ]);
+        let entries = keymap::resolve(&overrides, &Default::default());
+        assert_eq!(
+            accelerator(&entries, "session.new"),
+            Some("CmdOrCtrl+Shift+N")
+        );
+        assert_eq!(accelerator(&entries, "tab.close"), None);
+        assert_eq!(accelerator(&entries, "project.open"), Some("CmdOrCtrl+O"));
+        assert_eq!(accelerator(&entries, "agent.new"), None);
+    }
+}
@@ -0,0 +1,208 @@
+//! 应用自身更新模块
+//!
+//! - 按设置中的更新通道（stable / beta）选择 updater 地址
+//! - 支持"立即下载、退出时安装"：下载好的安装包暂存在内存中，
+//!   应用退出时再安装，避免打断正在进行的工作
+//! - 下载过程通过 `app-update:progress` 事件上报进度，可随时取消
+
+use crate::error::AxonError;
+use crate::opencode::UpdateChannel;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+use tokio::sync::Notify;
+use tracing::{debug, error, info, warn};
+
+/// 更新下载进度事件
+pub const EVENT_APP_UPDATE_PROGRESS: &str = "app-update:progress";
+
+/// 稳定版更新地址
+const STABLE_ENDPOINT: &str =
+    "https://github.com/code-yeongyu/axon-desktop/releases/download/latest/latest.json";
+
+/// 测试版更新地址
+const BETA_ENDPOINT: &str =
+    "https://github.com/code-yeongyu/axon-desktop/releases/download/beta/latest.json";
+
+/// 总大小未知时，每下载这么多字节发送一次进度
+const PROGRESS_BYTES_STEP: u64 = 1024 * 1024;
+
+/// 更新下载进度
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppUpdateProgress {
+    /// 正在下载的版本
+    pub version: String,
+    /// 已下载字节数
+    pub downloaded: u64,
+    /// 总字节数（服务器未返回时为空）
+    pub total: Option<u64>,
+    /// 下载百分比（0-100，总大小未知时为空）
+    pub percentage: Option<u32>,
+    /// 下载是否完成
+    pub finished: bool,
+}
+
+/// 已下载、等待退出时安装的更新
+struct StagedUpdate {
+    update: Update,
+    bytes: Vec<u8>,
+}
+
+/// 应用更新管理器
+pub struct AppUpdateManager {
+    staged: Mutex<Option<StagedUpdate>>,
+    /// 进行中下载的取消信号
+    download_cancel: Mutex<Option<Arc<Notify>>>,
+}
+
+impl AppUpdateManager {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            staged: Mutex::new(None),
+            download_cancel: Mutex::new(None),
+        })
+    }
+
+    /// 创建指定通道的 updater
+    pub fn updater(app: &AppHandle, channel: UpdateChannel) -> Result<Updater, AxonError> {
+        let endpoint = match channel {
+            UpdateChannel::Stable => STABLE_ENDPOINT,
+            UpdateChannel::Beta => BETA_ENDPOINT,
+        };
+        let url = endpoint
+            .parse()
+            .map_err(|e| AxonError::internal(format!("无效的更新地址 {}: {}", endpoint, e)))?;
+
+        app.updater_builder()
+            .endpoints(vec![url])
+            .and_then(|builder| builder.build())
+            .map_err(|e| {
+                error!("创建 updater 失败: {}", e);
+                AxonError::unavailable(format!("更新模块未初始化: {}", e))
+            })
+    }
+
+    /// 下载更新安装包并发送进度事件，同一时间只允许一个下载
+    ///
+    /// 下载过程中调用 `cancel_download` 会中止下载并返回 Cancelled 错误
+    pub async fn download(&self, app: &AppHandle, update: &Update) -> Result<Vec<u8>, AxonError> {
+        let cancel = {
+            let mut current = self.download_cancel.lock();
+            if current.is_some() {
+                return Err(AxonError::invalid_input("已有更新正在下载"));
+            }
+            let cancel = Arc::new(Notify::new());
+            *current = Some(Arc::clone(&cancel));
+            cancel
+        };
+
+        let version = update.version.clone();
+        let mut downloaded = 0u64;
+        let mut last_reported: Option<u64> = None;
+        let on_chunk = |chunk_len: usize, total: Option<u64>| {
+            downloaded += chunk_len as u64;
+            let percentage = total
+                .filter(|t| *t > 0)
+                .map(|t| (downloaded.min(t) * 100 / t) as u32);
+            // 按百分比变化（或总大小未知时按字节数）节流，避免事件过多
+            let mark = match percentage {
+                Some(p) => p as u64,
+                None => downloaded / PROGRESS_BYTES_STEP,
+            };
+            if last_reported == Some(mark) {
+                return;
+            }
+            last_reported = Some(mark);
+            emit_progress(
+                app,
+                AppUpdateProgress {
+                    version: version.clone(),
+                    downloaded,
+                    total,
+                    percentage,
+                    finished: false,
+                },
+            );
+        };
+
+        let result = tokio::select! {
+            result = update.download(on_chunk, || debug!("更新下载完成")) => {
+                result.map_err(|e| {
+                    error!("下载更新失败: {}", e);
+                    AxonError::network(format!("下载更新失败: {}", e))
+                })
+            }
+            _ = cancel.notified() => {
+                info!("更新下载已取消: {}", update.version);
+                Err(AxonError::cancelled("更新下载已取消"))
+            }
+        };
+        *self.download_cancel.lock() = None;
+
+        let bytes = result?;
+        let total = bytes.len() as u64;
+        emit_progress(
+            app,
+            AppUpdateProgress {
+                version: update.version.clone(),
+                downloaded: total,
+                total: Some(total),
+                percentage: Some(100),
+                finished: true,
+            },
+        );
+        Ok(bytes)
+    }
+
+    /// 取消进行中的下载，没有下载时返回 false
+    pub fn cancel_download(&self) -> bool {
+        match self.download_cancel.lock().as_ref() {
+            Some(cancel) => {
+                debug!("请求取消更新下载");
+                cancel.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 暂存已下载的更新，覆盖之前暂存的版本
+    pub fn stage(&self, update: Update, bytes: Vec<u8>) {
+        info!("更新 {} 已下载，将在退出时安装", update.version);
+        *self.staged.lock() = Some(StagedUpdate { update, bytes });
+    }
+
+    /// 暂存的更新版本
+    pub fn staged_version(&self) -> Option<String> {
+        self.staged
+            .lock()
+            .as_ref()
+            .map(|s| s.update.version.clone())
+    }
+
+    /// 丢弃暂存的更新，返回是否存在
+    pub fn discard(&self) -> bool {
+        self.staged.lock().take().is_some()
+    }
+
+    /// 安装暂存的更新（应用退出时调用）
+    pub fn install_staged(&self) {
+        let Some(staged) = self.staged.lock().take() else {
+            return;
+        };
+
+        info!("退出前安装更新: {}", staged.update.version);
+        if let Err(e) = staged.update.install(&staged.bytes) {
+            error!("安装暂存的更新失败: {}", e);
+        }
+    }
+}
+
+fn emit_progress(app: &AppHandle, progress: AppUpdateProgress) {
+    if let Err(e) = app.emit(EVENT_APP_UPDATE_PROGRESS, &progress) {
+        warn!("发送更新进度事件失败: {}", e);
+    }
+}
@@ -0,0 +1,371 @@
+//! 麦克风录音
+//!
+//! 录音在独立线程中进行（cpal 的输入流不能跨线程传递），采样统一转换为 16 位 PCM
+//! 写入 `<app_data_dir>/attachments/recording-*.wav`。录音期间节流发送 `audio:level`
+//! 事件供前端显示音量条；同一时间只允许一个录音，超过最长时长自动停止。
+
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::paths::get_attachments_dir;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SampleFormat, SizedSample, StreamConfig};
+use hound::{WavSpec, WavWriter};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, info, warn};
+
+/// 录音音量事件
+pub const EVENT_AUDIO_LEVEL: &str = "audio:level";
+
+/// 音量事件最小发送间隔
+const LEVEL_EMIT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 单次录音最长时长，超过后自动停止
+const MAX_RECORDING_DURATION: Duration = Duration::from_secs(10 * 60);
+
+type Writer = WavWriter<BufWriter<File>>;
+
+/// 音频输入设备
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioInputDevice {
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// 录音音量
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioLevel {
+    /// 均方根音量（0 - 1）
+    pub rms: f32,
+    /// 峰值音量（0 - 1）
+    pub peak: f32,
+    pub elapsed_ms: u64,
+}
+
+/// 录音结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recording {
+    /// WAV 文件路径
+    pub path: String,
+    pub device: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub duration_ms: u64,
+}
+
+/// 进行中的录音
+struct ActiveRecording {
+    stop_tx: mpsc::Sender<()>,
+    thread: JoinHandle<Result<Recording, AxonError>>,
+}
+
+/// 录音管理器
+pub struct AudioRecorder {
+    active: Mutex<Option<ActiveRecording>>,
+}
+
+impl AudioRecorder {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            active: Mutex::new(None),
+        })
+    }
+
+    /// 列出音频输入设备
+    pub fn input_devices(&self) -> Result<Vec<AudioInputDevice>, AxonError> {
+        let host = cpal::default_host();
+        let default_name = host.default_input_device().and_then(|d| d.name().ok());
+        let devices = host
+            .input_devices()
+            .map_err(|e| AxonError::unavailable(format!("获取音频输入设备失败: {}", e)))?;
+
+        Ok(devices
+            .filter_map(|device| device.name().ok())
+            .map(|name| AudioInputDevice {
+                is_default: default_name.as_deref() == Some(name.as_str()),
+                name,
+            })
+            .collect())
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.active
+            .lock()
+            .as_ref()
+            .is_some_and(|active| !active.thread.is_finished())
+    }
+
+    /// 开始录音，`device` 为设备名称，未指定时使用默认输入设备
+    ///
+    /// 设备打开成功后返回录音文件路径
+    pub fn start(&self, app: AppHandle, device: Option<String>) -> Result<String, AxonError> {
+        let mut active = self.active.lock();
+        if active.as_ref().is_some_and(|a| !a.thread.is_finished()) {
+            return Err(AxonError::already_exists("已有录音正在进行"));
+        }
+
+        let dir = get_attachments_dir().ok_or_else(|| {
+            AxonError::localized(
+                ErrorKind::Unavailable,
+                "app.data_dir_unavailable",
+                serde_json::json!(null),
+            )
+        })?;
+        std::fs::create_dir_all(&dir).map_err(|e| AxonError::io("创建附件目录失败", &e))?;
+        let path = dir.join(format!(
+            "recording-{}.wav",
+            chrono::Local::now().format("%Y%m%d-%H%M%S-%3f")
+        ));
+
+        let (stop_tx, stop_rx) = mpsc::channel();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let thread_path = path.clone();
+        let thread =
+            std::thread::spawn(move || record(app, device, thread_path, stop_rx, ready_tx));
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => {
+                *active = Some(ActiveRecording { stop_tx, thread });
+                Ok(path.to_string_lossy().to_string())
+            }
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(thread
+                .join()
+                .map_err(|_| AxonError::internal("录音线程异常退出"))?
+                .err()
+                .unwrap_or_else(|| AxonError::internal("录音线程异常退出"))),
+        }
+    }
+
+    /// 停止录音并返回录音文件
+    pub fn stop(&self) -> Result<Recording, AxonError> {
+        let active = self
+            .active
+            .lock()
+            .take()
+            .ok_or_else(|| AxonError::not_found("没有正在进行的录音"))?;
+
+        // 录音线程可能已因超时自行结束，发送失败可以忽略
+        let _ = active.stop_tx.send(());
+        active
+            .thread
+            .join()
+            .map_err(|_| AxonError::internal("录音线程异常退出"))?
+    }
+}
+
+/// 录音线程：打开设备、写入 WAV，直到收到停止信号或超时
+fn record(
+    app: AppHandle,
+    device_name: Option<String>,
+    path: PathBuf,
+    stop_rx: mpsc::Receiver<()>,
+    ready_tx: mpsc::Sender<Result<(), AxonError>>,
+) -> Result<Recording, AxonError> {
+    let opened = open_stream(app, device_name, &path);
+    let (stream, sink, device, config) = match opened {
+        Ok(opened) => {
+            let _ = ready_tx.send(Ok(()));
+            opened
+        }
+        Err(e) => {
+            let _ = ready_tx.send(Err(e.clone()));
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+    };
+    info!(
+        "开始录音: {}, {} Hz, {} 声道 -> {:?}",
+        device, config.sample_rate.0, config.channels, path
+    );
+
+    if stop_rx.recv_timeout(MAX_RECORDING_DURATION).is_err() {
+        warn!("录音达到最长时长或停止信号丢失，自动停止");
+    }
+    drop(stream);
+
+    let writer = sink.writer.lock().take();
+    if let Some(writer) = writer {
+        writer
+            .finalize()
+            .map_err(|e| AxonError::internal(format!("写入录音文件失败: {}", e)))?;
+    }
+
+    let frames = sink.samples.load(Ordering::Relaxed) / u64::from(config.channels.max(1));
+    let duration_ms = frames * 1000 / u64::from(config.sample_rate.0.max(1));
+    info!("录音结束: {:?}, {} ms", path, duration_ms);
+
+    Ok(Recording {
+        path: path.to_string_lossy().to_string(),
+        device,
+        sample_rate: config.sample_rate.0,
+        channels: config.channels,
+        duration_ms,
+    })
+}
+
+fn open_stream(
+    app: AppHandle,
+    device_name: Option<String>,
+    path: &PathBuf,
+) -> Result<(cpal::Stream, Arc<LevelSink>, String, StreamConfig), AxonError> {
+    let host = cpal::default_host();
+    let device = match &device_name {
+        Some(name) => host
+            .input_devices()
+            .map_err(|e| AxonError::unavailable(format!("获取音频输入设备失败: {}", e)))?
+            .find(|d| d.name().ok().as_deref() == Some(name.as_str()))
+            .ok_or_else(|| AxonError::not_found(format!("音频输入设备不存在: {}", name)))?,
+        None => host
+            .default_input_device()
+            .ok_or_else(|| AxonError::unavailable("没有可用的音频输入设备"))?,
+    };
+    let name = device.name().unwrap_or_default();
+
+    let supported = device
+        .default_input_config()
+        .map_err(|e| AxonError::unavailable(format!("获取音频输入配置失败: {}", e)))?;
+    let sample_format = supported.sample_format();
+    let config: StreamConfig = supported.into();
+
+    let spec = WavSpec {
+        channels: config.channels,
+        sample_rate: config.sample_rate.0,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let writer = WavWriter::create(path, spec)
+        .map_err(|e| AxonError::internal(format!("创建录音文件失败: {}", e)))?;
+    let sink = Arc::new(LevelSink {
+        app,
+        writer: Mutex::new(Some(writer)),
+        samples: AtomicU64::new(0),
+        started: Instant::now(),
+        last_emit: Mutex::new(Instant::now()),
+    });
+
+    let stream = match sample_format {
+        SampleFormat::F32 => build_stream::<f32>(&device, &config, Arc::clone(&sink)),
+        SampleFormat::I16 => build_stream::<i16>(&device, &config, Arc::clone(&sink)),
+        SampleFormat::U16 => build_stream::<u16>(&device, &config, Arc::clone(&sink)),
+        SampleFormat::I32 => build_stream::<i32>(&device, &config, Arc::clone(&sink)),
+        other => {
+            return Err(AxonError::unsupported(format!(
+                "不支持的音频采样格式: {:?}",
+                other
+            )))
+        }
+    }?;
+    stream
+        .play()
+        .map_err(|e| AxonError::unavailable(format!("启动录音失败: {}", e)))?;
+
+    Ok((stream, sink, name, config))
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    sink: Arc<LevelSink>,
+) -> Result<cpal::Stream, AxonError>
+where
+    T: SizedSample,
+    i16: FromSample<T>,
+    f32: FromSample<T>,
+{
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| sink.write(data),
+            |e| warn!("录音输入流错误: {}", e),
+            None,
+        )
+        .map_err(|e| AxonError::unavailable(format!("打开音频输入流失败: {}", e)))
+}
+
+/// 接收采样：写入 WAV 并节流发送音量事件
+struct LevelSink {
+    app: AppHandle,
+    writer: Mutex<Option<Writer>>,
+    samples: AtomicU64,
+    started: Instant,
+    last_emit: Mutex<Instant>,
+}
+
+impl LevelSink {
+    fn write<T>(&self, data: &[T])
+    where
+        T: Sample,
+        i16: FromSample<T>,
+        f32: FromSample<T>,
+    {
+        if let Some(writer) = self.writer.lock().as_mut() {
+            for &sample in data {
+                if let Err(e) = writer.write_sample(sample.to_sample::<i16>()) {
+                    debug!("写入录音采样失败: {}", e);
+                    break;
+                }
+            }
+        }
+        self.samples.fetch_add(data.len() as u64, Ordering::Relaxed);
+
+        let mut last_emit = self.last_emit.lock();
+        if last_emit.elapsed() < LEVEL_EMIT_INTERVAL {
+            return;
+        }
+        *last_emit = Instant::now();
+
+        let (rms, peak) = measure_level(data.iter().map(|s| s.to_sample::<f32>()));
+        let level = AudioLevel {
+            rms,
+            peak,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+        };
+        if let Err(e) = self.app.emit(EVENT_AUDIO_LEVEL, &level) {
+            debug!("发送录音音量事件失败: {}", e);
+        }
+    }
+}
+
+/// 计算一段采样的均方根和峰值音量
+fn measure_level(samples: impl Iterator<Item = f32>) -> (f32, f32) {
+    let (mut sum, mut peak, mut count) = (0.0f64, 0.0f32, 0usize);
+    for sample in samples {
+        let abs = sample.abs().min(1.0);
+        sum += f64::from(abs) * f64::from(abs);
+        peak = peak.max(abs);
+        count += 1;
+    }
+    if count == 0 {
+        return (0.0, 0.0);
+    }
+    ((sum / count as f64).sqrt() as f32, peak)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measure_level_of_samples() {
+        assert_eq!(measure_level(std::iter::empty()), (0.0, 0.0));
+
+        let (rms, peak) = measure_level([0.5, -0.5, 0.5, -0.5].into_iter());
+        assert!((rms - 0.5).abs() < 1e-6);
+        assert_eq!(peak, 0.5);
+
+        let (_, peak) = measure_level([2.0, -0.1].into_iter());
+        assert_eq!(peak, 1.0);
+    }
+}
@@ -0,0 +1,169 @@
+//! 审计日志写入与查询
+
+use crate::audit::types::{AuditEntry, AuditLogFilter, AuditOutcome};
+use crate::error::AxonError;
+use crate::logging::get_logs_dir;
+use crate::utils::rotating_file::RotatingFile;
+use parking_lot::Mutex;
+use std::future::Future;
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, warn};
+
+/// 日志文件名
+const AUDIT_FILE: &str = "audit.log";
+
+/// 单个日志文件大小上限（超过后轮转）
+const MAX_FILE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// 保留的轮转文件数（不含当前文件）
+const MAX_ROTATED_FILES: usize = 4;
+
+/// 默认返回条数
+const DEFAULT_LIMIT: usize = 200;
+
+/// 返回条数上限
+const MAX_LIMIT: usize = 5000;
+
+/// 命令审计日志
+#[derive(Debug)]
+pub struct AuditLog {
+    /// 串行化写入与轮转
+    write_lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    fn get_log_file() -> Option<RotatingFile> {
+        get_logs_dir().map(|p| RotatingFile::new(p.join(AUDIT_FILE), MAX_ROTATED_FILES))
+    }
+
+    /// 执行异步命令并记录调用结果
+    pub async fn track<T, F>(
+        &self,
+        command: &str,
+        args: serde_json::Value,
+        task: F,
+    ) -> Result<T, AxonError>
+    where
+        F: Future<Output = Result<T, AxonError>>,
+    {
+        let started = Instant::now();
+        let result = task.await;
+        self.record(command, args, &result, started);
+        result
+    }
+
+    /// 执行同步命令并记录调用结果
+    pub fn track_sync<T>(
+        &self,
+        command: &str,
+        args: serde_json::Value,
+        task: impl FnOnce() -> Result<T, AxonError>,
+    ) -> Result<T, AxonError> {
+        let started = Instant::now();
+        let result = task();
+        self.record(command, args, &result, started);
+        result
+    }
+
+    fn record<T>(
+        &self,
+        command: &str,
+        args: serde_json::Value,
+        result: &Result<T, AxonError>,
+        started: Instant,
+    ) {
+        let (outcome, error_kind, error) = match result {
+            Ok(_) => (AuditOutcome::Success, None, None),
+            Err(e) => (
+                AuditOutcome::Failure,
+                serde_json::to_value(e.kind)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string)),
+                Some(e.message.clone()),
+            ),
+        };
+        let entry = AuditEntry {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            command: command.to_string(),
+            args,
+            outcome,
+            error_kind,
+            error,
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+
+        if let Err(e) = self.append(&entry) {
+            warn!("写入审计日志失败: {}", e);
+        }
+    }
+
+    fn append(&self, entry: &AuditEntry) -> Result<(), String> {
+        let log_file = Self::get_log_file().ok_or("应用数据目录未初始化")?;
+        let path = log_file.path();
+        let mut line =
+            serde_json::to_string(entry).map_err(|e| format!("序列化审计记录失败: {}", e))?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建日志目录失败: {}", e))?;
+        }
+        if std::fs::metadata(path).is_ok_and(|m| m.len() + line.len() as u64 > MAX_FILE_SIZE) {
+            match log_file.rotate() {
+                Ok(()) => debug!("审计日志已轮转"),
+                Err(e) => warn!("轮转审计日志失败: {}", e),
+            }
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("打开审计日志失败: {}", e))?;
+        file.write_all(line.as_bytes())
+            .map_err(|e| format!("写入审计日志失败: {}", e))
+    }
+
+    /// 按条件查询审计记录（按时间倒序）
+    pub fn read(&self, filter: &AuditLogFilter) -> Result<Vec<AuditEntry>, AxonError> {
+        let Some(log_file) = Self::get_log_file() else {
+            return Ok(Vec::new());
+        };
+        let limit = filter.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let command = filter.command.as_deref().map(str::to_lowercase);
+        let query = filter.query.as_deref().map(str::to_lowercase);
+
+        let matches = |entry: &AuditEntry| {
+            command
+                .as_ref()
+                .is_none_or(|c| entry.command.to_lowercase().contains(c))
+                && filter.outcome.is_none_or(|o| entry.outcome == o)
+                && filter.since.is_none_or(|t| entry.timestamp >= t)
+                && filter.until.is_none_or(|t| entry.timestamp <= t)
+                && query.as_ref().is_none_or(|q| {
+                    entry.args.to_string().to_lowercase().contains(q)
+                        || entry
+                            .error
+                            .as_deref()
+                            .is_some_and(|e| e.to_lowercase().contains(q))
+                })
+        };
+
+        let _guard = self.write_lock.lock();
+        log_file
+            .read_recent(limit, |line| {
+                serde_json::from_str::<AuditEntry>(line)
+                    .ok()
+                    .filter(|entry| matches(entry))
+            })
+            .map_err(|e| AxonError::io("读取审计日志失败", &e))
+    }
+}
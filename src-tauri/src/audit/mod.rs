@@ -0,0 +1,16 @@
+//! 命令审计日志模块
+//!
+//! 记录所有会修改状态的命令调用（写入、删除、保存、执行等），
+//! 便于排查 Agent 的异常行为。
+//!
+//! ## 存储
+//!
+//! 每次调用追加一行 JSON 到 `<app_data_dir>/logs/audit.log`，
+//! 文件超过大小上限时轮转为 `audit.log.1` ~ `audit.log.N`。
+//! 参数只记录摘要（路径、长度等），不记录文件内容和密钥。
+
+mod log;
+mod types;
+
+pub use log::AuditLog;
+pub use types::*;
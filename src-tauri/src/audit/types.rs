@@ -0,0 +1,49 @@
+//! 审计日志类型定义
+
+use serde::{Deserialize, Serialize};
+
+/// 调用结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+/// 单条审计记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// 调用时间（毫秒时间戳）
+    pub timestamp: i64,
+    /// 命令名称
+    pub command: String,
+    /// 参数摘要
+    pub args: serde_json::Value,
+    pub outcome: AuditOutcome,
+    /// 失败时的错误类别
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<String>,
+    /// 失败时的错误信息
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 耗时（毫秒）
+    pub duration_ms: u64,
+}
+
+/// 审计日志查询条件
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogFilter {
+    /// 命令名称（包含匹配）
+    pub command: Option<String>,
+    pub outcome: Option<AuditOutcome>,
+    /// 起始时间（毫秒时间戳，包含）
+    pub since: Option<i64>,
+    /// 结束时间（毫秒时间戳，包含）
+    pub until: Option<i64>,
+    /// 在参数摘要和错误信息中搜索的关键字
+    pub query: Option<String>,
+    /// 返回条数上限
+    pub limit: Option<usize>,
+}
@@ -0,0 +1,291 @@
+//! 项目书签
+//!
+//! 每个项目一个 JSON 文件（`<app_data_dir>/bookmarks/<项目哈希>.json`），
+//! 记录用户收藏的文件和目录，可附带备注和颜色标签，供资源管理器快速访问。
+//! 书签以路径为标识，重复添加同一路径时更新备注和颜色。
+
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::json_store;
+use crate::utils::paths::{get_app_data_dir, normalize_project};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::debug;
+
+/// 存储目录（相对应用数据目录）
+const BOOKMARKS_DIR: &str = "bookmarks";
+
+/// 单个项目的书签数上限
+const MAX_BOOKMARKS: usize = 500;
+
+/// 备注的最大长度（字符）
+const MAX_NOTE_CHARS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookmarkKind {
+    File,
+    Directory,
+}
+
+/// 颜色标签
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookmarkColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+    Gray,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bookmark {
+    pub path: String,
+    pub kind: BookmarkKind,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub color: Option<BookmarkColor>,
+    /// Unix 毫秒
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 单个项目的持久化内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProjectBookmarks {
+    project: String,
+    bookmarks: Vec<Bookmark>,
+}
+
+/// 书签存储
+#[derive(Debug)]
+pub struct BookmarkStore {
+    /// 存储目录，为空时使用应用数据目录下的 bookmarks
+    dir: Option<PathBuf>,
+    /// 串行化读写
+    lock: Mutex<()>,
+}
+
+impl BookmarkStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            dir: None,
+            lock: Mutex::new(()),
+        })
+    }
+
+    #[cfg(test)]
+    fn with_dir(dir: PathBuf) -> Self {
+        Self {
+            dir: Some(dir),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 列出项目书签（按添加顺序）
+    pub fn list(&self, project: &str) -> Result<Vec<Bookmark>, AxonError> {
+        let _guard = self.lock.lock();
+        Ok(self
+            .read_project(project)?
+            .map(|file| file.bookmarks)
+            .unwrap_or_default())
+    }
+
+    /// 添加书签，路径已存在时更新备注和颜色
+    pub fn add(
+        &self,
+        project: &str,
+        path: &str,
+        note: Option<String>,
+        color: Option<BookmarkColor>,
+    ) -> Result<Bookmark, AxonError> {
+        let note = note
+            .map(|note| note.trim().to_string())
+            .filter(|note| !note.is_empty());
+        if note
+            .as_ref()
+            .is_some_and(|note| note.chars().count() > MAX_NOTE_CHARS)
+        {
+            return Err(AxonError::invalid_input(format!(
+                "书签备注不能超过 {} 个字符",
+                MAX_NOTE_CHARS
+            )));
+        }
+        let metadata =
+            std::fs::metadata(path).map_err(|e| AxonError::io("读取书签路径失败", &e))?;
+        let kind = if metadata.is_dir() {
+            BookmarkKind::Directory
+        } else {
+            BookmarkKind::File
+        };
+
+        let _guard = self.lock.lock();
+        let mut file = self
+            .read_project(project)?
+            .unwrap_or_else(|| ProjectBookmarks {
+                project: normalize_project(project),
+                bookmarks: Vec::new(),
+            });
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let bookmark = match file.bookmarks.iter_mut().find(|b| b.path == path) {
+            Some(existing) => {
+                existing.kind = kind;
+                existing.note = note;
+                existing.color = color;
+                existing.updated_at = now;
+                existing.clone()
+            }
+            None => {
+                if file.bookmarks.len() >= MAX_BOOKMARKS {
+                    return Err(AxonError::invalid_input(format!(
+                        "书签数超过上限（{} 个）",
+                        MAX_BOOKMARKS
+                    )));
+                }
+                let bookmark = Bookmark {
+                    path: path.to_string(),
+                    kind,
+                    note,
+                    color,
+                    created_at: now,
+                    updated_at: now,
+                };
+                file.bookmarks.push(bookmark.clone());
+                bookmark
+            }
+        };
+
+        self.write_project(&file)?;
+        debug!("已保存书签: {} / {}", file.project, path);
+        Ok(bookmark)
+    }
+
+    /// 删除书签，返回书签是否存在
+    pub fn remove(&self, project: &str, path: &str) -> Result<bool, AxonError> {
+        let _guard = self.lock.lock();
+        let Some(mut file) = self.read_project(project)? else {
+            return Ok(false);
+        };
+        let before = file.bookmarks.len();
+        file.bookmarks.retain(|b| b.path != path);
+        if file.bookmarks.len() == before {
+            return Ok(false);
+        }
+        self.write_project(&file)?;
+        Ok(true)
+    }
+
+    fn dir(&self) -> Result<PathBuf, AxonError> {
+        self.dir
+            .clone()
+            .or_else(|| get_app_data_dir().map(|d| d.join(BOOKMARKS_DIR)))
+            .ok_or_else(|| {
+                AxonError::localized(
+                    ErrorKind::Unavailable,
+                    "app.data_dir_unavailable",
+                    serde_json::json!(null),
+                )
+            })
+    }
+
+    fn project_path(&self, project: &str) -> Result<PathBuf, AxonError> {
+        let digest = Sha256::digest(normalize_project(project).as_bytes());
+        let name: String = digest
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Ok(self.dir()?.join(format!("{}.json", name)))
+    }
+
+    fn read_project(&self, project: &str) -> Result<Option<ProjectBookmarks>, AxonError> {
+        json_store::load(&self.project_path(project)?, "书签文件")
+    }
+
+    fn write_project(&self, file: &ProjectBookmarks) -> Result<(), AxonError> {
+        json_store::save(&self.project_path(&file.project)?, file, "书签文件")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store() -> (TempDir, BookmarkStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BookmarkStore::with_dir(dir.path().to_path_buf());
+        (dir, store)
+    }
+
+    #[test]
+    fn re_adding_updates_in_place() {
+        let (dir, store) = store();
+        let file = dir.path().join("main.rs");
+        std::fs::write(&file, "fn main() {}").unwrap();
+        let file = file.to_string_lossy().to_string();
+        let folder = dir.path().to_string_lossy().to_string();
+
+        let first = store
+            .add("/work/app", &file, Some("入口".to_string()), None)
+            .unwrap();
+        store.add("/work/app", &folder, None, None).unwrap();
+        // 项目路径末尾的分隔符不影响匹配；空白备注清除原备注
+        let updated = store
+            .add(
+                "/work/app/",
+                &file,
+                Some("  ".to_string()),
+                Some(BookmarkColor::Red),
+            )
+            .unwrap();
+        assert_eq!(updated.created_at, first.created_at);
+        assert_eq!(updated.note, None);
+
+        let listed = store.list("/work/app").unwrap();
+        let summary: Vec<_> = listed.iter().map(|b| (b.path.as_str(), b.kind)).collect();
+        assert_eq!(
+            summary,
+            [
+                (file.as_str(), BookmarkKind::File),
+                (folder.as_str(), BookmarkKind::Directory)
+            ]
+        );
+        assert_eq!(listed[0].color, Some(BookmarkColor::Red));
+        assert!(store.list("/work/other").unwrap().is_empty());
+    }
+
+    #[test]
+    fn limits_note_length_in_chars() {
+        let (dir, store) = store();
+        let target = dir.path().to_string_lossy();
+
+        let note = "好".repeat(MAX_NOTE_CHARS);
+        let saved = store
+            .add("/work/app", &target, Some(note.clone()), None)
+            .unwrap();
+        assert_eq!(saved.note, Some(note));
+        assert!(store
+            .add(
+                "/work/app",
+                &target,
+                Some("好".repeat(MAX_NOTE_CHARS + 1)),
+                None
+            )
+            .is_err());
+        assert!(store
+            .add("/work/app", "/definitely/not/here", None, None)
+            .is_err());
+
+        assert!(store.remove("/work/app", &target).unwrap());
+        assert!(!store.remove("/work/app", &target).unwrap());
+    }
+}
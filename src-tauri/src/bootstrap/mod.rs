@@ -0,0 +1,198 @@
+//! 启动就绪状态
+//!
+//! 启动过程拆分为若干阶段（应用目录、Plugin API、OpenCode 服务、前端），
+//! 每个阶段完成或失败时发送 `bootstrap:*` 事件，前端据此显示准确的启动进度，
+//! 也可以随时通过 `get_bootstrap_status` 查询当前状态。
+//!
+//! 主窗口只在前端报告就绪（`app-ready`）后显示，避免把加载失败的页面展示给用户；
+//! 前端长时间未就绪时记为失败，并弹出原生错误对话框（不依赖前端渲染），
+//! 由用户选择仍然显示窗口或退出应用。
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tracing::{error, info, warn};
+
+/// 应用数据目录就绪事件
+pub const EVENT_BOOTSTRAP_PATHS_READY: &str = "bootstrap:paths-ready";
+
+/// Plugin API 服务器启动事件
+pub const EVENT_BOOTSTRAP_PLUGIN_API_READY: &str = "bootstrap:plugin-api-ready";
+
+/// OpenCode 服务就绪事件
+pub const EVENT_BOOTSTRAP_OPENCODE_READY: &str = "bootstrap:opencode-ready";
+
+/// 前端就绪事件
+pub const EVENT_BOOTSTRAP_FRONTEND_READY: &str = "bootstrap:frontend-ready";
+
+/// 任一阶段失败事件
+pub const EVENT_BOOTSTRAP_FAILED: &str = "bootstrap:failed";
+
+/// 启动阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BootstrapStage {
+    Paths,
+    PluginApi,
+    Opencode,
+    Frontend,
+}
+
+impl BootstrapStage {
+    const ALL: [BootstrapStage; 4] = [Self::Paths, Self::PluginApi, Self::Opencode, Self::Frontend];
+
+    fn ready_event(self) -> &'static str {
+        match self {
+            Self::Paths => EVENT_BOOTSTRAP_PATHS_READY,
+            Self::PluginApi => EVENT_BOOTSTRAP_PLUGIN_API_READY,
+            Self::Opencode => EVENT_BOOTSTRAP_OPENCODE_READY,
+            Self::Frontend => EVENT_BOOTSTRAP_FRONTEND_READY,
+        }
+    }
+}
+
+/// 阶段状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Pending,
+    Ready,
+    Failed,
+}
+
+/// 单个阶段的状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrapStageState {
+    pub stage: BootstrapStage,
+    pub status: StageStatus,
+    /// 失败原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// 阶段结束时相对进程启动的时间（毫秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+}
+
+/// 启动状态汇总（也是各 bootstrap 事件的 payload）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootstrapStatus {
+    pub stages: Vec<BootstrapStageState>,
+    /// 所有阶段都已就绪
+    pub ready: bool,
+    /// 存在失败的阶段
+    pub failed: bool,
+}
+
+/// 启动状态记录器
+#[derive(Debug)]
+pub struct BootstrapTracker {
+    started: Instant,
+    stages: RwLock<Vec<BootstrapStageState>>,
+    app_handle: RwLock<Option<AppHandle>>,
+}
+
+impl BootstrapTracker {
+    pub fn new() -> Arc<Self> {
+        let stages = BootstrapStage::ALL
+            .into_iter()
+            .map(|stage| BootstrapStageState {
+                stage,
+                status: StageStatus::Pending,
+                message: None,
+                elapsed_ms: None,
+            })
+            .collect();
+        Arc::new(Self {
+            started: Instant::now(),
+            stages: RwLock::new(stages),
+            app_handle: RwLock::new(None),
+        })
+    }
+
+    /// 设置事件发送句柄（setup 开始时调用）
+    pub fn set_app_handle(&self, handle: AppHandle) {
+        *self.app_handle.write() = Some(handle);
+    }
+
+    /// 标记阶段就绪
+    pub fn ready(&self, stage: BootstrapStage) {
+        info!("启动阶段就绪: {:?}", stage);
+        self.update(stage, StageStatus::Ready, None);
+        self.emit(stage.ready_event());
+    }
+
+    /// 标记阶段失败
+    pub fn fail(&self, stage: BootstrapStage, message: impl Into<String>) {
+        let message = message.into();
+        error!("启动阶段失败: {:?}: {}", stage, message);
+        self.update(stage, StageStatus::Failed, Some(message));
+        self.emit(EVENT_BOOTSTRAP_FAILED);
+    }
+
+    /// 阶段是否仍未完成
+    pub fn is_pending(&self, stage: BootstrapStage) -> bool {
+        self.stages
+            .read()
+            .iter()
+            .any(|s| s.stage == stage && s.status == StageStatus::Pending)
+    }
+
+    /// 当前启动状态
+    pub fn status(&self) -> BootstrapStatus {
+        let stages = self.stages.read().clone();
+        BootstrapStatus {
+            ready: stages.iter().all(|s| s.status == StageStatus::Ready),
+            failed: stages.iter().any(|s| s.status == StageStatus::Failed),
+            stages,
+        }
+    }
+
+    fn update(&self, stage: BootstrapStage, status: StageStatus, message: Option<String>) {
+        let elapsed_ms = self.started.elapsed().as_millis() as u64;
+        if let Some(state) = self.stages.write().iter_mut().find(|s| s.stage == stage) {
+            state.status = status;
+            state.message = message;
+            state.elapsed_ms = Some(elapsed_ms);
+        }
+    }
+
+    fn emit(&self, event: &str) {
+        if let Some(handle) = self.app_handle.read().as_ref() {
+            if let Err(e) = handle.emit(event, self.status()) {
+                warn!("发送启动事件失败 {}: {}", event, e);
+            }
+        }
+    }
+}
+
+/// 前端未能就绪时的原生错误对话框
+///
+/// 前端可能已经无法渲染任何内容，所以用系统对话框提示；选择显示窗口后
+/// 前端稍后就绪也不受影响，选择退出则直接结束进程。
+pub fn show_frontend_failure(app: &AppHandle, message: &str) {
+    let handle = app.clone();
+    app.dialog()
+        .message(format!(
+            "界面未能正常加载：{}\n\n可以仍然显示主窗口查看，或退出后查看日志目录中的 axon.log。",
+            message
+        ))
+        .title("Axon 启动失败")
+        .kind(MessageDialogKind::Error)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "显示窗口".to_string(),
+            "退出".to_string(),
+        ))
+        .show(move |show| {
+            if show {
+                crate::tray::show_main_window(&handle);
+            } else {
+                info!("前端未就绪，用户选择退出");
+                handle.exit(1);
+            }
+        });
+}
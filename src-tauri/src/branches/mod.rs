@@ -0,0 +1,251 @@
+//! 会话分支
+//!
+//! 每个会话一个 JSON 文件（`<app_data_dir>/branches/<会话哈希>.json`），
+//! 记录从该会话的哪条消息分出的分支及其名称，供界面实现「编辑之前的消息并分叉」。
+//! 消息和分叉出的会话本身由 OpenCode 保存，这里只保存分支元数据。
+
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::json_store;
+use crate::utils::paths::get_app_data_dir;
+use parking_lot::Mutex;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::debug;
+
+/// 存储目录（相对应用数据目录）
+const BRANCHES_DIR: &str = "branches";
+
+/// 单个会话的分支数上限
+const MAX_BRANCHES: usize = 200;
+
+/// 分支名称的最大长度（字符）
+const MAX_NAME_CHARS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Branch {
+    pub id: String,
+    pub name: String,
+    /// 分支所属的会话
+    pub session_id: String,
+    /// 分支点：从该消息开始分叉
+    pub message_id: String,
+    /// 分叉出的会话（界面调用 OpenCode 分叉后关联）
+    #[serde(default)]
+    pub branch_session_id: Option<String>,
+    /// Unix 毫秒
+    pub created_at: i64,
+}
+
+/// 单个会话的持久化内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SessionBranches {
+    session_id: String,
+    branches: Vec<Branch>,
+}
+
+/// 会话分支存储
+#[derive(Debug)]
+pub struct BranchStore {
+    /// 存储目录，为空时使用应用数据目录下的 branches
+    dir: Option<PathBuf>,
+    /// 串行化读写
+    lock: Mutex<()>,
+}
+
+impl BranchStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            dir: None,
+            lock: Mutex::new(()),
+        })
+    }
+
+    #[cfg(test)]
+    fn with_dir(dir: PathBuf) -> Self {
+        Self {
+            dir: Some(dir),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 列出会话的分支（按创建顺序）
+    pub fn list(&self, session_id: &str) -> Result<Vec<Branch>, AxonError> {
+        let _guard = self.lock.lock();
+        Ok(self
+            .read_session(session_id)?
+            .map(|file| file.branches)
+            .unwrap_or_default())
+    }
+
+    /// 在会话的指定消息处创建分支，同一会话内名称不能重复
+    pub fn create(
+        &self,
+        session_id: &str,
+        message_id: &str,
+        name: &str,
+        branch_session_id: Option<String>,
+    ) -> Result<Branch, AxonError> {
+        if session_id.trim().is_empty() || message_id.trim().is_empty() {
+            return Err(AxonError::invalid_input("会话 ID 和消息 ID 不能为空"));
+        }
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AxonError::invalid_input("分支名称不能为空"));
+        }
+        if name.chars().count() > MAX_NAME_CHARS {
+            return Err(AxonError::invalid_input(format!(
+                "分支名称不能超过 {} 个字符",
+                MAX_NAME_CHARS
+            )));
+        }
+        let branch_session_id = branch_session_id
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty());
+
+        let _guard = self.lock.lock();
+        let mut file = self
+            .read_session(session_id)?
+            .unwrap_or_else(|| SessionBranches {
+                session_id: session_id.to_string(),
+                branches: Vec::new(),
+            });
+        if file.branches.iter().any(|b| b.name == name) {
+            return Err(AxonError::invalid_input(format!(
+                "分支名称已存在: {}",
+                name
+            )));
+        }
+        if file.branches.len() >= MAX_BRANCHES {
+            return Err(AxonError::invalid_input(format!(
+                "分支数超过上限（{} 个）",
+                MAX_BRANCHES
+            )));
+        }
+
+        let branch = Branch {
+            id: format!("branch-{:016x}", rand::thread_rng().gen::<u64>()),
+            name: name.to_string(),
+            session_id: session_id.to_string(),
+            message_id: message_id.to_string(),
+            branch_session_id,
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        file.branches.push(branch.clone());
+        self.write_session(&file)?;
+        debug!("已创建分支: {} / {} @ {}", session_id, name, message_id);
+        Ok(branch)
+    }
+
+    /// 删除分支，返回分支是否存在
+    pub fn delete(&self, session_id: &str, branch_id: &str) -> Result<bool, AxonError> {
+        let _guard = self.lock.lock();
+        let Some(mut file) = self.read_session(session_id)? else {
+            return Ok(false);
+        };
+        let before = file.branches.len();
+        file.branches.retain(|b| b.id != branch_id);
+        if file.branches.len() == before {
+            return Ok(false);
+        }
+        if file.branches.is_empty() {
+            let path = self.session_path(session_id)?;
+            std::fs::remove_file(&path).map_err(|e| AxonError::io("删除分支文件失败", &e))?;
+            return Ok(true);
+        }
+        self.write_session(&file)?;
+        Ok(true)
+    }
+
+    fn dir(&self) -> Result<PathBuf, AxonError> {
+        self.dir
+            .clone()
+            .or_else(|| get_app_data_dir().map(|d| d.join(BRANCHES_DIR)))
+            .ok_or_else(|| {
+                AxonError::localized(
+                    ErrorKind::Unavailable,
+                    "app.data_dir_unavailable",
+                    serde_json::json!(null),
+                )
+            })
+    }
+
+    fn session_path(&self, session_id: &str) -> Result<PathBuf, AxonError> {
+        let digest = Sha256::digest(session_id.as_bytes());
+        let name: String = digest
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Ok(self.dir()?.join(format!("{}.json", name)))
+    }
+
+    fn read_session(&self, session_id: &str) -> Result<Option<SessionBranches>, AxonError> {
+        json_store::load(&self.session_path(session_id)?, "分支文件")
+    }
+
+    fn write_session(&self, file: &SessionBranches) -> Result<(), AxonError> {
+        json_store::save(&self.session_path(&file.session_id)?, file, "分支文件")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store() -> (TempDir, BranchStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = BranchStore::with_dir(dir.path().to_path_buf());
+        (dir, store)
+    }
+
+    #[test]
+    fn records_branch_points_and_forks() {
+        let (_dir, store) = store();
+        let forked = store
+            .create("ses_1", "msg_3", " 换个思路 ", Some("ses_2".to_string()))
+            .unwrap();
+        let retry = store
+            .create("ses_1", "msg_5", "重试", Some("  ".to_string()))
+            .unwrap();
+        assert_eq!(forked.name, "换个思路");
+        assert_eq!(retry.branch_session_id, None);
+
+        // 分叉出的会话可以继续分支，名称只在同一会话内唯一
+        let nested = store.create("ses_2", "msg_1", "换个思路", None).unwrap();
+        assert_eq!(nested.session_id, "ses_2");
+
+        let listed = store.list("ses_1").unwrap();
+        let points: Vec<_> = listed
+            .iter()
+            .map(|b| (b.message_id.as_str(), b.branch_session_id.as_deref()))
+            .collect();
+        assert_eq!(points, [("msg_3", Some("ses_2")), ("msg_5", None)]);
+        assert_eq!(store.list("ses_2").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn validates_names_and_cleans_up_empty_sessions() {
+        let (_dir, store) = store();
+        assert!(store.create("ses_1", " ", "方案 A", None).is_err());
+        assert!(store.create("ses_1", "msg_1", "  ", None).is_err());
+        assert!(store
+            .create("ses_1", "msg_1", &"名".repeat(MAX_NAME_CHARS + 1), None)
+            .is_err());
+
+        let branch = store.create("ses_1", "msg_1", "方案 A", None).unwrap();
+        assert!(store.create("ses_1", "msg_2", " 方案 A ", None).is_err());
+
+        // 删除最后一个分支时移除会话文件
+        let path = store.session_path("ses_1").unwrap();
+        assert!(path.exists());
+        assert!(!store.delete("ses_1", "branch-unknown").unwrap());
+        assert!(store.delete("ses_1", &branch.id).unwrap());
+        assert!(!path.exists());
+        assert!(!store.delete("ses_1", &branch.id).unwrap());
+    }
+}
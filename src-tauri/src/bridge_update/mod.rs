@@ -0,0 +1,293 @@
+//! Axon Bridge 插件更新
+//!
+//! 从设置中配置的 GitHub 仓库查找带有 `axon-bridge.js` 附件的最新正式发布，与已安装
+//! 版本比较。更新时下载插件并按发布中的 `axon-bridge.js.sha256`（或 GitHub 提供的附件
+//! 摘要）校验 SHA-256，校验通过后替换插件文件。新插件需要重启 OpenCode 才会加载。
+
+use crate::error::AxonError;
+use crate::settings::SettingsManager;
+use crate::utils::plugin_installer::{
+    bundled_plugin_version, install_plugin, is_newer_version, read_installed_plugin,
+    InstalledPlugin, PluginSource,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// 发布中的插件附件名
+const ASSET_NAME: &str = "axon-bridge.js";
+
+/// 发布中的校验和附件名（`sha256sum` 输出格式）
+const CHECKSUM_ASSET_NAME: &str = "axon-bridge.js.sha256";
+
+/// 发布标签前缀（`axon-bridge-v0.2.0` 或 `v0.2.0`）
+const TAG_PREFIX: &str = "axon-bridge-";
+
+/// 请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 插件文件大小上限
+const MAX_ASSET_SIZE: usize = 20 * 1024 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    /// GitHub 计算的附件摘要（`sha256:<hex>`），较早的发布没有
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+impl GithubRelease {
+    fn asset(&self, name: &str) -> Option<&GithubAsset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// 插件更新检查结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeUpdateInfo {
+    /// 检查的 GitHub 仓库
+    pub repo: String,
+    /// 已安装版本（没有安装清单时为打包版本）
+    pub current_version: String,
+    /// 已安装插件的来源，没有安装清单时为空
+    pub current_source: Option<PluginSource>,
+    /// 最新发布的版本，仓库没有插件发布时为空
+    pub latest_version: Option<String>,
+    pub available: bool,
+    pub release_url: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Axon Bridge 插件更新器
+pub struct BridgeUpdater {
+    settings: Arc<SettingsManager>,
+    http: reqwest::Client,
+}
+
+impl BridgeUpdater {
+    pub fn new(settings: Arc<SettingsManager>) -> Arc<Self> {
+        Arc::new(Self {
+            settings,
+            http: reqwest::Client::builder()
+                // GitHub API 要求带 User-Agent
+                .user_agent(concat!("axon-desktop/", env!("CARGO_PKG_VERSION")))
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+        })
+    }
+
+    /// 检查是否有更新的插件发布
+    pub async fn check(&self) -> Result<BridgeUpdateInfo, AxonError> {
+        let repo = self.settings.get_bridge_update_repo();
+        let installed = read_installed_plugin();
+        let current_version = installed
+            .as_ref()
+            .map(|plugin| plugin.version.clone())
+            .unwrap_or_else(bundled_plugin_version);
+
+        let latest = latest_release(self.fetch_releases(&repo).await?);
+        let available = latest
+            .as_ref()
+            .is_some_and(|(version, _)| is_newer_version(version, &current_version));
+        debug!(
+            "Axon Bridge 插件: 当前 {}，最新 {:?}",
+            current_version,
+            latest.as_ref().map(|(version, _)| version)
+        );
+
+        Ok(BridgeUpdateInfo {
+            repo,
+            current_version,
+            current_source: installed.map(|plugin| plugin.source),
+            latest_version: latest.as_ref().map(|(version, _)| version.clone()),
+            available,
+            release_url: latest.as_ref().map(|(_, release)| release.html_url.clone()),
+            notes: latest.and_then(|(_, release)| release.body),
+        })
+    }
+
+    /// 下载、校验并安装最新的插件发布
+    pub async fn update(&self) -> Result<InstalledPlugin, AxonError> {
+        let repo = self.settings.get_bridge_update_repo();
+        let current_version = read_installed_plugin()
+            .map(|plugin| plugin.version)
+            .unwrap_or_else(bundled_plugin_version);
+        let (version, release) =
+            latest_release(self.fetch_releases(&repo).await?).ok_or_else(|| {
+                AxonError::not_found(format!("仓库 {} 中没有 Axon Bridge 插件发布", repo))
+            })?;
+        if !is_newer_version(&version, &current_version) {
+            return Err(AxonError::invalid_input(format!(
+                "Axon Bridge 插件已是最新版本（{}）",
+                current_version
+            )));
+        }
+
+        let expected = self.expected_checksum(&release).await?;
+        let asset = release
+            .asset(ASSET_NAME)
+            .ok_or_else(|| AxonError::not_found(format!("发布中缺少 {}", ASSET_NAME)))?;
+        let content = self.download(&asset.browser_download_url).await?;
+        let actual = format!("{:x}", Sha256::digest(&content));
+        if actual != expected {
+            return Err(AxonError::invalid_data(format!(
+                "Axon Bridge 插件校验失败：期望 {}，实际 {}",
+                expected, actual
+            )));
+        }
+
+        let installed = tokio::task::spawn_blocking(move || {
+            install_plugin(&content, &version, PluginSource::Release)
+        })
+        .await?
+        .map_err(AxonError::internal)?;
+        info!("Axon Bridge 插件已更新到 {}", installed.version);
+        Ok(installed)
+    }
+
+    async fn fetch_releases(&self, repo: &str) -> Result<Vec<GithubRelease>, AxonError> {
+        let url = format!("https://api.github.com/repos/{}/releases?per_page=30", repo);
+        let response = self
+            .http
+            .get(&url)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+            .send()
+            .await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(AxonError::not_found(format!("GitHub 仓库不存在: {}", repo)));
+        }
+        Ok(response.error_for_status()?.json().await?)
+    }
+
+    /// 发布中的期望校验和：优先使用校验和附件，其次使用 GitHub 的附件摘要
+    async fn expected_checksum(&self, release: &GithubRelease) -> Result<String, AxonError> {
+        if let Some(asset) = release.asset(CHECKSUM_ASSET_NAME) {
+            let content = self.download(&asset.browser_download_url).await?;
+            return parse_checksum(&String::from_utf8_lossy(&content)).ok_or_else(|| {
+                AxonError::invalid_data(format!("{} 格式无效", CHECKSUM_ASSET_NAME))
+            });
+        }
+        release
+            .asset(ASSET_NAME)
+            .and_then(|asset| asset.digest.as_deref())
+            .and_then(|digest| digest.strip_prefix("sha256:"))
+            .and_then(parse_checksum)
+            .ok_or_else(|| {
+                AxonError::invalid_data(format!("发布 {} 缺少校验和，拒绝安装", release.tag_name))
+            })
+    }
+
+    async fn download(&self, url: &str) -> Result<Vec<u8>, AxonError> {
+        let bytes = self
+            .http
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        if bytes.len() > MAX_ASSET_SIZE {
+            return Err(AxonError::invalid_data("下载的插件文件过大"));
+        }
+        Ok(bytes.to_vec())
+    }
+}
+
+/// 标签对应的版本号（去掉 `axon-bridge-` 和 `v` 前缀）
+fn release_version(tag: &str) -> Option<String> {
+    let version = tag.strip_prefix(TAG_PREFIX).unwrap_or(tag);
+    let version = version.strip_prefix('v').unwrap_or(version);
+    semver::Version::parse(version).ok()?;
+    Some(version.to_string())
+}
+
+/// 版本最高的、带插件附件的正式发布
+fn latest_release(releases: Vec<GithubRelease>) -> Option<(String, GithubRelease)> {
+    releases
+        .into_iter()
+        .filter(|release| !release.draft && !release.prerelease)
+        .filter(|release| release.asset(ASSET_NAME).is_some())
+        .filter_map(|release| Some((release_version(&release.tag_name)?, release)))
+        .reduce(|best, candidate| {
+            if is_newer_version(&candidate.0, &best.0) {
+                candidate
+            } else {
+                best
+            }
+        })
+}
+
+/// 解析 `sha256sum` 格式（`<hex>  <文件名>`）或纯十六进制的校验和
+fn parse_checksum(text: &str) -> Option<String> {
+    let hex = text.split_whitespace().next()?.to_ascii_lowercase();
+    (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit())).then_some(hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, assets: &[&str], prerelease: bool) -> GithubRelease {
+        GithubRelease {
+            tag_name: tag.to_string(),
+            html_url: format!("https://github.com/o/r/releases/tag/{}", tag),
+            body: None,
+            draft: false,
+            prerelease,
+            assets: assets
+                .iter()
+                .map(|name| GithubAsset {
+                    name: name.to_string(),
+                    browser_download_url: String::new(),
+                    digest: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn picks_highest_stable_release_with_plugin_asset() {
+        let releases = vec![
+            release("v0.3.0", &["axon-desktop.dmg"], false),
+            release("axon-bridge-v0.2.1", &[ASSET_NAME], false),
+            release("v0.4.0-beta.1", &[ASSET_NAME], true),
+            release("v0.2.0", &[ASSET_NAME, CHECKSUM_ASSET_NAME], false),
+            release("nightly", &[ASSET_NAME], false),
+        ];
+        let (version, release) = latest_release(releases).unwrap();
+        assert_eq!(version, "0.2.1");
+        assert_eq!(release.tag_name, "axon-bridge-v0.2.1");
+    }
+
+    #[test]
+    fn parses_sha256sum_output() {
+        let hex = "a".repeat(64);
+        assert_eq!(
+            parse_checksum(&format!("{}  axon-bridge.js\n", hex.to_uppercase())),
+            Some(hex.clone())
+        );
+        assert_eq!(parse_checksum(&hex), Some(hex));
+        assert_eq!(parse_checksum("deadbeef  axon-bridge.js"), None);
+        assert_eq!(parse_checksum(""), None);
+    }
+}
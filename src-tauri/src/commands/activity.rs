@@ -0,0 +1,18 @@
+//! Agent 活动时间线命令
+
+use crate::activity::ActivityEntry;
+use crate::error::AxonError;
+use crate::state::AppState;
+use tauri::State;
+use tracing::debug;
+
+/// 获取会话最近的工具调用记录（按开始顺序），`limit` 为空时返回全部保留的记录
+#[tauri::command]
+pub async fn get_session_activity(
+    state: State<'_, AppState>,
+    session_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<ActivityEntry>, AxonError> {
+    debug!("获取会话活动: {}", session_id);
+    Ok(state.activity.session_activity(&session_id, limit))
+}
@@ -6,9 +6,14 @@
 //! - 删除 Agent 配置
 //! - 获取 Agent 存储目录
 
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::team_sync::{DefinitionScope, TeamItemKind};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, State};
 use tracing::{debug, error, info};
 
 /// Agent 配置目录名称
@@ -37,13 +42,16 @@ pub struct AgentSummary {
     pub builtin: Option<bool>,
     /// 更新时间
     pub updated_at: i64,
+    /// 来源（团队仓库中的定义只读）
+    #[serde(default)]
+    pub scope: DefinitionScope,
 }
 
 /// 获取 Agent 配置存储目录
 /// 
 /// 返回应用数据目录下的 agents 文件夹路径
 #[tauri::command]
-pub async fn get_agents_directory(app: AppHandle) -> Result<String, String> {
+pub async fn get_agents_directory(app: AppHandle) -> Result<String, AxonError> {
     let app_data_dir = app
         .path()
         .app_data_dir()
@@ -55,7 +63,7 @@ pub async fn get_agents_directory(app: AppHandle) -> Result<String, String> {
     if !agents_dir.exists() {
         std::fs::create_dir_all(&agents_dir).map_err(|e| {
             error!("创建 agents 目录失败: {:?}, 错误: {}", agents_dir, e);
-            format!("创建 agents 目录失败: {}", e)
+            AxonError::io("创建 agents 目录失败", &e)
         })?;
         info!("创建 agents 目录: {:?}", agents_dir);
     }
@@ -67,75 +75,33 @@ pub async fn get_agents_directory(app: AppHandle) -> Result<String, String> {
 /// 
 /// 读取 agents 目录下的所有 JSON 文件，返回配置摘要列表
 #[tauri::command]
-pub async fn list_agents(app: AppHandle) -> Result<Vec<AgentSummary>, String> {
-    let agents_dir = get_agents_dir_path(&app)?;
-    
-    debug!("列出 agents 目录: {:?}", agents_dir);
-    
-    if !agents_dir.exists() {
-        debug!("agents 目录不存在，返回空列表");
-        return Ok(Vec::new());
-    }
-    
-    let mut agents = Vec::new();
-    
-    let entries = std::fs::read_dir(&agents_dir).map_err(|e| {
-        error!("读取 agents 目录失败: {:?}, 错误: {}", agents_dir, e);
-        format!("读取 agents 目录失败: {}", e)
-    })?;
-    
-    for entry in entries {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(e) => {
-                debug!("跳过无法读取的条目: {}", e);
-                continue;
-            }
-        };
-        
-        let path = entry.path();
-        
-        // 只处理 .json 文件
-        if !path.is_file() || path.extension().map(|e| e != "json").unwrap_or(true) {
-            continue;
-        }
-        
-        // 读取并解析 JSON
-        match read_agent_summary(&path) {
-            Ok(summary) => {
-                agents.push(summary);
-            }
-            Err(e) => {
-                debug!("跳过无法解析的 agent 文件 {:?}: {}", path, e);
-            }
-        }
-    }
-    
-    // 按更新时间降序排序
-    agents.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
-    
-    debug!("找到 {} 个 agent 配置", agents.len());
-    Ok(agents)
+pub async fn list_agents(app: AppHandle, state: State<'_, AppState>) -> Result<Vec<AgentSummary>, AxonError> {
+    collect_agents(&app, &state)
 }
 
 /// 读取单个 Agent 完整配置
 /// 
 /// 根据 Agent ID 读取完整的 JSON 配置
 #[tauri::command]
-pub async fn read_agent(app: AppHandle, agent_id: String) -> Result<String, String> {
+pub async fn read_agent(app: AppHandle, state: State<'_, AppState>, agent_id: String) -> Result<String, AxonError> {
     let agents_dir = get_agents_dir_path(&app)?;
     let agent_path = agents_dir.join(format!("{}{}", agent_id, AGENT_FILE_EXT));
     
     debug!("读取 agent 配置: {:?}", agent_path);
     
-    if !agent_path.exists() {
+    // 本地不存在时读取团队仓库中的定义
+    let agent_path = if agent_path.exists() {
+        agent_path
+    } else if let Some(team_path) = state.team_sync.definition_path(TeamItemKind::Agent, &agent_id) {
+        team_path
+    } else {
         error!("Agent 配置文件不存在: {:?}", agent_path);
-        return Err(format!("Agent 不存在: {}", agent_id));
-    }
+        return Err(AxonError::not_found(format!("Agent 不存在: {}", agent_id)));
+    };
     
     let content = std::fs::read_to_string(&agent_path).map_err(|e| {
         error!("读取 agent 文件失败: {:?}, 错误: {}", agent_path, e);
-        format!("读取 Agent 配置失败: {}", e)
+        AxonError::io("读取 Agent 配置失败", &e)
     })?;
     
     Ok(content)
@@ -145,61 +111,77 @@ pub async fn read_agent(app: AppHandle, agent_id: String) -> Result<String, Stri
 /// 
 /// 将 Agent 配置保存到文件，文件名为 {agent_id}.json
 #[tauri::command]
-pub async fn save_agent(app: AppHandle, agent_id: String, config: String) -> Result<(), String> {
-    let agents_dir = get_agents_dir_path(&app)?;
-    
-    // 确保目录存在
-    if !agents_dir.exists() {
-        std::fs::create_dir_all(&agents_dir).map_err(|e| {
-            error!("创建 agents 目录失败: {:?}, 错误: {}", agents_dir, e);
-            format!("创建 agents 目录失败: {}", e)
+pub async fn save_agent(app: AppHandle, state: State<'_, AppState>, agent_id: String, config: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "agentId": &agent_id, "bytes": config.len() });
+    state.audit.track("save_agent", audit_args, async {
+        let agents_dir = get_agents_dir_path(&app)?;
+
+        // 确保目录存在
+        if !agents_dir.exists() {
+            std::fs::create_dir_all(&agents_dir).map_err(|e| {
+                error!("创建 agents 目录失败: {:?}, 错误: {}", agents_dir, e);
+                AxonError::io("创建 agents 目录失败", &e)
+            })?;
+        }
+
+        let agent_path = agents_dir.join(format!("{}{}", agent_id, AGENT_FILE_EXT));
+
+        debug!("保存 agent 配置: {:?}", agent_path);
+
+        // 验证 JSON 格式
+        let _: serde_json::Value = serde_json::from_str(&config).map_err(|e| {
+            error!("无效的 JSON 格式: {}", e);
+            format!("无效的 Agent 配置格式: {}", e)
         })?;
-    }
-    
-    let agent_path = agents_dir.join(format!("{}{}", agent_id, AGENT_FILE_EXT));
-    
-    debug!("保存 agent 配置: {:?}", agent_path);
-    
-    // 验证 JSON 格式
-    let _: serde_json::Value = serde_json::from_str(&config).map_err(|e| {
-        error!("无效的 JSON 格式: {}", e);
-        format!("无效的 Agent 配置格式: {}", e)
-    })?;
-    
-    // 格式化 JSON 输出（便于阅读）
-    let formatted = format_json(&config)?;
-    
-    std::fs::write(&agent_path, formatted).map_err(|e| {
-        error!("写入 agent 文件失败: {:?}, 错误: {}", agent_path, e);
-        format!("保存 Agent 配置失败: {}", e)
-    })?;
-    
-    info!("Agent 配置已保存: {}", agent_id);
-    Ok(())
+
+        // 格式化 JSON 输出（便于阅读）
+        let formatted = format_json(&config)?;
+
+        std::fs::write(&agent_path, formatted).map_err(|e| {
+            error!("写入 agent 文件失败: {:?}, 错误: {}", agent_path, e);
+            AxonError::io("保存 Agent 配置失败", &e)
+        })?;
+
+        info!("Agent 配置已保存: {}", agent_id);
+        state.app_menu.refresh(&app);
+        Ok(())
+    })
+    .await
 }
 
 /// 删除 Agent 配置
 /// 
 /// 删除指定 ID 的 Agent 配置文件
 #[tauri::command]
-pub async fn delete_agent(app: AppHandle, agent_id: String) -> Result<(), String> {
-    let agents_dir = get_agents_dir_path(&app)?;
-    let agent_path = agents_dir.join(format!("{}{}", agent_id, AGENT_FILE_EXT));
-    
-    debug!("删除 agent 配置: {:?}", agent_path);
-    
-    if !agent_path.exists() {
-        error!("Agent 配置文件不存在: {:?}", agent_path);
-        return Err(format!("Agent 不存在: {}", agent_id));
-    }
-    
-    std::fs::remove_file(&agent_path).map_err(|e| {
-        error!("删除 agent 文件失败: {:?}, 错误: {}", agent_path, e);
-        format!("删除 Agent 配置失败: {}", e)
-    })?;
-    
-    info!("Agent 配置已删除: {}", agent_id);
-    Ok(())
+pub async fn delete_agent(app: AppHandle, state: State<'_, AppState>, agent_id: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "agentId": &agent_id });
+    state.audit.track("delete_agent", audit_args, async {
+        let agents_dir = get_agents_dir_path(&app)?;
+        let agent_path = agents_dir.join(format!("{}{}", agent_id, AGENT_FILE_EXT));
+
+        debug!("删除 agent 配置: {:?}", agent_path);
+
+        if !agent_path.exists() {
+            if state.team_sync.definition_path(TeamItemKind::Agent, &agent_id).is_some() {
+                return Err(AxonError::invalid_input(format!(
+                    "Agent {} 来自团队配置仓库，不能删除",
+                    agent_id
+                )));
+            }
+            error!("Agent 配置文件不存在: {:?}", agent_path);
+            return Err(AxonError::not_found(format!("Agent 不存在: {}", agent_id)));
+        }
+
+        std::fs::remove_file(&agent_path).map_err(|e| {
+            error!("删除 agent 文件失败: {:?}, 错误: {}", agent_path, e);
+            AxonError::io("删除 Agent 配置失败", &e)
+        })?;
+
+        info!("Agent 配置已删除: {}", agent_id);
+        state.app_menu.refresh(&app);
+        Ok(())
+    })
+    .await
 }
 
 /// 批量保存 Agent 配置
@@ -208,42 +190,48 @@ pub async fn delete_agent(app: AppHandle, agent_id: String) -> Result<(), String
 #[tauri::command]
 pub async fn save_agents_batch(
     app: AppHandle, 
+    state: State<'_, AppState>,
     agents: Vec<(String, String)>
-) -> Result<(), String> {
-    let agents_dir = get_agents_dir_path(&app)?;
-    
-    // 确保目录存在
-    if !agents_dir.exists() {
-        std::fs::create_dir_all(&agents_dir).map_err(|e| {
-            error!("创建 agents 目录失败: {:?}, 错误: {}", agents_dir, e);
-            format!("创建 agents 目录失败: {}", e)
-        })?;
-    }
-    
-    let mut errors = Vec::new();
-    
-    for (agent_id, config) in agents {
-        let agent_path = agents_dir.join(format!("{}{}", agent_id, AGENT_FILE_EXT));
-        
-        // 验证并格式化 JSON
-        match format_json(&config) {
-            Ok(formatted) => {
-                if let Err(e) = std::fs::write(&agent_path, formatted) {
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "agentIds": agents.iter().map(|(id, _)| id).collect::<Vec<_>>() });
+    state.audit.track("save_agents_batch", audit_args, async {
+        let agents_dir = get_agents_dir_path(&app)?;
+
+        // 确保目录存在
+        if !agents_dir.exists() {
+            std::fs::create_dir_all(&agents_dir).map_err(|e| {
+                error!("创建 agents 目录失败: {:?}, 错误: {}", agents_dir, e);
+                AxonError::io("创建 agents 目录失败", &e)
+            })?;
+        }
+
+        let mut errors = Vec::new();
+
+        for (agent_id, config) in agents {
+            let agent_path = agents_dir.join(format!("{}{}", agent_id, AGENT_FILE_EXT));
+
+            // 验证并格式化 JSON
+            match format_json(&config) {
+                Ok(formatted) => {
+                    if let Err(e) = std::fs::write(&agent_path, formatted) {
+                        errors.push(format!("{}: {}", agent_id, e));
+                    }
+                }
+                Err(e) => {
                     errors.push(format!("{}: {}", agent_id, e));
                 }
             }
-            Err(e) => {
-                errors.push(format!("{}: {}", agent_id, e));
-            }
         }
-    }
-    
-    if errors.is_empty() {
-        info!("批量保存 agent 配置成功");
-        Ok(())
-    } else {
-        Err(format!("部分保存失败: {}", errors.join(", ")))
-    }
+        state.app_menu.refresh(&app);
+
+        if errors.is_empty() {
+            info!("批量保存 agent 配置成功");
+            Ok(())
+        } else {
+            Err(AxonError::internal(format!("部分保存失败: {}", errors.join(", "))))
+        }
+    })
+    .await
 }
 
 // ============================================================================
@@ -251,7 +239,7 @@ pub async fn save_agents_batch(
 // ============================================================================
 
 /// 获取 agents 目录路径
-fn get_agents_dir_path(app: &AppHandle) -> Result<PathBuf, String> {
+pub(super) fn get_agents_dir_path(app: &AppHandle) -> Result<PathBuf, AxonError> {
     let app_data_dir = app
         .path()
         .app_data_dir()
@@ -260,10 +248,81 @@ fn get_agents_dir_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(app_data_dir.join(AGENTS_DIR))
 }
 
+/// 读取本地和团队的 Agent 配置摘要（按更新时间降序）
+pub(crate) fn collect_agents(app: &AppHandle, state: &AppState) -> Result<Vec<AgentSummary>, AxonError> {
+    let agents_dir = get_agents_dir_path(app)?;
+    
+    debug!("列出 agents 目录: {:?}", agents_dir);
+    
+    if !agents_dir.exists() {
+        debug!("agents 目录不存在，只返回团队定义");
+        let mut agents = Vec::new();
+        merge_team_agents(state, &mut agents);
+        return Ok(agents);
+    }
+    
+    let mut agents = Vec::new();
+    
+    let entries = std::fs::read_dir(&agents_dir).map_err(|e| {
+        error!("读取 agents 目录失败: {:?}, 错误: {}", agents_dir, e);
+        AxonError::io("读取 agents 目录失败", &e)
+    })?;
+    
+    for entry in entries {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(e) => {
+                debug!("跳过无法读取的条目: {}", e);
+                continue;
+            }
+        };
+        
+        let path = entry.path();
+        
+        // 只处理 .json 文件
+        if !path.is_file() || path.extension().map(|e| e != "json").unwrap_or(true) {
+            continue;
+        }
+        
+        // 读取并解析 JSON
+        match read_agent_summary(&path) {
+            Ok(summary) => {
+                agents.push(summary);
+            }
+            Err(e) => {
+                debug!("跳过无法解析的 agent 文件 {:?}: {}", path, e);
+            }
+        }
+    }
+    
+    merge_team_agents(state, &mut agents);
+
+    // 按更新时间降序排序
+    agents.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    
+    debug!("找到 {} 个 agent 配置", agents.len());
+    Ok(agents)
+}
+
+/// 合并团队仓库中的 Agent 定义（本地存在同 ID 的定义时跳过）
+fn merge_team_agents(state: &AppState, agents: &mut Vec<AgentSummary>) {
+    let local_ids: HashSet<String> = agents.iter().map(|s| s.id.clone()).collect();
+    for definition in state.team_sync.definitions(TeamItemKind::Agent) {
+        match read_agent_summary(&definition.path) {
+            Ok(mut summary) if !local_ids.contains(&summary.id) => {
+                summary.scope = DefinitionScope::Team;
+                agents.push(summary);
+            }
+            Ok(_) => {}
+            Err(e) => debug!("跳过无法解析的团队 agent 文件 {:?}: {}", definition.path, e),
+        }
+    }
+}
+
 /// 从文件读取 Agent 摘要
-fn read_agent_summary(path: &Path) -> Result<AgentSummary, String> {
+fn read_agent_summary(path: &Path) -> Result<AgentSummary, AxonError> {
     let content = std::fs::read_to_string(path)
-        .map_err(|e| format!("读取文件失败: {}", e))?;
+        .map_err(|e| AxonError::localized_io("fs.read_file_failed", &e))?;
     
     let json: serde_json::Value = serde_json::from_str(&content)
         .map_err(|e| format!("解析 JSON 失败: {}", e))?;
@@ -314,14 +373,15 @@ fn read_agent_summary(path: &Path) -> Result<AgentSummary, String> {
         model_id,
         builtin,
         updated_at,
+        scope: DefinitionScope::Local,
     })
 }
 
 /// 格式化 JSON 字符串（美化输出）
-fn format_json(json_str: &str) -> Result<String, String> {
+fn format_json(json_str: &str) -> Result<String, AxonError> {
     let value: serde_json::Value = serde_json::from_str(json_str)
-        .map_err(|e| format!("无效的 JSON: {}", e))?;
+        .map_err(|e| AxonError::invalid_input(format!("无效的 JSON: {}", e)))?;
     
     serde_json::to_string_pretty(&value)
-        .map_err(|e| format!("格式化 JSON 失败: {}", e))
+        .map_err(|e| AxonError::internal(format!("格式化 JSON 失败: {}", e)))
 }
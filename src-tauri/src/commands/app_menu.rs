@@ -0,0 +1,15 @@
+//! 应用菜单命令
+
+use crate::app_menu::RecentProject;
+use crate::state::AppState;
+use tauri::{AppHandle, State};
+
+/// 同步菜单中的最近项目（按最近使用排序），列表变化时重建菜单
+#[tauri::command]
+pub fn set_menu_recent_projects(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    projects: Vec<RecentProject>,
+) {
+    state.app_menu.set_recent_projects(&app, projects);
+}
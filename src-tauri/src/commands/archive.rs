@@ -0,0 +1,578 @@
+//! 归档命令模块
+//!
+//! 提供 zip 归档的打包与解压功能：
+//! - 将多个文件/目录打包为 zip
+//! - 将 zip 解压到指定目录（防止路径穿越和经由已有符号链接写出目标目录，
+//!   并限制条目数和解压后的总大小，防止 zip 炸弹）
+//! - 通过事件报告进度
+
+use crate::error::{AxonError, ErrorKind};
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use serde::Serialize;
+use serde_json::json;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tracing::{debug, error, info, warn};
+use zip::write::SimpleFileOptions;
+
+/// 归档进度事件
+pub const EVENT_ARCHIVE_PROGRESS: &str = "archive:progress";
+
+/// 进度事件最小发送间隔
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 解压限制
+#[derive(Debug, Clone, Copy)]
+struct ExtractLimits {
+    /// 条目数上限（含目录）
+    max_entries: usize,
+    /// 解压后总字节数上限
+    max_bytes: u64,
+}
+
+const EXTRACT_LIMITS: ExtractLimits = ExtractLimits {
+    max_entries: 100_000,
+    max_bytes: 8 * 1024 * 1024 * 1024,
+};
+
+/// 归档进度
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveProgress {
+    /// 归档文件路径（用于区分并发任务）
+    pub archive_path: String,
+    /// 操作类型（create / extract）
+    pub operation: String,
+    /// 已处理文件数
+    pub files_done: u64,
+    /// 文件总数
+    pub files_total: u64,
+    /// 已处理字节数（未压缩大小）
+    pub bytes_done: u64,
+    /// 总字节数（未压缩大小）
+    pub bytes_total: u64,
+    /// 当前处理的条目
+    pub current_entry: Option<String>,
+}
+
+/// 归档操作结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchiveSummary {
+    /// 归档文件路径
+    pub archive_path: String,
+    /// 打包来源或解压目标目录
+    pub target: String,
+    /// 处理的文件数
+    pub file_count: u64,
+    /// 未压缩总字节数
+    pub total_bytes: u64,
+    /// 归档文件大小（字节）
+    pub archive_size: u64,
+}
+
+/// 将多个文件/目录打包为 zip
+///
+/// 条目路径相对于各源路径的父目录，即保留顶层文件/目录名
+#[tauri::command]
+pub async fn create_archive(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+    dest_zip: String,
+) -> Result<ArchiveSummary, AxonError> {
+    let audit_args = json!({ "paths": &paths, "destZip": &dest_zip });
+    state.audit.track("create_archive", audit_args, async {
+        debug!("创建归档: {} 项 -> {}", paths.len(), dest_zip);
+
+        if paths.is_empty() {
+            return Err(AxonError::invalid_input("没有需要打包的路径"));
+        }
+        let sandbox = PathSandbox::from_settings(&state.settings);
+        let sources = paths
+            .iter()
+            .map(|path| sandbox.check(path))
+            .collect::<Result<Vec<_>, AxonError>>()?;
+        let dest_zip = sandbox.check(&dest_zip)?;
+
+        tokio::task::spawn_blocking(move || {
+            create_archive_sync(&sources, &dest_zip, |progress| {
+                emit_progress(&app, progress)
+            })
+        })
+        .await
+        .map_err(|e| AxonError::internal(format!("创建归档任务失败: {}", e)))?
+    })
+    .await
+}
+
+/// 将 zip 解压到目标目录
+///
+/// 含有绝对路径或 `..` 的条目、经过目标目录内已有符号链接的条目会被拒绝，
+/// 防止写出目标目录；条目数或解压后大小超过上限时中止
+#[tauri::command]
+pub async fn extract_archive(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    zip_path: String,
+    dest_dir: String,
+) -> Result<ArchiveSummary, AxonError> {
+    let audit_args = json!({ "zipPath": &zip_path, "destDir": &dest_dir });
+    state.audit.track("extract_archive", audit_args, async {
+        debug!("解压归档: {} -> {}", zip_path, dest_dir);
+        let sandbox = PathSandbox::from_settings(&state.settings);
+        let zip_path = sandbox.check(&zip_path)?;
+        let dest_dir = sandbox.check(&dest_dir)?;
+
+        tokio::task::spawn_blocking(move || {
+            extract_archive_sync(&zip_path, &dest_dir, EXTRACT_LIMITS, |progress| {
+                emit_progress(&app, progress)
+            })
+        })
+        .await
+        .map_err(|e| AxonError::internal(format!("解压归档任务失败: {}", e)))?
+    })
+    .await
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn emit_progress(app: &AppHandle, progress: &ArchiveProgress) {
+    if let Err(e) = app.emit(EVENT_ARCHIVE_PROGRESS, progress) {
+        warn!("发送归档进度失败: {}", e);
+    }
+}
+
+/// 进度跟踪器（节流发送进度事件）
+struct ProgressTracker<F: FnMut(&ArchiveProgress)> {
+    sink: F,
+    progress: ArchiveProgress,
+    last_emit: Instant,
+}
+
+impl<F: FnMut(&ArchiveProgress)> ProgressTracker<F> {
+    fn new(archive_path: &Path, operation: &str, totals: (u64, u64), sink: F) -> Self {
+        let mut tracker = Self {
+            sink,
+            progress: ArchiveProgress {
+                archive_path: archive_path.to_string_lossy().to_string(),
+                operation: operation.to_string(),
+                files_done: 0,
+                files_total: totals.0,
+                bytes_done: 0,
+                bytes_total: totals.1,
+                current_entry: None,
+            },
+            last_emit: Instant::now(),
+        };
+        tracker.emit();
+        tracker
+    }
+
+    fn advance(&mut self, entry: &str, bytes: u64) {
+        self.progress.files_done += 1;
+        self.progress.bytes_done += bytes;
+        self.progress.current_entry = Some(entry.to_string());
+
+        if self.last_emit.elapsed() >= PROGRESS_EMIT_INTERVAL {
+            self.emit();
+            self.last_emit = Instant::now();
+        }
+    }
+
+    fn emit(&mut self) {
+        (self.sink)(&self.progress);
+    }
+}
+
+/// 待打包的条目
+struct PendingEntry {
+    /// 磁盘路径
+    source: PathBuf,
+    /// zip 内路径（使用 / 分隔）
+    name: String,
+    is_dir: bool,
+    size: u64,
+}
+
+/// 收集源路径下的所有条目（不跟随符号链接）
+fn collect_entries(
+    path: &Path,
+    name: String,
+    entries: &mut Vec<PendingEntry>,
+) -> Result<(), AxonError> {
+    let metadata = std::fs::symlink_metadata(path)
+        .map_err(|e| AxonError::io(format!("读取元数据失败: {}", path.display()), &e))?;
+
+    if metadata.file_type().is_symlink() {
+        debug!("跳过符号链接: {:?}", path);
+        return Ok(());
+    }
+
+    if metadata.is_dir() {
+        entries.push(PendingEntry {
+            source: path.to_path_buf(),
+            name: format!("{}/", name),
+            is_dir: true,
+            size: 0,
+        });
+
+        let mut children: Vec<_> = std::fs::read_dir(path)
+            .map_err(|e| AxonError::localized_io("fs.read_dir_failed", &e))?
+            .flatten()
+            .collect();
+        children.sort_by_key(|e| e.file_name());
+
+        for child in children {
+            let child_name = format!("{}/{}", name, child.file_name().to_string_lossy());
+            collect_entries(&child.path(), child_name, entries)?;
+        }
+    } else {
+        entries.push(PendingEntry {
+            source: path.to_path_buf(),
+            name,
+            is_dir: false,
+            size: metadata.len(),
+        });
+    }
+
+    Ok(())
+}
+
+fn create_archive_sync(
+    sources: &[PathBuf],
+    dest_zip: &Path,
+    on_progress: impl FnMut(&ArchiveProgress),
+) -> Result<ArchiveSummary, AxonError> {
+    let mut entries = Vec::new();
+    for source in sources {
+        if !source.exists() {
+            return Err(AxonError::localized(
+                ErrorKind::NotFound,
+                "fs.source_not_found",
+                json!({ "path": source.to_string_lossy() }),
+            ));
+        }
+        let name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or_else(|| {
+                AxonError::invalid_input(format!("无法获取文件名: {}", source.display()))
+            })?;
+        collect_entries(source, name, &mut entries)?;
+    }
+
+    let files_total = entries.iter().filter(|e| !e.is_dir).count() as u64;
+    let bytes_total = entries.iter().map(|e| e.size).sum();
+
+    if let Some(parent) = dest_zip.parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            std::fs::create_dir_all(parent).map_err(|e| AxonError::io("创建目标目录失败", &e))?;
+        }
+    }
+
+    let file = std::fs::File::create(dest_zip).map_err(|e| {
+        error!("创建归档文件失败: {:?}, 错误: {}", dest_zip, e);
+        AxonError::io("创建归档文件失败", &e)
+    })?;
+    let mut writer = zip::ZipWriter::new(std::io::BufWriter::new(file));
+    let mut tracker =
+        ProgressTracker::new(dest_zip, "create", (files_total, bytes_total), on_progress);
+
+    for entry in &entries {
+        let mut options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Ok(metadata) = std::fs::metadata(&entry.source) {
+                options = options.unix_permissions(metadata.permissions().mode());
+            }
+        }
+
+        if entry.is_dir {
+            writer
+                .add_directory(entry.name.as_str(), options)
+                .map_err(|e| AxonError::internal(format!("写入目录条目失败: {}", e)))?;
+            continue;
+        }
+
+        options = options.large_file(entry.size >= u32::MAX as u64);
+        writer
+            .start_file(entry.name.as_str(), options)
+            .map_err(|e| AxonError::internal(format!("写入文件条目失败: {}", e)))?;
+
+        let mut source = std::fs::File::open(&entry.source)
+            .map_err(|e| AxonError::io(format!("打开文件失败: {}", entry.source.display()), &e))?;
+        std::io::copy(&mut source, &mut writer)
+            .map_err(|e| AxonError::io(format!("写入归档失败: {}", entry.source.display()), &e))?;
+
+        tracker.advance(&entry.name, entry.size);
+    }
+
+    writer
+        .finish()
+        .map_err(|e| AxonError::internal(format!("完成归档失败: {}", e)))?;
+    tracker.emit();
+
+    let archive_size = std::fs::metadata(dest_zip).map(|m| m.len()).unwrap_or(0);
+    info!("归档已创建: {:?}, {} 个文件", dest_zip, files_total);
+
+    Ok(ArchiveSummary {
+        archive_path: dest_zip.to_string_lossy().to_string(),
+        target: sources
+            .iter()
+            .map(|source| source.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(", "),
+        file_count: files_total,
+        total_bytes: bytes_total,
+        archive_size,
+    })
+}
+
+fn extract_archive_sync(
+    zip_path: &Path,
+    dest_dir: &Path,
+    limits: ExtractLimits,
+    on_progress: impl FnMut(&ArchiveProgress),
+) -> Result<ArchiveSummary, AxonError> {
+    let file = std::fs::File::open(zip_path).map_err(|e| {
+        error!("打开归档失败: {:?}, 错误: {}", zip_path, e);
+        AxonError::io("打开归档失败", &e)
+    })?;
+    let archive_size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| AxonError::invalid_data(format!("解析归档失败: {}", e)))?;
+    if archive.len() > limits.max_entries {
+        return Err(AxonError::invalid_input(format!(
+            "归档条目数超过上限（{} 个）",
+            limits.max_entries
+        )));
+    }
+
+    // 预先校验所有条目路径和声明的大小，任何不安全条目都会中止解压
+    let mut files_total = 0;
+    let mut bytes_total: u64 = 0;
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(read_entry_error)?;
+        if entry.enclosed_name().is_none() {
+            error!("归档包含不安全的路径: {}", entry.name());
+            return Err(unsafe_entry(entry.name()));
+        }
+        if !entry.is_dir() {
+            files_total += 1;
+            bytes_total = bytes_total.saturating_add(entry.size());
+        }
+    }
+    if bytes_total > limits.max_bytes {
+        return Err(AxonError::invalid_input(format!(
+            "归档解压后大小超过上限（{} MB）",
+            limits.max_bytes / 1024 / 1024
+        )));
+    }
+
+    std::fs::create_dir_all(dest_dir).map_err(|e| AxonError::io("创建目标目录失败", &e))?;
+    let dest_root = dest_dir
+        .canonicalize()
+        .map_err(|e| AxonError::io("解析目标目录失败", &e))?;
+    let mut tracker =
+        ProgressTracker::new(zip_path, "extract", (files_total, bytes_total), on_progress);
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(read_entry_error)?;
+        let relative = entry
+            .enclosed_name()
+            .ok_or_else(|| unsafe_entry(entry.name()))?;
+        let out_path = output_path(&dest_root, &relative)?;
+
+        if entry.is_dir() {
+            create_dirs(&dest_root, &out_path)?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            create_dirs(&dest_root, parent)?;
+        }
+
+        // 最多读取声明大小 + 1 字节，实际内容超过声明大小时中止（预检的总量基于声明大小）
+        let declared = entry.size();
+        let mut out_file = std::fs::File::create(&out_path)
+            .map_err(|e| AxonError::io(format!("创建文件失败: {}", out_path.display()), &e))?;
+        let written = std::io::copy(
+            &mut (&mut entry).take(declared.saturating_add(1)),
+            &mut out_file,
+        )
+        .map_err(|e| AxonError::io(format!("解压失败: {}", entry.name()), &e))?;
+        if written > declared {
+            drop(out_file);
+            let _ = std::fs::remove_file(&out_path);
+            return Err(AxonError::invalid_data(format!(
+                "归档条目实际大小超过声明大小: {}",
+                entry.name()
+            )));
+        }
+
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            let _ =
+                std::fs::set_permissions(&out_path, std::fs::Permissions::from_mode(mode & 0o777));
+        }
+
+        tracker.advance(entry.name(), written);
+    }
+
+    tracker.emit();
+    info!(
+        "归档已解压: {:?} -> {:?}, {} 个文件",
+        zip_path, dest_dir, files_total
+    );
+
+    Ok(ArchiveSummary {
+        archive_path: zip_path.to_string_lossy().to_string(),
+        target: dest_dir.to_string_lossy().to_string(),
+        file_count: files_total,
+        total_bytes: bytes_total,
+        archive_size,
+    })
+}
+
+fn read_entry_error(e: zip::result::ZipError) -> AxonError {
+    AxonError::invalid_data(format!("读取归档条目失败: {}", e))
+}
+
+fn unsafe_entry(name: &str) -> AxonError {
+    AxonError::invalid_input(format!("归档包含不安全的路径: {}", name))
+}
+
+/// 条目在目标目录下的路径
+///
+/// 逐级检查已存在的部分，拒绝经过符号链接（包括覆盖已有的符号链接），
+/// 否则写入会跟随链接落到目标目录之外
+fn output_path(dest_root: &Path, relative: &Path) -> Result<PathBuf, AxonError> {
+    let mut current = dest_root.to_path_buf();
+    for component in relative.components() {
+        current.push(component);
+        match std::fs::symlink_metadata(&current) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                error!("归档条目经过符号链接: {:?}", current);
+                return Err(unsafe_entry(&relative.to_string_lossy()));
+            }
+            Ok(_) => {}
+            // 之后的部分都还不存在，会由解压创建
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => break,
+            Err(e) => return Err(AxonError::io("读取解压路径失败", &e)),
+        }
+    }
+    Ok(dest_root.join(relative))
+}
+
+/// 创建目录后确认其真实路径仍在目标目录内（防止检查后被替换为符号链接）
+fn create_dirs(dest_root: &Path, dir: &Path) -> Result<(), AxonError> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| AxonError::localized_io("fs.create_dir_failed", &e))?;
+    let real = dir
+        .canonicalize()
+        .map_err(|e| AxonError::io("解析解压路径失败", &e))?;
+    if !real.starts_with(dest_root) {
+        error!("解压目录不在目标目录内: {:?}", real);
+        return Err(unsafe_entry(&dir.to_string_lossy()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const LIMITS: ExtractLimits = ExtractLimits {
+        max_entries: 10,
+        max_bytes: 1024,
+    };
+
+    fn write_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let file = std::fs::File::create(path).unwrap();
+        let mut writer = zip::ZipWriter::new(file);
+        for (name, content) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    fn extract(zip: &Path, dest: &Path) -> Result<ArchiveSummary, AxonError> {
+        extract_archive_sync(zip, dest, LIMITS, |_| {})
+    }
+
+    #[test]
+    fn extracts_nested_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip = dir.path().join("a.zip");
+        write_zip(
+            &zip,
+            &[("src/main.rs", b"fn main() {}"), ("README.md", b"# a")],
+        );
+
+        let dest = dir.path().join("out");
+        let summary = extract(&zip, &dest).unwrap();
+        assert_eq!(summary.file_count, 2);
+        assert_eq!(
+            std::fs::read_to_string(dest.join("src/main.rs")).unwrap(),
+            "fn main() {}"
+        );
+    }
+
+    #[test]
+    fn rejects_traversal_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip = dir.path().join("evil.zip");
+        write_zip(&zip, &[("ok.txt", b"ok"), ("../escape.txt", b"x")]);
+
+        assert!(extract(&zip, &dir.path().join("out")).is_err());
+        assert!(!dir.path().join("escape.txt").exists());
+        // 预检失败时不写入任何条目
+        assert!(!dir.path().join("out/ok.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuses_to_write_through_existing_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().join("outside");
+        let dest = dir.path().join("out");
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::create_dir_all(&dest).unwrap();
+        std::os::unix::fs::symlink(&outside, dest.join("link")).unwrap();
+        std::os::unix::fs::symlink(outside.join("target"), dest.join("file")).unwrap();
+
+        let zip = dir.path().join("a.zip");
+        write_zip(&zip, &[("link/pwned.txt", b"x")]);
+        assert!(extract(&zip, &dest).is_err());
+        write_zip(&zip, &[("file", b"x")]);
+        assert!(extract(&zip, &dest).is_err());
+        assert_eq!(std::fs::read_dir(&outside).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn enforces_entry_and_size_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip = dir.path().join("big.zip");
+        let dest = dir.path().join("out");
+
+        write_zip(&zip, &[("big.bin", &[0u8; 2048])]);
+        assert!(extract(&zip, &dest).is_err());
+        assert!(!dest.join("big.bin").exists());
+
+        let names: Vec<String> = (0..11).map(|i| format!("{}.txt", i)).collect();
+        let entries: Vec<(&str, &[u8])> = names.iter().map(|n| (n.as_str(), &b""[..])).collect();
+        write_zip(&zip, &entries);
+        assert!(extract(&zip, &dest).is_err());
+    }
+}
@@ -0,0 +1,58 @@
+//! 录音命令
+//!
+//! 为语音输入提供麦克风录音，录音文件可再交给服务商模型转写。
+
+use crate::audio::{AudioInputDevice, Recording};
+use crate::error::AxonError;
+use crate::state::AppState;
+use serde_json::json;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
+use tracing::debug;
+
+/// 列出音频输入设备
+#[tauri::command]
+pub async fn list_audio_input_devices(
+    state: State<'_, AppState>,
+) -> Result<Vec<AudioInputDevice>, AxonError> {
+    let audio = Arc::clone(&state.audio);
+    tokio::task::spawn_blocking(move || audio.input_devices())
+        .await
+        .map_err(|e| AxonError::internal(format!("获取音频输入设备任务失败: {}", e)))?
+}
+
+/// 开始录音，返回录音文件路径
+///
+/// `device` 为设备名称，未指定时使用系统默认输入设备
+#[tauri::command]
+pub async fn start_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device: Option<String>,
+) -> Result<String, AxonError> {
+    let audit_args = json!({ "device": &device });
+    state
+        .audit
+        .track("start_recording", audit_args, async {
+            debug!("开始录音, 设备: {:?}", device);
+            let audio = Arc::clone(&state.audio);
+            tokio::task::spawn_blocking(move || audio.start(app, device))
+                .await
+                .map_err(|e| AxonError::internal(format!("开始录音任务失败: {}", e)))?
+        })
+        .await
+}
+
+/// 停止录音，返回 WAV 文件信息
+#[tauri::command]
+pub fn stop_recording(state: State<'_, AppState>) -> Result<Recording, AxonError> {
+    state
+        .audit
+        .track_sync("stop_recording", json!({}), || state.audio.stop())
+}
+
+/// 是否正在录音
+#[tauri::command]
+pub fn is_recording(state: State<'_, AppState>) -> bool {
+    state.audio.is_recording()
+}
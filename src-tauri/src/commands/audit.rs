@@ -0,0 +1,20 @@
+//! 审计日志命令
+
+use crate::audit::{AuditEntry, AuditLogFilter};
+use crate::error::AxonError;
+use crate::state::AppState;
+use tauri::State;
+use tracing::debug;
+
+/// 查询审计日志
+///
+/// 返回符合条件的状态变更命令调用记录（按时间倒序），`filter` 为空时返回最近的记录
+#[tauri::command]
+pub async fn read_audit_log(
+    state: State<'_, AppState>,
+    filter: Option<AuditLogFilter>,
+) -> Result<Vec<AuditEntry>, AxonError> {
+    let filter = filter.unwrap_or_default();
+    debug!("查询审计日志: {:?}", filter);
+    state.audit.read(&filter)
+}
@@ -0,0 +1,44 @@
+//! 项目书签命令
+
+use crate::bookmarks::{Bookmark, BookmarkColor};
+use crate::error::AxonError;
+use crate::state::AppState;
+use serde_json::json;
+use tauri::State;
+
+/// 列出项目书签（按添加顺序）
+#[tauri::command]
+pub fn list_bookmarks(
+    state: State<'_, AppState>,
+    project_dir: String,
+) -> Result<Vec<Bookmark>, AxonError> {
+    state.bookmarks.list(&project_dir)
+}
+
+/// 添加书签，路径已收藏时更新备注和颜色
+#[tauri::command]
+pub fn add_bookmark(
+    state: State<'_, AppState>,
+    project_dir: String,
+    path: String,
+    note: Option<String>,
+    color: Option<BookmarkColor>,
+) -> Result<Bookmark, AxonError> {
+    let audit_args = json!({ "projectDir": &project_dir, "path": &path });
+    state.audit.track_sync("add_bookmark", audit_args, || {
+        state.bookmarks.add(&project_dir, &path, note, color)
+    })
+}
+
+/// 删除书签，返回书签是否存在
+#[tauri::command]
+pub fn remove_bookmark(
+    state: State<'_, AppState>,
+    project_dir: String,
+    path: String,
+) -> Result<bool, AxonError> {
+    let audit_args = json!({ "projectDir": &project_dir, "path": &path });
+    state.audit.track_sync("remove_bookmark", audit_args, || {
+        state.bookmarks.remove(&project_dir, &path)
+    })
+}
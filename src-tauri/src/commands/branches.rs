@@ -0,0 +1,46 @@
+//! 会话分支命令
+
+use crate::branches::Branch;
+use crate::error::AxonError;
+use crate::state::AppState;
+use serde_json::json;
+use tauri::State;
+
+/// 列出会话的分支（按创建顺序）
+#[tauri::command]
+pub fn list_branches(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<Branch>, AxonError> {
+    state.branches.list(&session_id)
+}
+
+/// 在会话的指定消息处创建分支，可关联 OpenCode 分叉出的会话
+#[tauri::command]
+pub fn create_branch(
+    state: State<'_, AppState>,
+    session_id: String,
+    message_id: String,
+    name: String,
+    branch_session_id: Option<String>,
+) -> Result<Branch, AxonError> {
+    let audit_args = json!({ "sessionId": &session_id, "messageId": &message_id, "name": &name });
+    state.audit.track_sync("create_branch", audit_args, || {
+        state
+            .branches
+            .create(&session_id, &message_id, &name, branch_session_id)
+    })
+}
+
+/// 删除分支（不删除分叉出的会话），返回分支是否存在
+#[tauri::command]
+pub fn delete_branch(
+    state: State<'_, AppState>,
+    session_id: String,
+    branch_id: String,
+) -> Result<bool, AxonError> {
+    let audit_args = json!({ "sessionId": &session_id, "branchId": &branch_id });
+    state.audit.track_sync("delete_branch", audit_args, || {
+        state.branches.delete(&session_id, &branch_id)
+    })
+}
@@ -0,0 +1,94 @@
+//! Axon Bridge 插件更新命令
+
+use crate::bridge_update::BridgeUpdateInfo;
+use crate::error::AxonError;
+use crate::opencode::ServiceStatus;
+use crate::state::AppState;
+use crate::utils::plugin_installer::InstalledPlugin;
+use serde::Serialize;
+use serde_json::json;
+use tauri::State;
+use tracing::warn;
+
+/// 插件更新结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeUpdateResult {
+    pub installed: InstalledPlugin,
+    /// 是否已重启 OpenCode 以加载新插件（服务未运行时不重启）
+    pub restarted: bool,
+}
+
+/// 检查 Axon Bridge 插件更新
+#[tauri::command]
+pub async fn check_bridge_update(
+    state: State<'_, AppState>,
+) -> Result<BridgeUpdateInfo, AxonError> {
+    state.bridge_update.check().await
+}
+
+/// 下载并安装最新的 Axon Bridge 插件，OpenCode 正在运行时自动重启
+#[tauri::command]
+pub async fn update_bridge_plugin(
+    state: State<'_, AppState>,
+) -> Result<BridgeUpdateResult, AxonError> {
+    let audit_args = json!({ "repo": state.settings.get_bridge_update_repo() });
+    state
+        .audit
+        .track("update_bridge_plugin", audit_args, async {
+            let installed = state.bridge_update.update().await?;
+            let restarted = if matches!(state.opencode.get_status(), ServiceStatus::Running { .. })
+            {
+                let plugin_api_port = state.plugin_api.read().state().get_port();
+                state.opencode.set_plugin_api_port(plugin_api_port);
+                match state.opencode.restart().await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        warn!("更新插件后重启 OpenCode 失败: {}", e);
+                        false
+                    }
+                }
+            } else {
+                false
+            };
+            Ok(BridgeUpdateResult {
+                installed,
+                restarted,
+            })
+        })
+        .await
+}
+
+/// 获取检查插件更新的 GitHub 仓库
+#[tauri::command]
+pub fn get_bridge_update_repo(state: State<'_, AppState>) -> String {
+    state.settings.get_bridge_update_repo()
+}
+
+/// 设置检查插件更新的 GitHub 仓库（`owner/name`）
+#[tauri::command]
+pub fn set_bridge_update_repo(state: State<'_, AppState>, repo: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "repo": &repo });
+    state
+        .audit
+        .track_sync("set_bridge_update_repo", audit_args, || {
+            let repo = repo.trim();
+            let valid = repo.split('/').count() == 2
+                && repo.split('/').all(|part| {
+                    !part.is_empty()
+                        && part
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+                });
+            if !valid {
+                return Err(AxonError::invalid_input(format!(
+                    "无效的 GitHub 仓库: {}（格式为 owner/name）",
+                    repo
+                )));
+            }
+            state
+                .settings
+                .set_bridge_update_repo(repo.to_string())
+                .map_err(AxonError::from)
+        })
+}
@@ -0,0 +1,75 @@
+//! 剪贴板命令
+//!
+//! 通过系统剪贴板读写文本和读取图片，webview 的剪贴板 API 受限时
+//! （如未获得权限、非安全上下文）聊天输入框仍可粘贴截图。
+
+use crate::error::AxonError;
+use base64::Engine;
+use image::codecs::png::PngEncoder;
+use image::{ExtendedColorType, ImageEncoder};
+use serde::Serialize;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tracing::debug;
+
+/// 剪贴板图片
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardImage {
+    /// base64 编码的 PNG 数据
+    pub data: String,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 读取剪贴板文本
+#[tauri::command]
+pub fn clipboard_read_text(app: AppHandle) -> Result<String, AxonError> {
+    app.clipboard()
+        .read_text()
+        .map_err(|e| AxonError::unavailable(format!("读取剪贴板文本失败: {}", e)))
+}
+
+/// 写入剪贴板文本
+#[tauri::command]
+pub fn clipboard_write_text(app: AppHandle, text: String) -> Result<(), AxonError> {
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| AxonError::unavailable(format!("写入剪贴板失败: {}", e)))
+}
+
+/// 读取剪贴板图片，剪贴板中没有图片时返回 None
+#[tauri::command]
+pub async fn clipboard_read_image(app: AppHandle) -> Result<Option<ClipboardImage>, AxonError> {
+    let (rgba, width, height) = match app.clipboard().read_image() {
+        Ok(image) => (image.rgba().to_vec(), image.width(), image.height()),
+        Err(e) => {
+            debug!("剪贴板中没有可读取的图片: {}", e);
+            return Ok(None);
+        }
+    };
+    if width == 0 || height == 0 {
+        return Ok(None);
+    }
+
+    let data = tokio::task::spawn_blocking(move || encode_png(&rgba, width, height))
+        .await
+        .map_err(|e| AxonError::internal(format!("编码剪贴板图片任务失败: {}", e)))??;
+    debug!("读取剪贴板图片: {}x{}, {} 字节", width, height, data.len());
+
+    Ok(Some(ClipboardImage {
+        data: base64::engine::general_purpose::STANDARD.encode(&data),
+        mime_type: "image/png".to_string(),
+        width,
+        height,
+    }))
+}
+
+fn encode_png(rgba: &[u8], width: u32, height: u32) -> Result<Vec<u8>, AxonError> {
+    let mut buffer = Vec::new();
+    PngEncoder::new(&mut buffer)
+        .write_image(rgba, width, height, ExtendedColorType::Rgba8)
+        .map_err(|e| AxonError::invalid_data(format!("编码剪贴板图片失败: {}", e)))?;
+    Ok(buffer)
+}
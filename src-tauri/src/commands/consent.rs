@@ -0,0 +1,52 @@
+//! 操作确认命令
+//!
+//! 前端通过 `consent:requested` / `consent:resolved` 事件感知待确认请求，
+//! 并通过这些命令回复请求、管理"始终允许"规则。
+
+use crate::consent::{ConsentAction, ConsentDecision, ConsentRequest, ConsentRule};
+use crate::error::AxonError;
+use crate::state::AppState;
+use serde_json::json;
+use tauri::State;
+
+/// 获取所有待确认请求（前端启动或重新挂载时补齐遗漏的事件）
+#[tauri::command]
+pub fn list_consent_requests(state: State<'_, AppState>) -> Vec<ConsentRequest> {
+    state.consent.pending()
+}
+
+/// 回复待确认请求
+#[tauri::command]
+pub fn respond_consent_request(
+    state: State<'_, AppState>,
+    id: String,
+    decision: ConsentDecision,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "id": &id, "decision": decision });
+    state
+        .audit
+        .track_sync("respond_consent_request", audit_args, || {
+            state.consent.respond(&id, decision)
+        })
+}
+
+/// 获取所有"始终允许"规则
+#[tauri::command]
+pub fn list_consent_rules(state: State<'_, AppState>) -> Vec<ConsentRule> {
+    state.consent.rules()
+}
+
+/// 删除"始终允许"规则，返回规则是否存在
+#[tauri::command]
+pub fn remove_consent_rule(
+    state: State<'_, AppState>,
+    project: String,
+    action: ConsentAction,
+) -> Result<bool, AxonError> {
+    let audit_args = json!({ "project": &project, "action": action });
+    state
+        .audit
+        .track_sync("remove_consent_rule", audit_args, || {
+            state.consent.remove_rule(&project, action)
+        })
+}
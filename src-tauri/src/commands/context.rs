@@ -0,0 +1,353 @@
+//! 项目上下文构建命令
+//!
+//! 在 token 预算内生成项目摘要（概览、目录树节选、README 摘录、关键文件开头），
+//! 用于为 Agent 预置项目上下文，避免前端进行大量文件 IO。
+
+use super::project::collect_project_info;
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::ignore::IgnoreMatcher;
+use crate::utils::tokens::estimate_tokens;
+use serde::Serialize;
+use std::path::Path;
+use tracing::debug;
+
+/// 默认 token 预算
+const DEFAULT_BUDGET_TOKENS: usize = 4000;
+
+/// 最小 token 预算
+const MIN_BUDGET_TOKENS: usize = 200;
+
+/// 目录树最大深度
+const TREE_MAX_DEPTH: usize = 3;
+
+/// 目录树中每个目录最多列出的条目数
+const TREE_MAX_ENTRIES_PER_DIR: usize = 25;
+
+/// 关键文件读取的最大行数
+const KEY_FILE_MAX_LINES: usize = 40;
+
+/// 候选 README 文件
+const README_CANDIDATES: &[&str] = &[
+    "README.md",
+    "readme.md",
+    "README.MD",
+    "README",
+    "README.txt",
+];
+
+/// 关键文件（清单与常见入口）
+const KEY_FILE_CANDIDATES: &[&str] = &[
+    "package.json",
+    "Cargo.toml",
+    "pyproject.toml",
+    "go.mod",
+    "src/main.rs",
+    "src/lib.rs",
+    "src/main.ts",
+    "src/main.tsx",
+    "src/index.ts",
+    "src/index.tsx",
+    "src/App.tsx",
+    "index.js",
+    "main.go",
+    "main.py",
+    "app.py",
+    "src-tauri/src/lib.rs",
+    "src-tauri/Cargo.toml",
+];
+
+/// 上下文分段信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextSection {
+    pub title: String,
+    pub tokens: usize,
+    /// 是否因预算不足被截断
+    pub truncated: bool,
+}
+
+/// 项目上下文
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectContext {
+    /// 生成的摘要文本（Markdown）
+    pub content: String,
+    /// 估算的 token 数
+    pub estimated_tokens: usize,
+    pub budget_tokens: usize,
+    pub sections: Vec<ContextSection>,
+}
+
+/// 在 token 预算内生成项目摘要
+#[tauri::command]
+pub async fn build_project_context(
+    project_dir: String,
+    budget_tokens: Option<usize>,
+) -> Result<ProjectContext, AxonError> {
+    let budget = budget_tokens
+        .unwrap_or(DEFAULT_BUDGET_TOKENS)
+        .max(MIN_BUDGET_TOKENS);
+    debug!("构建项目上下文: {}, 预算: {} tokens", project_dir, budget);
+
+    if !Path::new(&project_dir).is_dir() {
+        return Err(AxonError::localized(
+            ErrorKind::NotFound,
+            "project.dir_not_found",
+            serde_json::json!({ "path": project_dir }),
+        ));
+    }
+
+    tokio::task::spawn_blocking(move || build_context(Path::new(&project_dir), budget))
+        .await
+        .map_err(|e| AxonError::internal(format!("构建项目上下文任务失败: {}", e)))
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+/// 按固定比例分配预算，前面分段未用完的预算顺延给后续分段
+fn build_context(root: &Path, budget: usize) -> ProjectContext {
+    let mut builder = ContextBuilder::new(budget);
+
+    builder.add_section("项目概览", budget / 10, overview_section(root), None);
+    builder.add_section("目录结构", budget * 3 / 10, tree_section(root), Some(""));
+    if let Some(readme) = readme_section(root) {
+        builder.add_section("README 摘录", budget / 4, readme, None);
+    }
+
+    // 关键文件平分剩余预算
+    let key_files = key_file_sections(root);
+    let count = key_files.len();
+    for (index, (title, language, body)) in key_files.into_iter().enumerate() {
+        let share = builder.unallocated() / (count - index).max(1);
+        builder.add_section(&title, share, body, Some(&language));
+    }
+
+    builder.finish()
+}
+
+struct ContextBuilder {
+    budget: usize,
+    /// 已分配给各分段的预算总和
+    allocated: usize,
+    /// 已实际使用的 token
+    used: usize,
+    content: String,
+    sections: Vec<ContextSection>,
+}
+
+impl ContextBuilder {
+    fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            allocated: 0,
+            used: 0,
+            content: String::new(),
+            sections: Vec::new(),
+        }
+    }
+
+    fn unallocated(&self) -> usize {
+        self.budget.saturating_sub(self.allocated)
+    }
+
+    /// 追加分段；可用预算为本分段份额加上之前分段的结余。
+    /// `fence` 不为 None 时将内容包裹在代码块中（截断后再包裹，保证代码块闭合）
+    fn add_section(&mut self, title: &str, share: usize, body: String, fence: Option<&str>) {
+        self.allocated = (self.allocated + share).min(self.budget);
+        let limit = self.allocated.saturating_sub(self.used);
+
+        let (open, close) = match fence {
+            Some(language) => (format!("```{}\n", language), "```\n".to_string()),
+            None => (String::new(), String::new()),
+        };
+        let header = format!("## {}\n\n{}", title, open);
+        let overhead = estimate_tokens(&header) + estimate_tokens(&close) + 1;
+        if body.trim().is_empty() || limit <= overhead {
+            return;
+        }
+
+        let (body, truncated) = truncate_lines(&body, limit - overhead);
+        if body.trim().is_empty() {
+            return;
+        }
+
+        let section = format!("{}{}\n{}\n", header, body.trim_end(), close);
+        let tokens = estimate_tokens(&section);
+        self.used += tokens;
+        self.content.push_str(&section);
+        self.sections.push(ContextSection {
+            title: title.to_string(),
+            tokens,
+            truncated,
+        });
+    }
+
+    fn finish(self) -> ProjectContext {
+        let content = self.content.trim_end().to_string();
+        ProjectContext {
+            estimated_tokens: estimate_tokens(&content),
+            content,
+            budget_tokens: self.budget,
+            sections: self.sections,
+        }
+    }
+}
+
+/// 按行截断到 token 上限，返回（内容，是否截断）
+fn truncate_lines(text: &str, max_tokens: usize) -> (String, bool) {
+    let mut output = String::new();
+    let mut tokens = 0;
+
+    for line in text.lines() {
+        let line_tokens = estimate_tokens(line) + 1;
+        if tokens + line_tokens > max_tokens {
+            if !output.is_empty() {
+                output.push_str("…\n");
+            }
+            return (output, true);
+        }
+        tokens += line_tokens;
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    (output, false)
+}
+
+fn overview_section(root: &Path) -> String {
+    let info = collect_project_info(root);
+    let mut lines = Vec::new();
+
+    if let Some(name) = &info.name {
+        lines.push(format!("- 名称: {}", name));
+    }
+    if !info.languages.is_empty() {
+        lines.push(format!("- 语言: {}", info.languages.join(", ")));
+    }
+    if !info.package_managers.is_empty() {
+        lines.push(format!("- 构建工具: {}", info.package_managers.join(", ")));
+    }
+    if !info.frameworks.is_empty() {
+        lines.push(format!("- 框架: {}", info.frameworks.join(", ")));
+    }
+
+    let commands = &info.suggested_commands;
+    for (label, command) in [
+        ("安装", &commands.install),
+        ("构建", &commands.build),
+        ("测试", &commands.test),
+        ("运行", &commands.run),
+        ("检查", &commands.lint),
+    ] {
+        if let Some(command) = command {
+            lines.push(format!("- {}: `{}`", label, command));
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn tree_section(root: &Path) -> String {
+    let mut lines = Vec::new();
+    let name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| ".".to_string());
+    lines.push(format!("{}/", name));
+    collect_tree(root, root, &IgnoreMatcher::for_project(root), 1, &mut lines);
+    lines.join("\n")
+}
+
+fn collect_tree(
+    root: &Path,
+    dir: &Path,
+    ignore: &IgnoreMatcher,
+    depth: usize,
+    lines: &mut Vec<String>,
+) {
+    if depth > TREE_MAX_DEPTH {
+        return;
+    }
+
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut entries: Vec<(String, bool)> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            let path = entry.path();
+            let relative = path
+                .strip_prefix(root)
+                .ok()?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let skip = name.starts_with('.') || ignore.is_ignored(&relative, is_dir);
+            (!skip).then_some((name, is_dir))
+        })
+        .collect();
+
+    // 目录优先，再按名称排序
+    entries.sort_by(|a, b| {
+        b.1.cmp(&a.1)
+            .then_with(|| a.0.to_lowercase().cmp(&b.0.to_lowercase()))
+    });
+
+    let indent = "  ".repeat(depth);
+    let total = entries.len();
+    for (name, is_dir) in entries.into_iter().take(TREE_MAX_ENTRIES_PER_DIR) {
+        if is_dir {
+            lines.push(format!("{}{}/", indent, name));
+            collect_tree(root, &dir.join(&name), ignore, depth + 1, lines);
+        } else {
+            lines.push(format!("{}{}", indent, name));
+        }
+    }
+    if total > TREE_MAX_ENTRIES_PER_DIR {
+        lines.push(format!(
+            "{}… 另有 {} 项",
+            indent,
+            total - TREE_MAX_ENTRIES_PER_DIR
+        ));
+    }
+}
+
+fn readme_section(root: &Path) -> Option<String> {
+    let content = README_CANDIDATES
+        .iter()
+        .find_map(|name| std::fs::read_to_string(root.join(name)).ok())?;
+
+    // 跳过开头的徽章和图片行
+    let text: Vec<&str> = content
+        .lines()
+        .skip_while(|line| {
+            let trimmed = line.trim();
+            trimmed.is_empty()
+                || trimmed.starts_with("[![")
+                || trimmed.starts_with("![")
+                || trimmed.starts_with('<')
+        })
+        .collect();
+
+    Some(text.join("\n"))
+}
+
+/// 读取关键文件开头，返回（相对路径，代码块语言，内容）
+fn key_file_sections(root: &Path) -> Vec<(String, String, String)> {
+    KEY_FILE_CANDIDATES
+        .iter()
+        .filter_map(|relative| {
+            let content = std::fs::read_to_string(root.join(relative)).ok()?;
+            let language = Path::new(relative)
+                .extension()
+                .map(|e| e.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let head: Vec<&str> = content.lines().take(KEY_FILE_MAX_LINES).collect();
+            Some((relative.to_string(), language, head.join("\n")))
+        })
+        .collect()
+}
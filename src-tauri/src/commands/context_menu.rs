@@ -0,0 +1,44 @@
+//! 原生右键菜单命令
+
+use crate::context_menu::{FileKind, FileMenuAction};
+use crate::error::{AxonError, ErrorKind};
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use tauri::{AppHandle, State, Window};
+use tauri_plugin_opener::OpenerExt;
+use tracing::debug;
+
+/// 弹出文件浏览器的原生右键菜单，返回用户选择的操作（取消时为 null）
+///
+/// 菜单只负责选择，具体操作（重命名、删除等）仍由前端调用对应命令完成
+#[tauri::command]
+pub async fn show_file_context_menu(
+    window: Window,
+    state: State<'_, AppState>,
+    path: String,
+    kind: FileKind,
+) -> Result<Option<FileMenuAction>, AxonError> {
+    debug!("弹出右键菜单: {} ({:?})", path, kind);
+    state.context_menu.show_file_menu(&window, kind).await
+}
+
+/// 在系统文件管理器中显示文件或目录
+#[tauri::command]
+pub fn reveal_in_file_manager(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<(), AxonError> {
+    let checked = PathSandbox::from_settings(&state.settings).check(&path)?;
+    if !checked.exists() {
+        return Err(AxonError::localized(
+            ErrorKind::NotFound,
+            "fs.path_not_found",
+            serde_json::json!({ "path": path }),
+        ));
+    }
+
+    app.opener()
+        .reveal_item_in_dir(&checked)
+        .map_err(|e| AxonError::external(format!("在文件管理器中显示失败: {}", e)))
+}
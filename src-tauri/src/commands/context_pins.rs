@@ -0,0 +1,59 @@
+//! 上下文固定文件命令
+//!
+//! 固定的文件由 Bridge 插件通过 Plugin API 读取并加入 Agent 上下文，
+//! `project_dir` 为空时使用当前项目目录。
+
+use crate::context_pins::ContextPin;
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use serde_json::json;
+use tauri::State;
+
+fn resolve_project(state: &AppState, project_dir: Option<String>) -> Result<String, AxonError> {
+    project_dir
+        .or_else(|| state.settings.get_project_directory())
+        .ok_or_else(|| AxonError::invalid_input("未指定项目目录"))
+}
+
+/// 列出项目固定到上下文的文件
+#[tauri::command]
+pub fn list_context_pins(
+    state: State<'_, AppState>,
+    project_dir: Option<String>,
+) -> Result<Vec<ContextPin>, AxonError> {
+    let project_dir = resolve_project(&state, project_dir)?;
+    state.context_pins.list(&project_dir)
+}
+
+/// 把文件固定到 Agent 上下文
+#[tauri::command]
+pub fn pin_for_context(
+    state: State<'_, AppState>,
+    path: String,
+    project_dir: Option<String>,
+) -> Result<ContextPin, AxonError> {
+    let project_dir = resolve_project(&state, project_dir)?;
+    // 固定文件的内容会发送给 Agent，需要在允许访问的目录内；保存解析后的路径
+    let checked = PathSandbox::from_settings(&state.settings).check(&path)?;
+    let audit_args = json!({ "projectDir": &project_dir, "path": &path });
+    state.audit.track_sync("pin_for_context", audit_args, || {
+        state
+            .context_pins
+            .pin(&project_dir, &checked.to_string_lossy())
+    })
+}
+
+/// 取消固定，返回文件是否曾被固定
+#[tauri::command]
+pub fn unpin(
+    state: State<'_, AppState>,
+    path: String,
+    project_dir: Option<String>,
+) -> Result<bool, AxonError> {
+    let project_dir = resolve_project(&state, project_dir)?;
+    let audit_args = json!({ "projectDir": &project_dir, "path": &path });
+    state.audit.track_sync("unpin", audit_args, || {
+        state.context_pins.unpin(&project_dir, &path)
+    })
+}
@@ -0,0 +1,195 @@
+//! 诊断信息导出命令
+//!
+//! 将版本、设置、服务状态、OpenCode 配置、Plugin API 指标和最近日志
+//! 打包为单个 zip，方便附在问题反馈中。所有内容写入前都会脱敏。
+
+use crate::error::{AxonError, ErrorKind};
+use crate::logging::{list_crash_reports, read_recent_lines};
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use crate::utils::paths::{get_app_data_dir, get_opencode_config_path};
+use crate::utils::redact::{redact_json, redact_text};
+use serde::Serialize;
+use serde_json::json;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::State;
+use tracing::{info, warn};
+use zip::write::SimpleFileOptions;
+
+/// 诊断包中包含的日志行数
+const BUNDLE_LOG_LINES: usize = 5000;
+
+/// 诊断包中包含的崩溃报告数量
+const BUNDLE_CRASH_REPORTS: usize = 3;
+
+/// 查询最新版本的最长等待时间
+const VERSION_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 诊断包信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsBundle {
+    /// zip 文件路径
+    pub path: String,
+    /// zip 文件大小（字节）
+    pub size: u64,
+    /// 包内文件列表
+    pub files: Vec<String>,
+}
+
+/// 生成诊断包
+///
+/// `output_path` 为空时写入 `<app_data>/diagnostics/axon-diagnostics-<时间>.zip`
+#[tauri::command]
+pub async fn create_diagnostics_bundle(
+    state: State<'_, AppState>,
+    output_path: Option<String>,
+) -> Result<DiagnosticsBundle, AxonError> {
+    let audit_args = json!({ "outputPath": &output_path });
+    state
+        .audit
+        .track("create_diagnostics_bundle", audit_args, async {
+            let dest = match output_path.filter(|p| !p.trim().is_empty()) {
+                Some(path) => PathSandbox::from_settings(&state.settings).check(&path)?,
+                None => default_bundle_path()?,
+            };
+
+            let entries = collect_entries(&state).await;
+            tokio::task::spawn_blocking(move || write_bundle(&dest, entries))
+                .await
+                .map_err(|e| AxonError::internal(format!("生成诊断包任务失败: {}", e)))?
+        })
+        .await
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn default_bundle_path() -> Result<PathBuf, AxonError> {
+    let dir = get_app_data_dir()
+        .ok_or_else(|| {
+            AxonError::localized(ErrorKind::Unavailable, "app.data_dir_unavailable", json!(null))
+        })?
+        .join("diagnostics");
+    let name = format!(
+        "axon-diagnostics-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    Ok(dir.join(name))
+}
+
+/// 收集诊断包内容（文件名, 内容），均已脱敏
+async fn collect_entries(state: &AppState) -> Vec<(String, String)> {
+    let version_info = match tokio::time::timeout(
+        VERSION_CHECK_TIMEOUT,
+        state.opencode.get_version_info(),
+    )
+    .await
+    {
+        Ok(Ok(info)) => json!(info),
+        Ok(Err(e)) => json!({ "error": e.to_string() }),
+        Err(_) => json!({ "error": "查询版本超时" }),
+    };
+
+    let mut entries = vec![
+        json_entry(
+            "manifest.json",
+            json!({
+                "createdAt": chrono::Local::now().to_rfc3339(),
+                "appVersion": env!("CARGO_PKG_VERSION"),
+                "os": std::env::consts::OS,
+                "arch": std::env::consts::ARCH,
+                "opencode": version_info,
+            }),
+        ),
+        json_entry("settings.json", json!(state.settings.get_settings())),
+        json_entry(
+            "service.json",
+            json!({
+                "status": state.opencode.get_status(),
+                "config": state.opencode.get_config(),
+            }),
+        ),
+        json_entry("plugin_api.json", json!(state.plugin_api.read().metrics())),
+    ];
+
+    if let Some(entry) = opencode_config_entry() {
+        entries.push(entry);
+    }
+
+    match read_recent_lines(BUNDLE_LOG_LINES) {
+        Ok(lines) => entries.push(("logs/axon.log".to_string(), redact_text(&lines.join("\n")))),
+        Err(e) => warn!("读取日志失败，诊断包将不包含日志: {}", e.message),
+    }
+
+    for path in list_crash_reports().into_iter().take(BUNDLE_CRASH_REPORTS) {
+        let Some(name) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            entries.push((format!("logs/{}", name), redact_text(&content)));
+        }
+    }
+
+    entries
+}
+
+fn json_entry(name: &str, mut value: serde_json::Value) -> (String, String) {
+    redact_json(&mut value);
+    let content = serde_json::to_string_pretty(&value).unwrap_or_default();
+    (name.to_string(), content)
+}
+
+/// OpenCode 配置：能解析为 JSON 时按键脱敏，否则按文本脱敏
+fn opencode_config_entry() -> Option<(String, String)> {
+    let content = std::fs::read_to_string(get_opencode_config_path()?).ok()?;
+    Some(match serde_json::from_str(&content) {
+        Ok(value) => json_entry("opencode.json", value),
+        Err(_) => ("opencode.json".to_string(), redact_text(&content)),
+    })
+}
+
+fn write_bundle(
+    dest: &Path,
+    entries: Vec<(String, String)>,
+) -> Result<DiagnosticsBundle, AxonError> {
+    if let Some(parent) = dest.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent).map_err(|e| AxonError::io("创建目标目录失败", &e))?;
+        }
+    }
+
+    let file = std::fs::File::create(dest).map_err(|e| AxonError::io("创建诊断包失败", &e))?;
+    let mut writer = zip::ZipWriter::new(std::io::BufWriter::new(file));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut files = Vec::with_capacity(entries.len());
+    for (name, content) in entries {
+        writer
+            .start_file(name.as_str(), options)
+            .map_err(|e| format!("写入诊断包条目失败: {}", e))?;
+        writer
+            .write_all(content.as_bytes())
+            .map_err(|e| AxonError::io("写入诊断包失败", &e))?;
+        files.push(name);
+    }
+    writer
+        .finish()
+        .map_err(|e| format!("完成诊断包失败: {}", e))?;
+
+    let size = std::fs::metadata(dest).map(|m| m.len()).unwrap_or(0);
+    info!("诊断包已生成: {:?}, {} 个文件", dest, files.len());
+
+    Ok(DiagnosticsBundle {
+        path: dest.to_string_lossy().to_string(),
+        size,
+        files,
+    })
+}
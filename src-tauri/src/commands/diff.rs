@@ -2,9 +2,40 @@
 //!
 //! 使用 Rust 的 similar 库进行高性能差异计算，
 //! 支持行级别和字符级别的差异对比。
+//! 对 tree-sitter 支持的语言还提供按语法块对齐的结构化对比，
+//! 区分仅格式变化、移动、重命名和实际修改。
 
+use super::images::{read_image_info, ImageInfo};
+use super::outline::{classify_node, OutlineLanguage};
+use crate::diff_cache::{content_hash, CachedDiff, DiffCache};
+use crate::error::AxonError;
+use crate::jobs::JobHandle;
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
 use serde::{Deserialize, Serialize};
 use similar::{ChangeTag, TextDiff};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::Arc;
+use tauri::State;
+use tracing::debug;
+use tree_sitter::{Node, Parser, Tree};
+
+/// 逐行收集差异时检查取消的间隔（行数）
+const CANCEL_CHECK_INTERVAL: usize = 4096;
+
+/// 二进制对比可读取的最大文件大小
+const MAX_BINARY_DIFF_BYTES: u64 = 100 * 1024 * 1024;
+
+/// 二进制分块的最小 / 最大长度，平均长度约为 `BINARY_CHUNK_MASK + 1`
+const BINARY_CHUNK_MIN: usize = 64;
+const BINARY_CHUNK_MAX: usize = 64 * 1024;
+const BINARY_CHUNK_MASK: u64 = 0x3ff;
+
+/// 结构化对比的最大文本长度，超过时回退到行级对比
+const MAX_STRUCTURAL_DIFF_BYTES: usize = 1024 * 1024;
 
 /// 差异行类型
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -67,32 +98,108 @@ pub struct DiffResult {
 
 /// 计算两个文本之间的差异
 ///
+/// 在后台线程计算，结果按内容哈希缓存；只有一侧内容变化时复用上次结果中未变化的部分。
+/// 传入 `job_id` 时可通过 `cancel_job(job_id)` 取消
+///
 /// # 参数
 /// - `old_text`: 旧文本内容
 /// - `new_text`: 新文本内容
 /// - `file_name`: 可选的文件名
 /// - `context_lines`: 上下文行数（默认3行）
+/// - `job_id`: 可选的任务 ID
 ///
 /// # 返回
 /// 差异结果，包含所有变更块和统计信息
 #[tauri::command]
-pub fn compute_diff(
+pub async fn compute_diff(
+    state: State<'_, AppState>,
+    old_text: String,
+    new_text: String,
+    file_name: Option<String>,
+    context_lines: Option<usize>,
+    job_id: Option<String>,
+) -> Result<DiffResult, AxonError> {
+    let context = context_lines.unwrap_or(3);
+    let job = job_id
+        .as_deref()
+        .map(|id| state.jobs.register(id))
+        .transpose()?;
+    let cache = Arc::clone(&state.diff_cache);
+    let result = tokio::task::spawn_blocking(move || {
+        cached_diff(&cache, old_text, new_text, file_name, context, job.as_ref())
+    })
+    .await;
+    if let Some(job_id) = &job_id {
+        state.jobs.finish(job_id);
+    }
+    result?
+}
+
+/// 计算两个文本之间的差异（同步计算，不使用缓存）
+pub fn compute_line_diff(
     old_text: &str,
     new_text: &str,
     file_name: Option<String>,
     context_lines: Option<usize>,
 ) -> DiffResult {
-    let context = context_lines.unwrap_or(3);
+    let lines = collect_lines(old_text, new_text, 1, 1, &|| false).unwrap_or_default();
+    build_result(&lines, file_name, context_lines.unwrap_or(3))
+}
+
+fn cached_diff(
+    cache: &DiffCache,
+    old_text: String,
+    new_text: String,
+    file_name: Option<String>,
+    context: usize,
+    job: Option<&JobHandle>,
+) -> Result<DiffResult, AxonError> {
+    let old_hash = content_hash(&old_text);
+    let new_hash = content_hash(&new_text);
+    if let Some(entry) = cache.get(&old_hash, &new_hash) {
+        return Ok(build_result(&entry.lines, file_name, context));
+    }
+
+    let is_cancelled = || job.is_some_and(JobHandle::is_cancelled);
+    let lines = match cache.find_base(&old_hash, &new_hash) {
+        Some(base) => incremental_lines(&base, &old_text, &new_text, &is_cancelled),
+        None => collect_lines(&old_text, &new_text, 1, 1, &is_cancelled),
+    }
+    .ok_or_else(|| AxonError::cancelled("差异计算已取消"))?;
+
+    let result = build_result(&lines, file_name, context);
+    cache.insert(CachedDiff {
+        old_hash,
+        new_hash,
+        old_text,
+        new_text,
+        lines,
+    });
+    Ok(result)
+}
+
+/// 逐行对比，行号从 `first_old_line` / `first_new_line` 开始；取消时返回 `None`
+fn collect_lines(
+    old_text: &str,
+    new_text: &str,
+    first_old_line: usize,
+    first_new_line: usize,
+    is_cancelled: &dyn Fn() -> bool,
+) -> Option<Vec<DiffLine>> {
+    if is_cancelled() {
+        return None;
+    }
     let diff = TextDiff::from_lines(old_text, new_text);
 
     let mut all_lines: Vec<DiffLine> = Vec::new();
-    let mut additions = 0;
-    let mut deletions = 0;
-    let mut old_line_num: usize = 1;
-    let mut new_line_num: usize = 1;
+    let mut old_line_num = first_old_line;
+    let mut new_line_num = first_new_line;
 
     // 收集所有变更
-    for change in diff.iter_all_changes() {
+    for (index, change) in diff.iter_all_changes().enumerate() {
+        if index % CANCEL_CHECK_INTERVAL == 0 && is_cancelled() {
+            return None;
+        }
         let (line_type, old_ln, new_ln) = match change.tag() {
             ChangeTag::Equal => {
                 let result = (DiffLineType::Unchanged, Some(old_line_num), Some(new_line_num));
@@ -101,13 +208,11 @@ pub fn compute_diff(
                 result
             }
             ChangeTag::Delete => {
-                deletions += 1;
                 let result = (DiffLineType::Removed, Some(old_line_num), None);
                 old_line_num += 1;
                 result
             }
             ChangeTag::Insert => {
-                additions += 1;
                 let result = (DiffLineType::Added, None, Some(new_line_num));
                 new_line_num += 1;
                 result
@@ -124,9 +229,70 @@ pub fn compute_diff(
             new_line_number: new_ln,
         });
     }
+    Some(all_lines)
+}
+
+/// 在上次结果的基础上增量对比
+///
+/// 取上次结果中最后一个落在两侧公共前缀内的未修改行作为同步点，
+/// 同步点之前的结果直接复用，只重新对比之后的部分
+fn incremental_lines(
+    base: &CachedDiff,
+    old_text: &str,
+    new_text: &str,
+    is_cancelled: &dyn Fn() -> bool,
+) -> Option<Vec<DiffLine>> {
+    let old_common = common_prefix_lines(&base.old_text, old_text);
+    let new_common = common_prefix_lines(&base.new_text, new_text);
+    let sync = base.lines.iter().rposition(|line| {
+        line.line_type == DiffLineType::Unchanged
+            && line.old_line_number.is_some_and(|n| n <= old_common)
+            && line.new_line_number.is_some_and(|n| n <= new_common)
+    });
+    let Some(sync) = sync else {
+        return collect_lines(old_text, new_text, 1, 1, is_cancelled);
+    };
+
+    let old_line = base.lines[sync].old_line_number.unwrap_or_default();
+    let new_line = base.lines[sync].new_line_number.unwrap_or_default();
+    let rest = collect_lines(
+        &old_text[line_offset(old_text, old_line)..],
+        &new_text[line_offset(new_text, new_line)..],
+        old_line + 1,
+        new_line + 1,
+        is_cancelled,
+    )?;
+    let mut lines = Vec::with_capacity(sync + 1 + rest.len());
+    lines.extend_from_slice(&base.lines[..=sync]);
+    lines.extend(rest);
+    Some(lines)
+}
+
+/// 两个文本开头相同的完整行数
+fn common_prefix_lines(a: &str, b: &str) -> usize {
+    a.split_inclusive('\n')
+        .zip(b.split_inclusive('\n'))
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+/// 前 `lines` 行之后的字节偏移
+fn line_offset(text: &str, lines: usize) -> usize {
+    text.split_inclusive('\n').take(lines).map(str::len).sum()
+}
+
+fn build_result(lines: &[DiffLine], file_name: Option<String>, context: usize) -> DiffResult {
+    let additions = lines
+        .iter()
+        .filter(|l| l.line_type == DiffLineType::Added)
+        .count();
+    let deletions = lines
+        .iter()
+        .filter(|l| l.line_type == DiffLineType::Removed)
+        .count();
 
     // 将行分组为 hunks（带上下文）
-    let hunks = group_into_hunks(all_lines, context);
+    let hunks = group_into_hunks(lines, context);
     let has_changes = additions > 0 || deletions > 0;
 
     DiffResult {
@@ -139,7 +305,7 @@ pub fn compute_diff(
 }
 
 /// 将差异行分组为 hunks
-fn group_into_hunks(lines: Vec<DiffLine>, context: usize) -> Vec<DiffHunk> {
+fn group_into_hunks(lines: &[DiffLine], context: usize) -> Vec<DiffHunk> {
     if lines.is_empty() {
         return Vec::new();
     }
@@ -168,10 +334,7 @@ fn group_into_hunks(lines: Vec<DiffLine>, context: usize) -> Vec<DiffHunk> {
         // 检查是否应该开始新的 hunk
         let should_start_new = match last_change_idx {
             None => true,
-            Some(last) => {
-                // 如果两个变更之间的间隔大于 2*context，则开始新 hunk
-                change_idx > last + 2 * context + 1
-            }
+            Some(last) => starts_new_hunk(last, change_idx, context),
         };
 
         if should_start_new {
@@ -230,6 +393,11 @@ fn group_into_hunks(lines: Vec<DiffLine>, context: usize) -> Vec<DiffHunk> {
     hunks
 }
 
+/// 两个变更之间的间隔大于 2*context 时分属不同 hunk
+fn starts_new_hunk(last_change_idx: usize, change_idx: usize, context: usize) -> bool {
+    change_idx > last_change_idx + 2 * context + 1
+}
+
 /// 从行列表创建 hunk
 fn create_hunk(lines: &[DiffLine]) -> DiffHunk {
     let old_start = lines
@@ -261,6 +429,146 @@ fn create_hunk(lines: &[DiffLine]) -> DiffHunk {
     }
 }
 
+/// 部分应用 hunk 的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialApplyResult {
+    /// 在旧文本上只应用选中 hunk 后的内容
+    pub content: String,
+    /// 在新文本上只撤销选中 hunk 后的内容（即应用其余 hunk）
+    pub inverse_content: String,
+    /// hunk 总数
+    pub hunk_count: usize,
+}
+
+/// 只应用选中的 hunk
+///
+/// hunk 序号对应 `compute_diff` 返回的结果：同一对文本的差异仍在缓存中时复用缓存的逐行差异
+/// （可能来自增量计算，对齐方式与完整对比不同），否则重新完整对比
+///
+/// # 参数
+/// - `old_text`: 旧文本内容
+/// - `new_text`: 新文本内容
+/// - `hunk_indices`: 要应用的 hunk 序号（从 0 开始）
+/// - `context_lines`: 上下文行数（默认3行），需与 `compute_diff` 使用的值一致，
+///   否则 hunk 划分不同、序号对不上
+///
+/// # 返回
+/// 部分应用后的内容及其反向结果
+#[tauri::command]
+pub fn apply_selected_hunks(
+    state: State<'_, AppState>,
+    old_text: &str,
+    new_text: &str,
+    hunk_indices: Vec<usize>,
+    context_lines: Option<usize>,
+) -> Result<PartialApplyResult, AxonError> {
+    cached_apply(
+        &state.diff_cache,
+        old_text,
+        new_text,
+        hunk_indices,
+        context_lines.unwrap_or(3),
+    )
+}
+
+/// 只应用选中的 hunk（同步计算，不使用缓存），序号对应 `compute_line_diff` 返回的结果
+pub fn apply_line_hunks(
+    old_text: &str,
+    new_text: &str,
+    hunk_indices: Vec<usize>,
+    context_lines: Option<usize>,
+) -> Result<PartialApplyResult, AxonError> {
+    let lines = collect_lines(old_text, new_text, 1, 1, &|| false).unwrap_or_default();
+    apply_hunks(
+        &lines,
+        old_text,
+        new_text,
+        hunk_indices,
+        context_lines.unwrap_or(3),
+    )
+}
+
+fn cached_apply(
+    cache: &DiffCache,
+    old_text: &str,
+    new_text: &str,
+    hunk_indices: Vec<usize>,
+    context: usize,
+) -> Result<PartialApplyResult, AxonError> {
+    match cache.get(&content_hash(old_text), &content_hash(new_text)) {
+        Some(entry) => apply_hunks(&entry.lines, old_text, new_text, hunk_indices, context),
+        None => apply_line_hunks(old_text, new_text, hunk_indices, Some(context)),
+    }
+}
+
+/// 按逐行差异部分应用 hunk，行内容按行号从原文本取出以保留换行符
+fn apply_hunks(
+    lines: &[DiffLine],
+    old_text: &str,
+    new_text: &str,
+    hunk_indices: Vec<usize>,
+    context: usize,
+) -> Result<PartialApplyResult, AxonError> {
+    let selected: HashSet<usize> = hunk_indices.into_iter().collect();
+    let old_lines: Vec<&str> = old_text.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new_text.split_inclusive('\n').collect();
+    let text_of = |lines: &[&'_ str], number: Option<usize>| -> String {
+        number
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| lines.get(i))
+            .map(|line| line.to_string())
+            .unwrap_or_default()
+    };
+
+    let mut content = String::with_capacity(old_text.len());
+    let mut inverse_content = String::with_capacity(new_text.len());
+    let mut hunk_count = 0;
+    let mut last_change_idx: Option<usize> = None;
+
+    for (idx, line) in lines.iter().enumerate() {
+        let value = match line.line_type {
+            DiffLineType::Unchanged => {
+                let value = text_of(&old_lines, line.old_line_number);
+                content.push_str(&value);
+                inverse_content.push_str(&value);
+                continue;
+            }
+            DiffLineType::Removed => text_of(&old_lines, line.old_line_number),
+            DiffLineType::Added => text_of(&new_lines, line.new_line_number),
+        };
+
+        // 与 group_into_hunks 使用相同的划分规则
+        let is_new_hunk = last_change_idx.is_none_or(|last| starts_new_hunk(last, idx, context));
+        if is_new_hunk {
+            hunk_count += 1;
+        }
+        last_change_idx = Some(idx);
+
+        let is_selected = selected.contains(&(hunk_count - 1));
+        // 选中的 hunk：content 取新内容，inverse_content 取旧内容；未选中的相反
+        let keep_in_content = (line.line_type == DiffLineType::Added) == is_selected;
+        if keep_in_content {
+            content.push_str(&value);
+        } else {
+            inverse_content.push_str(&value);
+        }
+    }
+
+    if let Some(&invalid) = selected.iter().find(|&&idx| idx >= hunk_count) {
+        return Err(AxonError::invalid_input(format!(
+            "hunk 序号超出范围: {}（共 {} 个）",
+            invalid, hunk_count
+        )));
+    }
+
+    Ok(PartialApplyResult {
+        content,
+        inverse_content,
+        hunk_count,
+    })
+}
+
 /// 生成 unified diff 格式的文本
 ///
 /// # 参数
@@ -339,6 +647,858 @@ pub fn compute_diff_stats(old_text: &str, new_text: &str) -> DiffStats {
     }
 }
 
+// ============================================================================
+// Unified Diff 解析
+// ============================================================================
+
+/// 补丁中单个文件的解析状态
+#[derive(Default)]
+struct PatchFile {
+    old_path: Option<String>,
+    new_path: Option<String>,
+    /// 已读到 `---` 文件头
+    has_old_header: bool,
+    binary: bool,
+    hunks: Vec<DiffHunk>,
+}
+
+impl PatchFile {
+    fn into_result(self) -> DiffResult {
+        let lines = self.hunks.iter().flat_map(|h| &h.lines);
+        let additions = lines
+            .clone()
+            .filter(|l| l.line_type == DiffLineType::Added)
+            .count();
+        let deletions = lines
+            .filter(|l| l.line_type == DiffLineType::Removed)
+            .count();
+        DiffResult {
+            // 删除的文件没有新路径
+            file_name: self.new_path.or(self.old_path),
+            hunks: self.hunks,
+            additions,
+            deletions,
+            has_changes: additions > 0 || deletions > 0 || self.binary,
+        }
+    }
+}
+
+/// 解析 unified diff 文本
+///
+/// 支持 git 输出的多文件补丁（含新建、删除、重命名和二进制文件），
+/// 也兼容 LLM 输出中常见的问题：Markdown 代码围栏、缺少文件头、
+/// hunk 头没有行号或行数与内容不符
+///
+/// # 参数
+/// - `patch_text`: unified diff 文本
+///
+/// # 返回
+/// 每个文件一个差异结果，二进制文件没有 hunk
+#[tauri::command]
+pub fn parse_unified_diff(patch_text: &str) -> Result<Vec<DiffResult>, AxonError> {
+    let mut files: Vec<PatchFile> = Vec::new();
+    let mut lines = patch_text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .peekable();
+
+    while let Some(line) = lines.next() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            let mut file = PatchFile::default();
+            if let Some((old, new)) = rest.rsplit_once(" b/") {
+                file.old_path = parse_patch_path(old);
+                file.new_path = Some(new.to_string());
+            }
+            files.push(file);
+        } else if let Some(rest) = line.strip_prefix("--- ") {
+            let starts_new_file = files
+                .last()
+                .is_none_or(|f| f.has_old_header || !f.hunks.is_empty());
+            if starts_new_file {
+                files.push(PatchFile::default());
+            }
+            if let Some(file) = files.last_mut() {
+                file.old_path = parse_patch_path(rest);
+                file.has_old_header = true;
+            }
+        } else if let Some(rest) = line.strip_prefix("+++ ") {
+            if let Some(file) = files.last_mut() {
+                file.new_path = parse_patch_path(rest);
+            }
+        } else if let Some(rest) = line.strip_prefix("rename from ") {
+            if let Some(file) = files.last_mut() {
+                file.old_path = Some(rest.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("rename to ") {
+            if let Some(file) = files.last_mut() {
+                file.new_path = Some(rest.to_string());
+            }
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            if let Some(file) = files.last_mut() {
+                file.binary = true;
+            }
+        } else if line.starts_with("@@") {
+            if files.is_empty() {
+                files.push(PatchFile::default());
+            }
+            let hunk = parse_patch_hunk(line, &mut lines);
+            if let Some(file) = files.last_mut() {
+                file.hunks.push(hunk);
+            }
+        }
+    }
+
+    let results: Vec<DiffResult> = files
+        .into_iter()
+        .filter(|f| !f.hunks.is_empty() || f.binary || f.old_path != f.new_path)
+        .map(PatchFile::into_result)
+        .collect();
+    if results.is_empty() {
+        return Err(AxonError::invalid_input("未找到有效的 unified diff 内容"));
+    }
+    Ok(results)
+}
+
+/// 文件头中的路径，去掉 `a/` `b/` 前缀和时间戳，`/dev/null` 视为不存在
+fn parse_patch_path(raw: &str) -> Option<String> {
+    let path = raw.split('\t').next().unwrap_or_default().trim();
+    let path = path.trim_matches('"');
+    if path.is_empty() || path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// 解析 `-start,count` / `+start,count`，省略行数时为 1
+fn parse_hunk_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// 解析一个 hunk（头部和内容行）
+///
+/// hunk 头有行号时按行数读取内容；没有行号时读到第一个不是内容行的行为止，行号留空
+fn parse_patch_hunk<'a>(
+    header: &str,
+    lines: &mut std::iter::Peekable<impl Iterator<Item = &'a str>>,
+) -> DiffHunk {
+    let mut ranges = header.trim_start_matches('@').split_whitespace();
+    let old_range = ranges
+        .next()
+        .and_then(|r| r.strip_prefix('-'))
+        .and_then(parse_hunk_range);
+    let new_range = ranges
+        .next()
+        .and_then(|r| r.strip_prefix('+'))
+        .and_then(parse_hunk_range);
+    let ranges = old_range.zip(new_range);
+
+    let (mut old_remaining, mut new_remaining) = ranges.map_or((0, 0), |(o, n)| (o.1, n.1));
+    let (mut old_line, mut new_line) = ranges.map_or((0, 0), |(o, n)| (o.0, n.0));
+    let mut hunk_lines = Vec::new();
+
+    while let Some(&line) = lines.peek() {
+        let counted = ranges.is_some();
+        if counted && old_remaining == 0 && new_remaining == 0 {
+            break;
+        }
+        let (line_type, content) = match line.as_bytes().first() {
+            Some(b' ') => (DiffLineType::Unchanged, &line[1..]),
+            Some(b'-') if counted || !line.starts_with("--- ") => {
+                (DiffLineType::Removed, &line[1..])
+            }
+            Some(b'+') if counted || !line.starts_with("+++ ") => (DiffLineType::Added, &line[1..]),
+            // "\ No newline at end of file"
+            Some(b'\\') => {
+                lines.next();
+                continue;
+            }
+            // 有的输出会去掉空白上下文行的前导空格
+            None if counted => (DiffLineType::Unchanged, ""),
+            _ => break,
+        };
+        lines.next();
+
+        let old_number = (line_type != DiffLineType::Added).then(|| {
+            old_remaining = old_remaining.saturating_sub(1);
+            old_line += 1;
+            old_line - 1
+        });
+        let new_number = (line_type != DiffLineType::Removed).then(|| {
+            new_remaining = new_remaining.saturating_sub(1);
+            new_line += 1;
+            new_line - 1
+        });
+        hunk_lines.push(DiffLine {
+            line_type,
+            content: content.trim_end_matches('\r').to_string(),
+            old_line_number: old_number.filter(|_| counted),
+            new_line_number: new_number.filter(|_| counted),
+        });
+    }
+
+    DiffHunk {
+        old_start: ranges.map_or(0, |(o, _)| o.0),
+        old_count: hunk_lines
+            .iter()
+            .filter(|l| l.line_type != DiffLineType::Added)
+            .count(),
+        new_start: ranges.map_or(0, |(_, n)| n.0),
+        new_count: hunk_lines
+            .iter()
+            .filter(|l| l.line_type != DiffLineType::Removed)
+            .count(),
+        lines: hunk_lines,
+    }
+}
+
+// ============================================================================
+// 二进制 Diff
+// ============================================================================
+
+/// 二进制文件的差异摘要
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinaryDiffSummary {
+    /// 文件大小，文件不存在时为空
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+    /// 新文件相对旧文件的大小变化
+    pub size_delta: i64,
+    /// 内容完全相同
+    pub identical: bool,
+    /// 变化字节占比（0-100）：按内容分块后，只出现在一侧的块占两侧总字节数的比例
+    pub changed_percent: f64,
+    /// 图片信息（能识别为图片时）
+    pub old_image: Option<ImageInfo>,
+    pub new_image: Option<ImageInfo>,
+    /// 两侧都是图片时尺寸是否变化
+    pub dimensions_changed: Option<bool>,
+    /// 两侧都是图片时格式是否变化
+    pub format_changed: Option<bool>,
+}
+
+/// 计算二进制文件的差异摘要
+///
+/// 用于无法按文本对比的文件，一侧不存在时视为新增或删除
+///
+/// # 参数
+/// - `old_path`: 旧文件路径
+/// - `new_path`: 新文件路径
+#[tauri::command]
+pub async fn compute_binary_diff_summary(
+    state: State<'_, AppState>,
+    old_path: String,
+    new_path: String,
+) -> Result<BinaryDiffSummary, AxonError> {
+    let sandbox = PathSandbox::from_settings(&state.settings);
+    let old_path = sandbox.check(&old_path)?;
+    let new_path = sandbox.check(&new_path)?;
+    tokio::task::spawn_blocking(move || binary_diff_summary(&old_path, &new_path))
+    .await?
+}
+
+fn binary_diff_summary(old_path: &Path, new_path: &Path) -> Result<BinaryDiffSummary, AxonError> {
+    let old = read_binary(old_path)?;
+    let new = read_binary(new_path)?;
+    if old.is_none() && new.is_none() {
+        return Err(AxonError::not_found(format!(
+            "文件不存在: {} / {}",
+            old_path.display(),
+            new_path.display()
+        )));
+    }
+
+    let old_bytes = old.as_deref().unwrap_or_default();
+    let new_bytes = new.as_deref().unwrap_or_default();
+    let identical = old.is_some() && new.is_some() && old_bytes == new_bytes;
+    let changed_percent = if identical {
+        0.0
+    } else {
+        changed_bytes_percent(old_bytes, new_bytes)
+    };
+
+    let old_image = old.as_ref().and_then(|_| read_image_info(old_path).ok());
+    let new_image = new.as_ref().and_then(|_| read_image_info(new_path).ok());
+    let (dimensions_changed, format_changed) = match (&old_image, &new_image) {
+        (Some(old), Some(new)) => (
+            Some(old.width != new.width || old.height != new.height),
+            Some(old.format != new.format),
+        ),
+        _ => (None, None),
+    };
+
+    Ok(BinaryDiffSummary {
+        old_size: old.as_ref().map(|b| b.len() as u64),
+        new_size: new.as_ref().map(|b| b.len() as u64),
+        size_delta: new_bytes.len() as i64 - old_bytes.len() as i64,
+        identical,
+        changed_percent,
+        old_image,
+        new_image,
+        dimensions_changed,
+        format_changed,
+    })
+}
+
+/// 读取文件全部内容，不存在时为 `None`
+fn read_binary(path: &Path) -> Result<Option<Vec<u8>>, AxonError> {
+    let metadata = match std::fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(AxonError::io("读取文件失败", &e)),
+    };
+    if metadata.len() > MAX_BINARY_DIFF_BYTES {
+        return Err(AxonError::invalid_input(format!(
+            "文件过大（超过 {} MB）: {}",
+            MAX_BINARY_DIFF_BYTES / 1024 / 1024,
+            path.display()
+        )));
+    }
+    std::fs::read(path)
+        .map(Some)
+        .map_err(|e| AxonError::io("读取文件失败", &e))
+}
+
+/// 按内容分块（Gear 滚动哈希）后统计只出现在一侧的块的字节占比
+///
+/// 分块边界由内容决定，插入或删除字节只影响附近的块
+fn changed_bytes_percent(old: &[u8], new: &[u8]) -> f64 {
+    let total = old.len() + new.len();
+    if total == 0 {
+        return 0.0;
+    }
+
+    // 同一内容的块可能出现多次，按次数匹配
+    let mut old_chunks: HashMap<u64, usize> = HashMap::new();
+    for chunk in content_chunks(old) {
+        *old_chunks.entry(chunk_hash(chunk)).or_default() += 1;
+    }
+    let mut shared = 0;
+    for chunk in content_chunks(new) {
+        if let Some(count) = old_chunks.get_mut(&chunk_hash(chunk)).filter(|c| **c > 0) {
+            *count -= 1;
+            shared += chunk.len() * 2;
+        }
+    }
+    (total - shared) as f64 * 100.0 / total as f64
+}
+
+fn content_chunks(data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut rest = data;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let mut hash = 0u64;
+        let mut end = rest.len().min(BINARY_CHUNK_MAX);
+        for (i, byte) in rest.iter().enumerate().take(end) {
+            hash = (hash << 1).wrapping_add(GEAR[*byte as usize]);
+            if i + 1 >= BINARY_CHUNK_MIN && hash & BINARY_CHUNK_MASK == 0 {
+                end = i + 1;
+                break;
+            }
+        }
+        let (chunk, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(chunk)
+    })
+}
+
+fn chunk_hash(chunk: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    chunk.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Gear 哈希的随机表（splitmix64 生成，保证每次运行一致）
+static GEAR: [u64; 256] = gear_table();
+
+const fn gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+// ============================================================================
+// 结构化 Diff
+// ============================================================================
+
+/// 对比模式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffMode {
+    /// 按语法块对齐
+    Structural,
+    /// 行级对比
+    Line,
+}
+
+/// 语法块的变化类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockChangeKind {
+    /// 内容相同
+    Unchanged,
+    /// 只有空白、换行等格式不同
+    Reformatted,
+    /// 只有名称不同
+    Renamed,
+    /// 内容有修改
+    Modified,
+    /// 新增的块
+    Added,
+    /// 删除的块
+    Removed,
+}
+
+/// 行范围（从 1 开始，包含结束行）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// 单个语法块的变化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockChange {
+    pub kind: BlockChangeKind,
+    /// tree-sitter 节点类型，如 function_item
+    pub node_kind: String,
+    pub old_name: Option<String>,
+    pub new_name: Option<String>,
+    pub old_range: Option<LineRange>,
+    pub new_range: Option<LineRange>,
+    /// 与其他块的相对顺序发生了变化
+    pub moved: bool,
+    /// 重命名 / 修改的块内的行级差异（行号为文件中的行号）
+    pub hunks: Vec<DiffHunk>,
+    /// 容器块（impl / class 等）内部按成员的变化
+    pub children: Vec<BlockChange>,
+}
+
+/// 结构化对比结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuralDiffResult {
+    /// 实际使用的对比模式
+    pub mode: DiffMode,
+    /// 识别出的语言
+    pub language: Option<String>,
+    /// structural 模式下的块变化，按新文件顺序排列，删除的块位于原位置
+    pub blocks: Vec<BlockChange>,
+    /// line 模式下的行级差异
+    pub line_diff: Option<DiffResult>,
+    /// 回退到行级对比的原因
+    pub fallback_reason: Option<String>,
+    /// 是否有变更（包括仅格式变化和移动）
+    pub has_changes: bool,
+}
+
+/// 按语法块对比两个文本
+///
+/// 根据 `file_name` 的扩展名识别语言；语言不支持、文本过大或存在语法错误时
+/// 回退到行级对比（`mode` 为 line，结果在 `line_diff` 中）
+///
+/// # 参数
+/// - `old_text`: 旧文本内容
+/// - `new_text`: 新文本内容
+/// - `file_name`: 文件名，用于识别语言
+/// - `context_lines`: 块内行级差异的上下文行数（默认3行）
+#[tauri::command]
+pub fn compute_structural_diff(
+    old_text: &str,
+    new_text: &str,
+    file_name: Option<String>,
+    context_lines: Option<usize>,
+) -> StructuralDiffResult {
+    let context = context_lines.unwrap_or(3);
+    let language = file_name
+        .as_deref()
+        .and_then(|name| OutlineLanguage::from_path(Path::new(name)));
+
+    let blocks = match language {
+        None => Err("不支持结构化对比的语言".to_string()),
+        Some(_)
+            if old_text.len() > MAX_STRUCTURAL_DIFF_BYTES
+                || new_text.len() > MAX_STRUCTURAL_DIFF_BYTES =>
+        {
+            Err(format!(
+                "文本超过 {} MB，不进行结构化对比",
+                MAX_STRUCTURAL_DIFF_BYTES / 1024 / 1024
+            ))
+        }
+        Some(language) => structural_diff(language, old_text, new_text, context),
+    };
+
+    match blocks {
+        Ok(blocks) => StructuralDiffResult {
+            mode: DiffMode::Structural,
+            language: language.map(|l| l.name().to_string()),
+            has_changes: blocks
+                .iter()
+                .any(|b| b.kind != BlockChangeKind::Unchanged || b.moved),
+            blocks,
+            line_diff: None,
+            fallback_reason: None,
+        },
+        Err(reason) => {
+            debug!(
+                "结构化对比回退到行级对比: {:?}, 原因: {}",
+                file_name, reason
+            );
+            let line_diff = compute_line_diff(old_text, new_text, file_name, context_lines);
+            StructuralDiffResult {
+                mode: DiffMode::Line,
+                language: language.map(|l| l.name().to_string()),
+                blocks: Vec::new(),
+                has_changes: line_diff.has_changes,
+                line_diff: Some(line_diff),
+                fallback_reason: Some(reason),
+            }
+        }
+    }
+}
+
+/// 参与对齐的语法块
+struct Block<'a> {
+    node: Node<'a>,
+    name: Option<String>,
+    /// 整个文件的源码
+    source: &'a str,
+    text: &'a str,
+    /// 去掉格式后的 token 序列
+    tokens: String,
+    /// 不含名称的 token 序列，用于识别重命名
+    body_tokens: String,
+}
+
+/// token 之间的分隔符（不会出现在源码 token 中）
+const TOKEN_SEPARATOR: &str = "\u{0}";
+
+fn structural_diff(
+    language: OutlineLanguage,
+    old_text: &str,
+    new_text: &str,
+    context: usize,
+) -> Result<Vec<BlockChange>, String> {
+    let old_tree = parse_tree(language, old_text)?;
+    let new_tree = parse_tree(language, new_text)?;
+    let old_blocks = collect_blocks(language, old_tree.root_node(), old_text);
+    let new_blocks = collect_blocks(language, new_tree.root_node(), new_text);
+    Ok(diff_blocks(language, &old_blocks, &new_blocks, context))
+}
+
+fn parse_tree(language: OutlineLanguage, text: &str) -> Result<Tree, String> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language.grammar())
+        .map_err(|e| format!("加载语法失败: {}", e))?;
+    let tree = parser
+        .parse(text, None)
+        .ok_or_else(|| "解析源码失败".to_string())?;
+    if tree.root_node().has_error() {
+        return Err("源码存在语法错误".to_string());
+    }
+    Ok(tree)
+}
+
+fn collect_blocks<'a>(
+    language: OutlineLanguage,
+    parent: Node<'a>,
+    source: &'a str,
+) -> Vec<Block<'a>> {
+    let mut cursor = parent.walk();
+    parent
+        .named_children(&mut cursor)
+        .map(|node| {
+            let name_node = block_name(language, node);
+            let name = name_node
+                .and_then(|n| n.utf8_text(source.as_bytes()).ok())
+                .map(|s| s.trim().to_string());
+            let (tokens, body_tokens) = collect_tokens(node, source, name_node);
+            Block {
+                node,
+                name,
+                source,
+                text: &source[node.byte_range()],
+                tokens,
+                body_tokens,
+            }
+        })
+        .collect()
+}
+
+/// 块的名称节点；export 语句、装饰器、变量声明等包装节点取第一个可识别的子节点
+fn block_name<'a>(language: OutlineLanguage, node: Node<'a>) -> Option<Node<'a>> {
+    if let Some((_, name)) = classify_node(language, node, false) {
+        return name;
+    }
+    let mut cursor = node.walk();
+    let name = node
+        .named_children(&mut cursor)
+        .find_map(|child| classify_node(language, child, false))
+        .and_then(|(_, name)| name);
+    name
+}
+
+/// 按叶子节点收集 token，返回（全部 token，不含名称的 token）
+fn collect_tokens(node: Node, source: &str, name_node: Option<Node>) -> (String, String) {
+    let name_range = name_node.map(|n| n.byte_range());
+    let mut tokens = Vec::new();
+    let mut body_tokens = Vec::new();
+    let mut cursor = node.walk();
+    loop {
+        let current = cursor.node();
+        if current.child_count() == 0 {
+            let text = source[current.byte_range()].trim();
+            if !text.is_empty() {
+                tokens.push(text);
+                let in_name = name_range
+                    .as_ref()
+                    .is_some_and(|range| range.contains(&current.start_byte()));
+                if !in_name {
+                    body_tokens.push(text);
+                }
+            }
+        }
+        if cursor.goto_first_child() {
+            continue;
+        }
+        loop {
+            if cursor.goto_next_sibling() {
+                break;
+            }
+            if !cursor.goto_parent() {
+                return (
+                    tokens.join(TOKEN_SEPARATOR),
+                    body_tokens.join(TOKEN_SEPARATOR),
+                );
+            }
+        }
+    }
+}
+
+/// 对齐两组块：先匹配内容相同的块，再匹配同名的块，最后匹配只有名称不同的块
+fn diff_blocks(
+    language: OutlineLanguage,
+    old: &[Block],
+    new: &[Block],
+    context: usize,
+) -> Vec<BlockChange> {
+    type BlockPredicate = fn(&Block, &Block) -> bool;
+    let passes: [BlockPredicate; 3] = [
+        // 内容相同（忽略格式）
+        |a, b| a.tokens == b.tokens,
+        // 同名
+        |a, b| a.node.kind() == b.node.kind() && a.name.is_some() && a.name == b.name,
+        // 只有名称不同
+        |a, b| {
+            a.node.kind() == b.node.kind()
+                && a.name.is_some()
+                && b.name.is_some()
+                && a.body_tokens == b.body_tokens
+        },
+    ];
+
+    let mut old_matched = vec![false; old.len()];
+    let mut new_match: Vec<Option<usize>> = vec![None; new.len()];
+    for pass in passes {
+        for (new_idx, new_block) in new.iter().enumerate() {
+            if new_match[new_idx].is_some() {
+                continue;
+            }
+            let found = (0..old.len()).find(|&i| !old_matched[i] && pass(&old[i], new_block));
+            if let Some(old_idx) = found {
+                old_matched[old_idx] = true;
+                new_match[new_idx] = Some(old_idx);
+            }
+        }
+    }
+
+    let in_order = in_order_matches(&new_match);
+    let mut changes = Vec::with_capacity(old.len().max(new.len()));
+    let mut next_old = 0;
+    for (new_idx, new_block) in new.iter().enumerate() {
+        let Some(old_idx) = new_match[new_idx] else {
+            changes.push(added_or_removed(BlockChangeKind::Added, new_block));
+            continue;
+        };
+        // 删除的块放在其后第一个按顺序保留的块之前
+        if in_order[new_idx] {
+            while next_old < old_idx {
+                if !old_matched[next_old] {
+                    changes.push(added_or_removed(BlockChangeKind::Removed, &old[next_old]));
+                }
+                next_old += 1;
+            }
+        }
+        changes.push(matched_change(
+            language,
+            &old[old_idx],
+            new_block,
+            !in_order[new_idx],
+            context,
+        ));
+    }
+    for (old_idx, block) in old.iter().enumerate().skip(next_old) {
+        if !old_matched[old_idx] {
+            changes.push(added_or_removed(BlockChangeKind::Removed, block));
+        }
+    }
+    changes
+}
+
+/// 找出保持原有相对顺序的匹配（旧序号的最长递增子序列），其余视为移动
+fn in_order_matches(new_match: &[Option<usize>]) -> Vec<bool> {
+    let matched: Vec<(usize, usize)> = new_match
+        .iter()
+        .enumerate()
+        .filter_map(|(new_idx, old_idx)| old_idx.map(|old_idx| (new_idx, old_idx)))
+        .collect();
+    let mut lengths = vec![1usize; matched.len()];
+    let mut previous: Vec<Option<usize>> = vec![None; matched.len()];
+    for i in 0..matched.len() {
+        for j in 0..i {
+            if matched[j].1 < matched[i].1 && lengths[j] + 1 > lengths[i] {
+                lengths[i] = lengths[j] + 1;
+                previous[i] = Some(j);
+            }
+        }
+    }
+
+    let mut in_order = vec![false; new_match.len()];
+    let mut current = (0..matched.len()).max_by_key(|&i| lengths[i]);
+    while let Some(i) = current {
+        in_order[matched[i].0] = true;
+        current = previous[i];
+    }
+    in_order
+}
+
+fn matched_change(
+    language: OutlineLanguage,
+    old: &Block,
+    new: &Block,
+    moved: bool,
+    context: usize,
+) -> BlockChange {
+    let kind = if old.tokens == new.tokens {
+        if old.text == new.text {
+            BlockChangeKind::Unchanged
+        } else {
+            BlockChangeKind::Reformatted
+        }
+    } else if old.body_tokens == new.body_tokens {
+        BlockChangeKind::Renamed
+    } else {
+        BlockChangeKind::Modified
+    };
+
+    let hunks = match kind {
+        BlockChangeKind::Renamed | BlockChangeKind::Modified => block_hunks(old, new, context),
+        _ => Vec::new(),
+    };
+    let children = match (
+        kind,
+        container_body(language, old),
+        container_body(language, new),
+    ) {
+        (BlockChangeKind::Modified, Some(old_body), Some(new_body)) => {
+            let old_children = collect_blocks(language, old_body, old.source);
+            let new_children = collect_blocks(language, new_body, new.source);
+            diff_blocks(language, &old_children, &new_children, context)
+        }
+        _ => Vec::new(),
+    };
+
+    BlockChange {
+        kind,
+        node_kind: new.node.kind().to_string(),
+        old_name: old.name.clone(),
+        new_name: new.name.clone(),
+        old_range: Some(line_range(old.node)),
+        new_range: Some(line_range(new.node)),
+        moved,
+        hunks,
+        children,
+    }
+}
+
+fn added_or_removed(kind: BlockChangeKind, block: &Block) -> BlockChange {
+    let range = Some(line_range(block.node));
+    let (old_range, new_range) = if kind == BlockChangeKind::Added {
+        (None, range)
+    } else {
+        (range, None)
+    };
+    BlockChange {
+        kind,
+        node_kind: block.node.kind().to_string(),
+        old_name: old_range.and(block.name.clone()),
+        new_name: new_range.and(block.name.clone()),
+        old_range,
+        new_range,
+        moved: false,
+        hunks: Vec::new(),
+        children: Vec::new(),
+    }
+}
+
+/// impl / class / trait 等容器块的成员列表节点
+fn container_body<'a>(language: OutlineLanguage, block: &Block<'a>) -> Option<Node<'a>> {
+    let (kind, _) = classify_node(language, block.node, false)?;
+    let is_container = matches!(
+        kind,
+        "class" | "struct" | "enum" | "trait" | "interface" | "impl" | "module"
+    );
+    if is_container {
+        block.node.child_by_field_name("body")
+    } else {
+        None
+    }
+}
+
+/// 块内的行级差异，行号换算为文件中的行号
+fn block_hunks(old: &Block, new: &Block, context: usize) -> Vec<DiffHunk> {
+    let old_offset = old.node.start_position().row;
+    let new_offset = new.node.start_position().row;
+    let mut hunks = compute_line_diff(old.text, new.text, None, Some(context)).hunks;
+    for hunk in &mut hunks {
+        hunk.old_start += old_offset;
+        hunk.new_start += new_offset;
+        for line in &mut hunk.lines {
+            line.old_line_number = line.old_line_number.map(|n| n + old_offset);
+            line.new_line_number = line.new_line_number.map(|n| n + new_offset);
+        }
+    }
+    hunks
+}
+
+fn line_range(node: Node) -> LineRange {
+    LineRange {
+        start: node.start_position().row + 1,
+        end: node.end_position().row + 1,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -346,7 +1506,7 @@ mod tests {
     #[test]
     fn test_compute_diff_no_changes() {
         let text = "hello\nworld";
-        let result = compute_diff(text, text, None, None);
+        let result = compute_line_diff(text, text, None, None);
         assert!(!result.has_changes);
         assert_eq!(result.additions, 0);
         assert_eq!(result.deletions, 0);
@@ -356,7 +1516,7 @@ mod tests {
     fn test_compute_diff_with_changes() {
         let old = "line1\nline2\nline3";
         let new = "line1\nmodified\nline3";
-        let result = compute_diff(old, new, Some("test.txt".to_string()), None);
+        let result = compute_line_diff(old, new, Some("test.txt".to_string()), None);
 
         assert!(result.has_changes);
         assert_eq!(result.additions, 1);
@@ -398,7 +1558,7 @@ mod tests {
         // 测试 hunk 分组：多处变更应该被分组
         let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12";
         let new = "1\nX\n3\n4\n5\n6\n7\n8\n9\n10\nY\n12";
-        let result = compute_diff(old, new, None, Some(2));
+        let result = compute_line_diff(old, new, None, Some(2));
 
         // 变更之间间隔足够大，应该有2个 hunks
         assert!(result.has_changes);
@@ -406,4 +1566,221 @@ mod tests {
         assert_eq!(result.deletions, 2);
         assert!(result.hunks.len() >= 1); // 至少一个 hunk
     }
+
+    #[test]
+    fn test_apply_line_hunks() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+        let new = "1\nX\n3\n4\n5\n6\n7\n8\n9\n10\nY\n12\n";
+        let hunks = compute_line_diff(old, new, None, Some(2)).hunks;
+        assert_eq!(hunks.len(), 2);
+
+        let result = apply_line_hunks(old, new, vec![1], Some(2)).unwrap();
+        assert_eq!(result.hunk_count, 2);
+        assert_eq!(result.content, "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\nY\n12\n");
+        assert_eq!(
+            result.inverse_content,
+            "1\nX\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n"
+        );
+
+        let all = apply_line_hunks(old, new, vec![0, 1], Some(2)).unwrap();
+        assert_eq!(all.content, new);
+        assert_eq!(all.inverse_content, old);
+
+        // 上下文变大后两处变更合并为一个 hunk
+        let merged = apply_line_hunks(old, new, vec![0], Some(5)).unwrap();
+        assert_eq!(merged.hunk_count, 1);
+        assert_eq!(merged.content, new);
+
+        assert!(apply_line_hunks(old, new, vec![2], Some(2)).is_err());
+    }
+
+    #[test]
+    fn test_incremental_diff_matches_full_diff() {
+        let cache = DiffCache::new();
+        let old: String = (0..200).map(|i| format!("line {}\n", i)).collect();
+        let mut new = old.replace("line 10\n", "changed 10\n");
+        let first = cached_diff(&cache, old.clone(), new.clone(), None, 3, None).unwrap();
+        assert_eq!(first.additions, 1);
+
+        // 只有新文本的尾部变化
+        new = new.replace("line 190\n", "");
+        new.push_str("appended\n");
+        let incremental = cached_diff(&cache, old.clone(), new.clone(), None, 3, None).unwrap();
+        let full = compute_line_diff(&old, &new, None, None);
+        assert_eq!(incremental.additions, 2);
+        assert_eq!(incremental.deletions, 2);
+        assert_eq!(
+            serde_json::to_value(&incremental.hunks).unwrap(),
+            serde_json::to_value(&full.hunks).unwrap()
+        );
+
+        // 部分应用使用与增量结果相同的 hunk 划分
+        let applied = cached_apply(&cache, &old, &new, vec![1, 2], 3).unwrap();
+        assert_eq!(applied.hunk_count, incremental.hunks.len());
+        assert!(!applied.content.contains("changed 10"));
+        assert!(!applied.content.contains("line 190\n"));
+        assert!(applied.content.ends_with("appended\n"));
+        let all = cached_apply(&cache, &old, &new, (0..applied.hunk_count).collect(), 3).unwrap();
+        assert_eq!(all.content, new);
+        assert_eq!(all.inverse_content, old);
+
+        let jobs = crate::jobs::JobManager::new();
+        let job = jobs.register("diff").unwrap();
+        jobs.cancel("diff");
+        assert!(cached_diff(&cache, new.clone(), old.clone(), None, 3, Some(&job)).is_err());
+    }
+
+    #[test]
+    fn test_parse_git_patch() {
+        let patch = [
+            "diff --git a/src/lib.rs b/src/lib.rs",
+            "index 1111111..2222222 100644",
+            "--- a/src/lib.rs",
+            "+++ b/src/lib.rs",
+            "@@ -1,3 +1,3 @@ fn main",
+            " fn main() {",
+            "-    old();",
+            "+    new();",
+            " }",
+            "\\ No newline at end of file",
+            "diff --git a/notes.md b/notes.md",
+            "new file mode 100644",
+            "--- /dev/null",
+            "+++ b/notes.md",
+            "@@ -0,0 +1,2 @@",
+            "+--- not a header",
+            "+second",
+            "diff --git a/old_name.txt b/new_name.txt",
+            "similarity index 100%",
+            "rename from old_name.txt",
+            "rename to new_name.txt",
+            "diff --git a/logo.png b/logo.png",
+            "Binary files a/logo.png and b/logo.png differ",
+        ]
+        .join("\n");
+        let files = parse_unified_diff(&patch).unwrap();
+        assert_eq!(files.len(), 4);
+
+        assert_eq!(files[0].file_name.as_deref(), Some("src/lib.rs"));
+        assert_eq!((files[0].additions, files[0].deletions), (1, 1));
+        let hunk = &files[0].hunks[0];
+        assert_eq!((hunk.old_start, hunk.old_count, hunk.new_count), (1, 3, 3));
+        assert_eq!(hunk.lines[2].new_line_number, Some(2));
+        assert_eq!(hunk.lines[2].content, "    new();");
+
+        assert_eq!(files[1].file_name.as_deref(), Some("notes.md"));
+        assert_eq!(files[1].hunks[0].lines[0].content, "--- not a header");
+        assert_eq!(files[1].additions, 2);
+
+        assert_eq!(files[2].file_name.as_deref(), Some("new_name.txt"));
+        assert!(files[2].hunks.is_empty());
+        assert!(files[3].has_changes && files[3].hunks.is_empty());
+    }
+
+    #[test]
+    fn test_parse_llm_style_patch() {
+        // 代码围栏、没有文件头、hunk 头没有行号
+        let patch = "```diff\n@@ ... @@\n context\n-removed\n+added\n+another\n```\n";
+        let files = parse_unified_diff(patch).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].file_name, None);
+        assert_eq!((files[0].additions, files[0].deletions), (2, 1));
+        assert_eq!(files[0].hunks[0].lines.len(), 4);
+        assert_eq!(files[0].hunks[0].lines[0].old_line_number, None);
+
+        assert!(parse_unified_diff("just some text").is_err());
+    }
+
+    #[test]
+    fn test_binary_diff_summary() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        // 伪随机内容，保证分块边界分布正常
+        let mut state = 1u64;
+        let old: Vec<u8> = (0..64 * 1024)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                (state >> 56) as u8
+            })
+            .collect();
+        let mut new = old.clone();
+        new.splice(32 * 1024..32 * 1024, [0xAB; 100]);
+        let old_path = dir.join("old.bin");
+        let new_path = dir.join("new.bin");
+        std::fs::write(&old_path, &old).unwrap();
+        std::fs::write(&new_path, &new).unwrap();
+
+        let summary = binary_diff_summary(&old_path, &new_path).unwrap();
+        assert_eq!(summary.size_delta, 100);
+        assert!(!summary.identical);
+        // 插入只影响附近的块
+        assert!(summary.changed_percent > 0.0 && summary.changed_percent < 20.0);
+        assert!(summary.old_image.is_none() && summary.dimensions_changed.is_none());
+
+        let same = binary_diff_summary(&old_path, &old_path).unwrap();
+        assert!(same.identical);
+        assert_eq!(same.changed_percent, 0.0);
+
+        let added = binary_diff_summary(&dir.join("missing.bin"), &new_path).unwrap();
+        assert_eq!(added.old_size, None);
+        assert_eq!(added.changed_percent, 100.0);
+    }
+
+    #[test]
+    fn test_structural_diff_classifies_blocks() {
+        let old = "fn alpha() -> i32 { 1 }\n\nfn beta(x: i32) -> i32 {\n    x + 1\n}\n\nfn gamma() {}\n\nfn delta() {\n    println!(\"d\");\n}\n";
+        let new = "fn gamma() {}\n\nfn alpha() -> i32 {\n    1\n}\n\nfn beta2(x: i32) -> i32 {\n    x + 1\n}\n\nfn delta() {\n    println!(\"changed\");\n}\n\nfn epsilon() {}\n";
+        let result = compute_structural_diff(old, new, Some("lib.rs".to_string()), None);
+
+        assert_eq!(result.mode, DiffMode::Structural);
+        assert!(result.has_changes);
+        let summary: Vec<(BlockChangeKind, Option<&str>, bool)> = result
+            .blocks
+            .iter()
+            .map(|b| (b.kind, b.new_name.as_deref(), b.moved))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (BlockChangeKind::Unchanged, Some("gamma"), true),
+                (BlockChangeKind::Reformatted, Some("alpha"), false),
+                (BlockChangeKind::Renamed, Some("beta2"), false),
+                (BlockChangeKind::Modified, Some("delta"), false),
+                (BlockChangeKind::Added, Some("epsilon"), false),
+            ]
+        );
+        assert_eq!(result.blocks[2].old_name.as_deref(), Some("beta"));
+        // 块内差异的行号是文件中的行号
+        let delta_hunk = &result.blocks[3].hunks[0];
+        assert!(delta_hunk
+            .lines
+            .iter()
+            .any(|l| l.line_type == DiffLineType::Added && l.new_line_number == Some(12)));
+    }
+
+    #[test]
+    fn test_structural_diff_nested_and_fallback() {
+        let old = "impl Foo {\n    fn a() {}\n    fn b() { 1; }\n}\n";
+        let new = "impl Foo {\n    fn a() {}\n    fn b() { 2; }\n    fn c() {}\n}\n";
+        let result = compute_structural_diff(old, new, Some("foo.rs".to_string()), None);
+        let children: Vec<BlockChangeKind> =
+            result.blocks[0].children.iter().map(|b| b.kind).collect();
+        assert_eq!(
+            children,
+            vec![
+                BlockChangeKind::Unchanged,
+                BlockChangeKind::Modified,
+                BlockChangeKind::Added
+            ]
+        );
+
+        let plain = compute_structural_diff("a\n", "b\n", Some("notes.txt".to_string()), None);
+        assert_eq!(plain.mode, DiffMode::Line);
+        assert!(plain.line_diff.unwrap().has_changes);
+
+        let broken =
+            compute_structural_diff("fn a() {}\n", "fn a( {\n", Some("a.rs".to_string()), None);
+        assert_eq!(broken.mode, DiffMode::Line);
+        assert!(broken.fallback_reason.is_some());
+    }
 }
@@ -0,0 +1,236 @@
+//! 目录大小分析命令
+//!
+//! 在后台任务中递归统计目录大小，按子目录聚合并按大小排序，
+//! 过程中发送 `fs:size-progress` 事件，可通过 `cancel_job(job_id)` 取消。
+//! 不跟随符号链接，无法读取的条目计入 `skipped`。
+
+use crate::error::{AxonError, ErrorKind};
+use crate::jobs::JobHandle;
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tracing::{debug, warn};
+
+/// 目录大小分析进度事件
+pub const EVENT_FS_SIZE_PROGRESS: &str = "fs:size-progress";
+
+/// 进度事件最小发送间隔
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(150);
+
+/// 默认展开的层数
+const DEFAULT_DEPTH: usize = 2;
+
+/// 最大展开层数（更深的目录只统计总量）
+const MAX_DEPTH: usize = 8;
+
+/// 每个目录最多列出的子项数，其余只计入 omitted_children
+const MAX_CHILDREN: usize = 50;
+
+/// 目录（或文件）大小统计
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectorySize {
+    pub name: String,
+    pub path: String,
+    pub is_directory: bool,
+    /// 总字节数（包含所有子项）
+    pub size: u64,
+    /// 文件总数（包含所有子项）
+    pub file_count: u64,
+    /// 在展开层数内的子项（按大小降序）
+    pub children: Vec<DirectorySize>,
+    /// 超出 MAX_CHILDREN 未列出的子项数量
+    pub omitted_children: usize,
+}
+
+/// 目录大小分析结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectorySizeReport {
+    pub root: DirectorySize,
+    /// 无法读取而跳过的条目数
+    pub skipped: u64,
+    pub elapsed_ms: u64,
+}
+
+/// 目录大小分析进度
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectorySizeProgress {
+    pub job_id: String,
+    pub files_scanned: u64,
+    pub bytes_scanned: u64,
+    pub current_path: Option<String>,
+}
+
+/// 分析目录大小（后台任务）
+///
+/// `depth` 为结果中展开的子目录层数（默认 2），统计本身总是覆盖整个目录
+#[tauri::command]
+pub async fn analyze_directory_size(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+    path: String,
+    depth: Option<usize>,
+) -> Result<DirectorySizeReport, AxonError> {
+    let depth = depth.unwrap_or(DEFAULT_DEPTH).min(MAX_DEPTH);
+    debug!(
+        "分析目录大小: {}, 展开 {} 层, 任务: {}",
+        path, depth, job_id
+    );
+    let root = PathSandbox::from_settings(&state.settings).check(&path)?;
+    if !root.is_dir() {
+        return Err(AxonError::invalid_input(format!("路径不是目录: {}", path)));
+    }
+
+    let job = state.jobs.register(&job_id)?;
+    let jobs = Arc::clone(&state.jobs);
+
+    let result = tokio::task::spawn_blocking(move || {
+        let started = Instant::now();
+        let mut scanner = SizeScanner::new(&app, &job);
+        let root = scanner.scan(&root, depth)?;
+        scanner.emit();
+        Ok(DirectorySizeReport {
+            root,
+            skipped: scanner.skipped,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        })
+    })
+    .await;
+
+    jobs.finish(&job_id);
+    let report: Result<DirectorySizeReport, AxonError> =
+        result.map_err(|e| AxonError::internal(format!("目录大小分析任务失败: {}", e)))?;
+    match &report {
+        Ok(_) => state
+            .notifications
+            .notify_task_finished("目录大小分析完成", &path, true),
+        Err(e) if e.kind != ErrorKind::Cancelled => {
+            state
+                .notifications
+                .notify_task_finished("目录大小分析失败", &e.message, false)
+        }
+        Err(_) => {}
+    }
+    report
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+/// 递归统计目录大小（节流发送进度事件）
+struct SizeScanner<'a> {
+    app: &'a AppHandle,
+    job: &'a JobHandle,
+    progress: DirectorySizeProgress,
+    skipped: u64,
+    last_emit: Instant,
+}
+
+impl<'a> SizeScanner<'a> {
+    fn new(app: &'a AppHandle, job: &'a JobHandle) -> Self {
+        Self {
+            app,
+            job,
+            progress: DirectorySizeProgress {
+                job_id: job.id().to_string(),
+                files_scanned: 0,
+                bytes_scanned: 0,
+                current_path: None,
+            },
+            skipped: 0,
+            last_emit: Instant::now(),
+        }
+    }
+
+    fn emit(&self) {
+        if let Err(e) = self.app.emit(EVENT_FS_SIZE_PROGRESS, &self.progress) {
+            warn!("发送目录大小分析进度失败: {}", e);
+        }
+    }
+
+    /// 统计目录，`depth` 为仍需展开的层数
+    fn scan(&mut self, dir: &Path, depth: usize) -> Result<DirectorySize, AxonError> {
+        if self.job.is_cancelled() {
+            return Err(AxonError::cancelled("操作已取消"));
+        }
+
+        let mut node = DirectorySize {
+            name: file_name(dir),
+            path: dir.to_string_lossy().to_string(),
+            is_directory: true,
+            size: 0,
+            file_count: 0,
+            children: Vec::new(),
+            omitted_children: 0,
+        };
+
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            self.skipped += 1;
+            return Ok(node);
+        };
+
+        for entry in entries {
+            let Ok(entry) = entry else {
+                self.skipped += 1;
+                continue;
+            };
+            let path = entry.path();
+            let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+                self.skipped += 1;
+                continue;
+            };
+
+            let child = if metadata.is_dir() {
+                self.scan(&path, depth.saturating_sub(1))?
+            } else {
+                self.record_file(&path, metadata.len());
+                DirectorySize {
+                    name: file_name(&path),
+                    path: path.to_string_lossy().to_string(),
+                    is_directory: false,
+                    size: metadata.len(),
+                    file_count: 1,
+                    children: Vec::new(),
+                    omitted_children: 0,
+                }
+            };
+
+            node.size += child.size;
+            node.file_count += child.file_count;
+            if depth > 0 {
+                node.children.push(child);
+            }
+        }
+
+        node.children.sort_by_key(|c| std::cmp::Reverse(c.size));
+        if node.children.len() > MAX_CHILDREN {
+            node.omitted_children = node.children.len() - MAX_CHILDREN;
+            node.children.truncate(MAX_CHILDREN);
+        }
+        Ok(node)
+    }
+
+    fn record_file(&mut self, path: &Path, bytes: u64) {
+        self.progress.files_scanned += 1;
+        self.progress.bytes_scanned += bytes;
+        if self.last_emit.elapsed() >= PROGRESS_EMIT_INTERVAL {
+            self.progress.current_path = Some(path.to_string_lossy().to_string());
+            self.emit();
+            self.last_emit = Instant::now();
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
@@ -0,0 +1,146 @@
+//! 语义搜索命令
+
+use super::{resolve_provider_endpoint, ProviderApiStyle};
+use crate::embeddings::{EmbeddingClient, EmbeddingIndexStatus, SemanticMatch};
+use crate::error::{AxonError, ErrorKind};
+use crate::opencode::EmbeddingSettings;
+use crate::state::AppState;
+use serde_json::json;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
+use tracing::debug;
+
+/// 获取嵌入模型设置
+#[tauri::command]
+pub fn get_embedding_settings(state: State<'_, AppState>) -> EmbeddingSettings {
+    state.settings.get_embedding_settings()
+}
+
+/// 更新嵌入模型设置
+#[tauri::command]
+pub fn set_embedding_settings(
+    state: State<'_, AppState>,
+    embedding: EmbeddingSettings,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "embedding": &embedding });
+    state
+        .audit
+        .track_sync("set_embedding_settings", audit_args, || {
+            if embedding.model.trim().is_empty() {
+                return Err(AxonError::invalid_input("嵌入模型不能为空"));
+            }
+            state
+                .settings
+                .set_embedding_settings(embedding)
+                .map_err(AxonError::from)
+        })
+}
+
+/// 构建（或增量更新）当前项目的语义索引（后台任务）
+///
+/// 进度通过 `embeddings:progress` 事件发送，可通过 `cancel_job(job_id)` 取消
+#[tauri::command]
+pub async fn build_embedding_index(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<EmbeddingIndexStatus, AxonError> {
+    let audit_args = json!({ "jobId": &job_id });
+    state
+        .audit
+        .track("build_embedding_index", audit_args, async {
+            let client = embedding_client(&state)?;
+            let (root, files) = project_files(&state)?;
+            debug!(
+                "构建语义索引: {:?}, {} 个文件, 模型: {}",
+                root,
+                files.len(),
+                client.model()
+            );
+
+            let job = state.jobs.register(&job_id)?;
+            let result = state
+                .embeddings
+                .build(&app, &job, &client, &state.power, &root, files)
+                .await;
+            state.jobs.finish(&job_id);
+            match &result {
+                Ok(status) => state.notifications.notify_task_finished(
+                    "语义索引已建立",
+                    &format!(
+                        "{} 个文件, {} 个代码块",
+                        status.file_count, status.chunk_count
+                    ),
+                    true,
+                ),
+                Err(e) if e.kind != ErrorKind::Cancelled => state
+                    .notifications
+                    .notify_task_finished("语义索引构建失败", &e.message, false),
+                Err(_) => {}
+            }
+            result
+        })
+        .await
+}
+
+/// 获取语义索引状态
+#[tauri::command]
+pub fn get_embedding_index_status(state: State<'_, AppState>) -> EmbeddingIndexStatus {
+    state.embeddings.status()
+}
+
+/// 在当前项目中语义搜索代码，返回最相近的 `top_k` 个代码块（默认 10）
+#[tauri::command]
+pub async fn semantic_search(
+    state: State<'_, AppState>,
+    query: String,
+    top_k: Option<usize>,
+) -> Result<Vec<SemanticMatch>, AxonError> {
+    let client = embedding_client(&state)?;
+    let root = state
+        .file_index
+        .status()
+        .root
+        .map(PathBuf::from)
+        .ok_or_else(|| AxonError::unavailable("未设置项目目录"))?;
+    state.embeddings.search(&client, &root, &query, top_k).await
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+/// 按设置选择服务商：指定了服务商时使用该服务商，否则使用第一个可用的非 Anthropic 服务商
+fn embedding_client(state: &AppState) -> Result<EmbeddingClient, AxonError> {
+    let settings = state.settings.get_settings();
+    let embedding = settings.embedding;
+
+    let endpoint = match &embedding.provider_id {
+        Some(id) => {
+            let provider = settings
+                .providers
+                .iter()
+                .find(|p| &p.id == id)
+                .ok_or_else(|| AxonError::not_found(format!("服务商不存在: {}", id)))?;
+            resolve_provider_endpoint(state, provider).map_err(AxonError::invalid_input)?
+        }
+        None => settings
+            .providers
+            .iter()
+            .filter_map(|p| resolve_provider_endpoint(state, p).ok())
+            .find(|e| e.style != ProviderApiStyle::Anthropic)
+            .ok_or_else(|| AxonError::unavailable("没有可用于嵌入的服务商，请先配置服务商"))?,
+    };
+    EmbeddingClient::new(endpoint, embedding.model)
+}
+
+/// 当前项目目录和文件列表（来自文件索引）
+fn project_files(state: &AppState) -> Result<(PathBuf, Vec<String>), AxonError> {
+    if state.file_index.status().root.is_none() {
+        return Err(AxonError::unavailable("未设置项目目录"));
+    }
+    state
+        .file_index
+        .snapshot()
+        .ok_or_else(|| AxonError::unavailable("文件索引正在建立，请稍后重试"))
+}
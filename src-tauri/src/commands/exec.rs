@@ -0,0 +1,470 @@
+//! 非交互式命令执行模块
+//!
+//! 在项目目录中运行命令并捕获输出（无需完整 PTY）：
+//! - 通过事件实时推送 stdout/stderr
+//! - 超时与取消（复用后台任务机制）
+//! - 命令白名单；不在白名单中的命令通过操作确认（consent）询问用户
+//! - 工作目录受路径沙箱约束，拒绝可注入代码的环境变量和配置参数
+
+use crate::consent::{ConsentAction, ConsentDecision};
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+
+/// 命令输出事件
+pub const EVENT_EXEC_OUTPUT: &str = "exec:output";
+
+/// 命令未授权错误前缀
+pub const COMMAND_NOT_APPROVED: &str = "命令未授权";
+
+/// 默认超时时间（10 分钟）
+const DEFAULT_TIMEOUT_MS: u64 = 10 * 60 * 1000;
+
+/// 每个输出流最多保留的字节数
+const MAX_CAPTURE_BYTES: usize = 1024 * 1024;
+
+/// 进程结束后等待输出读取完成的最长时间
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 禁止覆盖的环境变量前缀（动态链接注入、Git 配置与钩子）
+const BLOCKED_ENV_PREFIXES: &[&str] = &["LD_", "DYLD_", "GIT_"];
+
+/// 禁止覆盖的环境变量（解释器启动注入、程序查找路径）
+const BLOCKED_ENV_NAMES: &[&str] = &[
+    "PATH",
+    "PATHEXT",
+    "COMSPEC",
+    "BASH_ENV",
+    "ENV",
+    "SHELLOPTS",
+    "PROMPT_COMMAND",
+    "NODE_OPTIONS",
+    "PYTHONPATH",
+    "PYTHONSTARTUP",
+    "PERL5OPT",
+    "RUBYOPT",
+    "JAVA_TOOL_OPTIONS",
+    "_JAVA_OPTIONS",
+];
+
+/// 可覆盖配置或执行任意代码的参数，白名单中的程序也不允许使用
+/// （如 `git -c core.fsmonitor=...`、`git --upload-pack=...`）
+const BLOCKED_ARG_PREFIXES: &[&str] = &[
+    "--config",
+    "--exec-path",
+    "--upload-pack",
+    "--receive-pack",
+    "--exec",
+];
+
+/// 命令输出事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecOutput {
+    pub run_id: String,
+    /// stdout / stderr
+    pub stream: String,
+    pub line: String,
+}
+
+/// 命令执行结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunCommandResult {
+    pub run_id: String,
+    /// 退出码（被终止时为 None）
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub timed_out: bool,
+    pub cancelled: bool,
+    pub stdout: String,
+    pub stderr: String,
+    /// 输出超过上限被截断
+    pub truncated: bool,
+    pub duration_ms: u64,
+}
+
+/// 运行命令并捕获输出
+///
+/// - `cmd` 不在白名单中时通过操作确认询问用户，拒绝或超时返回以 `命令未授权` 开头的错误；
+///   用户选择"始终允许"时加入白名单
+/// - `cwd` 为空时使用当前项目目录，须位于路径沙箱允许的目录内
+/// - `args` 不能包含 `-c`、`--config` 等覆盖配置的参数，`env` 不能覆盖 `PATH`、`LD_*`、`GIT_*` 等变量
+/// - `run_id` 可由前端生成，用于关联输出事件和调用 `cancel_job` 取消
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn run_command(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    cmd: String,
+    args: Option<Vec<String>>,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+    timeout_ms: Option<u64>,
+    run_id: Option<String>,
+) -> Result<RunCommandResult, AxonError> {
+    let audit_args = json!({
+        "cmd": &cmd,
+        "args": &args,
+        "cwd": &cwd,
+        "envKeys": env.as_ref().map(|e| e.keys().collect::<Vec<_>>()),
+        "runId": &run_id,
+    });
+    state.audit.track("run_command", audit_args, async {
+        let program = program_key(&cmd);
+        if program.is_empty() {
+            return Err(AxonError::invalid_input("命令不能为空"));
+        }
+        let args = args.unwrap_or_default();
+        check_args(&args)?;
+        let env = env.unwrap_or_default();
+        check_env(&env)?;
+
+        let cwd = cwd
+            .filter(|c| !c.trim().is_empty())
+            .or_else(|| state.settings.get_project_directory())
+            .ok_or_else(|| AxonError::invalid_input("未指定工作目录"))?;
+        let cwd = PathSandbox::from_settings(&state.settings).check(&cwd)?;
+        if !cwd.is_dir() {
+            return Err(AxonError::not_found(format!("工作目录不存在: {}", cwd.display())));
+        }
+
+        if !is_command_allowed(&state, &program) {
+            confirm_command(&state, &program, &cmd, &args, &cwd).await?;
+        }
+
+        let run_id = run_id.unwrap_or_else(|| format!("run-{}", chrono::Utc::now().timestamp_millis()));
+        let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+        let job = state.jobs.register(&run_id)?;
+
+        info!(
+            "执行命令: {} {:?} (cwd: {}, run_id: {})",
+            cmd, args, cwd.display(), run_id
+        );
+
+        let mut command = Command::new(&cmd);
+        command
+            .args(&args)
+            .current_dir(&cwd)
+            .envs(env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        // Windows 平台：避免弹出控制台窗口
+        #[cfg(target_os = "windows")]
+        {
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        let started = Instant::now();
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                state.jobs.finish(&run_id);
+                error!("启动命令失败: {}, 错误: {}", cmd, e);
+                return Err(AxonError::io("启动命令失败", &e));
+            }
+        };
+
+        let app = Arc::new(app);
+        let stdout_task = child
+            .stdout
+            .take()
+            .map(|out| spawn_reader(Arc::clone(&app), run_id.clone(), "stdout", out));
+        let stderr_task = child
+            .stderr
+            .take()
+            .map(|err| spawn_reader(Arc::clone(&app), run_id.clone(), "stderr", err));
+
+        let mut timed_out = false;
+        let mut cancelled = false;
+        let status = loop {
+            tokio::select! {
+                status = child.wait() => break status.ok(),
+                _ = tokio::time::sleep(Duration::from_millis(100)) => {
+                    if job.is_cancelled() {
+                        cancelled = true;
+                        break None;
+                    }
+                    if started.elapsed() >= timeout {
+                        timed_out = true;
+                        break None;
+                    }
+                }
+            }
+        };
+
+        if status.is_none() {
+            debug!(
+                "终止命令: {} (超时: {}, 取消: {})",
+                run_id, timed_out, cancelled
+            );
+            if let Err(e) = child.kill().await {
+                warn!("终止进程失败: {}", e);
+            }
+        }
+
+        let (stdout, stdout_truncated) = collect_output(stdout_task).await;
+        let (stderr, stderr_truncated) = collect_output(stderr_task).await;
+        state.jobs.finish(&run_id);
+
+        let exit_code = status.and_then(|s| s.code());
+        let duration_ms = started.elapsed().as_millis() as u64;
+        info!(
+            "命令结束: {} (退出码: {:?}, 耗时: {}ms)",
+            run_id, exit_code, duration_ms
+        );
+
+        Ok(RunCommandResult {
+            run_id,
+            exit_code,
+            success: exit_code == Some(0),
+            timed_out,
+            cancelled,
+            stdout,
+            stderr,
+            truncated: stdout_truncated || stderr_truncated,
+            duration_ms,
+        })
+    })
+    .await
+}
+
+/// 撤销命令授权（从白名单移除）
+#[tauri::command]
+pub fn revoke_command(state: State<'_, AppState>, cmd: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "cmd": &cmd });
+    state.audit.track_sync("revoke_command", audit_args, || {
+        let program = program_key(&cmd);
+        Ok(state.settings.remove_allowed_command(&program)?)
+    })
+}
+
+/// 获取命令白名单
+#[tauri::command]
+pub fn list_allowed_commands(state: State<'_, AppState>) -> Vec<String> {
+    state.settings.get_allowed_commands()
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+/// 归一化命令名
+///
+/// 裸命令名去掉 Windows 可执行扩展名并转小写（`npm.cmd` -> `npm`）；
+/// 带路径的命令按完整路径授权，避免同名程序绕过白名单
+fn program_key(cmd: &str) -> String {
+    let cmd = cmd.trim();
+    if cmd.contains('/') || cmd.contains('\\') {
+        return cmd.to_string();
+    }
+
+    let lower = cmd.to_lowercase();
+    [".exe", ".cmd", ".bat"]
+        .iter()
+        .find_map(|ext| lower.strip_suffix(ext))
+        .map(|stem| stem.to_string())
+        .unwrap_or(lower)
+}
+
+fn is_command_allowed(state: &AppState, program: &str) -> bool {
+    state
+        .settings
+        .get_allowed_commands()
+        .iter()
+        .any(|c| c == program)
+}
+
+/// 询问用户是否允许运行不在白名单中的命令
+///
+/// "仅本次"只放行当前调用，"始终允许"按程序名加入白名单
+async fn confirm_command(
+    state: &AppState,
+    program: &str,
+    cmd: &str,
+    args: &[String],
+    cwd: &Path,
+) -> Result<(), AxonError> {
+    let summary = format!("{} {}", cmd, args.join(" "));
+    let project = Some(cwd.to_string_lossy().to_string());
+    let decision = state
+        .consent
+        .ask(
+            ConsentAction::CommandExec,
+            "run_command".to_string(),
+            summary.trim_end().to_string(),
+            project,
+            None,
+        )
+        .await;
+
+    match decision {
+        Ok(ConsentDecision::AllowOnce) => Ok(()),
+        Ok(ConsentDecision::AlwaysAllow) => {
+            info!("加入命令白名单: {}", program);
+            Ok(state.settings.add_allowed_command(program)?)
+        }
+        Ok(ConsentDecision::Deny) | Err(_) => {
+            warn!("用户未授权命令: {}", cmd);
+            Err(AxonError::permission_denied(format!(
+                "{}: {}",
+                COMMAND_NOT_APPROVED, program
+            )))
+        }
+    }
+}
+
+/// 拒绝可覆盖程序配置或执行任意代码的参数
+fn check_args(args: &[String]) -> Result<(), AxonError> {
+    let blocked = args.iter().find(|arg| {
+        arg.as_str() == "-c"
+            || BLOCKED_ARG_PREFIXES.iter().any(|prefix| {
+                arg.strip_prefix(prefix)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(['=', '-']))
+            })
+    });
+    match blocked {
+        Some(arg) => Err(AxonError::invalid_input(format!("不允许使用参数: {}", arg))),
+        None => Ok(()),
+    }
+}
+
+/// 拒绝可注入代码或改变程序查找路径的环境变量
+fn check_env(env: &HashMap<String, String>) -> Result<(), AxonError> {
+    let blocked = env.keys().find(|name| {
+        let upper = name.to_uppercase();
+        BLOCKED_ENV_PREFIXES.iter().any(|p| upper.starts_with(p))
+            || BLOCKED_ENV_NAMES.contains(&upper.as_str())
+    });
+    match blocked {
+        Some(name) => Err(AxonError::invalid_input(format!("不允许覆盖环境变量: {}", name))),
+        None => Ok(()),
+    }
+}
+
+/// 已捕获的输出
+#[derive(Default)]
+struct CapturedOutput {
+    text: String,
+    truncated: bool,
+}
+
+/// 输出流读取任务
+struct OutputReader {
+    task: JoinHandle<()>,
+    captured: Arc<Mutex<CapturedOutput>>,
+}
+
+/// 逐行读取输出流并推送事件，同时保留有限长度的完整输出
+fn spawn_reader<R>(
+    app: Arc<AppHandle>,
+    run_id: String,
+    stream: &'static str,
+    reader: R,
+) -> OutputReader
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    let captured = Arc::new(Mutex::new(CapturedOutput::default()));
+    let sink = Arc::clone(&captured);
+
+    let task = tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+
+        loop {
+            let line = match lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(e) => {
+                    warn!("读取命令输出失败 ({}): {}", stream, e);
+                    break;
+                }
+            };
+
+            {
+                let mut sink = sink.lock();
+                if sink.text.len() + line.len() < MAX_CAPTURE_BYTES {
+                    sink.text.push_str(&line);
+                    sink.text.push('\n');
+                } else {
+                    sink.truncated = true;
+                }
+            }
+
+            let payload = ExecOutput {
+                run_id: run_id.clone(),
+                stream: stream.to_string(),
+                line,
+            };
+            if let Err(e) = app.emit(EVENT_EXEC_OUTPUT, &payload) {
+                warn!("发送命令输出事件失败: {}", e);
+            }
+        }
+    });
+
+    OutputReader { task, captured }
+}
+
+/// 等待输出读取完成；子进程遗留的后代进程可能持有管道，因此设置等待上限
+async fn collect_output(reader: Option<OutputReader>) -> (String, bool) {
+    let Some(mut reader) = reader else {
+        return (String::new(), false);
+    };
+
+    if tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, &mut reader.task)
+        .await
+        .is_err()
+    {
+        debug!("等待输出结束超时，停止读取");
+        reader.task.abort();
+    }
+
+    let mut captured = reader.captured.lock();
+    (std::mem::take(&mut captured.text), captured.truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn rejects_config_overrides() {
+        assert!(check_args(&args(&["status", "--short"])).is_ok());
+        assert!(check_args(&args(&["log", "--execute-hooks"])).is_ok());
+        assert!(check_args(&args(&["-c", "core.fsmonitor=evil", "status"])).is_err());
+        assert!(check_args(&args(&["--config-env=core.sshCommand=X", "fetch"])).is_err());
+        assert!(check_args(&args(&["fetch", "--upload-pack=evil"])).is_err());
+        assert!(check_args(&args(&["--exec-path", "/tmp"])).is_err());
+    }
+
+    #[test]
+    fn rejects_injecting_env_vars() {
+        let env = |name: &str| HashMap::from([(name.to_string(), "x".to_string())]);
+        assert!(check_env(&env("RUST_LOG")).is_ok());
+        assert!(check_env(&env("LD_PRELOAD")).is_err());
+        assert!(check_env(&env("git_ssh_command")).is_err());
+        assert!(check_env(&env("Path")).is_err());
+        assert!(check_env(&env("NODE_OPTIONS")).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+//! 从 opencode CLI 的全局配置导入 Agent 和服务商
+//!
+//! 导入服务商后前端需要调用 `client.instance.dispose()` 让 OpenCode 重新读取配置。
+
+use super::provider::{read_config_json, write_config_json};
+use crate::error::AxonError;
+use crate::opencode::auth::{read_auth_json, write_auth_json};
+use crate::opencode::external::{self, ExternalOpencodeConfig};
+use crate::opencode::{CustomConfig, ProviderAuth, UserProviderConfig};
+use crate::state::AppState;
+use crate::utils::paths::get_app_data_dir;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use tauri::State;
+use tracing::info;
+
+/// 未导入的条目
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSkip {
+    pub name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalImportReport {
+    /// 导入后的 Agent ID
+    pub imported_agents: Vec<String>,
+    /// 导入的服务商 ID
+    pub imported_providers: Vec<String>,
+    pub skipped: Vec<ImportSkip>,
+}
+
+/// 扫描用户在 opencode CLI 中的全局配置（Axon 隔离目录之外），列出可导入的 Agent 和服务商
+#[tauri::command]
+pub async fn scan_external_opencode_config(
+    state: State<'_, AppState>,
+) -> Result<ExternalOpencodeConfig, AxonError> {
+    let configured = configured_providers(&state);
+    Ok(tokio::task::spawn_blocking(move || scan_marked(&configured)).await?)
+}
+
+/// 导入选中的 Agent（按名称）和服务商（按 ID），`overwrite` 为 true 时覆盖已导入的 Agent
+#[tauri::command]
+pub async fn import_external_opencode_config(
+    state: State<'_, AppState>,
+    agents: Vec<String>,
+    providers: Vec<String>,
+    overwrite: Option<bool>,
+) -> Result<ExternalImportReport, AxonError> {
+    let audit_args = json!({ "agents": &agents, "providers": &providers });
+    state
+        .audit
+        .track("import_external_opencode_config", audit_args, async {
+            let configured = configured_providers(&state);
+            let overwrite = overwrite.unwrap_or(false);
+            let (report, new_providers) =
+                tokio::task::spawn_blocking(move || -> Result<_, AxonError> {
+                    import(&scan_marked(&configured), &agents, &providers, overwrite)
+                })
+                .await??;
+
+            if !new_providers.is_empty() {
+                let mut settings = state.settings.get_settings();
+                settings.providers.extend(new_providers);
+                state.settings.set_settings(settings)?;
+            }
+            info!(
+                "已从 opencode 全局配置导入 {} 个 Agent、{} 个服务商",
+                report.imported_agents.len(),
+                report.imported_providers.len()
+            );
+            Ok(report)
+        })
+        .await
+}
+
+fn configured_providers(state: &AppState) -> HashSet<String> {
+    state
+        .settings
+        .get_settings()
+        .providers
+        .into_iter()
+        .map(|provider| provider.registry_id)
+        .collect()
+}
+
+/// 扫描并标记已导入的 Agent 和已配置的服务商
+fn scan_marked(configured: &HashSet<String>) -> ExternalOpencodeConfig {
+    let app_data_dir = get_app_data_dir();
+    let mut config = external::scan(app_data_dir.as_deref());
+    if let Some(agents_dir) = app_data_dir.map(|dir| dir.join("agents")) {
+        for agent in &mut config.agents {
+            agent.already_imported = agents_dir
+                .join(format!("{}.json", agent.import_id))
+                .exists();
+        }
+    }
+    for provider in &mut config.providers {
+        provider.already_configured = configured.contains(&provider.id);
+    }
+    config
+}
+
+fn import(
+    config: &ExternalOpencodeConfig,
+    agents: &[String],
+    providers: &[String],
+    overwrite: bool,
+) -> Result<(ExternalImportReport, Vec<UserProviderConfig>), AxonError> {
+    let mut report = ExternalImportReport::default();
+    let skip = |report: &mut ExternalImportReport, name: &str, reason: &str| {
+        report.skipped.push(ImportSkip {
+            name: name.to_string(),
+            reason: reason.to_string(),
+        });
+    };
+    let app_data_dir =
+        get_app_data_dir().ok_or_else(|| AxonError::unavailable("应用数据目录不可用"))?;
+    let now = chrono::Utc::now();
+
+    let agents_dir = app_data_dir.join("agents");
+    for name in agents {
+        let Some(agent) = config.agents.iter().find(|agent| &agent.name == name) else {
+            skip(&mut report, name, "未找到该 Agent");
+            continue;
+        };
+        if agent.already_imported && !overwrite {
+            skip(&mut report, name, "已导入过");
+            continue;
+        }
+        std::fs::create_dir_all(&agents_dir)
+            .map_err(|e| AxonError::io("创建 agents 目录失败", &e))?;
+        let definition = external::to_agent_definition(agent, now.timestamp_millis());
+        let content = serde_json::to_string_pretty(&definition)
+            .map_err(|e| AxonError::internal(format!("序列化 Agent 失败: {}", e)))?;
+        std::fs::write(
+            agents_dir.join(format!("{}.json", agent.import_id)),
+            content,
+        )
+        .map_err(|e| AxonError::io("保存 Agent 配置失败", &e))?;
+        report.imported_agents.push(agent.import_id.clone());
+    }
+
+    let mut new_providers = Vec::new();
+    let mut auth_data = None;
+    let mut config_data = None;
+    for id in providers {
+        let Some(provider) = config.providers.iter().find(|provider| &provider.id == id) else {
+            skip(&mut report, id, "未找到该服务商");
+            continue;
+        };
+        if provider.already_configured {
+            skip(&mut report, id, "Axon 中已配置该服务商");
+            continue;
+        }
+        if provider
+            .auth_type
+            .as_deref()
+            .is_some_and(|kind| kind != "api")
+        {
+            skip(&mut report, id, "OAuth 登录需要在 Axon 中重新授权");
+            continue;
+        }
+
+        let key = provider.api_key();
+        if let Some(key) = &key {
+            let auth = match &mut auth_data {
+                Some(auth) => auth,
+                None => auth_data.insert(read_auth_json()?),
+            };
+            if let Some(object) = auth.as_object_mut() {
+                object.insert(id.clone(), json!({ "type": "api", "key": key }));
+            }
+        }
+
+        // 服务商的其它配置（baseURL、headers、模型列表等）写入 OpenCode 配置，API Key 只保存在 auth.json
+        let mut provider_config = provider.config.clone();
+        if let Some(Value::Object(options)) = provider_config.get_mut("options") {
+            options.remove("apiKey");
+        }
+        if !provider_config.is_empty() {
+            let data = match &mut config_data {
+                Some(data) => data,
+                None => config_data.insert(read_config_json()?),
+            };
+            if let Some(object) = data.as_object_mut() {
+                let section = object.entry("provider").or_insert_with(|| json!({}));
+                if let Some(section) = section.as_object_mut() {
+                    section.insert(id.clone(), Value::Object(provider_config));
+                }
+            }
+        }
+
+        let options = provider.config.get("options");
+        let custom_config = CustomConfig {
+            base_url: provider.base_url.clone(),
+            api_key: None,
+            enterprise_url: None,
+            set_cache_key: None,
+            timeout: options.and_then(|options| options.get("timeout")).cloned(),
+            headers: options
+                .and_then(|options| options.get("headers"))
+                .and_then(|headers| serde_json::from_value(headers.clone()).ok()),
+            env: None,
+            extra: None,
+        };
+        let timestamp = now.to_rfc3339();
+        new_providers.push(UserProviderConfig {
+            id: format!("provider_{}_{}", now.timestamp_millis(), id),
+            registry_id: id.clone(),
+            name: provider.name.clone().unwrap_or_else(|| id.clone()),
+            auth: ProviderAuth::Api {
+                key: key.unwrap_or_default(),
+            },
+            custom_config: Some(custom_config),
+            created_at: timestamp.clone(),
+            updated_at: timestamp,
+        });
+        report.imported_providers.push(id.clone());
+    }
+
+    if let Some(auth) = &auth_data {
+        write_auth_json(auth)?;
+    }
+    if let Some(data) = &config_data {
+        write_config_json(data)?;
+    }
+    Ok((report, new_providers))
+}
@@ -0,0 +1,23 @@
+//! 项目文件模糊查找命令
+
+use crate::file_index::{FileIndexStatus, FuzzyMatch};
+use crate::state::AppState;
+use tauri::State;
+
+/// 在当前项目的文件索引中模糊查找路径（命令面板"转到文件"）
+///
+/// 索引仍在构建时只返回已索引部分的结果
+#[tauri::command]
+pub fn fuzzy_find(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Vec<FuzzyMatch> {
+    state.file_index.find(&query, limit)
+}
+
+/// 获取文件索引状态
+#[tauri::command]
+pub fn get_file_index_status(state: State<'_, AppState>) -> FileIndexStatus {
+    state.file_index.status()
+}
@@ -4,11 +4,28 @@
 //! - 确保目录存在
 //! - 打开目录选择对话框
 //! - 读取目录内容
-
+//! - 检测与转换文件编码、换行符
+//! - 获取文件元数据与校验和
+//! - 带进度和取消支持的批量复制/删除
+
+use crate::error::{AxonError, ErrorKind};
+use crate::jobs::JobHandle;
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use crate::utils::text_encoding::{self, LineEnding, TextFormat};
 use serde::Serialize;
-use std::path::Path;
-use tauri::AppHandle;
-use tracing::{debug, error};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tracing::{debug, error, warn};
+
+/// 批量文件操作进度事件
+pub const EVENT_FS_BATCH_PROGRESS: &str = "fs:batch-progress";
+
+/// 进度事件最小发送间隔
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(100);
 
 /// 文件/目录条目信息
 #[derive(Debug, Clone, Serialize)]
@@ -27,50 +44,125 @@ pub struct FileEntry {
     pub modified_at: Option<u64>,
 }
 
+/// 文件/目录完整元数据
+#[derive(Debug, Clone, Serialize)]
+pub struct FileMetadata {
+    /// 完整路径
+    pub path: String,
+    /// 文件/目录名称
+    pub name: String,
+    /// 是否为目录
+    pub is_directory: bool,
+    /// 是否为文件
+    pub is_file: bool,
+    /// 是否为符号链接
+    pub is_symlink: bool,
+    /// 符号链接目标（仅符号链接有值）
+    pub symlink_target: Option<String>,
+    /// 大小（字节）
+    pub size: u64,
+    /// 创建时间（Unix 时间戳毫秒，部分文件系统不支持）
+    pub created_at: Option<u64>,
+    /// 修改时间（Unix 时间戳毫秒）
+    pub modified_at: Option<u64>,
+    /// 访问时间（Unix 时间戳毫秒）
+    pub accessed_at: Option<u64>,
+    /// 是否只读
+    pub readonly: bool,
+    /// Unix 权限位（八进制字符串，如 "644"），非 Unix 平台为 None
+    pub permissions: Option<String>,
+    /// 根据扩展名推测的 MIME 类型
+    pub mime_type: Option<String>,
+    /// SHA256 摘要（仅在请求时计算，目录为 None）
+    pub sha256: Option<String>,
+}
+
+/// 批量操作进度
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchProgress {
+    /// 任务 ID
+    pub job_id: String,
+    /// 操作类型（copy / delete）
+    pub operation: String,
+    /// 已处理文件数
+    pub files_done: u64,
+    /// 文件总数
+    pub files_total: u64,
+    /// 已处理字节数
+    pub bytes_done: u64,
+    /// 总字节数
+    pub bytes_total: u64,
+    /// 当前处理的路径
+    pub current_path: Option<String>,
+}
+
+/// 批量操作中单个条目的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchItemResult {
+    /// 源路径
+    pub source: String,
+    /// 是否成功
+    pub success: bool,
+    /// 结果路径（复制后的新路径）
+    pub result_path: Option<String>,
+    /// 错误信息
+    pub error: Option<String>,
+    /// 是否因任务取消而未完成
+    pub cancelled: bool,
+}
+
 /// 确保目录存在
 /// 如果目录不存在，则递归创建
 #[tauri::command]
-pub async fn ensure_directory_exists(path: String) -> Result<(), String> {
-    debug!("确保目录存在: {}", path);
-    
-    let path = Path::new(&path);
-    
-    if path.exists() {
-        if path.is_dir() {
-            debug!("目录已存在: {:?}", path);
-            return Ok(());
-        } else {
-            error!("路径存在但不是目录: {:?}", path);
-            return Err(format!("路径存在但不是目录: {:?}", path));
+pub async fn ensure_directory_exists(state: State<'_, AppState>, path: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "path": &path });
+    state.audit.track("ensure_directory_exists", audit_args, async {
+        debug!("确保目录存在: {}", path);
+        let checked = check_path(&state, &path)?;
+        let path = checked.as_path();
+
+        if path.exists() {
+            if path.is_dir() {
+                debug!("目录已存在: {:?}", path);
+                return Ok(());
+            } else {
+                error!("路径存在但不是目录: {:?}", path);
+                return Err(AxonError::invalid_input(format!("路径存在但不是目录: {:?}", path)));
+            }
         }
-    }
-    
-    // 递归创建目录
-    std::fs::create_dir_all(path).map_err(|e| {
-        error!("创建目录失败: {:?}, 错误: {}", path, e);
-        format!("创建目录失败: {}", e)
-    })?;
-    
-    debug!("目录创建成功: {:?}", path);
-    Ok(())
+
+        // 递归创建目录
+        std::fs::create_dir_all(path).map_err(|e| {
+            error!("创建目录失败: {:?}, 错误: {}", path, e);
+            AxonError::localized_io("fs.create_dir_failed", &e)
+        })?;
+
+        debug!("目录创建成功: {:?}", path);
+        Ok(())
+    })
+    .await
 }
 
 /// 读取目录内容
 /// 返回目录下的文件和子目录列表
 #[tauri::command]
-pub async fn read_directory(path: String, show_hidden: bool) -> Result<Vec<FileEntry>, String> {
+pub async fn read_directory(
+    state: State<'_, AppState>,
+    path: String,
+    show_hidden: bool,
+) -> Result<Vec<FileEntry>, AxonError> {
     debug!("读取目录内容: {}, 显示隐藏文件: {}", path, show_hidden);
-
-    let dir_path = Path::new(&path);
+    let checked = check_path(&state, &path)?;
+    let dir_path = checked.as_path();
 
     if !dir_path.exists() {
         error!("目录不存在: {:?}", dir_path);
-        return Err(format!("目录不存在: {}", path));
+        return Err(AxonError::not_found(format!("目录不存在: {}", path)));
     }
 
     if !dir_path.is_dir() {
         error!("路径不是目录: {:?}", dir_path);
-        return Err(format!("路径不是目录: {}", path));
+        return Err(AxonError::invalid_input(format!("路径不是目录: {}", path)));
     }
 
     let mut entries = Vec::new();
@@ -122,7 +214,7 @@ pub async fn read_directory(path: String, show_hidden: bool) -> Result<Vec<FileE
         }
         Err(e) => {
             error!("读取目录失败: {:?}, 错误: {}", dir_path, e);
-            return Err(format!("读取目录失败: {}", e));
+            return Err(AxonError::localized_io("fs.read_dir_failed", &e));
         }
     }
 
@@ -142,19 +234,27 @@ pub async fn read_directory(path: String, show_hidden: bool) -> Result<Vec<FileE
 /// 读取文件内容
 /// 返回文件的文本内容
 #[tauri::command]
-pub async fn read_file_content(path: String) -> Result<String, String> {
+pub async fn read_file_content(state: State<'_, AppState>, path: String) -> Result<String, AxonError> {
     debug!("读取文件内容: {}", path);
-
-    let file_path = Path::new(&path);
+    let checked = check_path(&state, &path)?;
+    let file_path = checked.as_path();
 
     if !file_path.exists() {
         error!("文件不存在: {:?}", file_path);
-        return Err(format!("文件不存在: {}", path));
+        return Err(AxonError::localized(
+            ErrorKind::NotFound,
+            "fs.file_not_found",
+            json!({ "path": path }),
+        ));
     }
 
     if !file_path.is_file() {
         error!("路径不是文件: {:?}", file_path);
-        return Err(format!("路径不是文件: {}", path));
+        return Err(AxonError::localized(
+            ErrorKind::InvalidInput,
+            "fs.not_a_file",
+            json!({ "path": path }),
+        ));
     }
 
     // 读取文件内容
@@ -175,12 +275,12 @@ pub async fn read_file_content(path: String) -> Result<String, String> {
                     }
                     Err(read_err) => {
                         error!("读取文件失败: {:?}, 错误: {}", file_path, read_err);
-                        Err(format!("读取文件失败: {}", read_err))
+                        Err(AxonError::localized_io("fs.read_file_failed", &read_err))
                     }
                 }
             } else {
                 error!("读取文件失败: {:?}, 错误: {}", file_path, e);
-                Err(format!("读取文件失败: {}", e))
+                Err(AxonError::localized_io("fs.read_file_failed", &e))
             }
         }
     }
@@ -188,220 +288,750 @@ pub async fn read_file_content(path: String) -> Result<String, String> {
 
 /// 写入文件内容
 /// 将内容写入指定文件路径
+///
+/// `preserve_format` 为 true 且目标文件已存在时，沿用原文件的编码、BOM 和换行符
 #[tauri::command]
-pub async fn write_file_content(path: String, content: String) -> Result<(), String> {
-    debug!("写入文件内容: {}", path);
-
-    let file_path = Path::new(&path);
-
-    // 确保父目录存在
-    if let Some(parent) = file_path.parent() {
-        if !parent.exists() {
-            std::fs::create_dir_all(parent).map_err(|e| {
-                error!("创建父目录失败: {:?}, 错误: {}", parent, e);
-                format!("创建父目录失败: {}", e)
+pub async fn write_file_content(
+    state: State<'_, AppState>,
+    path: String,
+    content: String,
+    preserve_format: Option<bool>,
+) -> Result<(), AxonError> {
+    let audit_args = json!({
+        "path": &path,
+        "bytes": content.len(),
+        "preserveFormat": preserve_format,
+    });
+    state.audit.track("write_file_content", audit_args, async {
+        debug!("写入文件内容: {}", path);
+        let checked = check_path(&state, &path)?;
+        let file_path = checked.as_path();
+
+        let bytes = if preserve_format.unwrap_or(false) && file_path.is_file() {
+            let original = std::fs::read(file_path).map_err(|e| {
+                error!("读取原文件失败: {:?}, 错误: {}", file_path, e);
+                AxonError::io("读取原文件失败", &e)
             })?;
+            let format = text_encoding::detect_format(&original);
+            let content = text_encoding::normalize_line_endings(&content, format.line_ending);
+            let (bytes, had_unmappable) = text_encoding::encode(&content, &format);
+            if had_unmappable {
+                return Err(AxonError::invalid_input(format!(
+                    "写入文件失败: 内容包含 {} 编码无法表示的字符",
+                    format.encoding.name()
+                )));
+            }
+            bytes
+        } else {
+            content.into_bytes()
+        };
+
+        // 确保父目录存在
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    error!("创建父目录失败: {:?}, 错误: {}", parent, e);
+                    AxonError::io("创建父目录失败", &e)
+                })?;
+            }
         }
-    }
 
-    // 写入文件
-    match std::fs::write(file_path, &content) {
-        Ok(()) => {
-            debug!("成功写入文件，大小: {} 字节", content.len());
-            Ok(())
-        }
-        Err(e) => {
-            error!("写入文件失败: {:?}, 错误: {}", file_path, e);
-            
-            #[cfg(target_os = "windows")]
-            {
-                use std::io::ErrorKind;
-                if e.kind() == ErrorKind::PermissionDenied {
-                    return Err("写入文件失败: 文件可能被其他程序占用，请关闭占用程序后重试".to_string());
+        // 写入文件
+        match std::fs::write(file_path, &bytes) {
+            Ok(()) => {
+                debug!("成功写入文件，大小: {} 字节", bytes.len());
+                Ok(())
+            }
+            Err(e) => {
+                error!("写入文件失败: {:?}, 错误: {}", file_path, e);
+
+                #[cfg(target_os = "windows")]
+                {
+                    use std::io::ErrorKind;
+                    if e.kind() == ErrorKind::PermissionDenied {
+                        return Err(AxonError::permission_denied("写入文件失败: 文件可能被其他程序占用，请关闭占用程序后重试"));
+                    }
                 }
+
+                Err(AxonError::localized_io("fs.write_file_failed", &e))
             }
-            
-            Err(format!("写入文件失败: {}", e))
+        }
+    })
+    .await
+}
+
+/// 文件编码信息
+#[derive(Debug, Clone, Serialize)]
+pub struct FileEncodingInfo {
+    /// 编码名称（如 UTF-8 / GBK / UTF-16LE）
+    pub encoding: String,
+    /// 是否带 BOM
+    pub has_bom: bool,
+    /// 换行符风格（lf / crlf / cr / mixed / none）
+    pub line_ending: LineEnding,
+}
+
+impl From<TextFormat> for FileEncodingInfo {
+    fn from(format: TextFormat) -> Self {
+        Self {
+            encoding: format.encoding.name().to_string(),
+            has_bom: format.has_bom,
+            line_ending: format.line_ending,
         }
     }
 }
 
+/// 检测文件编码、BOM 与换行符
+#[tauri::command]
+pub async fn detect_file_encoding(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<FileEncodingInfo, AxonError> {
+    debug!("检测文件编码: {}", path);
+    let file_path = check_path(&state, &path)?;
+
+    let bytes = std::fs::read(&file_path).map_err(|e| {
+        error!("读取文件失败: {}, 错误: {}", path, e);
+        AxonError::localized_io("fs.read_file_failed", &e)
+    })?;
+
+    Ok(text_encoding::detect_format(&bytes).into())
+}
+
+/// 转换文件编码和/或换行符
+///
+/// `encoding` 为编码标签（如 utf-8、gbk、utf-16le），`line_ending` 为 lf / crlf / cr；
+/// 未指定的项保持不变。`bom` 未指定时沿用原文件设置
+#[tauri::command]
+pub async fn convert_file(
+    state: State<'_, AppState>,
+    path: String,
+    encoding: Option<String>,
+    line_ending: Option<String>,
+    bom: Option<bool>,
+) -> Result<FileEncodingInfo, AxonError> {
+    let audit_args = json!({
+        "path": &path,
+        "encoding": &encoding,
+        "lineEnding": &line_ending,
+        "bom": bom,
+    });
+    state.audit.track("convert_file", audit_args, async {
+        debug!(
+            "转换文件: {}, 编码: {:?}, 换行符: {:?}",
+            path, encoding, line_ending
+        );
+        let file_path = check_path(&state, &path)?;
+
+        let bytes = std::fs::read(&file_path).map_err(|e| {
+            error!("读取文件失败: {}, 错误: {}", path, e);
+            AxonError::localized_io("fs.read_file_failed", &e)
+        })?;
+
+        let source = text_encoding::detect_format(&bytes);
+        let text = text_encoding::decode(&bytes, &source);
+
+        let mut target = source;
+        if let Some(label) = encoding {
+            target.encoding = text_encoding::encoding_for_label(&label)
+                .ok_or_else(|| AxonError::unsupported(format!("不支持的编码: {}", label)))?;
+        }
+        if let Some(value) = line_ending {
+            target.line_ending =
+                LineEnding::parse(&value).ok_or_else(|| AxonError::invalid_input(format!("不支持的换行符: {}", value)))?;
+        }
+        if let Some(bom) = bom {
+            target.has_bom = bom;
+        }
+
+        let text = text_encoding::normalize_line_endings(&text, target.line_ending);
+        let (output, had_unmappable) = text_encoding::encode(&text, &target);
+        if had_unmappable {
+            return Err(AxonError::invalid_input(format!(
+                "转换失败: 文件包含 {} 编码无法表示的字符",
+                target.encoding.name()
+            )));
+        }
+
+        std::fs::write(&file_path, &output).map_err(|e| {
+            error!("写入文件失败: {}, 错误: {}", path, e);
+            AxonError::localized_io("fs.write_file_failed", &e)
+        })?;
+
+        Ok(text_encoding::detect_format(&output).into())
+    })
+    .await
+}
+
 /// 删除文件或目录
 /// 如果是目录，递归删除所有内容
 #[tauri::command]
-pub async fn delete_path(path: String) -> Result<(), String> {
-    debug!("删除路径: {}", path);
+pub async fn delete_path(state: State<'_, AppState>, path: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "path": &path });
+    state.audit.track("delete_path", audit_args, async {
+        debug!("删除路径: {}", path);
+        let checked = check_path(&state, &path)?;
+        let target_path = checked.as_path();
+
+        if !target_path.exists() {
+            error!("路径不存在: {:?}", target_path);
+            return Err(AxonError::localized(
+                ErrorKind::NotFound,
+                "fs.path_not_found",
+                json!({ "path": path }),
+            ));
+        }
 
-    let target_path = Path::new(&path);
+        if target_path.is_dir() {
+            std::fs::remove_dir_all(target_path).map_err(|e| {
+                error!("删除目录失败: {:?}, 错误: {}", target_path, e);
+                AxonError::localized_io("fs.delete_dir_failed", &e)
+            })?;
+        } else {
+            std::fs::remove_file(target_path).map_err(|e| {
+                error!("删除文件失败: {:?}, 错误: {}", target_path, e);
+                AxonError::localized_io("fs.delete_file_failed", &e)
+            })?;
+        }
 
-    if !target_path.exists() {
-        error!("路径不存在: {:?}", target_path);
-        return Err(format!("路径不存在: {}", path));
-    }
+        debug!("删除成功: {:?}", target_path);
+        Ok(())
+    })
+    .await
+}
 
-    if target_path.is_dir() {
-        std::fs::remove_dir_all(target_path).map_err(|e| {
-            error!("删除目录失败: {:?}, 错误: {}", target_path, e);
-            format!("删除目录失败: {}", e)
+/// 重命名文件或目录
+#[tauri::command]
+pub async fn rename_path(state: State<'_, AppState>, old_path: String, new_name: String) -> Result<String, AxonError> {
+    let audit_args = json!({ "oldPath": &old_path, "newName": &new_name });
+    state.audit.track("rename_path", audit_args, async {
+        debug!("重命名: {} -> {}", old_path, new_name);
+        let checked = check_path(&state, &old_path)?;
+        let source_path = checked.as_path();
+
+        if !source_path.exists() {
+            error!("源路径不存在: {:?}", source_path);
+            return Err(AxonError::localized(
+                ErrorKind::NotFound,
+                "fs.source_not_found",
+                json!({ "path": old_path }),
+            ));
+        }
+
+        // 获取父目录并构建新路径
+        let parent = source_path.parent().ok_or_else(|| {
+            error!("无法获取父目录: {:?}", source_path);
+            "无法获取父目录".to_string()
         })?;
-    } else {
-        std::fs::remove_file(target_path).map_err(|e| {
-            error!("删除文件失败: {:?}, 错误: {}", target_path, e);
-            format!("删除文件失败: {}", e)
+
+        let new_path = check_path(&state, parent.join(&new_name))?;
+
+        if new_path.exists() {
+            error!("目标路径已存在: {:?}", new_path);
+            return Err(AxonError::already_exists(format!("目标路径已存在: {}", new_name)));
+        }
+
+        std::fs::rename(source_path, &new_path).map_err(|e| {
+            error!("重命名失败: {:?} -> {:?}, 错误: {}", source_path, new_path, e);
+            AxonError::io("重命名失败", &e)
         })?;
-    }
 
-    debug!("删除成功: {:?}", target_path);
-    Ok(())
+        let result = new_path.to_string_lossy().to_string();
+        debug!("重命名成功: {:?}", new_path);
+        Ok(result)
+    })
+    .await
 }
 
-/// 重命名文件或目录
+/// 复制文件或目录
+/// 返回新路径
 #[tauri::command]
-pub async fn rename_path(old_path: String, new_name: String) -> Result<String, String> {
-    debug!("重命名: {} -> {}", old_path, new_name);
-
-    let source_path = Path::new(&old_path);
+pub async fn copy_path(state: State<'_, AppState>, source: String, dest_dir: String) -> Result<String, AxonError> {
+    let audit_args = json!({ "source": &source, "destDir": &dest_dir });
+    state.audit.track("copy_path", audit_args, async {
+        debug!("复制: {} -> {}", source, dest_dir);
+        let checked_source = check_path(&state, &source)?;
+        let checked_dest_dir = check_path(&state, &dest_dir)?;
+        let source_path = checked_source.as_path();
+        let dest_dir_path = checked_dest_dir.as_path();
+
+        if !source_path.exists() {
+            error!("源路径不存在: {:?}", source_path);
+            return Err(AxonError::localized(
+                ErrorKind::NotFound,
+                "fs.source_not_found",
+                json!({ "path": source }),
+            ));
+        }
 
-    if !source_path.exists() {
-        error!("源路径不存在: {:?}", source_path);
-        return Err(format!("源路径不存在: {}", old_path));
-    }
+        if !dest_dir_path.is_dir() {
+            error!("目标必须是目录: {:?}", dest_dir_path);
+            return Err(AxonError::localized(
+                ErrorKind::InvalidInput,
+                "fs.target_not_dir",
+                json!({ "path": dest_dir }),
+            ));
+        }
 
-    // 获取父目录并构建新路径
-    let parent = source_path.parent().ok_or_else(|| {
-        error!("无法获取父目录: {:?}", source_path);
-        "无法获取父目录".to_string()
-    })?;
+        let file_name = source_path.file_name().ok_or_else(|| {
+            error!("无法获取文件名: {:?}", source_path);
+            "无法获取文件名".to_string()
+        })?;
 
-    let new_path = parent.join(&new_name);
+        let dest_path = dest_dir_path.join(file_name);
 
-    if new_path.exists() {
-        error!("目标路径已存在: {:?}", new_path);
-        return Err(format!("目标路径已存在: {}", new_name));
-    }
+        // 如果目标已存在，自动添加后缀
+        let final_dest = if dest_path.exists() {
+            generate_unique_path(&dest_path)
+        } else {
+            dest_path
+        };
 
-    std::fs::rename(source_path, &new_path).map_err(|e| {
-        error!("重命名失败: {:?} -> {:?}, 错误: {}", source_path, new_path, e);
-        format!("重命名失败: {}", e)
-    })?;
+        if source_path.is_dir() {
+            copy_dir_recursive(source_path, &final_dest)?;
+        } else {
+            std::fs::copy(source_path, &final_dest).map_err(|e| {
+                error!("复制文件失败: {:?} -> {:?}, 错误: {}", source_path, final_dest, e);
+                AxonError::localized_io("fs.copy_failed", &e)
+            })?;
+        }
 
-    let result = new_path.to_string_lossy().to_string();
-    debug!("重命名成功: {:?}", new_path);
-    Ok(result)
+        let result = final_dest.to_string_lossy().to_string();
+        debug!("复制成功: {:?}", final_dest);
+        Ok(result)
+    })
+    .await
 }
 
-/// 复制文件或目录
+/// 移动文件或目录
 /// 返回新路径
 #[tauri::command]
-pub async fn copy_path(source: String, dest_dir: String) -> Result<String, String> {
-    debug!("复制: {} -> {}", source, dest_dir);
+pub async fn move_path(state: State<'_, AppState>, source: String, dest_dir: String) -> Result<String, AxonError> {
+    let audit_args = json!({ "source": &source, "destDir": &dest_dir });
+    state.audit.track("move_path", audit_args, async {
+        debug!("移动: {} -> {}", source, dest_dir);
+        let checked_source = check_path(&state, &source)?;
+        let checked_dest_dir = check_path(&state, &dest_dir)?;
+        let source_path = checked_source.as_path();
+        let dest_dir_path = checked_dest_dir.as_path();
+
+        if !source_path.exists() {
+            error!("源路径不存在: {:?}", source_path);
+            return Err(AxonError::localized(
+                ErrorKind::NotFound,
+                "fs.source_not_found",
+                json!({ "path": source }),
+            ));
+        }
 
-    let source_path = Path::new(&source);
-    let dest_dir_path = Path::new(&dest_dir);
+        if !dest_dir_path.is_dir() {
+            error!("目标必须是目录: {:?}", dest_dir_path);
+            return Err(AxonError::localized(
+                ErrorKind::InvalidInput,
+                "fs.target_not_dir",
+                json!({ "path": dest_dir }),
+            ));
+        }
 
-    if !source_path.exists() {
-        error!("源路径不存在: {:?}", source_path);
-        return Err(format!("源路径不存在: {}", source));
-    }
+        let file_name = source_path.file_name().ok_or_else(|| {
+            error!("无法获取文件名: {:?}", source_path);
+            "无法获取文件名".to_string()
+        })?;
 
-    if !dest_dir_path.is_dir() {
-        error!("目标必须是目录: {:?}", dest_dir_path);
-        return Err(format!("目标必须是目录: {}", dest_dir));
-    }
+        let dest_path = dest_dir_path.join(file_name);
 
-    let file_name = source_path.file_name().ok_or_else(|| {
-        error!("无法获取文件名: {:?}", source_path);
-        "无法获取文件名".to_string()
-    })?;
+        // 如果目标已存在，自动添加后缀
+        let final_dest = if dest_path.exists() {
+            generate_unique_path(&dest_path)
+        } else {
+            dest_path
+        };
+
+        // 尝试直接 rename（同一文件系统）
+        match std::fs::rename(source_path, &final_dest) {
+            Ok(()) => {
+                let result = final_dest.to_string_lossy().to_string();
+                debug!("移动成功（rename）: {:?}", final_dest);
+                Ok(result)
+            }
+            Err(_) => {
+                // 跨文件系统移动：先复制再删除
+                if source_path.is_dir() {
+                    copy_dir_recursive(source_path, &final_dest)?;
+                    std::fs::remove_dir_all(source_path).map_err(|e| {
+                        error!("删除源目录失败: {:?}, 错误: {}", source_path, e);
+                        AxonError::io("移动成功但删除源目录失败", &e)
+                    })?;
+                } else {
+                    std::fs::copy(source_path, &final_dest).map_err(|e| {
+                        error!("复制文件失败: {:?}, 错误: {}", source_path, e);
+                        AxonError::localized_io("fs.copy_failed", &e)
+                    })?;
+                    std::fs::remove_file(source_path).map_err(|e| {
+                        error!("删除源文件失败: {:?}, 错误: {}", source_path, e);
+                        AxonError::io("移动成功但删除源文件失败", &e)
+                    })?;
+                }
+                let result = final_dest.to_string_lossy().to_string();
+                debug!("移动成功（copy+delete）: {:?}", final_dest);
+                Ok(result)
+            }
+        }
+    })
+    .await
+}
 
-    let dest_path = dest_dir_path.join(file_name);
+/// 批量复制文件或目录（后台任务）
+///
+/// 复制过程中发送 `fs:batch-progress` 事件，可通过 `cancel_job(job_id)` 取消，
+/// 返回每个源路径的处理结果
+#[tauri::command]
+pub async fn copy_paths_batch(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+    sources: Vec<String>,
+    dest_dir: String,
+) -> Result<Vec<BatchItemResult>, AxonError> {
+    let audit_args = json!({ "jobId": &job_id, "sources": &sources, "destDir": &dest_dir });
+    state.audit.track("copy_paths_batch", audit_args, async {
+        debug!("批量复制 {} 项 -> {}, 任务: {}", sources.len(), dest_dir, job_id);
+        let dest_dir_path = check_path(&state, &dest_dir)?;
+        let sources = check_paths(&state, sources)?;
+        if !dest_dir_path.is_dir() {
+            error!("目标必须是目录: {:?}", dest_dir_path);
+            return Err(AxonError::localized(
+                ErrorKind::InvalidInput,
+                "fs.target_not_dir",
+                json!({ "path": dest_dir }),
+            ));
+        }
 
-    // 如果目标已存在，自动添加后缀
-    let final_dest = if dest_path.exists() {
-        generate_unique_path(&dest_path)
+        let job = state.jobs.register(&job_id)?;
+        let jobs = Arc::clone(&state.jobs);
+
+        let result = tokio::task::spawn_blocking(move || {
+            run_copy_batch(&app, &job, &sources, &dest_dir_path)
+        })
+        .await;
+
+        jobs.finish(&job_id);
+        let items = result.map_err(|e| AxonError::internal(format!("批量复制任务失败: {}", e)))?;
+        notify_batch_finished(&state, "批量复制", &items);
+        Ok(items)
+    })
+    .await
+}
+
+/// 批量删除文件或目录（后台任务）
+///
+/// 删除过程中发送 `fs:batch-progress` 事件，可通过 `cancel_job(job_id)` 取消，
+/// 返回每个路径的处理结果
+#[tauri::command]
+pub async fn delete_paths_batch(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+    paths: Vec<String>,
+) -> Result<Vec<BatchItemResult>, AxonError> {
+    let audit_args = json!({ "jobId": &job_id, "paths": &paths });
+    state.audit.track("delete_paths_batch", audit_args, async {
+        debug!("批量删除 {} 项, 任务: {}", paths.len(), job_id);
+        let paths = check_paths(&state, paths)?;
+
+        let job = state.jobs.register(&job_id)?;
+        let jobs = Arc::clone(&state.jobs);
+
+        let result = tokio::task::spawn_blocking(move || run_delete_batch(&app, &job, &paths)).await;
+
+        jobs.finish(&job_id);
+        let items = result.map_err(|e| AxonError::internal(format!("批量删除任务失败: {}", e)))?;
+        notify_batch_finished(&state, "批量删除", &items);
+        Ok(items)
+    })
+    .await
+}
+
+/// 批量操作结束后发送系统通知（取消的任务不通知）
+fn notify_batch_finished(state: &AppState, action: &str, items: &[BatchItemResult]) {
+    if items.iter().any(|item| item.cancelled) {
+        return;
+    }
+    let failed = items.iter().filter(|item| !item.success).count();
+    let body = if failed == 0 {
+        format!("{} 项已全部完成", items.len())
     } else {
-        dest_path
+        format!("{} 项中有 {} 项失败", items.len(), failed)
     };
+    state
+        .notifications
+        .notify_task_finished(&format!("{}完成", action), &body, failed == 0);
+}
 
-    if source_path.is_dir() {
-        copy_dir_recursive(source_path, &final_dest)?;
-    } else {
-        std::fs::copy(source_path, &final_dest).map_err(|e| {
-            error!("复制文件失败: {:?} -> {:?}, 错误: {}", source_path, final_dest, e);
-            format!("复制文件失败: {}", e)
-        })?;
+/// 批量操作进度跟踪器（节流发送进度事件）
+struct BatchProgressTracker<'a> {
+    app: &'a AppHandle,
+    job: &'a JobHandle,
+    progress: BatchProgress,
+    last_emit: Instant,
+}
+
+impl<'a> BatchProgressTracker<'a> {
+    fn new(app: &'a AppHandle, job: &'a JobHandle, operation: &str, totals: (u64, u64)) -> Self {
+        let tracker = Self {
+            app,
+            job,
+            progress: BatchProgress {
+                job_id: job.id().to_string(),
+                operation: operation.to_string(),
+                files_done: 0,
+                files_total: totals.0,
+                bytes_done: 0,
+                bytes_total: totals.1,
+                current_path: None,
+            },
+            last_emit: Instant::now(),
+        };
+        tracker.emit();
+        tracker
     }
 
-    let result = final_dest.to_string_lossy().to_string();
-    debug!("复制成功: {:?}", final_dest);
-    Ok(result)
+    /// 记录一个已处理的文件
+    fn advance(&mut self, path: &Path, bytes: u64) {
+        self.progress.files_done += 1;
+        self.progress.bytes_done += bytes;
+        self.progress.current_path = Some(path.to_string_lossy().to_string());
+
+        if self.last_emit.elapsed() >= PROGRESS_EMIT_INTERVAL {
+            self.emit();
+            self.last_emit = Instant::now();
+        }
+    }
+
+    fn emit(&self) {
+        if let Err(e) = self.app.emit(EVENT_FS_BATCH_PROGRESS, &self.progress) {
+            warn!("发送批量操作进度失败: {}", e);
+        }
+    }
+
+    /// 检查取消状态
+    fn check_cancelled(&self) -> Result<(), AxonError> {
+        if self.job.is_cancelled() {
+            Err(AxonError::cancelled("操作已取消"))
+        } else {
+            Ok(())
+        }
+    }
 }
 
-/// 移动文件或目录
-/// 返回新路径
-#[tauri::command]
-pub async fn move_path(source: String, dest_dir: String) -> Result<String, String> {
-    debug!("移动: {} -> {}", source, dest_dir);
+/// 统计路径下的文件数和总字节数（不跟随符号链接）
+fn scan_totals(path: &Path) -> (u64, u64) {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return (0, 0);
+    };
 
-    let source_path = Path::new(&source);
-    let dest_dir_path = Path::new(&dest_dir);
+    if !metadata.is_dir() {
+        return (1, metadata.len());
+    }
 
-    if !source_path.exists() {
-        error!("源路径不存在: {:?}", source_path);
-        return Err(format!("源路径不存在: {}", source));
+    let mut files = 0;
+    let mut bytes = 0;
+    if let Ok(entries) = std::fs::read_dir(path) {
+        for entry in entries.flatten() {
+            let (f, b) = scan_totals(&entry.path());
+            files += f;
+            bytes += b;
+        }
     }
+    (files, bytes)
+}
 
-    if !dest_dir_path.is_dir() {
-        error!("目标必须是目录: {:?}", dest_dir_path);
-        return Err(format!("目标必须是目录: {}", dest_dir));
+fn run_copy_batch(
+    app: &AppHandle,
+    job: &JobHandle,
+    sources: &[(String, PathBuf)],
+    dest_dir: &Path,
+) -> Vec<BatchItemResult> {
+    let totals = sources
+        .iter()
+        .map(|(_, source_path)| scan_totals(source_path))
+        .fold((0, 0), |acc, t| (acc.0 + t.0, acc.1 + t.1));
+    let mut tracker = BatchProgressTracker::new(app, job, "copy", totals);
+    let mut results = Vec::with_capacity(sources.len());
+
+    for (source, source_path) in sources {
+        let outcome = copy_one_with_progress(source_path, dest_dir, &mut tracker);
+        results.push(to_batch_item(source, outcome, job));
     }
 
-    let file_name = source_path.file_name().ok_or_else(|| {
-        error!("无法获取文件名: {:?}", source_path);
-        "无法获取文件名".to_string()
-    })?;
+    tracker.emit();
+    results
+}
 
-    let dest_path = dest_dir_path.join(file_name);
+/// 复制单个源路径到目标目录，返回新路径
+fn copy_one_with_progress(
+    source_path: &Path,
+    dest_dir: &Path,
+    tracker: &mut BatchProgressTracker,
+) -> Result<String, AxonError> {
+    tracker.check_cancelled()?;
 
-    // 如果目标已存在，自动添加后缀
+    if !source_path.exists() {
+        return Err(AxonError::localized(
+            ErrorKind::NotFound,
+            "fs.source_not_found",
+            json!({ "path": source_path.display().to_string() }),
+        ));
+    }
+
+    // 防止把目录复制到其自身内部导致无限递归
+    if source_path.is_dir() && dest_dir.starts_with(source_path) {
+        return Err(AxonError::invalid_input("不能将目录复制到其自身内部"));
+    }
+
+    let file_name = source_path
+        .file_name()
+        .ok_or_else(|| AxonError::invalid_input("无法获取文件名"))?;
+    let dest_path = dest_dir.join(file_name);
     let final_dest = if dest_path.exists() {
         generate_unique_path(&dest_path)
     } else {
         dest_path
     };
 
-    // 尝试直接 rename（同一文件系统）
-    match std::fs::rename(source_path, &final_dest) {
-        Ok(()) => {
-            let result = final_dest.to_string_lossy().to_string();
-            debug!("移动成功（rename）: {:?}", final_dest);
-            Ok(result)
-        }
-        Err(_) => {
-            // 跨文件系统移动：先复制再删除
-            if source_path.is_dir() {
-                copy_dir_recursive(source_path, &final_dest)?;
-                std::fs::remove_dir_all(source_path).map_err(|e| {
-                    error!("删除源目录失败: {:?}, 错误: {}", source_path, e);
-                    format!("移动成功但删除源目录失败: {}", e)
-                })?;
-            } else {
-                std::fs::copy(source_path, &final_dest).map_err(|e| {
-                    error!("复制文件失败: {:?}, 错误: {}", source_path, e);
-                    format!("复制文件失败: {}", e)
-                })?;
-                std::fs::remove_file(source_path).map_err(|e| {
-                    error!("删除源文件失败: {:?}, 错误: {}", source_path, e);
-                    format!("移动成功但删除源文件失败: {}", e)
-                })?;
-            }
-            let result = final_dest.to_string_lossy().to_string();
-            debug!("移动成功（copy+delete）: {:?}", final_dest);
-            Ok(result)
+    copy_recursive_with_progress(source_path, &final_dest, tracker)?;
+    Ok(final_dest.to_string_lossy().to_string())
+}
+
+fn copy_recursive_with_progress(
+    src: &Path,
+    dst: &Path,
+    tracker: &mut BatchProgressTracker,
+) -> Result<(), AxonError> {
+    tracker.check_cancelled()?;
+
+    if src.is_dir() {
+        std::fs::create_dir_all(dst).map_err(|e| {
+            error!("创建目录失败: {:?}, 错误: {}", dst, e);
+            AxonError::localized_io("fs.create_dir_failed", &e)
+        })?;
+
+        for entry in
+            std::fs::read_dir(src).map_err(|e| AxonError::localized_io("fs.read_dir_failed", &e))?
+        {
+            let entry = entry.map_err(|e| format!("读取条目失败: {}", e))?;
+            copy_recursive_with_progress(&entry.path(), &dst.join(entry.file_name()), tracker)?;
         }
+    } else {
+        let bytes = std::fs::copy(src, dst).map_err(|e| {
+            error!("复制文件失败: {:?} -> {:?}, 错误: {}", src, dst, e);
+            AxonError::localized_io("fs.copy_failed", &e)
+        })?;
+        tracker.advance(src, bytes);
+    }
+
+    Ok(())
+}
+
+fn run_delete_batch(
+    app: &AppHandle,
+    job: &JobHandle,
+    paths: &[(String, PathBuf)],
+) -> Vec<BatchItemResult> {
+    let totals = paths
+        .iter()
+        .map(|(_, target)| scan_totals(target))
+        .fold((0, 0), |acc, t| (acc.0 + t.0, acc.1 + t.1));
+    let mut tracker = BatchProgressTracker::new(app, job, "delete", totals);
+    let mut results = Vec::with_capacity(paths.len());
+
+    for (path, target) in paths {
+        let outcome = if target.exists() || target.is_symlink() {
+            delete_recursive_with_progress(target, &mut tracker).map(|_| path.clone())
+        } else {
+            Err(AxonError::localized(
+                ErrorKind::NotFound,
+                "fs.path_not_found",
+                json!({ "path": path }),
+            ))
+        };
+        results.push(to_batch_item(path, outcome, job));
+    }
+
+    tracker.emit();
+    results
+}
+
+fn delete_recursive_with_progress(
+    path: &Path,
+    tracker: &mut BatchProgressTracker,
+) -> Result<(), AxonError> {
+    tracker.check_cancelled()?;
+
+    let metadata = std::fs::symlink_metadata(path).map_err(|e| AxonError::io("读取元数据失败", &e))?;
+
+    if metadata.is_dir() {
+        for entry in
+            std::fs::read_dir(path).map_err(|e| AxonError::localized_io("fs.read_dir_failed", &e))?
+        {
+            let entry = entry.map_err(|e| format!("读取条目失败: {}", e))?;
+            delete_recursive_with_progress(&entry.path(), tracker)?;
+        }
+        std::fs::remove_dir(path).map_err(|e| {
+            error!("删除目录失败: {:?}, 错误: {}", path, e);
+            AxonError::localized_io("fs.delete_dir_failed", &e)
+        })?;
+    } else {
+        std::fs::remove_file(path).map_err(|e| {
+            error!("删除文件失败: {:?}, 错误: {}", path, e);
+            AxonError::localized_io("fs.delete_file_failed", &e)
+        })?;
+        tracker.advance(path, metadata.len());
+    }
+
+    Ok(())
+}
+
+/// 将单项操作结果转换为 BatchItemResult
+fn to_batch_item(source: &str, outcome: Result<String, AxonError>, job: &JobHandle) -> BatchItemResult {
+    match outcome {
+        Ok(result_path) => BatchItemResult {
+            source: source.to_string(),
+            success: true,
+            result_path: Some(result_path),
+            error: None,
+            cancelled: false,
+        },
+        Err(e) => BatchItemResult {
+            source: source.to_string(),
+            success: false,
+            result_path: None,
+            error: Some(e.message),
+            cancelled: job.is_cancelled(),
+        },
     }
 }
 
+/// 检查路径是否在沙箱允许访问的范围内，返回解析后的路径
+fn check_path(state: &AppState, path: impl AsRef<Path>) -> Result<PathBuf, AxonError> {
+    PathSandbox::from_settings(&state.settings).check(path)
+}
+
+/// 批量检查路径，返回（原始路径, 解析后的路径），结果仍按原始路径报告
+fn check_paths(
+    state: &AppState,
+    paths: Vec<String>,
+) -> Result<Vec<(String, PathBuf)>, AxonError> {
+    let sandbox = PathSandbox::from_settings(&state.settings);
+    paths
+        .into_iter()
+        .map(|path| {
+            let checked = sandbox.check(&path)?;
+            Ok((path, checked))
+        })
+        .collect()
+}
+
 /// 生成唯一路径（当目标已存在时）
 fn generate_unique_path(path: &Path) -> std::path::PathBuf {
     let parent = path.parent().unwrap_or(Path::new(""));
@@ -420,13 +1050,15 @@ fn generate_unique_path(path: &Path) -> std::path::PathBuf {
 }
 
 /// 递归复制目录
-fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
+fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), AxonError> {
     std::fs::create_dir_all(dst).map_err(|e| {
         error!("创建目录失败: {:?}, 错误: {}", dst, e);
-        format!("创建目录失败: {}", e)
+        AxonError::localized_io("fs.create_dir_failed", &e)
     })?;
 
-    for entry in std::fs::read_dir(src).map_err(|e| format!("读取目录失败: {}", e))? {
+    for entry in
+        std::fs::read_dir(src).map_err(|e| AxonError::localized_io("fs.read_dir_failed", &e))?
+    {
         let entry = entry.map_err(|e| format!("读取条目失败: {}", e))?;
         let src_path = entry.path();
         let dst_path = dst.join(entry.file_name());
@@ -436,7 +1068,7 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
         } else {
             std::fs::copy(&src_path, &dst_path).map_err(|e| {
                 error!("复制文件失败: {:?} -> {:?}, 错误: {}", src_path, dst_path, e);
-                format!("复制文件失败: {}", e)
+                AxonError::localized_io("fs.copy_failed", &e)
             })?;
         }
     }
@@ -447,19 +1079,27 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
 /// 读取文件内容为 Base64
 /// 用于读取图片等二进制文件
 #[tauri::command]
-pub async fn read_file_binary(path: String) -> Result<String, String> {
+pub async fn read_file_binary(state: State<'_, AppState>, path: String) -> Result<String, AxonError> {
     debug!("读取二进制文件: {}", path);
-
-    let file_path = Path::new(&path);
+    let checked = check_path(&state, &path)?;
+    let file_path = checked.as_path();
 
     if !file_path.exists() {
         error!("文件不存在: {:?}", file_path);
-        return Err(format!("文件不存在: {}", path));
+        return Err(AxonError::localized(
+            ErrorKind::NotFound,
+            "fs.file_not_found",
+            json!({ "path": path }),
+        ));
     }
 
     if !file_path.is_file() {
         error!("路径不是文件: {:?}", file_path);
-        return Err(format!("路径不是文件: {}", path));
+        return Err(AxonError::localized(
+            ErrorKind::InvalidInput,
+            "fs.not_a_file",
+            json!({ "path": path }),
+        ));
     }
 
     // 读取文件为字节
@@ -472,15 +1112,175 @@ pub async fn read_file_binary(path: String) -> Result<String, String> {
         }
         Err(e) => {
             error!("读取文件失败: {:?}, 错误: {}", file_path, e);
-            Err(format!("读取文件失败: {}", e))
+            Err(AxonError::localized_io("fs.read_file_failed", &e))
         }
     }
 }
 
+/// 获取文件/目录的完整元数据
+/// `with_hash` 为 true 时在阻塞线程中计算文件的 SHA256 摘要
+#[tauri::command]
+pub async fn stat_path(
+    state: State<'_, AppState>,
+    path: String,
+    with_hash: Option<bool>,
+) -> Result<FileMetadata, AxonError> {
+    debug!("获取路径元数据: {}, 计算摘要: {:?}", path, with_hash);
+    let checked = check_path(&state, &path)?;
+    let target_path = checked.as_path();
+
+    // 使用 symlink_metadata 以便识别符号链接本身
+    let link_metadata = std::fs::symlink_metadata(target_path).map_err(|e| {
+        error!("读取元数据失败: {:?}, 错误: {}", target_path, e);
+        AxonError::io("读取元数据失败", &e)
+    })?;
+
+    let is_symlink = link_metadata.file_type().is_symlink();
+    let symlink_target = if is_symlink {
+        std::fs::read_link(target_path)
+            .ok()
+            .map(|p| p.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    // 符号链接跟随到目标读取实际元数据，目标失效时退回链接自身的元数据
+    let metadata = if is_symlink {
+        std::fs::metadata(target_path).unwrap_or_else(|_| link_metadata.clone())
+    } else {
+        link_metadata
+    };
+
+    let is_directory = metadata.is_dir();
+    let is_file = metadata.is_file();
+
+    #[cfg(unix)]
+    let permissions = {
+        use std::os::unix::fs::PermissionsExt;
+        Some(format!("{:o}", metadata.permissions().mode() & 0o777))
+    };
+    #[cfg(not(unix))]
+    let permissions = None;
+
+    let sha256 = if with_hash.unwrap_or(false) && is_file {
+        let hash_path = target_path.to_path_buf();
+        let digest = tokio::task::spawn_blocking(move || compute_file_sha256(&hash_path))
+            .await
+            .map_err(|e| AxonError::internal(format!("计算摘要任务失败: {}", e)))??;
+        Some(digest)
+    } else {
+        None
+    };
+
+    let name = target_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.clone());
+
+    Ok(FileMetadata {
+        path: target_path.to_string_lossy().to_string(),
+        mime_type: if is_directory { None } else { guess_mime_type(target_path) },
+        name,
+        is_directory,
+        is_file,
+        is_symlink,
+        symlink_target,
+        size: metadata.len(),
+        created_at: metadata.created().ok().and_then(system_time_to_millis),
+        modified_at: metadata.modified().ok().and_then(system_time_to_millis),
+        accessed_at: metadata.accessed().ok().and_then(system_time_to_millis),
+        readonly: metadata.permissions().readonly(),
+        permissions,
+        sha256,
+    })
+}
+
+/// 将 SystemTime 转换为 Unix 时间戳毫秒
+fn system_time_to_millis(time: std::time::SystemTime) -> Option<u64> {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_millis() as u64)
+}
+
+/// 流式计算文件 SHA256（避免一次性读入大文件）
+fn compute_file_sha256(path: &Path) -> Result<String, AxonError> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path).map_err(|e| {
+        error!("打开文件失败: {:?}, 错误: {}", path, e);
+        AxonError::io("打开文件失败", &e)
+    })?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("读取文件失败: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 根据扩展名推测 MIME 类型
+fn guess_mime_type(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    let mime = match ext.as_str() {
+        "txt" | "log" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "xml" => "application/xml",
+        "json" => "application/json",
+        "yaml" | "yml" => "application/yaml",
+        "toml" => "application/toml",
+        "js" | "mjs" | "cjs" => "text/javascript",
+        "ts" | "tsx" | "mts" | "cts" => "text/typescript",
+        "jsx" => "text/jsx",
+        "rs" => "text/x-rust",
+        "py" => "text/x-python",
+        "go" => "text/x-go",
+        "java" => "text/x-java",
+        "c" | "h" => "text/x-c",
+        "cpp" | "cc" | "hpp" => "text/x-c++",
+        "sh" | "bash" => "application/x-sh",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" | "tgz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "wasm" => "application/wasm",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        _ => return None,
+    };
+    Some(mime.to_string())
+}
+
 /// 打开目录选择对话框
 /// 返回用户选择的目录路径，如果用户取消则返回 None
 #[tauri::command]
-pub async fn select_directory(app: AppHandle) -> Result<Option<String>, String> {
+pub async fn select_directory(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, AxonError> {
     use tauri::Manager;
     use tauri_plugin_dialog::DialogExt;
     
@@ -506,6 +1306,10 @@ pub async fn select_directory(app: AppHandle) -> Result<Option<String>, String>
         Some(path) => {
             let path_str = path.to_string();
             debug!("用户选择目录: {}", path_str);
+            // 用户通过系统对话框选择的目录视为显式授权
+            if let Err(e) = super::sandbox::grant(&state, &path_str) {
+                warn!("授权所选目录失败: {}", e);
+            }
             Ok(Some(path_str))
         }
         None => {
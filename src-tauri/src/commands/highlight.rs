@@ -0,0 +1,163 @@
+//! 语法高亮命令
+//!
+//! `highlight_code` 一次返回全部结果，适合代码片段和 Diff 中的文本；
+//! `highlight_file` 用于大文件预览：在后台任务中逐块高亮，每块通过
+//! `highlight:chunk` 事件发送，可通过 `cancel_job(job_id)` 取消。
+
+use crate::error::AxonError;
+use crate::highlight::{self, HighlightFormat, HighlightedCode, HighlightedLine, LineHighlighter};
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use crate::utils::text_encoding;
+use serde::Serialize;
+use std::sync::Arc;
+use syntect::util::LinesWithEndings;
+use tauri::{AppHandle, Emitter, State};
+use tracing::{debug, warn};
+
+/// 分块高亮事件
+pub const EVENT_HIGHLIGHT_CHUNK: &str = "highlight:chunk";
+
+/// 每块的行数
+const CHUNK_LINES: usize = 500;
+
+/// `highlight_code` 接受的最大文本长度，更大的内容使用 `highlight_file`
+const MAX_CODE_BYTES: usize = 2 * 1024 * 1024;
+
+/// `highlight_file` 接受的最大文件大小
+const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightChunk {
+    pub job_id: String,
+    /// 本块第一行的行号（从 0 开始）
+    pub start_line: usize,
+    pub lines: Vec<HighlightedLine>,
+}
+
+/// 分块高亮结果（不含行内容，行内容通过事件发送）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightedFile {
+    pub language: String,
+    pub theme: String,
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+    pub total_lines: usize,
+}
+
+/// 高亮代码片段
+///
+/// `language` 可以是语言名称或扩展名（如 `rust` / `rs`），为空时按首行识别
+#[tauri::command]
+pub async fn highlight_code(
+    text: String,
+    language: Option<String>,
+    theme: Option<String>,
+    format: Option<HighlightFormat>,
+) -> Result<HighlightedCode, AxonError> {
+    if text.len() > MAX_CODE_BYTES {
+        return Err(AxonError::invalid_input(
+            "代码过长，请使用 highlight_file 分块高亮",
+        ));
+    }
+    tokio::task::spawn_blocking(move || {
+        highlight::highlight(
+            &text,
+            language.as_deref(),
+            theme.as_deref(),
+            format.unwrap_or_default(),
+        )
+    })
+    .await
+    .map_err(|e| AxonError::internal(format!("语法高亮任务失败: {}", e)))?
+}
+
+/// 分块高亮文件（后台任务）
+///
+/// `language` 为空时按扩展名和首行识别
+#[tauri::command]
+pub async fn highlight_file(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+    path: String,
+    language: Option<String>,
+    theme: Option<String>,
+    format: Option<HighlightFormat>,
+) -> Result<HighlightedFile, AxonError> {
+    debug!("分块高亮文件: {}, 任务: {}", path, job_id);
+    let file_path = PathSandbox::from_settings(&state.settings).check(&path)?;
+    let metadata = std::fs::metadata(&file_path).map_err(|e| AxonError::io("读取文件失败", &e))?;
+    if !metadata.is_file() {
+        return Err(AxonError::invalid_input(format!("路径不是文件: {}", path)));
+    }
+    if metadata.len() > MAX_FILE_BYTES {
+        return Err(AxonError::invalid_input(format!(
+            "文件过大（超过 {} MB）",
+            MAX_FILE_BYTES / 1024 / 1024
+        )));
+    }
+
+    let job = state.jobs.register(&job_id)?;
+    let jobs = Arc::clone(&state.jobs);
+    let result = tokio::task::spawn_blocking(move || {
+        let bytes = std::fs::read(&file_path).map_err(|e| AxonError::io("读取文件失败", &e))?;
+        let text = text_encoding::decode(&bytes, &text_encoding::detect_format(&bytes));
+        let file_name = file_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+        let first_line = text.lines().next().unwrap_or_default();
+        let syntax = highlight::find_syntax(language.as_deref(), file_name.as_deref(), first_line);
+        let mut highlighter =
+            LineHighlighter::new(syntax, theme.as_deref(), format.unwrap_or_default())?;
+
+        let mut chunk = HighlightChunk {
+            job_id: job.id().to_string(),
+            start_line: 0,
+            lines: Vec::with_capacity(CHUNK_LINES),
+        };
+        let mut total_lines = 0;
+        for line in LinesWithEndings::from(&text) {
+            chunk.lines.push(highlighter.line(line)?);
+            total_lines += 1;
+            if chunk.lines.len() == CHUNK_LINES {
+                if job.is_cancelled() {
+                    return Err(AxonError::cancelled("操作已取消"));
+                }
+                emit_chunk(&app, &chunk);
+                chunk.start_line = total_lines;
+                chunk.lines.clear();
+            }
+        }
+        if !chunk.lines.is_empty() {
+            emit_chunk(&app, &chunk);
+        }
+
+        let summary = highlighter.finish(Vec::new());
+        Ok(HighlightedFile {
+            language: summary.language,
+            theme: summary.theme,
+            background: summary.background,
+            foreground: summary.foreground,
+            total_lines,
+        })
+    })
+    .await;
+
+    jobs.finish(&job_id);
+    result.map_err(|e| AxonError::internal(format!("语法高亮任务失败: {}", e)))?
+}
+
+/// 列出可用的高亮主题
+#[tauri::command]
+pub fn list_highlight_themes() -> Vec<String> {
+    highlight::theme_names()
+}
+
+fn emit_chunk(app: &AppHandle, chunk: &HighlightChunk) {
+    if let Err(e) = app.emit(EVENT_HIGHLIGHT_CHUNK, chunk) {
+        warn!("发送高亮结果失败: {}", e);
+    }
+}
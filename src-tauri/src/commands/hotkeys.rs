@@ -0,0 +1,50 @@
+//! 全局快捷键命令
+
+use crate::error::AxonError;
+use crate::hotkeys;
+use crate::opencode::HotkeySettings;
+use crate::state::AppState;
+use serde_json::json;
+use tauri::{AppHandle, State};
+
+/// 获取全局快捷键设置
+#[tauri::command]
+pub fn get_hotkeys(state: State<'_, AppState>) -> HotkeySettings {
+    state.settings.get_hotkeys()
+}
+
+/// 设置全局快捷键，注册成功后才保存
+#[tauri::command]
+pub fn set_hotkeys(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    hotkeys: HotkeySettings,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "hotkeys": &hotkeys });
+    state.audit.track_sync("set_hotkeys", audit_args, || {
+        state.hotkeys.apply(&app, &hotkeys)?;
+        state.settings.set_hotkeys(hotkeys).map_err(AxonError::from)
+    })
+}
+
+/// 打开快速提问窗口
+#[tauri::command]
+pub fn show_quick_prompt(app: AppHandle) -> Result<(), AxonError> {
+    hotkeys::show_quick_prompt(&app)
+}
+
+/// 关闭快速提问窗口
+#[tauri::command]
+pub fn hide_quick_prompt(app: AppHandle) {
+    hotkeys::hide_quick_prompt(&app);
+}
+
+/// 提交快速提问，发送到主窗口的当前会话
+#[tauri::command]
+pub fn submit_quick_prompt(app: AppHandle, text: String) -> Result<(), AxonError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(AxonError::invalid_input("提问内容不能为空"));
+    }
+    hotkeys::submit_quick_prompt(&app, text.to_string())
+}
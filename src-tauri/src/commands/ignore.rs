@@ -0,0 +1,124 @@
+//! 项目忽略规则命令
+//!
+//! 读写项目根目录的 `.gitignore` / `.axonignore`，规则由搜索替换、文件索引和
+//! 项目上下文共用（见 [`crate::utils::ignore`]）。文件索引通过文件监听自动按新规则重建。
+
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::utils::ignore::{
+    invalid_patterns, IgnoreMatcher, AXONIGNORE_FILE, GITIGNORE_FILE, IGNORED_DIRS,
+};
+use crate::utils::path_sandbox::PathSandbox;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IgnoreFile {
+    Gitignore,
+    Axonignore,
+}
+
+impl IgnoreFile {
+    fn file_name(self) -> &'static str {
+        match self {
+            IgnoreFile::Gitignore => GITIGNORE_FILE,
+            IgnoreFile::Axonignore => AXONIGNORE_FILE,
+        }
+    }
+}
+
+/// 项目的忽略规则
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IgnoreRules {
+    pub project_dir: String,
+    /// 始终跳过的内置目录
+    pub builtin: Vec<String>,
+    /// `.gitignore` 内容，文件不存在时为空
+    pub gitignore: Option<String>,
+    /// `.axonignore` 内容，文件不存在时为空
+    pub axonignore: Option<String>,
+}
+
+fn resolve_project(state: &AppState, project_dir: Option<String>) -> Result<PathBuf, AxonError> {
+    let project_dir = project_dir
+        .or_else(|| state.settings.get_project_directory())
+        .ok_or_else(|| AxonError::invalid_input("未指定项目目录"))?;
+    let path = PathBuf::from(&project_dir);
+    if !path.is_dir() {
+        return Err(AxonError::not_found(format!(
+            "项目目录不存在: {}",
+            project_dir
+        )));
+    }
+    Ok(path)
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>, AxonError> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(AxonError::io("读取忽略文件失败", &e)),
+    }
+}
+
+/// 读取项目的忽略规则
+#[tauri::command]
+pub fn get_ignore_rules(
+    state: State<'_, AppState>,
+    project_dir: Option<String>,
+) -> Result<IgnoreRules, AxonError> {
+    let root = resolve_project(&state, project_dir)?;
+    Ok(IgnoreRules {
+        project_dir: root.to_string_lossy().to_string(),
+        builtin: IGNORED_DIRS.iter().map(|dir| dir.to_string()).collect(),
+        gitignore: read_optional(&root.join(GITIGNORE_FILE))?,
+        axonignore: read_optional(&root.join(AXONIGNORE_FILE))?,
+    })
+}
+
+/// 保存忽略文件（覆盖原内容）
+#[tauri::command]
+pub fn set_ignore_rules(
+    state: State<'_, AppState>,
+    file: IgnoreFile,
+    content: String,
+    project_dir: Option<String>,
+) -> Result<(), AxonError> {
+    let root = resolve_project(&state, project_dir)?;
+    let path = PathSandbox::from_settings(&state.settings).check(root.join(file.file_name()))?;
+
+    let invalid = invalid_patterns(&content);
+    if !invalid.is_empty() {
+        return Err(AxonError::invalid_input(format!(
+            "忽略规则无效: {}",
+            invalid.join("; ")
+        )));
+    }
+
+    let audit_args = json!({ "path": path.to_string_lossy(), "bytes": content.len() });
+    state.audit.track_sync("set_ignore_rules", audit_args, || {
+        std::fs::write(&path, &content).map_err(|e| AxonError::io("写入忽略文件失败", &e))
+    })
+}
+
+/// 检查路径（相对项目目录）是否被忽略，返回被忽略的路径
+#[tauri::command]
+pub fn check_ignored_paths(
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+    project_dir: Option<String>,
+) -> Result<Vec<String>, AxonError> {
+    let root = resolve_project(&state, project_dir)?;
+    let matcher = IgnoreMatcher::for_project(&root);
+    Ok(paths
+        .into_iter()
+        .filter(|relative| {
+            let normalized = relative.replace('\\', "/");
+            matcher.is_ignored(&normalized, root.join(&normalized).is_dir())
+        })
+        .collect())
+}
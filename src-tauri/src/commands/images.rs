@@ -0,0 +1,188 @@
+//! 图片相关命令
+//!
+//! 为资源查看器提供：
+//! - 读取图片尺寸与格式（仅解析文件头，不解码像素）
+//! - 生成缩略图，避免将大图完整传入 webview
+
+use crate::error::{AxonError, ErrorKind};
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use base64::Engine;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use serde::Serialize;
+use std::io::Cursor;
+use std::path::Path;
+use tauri::State;
+use tracing::{debug, error};
+
+/// 默认缩略图最长边
+const DEFAULT_THUMBNAIL_EDGE: u32 = 256;
+
+/// 缩略图最长边上限
+const MAX_THUMBNAIL_EDGE: u32 = 2048;
+
+/// 图片基础信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageInfo {
+    /// 宽度（像素）
+    pub width: u32,
+    /// 高度（像素）
+    pub height: u32,
+    /// 图片格式（png / jpeg / gif ...）
+    pub format: String,
+    /// MIME 类型
+    pub mime_type: String,
+    /// 文件大小（字节）
+    pub file_size: u64,
+}
+
+/// 缩略图
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageThumbnail {
+    /// base64 编码的 PNG 数据
+    pub data: String,
+    /// MIME 类型
+    pub mime_type: String,
+    /// 缩略图宽度
+    pub width: u32,
+    /// 缩略图高度
+    pub height: u32,
+    /// 原图宽度
+    pub original_width: u32,
+    /// 原图高度
+    pub original_height: u32,
+}
+
+/// 获取图片尺寸与格式
+#[tauri::command]
+pub async fn get_image_info(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<ImageInfo, AxonError> {
+    debug!("读取图片信息: {}", path);
+    let path = PathSandbox::from_settings(&state.settings).check(&path)?;
+
+    tokio::task::spawn_blocking(move || read_image_info(&path))
+        .await
+        .map_err(|e| AxonError::internal(format!("读取图片信息任务失败: {}", e)))?
+}
+
+/// 生成图片缩略图
+///
+/// 按比例缩放至最长边不超过 `max_edge`（默认 256），小图不放大
+#[tauri::command]
+pub async fn generate_thumbnail(
+    state: State<'_, AppState>,
+    path: String,
+    max_edge: Option<u32>,
+) -> Result<ImageThumbnail, AxonError> {
+    let max_edge = max_edge
+        .unwrap_or(DEFAULT_THUMBNAIL_EDGE)
+        .clamp(1, MAX_THUMBNAIL_EDGE);
+    debug!("生成缩略图: {}, 最长边: {}", path, max_edge);
+    let path = PathSandbox::from_settings(&state.settings).check(&path)?;
+
+    tokio::task::spawn_blocking(move || create_thumbnail(&path, max_edge))
+        .await
+        .map_err(|e| AxonError::internal(format!("生成缩略图任务失败: {}", e)))?
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+/// 打开图片并根据文件内容识别格式
+fn open_image(path: &Path) -> Result<ImageReader<std::io::BufReader<std::fs::File>>, AxonError> {
+    if !path.is_file() {
+        error!("图片文件不存在: {:?}", path);
+        return Err(AxonError::localized(
+            ErrorKind::NotFound,
+            "fs.file_not_found",
+            serde_json::json!({ "path": path.display().to_string() }),
+        ));
+    }
+
+    ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| {
+            error!("打开图片失败: {:?}, 错误: {}", path, e);
+            AxonError::io("打开图片失败", &e)
+        })
+}
+
+fn format_name(format: ImageFormat) -> String {
+    format
+        .extensions_str()
+        .first()
+        .map(|ext| ext.to_string())
+        .unwrap_or_else(|| format!("{:?}", format).to_lowercase())
+}
+
+pub(super) fn read_image_info(path: &Path) -> Result<ImageInfo, AxonError> {
+    let file_size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let reader = open_image(path)?;
+    let format = reader
+        .format()
+        .ok_or_else(|| AxonError::unsupported(format!("不支持的图片格式: {}", path.display())))?;
+
+    let (width, height) = reader
+        .into_dimensions()
+        .map_err(|e| AxonError::invalid_data(format!("读取图片尺寸失败: {}", e)))?;
+
+    Ok(ImageInfo {
+        width,
+        height,
+        format: format_name(format),
+        mime_type: format.to_mime_type().to_string(),
+        file_size,
+    })
+}
+
+fn create_thumbnail(path: &Path, max_edge: u32) -> Result<ImageThumbnail, AxonError> {
+    let reader = open_image(path)?;
+    if reader.format().is_none() {
+        return Err(AxonError::unsupported(format!("不支持的图片格式: {}", path.display())));
+    }
+
+    let image = reader.decode().map_err(|e| {
+        error!("解码图片失败: {:?}, 错误: {}", path, e);
+        AxonError::invalid_data(format!("解码图片失败: {}", e))
+    })?;
+    encode_thumbnail(image, max_edge)
+}
+
+/// 将已解码的图片缩放并编码为 PNG 缩略图
+pub(super) fn encode_thumbnail(
+    image: DynamicImage,
+    max_edge: u32,
+) -> Result<ImageThumbnail, AxonError> {
+    let (original_width, original_height) = (image.width(), image.height());
+    let thumbnail = if original_width.max(original_height) > max_edge {
+        image.thumbnail(max_edge, max_edge)
+    } else {
+        image
+    };
+
+    let mut buffer = Vec::new();
+    thumbnail
+        .write_to(&mut Cursor::new(&mut buffer), ImageFormat::Png)
+        .map_err(|e| format!("编码缩略图失败: {}", e))?;
+
+    debug!(
+        "缩略图已生成: {}x{} -> {}x{}, {} 字节",
+        original_width,
+        original_height,
+        thumbnail.width(),
+        thumbnail.height(),
+        buffer.len()
+    );
+
+    Ok(ImageThumbnail {
+        data: base64::engine::general_purpose::STANDARD.encode(&buffer),
+        mime_type: "image/png".to_string(),
+        width: thumbnail.width(),
+        height: thumbnail.height(),
+        original_width,
+        original_height,
+    })
+}
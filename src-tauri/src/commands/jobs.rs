@@ -0,0 +1,19 @@
+//! 后台任务控制命令
+
+use crate::state::AppState;
+use tauri::State;
+use tracing::debug;
+
+/// 取消后台任务
+/// 返回任务是否存在
+#[tauri::command]
+pub fn cancel_job(state: State<'_, AppState>, job_id: String) -> bool {
+    debug!("取消后台任务: {}", job_id);
+    state.jobs.cancel(&job_id)
+}
+
+/// 列出运行中的后台任务 ID
+#[tauri::command]
+pub fn list_jobs(state: State<'_, AppState>) -> Vec<String> {
+    state.jobs.list()
+}
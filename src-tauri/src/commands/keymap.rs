@@ -0,0 +1,132 @@
+//! 快捷键映射命令
+
+use crate::error::AxonError;
+use crate::keymap::{self, KeymapEntry, KeymapScope, ACTION_QUICK_PROMPT, EVENT_KEYMAP_CHANGED};
+use crate::opencode::HotkeySettings;
+use crate::state::AppState;
+use serde_json::json;
+use tauri::{AppHandle, Emitter, State};
+use tracing::warn;
+
+/// 获取完整的快捷键映射（内置操作和已绑定的片段操作）
+#[tauri::command]
+pub fn get_keymap(state: State<'_, AppState>) -> Vec<KeymapEntry> {
+    keymap::resolve(&state.settings.get_keymap(), &state.settings.get_hotkeys())
+}
+
+/// 绑定操作的快捷键，`accelerator` 为空时解除绑定，与其它操作冲突时返回错误
+///
+/// 全局操作修改后立即重新注册，注册成功后才保存
+#[tauri::command]
+pub fn set_binding(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    action: String,
+    accelerator: Option<String>,
+) -> Result<Vec<KeymapEntry>, AxonError> {
+    let audit_args = json!({ "action": &action, "accelerator": &accelerator });
+    state.audit.track_sync("set_binding", audit_args, || {
+        let scope = keymap::action_scope(&action)
+            .ok_or_else(|| AxonError::invalid_input(format!("未知的操作: {}", action)))?;
+        let accelerator = accelerator
+            .as_deref()
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(|a| keymap::normalize_accelerator(a, scope))
+            .transpose()?;
+        if let Some(accelerator) = &accelerator {
+            let entries =
+                keymap::resolve(&state.settings.get_keymap(), &state.settings.get_hotkeys());
+            keymap::check_conflict(&entries, &action, accelerator)?;
+        }
+
+        match scope {
+            KeymapScope::Global => {
+                let mut hotkeys = state.settings.get_hotkeys();
+                if action == ACTION_QUICK_PROMPT {
+                    hotkeys.quick_prompt = accelerator;
+                } else {
+                    hotkeys.summon = accelerator;
+                }
+                apply_hotkeys(&app, &state, hotkeys)?;
+            }
+            KeymapScope::App => {
+                let mut overrides = state.settings.get_keymap();
+                if accelerator.as_deref() == keymap::default_accelerator(&action) {
+                    overrides.remove(&action);
+                } else {
+                    overrides.insert(action.clone(), accelerator);
+                }
+                state.settings.set_keymap(overrides)?;
+            }
+        }
+        Ok(keymap_changed(&app, &state))
+    })
+}
+
+/// 恢复默认快捷键，`action` 为空时恢复全部（同时清除片段操作的绑定）
+#[tauri::command]
+pub fn reset_keymap(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    action: Option<String>,
+) -> Result<Vec<KeymapEntry>, AxonError> {
+    let audit_args = json!({ "action": &action });
+    state.audit.track_sync("reset_keymap", audit_args, || {
+        let Some(action) = action else {
+            apply_hotkeys(&app, &state, HotkeySettings::default())?;
+            state.settings.set_keymap(Default::default())?;
+            return Ok(keymap_changed(&app, &state));
+        };
+
+        let scope = keymap::action_scope(&action)
+            .ok_or_else(|| AxonError::invalid_input(format!("未知的操作: {}", action)))?;
+        let entries = keymap::resolve(&state.settings.get_keymap(), &state.settings.get_hotkeys());
+        let default = entries
+            .iter()
+            .find(|e| e.action == action)
+            .and_then(|e| e.default_accelerator.clone())
+            .or_else(|| keymap::default_accelerator(&action).map(str::to_string));
+        if let Some(default) = &default {
+            keymap::check_conflict(&entries, &action, default)?;
+        }
+
+        match scope {
+            KeymapScope::Global => {
+                let mut hotkeys = state.settings.get_hotkeys();
+                if action == ACTION_QUICK_PROMPT {
+                    hotkeys.quick_prompt = default;
+                } else {
+                    hotkeys.summon = default;
+                }
+                apply_hotkeys(&app, &state, hotkeys)?;
+            }
+            KeymapScope::App => {
+                let mut overrides = state.settings.get_keymap();
+                overrides.remove(&action);
+                state.settings.set_keymap(overrides)?;
+            }
+        }
+        Ok(keymap_changed(&app, &state))
+    })
+}
+
+/// 重新注册全局快捷键，成功后保存
+fn apply_hotkeys(
+    app: &AppHandle,
+    state: &AppState,
+    hotkeys: HotkeySettings,
+) -> Result<(), AxonError> {
+    state.hotkeys.apply(app, &hotkeys)?;
+    state.settings.set_hotkeys(hotkeys).map_err(AxonError::from)
+}
+
+/// 通知所有窗口映射已变化，返回新的映射
+fn keymap_changed(app: &AppHandle, state: &AppState) -> Vec<KeymapEntry> {
+    let entries = keymap::resolve(&state.settings.get_keymap(), &state.settings.get_hotkeys());
+    state.app_menu.refresh(app);
+    if let Err(e) = app.emit(EVENT_KEYMAP_CHANGED, &entries) {
+        warn!("发送快捷键映射变化事件失败: {}", e);
+    }
+    entries
+}
@@ -2,18 +2,26 @@
 //!
 //! 提供基于项目目录的布局持久化功能：
 //! - 每个项目独立存储布局配置
-//! - 包括面板宽度、打开的文件标签等
+//! - 包括面板宽度、打开的文件标签、窗口位置和大小等
 //! - 使用 JSON 文件存储在应用数据目录下
 
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::path::PathBuf;
+use tauri::{State, WebviewWindow};
 use tracing::debug;
 
+use crate::error::{AxonError, ErrorKind};
+use crate::state::AppState;
 use crate::utils::paths::get_app_data_dir;
+use crate::utils::window_bounds::{self, Rect};
 
 /// 布局配置存储子目录
 const LAYOUT_DIR: &str = "layouts";
 
+/// 每个项目保留的最近文件数
+const MAX_RECENT_FILES: usize = 50;
+
 /// 打开的文件标签信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenedTab {
@@ -25,6 +33,26 @@ pub struct OpenedTab {
     pub language: String,
 }
 
+/// 最近打开的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentFile {
+    /// 文件路径
+    pub path: String,
+    /// 最后打开时间（Unix 时间戳毫秒）
+    pub opened_at: u64,
+}
+
+/// 窗口位置和大小（物理像素）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// 是否最大化（最大化时位置和大小为还原后的值）
+    pub maximized: bool,
+}
+
 /// 工作区布局配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkspaceLayout {
@@ -42,6 +70,12 @@ pub struct WorkspaceLayout {
     pub active_tab_path: Option<String>,
     /// 编辑器面板是否可见
     pub editor_visible: bool,
+    /// 窗口位置和大小
+    #[serde(default)]
+    pub window_geometry: Option<WindowGeometry>,
+    /// 最近打开的文件（最近的在前）
+    #[serde(default)]
+    pub recent_files: Vec<RecentFile>,
     /// 最后更新时间（Unix 时间戳毫秒）
     pub updated_at: u64,
 }
@@ -56,20 +90,24 @@ impl Default for WorkspaceLayout {
             opened_tabs: Vec::new(),
             active_tab_path: None,
             editor_visible: false,
+            window_geometry: None,
+            recent_files: Vec::new(),
             updated_at: 0,
         }
     }
 }
 
 /// 获取布局存储目录
-fn get_layout_dir() -> Result<PathBuf, String> {
-    let app_dir = get_app_data_dir().ok_or("应用数据目录未初始化")?;
+fn get_layout_dir() -> Result<PathBuf, AxonError> {
+    let app_dir = get_app_data_dir().ok_or_else(|| {
+        AxonError::localized(ErrorKind::Unavailable, "app.data_dir_unavailable", json!(null))
+    })?;
     let layout_dir = app_dir.join(LAYOUT_DIR);
     
     // 确保目录存在
     if !layout_dir.exists() {
         std::fs::create_dir_all(&layout_dir)
-            .map_err(|e| format!("创建布局目录失败: {}", e))?;
+            .map_err(|e| AxonError::io("创建布局目录失败", &e))?;
     }
     
     Ok(layout_dir)
@@ -96,79 +134,224 @@ fn get_layout_filename(project_directory: &str) -> String {
 /// 保存工作区布局
 /// 将布局配置保存到项目特定的 JSON 文件中
 #[tauri::command]
-pub async fn save_workspace_layout(layout: WorkspaceLayout) -> Result<(), String> {
-    debug!("保存工作区布局: {}", layout.project_directory);
-    
-    let layout_dir = get_layout_dir()?;
-    let filename = get_layout_filename(&layout.project_directory);
-    let file_path = layout_dir.join(&filename);
-    
-    // 更新时间戳
-    let mut layout = layout;
-    layout.updated_at = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0);
-    
-    // 序列化并保存
-    let json = serde_json::to_string_pretty(&layout)
-        .map_err(|e| format!("序列化布局失败: {}", e))?;
-    
-    std::fs::write(&file_path, json)
-        .map_err(|e| format!("保存布局文件失败: {}", e))?;
-    
-    debug!("布局已保存到: {:?}", file_path);
-    Ok(())
+pub async fn save_workspace_layout(state: State<'_, AppState>, layout: WorkspaceLayout) -> Result<(), AxonError> {
+    let audit_args = json!({ "projectDirectory": &layout.project_directory });
+    state.audit.track("save_workspace_layout", audit_args, async {
+        debug!("保存工作区布局: {}", layout.project_directory);
+
+        // 窗口位置和最近文件由各自的命令维护，这里始终保留已保存的值
+        let mut layout = layout;
+        let saved = read_layout(&layout.project_directory).ok().flatten();
+        layout.window_geometry = saved.as_ref().and_then(|saved| saved.window_geometry);
+        layout.recent_files = saved.map(|saved| saved.recent_files).unwrap_or_default();
+        write_layout(layout)
+    })
+    .await
 }
 
 /// 加载工作区布局
 /// 从项目特定的 JSON 文件中加载布局配置
 #[tauri::command]
-pub async fn load_workspace_layout(project_directory: String) -> Result<Option<WorkspaceLayout>, String> {
+pub async fn load_workspace_layout(project_directory: String) -> Result<Option<WorkspaceLayout>, AxonError> {
     debug!("加载工作区布局: {}", project_directory);
-    
-    let layout_dir = get_layout_dir()?;
-    let filename = get_layout_filename(&project_directory);
-    let file_path = layout_dir.join(&filename);
-    
+
+    let layout = read_layout(&project_directory)?;
+    if let Some(layout) = &layout {
+        debug!("成功加载布局，打开的标签数: {}", layout.opened_tabs.len());
+    }
+    Ok(layout)
+}
+
+/// 保存当前窗口位置和大小到项目布局
+#[tauri::command]
+pub async fn save_project_window_state(
+    window: WebviewWindow,
+    state: State<'_, AppState>,
+    project_directory: String,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "projectDirectory": &project_directory });
+    state
+        .audit
+        .track("save_project_window_state", audit_args, async {
+            let mut layout = read_layout(&project_directory)?.unwrap_or_else(|| WorkspaceLayout {
+                project_directory: project_directory.clone(),
+                ..Default::default()
+            });
+
+            let maximized = window.is_maximized().unwrap_or(false);
+            // 最大化时保留之前保存的还原尺寸，只更新最大化标记
+            let geometry = match (maximized, layout.window_geometry) {
+                (true, Some(saved)) => WindowGeometry {
+                    maximized: true,
+                    ..saved
+                },
+                _ => {
+                    let rect = window_bounds::current_rect(&window)
+                        .ok_or_else(|| AxonError::internal("获取窗口位置失败"))?;
+                    WindowGeometry {
+                        x: rect.x,
+                        y: rect.y,
+                        width: rect.width,
+                        height: rect.height,
+                        maximized,
+                    }
+                }
+            };
+            debug!("保存项目窗口状态: {} {:?}", project_directory, geometry);
+
+            layout.window_geometry = Some(geometry);
+            write_layout(layout)
+        })
+        .await
+}
+
+/// 恢复项目保存的窗口位置和大小，返回是否有可恢复的状态
+///
+/// 保存的位置不在任何当前显示器内时，窗口会移回主显示器
+#[tauri::command]
+pub async fn restore_project_window_state(
+    window: WebviewWindow,
+    project_directory: String,
+) -> Result<bool, AxonError> {
+    let Some(geometry) = read_layout(&project_directory)?.and_then(|l| l.window_geometry) else {
+        return Ok(false);
+    };
+    debug!("恢复项目窗口状态: {} {:?}", project_directory, geometry);
+
+    if window.is_maximized().unwrap_or(false) {
+        let _ = window.unmaximize();
+    }
+    window_bounds::apply_rect(
+        &window,
+        Rect {
+            x: geometry.x,
+            y: geometry.y,
+            width: geometry.width,
+            height: geometry.height,
+        },
+    );
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+    Ok(true)
+}
+
+/// 记录最近打开的文件，`project_directory` 为空时使用当前项目目录
+#[tauri::command]
+pub async fn touch_recent_file(
+    state: State<'_, AppState>,
+    path: String,
+    project_directory: Option<String>,
+) -> Result<(), AxonError> {
+    let project_directory = project_directory
+        .or_else(|| state.settings.get_project_directory())
+        .ok_or_else(|| AxonError::invalid_input("未指定项目目录"))?;
+    let audit_args = json!({ "path": &path, "projectDirectory": &project_directory });
+    state
+        .audit
+        .track("touch_recent_file", audit_args, async {
+            let mut layout = read_layout(&project_directory)?.unwrap_or_else(|| WorkspaceLayout {
+                project_directory: project_directory.clone(),
+                ..Default::default()
+            });
+            push_recent_file(&mut layout.recent_files, path, now_millis());
+            write_layout(layout)
+        })
+        .await
+}
+
+/// 列出项目最近打开的文件（最近的在前），跳过已不存在的文件
+#[tauri::command]
+pub async fn list_recent_files(
+    project_directory: String,
+    limit: Option<usize>,
+) -> Result<Vec<RecentFile>, AxonError> {
+    let recent_files = read_layout(&project_directory)?
+        .map(|layout| layout.recent_files)
+        .unwrap_or_default();
+    Ok(recent_files
+        .into_iter()
+        .filter(|file| std::path::Path::new(&file.path).exists())
+        .take(limit.unwrap_or(MAX_RECENT_FILES))
+        .collect())
+}
+
+/// 把文件移到最近列表开头，超出上限的旧记录被丢弃
+fn push_recent_file(recent_files: &mut Vec<RecentFile>, path: String, opened_at: u64) {
+    recent_files.retain(|file| file.path != path);
+    recent_files.insert(0, RecentFile { path, opened_at });
+    recent_files.truncate(MAX_RECENT_FILES);
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 读取项目布局文件，不存在时返回 None
+fn read_layout(project_directory: &str) -> Result<Option<WorkspaceLayout>, AxonError> {
+    let file_path = get_layout_dir()?.join(get_layout_filename(project_directory));
+
     if !file_path.exists() {
         debug!("布局文件不存在: {:?}", file_path);
         return Ok(None);
     }
-    
+
     let json = std::fs::read_to_string(&file_path)
-        .map_err(|e| format!("读取布局文件失败: {}", e))?;
-    
+        .map_err(|e| AxonError::io("读取布局文件失败", &e))?;
+
     let layout: WorkspaceLayout = serde_json::from_str(&json)
         .map_err(|e| format!("解析布局文件失败: {}", e))?;
-    
-    debug!("成功加载布局，打开的标签数: {}", layout.opened_tabs.len());
     Ok(Some(layout))
 }
 
+/// 更新时间戳并写入项目布局文件
+fn write_layout(mut layout: WorkspaceLayout) -> Result<(), AxonError> {
+    let file_path = get_layout_dir()?.join(get_layout_filename(&layout.project_directory));
+
+    // 更新时间戳
+    layout.updated_at = now_millis();
+
+    // 序列化并保存
+    let json = serde_json::to_string_pretty(&layout)
+        .map_err(|e| format!("序列化布局失败: {}", e))?;
+
+    std::fs::write(&file_path, json)
+        .map_err(|e| AxonError::io("保存布局文件失败", &e))?;
+
+    debug!("布局已保存到: {:?}", file_path);
+    Ok(())
+}
+
 /// 删除工作区布局
 /// 当项目被关闭或删除时，可以选择删除其布局配置
 #[tauri::command]
-pub async fn delete_workspace_layout(project_directory: String) -> Result<(), String> {
-    debug!("删除工作区布局: {}", project_directory);
-    
-    let layout_dir = get_layout_dir()?;
-    let filename = get_layout_filename(&project_directory);
-    let file_path = layout_dir.join(&filename);
-    
-    if file_path.exists() {
-        std::fs::remove_file(&file_path)
-            .map_err(|e| format!("删除布局文件失败: {}", e))?;
-        debug!("布局文件已删除: {:?}", file_path);
-    }
-    
-    Ok(())
+pub async fn delete_workspace_layout(state: State<'_, AppState>, project_directory: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "projectDirectory": &project_directory });
+    state.audit.track("delete_workspace_layout", audit_args, async {
+        debug!("删除工作区布局: {}", project_directory);
+
+        let layout_dir = get_layout_dir()?;
+        let filename = get_layout_filename(&project_directory);
+        let file_path = layout_dir.join(&filename);
+
+        if file_path.exists() {
+            std::fs::remove_file(&file_path)
+                .map_err(|e| AxonError::io("删除布局文件失败", &e))?;
+            debug!("布局文件已删除: {:?}", file_path);
+        }
+
+        Ok(())
+    })
+    .await
 }
 
 /// 列出所有已保存的布局
 /// 返回所有已保存布局的项目目录列表
 #[tauri::command]
-pub async fn list_workspace_layouts() -> Result<Vec<WorkspaceLayout>, String> {
+pub async fn list_workspace_layouts() -> Result<Vec<WorkspaceLayout>, AxonError> {
     debug!("列出所有工作区布局");
     
     let layout_dir = get_layout_dir()?;
@@ -176,7 +359,7 @@ pub async fn list_workspace_layouts() -> Result<Vec<WorkspaceLayout>, String> {
     let mut layouts = Vec::new();
     
     for entry in std::fs::read_dir(&layout_dir)
-        .map_err(|e| format!("读取布局目录失败: {}", e))?
+        .map_err(|e| AxonError::io("读取布局目录失败", &e))?
     {
         let entry = entry.map_err(|e| format!("读取目录条目失败: {}", e))?;
         let path = entry.path();
@@ -196,3 +379,24 @@ pub async fn list_workspace_layouts() -> Result<Vec<WorkspaceLayout>, String> {
     debug!("找到 {} 个布局配置", layouts.len());
     Ok(layouts)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_reopened_file_to_front() {
+        let mut recent_files = Vec::new();
+        push_recent_file(&mut recent_files, "/p/a.rs".to_string(), 1);
+        push_recent_file(&mut recent_files, "/p/b.rs".to_string(), 2);
+        push_recent_file(&mut recent_files, "/p/a.rs".to_string(), 3);
+        let paths: Vec<_> = recent_files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["/p/a.rs", "/p/b.rs"]);
+        assert_eq!(recent_files[0].opened_at, 3);
+
+        for i in 0..MAX_RECENT_FILES {
+            push_recent_file(&mut recent_files, format!("/p/{}.rs", i), 10 + i as u64);
+        }
+        assert_eq!(recent_files.len(), MAX_RECENT_FILES);
+    }
+}
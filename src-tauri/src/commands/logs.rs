@@ -0,0 +1,67 @@
+//! 日志命令
+//!
+//! 为问题反馈提供最近的应用日志、日志目录入口，以及运行时调整日志级别
+
+use crate::error::{AxonError, ErrorKind};
+use crate::logging::{self, get_logs_dir, read_recent_lines};
+use crate::opencode::LogLevelSettings;
+use crate::state::AppState;
+use serde_json::json;
+use tauri::{AppHandle, State};
+use tauri_plugin_opener::OpenerExt;
+use tracing::{debug, info};
+
+/// 默认返回行数
+const DEFAULT_LOG_LINES: usize = 500;
+
+/// 返回行数上限
+const MAX_LOG_LINES: usize = 10_000;
+
+/// 获取最近的应用日志（按时间正序）
+#[tauri::command]
+pub fn get_recent_logs(lines: Option<usize>) -> Result<Vec<String>, AxonError> {
+    let limit = lines.unwrap_or(DEFAULT_LOG_LINES).clamp(1, MAX_LOG_LINES);
+    debug!("读取最近 {} 行日志", limit);
+    read_recent_lines(limit)
+}
+
+/// 在系统文件管理器中打开日志目录
+#[tauri::command]
+pub fn open_logs_directory(app: AppHandle) -> Result<(), AxonError> {
+    let dir = get_logs_dir().ok_or_else(|| {
+        AxonError::localized(ErrorKind::Unavailable, "app.data_dir_unavailable", json!(null))
+    })?;
+    std::fs::create_dir_all(&dir).map_err(|e| AxonError::io("创建日志目录失败", &e))?;
+
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| AxonError::external(format!("打开日志目录失败: {}", e)))
+}
+
+/// 获取当前日志级别设置
+#[tauri::command]
+pub fn get_log_level(state: State<'_, AppState>) -> LogLevelSettings {
+    state.settings.get_log_level()
+}
+
+/// 设置日志级别，立即生效并持久化
+///
+/// `targets` 为空时作用于整个应用，否则只调整指定模块（如 `axon_desktop::opencode`）
+#[tauri::command]
+pub fn set_log_level(
+    state: State<'_, AppState>,
+    level: String,
+    targets: Option<Vec<String>>,
+) -> Result<LogLevelSettings, AxonError> {
+    let audit_args = json!({ "level": &level, "targets": &targets });
+    state.audit.track_sync("set_log_level", audit_args, || {
+        let settings = logging::normalize(LogLevelSettings {
+            level,
+            targets: targets.unwrap_or_default(),
+        })?;
+        logging::apply(&settings)?;
+        state.settings.set_log_level(settings.clone())?;
+        info!("日志级别已更新: {} {:?}", settings.level, settings.targets);
+        Ok(settings)
+    })
+}
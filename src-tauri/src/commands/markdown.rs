@@ -0,0 +1,35 @@
+//! Markdown 渲染命令
+
+use crate::error::AxonError;
+use crate::highlight::MarkdownHighlighter;
+use crate::markdown::{self, CodeHighlighter, MarkdownOptions};
+
+/// 单次渲染的最大文本长度
+const MAX_MARKDOWN_BYTES: usize = 8 * 1024 * 1024;
+
+/// 把 Markdown 渲染为已清理的 HTML
+#[tauri::command]
+pub async fn render_markdown(
+    text: String,
+    options: Option<MarkdownOptions>,
+) -> Result<String, AxonError> {
+    if text.len() > MAX_MARKDOWN_BYTES {
+        return Err(AxonError::invalid_input(format!(
+            "Markdown 文本过长（超过 {} MB）",
+            MAX_MARKDOWN_BYTES / 1024 / 1024
+        )));
+    }
+    let options = options.unwrap_or_default();
+    let highlighter = options
+        .highlight_theme
+        .as_deref()
+        .map(MarkdownHighlighter::new)
+        .transpose()?;
+
+    tokio::task::spawn_blocking(move || {
+        let highlighter = highlighter.as_ref().map(|h| h as &dyn CodeHighlighter);
+        markdown::render(&text, &options, highlighter)
+    })
+    .await
+    .map_err(|e| AxonError::internal(format!("渲染 Markdown 任务失败: {}", e)))
+}
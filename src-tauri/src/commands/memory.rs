@@ -0,0 +1,78 @@
+//! Agent 记忆命令
+//!
+//! 供前端查看和管理 Agent 通过 Plugin API（`/api/plugin/memory`）保存的记忆。
+
+use crate::error::AxonError;
+use crate::memory::{MemoryEntry, MemoryScope, MemoryScopeSummary};
+use crate::state::AppState;
+use serde_json::json;
+use tauri::State;
+
+/// 列出 Agent 在项目中的记忆（`project` 为空时为该 Agent 的全局记忆）
+#[tauri::command]
+pub fn list_memory_entries(
+    state: State<'_, AppState>,
+    agent: String,
+    project: Option<String>,
+) -> Result<Vec<MemoryEntry>, AxonError> {
+    state.memory.list(&MemoryScope { agent, project })
+}
+
+/// 列出所有记忆作用域及其用量
+#[tauri::command]
+pub fn list_memory_scopes(
+    state: State<'_, AppState>,
+) -> Result<Vec<MemoryScopeSummary>, AxonError> {
+    state.memory.scopes()
+}
+
+/// 写入（或覆盖）一条记忆
+#[tauri::command]
+pub fn set_memory_entry(
+    state: State<'_, AppState>,
+    agent: String,
+    project: Option<String>,
+    key: String,
+    value: String,
+) -> Result<MemoryEntry, AxonError> {
+    let audit_args = json!({
+        "agent": &agent,
+        "project": &project,
+        "key": &key,
+        "bytes": value.len(),
+    });
+    state.audit.track_sync("set_memory_entry", audit_args, || {
+        state
+            .memory
+            .set(&MemoryScope { agent, project }, &key, value)
+    })
+}
+
+/// 删除一条记忆，返回条目是否存在
+#[tauri::command]
+pub fn delete_memory_entry(
+    state: State<'_, AppState>,
+    agent: String,
+    project: Option<String>,
+    key: String,
+) -> Result<bool, AxonError> {
+    let audit_args = json!({ "agent": &agent, "project": &project, "key": &key });
+    state
+        .audit
+        .track_sync("delete_memory_entry", audit_args, || {
+            state.memory.delete(&MemoryScope { agent, project }, &key)
+        })
+}
+
+/// 清空 Agent 在项目中的全部记忆
+#[tauri::command]
+pub fn clear_memory(
+    state: State<'_, AppState>,
+    agent: String,
+    project: Option<String>,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "agent": &agent, "project": &project });
+    state.audit.track_sync("clear_memory", audit_args, || {
+        state.memory.clear(&MemoryScope { agent, project })
+    })
+}
@@ -1,27 +1,139 @@
 //! Tauri command handlers
 
+mod activity;
 mod agent;
+mod app_menu;
+mod archive;
+mod audio;
+mod audit;
+mod bookmarks;
+mod branches;
+mod bridge_update;
+mod clipboard;
+mod consent;
+mod context;
+mod context_menu;
+mod context_pins;
+mod diagnostics;
 mod diff;
+mod disk_usage;
+mod embeddings;
+mod exec;
+mod external_config;
+mod file_index;
 mod filesystem;
+mod highlight;
+mod hotkeys;
+mod keymap;
+mod ignore;
+mod images;
+mod jobs;
 mod layout;
+mod logs;
+mod markdown;
+mod memory;
 mod models_registry;
+mod notebook;
+mod notifications;
+mod oauth;
 mod opencode;
+mod opencode_plugins;
 mod orchestration;
+mod outline;
+mod pdf;
+mod plugin_api;
+mod power;
+mod project;
 mod provider;
+mod replace;
+mod reviews;
+mod run_sandbox;
+mod sandbox;
+mod scaffold;
+mod screenshot;
 mod settings;
+mod snippets;
+mod spellcheck;
+mod startup;
+mod stats;
+mod team_sync;
+mod terminal;
+mod tokens;
+mod tools;
 mod update;
+mod usage;
+mod webhooks;
 mod window;
 mod workflow;
+mod workflow_bundle;
+mod workflow_templates;
+mod workspace_stats;
 
+pub use activity::*;
 pub use agent::*;
+pub use app_menu::*;
+pub use archive::*;
+pub use audio::*;
+pub use audit::*;
+pub use bookmarks::*;
+pub use branches::*;
+pub use bridge_update::*;
+pub use clipboard::*;
+pub use consent::*;
+pub use context::*;
+pub use context_menu::*;
+pub use context_pins::*;
+pub use diagnostics::*;
 pub use diff::*;
+pub use disk_usage::*;
+pub use embeddings::*;
+pub use exec::*;
+pub use external_config::*;
+pub use file_index::*;
 pub use filesystem::*;
+pub use highlight::*;
+pub use hotkeys::*;
+pub use keymap::*;
+pub use ignore::*;
+pub use images::*;
+pub use jobs::*;
 pub use layout::*;
+pub use logs::*;
+pub use markdown::*;
+pub use memory::*;
 pub use models_registry::*;
+pub use notebook::*;
+pub use notifications::*;
+pub use oauth::*;
 pub use opencode::*;
+pub use opencode_plugins::*;
 pub use orchestration::*;
+pub use outline::*;
+pub use pdf::*;
+pub use plugin_api::*;
+pub use power::*;
+pub use project::*;
 pub use provider::*;
+pub use replace::*;
+pub use reviews::*;
+pub use run_sandbox::*;
+pub use sandbox::*;
+pub use scaffold::*;
+pub use screenshot::*;
 pub use settings::*;
+pub use snippets::*;
+pub use spellcheck::*;
+pub use startup::*;
+pub use stats::*;
+pub use team_sync::*;
+pub use terminal::*;
+pub use tokens::*;
+pub use tools::*;
 pub use update::*;
+pub use usage::*;
+pub use webhooks::*;
 pub use window::*;
 pub use workflow::*;
+pub use workflow_bundle::*;
+pub use workflow_templates::*;
+pub use workspace_stats::*;
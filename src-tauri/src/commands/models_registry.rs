@@ -2,8 +2,14 @@
 //!
 //! 提供给前端调用的模型注册表相关接口
 
-use crate::models_registry::ModelDefaults;
+use crate::error::AxonError;
+use crate::models_registry::{ModelDefaults, UserModelEntry};
+use crate::opencode::is_model_allowed;
 use crate::state::AppState;
+use serde::Serialize;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tauri::State;
 use tracing::debug;
 
@@ -26,11 +32,11 @@ pub fn get_model_defaults(
 /// 获取所有模型的默认参数列表
 ///
 /// # 返回
-/// 所有已缓存模型的默认参数列表
+/// 所有已缓存模型的默认参数列表（不包含被模型过滤规则排除的模型）
 #[tauri::command]
 pub fn get_all_model_defaults(state: State<'_, AppState>) -> Vec<ModelDefaults> {
     debug!("获取所有模型默认参数");
-    state.models_registry.get_all_model_defaults()
+    allowed_only(&state, state.models_registry.get_all_model_defaults())
 }
 
 /// 搜索模型
@@ -39,11 +45,20 @@ pub fn get_all_model_defaults(state: State<'_, AppState>) -> Vec<ModelDefaults>
 /// - `query`: 搜索关键词，匹配模型 ID、名称或 Provider 名称
 ///
 /// # 返回
-/// 匹配的模型列表
+/// 匹配的模型列表（不包含被模型过滤规则排除的模型）
 #[tauri::command]
 pub fn search_models(state: State<'_, AppState>, query: String) -> Vec<ModelDefaults> {
     debug!("搜索模型: {}", query);
-    state.models_registry.search_models(&query)
+    allowed_only(&state, state.models_registry.search_models(&query))
+}
+
+/// 去掉被设置中的模型过滤规则排除的模型
+fn allowed_only(state: &AppState, models: Vec<ModelDefaults>) -> Vec<ModelDefaults> {
+    let filters = state.settings.get_model_filters();
+    models
+        .into_iter()
+        .filter(|model| is_model_allowed(&filters, &model.model_id))
+        .collect()
 }
 
 /// 获取缓存信息
@@ -61,17 +76,105 @@ pub fn get_models_registry_cache_info(
 ///
 /// 从远程重新获取数据，忽略缓存
 #[tauri::command]
-pub async fn refresh_models_registry(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn refresh_models_registry(state: State<'_, AppState>) -> Result<(), AxonError> {
     debug!("强制刷新模型注册表");
-    state.models_registry.force_refresh().await
+    state
+        .models_registry
+        .force_refresh()
+        .await
+        .map_err(AxonError::network)
 }
 
 /// 触发后台刷新（静默）
 ///
 /// 如果距上次刷新超过 6 小时，则在后台刷新数据
 #[tauri::command]
-pub async fn trigger_background_refresh(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn trigger_background_refresh(state: State<'_, AppState>) -> Result<(), AxonError> {
     debug!("触发后台刷新模型注册表");
     state.models_registry.refresh_in_background().await;
     Ok(())
 }
+
+/// 注册表导入结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelsRegistryImportResult {
+    pub provider_count: usize,
+    pub model_count: usize,
+}
+
+/// 从本地文件导入模型注册表
+///
+/// 用于离线环境手动更新注册表数据
+///
+/// # 参数
+/// - `path`: models.dev api.json 的导出文件，支持 gzip 压缩
+#[tauri::command]
+pub async fn import_models_registry(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<ModelsRegistryImportResult, AxonError> {
+    let audit_args = json!({ "path": &path });
+    state.audit.track("import_models_registry", audit_args, async {
+        debug!("导入模型注册表: {}", path);
+        let registry = Arc::clone(&state.models_registry);
+        let (provider_count, model_count) =
+            tokio::task::spawn_blocking(move || registry.import_from_file(&PathBuf::from(path)))
+                .await
+                .map_err(|e| AxonError::internal(format!("导入模型注册表任务失败: {}", e)))??;
+
+        Ok(ModelsRegistryImportResult {
+            provider_count,
+            model_count,
+        })
+    })
+    .await
+}
+
+/// 获取用户自定义模型列表
+#[tauri::command]
+pub fn list_user_models(state: State<'_, AppState>) -> Vec<UserModelEntry> {
+    state.models_registry.get_user_models()
+}
+
+/// 添加或更新用户自定义模型
+///
+/// # 参数
+/// - `entry`: 模型定义，按 provider_id + model_id 匹配已有条目
+///
+/// # 返回
+/// 保存后的模型定义（包含时间戳）
+#[tauri::command]
+pub fn save_user_model(
+    state: State<'_, AppState>,
+    entry: UserModelEntry,
+) -> Result<UserModelEntry, AxonError> {
+    let audit_args = json!({ "model": entry.full_id() });
+    state.audit.track_sync("save_user_model", audit_args, || {
+        debug!("保存用户自定义模型: {}", entry.full_id());
+        state
+            .models_registry
+            .upsert_user_model(entry)
+            .map_err(AxonError::from)
+    })
+}
+
+/// 删除用户自定义模型
+///
+/// # 返回
+/// 模型存在并已删除时返回 true
+#[tauri::command]
+pub fn delete_user_model(
+    state: State<'_, AppState>,
+    provider_id: String,
+    model_id: String,
+) -> Result<bool, AxonError> {
+    let audit_args = json!({ "providerId": &provider_id, "modelId": &model_id });
+    state.audit.track_sync("delete_user_model", audit_args, || {
+        debug!("删除用户自定义模型: {}/{}", provider_id, model_id);
+        state
+            .models_registry
+            .delete_user_model(&provider_id, &model_id)
+            .map_err(AxonError::from)
+    })
+}
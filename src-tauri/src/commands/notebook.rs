@@ -0,0 +1,40 @@
+//! Jupyter Notebook 命令
+
+use crate::error::AxonError;
+use crate::notebook::{self, Notebook, NotebookCell};
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use serde_json::json;
+use tauri::State;
+use tracing::debug;
+
+/// 读取 Notebook，返回按单元格组织的内容
+#[tauri::command]
+pub async fn read_notebook(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<Notebook, AxonError> {
+    debug!("读取 Notebook: {}", path);
+    let path = PathSandbox::from_settings(&state.settings).check(&path)?;
+    tokio::task::spawn_blocking(move || notebook::read(&path)).await?
+}
+
+/// 写入 Notebook 的全部单元格，保留文件原有的元数据
+///
+/// 返回写入后的内容（包含为新单元格生成的 id）
+#[tauri::command]
+pub async fn write_notebook(
+    state: State<'_, AppState>,
+    path: String,
+    cells: Vec<NotebookCell>,
+) -> Result<Notebook, AxonError> {
+    let checked = PathSandbox::from_settings(&state.settings).check(&path)?;
+    let audit_args = json!({ "path": &path, "cells": cells.len() });
+    state
+        .audit
+        .track("write_notebook", audit_args, async {
+            debug!("写入 Notebook: {}, {} 个单元格", path, cells.len());
+            tokio::task::spawn_blocking(move || notebook::write(&checked, &cells)).await?
+        })
+        .await
+}
@@ -0,0 +1,44 @@
+//! 系统通知命令
+
+use crate::error::AxonError;
+use crate::notifications::NotificationKind;
+use crate::opencode::NotificationSettings;
+use crate::state::AppState;
+use serde_json::json;
+use tauri::State;
+
+/// 发送系统通知
+#[tauri::command]
+pub fn notify(
+    state: State<'_, AppState>,
+    title: String,
+    body: String,
+    kind: Option<NotificationKind>,
+) -> Result<(), AxonError> {
+    state
+        .notifications
+        .notify(&title, &body, kind.unwrap_or_default())
+}
+
+/// 获取系统通知设置
+#[tauri::command]
+pub fn get_notification_settings(state: State<'_, AppState>) -> NotificationSettings {
+    state.settings.get_notification_settings()
+}
+
+/// 更新系统通知设置
+#[tauri::command]
+pub fn set_notification_settings(
+    state: State<'_, AppState>,
+    notifications: NotificationSettings,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "notifications": &notifications });
+    state
+        .audit
+        .track_sync("set_notification_settings", audit_args, || {
+            state
+                .settings
+                .set_notification_settings(notifications)
+                .map_err(AxonError::from)
+        })
+}
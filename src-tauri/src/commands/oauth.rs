@@ -0,0 +1,64 @@
+//! 服务商 OAuth 授权命令
+
+use crate::error::AxonError;
+use crate::oauth::{OAuthClientConfig, OAuthSession};
+use crate::state::AppState;
+use tauri::{AppHandle, State};
+use tracing::debug;
+
+/// 启动服务商 OAuth 授权
+///
+/// 未传入 `config` 时依次使用服务商自定义配置中的 `oauth` 字段、
+/// 上次授权成功时保存的客户端配置。授权进度通过 `oauth:progress` 事件通知。
+#[tauri::command]
+pub async fn start_provider_oauth(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    provider_id: String,
+    config: Option<OAuthClientConfig>,
+) -> Result<OAuthSession, AxonError> {
+    let config = match config {
+        Some(config) => config,
+        None => configured_client(&state, &provider_id)
+            .or_else(|| state.oauth.saved_client(&provider_id))
+            .ok_or_else(|| {
+                AxonError::not_found(format!("服务商 {} 未配置 OAuth 客户端", provider_id))
+            })?,
+    };
+
+    state
+        .oauth
+        .start(app, provider_id, config)
+        .await
+        .map_err(AxonError::from)
+}
+
+/// 取消进行中的 OAuth 授权，会话不存在时返回 false
+#[tauri::command]
+pub async fn cancel_provider_oauth(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<bool, AxonError> {
+    Ok(state.oauth.cancel(&session_id))
+}
+
+/// 从用户服务商的自定义配置中读取 OAuth 客户端配置
+fn configured_client(state: &AppState, provider_id: &str) -> Option<OAuthClientConfig> {
+    let settings = state.settings.get_settings();
+    let value = settings
+        .providers
+        .iter()
+        .filter(|p| p.registry_id == provider_id)
+        .find_map(|p| {
+            p.custom_config
+                .as_ref()?
+                .extra
+                .as_ref()?
+                .get("oauth")
+                .cloned()
+        })?;
+
+    serde_json::from_value(value)
+        .map_err(|e| debug!("服务商 {} 的 OAuth 配置无效: {}", provider_id, e))
+        .ok()
+}
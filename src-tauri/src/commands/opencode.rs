@@ -1,7 +1,14 @@
 //! OpenCode service commands
 
-use crate::opencode::{ServiceConfig, ServiceMode, ServiceStatus, VersionInfo};
+use crate::error::AxonError;
+use crate::opencode::{
+    is_reserved_env, InstanceInfo, ServiceConfig, ServiceEnvVar, ServiceMode, ServiceStatus,
+    VersionInfo,
+};
 use crate::state::AppState;
+use crate::utils::redact::{is_sensitive_key, redact_text, REDACTED};
+use serde_json::json;
+use std::collections::BTreeMap;
 use tauri::State;
 
 /// Get current service status
@@ -30,34 +37,59 @@ pub fn set_service_config(state: State<'_, AppState>, config: ServiceConfig) {
 
 /// Initialize the opencode service
 #[tauri::command]
-pub async fn initialize_service(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn initialize_service(state: State<'_, AppState>) -> Result<(), AxonError> {
     state
         .opencode
         .initialize()
         .await
-        .map_err(|e| e.to_string())
+        .map_err(AxonError::from)
 }
 
 /// Start the opencode service
 #[tauri::command]
-pub async fn start_service(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn start_service(state: State<'_, AppState>) -> Result<(), AxonError> {
     let plugin_api_port = state.plugin_api.read().state().get_port();
     state.opencode.set_plugin_api_port(plugin_api_port);
-    state.opencode.start().await.map_err(|e| e.to_string())
+    state.opencode.start().await.map_err(AxonError::from)
 }
 
 /// Stop the opencode service
 #[tauri::command]
-pub async fn stop_service(state: State<'_, AppState>) -> Result<(), String> {
-    state.opencode.stop().await.map_err(|e| e.to_string())
+pub async fn stop_service(state: State<'_, AppState>) -> Result<(), AxonError> {
+    state.opencode.stop().await.map_err(AxonError::from)
 }
 
 /// Restart the opencode service
 #[tauri::command]
-pub async fn restart_service(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn restart_service(state: State<'_, AppState>) -> Result<(), AxonError> {
     let plugin_api_port = state.plugin_api.read().state().get_port();
     state.opencode.set_plugin_api_port(plugin_api_port);
-    state.opencode.restart().await.map_err(|e| e.to_string())
+    state.opencode.restart().await.map_err(AxonError::from)
+}
+
+/// 切换项目目录并以新目录重启服务，失败时恢复原目录
+#[tauri::command]
+pub async fn switch_project(
+    state: State<'_, AppState>,
+    project_dir: Option<String>,
+) -> Result<(), AxonError> {
+    let plugin_api_port = state.plugin_api.read().state().get_port();
+    state.opencode.set_plugin_api_port(plugin_api_port);
+    let audit_args = json!({ "projectDir": &project_dir });
+    state
+        .audit
+        .track("switch_project", audit_args, async {
+            let result = state.opencode.switch_project(project_dir).await;
+            // 回滚后设置中是原目录，文件索引始终跟随设置
+            state.file_index.set_root(
+                state
+                    .settings
+                    .get_project_directory()
+                    .map(std::path::PathBuf::from),
+            );
+            result.map_err(AxonError::from)
+        })
+        .await
 }
 
 /// Get the service endpoint URL
@@ -66,17 +98,129 @@ pub fn get_service_endpoint(state: State<'_, AppState>) -> Option<String> {
     state.opencode.get_endpoint()
 }
 
+/// 获取启动 opencode 时额外注入的环境变量
+#[tauri::command]
+pub fn get_service_extra_env(state: State<'_, AppState>) -> BTreeMap<String, String> {
+    state.settings.get_extra_env()
+}
+
+/// 设置启动 opencode 时额外注入的环境变量（下次启动服务时生效）
+#[tauri::command]
+pub fn set_service_extra_env(
+    state: State<'_, AppState>,
+    env: BTreeMap<String, String>,
+) -> Result<(), AxonError> {
+    // 审计日志只记录变量名
+    let audit_args = json!({ "names": env.keys().collect::<Vec<_>>() });
+    state
+        .audit
+        .track_sync("set_service_extra_env", audit_args, || {
+            for name in env.keys() {
+                let valid = name
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+                if !valid {
+                    return Err(AxonError::invalid_input(format!(
+                        "无效的环境变量名: {}（只能包含字母、数字和 _，且不能以数字开头）",
+                        name
+                    )));
+                }
+                if is_reserved_env(name) {
+                    return Err(AxonError::invalid_input(format!(
+                        "环境变量 {} 由 Axon 管理，不能覆盖",
+                        name
+                    )));
+                }
+            }
+            state.settings.set_extra_env(env).map_err(AxonError::from)
+        })
+}
+
+/// 获取 opencode 进程的环境变量（敏感值已脱敏，用于调试）
+#[tauri::command]
+pub fn get_service_environment(state: State<'_, AppState>) -> Vec<ServiceEnvVar> {
+    state
+        .opencode
+        .environment()
+        .into_iter()
+        .map(|mut var| {
+            var.value = if is_sensitive_key(&var.name) {
+                REDACTED.to_string()
+            } else {
+                redact_text(&var.value)
+            };
+            var
+        })
+        .collect()
+}
+
+/// 列出所有 OpenCode 实例（默认实例在前）
+#[tauri::command]
+pub fn list_service_instances(state: State<'_, AppState>) -> Vec<InstanceInfo> {
+    state.services.list()
+}
+
+/// 以指定工作目录启动附加的 OpenCode 实例，`port` 为空时自动分配
+#[tauri::command]
+pub async fn start_service_instance(
+    state: State<'_, AppState>,
+    name: String,
+    directory: String,
+    port: Option<u16>,
+) -> Result<InstanceInfo, AxonError> {
+    let plugin_api_port = state.plugin_api.read().state().get_port();
+    let audit_args = json!({ "name": &name, "directory": &directory, "port": port });
+    state
+        .audit
+        .track(
+            "start_service_instance",
+            audit_args,
+            state
+                .services
+                .start(&name, &directory, port.unwrap_or(0), plugin_api_port),
+        )
+        .await
+}
+
+/// 停止 OpenCode 实例（附加实例停止后移除）
+#[tauri::command]
+pub async fn stop_service_instance(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "name": &name });
+    state
+        .audit
+        .track("stop_service_instance", audit_args, state.services.stop(&name))
+        .await
+}
+
 #[tauri::command]
-pub async fn get_version_info(state: State<'_, AppState>) -> Result<VersionInfo, String> {
-    state.opencode.get_version_info().await.map_err(|e| e.to_string())
+pub async fn get_version_info(state: State<'_, AppState>) -> Result<VersionInfo, AxonError> {
+    state.opencode.get_version_info().await.map_err(AxonError::from)
 }
 
 #[tauri::command]
-pub async fn check_for_update(state: State<'_, AppState>) -> Result<VersionInfo, String> {
-    state.opencode.check_for_update().await.map_err(|e| e.to_string())
+pub async fn check_for_update(state: State<'_, AppState>) -> Result<VersionInfo, AxonError> {
+    state.opencode.check_for_update().await.map_err(AxonError::from)
 }
 
 #[tauri::command]
-pub async fn update_opencode(state: State<'_, AppState>) -> Result<(), String> {
-    state.opencode.update_opencode().await.map_err(|e| e.to_string())
+pub async fn update_opencode(state: State<'_, AppState>) -> Result<(), AxonError> {
+    let audit_args = json!({});
+    state.audit.track("update_opencode", audit_args, async {
+        let result = state.opencode.update_opencode().await.map_err(AxonError::from);
+        match &result {
+            Ok(()) => state
+                .notifications
+                .notify_task_finished("OpenCode 更新完成", "已下载并安装最新版本", true),
+            Err(e) => state
+                .notifications
+                .notify_task_finished("OpenCode 更新失败", &e.message, false),
+        }
+        result
+    })
+    .await
 }
@@ -0,0 +1,47 @@
+//! OpenCode 第三方插件管理命令
+//!
+//! 修改 opencode.json 中的插件列表，重启 OpenCode 后生效。
+
+use crate::error::AxonError;
+use crate::opencode::plugins::{self, OpencodePlugin};
+use crate::state::AppState;
+use serde_json::json;
+use tauri::State;
+
+/// 列出 opencode.json 中配置的插件
+#[tauri::command]
+pub async fn list_opencode_plugins() -> Result<Vec<OpencodePlugin>, AxonError> {
+    tokio::task::spawn_blocking(plugins::list_plugins).await?
+}
+
+/// 添加插件（本地文件绝对路径或 http(s) URL），校验后复制到插件目录
+#[tauri::command]
+pub async fn add_opencode_plugin(
+    state: State<'_, AppState>,
+    url_or_path: String,
+) -> Result<OpencodePlugin, AxonError> {
+    let audit_args = json!({ "urlOrPath": &url_or_path });
+    state
+        .audit
+        .track(
+            "add_opencode_plugin",
+            audit_args,
+            plugins::add_plugin(&url_or_path),
+        )
+        .await
+}
+
+/// 移除插件及其本地副本
+#[tauri::command]
+pub async fn remove_opencode_plugin(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "id": &id });
+    state
+        .audit
+        .track("remove_opencode_plugin", audit_args, async move {
+            tokio::task::spawn_blocking(move || plugins::remove_plugin(&id)).await?
+        })
+        .await
+}
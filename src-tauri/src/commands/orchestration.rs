@@ -6,8 +6,13 @@
 //! - 删除编排组配置
 //! - 获取编排组存储目录
 
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::team_sync::TeamItemKind;
+use serde_json::json;
+use std::collections::HashSet;
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, State};
 use tracing::{debug, error, info};
 
 /// 编排组配置目录名称
@@ -20,7 +25,7 @@ const ORCHESTRATION_FILE_EXT: &str = ".json";
 ///
 /// 返回应用数据目录下的 orchestrations 文件夹路径
 #[tauri::command]
-pub async fn get_orchestrations_directory(app: AppHandle) -> Result<String, String> {
+pub async fn get_orchestrations_directory(app: AppHandle) -> Result<String, AxonError> {
     let app_data_dir = app
         .path()
         .app_data_dir()
@@ -35,7 +40,7 @@ pub async fn get_orchestrations_directory(app: AppHandle) -> Result<String, Stri
                 "创建 orchestrations 目录失败: {:?}, 错误: {}",
                 orchestrations_dir, e
             );
-            format!("创建 orchestrations 目录失败: {}", e)
+            AxonError::io("创建 orchestrations 目录失败", &e)
         })?;
         info!("创建 orchestrations 目录: {:?}", orchestrations_dir);
     }
@@ -47,14 +52,19 @@ pub async fn get_orchestrations_directory(app: AppHandle) -> Result<String, Stri
 ///
 /// 读取 orchestrations 目录下的所有 JSON 文件，返回完整配置列表（JSON 字符串）
 #[tauri::command]
-pub async fn list_orchestrations(app: AppHandle) -> Result<String, String> {
+pub async fn list_orchestrations(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AxonError> {
     let orchestrations_dir = get_orchestrations_dir_path(&app)?;
 
     debug!("列出 orchestrations 目录: {:?}", orchestrations_dir);
 
     if !orchestrations_dir.exists() {
-        debug!("orchestrations 目录不存在，返回空列表");
-        return Ok("[]".to_string());
+        debug!("orchestrations 目录不存在，只返回团队定义");
+        let mut groups = Vec::new();
+        merge_team_orchestrations(&state, &mut groups);
+        return Ok(format!("[{}]", groups.join(",")));
     }
 
     let mut groups = Vec::new();
@@ -64,7 +74,7 @@ pub async fn list_orchestrations(app: AppHandle) -> Result<String, String> {
             "读取 orchestrations 目录失败: {:?}, 错误: {}",
             orchestrations_dir, e
         );
-        format!("读取 orchestrations 目录失败: {}", e)
+        AxonError::io("读取 orchestrations 目录失败", &e)
     })?;
 
     for entry in entries {
@@ -99,6 +109,8 @@ pub async fn list_orchestrations(app: AppHandle) -> Result<String, String> {
         }
     }
 
+    merge_team_orchestrations(&state, &mut groups);
+
     // 构建 JSON 数组
     let result = format!("[{}]", groups.join(","));
 
@@ -112,25 +124,34 @@ pub async fn list_orchestrations(app: AppHandle) -> Result<String, String> {
 #[tauri::command]
 pub async fn read_orchestration(
     app: AppHandle,
+    state: State<'_, AppState>,
     orchestration_id: String,
-) -> Result<String, String> {
+) -> Result<String, AxonError> {
     let orchestrations_dir = get_orchestrations_dir_path(&app)?;
     let orchestration_path =
         orchestrations_dir.join(format!("{}{}", orchestration_id, ORCHESTRATION_FILE_EXT));
 
     debug!("读取编排组配置: {:?}", orchestration_path);
 
-    if !orchestration_path.exists() {
+    // 本地不存在时读取团队仓库中的定义
+    let orchestration_path = if orchestration_path.exists() {
+        orchestration_path
+    } else if let Some(team_path) = state
+        .team_sync
+        .definition_path(TeamItemKind::Orchestration, &orchestration_id)
+    {
+        team_path
+    } else {
         error!("编排组配置文件不存在: {:?}", orchestration_path);
-        return Err(format!("编排组不存在: {}", orchestration_id));
-    }
+        return Err(AxonError::not_found(format!("编排组不存在: {}", orchestration_id)));
+    };
 
     let content = std::fs::read_to_string(&orchestration_path).map_err(|e| {
         error!(
             "读取编排组文件失败: {:?}, 错误: {}",
             orchestration_path, e
         );
-        format!("读取编排组配置失败: {}", e)
+        AxonError::io("读取编排组配置失败", &e)
     })?;
 
     Ok(content)
@@ -142,46 +163,51 @@ pub async fn read_orchestration(
 #[tauri::command]
 pub async fn save_orchestration(
     app: AppHandle,
+    state: State<'_, AppState>,
     orchestration_id: String,
     config: String,
-) -> Result<(), String> {
-    let orchestrations_dir = get_orchestrations_dir_path(&app)?;
-
-    // 确保目录存在
-    if !orchestrations_dir.exists() {
-        std::fs::create_dir_all(&orchestrations_dir).map_err(|e| {
-            error!(
-                "创建 orchestrations 目录失败: {:?}, 错误: {}",
-                orchestrations_dir, e
-            );
-            format!("创建 orchestrations 目录失败: {}", e)
-        })?;
-    }
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "orchestrationId": &orchestration_id, "bytes": config.len() });
+    state.audit.track("save_orchestration", audit_args, async {
+        let orchestrations_dir = get_orchestrations_dir_path(&app)?;
+
+        // 确保目录存在
+        if !orchestrations_dir.exists() {
+            std::fs::create_dir_all(&orchestrations_dir).map_err(|e| {
+                error!(
+                    "创建 orchestrations 目录失败: {:?}, 错误: {}",
+                    orchestrations_dir, e
+                );
+                AxonError::io("创建 orchestrations 目录失败", &e)
+            })?;
+        }
 
-    let orchestration_path =
-        orchestrations_dir.join(format!("{}{}", orchestration_id, ORCHESTRATION_FILE_EXT));
+        let orchestration_path =
+            orchestrations_dir.join(format!("{}{}", orchestration_id, ORCHESTRATION_FILE_EXT));
 
-    debug!("保存编排组配置: {:?}", orchestration_path);
+        debug!("保存编排组配置: {:?}", orchestration_path);
 
-    // 验证 JSON 格式
-    let _: serde_json::Value = serde_json::from_str(&config).map_err(|e| {
-        error!("无效的 JSON 格式: {}", e);
-        format!("无效的编排组配置格式: {}", e)
-    })?;
+        // 验证 JSON 格式
+        let _: serde_json::Value = serde_json::from_str(&config).map_err(|e| {
+            error!("无效的 JSON 格式: {}", e);
+            format!("无效的编排组配置格式: {}", e)
+        })?;
 
-    // 格式化 JSON 输出（便于阅读）
-    let formatted = format_json(&config)?;
+        // 格式化 JSON 输出（便于阅读）
+        let formatted = format_json(&config)?;
 
-    std::fs::write(&orchestration_path, formatted).map_err(|e| {
-        error!(
-            "写入编排组文件失败: {:?}, 错误: {}",
-            orchestration_path, e
-        );
-        format!("保存编排组配置失败: {}", e)
-    })?;
+        std::fs::write(&orchestration_path, formatted).map_err(|e| {
+            error!(
+                "写入编排组文件失败: {:?}, 错误: {}",
+                orchestration_path, e
+            );
+            AxonError::io("保存编排组配置失败", &e)
+        })?;
 
-    info!("编排组配置已保存: {}", orchestration_id);
-    Ok(())
+        info!("编排组配置已保存: {}", orchestration_id);
+        Ok(())
+    })
+    .await
 }
 
 /// 删除编排组配置
@@ -190,29 +216,44 @@ pub async fn save_orchestration(
 #[tauri::command]
 pub async fn delete_orchestration(
     app: AppHandle,
+    state: State<'_, AppState>,
     orchestration_id: String,
-) -> Result<(), String> {
-    let orchestrations_dir = get_orchestrations_dir_path(&app)?;
-    let orchestration_path =
-        orchestrations_dir.join(format!("{}{}", orchestration_id, ORCHESTRATION_FILE_EXT));
-
-    debug!("删除编排组配置: {:?}", orchestration_path);
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "orchestrationId": &orchestration_id });
+    state.audit.track("delete_orchestration", audit_args, async {
+        let orchestrations_dir = get_orchestrations_dir_path(&app)?;
+        let orchestration_path =
+            orchestrations_dir.join(format!("{}{}", orchestration_id, ORCHESTRATION_FILE_EXT));
 
-    if !orchestration_path.exists() {
-        error!("编排组配置文件不存在: {:?}", orchestration_path);
-        return Err(format!("编排组不存在: {}", orchestration_id));
-    }
+        debug!("删除编排组配置: {:?}", orchestration_path);
+
+        if !orchestration_path.exists() {
+            if state
+                .team_sync
+                .definition_path(TeamItemKind::Orchestration, &orchestration_id)
+                .is_some()
+            {
+                return Err(AxonError::invalid_input(format!(
+                    "编排组 {} 来自团队配置仓库，不能删除",
+                    orchestration_id
+                )));
+            }
+            error!("编排组配置文件不存在: {:?}", orchestration_path);
+            return Err(AxonError::not_found(format!("编排组不存在: {}", orchestration_id)));
+        }
 
-    std::fs::remove_file(&orchestration_path).map_err(|e| {
-        error!(
-            "删除编排组文件失败: {:?}, 错误: {}",
-            orchestration_path, e
-        );
-        format!("删除编排组配置失败: {}", e)
-    })?;
+        std::fs::remove_file(&orchestration_path).map_err(|e| {
+            error!(
+                "删除编排组文件失败: {:?}, 错误: {}",
+                orchestration_path, e
+            );
+            AxonError::io("删除编排组配置失败", &e)
+        })?;
 
-    info!("编排组配置已删除: {}", orchestration_id);
-    Ok(())
+        info!("编排组配置已删除: {}", orchestration_id);
+        Ok(())
+    })
+    .await
 }
 
 /// 批量保存编排组配置
@@ -221,54 +262,89 @@ pub async fn delete_orchestration(
 #[tauri::command]
 pub async fn save_orchestrations_batch(
     app: AppHandle,
+    state: State<'_, AppState>,
     orchestrations: Vec<(String, String)>,
-) -> Result<(), String> {
-    let orchestrations_dir = get_orchestrations_dir_path(&app)?;
-
-    // 确保目录存在
-    if !orchestrations_dir.exists() {
-        std::fs::create_dir_all(&orchestrations_dir).map_err(|e| {
-            error!(
-                "创建 orchestrations 目录失败: {:?}, 错误: {}",
-                orchestrations_dir, e
-            );
-            format!("创建 orchestrations 目录失败: {}", e)
-        })?;
-    }
+) -> Result<(), AxonError> {
+    let audit_args = json!({
+        "orchestrationIds": orchestrations.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+    });
+    state.audit.track("save_orchestrations_batch", audit_args, async {
+        let orchestrations_dir = get_orchestrations_dir_path(&app)?;
+
+        // 确保目录存在
+        if !orchestrations_dir.exists() {
+            std::fs::create_dir_all(&orchestrations_dir).map_err(|e| {
+                error!(
+                    "创建 orchestrations 目录失败: {:?}, 错误: {}",
+                    orchestrations_dir, e
+                );
+                AxonError::io("创建 orchestrations 目录失败", &e)
+            })?;
+        }
 
-    let mut errors = Vec::new();
+        let mut errors = Vec::new();
 
-    for (orchestration_id, config) in orchestrations {
-        let orchestration_path =
-            orchestrations_dir.join(format!("{}{}", orchestration_id, ORCHESTRATION_FILE_EXT));
+        for (orchestration_id, config) in orchestrations {
+            let orchestration_path =
+                orchestrations_dir.join(format!("{}{}", orchestration_id, ORCHESTRATION_FILE_EXT));
 
-        // 验证并格式化 JSON
-        match format_json(&config) {
-            Ok(formatted) => {
-                if let Err(e) = std::fs::write(&orchestration_path, formatted) {
+            // 验证并格式化 JSON
+            match format_json(&config) {
+                Ok(formatted) => {
+                    if let Err(e) = std::fs::write(&orchestration_path, formatted) {
+                        errors.push(format!("{}: {}", orchestration_id, e));
+                    }
+                }
+                Err(e) => {
                     errors.push(format!("{}: {}", orchestration_id, e));
                 }
             }
-            Err(e) => {
-                errors.push(format!("{}: {}", orchestration_id, e));
-            }
         }
-    }
 
-    if errors.is_empty() {
-        info!("批量保存编排组配置成功");
-        Ok(())
-    } else {
-        Err(format!("部分保存失败: {}", errors.join(", ")))
-    }
+        if errors.is_empty() {
+            info!("批量保存编排组配置成功");
+            Ok(())
+        } else {
+            Err(AxonError::internal(format!("部分保存失败: {}", errors.join(", "))))
+        }
+    })
+    .await
 }
 
 // ============================================================================
 // 辅助函数
 // ============================================================================
 
+/// 合并团队仓库中的编排组（加上 `"scope": "team"`，本地存在同 ID 的编排组时跳过）
+fn merge_team_orchestrations(state: &AppState, groups: &mut Vec<String>) {
+    let group_id = |value: &serde_json::Value| {
+        value.get("id").and_then(|v| v.as_str()).map(String::from)
+    };
+    let local_ids: HashSet<String> = groups
+        .iter()
+        .filter_map(|content| group_id(&serde_json::from_str(content).ok()?))
+        .collect();
+
+    for definition in state.team_sync.definitions(TeamItemKind::Orchestration) {
+        let Some(mut value) = std::fs::read_to_string(&definition.path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        else {
+            debug!("跳过无法解析的团队编排组文件: {:?}", definition.path);
+            continue;
+        };
+        if group_id(&value).is_some_and(|id| local_ids.contains(&id)) {
+            continue;
+        }
+        if let Some(object) = value.as_object_mut() {
+            object.insert("scope".to_string(), json!("team"));
+            groups.push(value.to_string());
+        }
+    }
+}
+
 /// 获取 orchestrations 目录路径
-fn get_orchestrations_dir_path(app: &AppHandle) -> Result<PathBuf, String> {
+fn get_orchestrations_dir_path(app: &AppHandle) -> Result<PathBuf, AxonError> {
     let app_data_dir = app
         .path()
         .app_data_dir()
@@ -278,9 +354,9 @@ fn get_orchestrations_dir_path(app: &AppHandle) -> Result<PathBuf, String> {
 }
 
 /// 格式化 JSON 字符串（美化输出）
-fn format_json(json_str: &str) -> Result<String, String> {
+fn format_json(json_str: &str) -> Result<String, AxonError> {
     let value: serde_json::Value =
-        serde_json::from_str(json_str).map_err(|e| format!("无效的 JSON: {}", e))?;
+        serde_json::from_str(json_str).map_err(|e| AxonError::invalid_input(format!("无效的 JSON: {}", e)))?;
 
-    serde_json::to_string_pretty(&value).map_err(|e| format!("格式化 JSON 失败: {}", e))
+    serde_json::to_string_pretty(&value).map_err(|e| AxonError::internal(format!("格式化 JSON 失败: {}", e)))
 }
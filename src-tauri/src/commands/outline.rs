@@ -0,0 +1,311 @@
+//! 代码大纲命令
+//!
+//! 基于 tree-sitter 解析源码，提取函数、类、结构体等符号及其行范围，
+//! 供大纲侧边栏展示，以及工作流按名称定位符号使用。
+
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use serde::Serialize;
+use std::path::Path;
+use tauri::State;
+use tracing::{debug, error};
+use tree_sitter::{Language, Node, Parser};
+
+/// 可解析的最大文件大小（5MB）
+const MAX_OUTLINE_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// 支持大纲解析的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum OutlineLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+}
+
+impl OutlineLanguage {
+    /// 根据文件扩展名识别语言
+    pub(super) fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "rs" => Some(Self::Rust),
+            "py" | "pyi" => Some(Self::Python),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::Python => "python",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+            Self::Tsx => "tsx",
+            Self::Go => "go",
+        }
+    }
+
+    pub(super) fn grammar(self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+}
+
+/// 大纲符号
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineSymbol {
+    /// 符号名称
+    pub name: String,
+    /// 符号类型（function / method / class / struct / enum / trait / interface / impl / module / type）
+    pub kind: String,
+    /// 起始行（从 1 开始）
+    pub start_line: usize,
+    /// 结束行（从 1 开始，包含）
+    pub end_line: usize,
+    /// 嵌套的子符号
+    pub children: Vec<OutlineSymbol>,
+}
+
+/// 代码大纲
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeOutline {
+    /// 识别出的语言
+    pub language: String,
+    /// 顶层符号
+    pub symbols: Vec<OutlineSymbol>,
+}
+
+/// 获取文件的代码大纲
+#[tauri::command]
+pub async fn get_code_outline(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<CodeOutline, AxonError> {
+    debug!("提取代码大纲: {}", path);
+    let path = PathSandbox::from_settings(&state.settings).check(&path)?;
+
+    tokio::task::spawn_blocking(move || extract_outline(&path))
+        .await
+        .map_err(|e| AxonError::internal(format!("提取代码大纲任务失败: {}", e)))?
+}
+
+/// 按名称查找符号
+///
+/// 支持 `Type.method` / `Type::method` 形式的限定名；
+/// 返回深度优先遍历中第一个匹配的符号
+#[tauri::command]
+pub async fn find_code_symbol(
+    state: State<'_, AppState>,
+    path: String,
+    name: String,
+) -> Result<Option<OutlineSymbol>, AxonError> {
+    debug!("查找符号: {} in {}", name, path);
+    let path = PathSandbox::from_settings(&state.settings).check(&path)?;
+
+    let outline = tokio::task::spawn_blocking(move || extract_outline(&path))
+        .await
+        .map_err(|e| AxonError::internal(format!("提取代码大纲任务失败: {}", e)))??;
+
+    let segments: Vec<&str> = name
+        .split("::")
+        .flat_map(|s| s.split('.'))
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    if segments.is_empty() {
+        return Err(AxonError::invalid_input("符号名称不能为空"));
+    }
+
+    Ok(find_symbol(&outline.symbols, &segments))
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn extract_outline(path: &Path) -> Result<CodeOutline, AxonError> {
+    let language = OutlineLanguage::from_path(path)
+        .ok_or_else(|| AxonError::unsupported(format!("不支持的语言: {}", path.display())))?;
+
+    let metadata = std::fs::metadata(path).map_err(|e| {
+        error!("读取文件元数据失败: {:?}, 错误: {}", path, e);
+        AxonError::localized_io("fs.read_file_failed", &e)
+    })?;
+    if metadata.len() > MAX_OUTLINE_FILE_SIZE {
+        return Err(AxonError::invalid_input(format!("文件过大，无法解析大纲: {} 字节", metadata.len())));
+    }
+
+    let source = std::fs::read_to_string(path).map_err(|e| {
+        error!("读取文件失败: {:?}, 错误: {}", path, e);
+        AxonError::localized_io("fs.read_file_failed", &e)
+    })?;
+
+    let symbols = parse_symbols(language, &source)?;
+    debug!("大纲提取完成: {:?}, {} 个顶层符号", path, symbols.len());
+
+    Ok(CodeOutline {
+        language: language.name().to_string(),
+        symbols,
+    })
+}
+
+fn parse_symbols(language: OutlineLanguage, source: &str) -> Result<Vec<OutlineSymbol>, AxonError> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&language.grammar())
+        .map_err(|e| format!("加载语法失败: {}", e))?;
+
+    let tree = parser
+        .parse(source, None)
+        .ok_or_else(|| AxonError::invalid_data("解析源码失败"))?;
+
+    let mut symbols = Vec::new();
+    collect_symbols(
+        language,
+        tree.root_node(),
+        source.as_bytes(),
+        false,
+        &mut symbols,
+    );
+    Ok(symbols)
+}
+
+/// 递归收集符号；`in_container` 表示当前位于类/impl 等容器内（用于区分方法）
+fn collect_symbols(
+    language: OutlineLanguage,
+    node: Node,
+    source: &[u8],
+    in_container: bool,
+    out: &mut Vec<OutlineSymbol>,
+) {
+    let mut cursor = node.walk();
+    let children: Vec<Node> = node.named_children(&mut cursor).collect();
+
+    for child in children {
+        match classify_node(language, child, in_container) {
+            Some((kind, name_node)) => {
+                let name = name_node
+                    .and_then(|n| n.utf8_text(source).ok())
+                    .map(|s| s.trim().to_string())
+                    .unwrap_or_else(|| "<anonymous>".to_string());
+
+                let mut symbol = OutlineSymbol {
+                    name,
+                    kind: kind.to_string(),
+                    start_line: child.start_position().row + 1,
+                    end_line: child.end_position().row + 1,
+                    children: Vec::new(),
+                };
+
+                let is_container = matches!(
+                    kind,
+                    "class" | "struct" | "enum" | "trait" | "interface" | "impl" | "module"
+                );
+                collect_symbols(language, child, source, is_container, &mut symbol.children);
+                out.push(symbol);
+            }
+            None => collect_symbols(language, child, source, in_container, out),
+        }
+    }
+}
+
+/// 判断节点是否为大纲符号，返回（符号类型，名称节点）
+pub(super) fn classify_node<'a>(
+    language: OutlineLanguage,
+    node: Node<'a>,
+    in_container: bool,
+) -> Option<(&'static str, Option<Node<'a>>)> {
+    let name = node.child_by_field_name("name");
+    let function_kind = if in_container { "method" } else { "function" };
+
+    let kind = match language {
+        OutlineLanguage::Rust => match node.kind() {
+            "function_item" | "function_signature_item" => function_kind,
+            "struct_item" => "struct",
+            "enum_item" => "enum",
+            "union_item" => "struct",
+            "trait_item" => "trait",
+            "mod_item" => "module",
+            "type_item" => "type",
+            "macro_definition" => "macro",
+            "impl_item" => {
+                return Some(("impl", node.child_by_field_name("type")));
+            }
+            _ => return None,
+        },
+        OutlineLanguage::Python => match node.kind() {
+            "function_definition" => function_kind,
+            "class_definition" => "class",
+            _ => return None,
+        },
+        OutlineLanguage::JavaScript | OutlineLanguage::TypeScript | OutlineLanguage::Tsx => {
+            match node.kind() {
+                "function_declaration" | "generator_function_declaration" => "function",
+                "method_definition" | "method_signature" | "abstract_method_signature" => "method",
+                "class_declaration" | "abstract_class_declaration" => "class",
+                "interface_declaration" => "interface",
+                "enum_declaration" => "enum",
+                "type_alias_declaration" => "type",
+                "internal_module" | "module" => "module",
+                // const foo = () => {} / const foo = function () {}
+                "variable_declarator" => {
+                    let value = node.child_by_field_name("value")?;
+                    match value.kind() {
+                        "arrow_function" | "function_expression" | "function" => "function",
+                        "class" => "class",
+                        _ => return None,
+                    }
+                }
+                _ => return None,
+            }
+        }
+        OutlineLanguage::Go => match node.kind() {
+            "function_declaration" => "function",
+            "method_declaration" => "method",
+            "type_spec" => match node.child_by_field_name("type").map(|t| t.kind()) {
+                Some("struct_type") => "struct",
+                Some("interface_type") => "interface",
+                _ => "type",
+            },
+            _ => return None,
+        },
+    };
+
+    Some((kind, name))
+}
+
+/// 按限定名路径查找符号
+fn find_symbol(symbols: &[OutlineSymbol], segments: &[&str]) -> Option<OutlineSymbol> {
+    let (first, rest) = segments.split_first()?;
+
+    for symbol in symbols {
+        if symbol.name == *first {
+            if rest.is_empty() {
+                return Some(symbol.clone());
+            }
+            if let Some(found) = find_symbol(&symbol.children, rest) {
+                return Some(found);
+            }
+        }
+    }
+
+    // 未在当前层匹配时继续向下搜索（允许省略外层容器）
+    symbols
+        .iter()
+        .find_map(|symbol| find_symbol(&symbol.children, segments))
+}
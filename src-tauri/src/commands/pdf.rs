@@ -0,0 +1,231 @@
+//! PDF 文本提取命令
+//!
+//! 使用 lopdf 按页提取文本，供把 PDF 作为文本附加到提示词。
+//! 总文本量有上限，超出后停止提取并标记 `truncated`；单页提取失败时只记录该页的错误。
+//! 只有空用户密码的加密文件（仅限制编辑 / 打印）可以读取，其余加密文件返回错误。
+
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use lopdf::Document;
+use serde::Serialize;
+use std::path::Path;
+use tauri::State;
+use tracing::debug;
+
+/// 接受的最大文件大小
+const MAX_PDF_BYTES: u64 = 100 * 1024 * 1024;
+
+/// 返回的最大文本量
+const MAX_TEXT_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfPageText {
+    /// 页码（从 1 开始）
+    pub page: u32,
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PdfText {
+    pub path: String,
+    /// 文档总页数
+    pub page_count: u32,
+    pub pages: Vec<PdfPageText>,
+    /// 文本量达到上限，后面的页未提取
+    pub truncated: bool,
+}
+
+/// 提取 PDF 文本
+///
+/// `page_range` 形如 `1-3,5,8-`（页码从 1 开始），为空时提取全部页
+#[tauri::command]
+pub async fn extract_pdf_text(
+    state: State<'_, AppState>,
+    path: String,
+    page_range: Option<String>,
+) -> Result<PdfText, AxonError> {
+    debug!("提取 PDF 文本: {}, 页码: {:?}", path, page_range);
+    let path = PathSandbox::from_settings(&state.settings).check(&path)?;
+    tokio::task::spawn_blocking(move || extract(&path, page_range.as_deref())).await?
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn extract(path: &Path, page_range: Option<&str>) -> Result<PdfText, AxonError> {
+    let metadata = std::fs::metadata(path).map_err(|e| AxonError::io("读取 PDF 失败", &e))?;
+    if metadata.len() > MAX_PDF_BYTES {
+        return Err(AxonError::invalid_input(format!(
+            "PDF 文件过大（超过 {} MB）",
+            MAX_PDF_BYTES / 1024 / 1024
+        )));
+    }
+    let bytes = std::fs::read(path).map_err(|e| AxonError::io("读取 PDF 失败", &e))?;
+    let mut document = Document::load_mem(&bytes)
+        .map_err(|e| AxonError::invalid_data(format!("无法解析 PDF: {}", e)))?;
+    if document.is_encrypted() && document.decrypt("").is_err() {
+        return Err(AxonError::unsupported("PDF 已加密，无法提取文本"));
+    }
+
+    let page_count = document.get_pages().len() as u32;
+    let pages = match page_range {
+        Some(range) if !range.trim().is_empty() => parse_page_range(range, page_count)?,
+        _ => (1..=page_count).collect(),
+    };
+    Ok(extract_pages(&document, path, page_count, &pages))
+}
+
+fn extract_pages(document: &Document, path: &Path, page_count: u32, pages: &[u32]) -> PdfText {
+    let mut result = PdfText {
+        path: path.to_string_lossy().to_string(),
+        page_count,
+        pages: Vec::with_capacity(pages.len()),
+        truncated: false,
+    };
+    let mut total = 0;
+    for &page in pages {
+        if total >= MAX_TEXT_BYTES {
+            result.truncated = true;
+            break;
+        }
+        let (mut text, error) = match document.extract_text(&[page]) {
+            Ok(text) => (text, None),
+            Err(e) => (String::new(), Some(format!("提取失败: {}", e))),
+        };
+        if total + text.len() > MAX_TEXT_BYTES {
+            let mut end = MAX_TEXT_BYTES - total;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            text.truncate(end);
+            result.truncated = true;
+        }
+        total += text.len();
+        result.pages.push(PdfPageText { page, text, error });
+    }
+    result
+}
+
+/// 解析页码范围，结果按出现顺序去重
+fn parse_page_range(range: &str, page_count: u32) -> Result<Vec<u32>, AxonError> {
+    let invalid = || AxonError::invalid_input(format!("无效的页码范围: {}", range));
+    let parse = |value: &str| value.trim().parse::<u32>().map_err(|_| invalid());
+
+    let mut pages = Vec::new();
+    for part in range.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => {
+                let end = if end.trim().is_empty() {
+                    page_count
+                } else {
+                    parse(end)?
+                };
+                (parse(start)?, end)
+            }
+            None => (parse(part)?, parse(part)?),
+        };
+        if start == 0 || start > end {
+            return Err(invalid());
+        }
+        if start > page_count {
+            return Err(AxonError::invalid_input(format!(
+                "页码超出范围: {}（共 {} 页）",
+                start, page_count
+            )));
+        }
+        for page in start..=end.min(page_count) {
+            if !pages.contains(&page) {
+                pages.push(page);
+            }
+        }
+    }
+    if pages.is_empty() {
+        return Err(invalid());
+    }
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::content::{Content, Operation};
+    use lopdf::{dictionary, Object, Stream};
+
+    /// 生成每页一行文本的 PDF
+    fn sample_pdf(texts: &[&str]) -> Document {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+        let mut kids = Vec::new();
+        for text in texts {
+            let content = Content {
+                operations: vec![
+                    Operation::new("BT", vec![]),
+                    Operation::new("Tf", vec!["F1".into(), 24.into()]),
+                    Operation::new("Td", vec![100.into(), 600.into()]),
+                    Operation::new("Tj", vec![Object::string_literal(*text)]),
+                    Operation::new("ET", vec![]),
+                ],
+            };
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+            });
+            kids.push(page_id.into());
+        }
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => texts.len() as u32,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc
+    }
+
+    #[test]
+    fn extracts_selected_pages() {
+        let doc = sample_pdf(&["first page", "second page", "third page"]);
+        let pages = parse_page_range("3,1-2", 3).unwrap();
+        let result = extract_pages(&doc, Path::new("spec.pdf"), 3, &pages);
+        assert_eq!(result.pages.len(), 3);
+        assert_eq!(result.pages[0].page, 3);
+        assert!(result.pages[0].text.contains("third page"));
+        assert!(result.pages[1].text.contains("first page"));
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn parses_page_ranges() {
+        assert_eq!(parse_page_range("2-", 4).unwrap(), vec![2, 3, 4]);
+        assert_eq!(parse_page_range("1, 1-2", 4).unwrap(), vec![1, 2]);
+        assert_eq!(parse_page_range("3-10", 4).unwrap(), vec![3, 4]);
+        assert!(parse_page_range("0", 4).is_err());
+        assert!(parse_page_range("5", 4).is_err());
+        assert!(parse_page_range("3-1", 4).is_err());
+        assert!(parse_page_range("a", 4).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! 插件 API 命令
+
+use crate::error::AxonError;
+use crate::opencode::{RateLimitRule, RateLimitSettings};
+use crate::plugin_api::{AgentOverride, PluginApiMetrics};
+use crate::state::AppState;
+use serde_json::json;
+use std::collections::HashMap;
+use tauri::State;
+
+/// 获取插件 API 运行指标（含各路由的限流计数）
+#[tauri::command]
+pub fn get_plugin_api_metrics(state: State<'_, AppState>) -> PluginApiMetrics {
+    state.plugin_api.read().metrics()
+}
+
+/// 获取插件 API 限流设置
+#[tauri::command]
+pub fn get_plugin_api_rate_limit(state: State<'_, AppState>) -> RateLimitSettings {
+    state.settings.get_plugin_api_rate_limit()
+}
+
+/// 更新插件 API 限流设置，立即生效
+#[tauri::command]
+pub fn set_plugin_api_rate_limit(
+    state: State<'_, AppState>,
+    rate_limit: RateLimitSettings,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "rateLimit": &rate_limit });
+    state
+        .audit
+        .track_sync("set_plugin_api_rate_limit", audit_args, || {
+            validate_rule("默认", &rate_limit.default_rule)?;
+            for (route, rule) in &rate_limit.routes {
+                validate_rule(route, rule)?;
+            }
+            // 限流器订阅了设置变化，保存后自动生效
+            state
+                .settings
+                .set_plugin_api_rate_limit(rate_limit)
+                .map_err(AxonError::from)
+        })
+}
+
+/// 获取会话的 Agent 参数覆盖
+#[tauri::command]
+pub fn get_session_agent_overrides(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> HashMap<String, AgentOverride> {
+    state
+        .plugin_api
+        .read()
+        .state()
+        .get_session_overrides(&session_id)
+}
+
+/// 设置会话中某个 Agent 的参数覆盖，只在内存中生效，不修改保存的 Agent 配置
+///
+/// 覆盖为空时移除该 Agent 的覆盖
+#[tauri::command]
+pub fn set_session_agent_override(
+    state: State<'_, AppState>,
+    session_id: String,
+    agent: String,
+    overrides: AgentOverride,
+) -> Result<HashMap<String, AgentOverride>, AxonError> {
+    let plugin_api = state.plugin_api.read().state().clone();
+    let audit_args = json!({ "sessionId": &session_id, "agent": &agent, "overrides": &overrides });
+    state
+        .audit
+        .track_sync("set_session_agent_override", audit_args, || {
+            plugin_api
+                .set_session_override(&session_id, &agent, overrides)
+                .map_err(AxonError::invalid_input)?;
+            Ok(plugin_api.get_session_overrides(&session_id))
+        })
+}
+
+/// 清除会话的 Agent 参数覆盖，返回是否存在覆盖
+#[tauri::command]
+pub fn clear_session_agent_overrides(state: State<'_, AppState>, session_id: String) -> bool {
+    state
+        .plugin_api
+        .read()
+        .state()
+        .clear_session_overrides(&session_id)
+}
+
+fn validate_rule(route: &str, rule: &RateLimitRule) -> Result<(), AxonError> {
+    if rule.burst == 0 || rule.per_second == 0 {
+        return Err(AxonError::invalid_input(format!(
+            "限流规则「{}」的突发数和速率必须大于 0",
+            route
+        )));
+    }
+    Ok(())
+}
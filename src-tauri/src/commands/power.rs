@@ -0,0 +1,40 @@
+//! 空闲与电源命令
+
+use crate::error::AxonError;
+use crate::opencode::PowerSettings;
+use crate::power::PowerState;
+use crate::state::AppState;
+use serde_json::json;
+use std::sync::Arc;
+use tauri::State;
+
+/// 获取当前电源与空闲状态（立即重新检测）
+#[tauri::command]
+pub async fn get_power_state(state: State<'_, AppState>) -> Result<PowerState, AxonError> {
+    let power = Arc::clone(&state.power);
+    Ok(tokio::task::spawn_blocking(move || power.refresh()).await?)
+}
+
+/// 获取节能设置
+#[tauri::command]
+pub fn get_power_settings(state: State<'_, AppState>) -> PowerSettings {
+    state.settings.get_power_settings()
+}
+
+/// 更新节能设置，返回按新设置判断后的状态
+#[tauri::command]
+pub fn set_power_settings(
+    state: State<'_, AppState>,
+    power: PowerSettings,
+) -> Result<PowerState, AxonError> {
+    let audit_args = json!({ "power": &power });
+    state
+        .audit
+        .track_sync("set_power_settings", audit_args, || {
+            state
+                .settings
+                .set_power_settings(power)
+                .map_err(AxonError::from)?;
+            Ok(state.power.reevaluate())
+        })
+}
@@ -0,0 +1,499 @@
+//! 项目信息检测命令
+//!
+//! 扫描项目根目录下的清单文件（package.json、Cargo.toml、pyproject.toml、go.mod 等），
+//! 识别语言、包管理器、可用脚本，并给出建议的安装/构建/测试/运行命令，
+//! 供编排界面预填 Agent 上下文。
+
+use crate::error::{AxonError, ErrorKind};
+use serde::Serialize;
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// 项目中的可运行任务（脚本 / Makefile 目标等）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTask {
+    /// 任务名称
+    pub name: String,
+    /// 完整的运行命令
+    pub command: String,
+    /// 来源文件（package.json / Makefile / pyproject.toml ...）
+    pub source: String,
+}
+
+/// 建议命令
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedCommands {
+    pub install: Option<String>,
+    pub build: Option<String>,
+    pub test: Option<String>,
+    pub run: Option<String>,
+    pub lint: Option<String>,
+}
+
+/// 项目工具链信息
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectInfo {
+    pub project_dir: String,
+    /// 项目名称（取自清单文件，没有时为目录名）
+    pub name: Option<String>,
+    /// 检测到的语言
+    pub languages: Vec<String>,
+    /// 检测到的包管理器/构建工具
+    pub package_managers: Vec<String>,
+    /// 检测到的框架
+    pub frameworks: Vec<String>,
+    /// 找到的清单文件
+    pub manifests: Vec<String>,
+    /// 可运行的任务
+    pub tasks: Vec<ProjectTask>,
+    /// 建议命令
+    pub suggested_commands: SuggestedCommands,
+}
+
+impl ProjectInfo {
+    fn add_language(&mut self, language: &str) {
+        push_unique(&mut self.languages, language);
+    }
+
+    fn add_package_manager(&mut self, manager: &str) {
+        push_unique(&mut self.package_managers, manager);
+    }
+
+    fn add_framework(&mut self, framework: &str) {
+        push_unique(&mut self.frameworks, framework);
+    }
+}
+
+/// 检测项目工具链信息
+#[tauri::command]
+pub async fn detect_project_info(project_dir: String) -> Result<ProjectInfo, AxonError> {
+    debug!("检测项目信息: {}", project_dir);
+
+    let root = Path::new(&project_dir);
+    if !root.is_dir() {
+        return Err(AxonError::localized(
+            ErrorKind::NotFound,
+            "project.dir_not_found",
+            serde_json::json!({ "path": project_dir }),
+        ));
+    }
+
+    let info = collect_project_info(root);
+    debug!(
+        "项目信息检测完成: 语言 {:?}, 包管理器 {:?}, {} 个任务",
+        info.languages,
+        info.package_managers,
+        info.tasks.len()
+    );
+    Ok(info)
+}
+
+/// 同步检测项目信息（供其他模块复用）
+pub(crate) fn collect_project_info(root: &Path) -> ProjectInfo {
+    let mut info = ProjectInfo {
+        project_dir: root.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+
+    detect_node(root, &mut info);
+    detect_rust(root, &mut info);
+    detect_python(root, &mut info);
+    detect_go(root, &mut info);
+    detect_jvm(root, &mut info);
+    detect_makefile(root, &mut info);
+
+    if info.name.is_none() {
+        info.name = root.file_name().map(|n| n.to_string_lossy().to_string());
+    }
+
+    info
+}
+
+// ============================================================================
+// 各生态检测
+// ============================================================================
+
+fn detect_node(root: &Path, info: &mut ProjectInfo) {
+    let Some(manifest) = read_json(&root.join("package.json")) else {
+        return;
+    };
+    info.manifests.push("package.json".to_string());
+
+    let is_typescript =
+        root.join("tsconfig.json").exists() || has_dependency(&manifest, "typescript");
+    info.add_language(if is_typescript {
+        "typescript"
+    } else {
+        "javascript"
+    });
+
+    if info.name.is_none() {
+        info.name = manifest["name"].as_str().map(String::from);
+    }
+
+    // 优先使用 packageManager 字段，其次根据锁文件判断
+    let manager = manifest["packageManager"]
+        .as_str()
+        .and_then(|pm| pm.split('@').next())
+        .map(String::from)
+        .or_else(|| {
+            [
+                ("pnpm-lock.yaml", "pnpm"),
+                ("yarn.lock", "yarn"),
+                ("bun.lockb", "bun"),
+                ("bun.lock", "bun"),
+                ("package-lock.json", "npm"),
+            ]
+            .iter()
+            .find(|(lock, _)| root.join(lock).exists())
+            .map(|(_, pm)| pm.to_string())
+        })
+        .unwrap_or_else(|| "npm".to_string());
+    info.add_package_manager(&manager);
+
+    for (dependency, framework) in [
+        ("react", "react"),
+        ("vue", "vue"),
+        ("svelte", "svelte"),
+        ("next", "next"),
+        ("nuxt", "nuxt"),
+        ("vite", "vite"),
+        ("@tauri-apps/api", "tauri"),
+        ("electron", "electron"),
+        ("express", "express"),
+    ] {
+        if has_dependency(&manifest, dependency) {
+            info.add_framework(framework);
+        }
+    }
+
+    let run_script = |name: &str| match manager.as_str() {
+        "npm" => format!("npm run {}", name),
+        other => format!("{} {}", other, name),
+    };
+
+    if let Some(scripts) = manifest["scripts"].as_object() {
+        for name in scripts.keys() {
+            info.tasks.push(ProjectTask {
+                name: name.clone(),
+                command: run_script(name),
+                source: "package.json".to_string(),
+            });
+        }
+
+        let has = |name: &str| scripts.contains_key(name);
+        let suggested = &mut info.suggested_commands;
+        suggested
+            .install
+            .get_or_insert_with(|| format!("{} install", manager));
+        if has("build") {
+            suggested.build.get_or_insert_with(|| run_script("build"));
+        }
+        if has("test") {
+            suggested.test.get_or_insert_with(|| run_script("test"));
+        }
+        if let Some(run) = ["dev", "start", "serve"].into_iter().find(|s| has(s)) {
+            suggested.run.get_or_insert_with(|| run_script(run));
+        }
+        if has("lint") {
+            suggested.lint.get_or_insert_with(|| run_script("lint"));
+        }
+    } else {
+        info.suggested_commands
+            .install
+            .get_or_insert_with(|| format!("{} install", manager));
+    }
+}
+
+fn detect_rust(root: &Path, info: &mut ProjectInfo) {
+    let Some(manifest) = read_toml(&root.join("Cargo.toml")) else {
+        return;
+    };
+    info.manifests.push("Cargo.toml".to_string());
+    info.add_language("rust");
+    info.add_package_manager("cargo");
+
+    if info.name.is_none() {
+        info.name = manifest
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .map(String::from);
+    }
+
+    let is_workspace = manifest.contains_key("workspace");
+    let suggested = &mut info.suggested_commands;
+    suggested.build.get_or_insert_with(|| {
+        if is_workspace {
+            "cargo build --workspace".to_string()
+        } else {
+            "cargo build".to_string()
+        }
+    });
+    suggested.test.get_or_insert_with(|| {
+        if is_workspace {
+            "cargo test --workspace".to_string()
+        } else {
+            "cargo test".to_string()
+        }
+    });
+    suggested
+        .lint
+        .get_or_insert_with(|| "cargo clippy --all-targets".to_string());
+    if manifest.contains_key("package") {
+        suggested.run.get_or_insert_with(|| "cargo run".to_string());
+    }
+
+    if let Some(bins) = manifest.get("bin").and_then(|b| b.as_array()) {
+        for name in bins
+            .iter()
+            .filter_map(|b| b.get("name").and_then(|n| n.as_str()))
+        {
+            info.tasks.push(ProjectTask {
+                name: name.to_string(),
+                command: format!("cargo run --bin {}", name),
+                source: "Cargo.toml".to_string(),
+            });
+        }
+    }
+}
+
+fn detect_python(root: &Path, info: &mut ProjectInfo) {
+    let pyproject = read_toml(&root.join("pyproject.toml"));
+    let has_requirements = root.join("requirements.txt").exists();
+    let has_setup = root.join("setup.py").exists();
+    let has_pipfile = root.join("Pipfile").exists();
+
+    if pyproject.is_none() && !has_requirements && !has_setup && !has_pipfile {
+        return;
+    }
+    info.add_language("python");
+
+    let tool = pyproject.as_ref().and_then(|p| p.get("tool"));
+    let manager = if root.join("uv.lock").exists() {
+        "uv"
+    } else if root.join("poetry.lock").exists() || tool.and_then(|t| t.get("poetry")).is_some() {
+        "poetry"
+    } else if has_pipfile {
+        "pipenv"
+    } else {
+        "pip"
+    };
+    info.add_package_manager(manager);
+
+    if let Some(pyproject) = &pyproject {
+        info.manifests.push("pyproject.toml".to_string());
+        if info.name.is_none() {
+            info.name = pyproject
+                .get("project")
+                .and_then(|p| p.get("name"))
+                .and_then(|n| n.as_str())
+                .map(String::from);
+        }
+
+        // [project.scripts] 定义的命令行入口
+        if let Some(scripts) = pyproject
+            .get("project")
+            .and_then(|p| p.get("scripts"))
+            .and_then(|s| s.as_table())
+        {
+            for name in scripts.keys() {
+                let command = match manager {
+                    "uv" => format!("uv run {}", name),
+                    "poetry" => format!("poetry run {}", name),
+                    _ => name.clone(),
+                };
+                info.tasks.push(ProjectTask {
+                    name: name.clone(),
+                    command,
+                    source: "pyproject.toml".to_string(),
+                });
+            }
+        }
+        if tool.and_then(|t| t.get("ruff")).is_some() {
+            info.suggested_commands
+                .lint
+                .get_or_insert_with(|| "ruff check .".to_string());
+        }
+    }
+    if has_requirements {
+        info.manifests.push("requirements.txt".to_string());
+    }
+
+    let suggested = &mut info.suggested_commands;
+    suggested.install.get_or_insert_with(|| {
+        match manager {
+            "uv" => "uv sync",
+            "poetry" => "poetry install",
+            "pipenv" => "pipenv install",
+            _ if has_requirements => "pip install -r requirements.txt",
+            _ => "pip install -e .",
+        }
+        .to_string()
+    });
+    suggested.test.get_or_insert_with(|| {
+        match manager {
+            "uv" => "uv run pytest",
+            "poetry" => "poetry run pytest",
+            _ => "pytest",
+        }
+        .to_string()
+    });
+}
+
+fn detect_go(root: &Path, info: &mut ProjectInfo) {
+    let Ok(content) = std::fs::read_to_string(root.join("go.mod")) else {
+        return;
+    };
+    info.manifests.push("go.mod".to_string());
+    info.add_language("go");
+    info.add_package_manager("go");
+
+    if info.name.is_none() {
+        info.name = content
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("module "))
+            .map(|m| m.trim().to_string());
+    }
+
+    let suggested = &mut info.suggested_commands;
+    suggested
+        .install
+        .get_or_insert_with(|| "go mod download".to_string());
+    suggested
+        .build
+        .get_or_insert_with(|| "go build ./...".to_string());
+    suggested
+        .test
+        .get_or_insert_with(|| "go test ./...".to_string());
+    suggested
+        .lint
+        .get_or_insert_with(|| "go vet ./...".to_string());
+    if root.join("main.go").exists() {
+        suggested.run.get_or_insert_with(|| "go run .".to_string());
+    }
+}
+
+fn detect_jvm(root: &Path, info: &mut ProjectInfo) {
+    if root.join("pom.xml").exists() {
+        info.manifests.push("pom.xml".to_string());
+        info.add_language("java");
+        info.add_package_manager("maven");
+        let suggested = &mut info.suggested_commands;
+        suggested
+            .build
+            .get_or_insert_with(|| "mvn package".to_string());
+        suggested.test.get_or_insert_with(|| "mvn test".to_string());
+    }
+
+    let gradle = ["build.gradle.kts", "build.gradle"]
+        .into_iter()
+        .find(|f| root.join(f).exists());
+    if let Some(gradle) = gradle {
+        info.manifests.push(gradle.to_string());
+        info.add_language(if gradle.ends_with(".kts") {
+            "kotlin"
+        } else {
+            "java"
+        });
+        info.add_package_manager("gradle");
+
+        let wrapper = if root.join("gradlew").exists() {
+            "./gradlew"
+        } else {
+            "gradle"
+        };
+        let suggested = &mut info.suggested_commands;
+        suggested
+            .build
+            .get_or_insert_with(|| format!("{} build", wrapper));
+        suggested
+            .test
+            .get_or_insert_with(|| format!("{} test", wrapper));
+    }
+}
+
+fn detect_makefile(root: &Path, info: &mut ProjectInfo) {
+    let Ok(content) = std::fs::read_to_string(root.join("Makefile")) else {
+        return;
+    };
+    info.manifests.push("Makefile".to_string());
+    info.add_package_manager("make");
+
+    // 仅识别形如 `target:` 的简单目标，跳过变量赋值和特殊目标
+    for line in content.lines() {
+        if line.starts_with(['\t', ' ', '#', '.']) {
+            continue;
+        }
+        let Some((target, rest)) = line.split_once(':') else {
+            continue;
+        };
+        let target = target.trim();
+        if rest.starts_with('=')
+            || target.is_empty()
+            || target.contains(['$', '%', ' ', '='])
+            || info
+                .tasks
+                .iter()
+                .any(|t| t.source == "Makefile" && t.name == target)
+        {
+            continue;
+        }
+
+        info.tasks.push(ProjectTask {
+            name: target.to_string(),
+            command: format!("make {}", target),
+            source: "Makefile".to_string(),
+        });
+    }
+
+    let has_target = |name: &str| {
+        info.tasks
+            .iter()
+            .any(|t| t.source == "Makefile" && t.name == name)
+    };
+    let (build, test) = (has_target("build"), has_target("test"));
+    if build {
+        info.suggested_commands
+            .build
+            .get_or_insert_with(|| "make build".to_string());
+    }
+    if test {
+        info.suggested_commands
+            .test
+            .get_or_insert_with(|| "make test".to_string());
+    }
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn push_unique(list: &mut Vec<String>, value: &str) {
+    if !list.iter().any(|v| v == value) {
+        list.push(value.to_string());
+    }
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| warn!("解析 {:?} 失败: {}", path, e))
+        .ok()
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    let content = std::fs::read_to_string(path).ok()?;
+    toml::from_str(&content)
+        .map_err(|e| warn!("解析 {:?} 失败: {}", path, e))
+        .ok()
+}
+
+fn has_dependency(manifest: &serde_json::Value, name: &str) -> bool {
+    ["dependencies", "devDependencies", "peerDependencies"]
+        .iter()
+        .any(|section| manifest[section].get(name).is_some())
+}
@@ -1,84 +1,29 @@
-use crate::opencode::UserProviderConfig;
+use crate::error::{AxonError, ErrorKind};
+use crate::opencode::auth::{
+    provider_auth_statuses, read_auth_json, write_auth_json, ProviderAuthStatus,
+};
+use crate::opencode::{is_model_allowed, ModelFilter, ProviderAuth, UserProviderConfig};
 use crate::state::AppState;
 use crate::utils::paths::get_app_data_dir;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 use tauri::State;
 use tracing::{debug, info};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProviderAuthStatus {
-    /// Provider ID
-    pub provider_id: String,
-    /// 是否已认证
-    pub authenticated: bool,
-    /// 认证类型 (api/oauth/wellknown)
-    pub auth_type: Option<String>,
-}
-
-/// 获取 auth.json 文件路径
-fn get_auth_json_path() -> Result<std::path::PathBuf, String> {
-    let app_data_dir = get_app_data_dir()
-        .ok_or_else(|| "应用数据目录未初始化".to_string())?;
-    // OpenCode 的 auth.json 位于 <app_data_dir>/opencode/auth.json
-    // 因为 Axon 设置了 XDG_DATA_HOME=app_data_dir，
-    // xdg-basedir 会在其下创建 /opencode 子目录
-    Ok(app_data_dir.join("opencode").join("auth.json"))
-}
-
 /// 获取 config.json 文件路径
-fn get_config_json_path() -> Result<std::path::PathBuf, String> {
+fn get_config_json_path() -> Result<std::path::PathBuf, AxonError> {
     let app_data_dir = get_app_data_dir()
-        .ok_or_else(|| "应用数据目录未初始化".to_string())?;
+        .ok_or_else(|| {
+            AxonError::localized(ErrorKind::Unavailable, "app.data_dir_unavailable", json!(null))
+        })?;
     // OpenCode 的 config.json 位于 <app_data_dir>/opencode/config.json
     Ok(app_data_dir.join("opencode").join("config.json"))
 }
 
-/// 读取 auth.json 内容
-fn read_auth_json() -> Result<serde_json::Value, String> {
-    let auth_path = get_auth_json_path()?;
-    
-    if !auth_path.exists() {
-        debug!("auth.json 不存在，返回空对象");
-        return Ok(serde_json::json!({}));
-    }
-    
-    let content = std::fs::read_to_string(&auth_path)
-        .map_err(|e| format!("读取 auth.json 失败: {}", e))?;
-    
-    serde_json::from_str(&content)
-        .map_err(|e| format!("解析 auth.json 失败: {}", e))
-}
-
-/// 写入 auth.json 内容
-fn write_auth_json(data: &serde_json::Value) -> Result<(), String> {
-    let auth_path = get_auth_json_path()?;
-    
-    // 确保目录存在
-    if let Some(parent) = auth_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("创建 auth.json 目录失败: {}", e))?;
-    }
-    
-    let content = serde_json::to_string_pretty(data)
-        .map_err(|e| format!("序列化 auth.json 失败: {}", e))?;
-    
-    std::fs::write(&auth_path, content)
-        .map_err(|e| format!("写入 auth.json 失败: {}", e))?;
-    
-    // 设置文件权限为 600（仅所有者可读写）- 仅 Unix 系统
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let permissions = std::fs::Permissions::from_mode(0o600);
-        std::fs::set_permissions(&auth_path, permissions)
-            .map_err(|e| format!("设置 auth.json 权限失败: {}", e))?;
-    }
-    
-    Ok(())
-}
-
 /// 读取 config.json 内容
-fn read_config_json() -> Result<serde_json::Value, String> {
+pub(crate) fn read_config_json() -> Result<serde_json::Value, AxonError> {
     let config_path = get_config_json_path()?;
     
     if !config_path.exists() {
@@ -87,27 +32,27 @@ fn read_config_json() -> Result<serde_json::Value, String> {
     }
     
     let content = std::fs::read_to_string(&config_path)
-        .map_err(|e| format!("读取 config.json 失败: {}", e))?;
+        .map_err(|e| AxonError::io("读取 config.json 失败", &e))?;
     
     serde_json::from_str(&content)
-        .map_err(|e| format!("解析 config.json 失败: {}", e))
+        .map_err(|e| AxonError::invalid_data(format!("解析 config.json 失败: {}", e)))
 }
 
 /// 写入 config.json 内容
-fn write_config_json(data: &serde_json::Value) -> Result<(), String> {
+pub(crate) fn write_config_json(data: &serde_json::Value) -> Result<(), AxonError> {
     let config_path = get_config_json_path()?;
     
     // 确保目录存在
     if let Some(parent) = config_path.parent() {
         std::fs::create_dir_all(parent)
-            .map_err(|e| format!("创建 config.json 目录失败: {}", e))?;
+            .map_err(|e| AxonError::io("创建 config.json 目录失败", &e))?;
     }
     
     let content = serde_json::to_string_pretty(data)
         .map_err(|e| format!("序列化 config.json 失败: {}", e))?;
     
     std::fs::write(&config_path, content)
-        .map_err(|e| format!("写入 config.json 失败: {}", e))?;
+        .map_err(|e| AxonError::io("写入 config.json 失败", &e))?;
     
     Ok(())
 }
@@ -120,42 +65,46 @@ fn write_config_json(data: &serde_json::Value) -> Result<(), String> {
 ///
 /// 注意：前端调用后应该调用 client.instance.dispose() 刷新 OpenCode 缓存
 #[tauri::command]
-pub async fn remove_provider_auth(provider_id: String) -> Result<(), String> {
-    info!("删除 provider 认证和配置: {}", provider_id);
-
-    // 1. 删除 auth.json 中的认证信息
-    let mut auth_data = read_auth_json()?;
-    if let Some(obj) = auth_data.as_object_mut() {
-        if obj.remove(&provider_id).is_some() {
-            write_auth_json(&auth_data)?;
-            info!("已删除 provider {} 的认证信息 (auth.json)", provider_id);
-        } else {
-            debug!("provider {} 的认证信息不存在 (auth.json)", provider_id);
+pub async fn remove_provider_auth(state: State<'_, AppState>, provider_id: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "providerId": &provider_id });
+    state.audit.track("remove_provider_auth", audit_args, async {
+        info!("删除 provider 认证和配置: {}", provider_id);
+
+        // 1. 删除 auth.json 中的认证信息
+        let mut auth_data = read_auth_json()?;
+        if let Some(obj) = auth_data.as_object_mut() {
+            if obj.remove(&provider_id).is_some() {
+                write_auth_json(&auth_data)?;
+                info!("已删除 provider {} 的认证信息 (auth.json)", provider_id);
+            } else {
+                debug!("provider {} 的认证信息不存在 (auth.json)", provider_id);
+            }
         }
-    }
 
-    // 2. 删除 config.json 中的整个 provider 配置项
-    let mut config_data = read_config_json()?;
-    if let Some(config_obj) = config_data.as_object_mut() {
-        if let Some(provider_section) = config_obj.get_mut("provider") {
-            if let Some(provider_obj) = provider_section.as_object_mut() {
-                if provider_obj.remove(&provider_id).is_some() {
-                    write_config_json(&config_data)?;
-                    info!("已删除 provider {} 的全部配置 (config.json)", provider_id);
-                } else {
-                    debug!("provider {} 在 config.json 中不存在", provider_id);
+        // 2. 删除 config.json 中的整个 provider 配置项
+        let mut config_data = read_config_json()?;
+        if let Some(config_obj) = config_data.as_object_mut() {
+            if let Some(provider_section) = config_obj.get_mut("provider") {
+                if let Some(provider_obj) = provider_section.as_object_mut() {
+                    if provider_obj.remove(&provider_id).is_some() {
+                        write_config_json(&config_data)?;
+                        info!("已删除 provider {} 的全部配置 (config.json)", provider_id);
+                    } else {
+                        debug!("provider {} 在 config.json 中不存在", provider_id);
+                    }
                 }
             }
         }
-    }
 
-    info!("provider {} 的认证和配置清理完成", provider_id);
-    Ok(())
+        info!("provider {} 的认证和配置清理完成", provider_id);
+        Ok(())
+    })
+    .await
 }
 
 /// 获取指定 provider 的认证状态
 #[tauri::command]
-pub async fn get_provider_auth_status(provider_id: String) -> Result<ProviderAuthStatus, String> {
+pub async fn get_provider_auth_status(provider_id: String) -> Result<ProviderAuthStatus, AxonError> {
     let auth_data = read_auth_json()?;
     
     let (authenticated, auth_type) = if let Some(auth_info) = auth_data.get(&provider_id) {
@@ -176,37 +125,24 @@ pub async fn get_provider_auth_status(provider_id: String) -> Result<ProviderAut
 
 /// 获取所有已认证的 provider 列表
 #[tauri::command]
-pub async fn get_all_provider_auth_status() -> Result<Vec<ProviderAuthStatus>, String> {
+pub async fn get_all_provider_auth_status() -> Result<Vec<ProviderAuthStatus>, AxonError> {
     let auth_data = read_auth_json()?;
-    
-    let mut statuses = Vec::new();
-    
-    if let Some(obj) = auth_data.as_object() {
-        for (provider_id, auth_info) in obj {
-            let auth_type = auth_info.get("type")
-                .and_then(|v| v.as_str())
-                .map(String::from);
-            
-            statuses.push(ProviderAuthStatus {
-                provider_id: provider_id.clone(),
-                authenticated: true,
-                auth_type,
-            });
-        }
-    }
-    
-    Ok(statuses)
+    Ok(provider_auth_statuses(&auth_data))
 }
 
 #[tauri::command]
 pub async fn add_user_provider(
     state: State<'_, AppState>,
     config: UserProviderConfig,
-) -> Result<(), String> {
-    let mut settings = state.settings.get_settings();
-    settings.providers.push(config);
-    state.settings.set_settings(settings)?;
-    Ok(())
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "id": &config.id, "registryId": &config.registry_id });
+    state.audit.track("add_user_provider", audit_args, async {
+        let mut settings = state.settings.get_settings();
+        settings.providers.push(config);
+        state.settings.set_settings(settings)?;
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
@@ -214,59 +150,388 @@ pub async fn update_user_provider(
     state: State<'_, AppState>,
     id: String,
     updates: serde_json::Value,
-) -> Result<(), String> {
-    use crate::opencode::{CustomConfig, ProviderAuth};
-    
-    let mut settings = state.settings.get_settings();
-    
-    let provider = settings
-        .providers
-        .iter_mut()
-        .find(|p| p.id == id)
-        .ok_or_else(|| "Provider not found".to_string())?;
-    
-    // 更新名称
-    if let Some(name) = updates.get("name").and_then(|v| v.as_str()) {
-        provider.name = name.to_string();
-    }
-    
-    // 更新认证信息
-    if let Some(auth_value) = updates.get("auth") {
-        if let Ok(auth) = serde_json::from_value::<ProviderAuth>(auth_value.clone()) {
-            provider.auth = auth;
+) -> Result<(), AxonError> {
+    let audit_args = json!({
+        "id": &id,
+        "fields": updates.as_object().map(|o| o.keys().collect::<Vec<_>>()),
+    });
+    state.audit.track("update_user_provider", audit_args, async {
+        use crate::opencode::CustomConfig;
+
+        let mut settings = state.settings.get_settings();
+
+        let provider = settings
+            .providers
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| AxonError::not_found("Provider not found"))?;
+
+        // 更新名称
+        if let Some(name) = updates.get("name").and_then(|v| v.as_str()) {
+            provider.name = name.to_string();
         }
-    }
-    
-    // 更新自定义配置
-    if let Some(custom_config_value) = updates.get("customConfig") {
-        if custom_config_value.is_null() {
-            provider.custom_config = None;
-        } else if let Ok(custom_config) = serde_json::from_value::<CustomConfig>(custom_config_value.clone()) {
-            provider.custom_config = Some(custom_config);
+
+        // 更新认证信息
+        if let Some(auth_value) = updates.get("auth") {
+            if let Ok(auth) = serde_json::from_value::<ProviderAuth>(auth_value.clone()) {
+                provider.auth = auth;
+            }
         }
-    }
-    
-    provider.updated_at = chrono::Utc::now().to_rfc3339();
-    
-    state.settings.set_settings(settings)?;
-    Ok(())
+
+        // 更新自定义配置
+        if let Some(custom_config_value) = updates.get("customConfig") {
+            if custom_config_value.is_null() {
+                provider.custom_config = None;
+            } else if let Ok(custom_config) = serde_json::from_value::<CustomConfig>(custom_config_value.clone()) {
+                provider.custom_config = Some(custom_config);
+            }
+        }
+
+        provider.updated_at = chrono::Utc::now().to_rfc3339();
+
+        state.settings.set_settings(settings)?;
+        Ok(())
+    })
+    .await
 }
 
 #[tauri::command]
 pub async fn remove_user_provider(
     state: State<'_, AppState>,
     id: String,
-) -> Result<(), String> {
-    let mut settings = state.settings.get_settings();
-    settings.providers.retain(|p| p.id != id);
-    state.settings.set_settings(settings)?;
-    Ok(())
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "id": &id });
+    state.audit.track("remove_user_provider", audit_args, async {
+        let mut settings = state.settings.get_settings();
+        settings.providers.retain(|p| p.id != id);
+        state.settings.set_settings(settings)?;
+        Ok(())
+    })
+    .await
+}
+
+/// 获取按服务商配置的模型过滤规则
+#[tauri::command]
+pub fn get_model_filters(state: State<'_, AppState>) -> BTreeMap<String, ModelFilter> {
+    state.settings.get_model_filters()
 }
 
+/// 设置按服务商配置的模型过滤规则（键为 provider ID）
+#[tauri::command]
+pub fn set_model_filters(
+    state: State<'_, AppState>,
+    filters: BTreeMap<String, ModelFilter>,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "filters": &filters });
+    state.audit.track_sync("set_model_filters", audit_args, || {
+        for (provider_id, filter) in &filters {
+            if provider_id.trim().is_empty() || provider_id.contains('/') {
+                return Err(AxonError::invalid_input(format!(
+                    "无效的 provider ID: {:?}",
+                    provider_id
+                )));
+            }
+            for pattern in filter.allow.iter().chain(&filter.deny) {
+                let body = pattern.strip_suffix('*').unwrap_or(pattern);
+                if pattern.trim().is_empty() || body.contains('*') {
+                    return Err(AxonError::invalid_input(format!(
+                        "无效的模型规则: {:?}（只能在末尾使用 *）",
+                        pattern
+                    )));
+                }
+            }
+        }
+        state.settings.set_model_filters(filters).map_err(AxonError::from)
+    })
+}
+
+/// 按模型过滤规则筛选 `provider/model` 格式的模型列表，保持原有顺序
+#[tauri::command]
+pub fn filter_allowed_models(state: State<'_, AppState>, models: Vec<String>) -> Vec<String> {
+    let filters = state.settings.get_model_filters();
+    models
+        .into_iter()
+        .filter(|model| is_model_allowed(&filters, model))
+        .collect()
+}
+
+/// 连接测试超时时间
+const CONNECTION_TEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 连接测试结果状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionTestStatus {
+    /// 连接正常，凭据有效
+    Ok,
+    /// 凭据无效（401）
+    InvalidKey,
+    /// 凭据有效但无权限（403）
+    Forbidden,
+    /// 额度不足或账户欠费
+    QuotaExceeded,
+    /// 请求过于频繁（凭据本身有效）
+    RateLimited,
+    /// 端点不存在（通常是 baseURL 配置错误）
+    EndpointNotFound,
+    /// 服务端错误
+    ServerError,
+    /// 网络错误（DNS、连接失败、TLS 等）
+    NetworkError,
+    /// 请求超时
+    Timeout,
+    /// 缺少凭据或无法确定端点
+    NotConfigured,
+    Unknown,
+}
+
+/// 连接测试结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTestResult {
+    pub success: bool,
+    pub status: ConnectionTestStatus,
+    /// HTTP 状态码（未收到响应时为空）
+    pub http_status: Option<u16>,
+    /// 面向用户的说明
+    pub message: String,
+    /// 实际请求的端点
+    pub endpoint: Option<String>,
+    pub latency_ms: u64,
+}
+
+impl ConnectionTestResult {
+    fn failure(status: ConnectionTestStatus, message: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            status,
+            http_status: None,
+            message: message.into(),
+            endpoint: None,
+            latency_ms: 0,
+        }
+    }
+}
+
+/// 服务商 API 风格（决定请求路径和认证头）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProviderApiStyle {
+    Anthropic,
+    Google,
+    OpenAiCompatible,
+}
+
+/// 服务商 API 地址与凭据
+#[derive(Debug, Clone)]
+pub(crate) struct ProviderEndpoint {
+    pub style: ProviderApiStyle,
+    pub base_url: String,
+    pub credential: String,
+    /// 自定义配置中的额外请求头
+    pub headers: Vec<(String, String)>,
+}
+
+/// 根据服务商配置、模型注册表和 auth.json 确定 API 地址与凭据
+///
+/// 无法确定时返回面向用户的说明
+pub(crate) fn resolve_provider_endpoint(
+    state: &AppState,
+    provider: &UserProviderConfig,
+) -> Result<ProviderEndpoint, &'static str> {
+    let registry = state
+        .models_registry
+        .get_providers()
+        .into_iter()
+        .find(|p| p.id == provider.registry_id);
+
+    let style = api_style(&provider.registry_id, registry.as_ref().and_then(|r| r.npm.as_deref()));
+    let custom = provider.custom_config.as_ref();
+
+    let base_url = custom
+        .and_then(|c| c.base_url.clone())
+        .or_else(|| registry.as_ref().and_then(|r| r.api.clone()))
+        .or_else(|| default_base_url(&provider.registry_id).map(String::from))
+        .ok_or("无法确定服务商 API 地址，请在自定义配置中填写 baseURL")?;
+
+    let credential = match &provider.auth {
+        ProviderAuth::Api { key } if !key.is_empty() => Some(key.clone()),
+        _ => None,
+    }
+    .or_else(|| custom.and_then(|c| c.api_key.clone()))
+    .or_else(|| read_oauth_access_token(&provider.registry_id))
+    .ok_or("未配置 API Key 或授权信息")?;
+
+    let headers = custom
+        .and_then(|c| c.headers.as_ref())
+        .map(|h| h.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default();
+
+    Ok(ProviderEndpoint {
+        style,
+        base_url: base_url.trim_end_matches('/').to_string(),
+        credential,
+        headers,
+    })
+}
+
+/// 测试服务商连接
+///
+/// 使用已保存的凭据向服务商发送一个最小的鉴权请求（列出模型），
+/// 并将响应映射为结构化结果
 #[tauri::command]
 pub async fn test_provider_connection(
-    _state: State<'_, AppState>,
-    _id: String,
-) -> Result<bool, String> {
-    Ok(true)
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<ConnectionTestResult, AxonError> {
+    let provider = state
+        .settings
+        .get_settings()
+        .providers
+        .into_iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| AxonError::not_found("Provider not found"))?;
+
+    info!("测试 provider 连接: {} ({})", provider.name, provider.registry_id);
+
+    let ProviderEndpoint { style, base_url, credential, headers } =
+        match resolve_provider_endpoint(&state, &provider) {
+            Ok(endpoint) => endpoint,
+            Err(message) => {
+                return Ok(ConnectionTestResult::failure(
+                    ConnectionTestStatus::NotConfigured,
+                    message,
+                ))
+            }
+        };
+
+    let endpoint = format!("{}/models", base_url);
+    let client = reqwest::Client::builder()
+        .timeout(CONNECTION_TEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+
+    let mut request = client.get(&endpoint);
+    request = match style {
+        ProviderApiStyle::Anthropic => request
+            .header("x-api-key", &credential)
+            .header("anthropic-version", "2023-06-01"),
+        ProviderApiStyle::Google => request.header("x-goog-api-key", &credential),
+        ProviderApiStyle::OpenAiCompatible => request.bearer_auth(&credential),
+    };
+    for (name, value) in &headers {
+        request = request.header(name, value);
+    }
+
+    let started = Instant::now();
+    let response = request.send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let result = match response {
+        Ok(response) => {
+            let http_status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            let (status, message) = classify_response(http_status, &body);
+            ConnectionTestResult {
+                success: status == ConnectionTestStatus::Ok,
+                status,
+                http_status: Some(http_status.as_u16()),
+                message,
+                endpoint: Some(endpoint),
+                latency_ms,
+            }
+        }
+        Err(e) => {
+            let (status, message) = if e.is_timeout() {
+                (
+                    ConnectionTestStatus::Timeout,
+                    format!("请求超时（{} 秒）", CONNECTION_TEST_TIMEOUT.as_secs()),
+                )
+            } else {
+                (ConnectionTestStatus::NetworkError, format!("网络错误: {}", e))
+            };
+            ConnectionTestResult {
+                success: false,
+                status,
+                http_status: None,
+                message,
+                endpoint: Some(endpoint),
+                latency_ms,
+            }
+        }
+    };
+
+    info!(
+        "provider {} 连接测试结果: {:?} ({}ms)",
+        provider.registry_id, result.status, result.latency_ms
+    );
+    Ok(result)
+}
+
+/// 根据服务商 ID 和 npm 包判断 API 风格
+fn api_style(registry_id: &str, npm: Option<&str>) -> ProviderApiStyle {
+    match (registry_id, npm) {
+        ("anthropic", _) | (_, Some("@ai-sdk/anthropic")) => ProviderApiStyle::Anthropic,
+        ("google", _) | (_, Some("@ai-sdk/google")) => ProviderApiStyle::Google,
+        _ => ProviderApiStyle::OpenAiCompatible,
+    }
+}
+
+/// 注册表未提供 api 字段的常见服务商的默认地址
+fn default_base_url(registry_id: &str) -> Option<&'static str> {
+    match registry_id {
+        "openai" => Some("https://api.openai.com/v1"),
+        "anthropic" => Some("https://api.anthropic.com/v1"),
+        "google" => Some("https://generativelanguage.googleapis.com/v1beta"),
+        "deepseek" => Some("https://api.deepseek.com/v1"),
+        "mistral" => Some("https://api.mistral.ai/v1"),
+        "groq" => Some("https://api.groq.com/openai/v1"),
+        "xai" => Some("https://api.x.ai/v1"),
+        "openrouter" => Some("https://openrouter.ai/api/v1"),
+        _ => None,
+    }
+}
+
+/// 从 auth.json 读取 OAuth access token
+fn read_oauth_access_token(registry_id: &str) -> Option<String> {
+    let auth_data = read_auth_json().ok()?;
+    let entry = auth_data.get(registry_id)?;
+    match entry.get("type").and_then(|v| v.as_str()) {
+        Some("oauth") => entry.get("access").and_then(|v| v.as_str()).map(String::from),
+        Some("api") => entry.get("key").and_then(|v| v.as_str()).map(String::from),
+        _ => None,
+    }
+}
+
+/// 将 HTTP 响应映射为测试结果
+fn classify_response(status: reqwest::StatusCode, body: &str) -> (ConnectionTestStatus, String) {
+    let body_lower = body.to_lowercase();
+    let mentions_quota = ["quota", "insufficient", "billing", "balance", "credit"]
+        .iter()
+        .any(|k| body_lower.contains(k));
+
+    let code = status.as_u16();
+    let kind = match code {
+        200..=299 => ConnectionTestStatus::Ok,
+        401 => ConnectionTestStatus::InvalidKey,
+        402 => ConnectionTestStatus::QuotaExceeded,
+        403 | 429 if mentions_quota => ConnectionTestStatus::QuotaExceeded,
+        403 => ConnectionTestStatus::Forbidden,
+        404 => ConnectionTestStatus::EndpointNotFound,
+        429 => ConnectionTestStatus::RateLimited,
+        500..=599 => ConnectionTestStatus::ServerError,
+        _ => ConnectionTestStatus::Unknown,
+    };
+
+    let message = match kind {
+        ConnectionTestStatus::Ok => "连接成功".to_string(),
+        ConnectionTestStatus::InvalidKey => "API Key 无效或已过期".to_string(),
+        ConnectionTestStatus::QuotaExceeded => "账户额度不足或余额不足".to_string(),
+        ConnectionTestStatus::Forbidden => "无访问权限（可能是地区或账户限制）".to_string(),
+        ConnectionTestStatus::EndpointNotFound => "接口不存在，请检查 baseURL 配置".to_string(),
+        ConnectionTestStatus::RateLimited => "请求过于频繁，凭据有效".to_string(),
+        ConnectionTestStatus::ServerError => format!("服务端错误: HTTP {}", code),
+        _ => format!("未知响应: HTTP {}", code),
+    };
+
+    (kind, message)
 }
@@ -0,0 +1,614 @@
+//! 工作区查找替换命令
+//!
+//! 分两步进行：`replace_in_files` 只做预览，返回每个文件的匹配位置、替换结果和
+//! DiffStats；`apply_replace_in_files` 对用户确认的文件重新计算替换并逐个原子写入
+//! （先写临时文件再重命名）。确认时会校验文件哈希，预览后被修改过的文件会被跳过。
+
+use super::diff::{compute_diff_stats, DiffStats};
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::utils::ignore::IgnoreMatcher;
+use crate::utils::path_sandbox::PathSandbox;
+use crate::utils::text_encoding;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tauri::State;
+use tracing::{debug, info, warn};
+
+/// 单次预览最多包含的文件数
+const MAX_PREVIEW_FILES: usize = 500;
+
+/// 每个文件预览中最多列出的匹配数（替换时不受限制）
+const MAX_PREVIEW_MATCHES_PER_FILE: usize = 200;
+
+/// 跳过超过该大小的文件
+const MAX_FILE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// 预览中单行文本的最大字符数
+const MAX_LINE_PREVIEW_CHARS: usize = 240;
+
+/// 查找替换选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReplaceOptions {
+    /// 搜索根目录，为空时使用当前项目目录
+    pub root: Option<String>,
+    /// `query` 按正则表达式解析，替换文本支持 `$1` / `${name}` 捕获组
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// 只处理匹配这些 glob 的文件（相对根目录），为空时处理全部
+    pub include: Vec<String>,
+    /// 排除匹配这些 glob 的文件或目录（相对根目录）
+    pub exclude: Vec<String>,
+}
+
+/// 单个匹配的预览
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceMatch {
+    /// 行号（从 1 开始）
+    pub line: usize,
+    /// 列号（从 1 开始，按字符计）
+    pub column: usize,
+    /// 匹配到的文本
+    pub matched: String,
+    /// 展开捕获组后的替换文本
+    pub replacement: String,
+    /// 替换前的整行
+    pub line_before: String,
+    /// 只替换该处匹配后的整行
+    pub line_after: String,
+}
+
+/// 单个文件的替换预览
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileReplacePreview {
+    pub path: String,
+    /// 相对根目录的路径
+    pub relative_path: String,
+    /// 预览时文件内容的 SHA256，确认替换时用于检测文件是否已变化
+    pub hash: String,
+    /// 匹配总数
+    pub match_count: usize,
+    /// 匹配预览（最多 MAX_PREVIEW_MATCHES_PER_FILE 条）
+    pub matches: Vec<ReplaceMatch>,
+    pub stats: DiffStats,
+}
+
+/// 替换预览结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacePreview {
+    pub root: String,
+    pub files: Vec<FileReplacePreview>,
+    pub total_matches: usize,
+    /// 文件数超过上限，结果不完整
+    pub truncated: bool,
+}
+
+/// 用户确认要替换的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmedReplaceFile {
+    pub path: String,
+    /// 预览返回的哈希
+    pub hash: String,
+}
+
+/// 未替换的文件及原因
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedReplaceFile {
+    pub path: String,
+    pub reason: String,
+}
+
+/// 替换结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceResult {
+    /// 已写入的文件
+    pub applied: Vec<String>,
+    pub skipped: Vec<SkippedReplaceFile>,
+    pub total_replacements: usize,
+}
+
+/// 预览工作区查找替换（不修改文件）
+#[tauri::command]
+pub async fn replace_in_files(
+    state: State<'_, AppState>,
+    query: String,
+    replacement: String,
+    options: Option<ReplaceOptions>,
+) -> Result<ReplacePreview, AxonError> {
+    let options = options.unwrap_or_default();
+    let root = resolve_root(&state, &options)?;
+    let replacer = Replacer::new(&query, &replacement, &options)?;
+    debug!("查找替换预览: {:?} in {:?}", query, root);
+
+    tokio::task::spawn_blocking(move || preview(&root, &replacer))
+        .await
+        .map_err(|e| AxonError::internal(format!("查找替换预览任务失败: {}", e)))?
+}
+
+/// 对确认的文件执行替换
+///
+/// 参数需与预览时一致；每个文件独立写入，单个文件失败不影响其他文件
+#[tauri::command]
+pub async fn apply_replace_in_files(
+    state: State<'_, AppState>,
+    query: String,
+    replacement: String,
+    options: Option<ReplaceOptions>,
+    files: Vec<ConfirmedReplaceFile>,
+) -> Result<ReplaceResult, AxonError> {
+    let audit_args = json!({
+        "query": &query,
+        "replacement": &replacement,
+        "options": &options,
+        "files": files.iter().map(|f| &f.path).collect::<Vec<_>>(),
+    });
+    state
+        .audit
+        .track("apply_replace_in_files", audit_args, async {
+            let options = options.unwrap_or_default();
+            let root = resolve_root(&state, &options)?;
+            let replacer = Replacer::new(&query, &replacement, &options)?;
+            let sandbox = PathSandbox::from_settings(&state.settings);
+            let files = files
+                .into_iter()
+                .map(|file| Ok((sandbox.check(&file.path)?, file)))
+                .collect::<Result<Vec<_>, AxonError>>()?;
+
+            let result = tokio::task::spawn_blocking(move || apply(&root, &replacer, files))
+                .await
+                .map_err(|e| AxonError::internal(format!("查找替换任务失败: {}", e)))?;
+            info!(
+                "查找替换完成: {} 个文件, {} 处替换, 跳过 {} 个",
+                result.applied.len(),
+                result.total_replacements,
+                result.skipped.len()
+            );
+            Ok(result)
+        })
+        .await
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn resolve_root(state: &AppState, options: &ReplaceOptions) -> Result<PathBuf, AxonError> {
+    let root = options
+        .root
+        .clone()
+        .filter(|r| !r.trim().is_empty())
+        .or_else(|| state.settings.get_project_directory())
+        .ok_or_else(|| AxonError::invalid_input("未指定搜索目录且未设置项目目录"))?;
+    let root = PathSandbox::from_settings(&state.settings).check(&root)?;
+    if !root.is_dir() {
+        return Err(AxonError::not_found(format!(
+            "搜索目录不存在: {}",
+            root.display()
+        )));
+    }
+    Ok(root)
+}
+
+/// 匹配与替换规则
+struct Replacer {
+    pattern: Regex,
+    replacement: String,
+    /// 替换文本是否展开捕获组（仅正则模式）
+    expand: bool,
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+}
+
+impl Replacer {
+    fn new(query: &str, replacement: &str, options: &ReplaceOptions) -> Result<Self, AxonError> {
+        if query.is_empty() {
+            return Err(AxonError::invalid_input("查找内容不能为空"));
+        }
+
+        let mut source = if options.regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        if options.whole_word {
+            source = format!(r"\b(?:{})\b", source);
+        }
+        let pattern = RegexBuilder::new(&source)
+            .case_insensitive(!options.case_sensitive)
+            .multi_line(true)
+            .build()
+            .map_err(|e| AxonError::invalid_input(format!("无效的正则表达式: {}", e)))?;
+
+        Ok(Self {
+            pattern,
+            replacement: replacement.to_string(),
+            expand: options.regex,
+            include: compile_globs(&options.include)?,
+            exclude: compile_globs(&options.exclude)?,
+        })
+    }
+
+    fn is_excluded(&self, relative: &str) -> bool {
+        self.exclude.iter().any(|p| p.matches(relative))
+    }
+
+    fn is_included(&self, relative: &str) -> bool {
+        self.include.is_empty() || self.include.iter().any(|p| p.matches(relative))
+    }
+
+    /// 单处匹配的替换文本
+    fn expand(&self, caps: &regex::Captures<'_>) -> String {
+        if self.expand {
+            let mut dst = String::new();
+            caps.expand(&self.replacement, &mut dst);
+            dst
+        } else {
+            self.replacement.clone()
+        }
+    }
+
+    /// 替换全部匹配，返回新文本和替换次数
+    fn replace_all(&self, text: &str) -> (String, usize) {
+        let mut count = 0;
+        let replaced = self
+            .pattern
+            .replace_all(text, |caps: &regex::Captures<'_>| {
+                count += 1;
+                self.expand(caps)
+            });
+        (replaced.into_owned(), count)
+    }
+}
+
+fn compile_globs(patterns: &[String]) -> Result<Vec<glob::Pattern>, AxonError> {
+    patterns
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            glob::Pattern::new(p)
+                .map_err(|e| AxonError::invalid_input(format!("无效的 glob 模式 {}: {}", p, e)))
+        })
+        .collect()
+}
+
+/// 相对根目录的路径（统一使用 `/` 分隔，便于 glob 匹配）
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// 收集候选文件（跳过隐藏目录、忽略规则匹配的路径和排除项）
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    replacer: &Replacer,
+    ignore: &IgnoreMatcher,
+    files: &mut Vec<PathBuf>,
+) {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut entries: Vec<_> = read_dir.flatten().collect();
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let relative = relative_path(root, &path);
+        if replacer.is_excluded(&relative) || ignore.is_ignored(&relative, file_type.is_dir()) {
+            continue;
+        }
+
+        if file_type.is_dir() {
+            if name.starts_with('.') {
+                continue;
+            }
+            collect_files(root, &path, replacer, ignore, files);
+        } else if file_type.is_file() && replacer.is_included(&relative) {
+            files.push(path);
+        }
+    }
+}
+
+/// 读取的文本文件
+struct TextFile {
+    bytes: Vec<u8>,
+    text: String,
+    format: text_encoding::TextFormat,
+}
+
+/// 读取文本文件，二进制或过大的文件返回 None
+fn read_text_file(path: &Path) -> Result<Option<TextFile>, AxonError> {
+    let metadata = std::fs::metadata(path).map_err(|e| AxonError::io("读取元数据失败", &e))?;
+    if metadata.len() > MAX_FILE_SIZE {
+        return Ok(None);
+    }
+
+    let bytes =
+        std::fs::read(path).map_err(|e| AxonError::localized_io("fs.read_file_failed", &e))?;
+    let format = text_encoding::detect_format(&bytes);
+    let is_utf16 =
+        format.encoding == encoding_rs::UTF_16LE || format.encoding == encoding_rs::UTF_16BE;
+    if !is_utf16 && bytes.iter().take(8192).any(|b| *b == 0) {
+        return Ok(None);
+    }
+
+    let text = text_encoding::decode(&bytes, &format);
+    Ok(Some(TextFile {
+        bytes,
+        text,
+        format,
+    }))
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn truncate_line(line: &str) -> String {
+    if line.chars().count() <= MAX_LINE_PREVIEW_CHARS {
+        line.to_string()
+    } else {
+        let mut truncated: String = line.chars().take(MAX_LINE_PREVIEW_CHARS).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// 单个文件的匹配预览
+fn file_matches(text: &str, replacer: &Replacer) -> (Vec<ReplaceMatch>, usize) {
+    let mut matches = Vec::new();
+    let mut count = 0;
+    for caps in replacer.pattern.captures_iter(text) {
+        let Some(m) = caps.get(0) else {
+            continue;
+        };
+        count += 1;
+        if matches.len() >= MAX_PREVIEW_MATCHES_PER_FILE {
+            continue;
+        }
+
+        let line_start = text[..m.start()].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[m.end()..]
+            .find('\n')
+            .map_or(text.len(), |i| m.end() + i);
+        let line = text[..m.start()].matches('\n').count() + 1;
+        let column = text[line_start..m.start()].chars().count() + 1;
+        let replacement = replacer.expand(&caps);
+        let line_before = text[line_start..line_end].trim_end_matches('\r');
+        let line_after = format!(
+            "{}{}{}",
+            &text[line_start..m.start()],
+            replacement,
+            &text[m.end()..line_end]
+        );
+
+        matches.push(ReplaceMatch {
+            line,
+            column,
+            matched: m.as_str().to_string(),
+            replacement,
+            line_before: truncate_line(line_before),
+            line_after: truncate_line(line_after.trim_end_matches('\r')),
+        });
+    }
+    (matches, count)
+}
+
+fn preview(root: &Path, replacer: &Replacer) -> Result<ReplacePreview, AxonError> {
+    let mut candidates = Vec::new();
+    collect_files(
+        root,
+        root,
+        replacer,
+        &IgnoreMatcher::for_project(root),
+        &mut candidates,
+    );
+
+    let mut files = Vec::new();
+    let mut total_matches = 0;
+    let mut truncated = false;
+    for path in candidates {
+        let file = match read_text_file(&path) {
+            Ok(Some(file)) => file,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("查找替换跳过文件 {:?}: {}", path, e);
+                continue;
+            }
+        };
+
+        let (matches, match_count) = file_matches(&file.text, replacer);
+        if match_count == 0 {
+            continue;
+        }
+        if files.len() >= MAX_PREVIEW_FILES {
+            truncated = true;
+            break;
+        }
+
+        let (replaced, _) = replacer.replace_all(&file.text);
+        total_matches += match_count;
+        files.push(FileReplacePreview {
+            path: path.to_string_lossy().to_string(),
+            relative_path: relative_path(root, &path),
+            hash: sha256_hex(&file.bytes),
+            match_count,
+            matches,
+            stats: compute_diff_stats(&file.text, &replaced),
+        });
+    }
+
+    Ok(ReplacePreview {
+        root: root.to_string_lossy().to_string(),
+        files,
+        total_matches,
+        truncated,
+    })
+}
+
+/// `files` 为（沙箱检查后的路径, 确认的文件）
+fn apply(
+    root: &Path,
+    replacer: &Replacer,
+    files: Vec<(PathBuf, ConfirmedReplaceFile)>,
+) -> ReplaceResult {
+    let mut result = ReplaceResult {
+        applied: Vec::new(),
+        skipped: Vec::new(),
+        total_replacements: 0,
+    };
+
+    for (path, file) in files {
+        match apply_file(root, replacer, &path, &file) {
+            Ok(count) => {
+                result.total_replacements += count;
+                result.applied.push(file.path);
+            }
+            Err(e) => {
+                warn!("查找替换跳过文件 {}: {}", file.path, e);
+                result.skipped.push(SkippedReplaceFile {
+                    path: file.path,
+                    reason: e.message,
+                });
+            }
+        }
+    }
+    result
+}
+
+/// 替换单个文件，返回替换次数
+fn apply_file(
+    root: &Path,
+    replacer: &Replacer,
+    path: &Path,
+    confirmed: &ConfirmedReplaceFile,
+) -> Result<usize, AxonError> {
+    if !path.starts_with(root) {
+        return Err(AxonError::permission_denied(format!(
+            "文件不在搜索目录中: {}",
+            confirmed.path
+        )));
+    }
+    let relative = relative_path(root, path);
+    if replacer.is_excluded(&relative) || !replacer.is_included(&relative) {
+        return Err(AxonError::invalid_input(format!(
+            "文件不在替换范围内: {}",
+            relative
+        )));
+    }
+
+    let file = read_text_file(path)?
+        .ok_or_else(|| AxonError::unsupported(format!("不支持替换的文件: {}", relative)))?;
+    if sha256_hex(&file.bytes) != confirmed.hash {
+        return Err(
+            AxonError::already_exists(format!("文件在预览后已被修改: {}", relative))
+                .with_details(json!({ "path": confirmed.path })),
+        );
+    }
+
+    let (replaced, count) = replacer.replace_all(&file.text);
+    if count == 0 {
+        return Ok(0);
+    }
+    let (bytes, had_unmappable) = text_encoding::encode(&replaced, &file.format);
+    if had_unmappable {
+        return Err(AxonError::invalid_input(format!(
+            "替换结果包含 {} 编码无法表示的字符",
+            file.format.encoding.name()
+        )));
+    }
+
+    write_atomic(path, &bytes)?;
+    Ok(count)
+}
+
+/// 写入同目录下的临时文件后重命名覆盖原文件，保留原文件权限
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), AxonError> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".{}.axon-replace.tmp", name));
+
+    let written = std::fs::write(&temp, bytes).and_then(|_| {
+        if let Ok(metadata) = std::fs::metadata(path) {
+            std::fs::set_permissions(&temp, metadata.permissions())?;
+        }
+        std::fs::rename(&temp, path)
+    });
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&temp);
+        return Err(AxonError::localized_io("fs.write_file_failed", &e));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacer(query: &str, replacement: &str, options: ReplaceOptions) -> Replacer {
+        Replacer::new(query, replacement, &options).unwrap()
+    }
+
+    #[test]
+    fn regex_replacement_expands_capture_groups() {
+        let options = ReplaceOptions {
+            regex: true,
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let r = replacer(r"foo\((\w+)\)", "bar($1, ctx)", options);
+        let (text, count) = r.replace_all("foo(a)\nfoo(b) foo()");
+        assert_eq!(text, "bar(a, ctx)\nbar(b, ctx) foo()");
+        assert_eq!(count, 2);
+
+        let (matches, total) = file_matches("x\r\n  foo(b) y\r\n", &r);
+        assert_eq!(total, 1);
+        assert_eq!((matches[0].line, matches[0].column), (2, 3));
+        assert_eq!(matches[0].line_after, "  bar(b, ctx) y");
+    }
+
+    #[test]
+    fn literal_mode_does_not_expand_and_respects_whole_word() {
+        let options = ReplaceOptions {
+            whole_word: true,
+            ..Default::default()
+        };
+        let r = replacer("a.b", "$1", options);
+        let (text, count) = r.replace_all("A.B a.bc axb a.b");
+        assert_eq!(text, "$1 a.bc axb $1");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn globs_filter_relative_paths() {
+        let options = ReplaceOptions {
+            include: vec!["**/*.rs".into()],
+            exclude: vec!["**/generated/**".into()],
+            ..Default::default()
+        };
+        let r = replacer("x", "y", options);
+        assert!(r.is_included("src/lib.rs"));
+        assert!(!r.is_included("src/lib.ts"));
+        assert!(r.is_excluded("src/generated/a.rs"));
+    }
+}
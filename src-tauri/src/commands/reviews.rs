@@ -0,0 +1,93 @@
+//! Diff 审查会话命令
+
+use crate::error::AxonError;
+use crate::reviews::{FinalizeResult, NewReview, Review, ReviewDecision, ReviewSummary};
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use serde_json::json;
+use std::path::Path;
+use tauri::State;
+
+/// 创建审查，保存各文件修改前后的快照
+#[tauri::command]
+pub fn create_review(
+    state: State<'_, AppState>,
+    mut session: NewReview,
+) -> Result<Review, AxonError> {
+    // 审查记录保存解析后的路径，定稿时写入这些路径
+    let sandbox = PathSandbox::from_settings(&state.settings);
+    for file in &mut session.files {
+        file.path = sandbox.check(&file.path)?.to_string_lossy().to_string();
+    }
+    let audit_args = json!({
+        "sessionId": &session.session_id,
+        "files": session.files.iter().map(|f| &f.path).collect::<Vec<_>>(),
+    });
+    state.audit.track_sync("create_review", audit_args, || {
+        state.reviews.create(session)
+    })
+}
+
+/// 列出审查（最近更新的在前）
+#[tauri::command]
+pub fn list_reviews(
+    state: State<'_, AppState>,
+    project_dir: Option<String>,
+) -> Result<Vec<ReviewSummary>, AxonError> {
+    state.reviews.list(project_dir.as_deref())
+}
+
+#[tauri::command]
+pub fn get_review(state: State<'_, AppState>, id: String) -> Result<Review, AxonError> {
+    state.reviews.get(&id)
+}
+
+/// 接受或拒绝文件中的 hunk，`hunk_indices` 为空时作用于整个文件
+#[tauri::command]
+pub fn set_review_decision(
+    state: State<'_, AppState>,
+    id: String,
+    path: String,
+    hunk_indices: Option<Vec<usize>>,
+    decision: ReviewDecision,
+) -> Result<Review, AxonError> {
+    state
+        .reviews
+        .decide(&id, &path, hunk_indices.as_deref(), decision)
+}
+
+/// 定稿：写入接受的 hunk，丢弃拒绝的 hunk
+#[tauri::command]
+pub async fn finalize_review(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<FinalizeResult, AxonError> {
+    let review = state.reviews.get(&id)?;
+    let sandbox = PathSandbox::from_settings(&state.settings);
+    for file in &review.files {
+        // 创建后父目录中的符号链接被替换时拒绝写入
+        if sandbox.check(&file.path)? != Path::new(&file.path) {
+            return Err(AxonError::permission_denied(format!(
+                "文件路径在审查创建后已变化: {}",
+                file.path
+            )));
+        }
+    }
+    let reviews = state.reviews.clone();
+    let audit_args = json!({ "id": &id });
+    state
+        .audit
+        .track("finalize_review", audit_args, async {
+            tokio::task::spawn_blocking(move || reviews.finalize(&id)).await?
+        })
+        .await
+}
+
+/// 删除审查，返回审查是否存在
+#[tauri::command]
+pub fn delete_review(state: State<'_, AppState>, id: String) -> Result<bool, AxonError> {
+    let audit_args = json!({ "id": &id });
+    state
+        .audit
+        .track_sync("delete_review", audit_args, || state.reviews.delete(&id))
+}
@@ -0,0 +1,95 @@
+//! 运行沙箱命令
+//!
+//! 在项目的一次性副本中运行工作流 / Agent，审阅改动后再应用回项目。
+
+use crate::error::AxonError;
+use crate::run_sandbox::{RunSandbox, SandboxDiff};
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use serde_json::json;
+use tauri::State;
+
+/// 为项目创建运行沙箱，并以沙箱目录启动名为 `instance` 的 OpenCode 实例供 Agent 运行
+#[tauri::command]
+pub async fn create_sandbox(
+    state: State<'_, AppState>,
+    project_dir: String,
+) -> Result<RunSandbox, AxonError> {
+    let project = PathSandbox::from_settings(&state.settings).check(&project_dir)?;
+    let plugin_api_port = state.plugin_api.read().state().get_port();
+    let audit_args = json!({ "projectDir": &project_dir });
+    state
+        .audit
+        .track("create_sandbox", audit_args, async {
+            let sandbox = state.run_sandboxes.create(&project.to_string_lossy()).await?;
+            let started = state
+                .services
+                .start(&sandbox.instance, &sandbox.directory, 0, plugin_api_port)
+                .await;
+            if let Err(e) = started {
+                // 没有实例时沙箱无法使用，直接清理
+                let _ = state.run_sandboxes.discard(&sandbox.id).await;
+                return Err(e);
+            }
+            Ok(sandbox)
+        })
+        .await
+}
+
+/// 列出运行沙箱
+#[tauri::command]
+pub fn list_sandboxes(state: State<'_, AppState>) -> Vec<RunSandbox> {
+    state.run_sandboxes.list()
+}
+
+/// 沙箱相对创建时的改动，`paths` 为空时包含全部文件
+#[tauri::command]
+pub async fn get_sandbox_diff(
+    state: State<'_, AppState>,
+    sandbox_id: String,
+    paths: Option<Vec<String>>,
+) -> Result<SandboxDiff, AxonError> {
+    state
+        .run_sandboxes
+        .diff(&sandbox_id, &paths.unwrap_or_default())
+        .await
+}
+
+/// 把沙箱中的改动应用回项目，`paths` 为空时应用全部改动
+#[tauri::command]
+pub async fn promote_sandbox(
+    state: State<'_, AppState>,
+    sandbox_id: String,
+    paths: Option<Vec<String>>,
+) -> Result<SandboxDiff, AxonError> {
+    let paths = paths.unwrap_or_default();
+    let audit_args = json!({ "sandboxId": &sandbox_id, "paths": &paths });
+    state
+        .audit
+        .track(
+            "promote_sandbox",
+            audit_args,
+            state.run_sandboxes.promote(&sandbox_id, &paths),
+        )
+        .await
+}
+
+/// 停止沙箱的 OpenCode 实例，删除沙箱及其目录
+#[tauri::command]
+pub async fn discard_sandbox(
+    state: State<'_, AppState>,
+    sandbox_id: String,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "sandboxId": &sandbox_id });
+    state
+        .audit
+        .track("discard_sandbox", audit_args, async {
+            let sandbox = state.run_sandboxes.get(&sandbox_id)?;
+            // 先停止实例，避免进程仍在沙箱目录中读写；应用重启后实例可能已不存在
+            if state.services.get(&sandbox.instance).is_ok() {
+                state.services.stop(&sandbox.instance).await?;
+            }
+            state.run_sandboxes.discard(&sandbox_id).await
+        })
+        .await
+}
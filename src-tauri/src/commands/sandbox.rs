@@ -0,0 +1,93 @@
+//! 文件系统路径沙箱命令
+//!
+//! 查询沙箱状态、开关沙箱，以及管理用户显式授权的目录
+
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::utils::path_sandbox::{self, PathSandbox};
+use serde::Serialize;
+use serde_json::json;
+use std::path::Path;
+use tauri::State;
+use tracing::info;
+
+/// 路径沙箱状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathSandboxStatus {
+    pub enabled: bool,
+    /// 用户显式授权的目录
+    pub allowed_paths: Vec<String>,
+    /// 当前生效的全部根目录（项目目录、应用数据目录和授权目录）
+    pub roots: Vec<String>,
+}
+
+/// 获取路径沙箱状态
+#[tauri::command]
+pub fn get_path_sandbox(state: State<'_, AppState>) -> PathSandboxStatus {
+    let config = state.settings.get_path_sandbox();
+    let sandbox = PathSandbox::from_settings(&state.settings);
+    PathSandboxStatus {
+        enabled: config.enabled,
+        allowed_paths: config.allowed_paths,
+        roots: sandbox
+            .roots()
+            .iter()
+            .map(|root| root.to_string_lossy().to_string())
+            .collect(),
+    }
+}
+
+/// 启用或关闭路径沙箱
+#[tauri::command]
+pub fn set_path_sandbox_enabled(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "enabled": enabled });
+    state
+        .audit
+        .track_sync("set_path_sandbox_enabled", audit_args, || {
+            info!("路径沙箱: {}", if enabled { "启用" } else { "关闭" });
+            Ok(state.settings.set_path_sandbox_enabled(enabled)?)
+        })
+}
+
+/// 授权访问目录（持久化）
+///
+/// 目录必须已存在，保存规整后的绝对路径
+///
+/// # 返回
+/// 实际保存的路径
+#[tauri::command]
+pub fn grant_path_access(state: State<'_, AppState>, path: String) -> Result<String, AxonError> {
+    let audit_args = json!({ "path": &path });
+    state
+        .audit
+        .track_sync("grant_path_access", audit_args, || grant(&state, &path))
+}
+
+/// 撤销目录授权
+#[tauri::command]
+pub fn revoke_path_access(state: State<'_, AppState>, path: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "path": &path });
+    state
+        .audit
+        .track_sync("revoke_path_access", audit_args, || {
+            info!("撤销目录授权: {}", path);
+            Ok(state.settings.remove_allowed_path(&path)?)
+        })
+}
+
+/// 将目录加入授权列表
+pub(crate) fn grant(state: &AppState, path: &str) -> Result<String, AxonError> {
+    if !Path::new(path).is_dir() {
+        return Err(AxonError::not_found(format!("目录不存在: {}", path)));
+    }
+    let resolved = path_sandbox::resolve(Path::new(path))?
+        .to_string_lossy()
+        .to_string();
+    info!("授权访问目录: {}", resolved);
+    state.settings.add_allowed_path(&resolved)?;
+    Ok(resolved)
+}
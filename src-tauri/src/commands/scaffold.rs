@@ -0,0 +1,81 @@
+//! 项目模板命令
+//!
+//! 用户模板放在 `<app_data_dir>/templates/<ID>/` 下即可被列出，无需注册。
+
+use crate::error::AxonError;
+use crate::scaffold::{self, CreatedProject, ProjectTemplate};
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tauri::State;
+use tracing::warn;
+
+/// 列出内置模板和用户模板
+#[tauri::command]
+pub fn list_project_templates(
+    state: State<'_, AppState>,
+) -> Result<Vec<ProjectTemplate>, AxonError> {
+    state.templates.list()
+}
+
+/// 删除用户模板，返回模板是否存在
+#[tauri::command]
+pub fn delete_project_template(
+    state: State<'_, AppState>,
+    template_id: String,
+) -> Result<bool, AxonError> {
+    let audit_args = json!({ "templateId": &template_id });
+    state
+        .audit
+        .track_sync("delete_project_template", audit_args, || {
+            state.templates.delete(&template_id)
+        })
+}
+
+/// 从模板创建项目，目标目录必须不存在或为空；`init_git` 为 true 时初始化 Git 仓库
+#[tauri::command]
+pub async fn create_project_from_template(
+    state: State<'_, AppState>,
+    template_id: String,
+    dest_dir: String,
+    variables: Option<BTreeMap<String, String>>,
+    init_git: Option<bool>,
+) -> Result<CreatedProject, AxonError> {
+    let dest = PathSandbox::from_settings(&state.settings).check(&dest_dir)?;
+    let audit_args = json!({ "templateId": &template_id, "destDir": &dest_dir });
+    let templates = Arc::clone(&state.templates);
+    state
+        .audit
+        .track("create_project_from_template", audit_args, async move {
+            let files = {
+                let template_id = template_id.clone();
+                let dest = dest.clone();
+                let variables = variables.unwrap_or_default();
+                tokio::task::spawn_blocking(move || {
+                    templates.instantiate(&template_id, &dest, &variables)
+                })
+                .await??
+            };
+
+            let mut project = CreatedProject {
+                path: dest_dir,
+                template_id,
+                files,
+                git_initialized: false,
+                git_error: None,
+            };
+            if init_git.unwrap_or(false) {
+                match scaffold::init_git(&dest).await {
+                    Ok(()) => project.git_initialized = true,
+                    Err(e) => {
+                        warn!("初始化 Git 仓库失败: {}", e);
+                        project.git_error = Some(e.to_string());
+                    }
+                }
+            }
+            Ok(project)
+        })
+        .await
+}
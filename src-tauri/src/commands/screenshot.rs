@@ -0,0 +1,280 @@
+//! 截图命令
+//!
+//! 截取整个屏幕、其他应用窗口或屏幕区域，保存为
+//! `<app_data_dir>/attachments/screenshot-*.png`，并返回路径和缩略图预览，
+//! 聊天中可直接附加截图而无需离开 Axon。
+
+use super::images::{encode_thumbnail, ImageThumbnail};
+use crate::error::{AxonError, ErrorKind};
+use crate::state::AppState;
+use crate::utils::paths::get_attachments_dir;
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+use tauri::State;
+use tracing::{debug, warn};
+use xcap::{Monitor, Window, XCapError};
+
+/// 预览缩略图最长边
+const PREVIEW_EDGE: u32 = 512;
+
+/// 截图模式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotMode {
+    /// 主显示器全屏
+    Full,
+    /// 单个窗口（默认最上层的非 Axon 窗口）
+    Window,
+    /// 屏幕区域
+    Region,
+}
+
+/// 截图区域（屏幕坐标，物理像素）
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ScreenshotRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 可截取的窗口
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotWindow {
+    pub id: u32,
+    pub title: String,
+    pub app_name: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 截图结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Screenshot {
+    /// 保存的 PNG 文件路径
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    /// 缩略图预览
+    pub preview: ImageThumbnail,
+}
+
+/// 列出可截取的窗口（排除 Axon 自身和最小化的窗口）
+#[tauri::command]
+pub async fn list_screenshot_windows() -> Result<Vec<ScreenshotWindow>, AxonError> {
+    tokio::task::spawn_blocking(|| {
+        let windows = capturable_windows()?
+            .iter()
+            .filter_map(|window| {
+                Some(ScreenshotWindow {
+                    id: window.id().ok()?,
+                    title: window.title().unwrap_or_default(),
+                    app_name: window.app_name().unwrap_or_default(),
+                    width: window.width().ok()?,
+                    height: window.height().ok()?,
+                })
+            })
+            .collect();
+        Ok(windows)
+    })
+    .await
+    .map_err(|e| AxonError::internal(format!("列出窗口任务失败: {}", e)))?
+}
+
+/// 截图并保存到附件目录
+///
+/// - `full`：截取主显示器
+/// - `window`：截取 `window_id` 指定的窗口，未指定时截取最上层的非 Axon 窗口
+/// - `region`：截取 `region` 指定的屏幕区域（超出所在显示器的部分会被裁掉）
+#[tauri::command]
+pub async fn capture_screenshot(
+    state: State<'_, AppState>,
+    mode: ScreenshotMode,
+    region: Option<ScreenshotRegion>,
+    window_id: Option<u32>,
+) -> Result<Screenshot, AxonError> {
+    let audit_args = json!({ "mode": mode, "region": region, "windowId": window_id });
+    state
+        .audit
+        .track("capture_screenshot", audit_args, async move {
+            debug!(
+                "截图: {:?}, 区域: {:?}, 窗口: {:?}",
+                mode, region, window_id
+            );
+
+            tokio::task::spawn_blocking(move || {
+                let image = match mode {
+                    ScreenshotMode::Full => capture_primary_monitor()?,
+                    ScreenshotMode::Window => capture_window(window_id)?,
+                    ScreenshotMode::Region => {
+                        let region = region
+                            .ok_or_else(|| AxonError::invalid_input("区域截图需要指定 region"))?;
+                        capture_region(region)?
+                    }
+                };
+                save_screenshot(image)
+            })
+            .await
+            .map_err(|e| AxonError::internal(format!("截图任务失败: {}", e)))?
+        })
+        .await
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn capture_error(e: XCapError) -> AxonError {
+    match e {
+        XCapError::NotSupported => AxonError::unsupported("当前平台不支持截图"),
+        e => AxonError::unavailable(format!("截图失败: {}", e)),
+    }
+}
+
+fn capture_primary_monitor() -> Result<RgbaImage, AxonError> {
+    let monitors = Monitor::all().map_err(capture_error)?;
+    let monitor = monitors
+        .iter()
+        .find(|m| m.is_primary().unwrap_or(false))
+        .or_else(|| monitors.first())
+        .ok_or_else(|| AxonError::unavailable("未找到显示器"))?;
+    monitor.capture_image().map_err(capture_error)
+}
+
+/// 除 Axon 自身和最小化窗口以外的窗口
+fn capturable_windows() -> Result<Vec<Window>, AxonError> {
+    let own_pid = std::process::id();
+    let windows = Window::all().map_err(capture_error)?;
+    Ok(windows
+        .into_iter()
+        .filter(|w| w.pid().map_or(true, |pid| pid != own_pid))
+        .filter(|w| !w.is_minimized().unwrap_or(false))
+        .filter(|w| w.width().unwrap_or(0) > 0 && w.height().unwrap_or(0) > 0)
+        .collect())
+}
+
+fn capture_window(window_id: Option<u32>) -> Result<RgbaImage, AxonError> {
+    let windows = capturable_windows()?;
+    let window = match window_id {
+        Some(id) => windows
+            .iter()
+            .find(|w| w.id().ok() == Some(id))
+            .ok_or_else(|| AxonError::not_found(format!("窗口不存在或不可截取: {}", id)))?,
+        None => windows
+            .iter()
+            .max_by_key(|w| w.z().unwrap_or(i32::MIN))
+            .ok_or_else(|| AxonError::not_found("没有可截取的窗口"))?,
+    };
+    window.capture_image().map_err(capture_error)
+}
+
+fn capture_region(region: ScreenshotRegion) -> Result<RgbaImage, AxonError> {
+    let monitor = Monitor::from_point(region.x, region.y).map_err(capture_error)?;
+    let bounds = ScreenshotRegion {
+        x: monitor.x().map_err(capture_error)?,
+        y: monitor.y().map_err(capture_error)?,
+        width: monitor.width().map_err(capture_error)?,
+        height: monitor.height().map_err(capture_error)?,
+    };
+    let (x, y, width, height) = clip_region(region, bounds)
+        .ok_or_else(|| AxonError::invalid_input("截图区域为空或不在显示器范围内"))?;
+    monitor
+        .capture_region(x, y, width, height)
+        .map_err(capture_error)
+}
+
+/// 将区域裁剪到显示器范围内，返回相对显示器左上角的 (x, y, width, height)
+fn clip_region(
+    region: ScreenshotRegion,
+    monitor: ScreenshotRegion,
+) -> Option<(u32, u32, u32, u32)> {
+    let left = i64::from(region.x).max(i64::from(monitor.x));
+    let top = i64::from(region.y).max(i64::from(monitor.y));
+    let right = (i64::from(region.x) + i64::from(region.width))
+        .min(i64::from(monitor.x) + i64::from(monitor.width));
+    let bottom = (i64::from(region.y) + i64::from(region.height))
+        .min(i64::from(monitor.y) + i64::from(monitor.height));
+    if right <= left || bottom <= top {
+        return None;
+    }
+    Some((
+        (left - i64::from(monitor.x)) as u32,
+        (top - i64::from(monitor.y)) as u32,
+        (right - left) as u32,
+        (bottom - top) as u32,
+    ))
+}
+
+fn attachments_dir() -> Result<PathBuf, AxonError> {
+    let dir = get_attachments_dir().ok_or_else(|| {
+        AxonError::localized(
+            ErrorKind::Unavailable,
+            "app.data_dir_unavailable",
+            json!(null),
+        )
+    })?;
+    std::fs::create_dir_all(&dir).map_err(|e| AxonError::io("创建附件目录失败", &e))?;
+    Ok(dir)
+}
+
+fn save_screenshot(image: RgbaImage) -> Result<Screenshot, AxonError> {
+    let (width, height) = (image.width(), image.height());
+    if width == 0 || height == 0 {
+        return Err(AxonError::unavailable("截图结果为空"));
+    }
+
+    let file_name = format!(
+        "screenshot-{}.png",
+        chrono::Local::now().format("%Y%m%d-%H%M%S-%3f")
+    );
+    let path = attachments_dir()?.join(file_name);
+    image
+        .save_with_format(&path, ImageFormat::Png)
+        .map_err(|e| {
+            warn!("保存截图失败: {:?}, 错误: {}", path, e);
+            AxonError::internal(format!("保存截图失败: {}", e))
+        })?;
+
+    let preview = encode_thumbnail(DynamicImage::ImageRgba8(image), PREVIEW_EDGE)?;
+    debug!("截图已保存: {:?}, {}x{}", path, width, height);
+
+    Ok(Screenshot {
+        path: path.to_string_lossy().to_string(),
+        width,
+        height,
+        preview,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> ScreenshotRegion {
+        ScreenshotRegion {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn clip_region_to_monitor() {
+        let monitor = rect(1920, 0, 1280, 1024);
+        assert_eq!(
+            clip_region(rect(2000, 100, 300, 200), monitor),
+            Some((80, 100, 300, 200))
+        );
+        assert_eq!(
+            clip_region(rect(3000, 900, 500, 500), monitor),
+            Some((1080, 900, 200, 124))
+        );
+        assert_eq!(clip_region(rect(0, 0, 100, 100), monitor), None);
+        assert_eq!(clip_region(rect(2000, 100, 0, 50), monitor), None);
+    }
+}
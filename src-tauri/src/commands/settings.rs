@@ -1,8 +1,12 @@
 //! 应用设置命令
 
-use crate::opencode::AppSettings;
+use crate::error::{AxonError, ErrorKind};
+use crate::opencode::{AppSettings, Language};
+use crate::settings::SettingsEncryptionStatus;
 use crate::state::AppState;
 use crate::utils::paths;
+use serde_json::json;
+use std::sync::Arc;
 use tauri::State;
 
 #[tauri::command]
@@ -11,29 +15,136 @@ pub fn get_app_settings(state: State<'_, AppState>) -> AppSettings {
 }
 
 #[tauri::command]
-pub fn set_app_settings(state: State<'_, AppState>, settings: AppSettings) -> Result<(), String> {
-    state.settings.set_settings(settings)
+pub fn set_app_settings(state: State<'_, AppState>, settings: AppSettings) -> Result<(), AxonError> {
+    let audit_args = json!({ "settings": &settings });
+    state.audit.track_sync("set_app_settings", audit_args, || {
+        state.settings.set_settings(settings).map_err(AxonError::from)
+    })
 }
 
+/// 获取设置加密状态
 #[tauri::command]
-pub fn set_auto_update(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
-    state.settings.set_auto_update(enabled)
+pub fn get_settings_encryption(state: State<'_, AppState>) -> SettingsEncryptionStatus {
+    state.settings.encryption_status()
+}
+
+/// 启用设置加密：生成密钥保存到系统钥匙串，并把设置文件中的敏感字段加密重写
+#[tauri::command]
+pub async fn enable_settings_encryption(
+    state: State<'_, AppState>,
+) -> Result<SettingsEncryptionStatus, AxonError> {
+    set_settings_encryption(&state, true).await
+}
+
+/// 关闭设置加密，设置文件改回明文保存
+#[tauri::command]
+pub async fn disable_settings_encryption(
+    state: State<'_, AppState>,
+) -> Result<SettingsEncryptionStatus, AxonError> {
+    set_settings_encryption(&state, false).await
+}
+
+/// 丢弃设置文件中无法解密的敏感字段并恢复保存，`confirm` 必须为 true
+#[tauri::command]
+pub async fn reset_settings_encryption(
+    state: State<'_, AppState>,
+    confirm: bool,
+) -> Result<SettingsEncryptionStatus, AxonError> {
+    if !confirm {
+        return Err(AxonError::invalid_input(
+            "重置设置加密会永久丢弃无法解密的服务商配置、Webhook 和环境变量，需要确认",
+        ));
+    }
+    let settings = Arc::clone(&state.settings);
+    state
+        .audit
+        .track("reset_settings_encryption", json!({}), async move {
+            tokio::task::spawn_blocking(move || -> Result<_, AxonError> {
+                settings.reset_encryption()?;
+                Ok(settings.encryption_status())
+            })
+            .await?
+        })
+        .await
+}
+
+async fn set_settings_encryption(
+    state: &AppState,
+    enabled: bool,
+) -> Result<SettingsEncryptionStatus, AxonError> {
+    let name = if enabled {
+        "enable_settings_encryption"
+    } else {
+        "disable_settings_encryption"
+    };
+    let settings = Arc::clone(&state.settings);
+    state
+        .audit
+        .track(name, json!({}), async move {
+            // 钥匙串访问可能阻塞（可能弹出系统授权）
+            tokio::task::spawn_blocking(move || -> Result<_, AxonError> {
+                settings.set_encryption(enabled)?;
+                Ok(settings.encryption_status())
+            })
+            .await?
+        })
+        .await
+}
+
+#[tauri::command]
+pub fn set_auto_update(state: State<'_, AppState>, enabled: bool) -> Result<(), AxonError> {
+    let audit_args = json!({ "enabled": enabled });
+    state.audit.track_sync("set_auto_update", audit_args, || {
+        state.settings.set_auto_update(enabled).map_err(AxonError::from)
+    })
+}
+
+#[tauri::command]
+pub fn set_minimize_to_tray(state: State<'_, AppState>, enabled: bool) -> Result<(), AxonError> {
+    let audit_args = json!({ "enabled": enabled });
+    state.audit.track_sync("set_minimize_to_tray", audit_args, || {
+        state.settings.set_minimize_to_tray(enabled).map_err(AxonError::from)
+    })
+}
+
+#[tauri::command]
+pub fn get_language(state: State<'_, AppState>) -> Language {
+    state.settings.get_language()
+}
+
+/// 设置后端消息语言，立即作用于之后的错误提示
+#[tauri::command]
+pub fn set_language(state: State<'_, AppState>, language: Language) -> Result<(), AxonError> {
+    let audit_args = json!({ "language": language });
+    state.audit.track_sync("set_language", audit_args, || {
+        state.settings.set_language(language)?;
+        crate::i18n::set_language(language);
+        Ok(())
+    })
 }
 
 #[tauri::command]
 pub fn set_custom_opencode_path(
     state: State<'_, AppState>,
     path: Option<String>,
-) -> Result<(), String> {
-    state.settings.set_custom_opencode_path(path)
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "path": &path });
+    state.audit.track_sync("set_custom_opencode_path", audit_args, || {
+        state.settings.set_custom_opencode_path(path).map_err(AxonError::from)
+    })
 }
 
 #[tauri::command]
 pub fn set_project_directory(
     state: State<'_, AppState>,
     path: Option<String>,
-) -> Result<(), String> {
-    state.settings.set_project_directory(path)
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "path": &path });
+    state.audit.track_sync("set_project_directory", audit_args, || {
+        state.settings.set_project_directory(path.clone())?;
+        state.file_index.set_root(path.map(std::path::PathBuf::from));
+        Ok(())
+    })
 }
 
 #[tauri::command]
@@ -42,8 +153,10 @@ pub fn get_project_directory(state: State<'_, AppState>) -> Option<String> {
 }
 
 #[tauri::command]
-pub fn get_opencode_config_path() -> Result<String, String> {
+pub fn get_opencode_config_path() -> Result<String, AxonError> {
     paths::get_opencode_config_path()
         .map(|p| p.to_string_lossy().to_string())
-        .ok_or_else(|| "应用数据目录未初始化".to_string())
+        .ok_or_else(|| {
+            AxonError::localized(ErrorKind::Unavailable, "app.data_dir_unavailable", json!(null))
+        })
 }
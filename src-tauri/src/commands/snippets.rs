@@ -0,0 +1,45 @@
+//! 提示词片段命令
+
+use crate::error::AxonError;
+use crate::snippets::{Snippet, SnippetInput};
+use crate::state::AppState;
+use serde_json::json;
+use std::collections::BTreeMap;
+use tauri::State;
+
+/// 列出片段，固定的在前，其余按名称排序
+#[tauri::command]
+pub fn list_snippets(state: State<'_, AppState>) -> Result<Vec<Snippet>, AxonError> {
+    state.snippets.list()
+}
+
+/// 新建（`id` 为空）或更新片段
+#[tauri::command]
+pub fn save_snippet(
+    state: State<'_, AppState>,
+    snippet: SnippetInput,
+) -> Result<Snippet, AxonError> {
+    let audit_args = json!({ "id": &snippet.id, "name": &snippet.name });
+    state
+        .audit
+        .track_sync("save_snippet", audit_args, || state.snippets.save(snippet))
+}
+
+/// 删除片段，返回片段是否存在
+#[tauri::command]
+pub fn delete_snippet(state: State<'_, AppState>, id: String) -> Result<bool, AxonError> {
+    let audit_args = json!({ "id": &id });
+    state
+        .audit
+        .track_sync("delete_snippet", audit_args, || state.snippets.delete(&id))
+}
+
+/// 展开片段，替换提供的变量（未提供的变量保持 `{{ 变量 }}` 原样）
+#[tauri::command]
+pub fn expand_snippet(
+    state: State<'_, AppState>,
+    id: String,
+    variables: Option<BTreeMap<String, String>>,
+) -> Result<String, AxonError> {
+    state.snippets.expand(&id, &variables.unwrap_or_default())
+}
@@ -0,0 +1,36 @@
+//! 拼写检查命令
+
+use crate::error::AxonError;
+use crate::spellcheck::SpellingIssue;
+use crate::state::AppState;
+use serde_json::json;
+use std::sync::Arc;
+use tauri::State;
+
+/// 检查文本拼写，`language` 为词典语言（如 en_US、en-US 或 en）
+///
+/// 首次使用某语言时需要加载词典，在后台线程执行
+#[tauri::command]
+pub async fn check_spelling(
+    state: State<'_, AppState>,
+    text: String,
+    language: String,
+) -> Result<Vec<SpellingIssue>, AxonError> {
+    let spellcheck = Arc::clone(&state.spellcheck);
+    tokio::task::spawn_blocking(move || spellcheck.check(&text, &language)).await?
+}
+
+/// 添加到用户词典（对所有语言生效），返回是否新增
+#[tauri::command]
+pub fn add_to_dictionary(state: State<'_, AppState>, word: String) -> Result<bool, AxonError> {
+    let audit_args = json!({ "word": &word });
+    state.audit.track_sync("add_to_dictionary", audit_args, || {
+        state.spellcheck.add_word(&word)
+    })
+}
+
+/// 可用的词典语言
+#[tauri::command]
+pub fn list_spelling_languages(state: State<'_, AppState>) -> Vec<String> {
+    state.spellcheck.languages()
+}
@@ -0,0 +1,18 @@
+//! 启动耗时与就绪状态命令
+
+use crate::bootstrap::BootstrapStatus;
+use crate::startup::StartupTimings;
+use crate::state::AppState;
+use tauri::State;
+
+/// 获取启动各阶段耗时（毫秒）
+#[tauri::command]
+pub fn get_startup_timings(state: State<'_, AppState>) -> StartupTimings {
+    state.startup.timings()
+}
+
+/// 获取启动各阶段的就绪状态
+#[tauri::command]
+pub fn get_bootstrap_status(state: State<'_, AppState>) -> BootstrapStatus {
+    state.bootstrap.status()
+}
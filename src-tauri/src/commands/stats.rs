@@ -0,0 +1,13 @@
+//! 进程资源占用命令
+
+use crate::state::AppState;
+use crate::stats::ProcessStats;
+use tauri::State;
+
+/// 获取 Axon、opencode 及其子进程的资源占用历史
+///
+/// 新的采样通过 `stats:update` 事件推送
+#[tauri::command]
+pub fn get_process_stats(state: State<'_, AppState>) -> ProcessStats {
+    state.stats.history()
+}
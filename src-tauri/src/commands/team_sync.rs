@@ -0,0 +1,70 @@
+//! 团队配置同步命令
+
+use crate::error::AxonError;
+use crate::opencode::TeamSyncSettings;
+use crate::state::AppState;
+use crate::team_sync::TeamSyncStatus;
+use serde_json::json;
+use tauri::State;
+
+/// 获取团队配置同步设置
+#[tauri::command]
+pub fn get_team_sync_settings(state: State<'_, AppState>) -> TeamSyncSettings {
+    state.settings.get_team_sync()
+}
+
+/// 更新团队配置同步设置（启用状态下会立即触发一次同步）
+#[tauri::command]
+pub fn set_team_sync_settings(
+    state: State<'_, AppState>,
+    team_sync: TeamSyncSettings,
+) -> Result<TeamSyncStatus, AxonError> {
+    // 仓库地址可能包含凭据，审计日志只记录是否配置
+    let audit_args = json!({
+        "enabled": team_sync.enabled,
+        "hasRepoUrl": team_sync.repo_url.is_some(),
+        "branch": &team_sync.branch,
+        "refreshIntervalMinutes": team_sync.refresh_interval_minutes,
+    });
+    state
+        .audit
+        .track_sync("set_team_sync_settings", audit_args, || {
+            let mut team_sync = team_sync;
+            team_sync.repo_url = team_sync
+                .repo_url
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty());
+            team_sync.branch = team_sync
+                .branch
+                .map(|branch| branch.trim().to_string())
+                .filter(|branch| !branch.is_empty());
+            if team_sync.enabled && team_sync.repo_url.is_none() {
+                return Err(AxonError::invalid_input("启用团队配置同步需要填写仓库地址"));
+            }
+            if let Some(branch) = &team_sync.branch {
+                if branch.starts_with('-') || branch.contains(char::is_whitespace) {
+                    return Err(AxonError::invalid_input(format!("无效的分支名: {}", branch)));
+                }
+            }
+            state
+                .settings
+                .set_team_sync(team_sync)
+                .map_err(AxonError::from)?;
+            Ok(state.team_sync.status())
+        })
+}
+
+/// 获取团队配置同步状态（含冲突）
+#[tauri::command]
+pub fn get_team_sync_status(state: State<'_, AppState>) -> TeamSyncStatus {
+    state.team_sync.status()
+}
+
+/// 立即同步团队配置仓库
+#[tauri::command]
+pub async fn sync_team_config(state: State<'_, AppState>) -> Result<TeamSyncStatus, AxonError> {
+    state
+        .audit
+        .track("sync_team_config", json!({}), state.team_sync.sync())
+        .await
+}
@@ -0,0 +1,412 @@
+//! 终端相关命令
+//!
+//! 终端会话本身由 opencode PTY API 管理，这里负责 Shell 配置：
+//! - 检测系统内置 Shell
+//! - 用户自定义 Shell 配置的增删改查
+//! - 将配置解析为可直接传给 PTY 创建接口的命令、参数、环境变量和工作目录
+//! - 终端会话快照的保存与恢复（跨应用重启）
+
+use crate::error::{AxonError, ErrorKind};
+use crate::opencode::{ShellProfile, StartupDirectory};
+use crate::state::AppState;
+use crate::utils::paths::get_app_data_dir;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::State;
+use tracing::{debug, error, warn};
+
+/// 内置配置 ID 前缀
+const BUILTIN_PROFILE_PREFIX: &str = "system-";
+
+/// 终端会话快照文件
+const TERMINAL_SESSIONS_FILE: &str = "terminal_sessions.json";
+
+/// 每个终端保留的回滚内容上限（字节）
+const MAX_SCROLLBACK_BYTES: usize = 64 * 1024;
+
+/// 解析后的 Shell 启动参数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedShell {
+    pub profile_id: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub cwd: String,
+}
+
+/// 终端会话快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalSessionSnapshot {
+    pub title: String,
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    pub cwd: String,
+    /// 回滚内容末尾（序列化后的终端输出）
+    #[serde(default)]
+    pub scrollback: String,
+    #[serde(default)]
+    pub rows: Option<u16>,
+    #[serde(default)]
+    pub cols: Option<u16>,
+    /// 保存时间（毫秒时间戳）
+    #[serde(default)]
+    pub saved_at: i64,
+}
+
+/// 列出所有 Shell 配置（系统检测 + 用户自定义）
+///
+/// 用户配置与内置配置 id 相同时，以用户配置为准
+#[tauri::command]
+pub fn list_shell_profiles(state: State<'_, AppState>) -> Vec<ShellProfile> {
+    collect_shell_profiles(&state)
+}
+
+/// 保存 Shell 配置（id 已存在则更新）
+#[tauri::command]
+pub fn save_shell_profile(
+    state: State<'_, AppState>,
+    mut profile: ShellProfile,
+) -> Result<ShellProfile, AxonError> {
+    let audit_args = json!({ "id": &profile.id, "name": &profile.name });
+    state.audit.track_sync("save_shell_profile", audit_args, || {
+        if profile.name.trim().is_empty() {
+            return Err(AxonError::invalid_input("配置名称不能为空"));
+        }
+        if profile.executable.trim().is_empty() {
+            return Err(AxonError::invalid_input("可执行文件不能为空"));
+        }
+        if let StartupDirectory::Custom { path } = &profile.startup_directory {
+            if !Path::new(path).is_dir() {
+                return Err(AxonError::not_found(format!("启动目录不存在: {}", path)));
+            }
+        }
+
+        if profile.id.trim().is_empty() {
+            profile.id = format!("shell-{}", chrono::Utc::now().timestamp_millis());
+        }
+        profile.builtin = false;
+
+        debug!("保存 Shell 配置: {} ({})", profile.name, profile.id);
+        state.settings.upsert_shell_profile(profile.clone())?;
+        Ok(profile)
+    })
+}
+
+/// 删除用户自定义 Shell 配置
+#[tauri::command]
+pub fn delete_shell_profile(state: State<'_, AppState>, id: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "id": &id });
+    state.audit.track_sync("delete_shell_profile", audit_args, || {
+        debug!("删除 Shell 配置: {}", id);
+        state.settings.delete_shell_profile(&id).map_err(AxonError::from)
+    })
+}
+
+/// 设置默认 Shell 配置
+#[tauri::command]
+pub fn set_default_shell_profile(
+    state: State<'_, AppState>,
+    id: Option<String>,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "id": &id });
+    state.audit.track_sync("set_default_shell_profile", audit_args, || {
+        if let Some(id) = &id {
+            if !collect_shell_profiles(&state).iter().any(|p| &p.id == id) {
+                return Err(AxonError::not_found(format!("Shell 配置不存在: {}", id)));
+            }
+        }
+        state.settings.set_default_shell_profile_id(id).map_err(AxonError::from)
+    })
+}
+
+/// 将 Shell 配置解析为 PTY 启动参数
+///
+/// `profile_id` 为空时使用默认配置；`cwd` 显式指定时优先于配置中的启动目录策略
+#[tauri::command]
+pub fn resolve_shell_profile(
+    state: State<'_, AppState>,
+    profile_id: Option<String>,
+    cwd: Option<String>,
+) -> Result<ResolvedShell, AxonError> {
+    let profiles = collect_shell_profiles(&state);
+    let wanted = profile_id.or_else(|| state.settings.get_default_shell_profile_id());
+
+    let profile = match wanted {
+        Some(id) => profiles
+            .iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| AxonError::not_found(format!("Shell 配置不存在: {}", id)))?,
+        None => profiles
+            .first()
+            .ok_or_else(|| AxonError::not_found("未找到可用的 Shell"))?,
+    };
+
+    let cwd = match cwd.filter(|c| !c.trim().is_empty()) {
+        Some(cwd) => cwd,
+        None => resolve_startup_directory(&state, &profile.startup_directory),
+    };
+
+    debug!(
+        "解析 Shell 配置: {} -> {} {:?}, cwd: {}",
+        profile.id, profile.executable, profile.args, cwd
+    );
+
+    Ok(ResolvedShell {
+        profile_id: profile.id.clone(),
+        command: profile.executable.clone(),
+        args: profile.args.clone(),
+        env: profile.env.clone(),
+        cwd,
+    })
+}
+
+/// 启用或关闭终端会话持久化
+///
+/// 关闭时会删除已保存的快照
+#[tauri::command]
+pub fn set_terminal_persistence(state: State<'_, AppState>, enabled: bool) -> Result<(), AxonError> {
+    let audit_args = json!({ "enabled": enabled });
+    state.audit.track_sync("set_terminal_persistence", audit_args, || {
+        state.settings.set_persist_terminal_sessions(enabled)?;
+        if !enabled {
+            remove_sessions_file();
+        }
+        Ok(())
+    })
+}
+
+/// 保存终端会话快照（通常在应用关闭前调用）
+///
+/// 未启用持久化时不保存；回滚内容只保留末尾部分
+#[tauri::command]
+pub fn save_terminal_sessions(
+    state: State<'_, AppState>,
+    sessions: Vec<TerminalSessionSnapshot>,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "count": sessions.len() });
+    state.audit.track_sync("save_terminal_sessions", audit_args, || {
+        if !state.settings.get_persist_terminal_sessions() {
+            return Ok(());
+        }
+
+        let path = get_sessions_path().ok_or_else(|| {
+            AxonError::localized(ErrorKind::Unavailable, "app.data_dir_unavailable", json!(null))
+        })?;
+        let now = chrono::Utc::now().timestamp_millis();
+        let sessions: Vec<TerminalSessionSnapshot> = sessions
+            .into_iter()
+            .map(|mut session| {
+                session.scrollback = tail_of(&session.scrollback, MAX_SCROLLBACK_BYTES).to_string();
+                session.saved_at = now;
+                session
+            })
+            .collect();
+
+        let content = serde_json::to_string_pretty(&sessions)
+            .map_err(|e| format!("序列化终端会话失败: {}", e))?;
+        std::fs::write(&path, content).map_err(|e| {
+            error!("写入终端会话失败: {:?}, 错误: {}", path, e);
+            AxonError::io("写入终端会话失败", &e)
+        })?;
+
+        debug!("已保存 {} 个终端会话", sessions.len());
+        Ok(())
+    })
+}
+
+/// 取出上次保存的终端会话快照
+///
+/// 读取后删除快照文件，避免重复恢复；前端据此在原工作目录重新创建 PTY 并回放回滚内容
+#[tauri::command]
+pub fn restore_terminals(state: State<'_, AppState>) -> Vec<TerminalSessionSnapshot> {
+    if !state.settings.get_persist_terminal_sessions() {
+        return Vec::new();
+    }
+
+    let Some(path) = get_sessions_path().filter(|p| p.exists()) else {
+        return Vec::new();
+    };
+
+    let sessions = std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            serde_json::from_str::<Vec<TerminalSessionSnapshot>>(&content)
+                .map_err(|e| e.to_string())
+        })
+        .unwrap_or_else(|e| {
+            warn!("读取终端会话失败: {}", e);
+            Vec::new()
+        });
+    remove_sessions_file();
+
+    // 工作目录已不存在时回退到项目目录
+    let fallback = resolve_startup_directory(&state, &StartupDirectory::Project);
+    let sessions: Vec<TerminalSessionSnapshot> = sessions
+        .into_iter()
+        .map(|mut session| {
+            if !Path::new(&session.cwd).is_dir() {
+                session.cwd = fallback.clone();
+            }
+            session
+        })
+        .collect();
+
+    debug!("恢复 {} 个终端会话", sessions.len());
+    sessions
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn get_sessions_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|p| p.join(TERMINAL_SESSIONS_FILE))
+}
+
+fn remove_sessions_file() {
+    if let Some(path) = get_sessions_path().filter(|p| p.exists()) {
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!("删除终端会话文件失败: {}", e);
+        }
+    }
+}
+
+/// 截取字符串末尾不超过 `max_bytes` 的部分（按行对齐）
+fn tail_of(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+    let mut start = text.len() - max_bytes;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    // 从下一行开始，避免截断半行内容或转义序列
+    if let Some(pos) = text[start..].find('\n') {
+        start += pos + 1;
+    }
+    &text[start..]
+}
+
+fn collect_shell_profiles(state: &AppState) -> Vec<ShellProfile> {
+    let user_profiles = state.settings.get_shell_profiles();
+    let mut profiles: Vec<ShellProfile> = detect_builtin_profiles()
+        .into_iter()
+        .filter(|builtin| !user_profiles.iter().any(|p| p.id == builtin.id))
+        .collect();
+    profiles.extend(user_profiles);
+    profiles
+}
+
+fn resolve_startup_directory(state: &AppState, strategy: &StartupDirectory) -> String {
+    let home = || {
+        dirs::home_dir()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| ".".to_string())
+    };
+
+    match strategy {
+        StartupDirectory::Project => state
+            .settings
+            .get_project_directory()
+            .filter(|p| Path::new(p).is_dir())
+            .unwrap_or_else(home),
+        StartupDirectory::Home => home(),
+        StartupDirectory::Custom { path } => {
+            if Path::new(path).is_dir() {
+                path.clone()
+            } else {
+                warn!("启动目录不存在，回退到主目录: {}", path);
+                home()
+            }
+        }
+    }
+}
+
+fn builtin_profile(id: &str, name: &str, executable: PathBuf, args: &[&str]) -> ShellProfile {
+    ShellProfile {
+        id: format!("{}{}", BUILTIN_PROFILE_PREFIX, id),
+        name: name.to_string(),
+        executable: executable.to_string_lossy().to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+        env: HashMap::new(),
+        startup_directory: StartupDirectory::Project,
+        builtin: true,
+    }
+}
+
+/// 在 PATH 中查找可执行文件
+fn find_in_path(name: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var)
+        .map(|dir| dir.join(name))
+        .find(|candidate| candidate.is_file())
+}
+
+/// 检测系统中可用的 Shell
+#[cfg(target_os = "windows")]
+fn detect_builtin_profiles() -> Vec<ShellProfile> {
+    let mut profiles = Vec::new();
+
+    if let Some(path) = find_in_path("pwsh.exe") {
+        profiles.push(builtin_profile("pwsh", "PowerShell 7", path, &["-NoLogo"]));
+    }
+    if let Some(path) = find_in_path("powershell.exe") {
+        profiles.push(builtin_profile(
+            "powershell",
+            "Windows PowerShell",
+            path,
+            &["-NoLogo"],
+        ));
+    }
+    if let Some(path) = find_in_path("cmd.exe") {
+        profiles.push(builtin_profile("cmd", "Command Prompt", path, &[]));
+    }
+
+    // Git Bash 通常不在 PATH 中
+    let git_bash = PathBuf::from(r"C:\Program Files\Git\bin\bash.exe");
+    if git_bash.is_file() {
+        profiles.push(builtin_profile(
+            "git-bash",
+            "Git Bash",
+            git_bash,
+            &["--login", "-i"],
+        ));
+    }
+
+    profiles
+}
+
+/// 检测系统中可用的 Shell（登录 Shell 排在首位）
+#[cfg(not(target_os = "windows"))]
+fn detect_builtin_profiles() -> Vec<ShellProfile> {
+    let mut profiles = Vec::new();
+
+    let login_shell = std::env::var("SHELL")
+        .ok()
+        .map(PathBuf::from)
+        .filter(|p| p.is_file());
+    if let Some(path) = &login_shell {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "shell".to_string());
+        profiles.push(builtin_profile(&name, &name, path.clone(), &["-l"]));
+    }
+
+    for name in ["zsh", "bash", "fish", "sh"] {
+        let id = format!("{}{}", BUILTIN_PROFILE_PREFIX, name);
+        if profiles.iter().any(|p| p.id == id) {
+            continue;
+        }
+        if let Some(path) = find_in_path(name) {
+            let args: &[&str] = if name == "sh" { &[] } else { &["-l"] };
+            profiles.push(builtin_profile(name, name, path, args));
+        }
+    }
+
+    profiles
+}
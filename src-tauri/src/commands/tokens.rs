@@ -0,0 +1,93 @@
+//! Token 计数命令
+//!
+//! 为成本估算和上下文窗口管理提供 token 计数，
+//! 并结合模型注册表中的上下文窗口大小判断内容是否能放入上下文。
+
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::utils::tokens::{self, CountMethod};
+use serde::Serialize;
+use tauri::State;
+use tracing::debug;
+
+/// 单段文本的 token 计数
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenCount {
+    pub tokens: usize,
+    /// 计数方式（exact / approximate / estimate）
+    pub method: CountMethod,
+    /// 使用的分词器（启发式估算时为空）
+    pub tokenizer: Option<String>,
+}
+
+/// Token 计数结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenCountResponse {
+    pub model_id: Option<String>,
+    /// 各段文本的计数（单条计数时只有一项）
+    pub items: Vec<TokenCount>,
+    /// 总 token 数
+    pub total_tokens: usize,
+    /// 模型上下文窗口（注册表中无此模型时为空）
+    pub context_window: Option<u64>,
+    /// 是否能放入上下文窗口（未知上下文窗口时为空）
+    pub fits_in_context: Option<bool>,
+}
+
+/// 计算文本的 token 数
+///
+/// `model_id` 为 "provider/model" 格式，用于选择分词器和查询上下文窗口
+#[tauri::command]
+pub async fn count_tokens(
+    state: State<'_, AppState>,
+    text: String,
+    model_id: Option<String>,
+) -> Result<TokenCountResponse, AxonError> {
+    count_tokens_batch(state, vec![text], model_id).await
+}
+
+/// 批量计算多段文本的 token 数
+#[tauri::command]
+pub async fn count_tokens_batch(
+    state: State<'_, AppState>,
+    texts: Vec<String>,
+    model_id: Option<String>,
+) -> Result<TokenCountResponse, AxonError> {
+    debug!("计算 token: {} 段文本, 模型: {:?}", texts.len(), model_id);
+
+    let context_window = model_id
+        .as_deref()
+        .and_then(|id| state.models_registry.get_model_defaults(id))
+        .map(|defaults| defaults.context_window)
+        .filter(|window| *window > 0);
+
+    // 分词器首次加载和长文本编码较耗时，放到阻塞线程中执行
+    let model = model_id.clone();
+    let items = tokio::task::spawn_blocking(move || {
+        texts
+            .iter()
+            .map(|text| {
+                let result = tokens::count_tokens(text, model.as_deref());
+                TokenCount {
+                    tokens: result.tokens,
+                    method: result.method,
+                    tokenizer: result.tokenizer.map(String::from),
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| AxonError::internal(format!("计算 token 任务失败: {}", e)))?;
+
+    let total_tokens = items.iter().map(|item| item.tokens).sum::<usize>();
+
+    Ok(TokenCountResponse {
+        model_id,
+        items,
+        total_tokens,
+        context_window,
+        fits_in_context: context_window.map(|window| total_tokens as u64 <= window),
+    })
+}
@@ -0,0 +1,97 @@
+//! 自定义工具命令
+//!
+//! 工具在 Bridge 插件启动时注册到 OpenCode，增删改后需要重启 OpenCode 才会生效。
+//! HTTP 工具模板中引用的密钥保存在系统钥匙串。
+
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::tools::{ToolDefinition, ToolExecution};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tauri::State;
+
+/// 列出所有自定义工具
+#[tauri::command]
+pub fn list_tools(state: State<'_, AppState>) -> Result<Vec<ToolDefinition>, AxonError> {
+    state.tools.list()
+}
+
+/// 获取单个工具，不存在时返回 null
+#[tauri::command]
+pub fn get_tool(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Option<ToolDefinition>, AxonError> {
+    state.tools.get(&name)
+}
+
+/// 创建或更新工具
+#[tauri::command]
+pub fn save_tool(
+    state: State<'_, AppState>,
+    tool: ToolDefinition,
+) -> Result<ToolDefinition, AxonError> {
+    let audit_args = json!({ "name": &tool.name, "enabled": tool.enabled });
+    state
+        .audit
+        .track_sync("save_tool", audit_args, || state.tools.save(tool))
+}
+
+/// 删除工具，返回工具是否存在
+#[tauri::command]
+pub fn delete_tool(state: State<'_, AppState>, name: String) -> Result<bool, AxonError> {
+    let audit_args = json!({ "name": &name });
+    state
+        .audit
+        .track_sync("delete_tool", audit_args, || state.tools.delete(&name))
+}
+
+/// 用给定参数试运行工具
+#[tauri::command]
+pub async fn test_tool(
+    state: State<'_, AppState>,
+    name: String,
+    args: Value,
+) -> Result<ToolExecution, AxonError> {
+    let audit_args = json!({ "name": &name });
+    state
+        .audit
+        .track("test_tool", audit_args, state.tools.execute(&name, args))
+        .await
+}
+
+/// 列出已保存到钥匙串的工具密钥名称
+#[tauri::command]
+pub fn list_tool_secrets(state: State<'_, AppState>) -> Result<Vec<String>, AxonError> {
+    state.tools.list_secrets()
+}
+
+/// 保存工具密钥到系统钥匙串（审计日志不记录密钥值）
+#[tauri::command]
+pub async fn set_tool_secret(
+    state: State<'_, AppState>,
+    name: String,
+    value: String,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "name": &name });
+    let tools = Arc::clone(&state.tools);
+    state
+        .audit
+        .track("set_tool_secret", audit_args, async move {
+            tokio::task::spawn_blocking(move || tools.set_secret(&name, &value)).await?
+        })
+        .await
+}
+
+/// 从系统钥匙串删除工具密钥
+#[tauri::command]
+pub async fn delete_tool_secret(state: State<'_, AppState>, name: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "name": &name });
+    let tools = Arc::clone(&state.tools);
+    state
+        .audit
+        .track("delete_tool_secret", audit_args, async move {
+            tokio::task::spawn_blocking(move || tools.delete_secret(&name)).await?
+        })
+        .await
+}
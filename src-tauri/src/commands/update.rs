@@ -1,8 +1,12 @@
 // 应用更新相关的命令
 
+use crate::app_update::AppUpdateManager;
+use crate::error::AxonError;
+use crate::opencode::UpdateChannel;
+use crate::state::AppState;
 use serde::{Deserialize, Serialize};
-use tauri::command;
-use tauri_plugin_updater::UpdaterExt;
+use serde_json::json;
+use tauri::{command, State};
 
 /// 更新信息响应
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -17,59 +21,63 @@ pub struct UpdateInfo {
     pub update_notes: Option<String>,
     /// 下载进度百分比（0-100）
     pub download_progress: u32,
+    /// 检查所用的更新通道
+    pub channel: UpdateChannel,
 }
 
 /// 检查应用更新
 ///
-/// 查询 GitHub releases 获取最新版本信息。
+/// 按设置中的更新通道查询 GitHub releases 获取最新版本信息。
 /// 如果有新版本可用，将在后台自动下载。
 #[command]
-pub async fn check_app_update(app: tauri::AppHandle) -> Result<UpdateInfo, String> {
+pub async fn check_app_update(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<UpdateInfo, AxonError> {
     // 获取当前版本
     let current_version = app.package_info().version.to_string();
+    let channel = state.settings.get_update_channel();
 
     // 创建 updater 查询
-    match app.updater() {
-        Ok(updater) => {
-            match updater.check().await {
-                Ok(update_response) => match update_response {
-                    Some(update) => {
-                        // 有可用的更新，获取版本和更新说明
-                        let new_version = update.version.clone();
-                        let update_notes = update.body.clone();
-
-                        tracing::info!("发现新版本: {}", new_version);
-
-                        // 不自动安装，让前端决定
-                        Ok(UpdateInfo {
-                            available: true,
-                            current_version,
-                            new_version: Some(new_version),
-                            update_notes,
-                            download_progress: 100,
-                        })
-                    }
-                    None => {
-                        // 已是最新版本
-                        tracing::info!("已是最新版本: {}", current_version);
-                        Ok(UpdateInfo {
-                            available: false,
-                            current_version,
-                            new_version: None,
-                            update_notes: None,
-                            download_progress: 0,
-                        })
-                    }
-                },
-                Err(e) => {
-                    tracing::warn!("检查更新出错: {}", e);
-                    Err(format!("检查更新失败: {}", e))
-                }
+    let updater = AppUpdateManager::updater(&app, channel)?;
+    match updater.check().await {
+        Ok(update_response) => match update_response {
+            Some(update) => {
+                // 有可用的更新，获取版本和更新说明
+                let new_version = update.version.clone();
+                let update_notes = update.body.clone();
+
+                tracing::info!("发现新版本: {} ({:?})", new_version, channel);
+                state
+                    .webhooks
+                    .notify_update_available(&new_version, update_notes.as_deref());
+
+                // 不自动安装，让前端决定
+                Ok(UpdateInfo {
+                    available: true,
+                    current_version,
+                    new_version: Some(new_version),
+                    update_notes,
+                    download_progress: 100,
+                    channel,
+                })
             }
-        }
+            None => {
+                // 已是最新版本
+                tracing::info!("已是最新版本: {}", current_version);
+                Ok(UpdateInfo {
+                    available: false,
+                    current_version,
+                    new_version: None,
+                    update_notes: None,
+                    download_progress: 0,
+                    channel,
+                })
+            }
+        },
         Err(e) => {
-            tracing::error!("获取 updater 实例失败: {}", e);
-            Err("更新模块未初始化".to_string())
+            tracing::warn!("检查更新出错: {}", e);
+            Err(AxonError::network(format!("检查更新失败: {}", e)))
         }
     }
 }
@@ -82,55 +90,127 @@ pub async fn check_app_update(app: tauri::AppHandle) -> Result<UpdateInfo, Strin
 /// 3. 安装更新
 /// 4. 退出应用（安装程序会自动启动新版本）
 #[command]
-pub async fn install_app_update(app: tauri::AppHandle) -> Result<(), String> {
-    match app.updater() {
-        Ok(updater) => {
-            // 重新检查更新
-            match updater.check().await {
-                Ok(update_response) => match update_response {
-                    Some(update) => {
-                        tracing::info!("准备安装新版本: {}", update.version);
-
-                        // 安装更新（会自动退出应用）
-                        // 使用简单的回调函数来跟踪进度
-                        match update
-                            .download_and_install(
-                                |chunk_len, _content_length| {
-                                    tracing::debug!("下载进度: {} 字节", chunk_len);
-                                },
-                                || {
-                                    tracing::info!("下载完成，开始安装");
-                                },
-                            )
-                            .await
-                        {
-                            Ok(_) => {
-                                tracing::info!("更新下载并安装成功，正在重启应用");
-                                Ok(())
-                            }
-                            Err(e) => {
-                                tracing::error!("下载并安装更新失败: {}", e);
-                                Err(format!("更新安装失败: {}", e))
-                            }
-                        }
-                    }
-                    None => Err("已是最新版本，无需更新".to_string()),
-                },
-                Err(e) => {
-                    tracing::error!("重新检查更新失败: {}", e);
-                    Err(format!("检查更新失败: {}", e))
-                }
-            }
+pub async fn install_app_update(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AxonError> {
+    let update = find_update(&app, state.settings.get_update_channel()).await?;
+    tracing::info!("准备安装新版本: {}", update.version);
+
+    // 下载进度通过 app-update:progress 事件发送给前端
+    let bytes = state.app_update.download(&app, &update).await?;
+    tracing::info!("下载完成，开始安装");
+
+    // 安装更新（会自动退出应用）
+    match update.install(&bytes) {
+        Ok(_) => {
+            tracing::info!("更新下载并安装成功，正在重启应用");
+            // 已直接安装，之前暂存的版本不再需要
+            state.app_update.discard();
+            Ok(())
         }
         Err(e) => {
-            tracing::error!("获取 updater 实例失败: {}", e);
-            Err("更新模块未初始化".to_string())
+            tracing::error!("下载并安装更新失败: {}", e);
+            Err(AxonError::internal(format!("更新安装失败: {}", e)))
         }
     }
 }
 
+/// 下载更新，退出应用时再安装
+///
+/// 返回暂存的新版本号
+#[command]
+pub async fn stage_app_update(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, AxonError> {
+    let audit_args = json!({});
+    state
+        .audit
+        .track("stage_app_update", audit_args, async {
+            let update = find_update(&app, state.settings.get_update_channel()).await?;
+            tracing::info!("下载新版本（退出时安装）: {}", update.version);
+
+            let bytes = state.app_update.download(&app, &update).await?;
+
+            let version = update.version.clone();
+            state.app_update.stage(update, bytes);
+            state.notifications.notify_task_finished(
+                "更新已下载",
+                &format!("Axon {} 将在退出时安装", version),
+                true,
+            );
+            Ok(version)
+        })
+        .await
+}
+
+/// 取消进行中的更新下载，没有下载时返回 false
+#[command]
+pub fn cancel_app_update(state: State<'_, AppState>) -> Result<bool, AxonError> {
+    let audit_args = json!({});
+    state
+        .audit
+        .track_sync("cancel_app_update", audit_args, || {
+            Ok(state.app_update.cancel_download())
+        })
+}
+
+/// 获取已下载、等待退出时安装的版本
+#[command]
+pub fn get_staged_app_update(state: State<'_, AppState>) -> Option<String> {
+    state.app_update.staged_version()
+}
+
+/// 丢弃已下载的更新，返回是否存在
+#[command]
+pub fn discard_staged_app_update(state: State<'_, AppState>) -> Result<bool, AxonError> {
+    let audit_args = json!({});
+    state
+        .audit
+        .track_sync("discard_staged_app_update", audit_args, || {
+            Ok(state.app_update.discard())
+        })
+}
+
+/// 获取应用更新通道
+#[command]
+pub fn get_update_channel(state: State<'_, AppState>) -> UpdateChannel {
+    state.settings.get_update_channel()
+}
+
+/// 设置应用更新通道
+#[command]
+pub fn set_update_channel(
+    state: State<'_, AppState>,
+    channel: UpdateChannel,
+) -> Result<(), AxonError> {
+    let audit_args = json!({ "channel": channel });
+    state.audit.track_sync("set_update_channel", audit_args, || {
+        state.settings.set_update_channel(channel)?;
+        tracing::info!("应用更新通道已切换为 {:?}", channel);
+        Ok(())
+    })
+}
+
 /// 获取当前应用版本信息
 #[command]
 pub fn get_app_version(app: tauri::AppHandle) -> String {
     app.package_info().version.to_string()
 }
+
+/// 按通道重新检查更新，没有新版本时返回错误
+async fn find_update(
+    app: &tauri::AppHandle,
+    channel: UpdateChannel,
+) -> Result<tauri_plugin_updater::Update, AxonError> {
+    let updater = AppUpdateManager::updater(app, channel)?;
+    match updater.check().await {
+        Ok(Some(update)) => Ok(update),
+        Ok(None) => Err(AxonError::not_found("已是最新版本，无需更新")),
+        Err(e) => {
+            tracing::error!("重新检查更新失败: {}", e);
+            Err(AxonError::network(format!("检查更新失败: {}", e)))
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! 用量统计命令
+
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::usage::{ModelPerformance, UsageRange, UsageSummary};
+use tauri::State;
+use tracing::debug;
+
+/// 获取指定范围内各服务商 / 模型的用量汇总
+#[tauri::command]
+pub async fn get_usage_summary(
+    state: State<'_, AppState>,
+    range: UsageRange,
+) -> Result<UsageSummary, AxonError> {
+    debug!("获取用量汇总: {:?}", range);
+    state.usage.summary(&range).map_err(AxonError::invalid_input)
+}
+
+/// 获取指定范围内模型的性能统计（排队耗时、首 token 延迟、输出速度）
+///
+/// `model_id` 为 "provider/model" 时只统计该服务商，否则按服务商分别统计提供该模型的所有服务商
+#[tauri::command]
+pub async fn get_model_performance(
+    state: State<'_, AppState>,
+    model_id: String,
+    range: UsageRange,
+) -> Result<ModelPerformance, AxonError> {
+    debug!("获取模型性能: {} {:?}", model_id, range);
+    state
+        .usage
+        .performance(&model_id, &range)
+        .map_err(AxonError::invalid_input)
+}
@@ -0,0 +1,88 @@
+//! Webhook 通知命令
+
+use crate::error::AxonError;
+use crate::opencode::WebhookConfig;
+use crate::state::AppState;
+use crate::webhooks::WebhookEvent;
+use serde_json::json;
+use std::collections::HashSet;
+use tauri::State;
+
+/// 获取 Webhook 配置
+#[tauri::command]
+pub fn get_webhooks(state: State<'_, AppState>) -> Vec<WebhookConfig> {
+    state.settings.get_webhooks()
+}
+
+/// 保存 Webhook 配置（整体替换）
+#[tauri::command]
+pub fn set_webhooks(
+    state: State<'_, AppState>,
+    webhooks: Vec<WebhookConfig>,
+) -> Result<(), AxonError> {
+    // URL 中通常带有密钥，审计日志只记录 ID 和名称
+    let audit_args = json!({
+        "webhooks": webhooks
+            .iter()
+            .map(|w| json!({ "id": &w.id, "name": &w.name, "kind": w.kind }))
+            .collect::<Vec<_>>(),
+    });
+    state.audit.track_sync("set_webhooks", audit_args, || {
+        validate_webhooks(&webhooks)?;
+        state
+            .settings
+            .set_webhooks(webhooks)
+            .map_err(AxonError::from)
+    })
+}
+
+/// 向指定 Webhook 发送测试消息，返回 HTTP 状态码
+#[tauri::command]
+pub async fn test_webhook(state: State<'_, AppState>, id: String) -> Result<u16, AxonError> {
+    let audit_args = json!({ "id": &id });
+    state
+        .audit
+        .track("test_webhook", audit_args, state.webhooks.test(&id))
+        .await
+}
+
+/// 上报工作流执行结束，发送系统通知并向订阅了该事件的 Webhook 发送消息
+#[tauri::command]
+pub fn report_workflow_finished(
+    state: State<'_, AppState>,
+    workflow_id: String,
+    workflow_name: String,
+    success: bool,
+    summary: Option<String>,
+) {
+    let event =
+        WebhookEvent::workflow_finished(&workflow_id, &workflow_name, success, summary.as_deref());
+    state
+        .notifications
+        .notify_task_finished(&event.title, &event.message, success);
+    state.webhooks.dispatch(event);
+}
+
+fn validate_webhooks(webhooks: &[WebhookConfig]) -> Result<(), AxonError> {
+    let mut ids = HashSet::new();
+    for webhook in webhooks {
+        if webhook.id.trim().is_empty() {
+            return Err(AxonError::invalid_input("Webhook ID 不能为空"));
+        }
+        if !ids.insert(webhook.id.as_str()) {
+            return Err(AxonError::invalid_input(format!(
+                "Webhook ID 重复: {}",
+                webhook.id
+            )));
+        }
+        let valid_url = reqwest::Url::parse(&webhook.url)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+        if !valid_url {
+            return Err(AxonError::invalid_input(format!(
+                "Webhook「{}」的 URL 无效",
+                webhook.name
+            )));
+        }
+    }
+    Ok(())
+}
@@ -6,9 +6,19 @@
 //! - 删除 Workflow 配置
 //! - 获取 Workflow 存储目录
 
+use super::agent::get_agents_dir_path;
+use crate::error::AxonError;
+use crate::opencode::is_model_allowed;
+use crate::state::AppState;
+use crate::team_sync::{DefinitionScope, TeamItemKind};
+use crate::workflow::{
+    layered_layout, plan_execution, ExecutionPlan, NodePosition, WorkflowGraph, PRIMARY_NODE_ID,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, State};
 use tracing::{debug, error, info};
 
 /// Workflow 配置目录名称
@@ -37,13 +47,16 @@ pub struct WorkflowSummary {
     pub subagent_count: i64,
     /// 更新时间
     pub updated_at: i64,
+    /// 来源（团队仓库中的定义只读）
+    #[serde(default)]
+    pub scope: DefinitionScope,
 }
 
 /// 获取 Workflow 配置存储目录
 /// 
 /// 返回应用数据目录下的 workflows 文件夹路径
 #[tauri::command]
-pub async fn get_workflows_directory(app: AppHandle) -> Result<String, String> {
+pub async fn get_workflows_directory(app: AppHandle) -> Result<String, AxonError> {
     let app_data_dir = app
         .path()
         .app_data_dir()
@@ -55,7 +68,7 @@ pub async fn get_workflows_directory(app: AppHandle) -> Result<String, String> {
     if !workflows_dir.exists() {
         std::fs::create_dir_all(&workflows_dir).map_err(|e| {
             error!("创建 workflows 目录失败: {:?}, 错误: {}", workflows_dir, e);
-            format!("创建 workflows 目录失败: {}", e)
+            AxonError::io("创建 workflows 目录失败", &e)
         })?;
         info!("创建 workflows 目录: {:?}", workflows_dir);
     }
@@ -67,21 +80,23 @@ pub async fn get_workflows_directory(app: AppHandle) -> Result<String, String> {
 /// 
 /// 读取 workflows 目录下的所有 JSON 文件，返回配置摘要列表
 #[tauri::command]
-pub async fn list_workflows(app: AppHandle) -> Result<Vec<WorkflowSummary>, String> {
+pub async fn list_workflows(app: AppHandle, state: State<'_, AppState>) -> Result<Vec<WorkflowSummary>, AxonError> {
     let workflows_dir = get_workflows_dir_path(&app)?;
     
     debug!("列出 workflows 目录: {:?}", workflows_dir);
     
     if !workflows_dir.exists() {
-        debug!("workflows 目录不存在，返回空列表");
-        return Ok(Vec::new());
+        debug!("workflows 目录不存在，只返回团队定义");
+        let mut workflows = Vec::new();
+        merge_team_workflows(&state, &mut workflows);
+        return Ok(workflows);
     }
     
     let mut workflows = Vec::new();
     
     let entries = std::fs::read_dir(&workflows_dir).map_err(|e| {
         error!("读取 workflows 目录失败: {:?}, 错误: {}", workflows_dir, e);
-        format!("读取 workflows 目录失败: {}", e)
+        AxonError::io("读取 workflows 目录失败", &e)
     })?;
     
     for entry in entries {
@@ -111,6 +126,8 @@ pub async fn list_workflows(app: AppHandle) -> Result<Vec<WorkflowSummary>, Stri
         }
     }
     
+    merge_team_workflows(&state, &mut workflows);
+
     // 按更新时间降序排序
     workflows.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
     
@@ -122,20 +139,25 @@ pub async fn list_workflows(app: AppHandle) -> Result<Vec<WorkflowSummary>, Stri
 /// 
 /// 根据 Workflow ID 读取完整的 JSON 配置
 #[tauri::command]
-pub async fn read_workflow(app: AppHandle, workflow_id: String) -> Result<String, String> {
+pub async fn read_workflow(app: AppHandle, state: State<'_, AppState>, workflow_id: String) -> Result<String, AxonError> {
     let workflows_dir = get_workflows_dir_path(&app)?;
     let workflow_path = workflows_dir.join(format!("{}{}", workflow_id, WORKFLOW_FILE_EXT));
     
     debug!("读取 workflow 配置: {:?}", workflow_path);
     
-    if !workflow_path.exists() {
+    // 本地不存在时读取团队仓库中的定义
+    let workflow_path = if workflow_path.exists() {
+        workflow_path
+    } else if let Some(team_path) = state.team_sync.definition_path(TeamItemKind::Workflow, &workflow_id) {
+        team_path
+    } else {
         error!("Workflow 配置文件不存在: {:?}", workflow_path);
-        return Err(format!("Workflow 不存在: {}", workflow_id));
-    }
+        return Err(AxonError::not_found(format!("Workflow 不存在: {}", workflow_id)));
+    };
     
     let content = std::fs::read_to_string(&workflow_path).map_err(|e| {
         error!("读取 workflow 文件失败: {:?}, 错误: {}", workflow_path, e);
-        format!("读取 Workflow 配置失败: {}", e)
+        AxonError::io("读取 Workflow 配置失败", &e)
     })?;
     
     Ok(content)
@@ -145,61 +167,75 @@ pub async fn read_workflow(app: AppHandle, workflow_id: String) -> Result<String
 /// 
 /// 将 Workflow 配置保存到文件，文件名为 {workflow_id}.json
 #[tauri::command]
-pub async fn save_workflow(app: AppHandle, workflow_id: String, config: String) -> Result<(), String> {
-    let workflows_dir = get_workflows_dir_path(&app)?;
-    
-    // 确保目录存在
-    if !workflows_dir.exists() {
-        std::fs::create_dir_all(&workflows_dir).map_err(|e| {
-            error!("创建 workflows 目录失败: {:?}, 错误: {}", workflows_dir, e);
-            format!("创建 workflows 目录失败: {}", e)
+pub async fn save_workflow(app: AppHandle, state: State<'_, AppState>, workflow_id: String, config: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "workflowId": &workflow_id, "bytes": config.len() });
+    state.audit.track("save_workflow", audit_args, async {
+        let workflows_dir = get_workflows_dir_path(&app)?;
+
+        // 确保目录存在
+        if !workflows_dir.exists() {
+            std::fs::create_dir_all(&workflows_dir).map_err(|e| {
+                error!("创建 workflows 目录失败: {:?}, 错误: {}", workflows_dir, e);
+                AxonError::io("创建 workflows 目录失败", &e)
+            })?;
+        }
+
+        let workflow_path = workflows_dir.join(format!("{}{}", workflow_id, WORKFLOW_FILE_EXT));
+
+        debug!("保存 workflow 配置: {:?}", workflow_path);
+
+        // 验证 JSON 格式
+        let _: serde_json::Value = serde_json::from_str(&config).map_err(|e| {
+            error!("无效的 JSON 格式: {}", e);
+            format!("无效的 Workflow 配置格式: {}", e)
         })?;
-    }
-    
-    let workflow_path = workflows_dir.join(format!("{}{}", workflow_id, WORKFLOW_FILE_EXT));
-    
-    debug!("保存 workflow 配置: {:?}", workflow_path);
-    
-    // 验证 JSON 格式
-    let _: serde_json::Value = serde_json::from_str(&config).map_err(|e| {
-        error!("无效的 JSON 格式: {}", e);
-        format!("无效的 Workflow 配置格式: {}", e)
-    })?;
-    
-    // 格式化 JSON 输出（便于阅读）
-    let formatted = format_json(&config)?;
-    
-    std::fs::write(&workflow_path, formatted).map_err(|e| {
-        error!("写入 workflow 文件失败: {:?}, 错误: {}", workflow_path, e);
-        format!("保存 Workflow 配置失败: {}", e)
-    })?;
-    
-    info!("Workflow 配置已保存: {}", workflow_id);
-    Ok(())
+
+        // 格式化 JSON 输出（便于阅读）
+        let formatted = format_json(&config)?;
+
+        std::fs::write(&workflow_path, formatted).map_err(|e| {
+            error!("写入 workflow 文件失败: {:?}, 错误: {}", workflow_path, e);
+            AxonError::io("保存 Workflow 配置失败", &e)
+        })?;
+
+        info!("Workflow 配置已保存: {}", workflow_id);
+        Ok(())
+    })
+    .await
 }
 
 /// 删除 Workflow 配置
 /// 
 /// 删除指定 ID 的 Workflow 配置文件
 #[tauri::command]
-pub async fn delete_workflow(app: AppHandle, workflow_id: String) -> Result<(), String> {
-    let workflows_dir = get_workflows_dir_path(&app)?;
-    let workflow_path = workflows_dir.join(format!("{}{}", workflow_id, WORKFLOW_FILE_EXT));
-    
-    debug!("删除 workflow 配置: {:?}", workflow_path);
-    
-    if !workflow_path.exists() {
-        error!("Workflow 配置文件不存在: {:?}", workflow_path);
-        return Err(format!("Workflow 不存在: {}", workflow_id));
-    }
-    
-    std::fs::remove_file(&workflow_path).map_err(|e| {
-        error!("删除 workflow 文件失败: {:?}, 错误: {}", workflow_path, e);
-        format!("删除 Workflow 配置失败: {}", e)
-    })?;
-    
-    info!("Workflow 配置已删除: {}", workflow_id);
-    Ok(())
+pub async fn delete_workflow(app: AppHandle, state: State<'_, AppState>, workflow_id: String) -> Result<(), AxonError> {
+    let audit_args = json!({ "workflowId": &workflow_id });
+    state.audit.track("delete_workflow", audit_args, async {
+        let workflows_dir = get_workflows_dir_path(&app)?;
+        let workflow_path = workflows_dir.join(format!("{}{}", workflow_id, WORKFLOW_FILE_EXT));
+
+        debug!("删除 workflow 配置: {:?}", workflow_path);
+
+        if !workflow_path.exists() {
+            if state.team_sync.definition_path(TeamItemKind::Workflow, &workflow_id).is_some() {
+                return Err(AxonError::invalid_input(format!(
+                    "Workflow {} 来自团队配置仓库，不能删除",
+                    workflow_id
+                )));
+            }
+            error!("Workflow 配置文件不存在: {:?}", workflow_path);
+            return Err(AxonError::not_found(format!("Workflow 不存在: {}", workflow_id)));
+        }
+
+        std::fs::remove_file(&workflow_path).map_err(|e| {
+            error!("删除 workflow 文件失败: {:?}, 错误: {}", workflow_path, e);
+            AxonError::io("删除 Workflow 配置失败", &e)
+        })?;
+
+        info!("Workflow 配置已删除: {}", workflow_id);
+        Ok(())
+    })
+    .await
 }
 
 /// 批量保存 Workflow 配置
@@ -208,42 +244,192 @@ pub async fn delete_workflow(app: AppHandle, workflow_id: String) -> Result<(),
 #[tauri::command]
 pub async fn save_workflows_batch(
     app: AppHandle, 
+    state: State<'_, AppState>,
     workflows: Vec<(String, String)>
-) -> Result<(), String> {
-    let workflows_dir = get_workflows_dir_path(&app)?;
-    
-    // 确保目录存在
-    if !workflows_dir.exists() {
-        std::fs::create_dir_all(&workflows_dir).map_err(|e| {
-            error!("创建 workflows 目录失败: {:?}, 错误: {}", workflows_dir, e);
-            format!("创建 workflows 目录失败: {}", e)
-        })?;
-    }
-    
-    let mut errors = Vec::new();
-    
-    for (workflow_id, config) in workflows {
-        let workflow_path = workflows_dir.join(format!("{}{}", workflow_id, WORKFLOW_FILE_EXT));
-        
-        // 验证并格式化 JSON
-        match format_json(&config) {
-            Ok(formatted) => {
-                if let Err(e) = std::fs::write(&workflow_path, formatted) {
+) -> Result<(), AxonError> {
+    let audit_args = json!({
+        "workflowIds": workflows.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+    });
+    state.audit.track("save_workflows_batch", audit_args, async {
+        let workflows_dir = get_workflows_dir_path(&app)?;
+
+        // 确保目录存在
+        if !workflows_dir.exists() {
+            std::fs::create_dir_all(&workflows_dir).map_err(|e| {
+                error!("创建 workflows 目录失败: {:?}, 错误: {}", workflows_dir, e);
+                AxonError::io("创建 workflows 目录失败", &e)
+            })?;
+        }
+
+        let mut errors = Vec::new();
+
+        for (workflow_id, config) in workflows {
+            let workflow_path = workflows_dir.join(format!("{}{}", workflow_id, WORKFLOW_FILE_EXT));
+
+            // 验证并格式化 JSON
+            match format_json(&config) {
+                Ok(formatted) => {
+                    if let Err(e) = std::fs::write(&workflow_path, formatted) {
+                        errors.push(format!("{}: {}", workflow_id, e));
+                    }
+                }
+                Err(e) => {
                     errors.push(format!("{}: {}", workflow_id, e));
                 }
             }
-            Err(e) => {
-                errors.push(format!("{}: {}", workflow_id, e));
+        }
+
+        if errors.is_empty() {
+            info!("批量保存 workflow 配置成功");
+            Ok(())
+        } else {
+            Err(AxonError::internal(format!("部分保存失败: {}", errors.join(", "))))
+        }
+    })
+    .await
+}
+
+/// 自动布局 Workflow 画布
+///
+/// 按主 Agent 与子 Agent 之间的连线分层排列节点，写回各节点的 `position`。
+/// 导入或生成的 Workflow 节点没有位置（或都在原点）时使用。
+///
+/// # 返回
+/// 节点 ID 到新位置的映射
+#[tauri::command]
+pub async fn auto_layout_workflow(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    workflow_id: String,
+) -> Result<BTreeMap<String, NodePosition>, AxonError> {
+    let audit_args = json!({ "workflowId": &workflow_id });
+    state
+        .audit
+        .track("auto_layout_workflow", audit_args, async {
+            let workflows_dir = get_workflows_dir_path(&app)?;
+            let workflow_path = workflows_dir.join(format!("{}{}", workflow_id, WORKFLOW_FILE_EXT));
+            if !workflow_path.exists() {
+                if state
+                    .team_sync
+                    .definition_path(TeamItemKind::Workflow, &workflow_id)
+                    .is_some()
+                {
+                    return Err(AxonError::invalid_input(format!(
+                        "Workflow {} 来自团队配置仓库，不能修改",
+                        workflow_id
+                    )));
+                }
+                return Err(AxonError::not_found(format!(
+                    "Workflow 不存在: {}",
+                    workflow_id
+                )));
+            }
+
+            let content = std::fs::read_to_string(&workflow_path)
+                .map_err(|e| AxonError::io("读取 Workflow 配置失败", &e))?;
+            let mut workflow: Value = serde_json::from_str(&content)
+                .map_err(|e| AxonError::invalid_data(format!("无效的 Workflow 配置格式: {}", e)))?;
+
+            let graph = WorkflowGraph::from_definition(&workflow);
+            let positions = layered_layout(graph.nodes.len(), &graph.edges);
+            let by_id: BTreeMap<String, NodePosition> =
+                graph.nodes.into_iter().zip(positions).collect();
+
+            if let Some(position) = by_id.get(PRIMARY_NODE_ID) {
+                workflow["primaryAgent"]["position"] = json!(position);
             }
+            if let Some(subagents) = workflow["subagents"].as_array_mut() {
+                for subagent in subagents {
+                    let position = subagent["id"].as_str().and_then(|id| by_id.get(id));
+                    if let Some(position) = position {
+                        subagent["position"] = json!(position);
+                    }
+                }
+            }
+            workflow["updatedAt"] = json!(chrono::Utc::now().timestamp_millis());
+
+            let formatted = serde_json::to_string_pretty(&workflow)
+                .map_err(|e| AxonError::internal(format!("格式化 JSON 失败: {}", e)))?;
+            std::fs::write(&workflow_path, formatted)
+                .map_err(|e| AxonError::io("保存 Workflow 配置失败", &e))?;
+
+            info!(
+                "Workflow 已自动布局: {} ({} 个节点)",
+                workflow_id,
+                by_id.len()
+            );
+            Ok(by_id)
+        })
+        .await
+}
+
+/// 试运行 Workflow，返回执行计划
+///
+/// 不调用任何模型，按触发器和委托规则推演会执行的步骤，以及每一步使用的
+/// Agent、模型、工具和估算的 token 用量与费用
+#[tauri::command]
+pub async fn plan_workflow_execution(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    workflow_id: String,
+    input: String,
+) -> Result<ExecutionPlan, AxonError> {
+    let Some(workflow_path) = definition_path(&app, &state, TeamItemKind::Workflow, &workflow_id)?
+    else {
+        return Err(AxonError::not_found(format!(
+            "Workflow 不存在: {}",
+            workflow_id
+        )));
+    };
+    let workflow = read_json(&workflow_path)?;
+
+    let referenced = workflow["primaryAgent"]["agentId"]
+        .as_str()
+        .into_iter()
+        .chain(
+            workflow["subagents"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|s| s["agentId"].as_str()),
+        );
+    let mut agents = HashMap::new();
+    for agent_id in referenced {
+        if agents.contains_key(agent_id) {
+            continue;
+        }
+        // 找不到的 Agent 在计划中作为警告返回
+        if let Ok(Some(path)) = definition_path(&app, &state, TeamItemKind::Agent, agent_id) {
+            agents.insert(agent_id.to_string(), read_json(&path)?);
         }
     }
-    
-    if errors.is_empty() {
-        info!("批量保存 workflow 配置成功");
-        Ok(())
-    } else {
-        Err(format!("部分保存失败: {}", errors.join(", ")))
-    }
+
+    let mut plan = plan_execution(&workflow, &agents, &input, |model_id| {
+        state
+            .models_registry
+            .get_model_defaults(model_id)
+            .map(|m| (m.cost_input, m.cost_output))
+    });
+
+    let filters = state.settings.get_model_filters();
+    let blocked: BTreeSet<&str> = plan
+        .steps
+        .iter()
+        .filter_map(|s| s.model_id.as_deref())
+        .filter(|model_id| !is_model_allowed(&filters, model_id))
+        .collect();
+    let warnings: Vec<String> = blocked
+        .into_iter()
+        .map(|model_id| format!("模型 {} 已被模型过滤规则禁止", model_id))
+        .collect();
+    plan.warnings.extend(warnings);
+
+    debug!(
+        "Workflow 执行计划: {} ({} 个步骤)",
+        workflow_id,
+        plan.steps.len()
+    );
+    Ok(plan)
 }
 
 // ============================================================================
@@ -251,7 +437,7 @@ pub async fn save_workflows_batch(
 // ============================================================================
 
 /// 获取 workflows 目录路径
-fn get_workflows_dir_path(app: &AppHandle) -> Result<PathBuf, String> {
+pub(super) fn get_workflows_dir_path(app: &AppHandle) -> Result<PathBuf, AxonError> {
     let app_data_dir = app
         .path()
         .app_data_dir()
@@ -260,10 +446,63 @@ fn get_workflows_dir_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(app_data_dir.join(WORKFLOWS_DIR))
 }
 
+/// 本地定义优先，其次是团队仓库中的定义
+pub(super) fn definition_path(
+    app: &AppHandle,
+    state: &AppState,
+    kind: TeamItemKind,
+    id: &str,
+) -> Result<Option<PathBuf>, AxonError> {
+    validate_definition_id(id)?;
+    let dir = match kind {
+        TeamItemKind::Workflow => get_workflows_dir_path(app)?,
+        _ => get_agents_dir_path(app)?,
+    };
+    let local = dir.join(format!("{}.json", id));
+    if local.exists() {
+        return Ok(Some(local));
+    }
+    Ok(state.team_sync.definition_path(kind, id))
+}
+
+/// ID 同时用作文件名：字母或数字开头，只含字母、数字、`_` 和 `-`
+pub(super) fn validate_definition_id(id: &str) -> Result<(), AxonError> {
+    let valid = id.len() <= 128
+        && id.chars().next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(AxonError::invalid_input(format!("无效的定义 ID: {}", id)));
+    }
+    Ok(())
+}
+
+pub(super) fn read_json(path: &Path) -> Result<Value, AxonError> {
+    let content = std::fs::read_to_string(path).map_err(|e| AxonError::io("读取配置失败", &e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AxonError::invalid_data(format!("配置无法解析: {:?}: {}", path, e)))
+}
+
+/// 合并团队仓库中的 Workflow 定义（本地存在同 ID 的定义时跳过）
+fn merge_team_workflows(state: &AppState, workflows: &mut Vec<WorkflowSummary>) {
+    let local_ids: HashSet<String> = workflows.iter().map(|s| s.id.clone()).collect();
+    for definition in state.team_sync.definitions(TeamItemKind::Workflow) {
+        match read_workflow_summary(&definition.path) {
+            Ok(mut summary) if !local_ids.contains(&summary.id) => {
+                summary.scope = DefinitionScope::Team;
+                workflows.push(summary);
+            }
+            Ok(_) => {}
+            Err(e) => debug!("跳过无法解析的团队 workflow 文件 {:?}: {}", definition.path, e),
+        }
+    }
+}
+
 /// 从文件读取 Workflow 摘要
-fn read_workflow_summary(path: &Path) -> Result<WorkflowSummary, String> {
+pub(super) fn read_workflow_summary(path: &Path) -> Result<WorkflowSummary, AxonError> {
     let content = std::fs::read_to_string(path)
-        .map_err(|e| format!("读取文件失败: {}", e))?;
+        .map_err(|e| AxonError::localized_io("fs.read_file_failed", &e))?;
     
     let json: serde_json::Value = serde_json::from_str(&content)
         .map_err(|e| format!("解析 JSON 失败: {}", e))?;
@@ -315,14 +554,15 @@ fn read_workflow_summary(path: &Path) -> Result<WorkflowSummary, String> {
         status,
         subagent_count,
         updated_at,
+        scope: DefinitionScope::Local,
     })
 }
 
 /// 格式化 JSON 字符串（美化输出）
-fn format_json(json_str: &str) -> Result<String, String> {
+fn format_json(json_str: &str) -> Result<String, AxonError> {
     let value: serde_json::Value = serde_json::from_str(json_str)
-        .map_err(|e| format!("无效的 JSON: {}", e))?;
+        .map_err(|e| AxonError::invalid_input(format!("无效的 JSON: {}", e)))?;
     
     serde_json::to_string_pretty(&value)
-        .map_err(|e| format!("格式化 JSON 失败: {}", e))
+        .map_err(|e| AxonError::internal(format!("格式化 JSON 失败: {}", e)))
 }
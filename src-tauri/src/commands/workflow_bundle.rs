@@ -0,0 +1,703 @@
+//! Workflow 包导入导出命令
+//!
+//! Workflow 包是单个 zip 文件，包含 Workflow 定义以及它引用的 Agent
+//! （递归包含 Agent 的子 Agent）和自定义工具定义，方便分享。
+//! 提示词保存在 Agent 和 Workflow 定义中，随定义一起打包；工具密钥保存在钥匙串，不会打包。
+//!
+//! ```text
+//! manifest.json
+//! workflow.json
+//! agents/<Agent ID>.json
+//! tools/<工具名>.json
+//! ```
+//!
+//! 导入时 Workflow 总是使用新 ID。本地已有同 ID 但内容不同的 Agent 或工具时作为冲突返回，
+//! 由用户选择替换、保留本地或以新 ID 导入后再次调用；有未处理的冲突时不会写入任何内容。
+
+use super::agent::get_agents_dir_path;
+use super::workflow::{
+    definition_path, get_workflows_dir_path, read_json, read_workflow_summary,
+    validate_definition_id, WorkflowSummary,
+};
+use super::workflow_templates::new_workflow_id;
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::team_sync::TeamItemKind;
+use crate::tools::ToolDefinition;
+use crate::utils::path_sandbox::PathSandbox;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::io::{Read, Write};
+use std::path::Path;
+use tauri::{AppHandle, State};
+use tracing::{info, warn};
+use zip::write::SimpleFileOptions;
+
+/// 包格式版本，导入时拒绝更高的版本
+const BUNDLE_FORMAT: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const WORKFLOW_ENTRY: &str = "workflow.json";
+const AGENTS_PREFIX: &str = "agents/";
+const TOOLS_PREFIX: &str = "tools/";
+
+/// 包内单个条目的大小上限
+const MAX_ENTRY_BYTES: u64 = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleManifest {
+    format: u32,
+    app_version: String,
+    /// Unix 毫秒
+    created_at: i64,
+    workflow_id: String,
+    workflow_name: String,
+    agents: Vec<String>,
+    tools: Vec<String>,
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowBundleExport {
+    pub path: String,
+    pub size: u64,
+    pub agents: Vec<String>,
+    pub tools: Vec<String>,
+    /// 引用了但本地找不到定义的 Agent（如前端内置 Agent），未打包
+    pub missing_agents: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleItemKind {
+    Agent,
+    Tool,
+}
+
+/// 导入冲突：本地已有同 ID 但内容不同的定义
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleConflict {
+    pub kind: BundleItemKind,
+    pub id: String,
+    /// 包中定义的显示名称
+    pub name: String,
+}
+
+/// 冲突的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictResolution {
+    /// 用包中的定义覆盖本地定义
+    Replace,
+    /// 保留本地定义，引用指向本地定义
+    Keep,
+    /// 以新 ID 导入，包内引用改为新 ID
+    Rename,
+}
+
+/// 各冲突的处理方式（Agent 按 ID，工具按名称）
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictResolutions {
+    #[serde(default)]
+    pub agents: HashMap<String, ConflictResolution>,
+    #[serde(default)]
+    pub tools: HashMap<String, ConflictResolution>,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowBundleImport {
+    /// 需要用户选择处理方式的冲突，非空时没有写入任何内容
+    pub conflicts: Vec<BundleConflict>,
+    pub workflow: Option<WorkflowSummary>,
+    /// 写入的 Agent（导入后的 ID）
+    pub agents: Vec<String>,
+    /// 写入的工具（导入后的名称）
+    pub tools: Vec<String>,
+}
+
+/// 从包中读出的内容
+#[derive(Debug)]
+struct BundleContents {
+    workflow: Value,
+    agents: Vec<Value>,
+    tools: Vec<ToolDefinition>,
+}
+
+/// 导出 Workflow 包
+///
+/// # 参数
+/// - `workflow_id`: Workflow ID
+/// - `dest_path`: zip 文件路径
+#[tauri::command]
+pub async fn export_workflow_bundle(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    workflow_id: String,
+    dest_path: String,
+) -> Result<WorkflowBundleExport, AxonError> {
+    let dest = PathSandbox::from_settings(&state.settings).check(&dest_path)?;
+    let audit_args = json!({ "workflowId": &workflow_id, "destPath": &dest_path });
+    state
+        .audit
+        .track("export_workflow_bundle", audit_args, async {
+            let Some(workflow_path) =
+                definition_path(&app, &state, TeamItemKind::Workflow, &workflow_id)?
+            else {
+                return Err(AxonError::not_found(format!(
+                    "Workflow 不存在: {}",
+                    workflow_id
+                )));
+            };
+            let workflow = read_json(&workflow_path)?;
+
+            // 广度优先收集引用的 Agent（包括 Agent 的子 Agent）
+            let mut agents = BTreeMap::new();
+            let mut missing_agents = BTreeSet::new();
+            let mut queue: VecDeque<String> = agent_refs(&workflow).into();
+            while let Some(agent_id) = queue.pop_front() {
+                if agents.contains_key(&agent_id) || missing_agents.contains(&agent_id) {
+                    continue;
+                }
+                match definition_path(&app, &state, TeamItemKind::Agent, &agent_id)? {
+                    Some(path) => {
+                        let agent = read_json(&path)?;
+                        queue.extend(agent_refs(&agent));
+                        agents.insert(agent_id, agent);
+                    }
+                    None => {
+                        missing_agents.insert(agent_id);
+                    }
+                }
+            }
+
+            let mut tool_names: BTreeSet<String> = tool_refs(&workflow["primaryAgent"]["inline"])
+                .into_iter()
+                .collect();
+            for agent in agents.values() {
+                tool_names.extend(tool_refs(agent));
+            }
+            let mut tools = Vec::new();
+            for name in tool_names {
+                // 内置工具和 MCP 工具不在注册表中
+                if let Ok(Some(tool)) = state.tools.get(&name) {
+                    tools.push(tool);
+                }
+            }
+
+            let manifest = BundleManifest {
+                format: BUNDLE_FORMAT,
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+                created_at: chrono::Utc::now().timestamp_millis(),
+                workflow_id: workflow_id.clone(),
+                workflow_name: workflow["name"].as_str().unwrap_or_default().to_string(),
+                agents: agents.keys().cloned().collect(),
+                tools: tools.iter().map(|t| t.name.clone()).collect(),
+            };
+            let contents = BundleContents {
+                workflow,
+                agents: agents.into_values().collect(),
+                tools,
+            };
+            let size = tokio::task::spawn_blocking({
+                let manifest = manifest.clone();
+                move || write_bundle(&dest, &manifest, &contents)
+            })
+            .await??;
+
+            info!(
+                "Workflow 包已导出: {} -> {} ({} 个 Agent, {} 个工具)",
+                workflow_id,
+                dest_path,
+                manifest.agents.len(),
+                manifest.tools.len()
+            );
+            Ok(WorkflowBundleExport {
+                path: dest_path.clone(),
+                size,
+                agents: manifest.agents,
+                tools: manifest.tools,
+                missing_agents: missing_agents.into_iter().collect(),
+            })
+        })
+        .await
+}
+
+/// 导入 Workflow 包
+///
+/// 本地已有同 ID 但内容不同的 Agent 或工具需要在 `resolutions` 中指定处理方式，
+/// 否则作为冲突返回且不写入任何内容。内容相同的定义直接复用。
+///
+/// # 参数
+/// - `path`: zip 文件路径
+/// - `resolutions`: 冲突的处理方式
+#[tauri::command]
+pub async fn import_workflow_bundle(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    resolutions: Option<ConflictResolutions>,
+) -> Result<WorkflowBundleImport, AxonError> {
+    let bundle_path = PathSandbox::from_settings(&state.settings).check(&path)?;
+    let resolutions = resolutions.unwrap_or_default();
+    let audit_args = json!({
+        "path": &path,
+        "agents": &resolutions.agents,
+        "tools": &resolutions.tools,
+    });
+    state
+        .audit
+        .track("import_workflow_bundle", audit_args, async {
+            let mut contents =
+                tokio::task::spawn_blocking(move || read_bundle(&bundle_path)).await??;
+
+            let mut conflicts = Vec::new();
+            // 需要写入的 Agent（包中 ID）和 ID 映射
+            let mut agent_ids = HashMap::new();
+            let mut write_agents = BTreeSet::new();
+            for agent in &contents.agents {
+                let id = agent["id"].as_str().unwrap_or_default().to_string();
+                let existing = match definition_path(&app, &state, TeamItemKind::Agent, &id)? {
+                    Some(path) => Some(read_json(&path)?),
+                    None => None,
+                };
+                match existing {
+                    None => {
+                        write_agents.insert(id);
+                    }
+                    Some(existing) if existing == *agent => {}
+                    Some(_) => match resolutions.agents.get(&id) {
+                        Some(ConflictResolution::Replace) => {
+                            write_agents.insert(id);
+                        }
+                        Some(ConflictResolution::Keep) => {}
+                        Some(ConflictResolution::Rename) => {
+                            agent_ids.insert(id.clone(), new_agent_id());
+                            write_agents.insert(id);
+                        }
+                        None => conflicts.push(BundleConflict {
+                            kind: BundleItemKind::Agent,
+                            name: agent["name"].as_str().unwrap_or(&id).to_string(),
+                            id,
+                        }),
+                    },
+                }
+            }
+
+            let bundle_tools: BTreeSet<String> =
+                contents.tools.iter().map(|t| t.name.clone()).collect();
+            let mut tool_names = HashMap::new();
+            let mut write_tools = BTreeSet::new();
+            for tool in &contents.tools {
+                match state.tools.get(&tool.name)? {
+                    None => {
+                        write_tools.insert(tool.name.clone());
+                    }
+                    Some(existing) if same_tool(&existing, tool) => {}
+                    Some(_) => match resolutions.tools.get(&tool.name) {
+                        Some(ConflictResolution::Replace) => {
+                            write_tools.insert(tool.name.clone());
+                        }
+                        Some(ConflictResolution::Keep) => {}
+                        Some(ConflictResolution::Rename) => {
+                            let renamed = free_tool_name(&tool.name, |candidate| {
+                                bundle_tools.contains(candidate)
+                                    || tool_names.values().any(|n| n == candidate)
+                                    || !matches!(state.tools.get(candidate), Ok(None))
+                            });
+                            tool_names.insert(tool.name.clone(), renamed);
+                            write_tools.insert(tool.name.clone());
+                        }
+                        None => conflicts.push(BundleConflict {
+                            kind: BundleItemKind::Tool,
+                            id: tool.name.clone(),
+                            name: tool.name.clone(),
+                        }),
+                    },
+                }
+            }
+
+            if !conflicts.is_empty() {
+                info!("Workflow 包有 {} 个冲突需要处理: {}", conflicts.len(), path);
+                return Ok(WorkflowBundleImport {
+                    conflicts,
+                    ..Default::default()
+                });
+            }
+
+            let now = chrono::Utc::now().timestamp_millis();
+            let workflow_id = new_workflow_id(now);
+            remap(&mut contents, &agent_ids, &tool_names);
+
+            let mut result = WorkflowBundleImport::default();
+            for mut tool in contents.tools {
+                let original = tool.name.clone();
+                if !write_tools.contains(&original) {
+                    continue;
+                }
+                if let Some(renamed) = tool_names.get(&original) {
+                    tool.name = renamed.clone();
+                }
+                result.tools.push(state.tools.save(tool)?.name);
+            }
+
+            let agents_dir = get_agents_dir_path(&app)?;
+            std::fs::create_dir_all(&agents_dir)
+                .map_err(|e| AxonError::io("创建 agents 目录失败", &e))?;
+            for mut agent in contents.agents {
+                let original = agent["id"].as_str().unwrap_or_default().to_string();
+                if !write_agents.contains(&original) {
+                    continue;
+                }
+                let id = agent_ids.get(&original).cloned().unwrap_or(original);
+                agent["id"] = json!(id);
+                agent["updatedAt"] = json!(now);
+                write_json(&agents_dir.join(format!("{}.json", id)), &agent)?;
+                result.agents.push(id);
+            }
+
+            let mut workflow = contents.workflow;
+            workflow["id"] = json!(workflow_id);
+            workflow["createdAt"] = json!(now);
+            workflow["updatedAt"] = json!(now);
+            let workflows_dir = get_workflows_dir_path(&app)?;
+            std::fs::create_dir_all(&workflows_dir)
+                .map_err(|e| AxonError::io("创建 workflows 目录失败", &e))?;
+            let workflow_path = workflows_dir.join(format!("{}.json", workflow_id));
+            write_json(&workflow_path, &workflow)?;
+            result.workflow = Some(read_workflow_summary(&workflow_path)?);
+
+            info!(
+                "Workflow 包已导入: {} -> {} ({} 个 Agent, {} 个工具)",
+                path,
+                workflow_id,
+                result.agents.len(),
+                result.tools.len()
+            );
+            Ok(result)
+        })
+        .await
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn write_json(path: &Path, value: &Value) -> Result<(), AxonError> {
+    let content = serde_json::to_string_pretty(value)
+        .map_err(|e| AxonError::internal(format!("格式化 JSON 失败: {}", e)))?;
+    std::fs::write(path, content).map_err(|e| AxonError::io("保存配置失败", &e))
+}
+
+/// 定义中引用的 Agent：引用模式的主 Agent 和各子 Agent
+fn agent_refs(definition: &Value) -> Vec<String> {
+    let primary = definition["primaryAgent"]["agentId"]
+        .as_str()
+        .filter(|_| definition["primaryAgent"]["mode"] != "inline");
+    let subagents = definition["subagents"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s["agentId"].as_str());
+    primary
+        .into_iter()
+        .chain(subagents)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Agent 定义中出现的工具名：工具列表和权限
+fn tool_refs(agent: &Value) -> Vec<String> {
+    let listed = agent["tools"]["list"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|t| t.as_str())
+        .map(str::to_string);
+    let permitted = agent["permissions"]
+        .as_object()
+        .into_iter()
+        .flat_map(|p| p.keys().cloned());
+    listed.chain(permitted).collect()
+}
+
+/// 按映射改写包内的 Agent ID 和工具名引用
+fn remap(
+    contents: &mut BundleContents,
+    agent_ids: &HashMap<String, String>,
+    tool_names: &HashMap<String, String>,
+) {
+    remap_agent_refs(&mut contents.workflow, agent_ids);
+    remap_tool_refs(&mut contents.workflow["primaryAgent"]["inline"], tool_names);
+    for agent in &mut contents.agents {
+        remap_agent_refs(agent, agent_ids);
+        remap_tool_refs(agent, tool_names);
+    }
+}
+
+fn remap_agent_refs(definition: &mut Value, agent_ids: &HashMap<String, String>) {
+    let remap_id = |reference: &mut Value| {
+        if let Some(new_id) = reference.as_str().and_then(|id| agent_ids.get(id)) {
+            *reference = json!(new_id);
+        }
+    };
+    if let Some(reference) = definition
+        .get_mut("primaryAgent")
+        .and_then(|p| p.get_mut("agentId"))
+    {
+        remap_id(reference);
+    }
+    definition
+        .get_mut("subagents")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s.get_mut("agentId"))
+        .for_each(remap_id);
+}
+
+fn remap_tool_refs(agent: &mut Value, tool_names: &HashMap<String, String>) {
+    if tool_names.is_empty() {
+        return;
+    }
+    if let Some(list) = agent
+        .get_mut("tools")
+        .and_then(|t| t.get_mut("list"))
+        .and_then(Value::as_array_mut)
+    {
+        for name in list.iter_mut() {
+            if let Some(renamed) = name.as_str().and_then(|n| tool_names.get(n)) {
+                *name = json!(renamed);
+            }
+        }
+    }
+    if let Some(permissions) = agent.get_mut("permissions").and_then(Value::as_object_mut) {
+        for (original, renamed) in tool_names {
+            if let Some(permission) = permissions.remove(original) {
+                permissions.insert(renamed.clone(), permission);
+            }
+        }
+    }
+}
+
+/// 比较工具定义，忽略时间戳
+fn same_tool(a: &ToolDefinition, b: &ToolDefinition) -> bool {
+    let comparable = |tool: &ToolDefinition| {
+        let mut value = json!(tool);
+        if let Some(object) = value.as_object_mut() {
+            object.remove("createdAt");
+            object.remove("updatedAt");
+        }
+        value
+    };
+    comparable(a) == comparable(b)
+}
+
+/// `名称_2`、`名称_3`……中第一个未被占用的名称
+fn free_tool_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    (2..)
+        .map(|n| format!("{}_{}", name, n))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_default()
+}
+
+/// 与前端 `createDefaultAgentDefinition` 相同格式的 ID
+fn new_agent_id() -> String {
+    const CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut rng = rand::thread_rng();
+    let suffix: String = (0..7)
+        .map(|_| CHARS[rng.gen_range(0..CHARS.len())] as char)
+        .collect();
+    format!("agent-{}-{}", chrono::Utc::now().timestamp_millis(), suffix)
+}
+
+fn write_bundle(
+    dest: &Path,
+    manifest: &BundleManifest,
+    contents: &BundleContents,
+) -> Result<u64, AxonError> {
+    if let Some(parent) = dest.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent).map_err(|e| AxonError::io("创建目标目录失败", &e))?;
+        }
+    }
+
+    let mut entries = vec![
+        (MANIFEST_ENTRY.to_string(), json!(manifest)),
+        (WORKFLOW_ENTRY.to_string(), contents.workflow.clone()),
+    ];
+    for agent in &contents.agents {
+        let id = agent["id"].as_str().unwrap_or_default();
+        entries.push((format!("{}{}.json", AGENTS_PREFIX, id), agent.clone()));
+    }
+    for tool in &contents.tools {
+        entries.push((format!("{}{}.json", TOOLS_PREFIX, tool.name), json!(tool)));
+    }
+
+    let file =
+        std::fs::File::create(dest).map_err(|e| AxonError::io("创建 Workflow 包失败", &e))?;
+    let mut writer = zip::ZipWriter::new(std::io::BufWriter::new(file));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, value) in entries {
+        let content = serde_json::to_string_pretty(&value)
+            .map_err(|e| AxonError::internal(format!("格式化 JSON 失败: {}", e)))?;
+        writer
+            .start_file(name.as_str(), options)
+            .map_err(|e| format!("写入 Workflow 包条目失败: {}", e))?;
+        writer
+            .write_all(content.as_bytes())
+            .map_err(|e| AxonError::io("写入 Workflow 包失败", &e))?;
+    }
+    writer
+        .finish()
+        .map_err(|e| format!("完成 Workflow 包失败: {}", e))?;
+
+    Ok(std::fs::metadata(dest).map(|m| m.len()).unwrap_or(0))
+}
+
+fn read_bundle(path: &Path) -> Result<BundleContents, AxonError> {
+    let file = std::fs::File::open(path).map_err(|e| AxonError::io("打开 Workflow 包失败", &e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| AxonError::invalid_data(format!("不是有效的 Workflow 包: {}", e)))?;
+
+    let mut manifest = None;
+    let mut workflow = None;
+    let mut agents = Vec::new();
+    let mut tools = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| format!("读取 Workflow 包条目失败: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        if entry.size() > MAX_ENTRY_BYTES {
+            return Err(AxonError::invalid_data(format!(
+                "Workflow 包条目过大: {}",
+                name
+            )));
+        }
+        let mut content = String::new();
+        entry
+            .take(MAX_ENTRY_BYTES)
+            .read_to_string(&mut content)
+            .map_err(|e| AxonError::io("读取 Workflow 包失败", &e))?;
+        let parse = |content: &str| {
+            serde_json::from_str::<Value>(content)
+                .map_err(|e| AxonError::invalid_data(format!("{} 无法解析: {}", name, e)))
+        };
+
+        if name == MANIFEST_ENTRY {
+            manifest = Some(
+                serde_json::from_str::<BundleManifest>(&content)
+                    .map_err(|e| AxonError::invalid_data(format!("{} 无法解析: {}", name, e)))?,
+            );
+        } else if name == WORKFLOW_ENTRY {
+            workflow = Some(parse(&content)?);
+        } else if name.starts_with(AGENTS_PREFIX) {
+            let agent = parse(&content)?;
+            validate_definition_id(agent["id"].as_str().unwrap_or_default())?;
+            agents.push(agent);
+        } else if name.starts_with(TOOLS_PREFIX) {
+            let tool: ToolDefinition = serde_json::from_str(&content)
+                .map_err(|e| AxonError::invalid_data(format!("{} 无法解析: {}", name, e)))?;
+            tools.push(tool);
+        } else {
+            warn!("跳过 Workflow 包中的未知条目: {}", name);
+        }
+    }
+
+    let manifest =
+        manifest.ok_or_else(|| AxonError::invalid_data("Workflow 包缺少 manifest.json"))?;
+    if manifest.format > BUNDLE_FORMAT {
+        return Err(AxonError::unsupported(format!(
+            "Workflow 包格式版本 {} 高于当前支持的版本 {}，请升级应用",
+            manifest.format, BUNDLE_FORMAT
+        )));
+    }
+    let workflow = workflow
+        .filter(Value::is_object)
+        .ok_or_else(|| AxonError::invalid_data("Workflow 包缺少 workflow.json"))?;
+    Ok(BundleContents {
+        workflow,
+        agents,
+        tools,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tool(name: &str) -> ToolDefinition {
+        serde_json::from_value(json!({
+            "name": name,
+            "description": "查询工单",
+            "handler": { "type": "http", "method": "GET", "url": "https://example.com/{{args.id}}" },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn round_trips_bundle() {
+        let contents = BundleContents {
+            workflow: json!({
+                "id": "workflow-1",
+                "name": "Review",
+                "primaryAgent": { "mode": "reference", "agentId": "lead" },
+                "subagents": [{ "id": "s1", "agentId": "reviewer" }],
+            }),
+            agents: vec![
+                json!({ "id": "lead", "tools": { "mode": "whitelist", "list": ["ticket"] } }),
+                json!({ "id": "reviewer", "permissions": { "ticket": "ask", "bash": "deny" } }),
+            ],
+            tools: vec![sample_tool("ticket")],
+        };
+        let manifest = BundleManifest {
+            format: BUNDLE_FORMAT,
+            app_version: "0.0.0".into(),
+            created_at: 1,
+            workflow_id: "workflow-1".into(),
+            workflow_name: "Review".into(),
+            agents: vec!["lead".into(), "reviewer".into()],
+            tools: vec!["ticket".into()],
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bundle.zip");
+        write_bundle(&path, &manifest, &contents).unwrap();
+        let mut read = read_bundle(&path).unwrap();
+        assert_eq!(read.workflow, contents.workflow);
+        assert_eq!(read.agents.len(), 2);
+        assert!(same_tool(&read.tools[0], &contents.tools[0]));
+
+        let agent_ids = HashMap::from([("reviewer".to_string(), "agent-2".to_string())]);
+        let tool_names = HashMap::from([("ticket".to_string(), "ticket_2".to_string())]);
+        remap(&mut read, &agent_ids, &tool_names);
+        assert_eq!(read.workflow["primaryAgent"]["agentId"], "lead");
+        assert_eq!(read.workflow["subagents"][0]["agentId"], "agent-2");
+        assert_eq!(read.agents[0]["tools"]["list"][0], "ticket_2");
+        assert_eq!(read.agents[1]["permissions"]["ticket_2"], "ask");
+        assert!(read.agents[1]["permissions"].get("ticket").is_none());
+    }
+
+    #[test]
+    fn collects_references() {
+        let workflow = json!({
+            "primaryAgent": { "mode": "inline", "inline": { "tools": { "list": ["a"] } } },
+            "subagents": [{ "agentId": "x" }, { "agentId": "" }, { "agentId": "y" }],
+        });
+        assert_eq!(agent_refs(&workflow), vec!["x", "y"]);
+        assert_eq!(tool_refs(&workflow["primaryAgent"]["inline"]), vec!["a"]);
+        assert_eq!(free_tool_name("a", |n| n == "a_2"), "a_3");
+        assert!(validate_definition_id("../etc").is_err());
+    }
+}
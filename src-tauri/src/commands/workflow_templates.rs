@@ -0,0 +1,274 @@
+//! Workflow 模板命令
+//!
+//! 内置模板（代码审查流水线、Bug 修复分诊、文档生成）以 JSON 资源随应用发布。
+//! 实例化时模板 `workflow` 中字符串里的 `{{参数}}` 被替换为模型 / Agent 等选择，
+//! 生成新的草稿 Workflow 文件，可以直接在编排页面打开运行。
+
+use super::workflow::{get_workflows_dir_path, read_workflow_summary, WorkflowSummary};
+use crate::error::AxonError;
+use crate::opencode::is_model_allowed;
+use crate::scaffold::render;
+use crate::state::AppState;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use tauri::{AppHandle, State};
+use tracing::info;
+
+/// 内置模板
+const BUILTIN_TEMPLATES: &[&str] = &[
+    include_str!("../../resources/workflow_templates/code-review.json"),
+    include_str!("../../resources/workflow_templates/bug-triage.json"),
+    include_str!("../../resources/workflow_templates/docs-generation.json"),
+];
+
+/// 模板参数类型（决定前端使用的选择器）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateParamKind {
+    /// 模型 ID（provider/model）
+    Model,
+    /// 已定义的 Agent ID
+    Agent,
+    #[default]
+    Text,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowTemplateParam {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub kind: TemplateParamKind,
+    #[serde(default)]
+    pub default: Option<String>,
+    /// 为 true 且没有默认值时必须提供
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub params: Vec<WorkflowTemplateParam>,
+    /// Workflow 定义（不含 ID 和时间戳）
+    #[serde(skip_serializing)]
+    workflow: Value,
+}
+
+/// 列出内置 Workflow 模板
+#[tauri::command]
+pub fn list_workflow_templates() -> Result<Vec<WorkflowTemplate>, AxonError> {
+    builtin_templates()
+}
+
+/// 用模板创建新的 Workflow
+///
+/// # 参数
+/// - `template_id`: 模板 ID
+/// - `params`: 模板参数，未提供的使用默认值
+/// - `name`: 可选的 Workflow 名称，为空时使用模板中的名称
+///
+/// # 返回
+/// 新建 Workflow 的摘要
+#[tauri::command]
+pub async fn instantiate_workflow_template(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    template_id: String,
+    params: HashMap<String, String>,
+    name: Option<String>,
+) -> Result<WorkflowSummary, AxonError> {
+    let audit_args = json!({ "templateId": &template_id, "params": &params });
+    state
+        .audit
+        .track("instantiate_workflow_template", audit_args, async {
+            let template = builtin_templates()?
+                .into_iter()
+                .find(|t| t.id == template_id)
+                .ok_or_else(|| {
+                    AxonError::not_found(format!("Workflow 模板不存在: {}", template_id))
+                })?;
+            let values = resolve_params(&template, &params)?;
+
+            let filters = state.settings.get_model_filters();
+            for param in &template.params {
+                let value = &values[&param.name];
+                if param.kind == TemplateParamKind::Model && !is_model_allowed(&filters, value) {
+                    return Err(AxonError::invalid_input(format!(
+                        "模型 {} 已被模型过滤规则禁止",
+                        value
+                    )));
+                }
+            }
+
+            let now = chrono::Utc::now().timestamp_millis();
+            let id = new_workflow_id(now);
+            let mut workflow = instantiate(&template, &values, &id, now)?;
+            if let Some(name) = name.filter(|n| !n.trim().is_empty()) {
+                workflow["name"] = Value::String(name);
+            }
+
+            let workflows_dir = get_workflows_dir_path(&app)?;
+            std::fs::create_dir_all(&workflows_dir)
+                .map_err(|e| AxonError::io("创建 workflows 目录失败", &e))?;
+            let path = workflows_dir.join(format!("{}.json", id));
+            let content = serde_json::to_string_pretty(&workflow)
+                .map_err(|e| AxonError::internal(format!("格式化 JSON 失败: {}", e)))?;
+            std::fs::write(&path, content)
+                .map_err(|e| AxonError::io("保存 Workflow 配置失败", &e))?;
+
+            info!("已用模板 {} 创建 workflow: {}", template_id, id);
+            read_workflow_summary(&path)
+        })
+        .await
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn builtin_templates() -> Result<Vec<WorkflowTemplate>, AxonError> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .map(|content| {
+            serde_json::from_str(content)
+                .map_err(|e| AxonError::internal(format!("内置 Workflow 模板格式错误: {}", e)))
+        })
+        .collect()
+}
+
+/// 与前端 `createDefaultWorkflow` 相同格式的 ID
+pub(super) fn new_workflow_id(now: i64) -> String {
+    const CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut rng = rand::thread_rng();
+    let suffix: String = (0..7)
+        .map(|_| CHARS[rng.gen_range(0..CHARS.len())] as char)
+        .collect();
+    format!("workflow-{}-{}", now, suffix)
+}
+
+/// 合并提供的参数和默认值，拒绝模板未声明的参数
+fn resolve_params(
+    template: &WorkflowTemplate,
+    provided: &HashMap<String, String>,
+) -> Result<BTreeMap<String, String>, AxonError> {
+    if let Some(unknown) = provided
+        .keys()
+        .find(|name| !template.params.iter().any(|p| &p.name == *name))
+    {
+        return Err(AxonError::invalid_input(format!(
+            "未知的模板参数: {}",
+            unknown
+        )));
+    }
+    let mut values = BTreeMap::new();
+    for param in &template.params {
+        let value = provided
+            .get(&param.name)
+            .filter(|v| !v.trim().is_empty())
+            .or(param.default.as_ref());
+        match value {
+            Some(value) => {
+                values.insert(param.name.clone(), value.trim().to_string());
+            }
+            None if param.required => {
+                return Err(AxonError::invalid_input(format!(
+                    "缺少模板参数: {}",
+                    param.name
+                )));
+            }
+            None => {
+                values.insert(param.name.clone(), String::new());
+            }
+        }
+    }
+    Ok(values)
+}
+
+/// 生成 Workflow 定义：替换参数并填入 ID、状态和时间戳
+fn instantiate(
+    template: &WorkflowTemplate,
+    values: &BTreeMap<String, String>,
+    id: &str,
+    now: i64,
+) -> Result<Value, AxonError> {
+    let mut workflow = template.workflow.clone();
+    render_strings(&mut workflow, values);
+    let object = workflow
+        .as_object_mut()
+        .ok_or_else(|| AxonError::internal("模板中的 workflow 必须是对象"))?;
+    object.insert("id".into(), json!(id));
+    object.insert("status".into(), json!("draft"));
+    object.insert("templateId".into(), json!(template.id));
+    object.insert("createdAt".into(), json!(now));
+    object.insert("updatedAt".into(), json!(now));
+    object.insert("version".into(), json!(1));
+    Ok(workflow)
+}
+
+/// 只替换字符串值中的占位符，参数中的引号等字符不会破坏 JSON 结构
+fn render_strings(value: &mut Value, values: &BTreeMap<String, String>) {
+    match value {
+        Value::String(text) if text.contains("{{") => *text = render(text, values),
+        Value::Array(items) => items.iter_mut().for_each(|v| render_strings(v, values)),
+        Value::Object(map) => map.values_mut().for_each(|v| render_strings(v, values)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_templates_declare_all_placeholders() {
+        for template in builtin_templates().unwrap() {
+            let values = resolve_params(&template, &HashMap::new()).unwrap();
+            let workflow = instantiate(&template, &values, "workflow-1", 1).unwrap();
+            let text = workflow.to_string();
+            assert!(!text.contains("{{"), "{} 中有未声明的参数", template.id);
+            assert_eq!(workflow["id"], "workflow-1");
+            assert!(workflow["subagents"]
+                .as_array()
+                .is_some_and(|s| !s.is_empty()));
+        }
+    }
+
+    #[test]
+    fn instantiates_with_params() {
+        let template = builtin_templates()
+            .unwrap()
+            .into_iter()
+            .find(|t| t.id == "code-review")
+            .unwrap();
+        let params = HashMap::from([(
+            "reviewerModel".to_string(),
+            "openai/gpt-5 \"mini\"".to_string(),
+        )]);
+        let values = resolve_params(&template, &params).unwrap();
+        let workflow = instantiate(&template, &values, "workflow-2", 2).unwrap();
+        assert_eq!(
+            workflow["subagents"][0]["overrides"]["model"]["modelId"],
+            "openai/gpt-5 \"mini\""
+        );
+        assert_eq!(
+            workflow["primaryAgent"]["inline"]["model"]["modelId"],
+            "anthropic/claude-sonnet-4-5"
+        );
+
+        let unknown = HashMap::from([("nope".to_string(), "x".to_string())]);
+        assert!(resolve_params(&template, &unknown).is_err());
+    }
+}
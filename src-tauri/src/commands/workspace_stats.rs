@@ -0,0 +1,50 @@
+//! 项目概览统计命令
+//!
+//! 统计在后台任务中进行，可通过 `cancel_job(job_id)` 取消；
+//! 结果按项目目录缓存，`refresh` 为 true 时忽略缓存重新统计。
+
+use crate::error::AxonError;
+use crate::state::AppState;
+use crate::utils::path_sandbox::PathSandbox;
+use crate::workspace_stats::{self, WorkspaceStats};
+use std::sync::Arc;
+use tauri::State;
+use tracing::debug;
+
+/// 获取项目统计（文件数、各语言行数、最大文件、改动热点）
+#[tauri::command]
+pub async fn get_workspace_stats(
+    state: State<'_, AppState>,
+    job_id: String,
+    project_dir: Option<String>,
+    refresh: Option<bool>,
+) -> Result<WorkspaceStats, AxonError> {
+    let project_dir = project_dir
+        .or_else(|| state.settings.get_project_directory())
+        .ok_or_else(|| AxonError::invalid_input("未指定项目目录"))?;
+    if !refresh.unwrap_or(false) {
+        if let Some(stats) = state.workspace_stats.get(&project_dir) {
+            return Ok(stats);
+        }
+    }
+
+    let root = PathSandbox::from_settings(&state.settings).check(&project_dir)?;
+    if !root.is_dir() {
+        return Err(AxonError::invalid_input(format!(
+            "路径不是目录: {}",
+            project_dir
+        )));
+    }
+    debug!("统计项目: {}, 任务: {}", project_dir, job_id);
+
+    let job = state.jobs.register(&job_id)?;
+    let jobs = Arc::clone(&state.jobs);
+    let scan_root = root.clone();
+    let result = tokio::task::spawn_blocking(move || workspace_stats::scan(&scan_root, &job)).await;
+    jobs.finish(&job_id);
+
+    let mut stats = result??;
+    stats.hotspots = workspace_stats::git_hotspots(&root).await;
+    state.workspace_stats.insert(&project_dir, stats.clone());
+    Ok(stats)
+}
@@ -0,0 +1,548 @@
+//! 操作确认调度
+//!
+//! 每个待确认请求持有一个 oneshot 通道，`request` 等待用户回复或超时，
+//! `respond` 由前端命令调用并唤醒对应的等待方。
+
+use crate::consent::types::{
+    ConsentAction, ConsentDecision, ConsentOutcome, ConsentRequest, ConsentResolved, ConsentRule,
+    ConsentRuleStore,
+};
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::json_store;
+use crate::utils::paths::{get_app_data_dir, normalize_project};
+use parking_lot::{Mutex, RwLock};
+use rand::Rng;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+use tracing::{debug, info, warn};
+
+/// 新的待确认请求事件
+pub const EVENT_CONSENT_REQUESTED: &str = "consent:requested";
+
+/// 请求结束事件（用户回复或超时）
+pub const EVENT_CONSENT_RESOLVED: &str = "consent:resolved";
+
+/// 持久化文件名
+const RULES_FILE: &str = "consent_rules.json";
+
+/// 等待用户回复的最长时间，超时视为拒绝
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// 会删除文件的命令
+const DELETE_PROGRAMS: &[&str] = &[
+    "rm",
+    "rmdir",
+    "unlink",
+    "shred",
+    "del",
+    "erase",
+    "rd",
+    "remove-item",
+];
+
+/// 命令前缀，实际执行的程序在其后
+const COMMAND_PREFIXES: &[&str] = &[
+    "sudo", "doas", "command", "env", "exec", "nohup", "time", "xargs",
+];
+
+/// 等待回复的请求
+#[derive(Debug)]
+struct PendingConsent {
+    request: ConsentRequest,
+    responder: oneshot::Sender<ConsentDecision>,
+}
+
+/// 操作确认调度器
+#[derive(Debug)]
+pub struct ConsentBroker {
+    pending: Mutex<HashMap<String, PendingConsent>>,
+    rules: RwLock<Vec<ConsentRule>>,
+    app_handle: RwLock<Option<AppHandle>>,
+    /// 规则文件路径，为空时使用应用数据目录下的 consent_rules.json
+    rules_path: Option<PathBuf>,
+}
+
+impl ConsentBroker {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::with_rules_path(None))
+    }
+
+    fn with_rules_path(rules_path: Option<PathBuf>) -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            rules: RwLock::new(Vec::new()),
+            app_handle: RwLock::new(None),
+            rules_path,
+        }
+    }
+
+    fn rules_path(&self) -> Option<PathBuf> {
+        self.rules_path
+            .clone()
+            .or_else(|| get_app_data_dir().map(|p| p.join(RULES_FILE)))
+    }
+
+    /// 初始化：加载已保存的规则并设置事件发送句柄（应用数据目录初始化后调用）
+    pub fn initialize(&self, handle: AppHandle) {
+        *self.app_handle.write() = Some(handle);
+        self.load_rules();
+    }
+
+    fn load_rules(&self) {
+        let Some(path) = self.rules_path() else {
+            return;
+        };
+        match json_store::load::<ConsentRuleStore>(&path, "确认规则文件") {
+            Ok(Some(store)) => {
+                info!("已加载 {} 条始终允许规则", store.rules.len());
+                *self.rules.write() = store.rules;
+            }
+            Ok(None) => debug!("确认规则文件不存在，使用空规则"),
+            Err(e) => warn!("加载确认规则失败: {}", e),
+        }
+    }
+
+    fn save(&self) -> Result<(), AxonError> {
+        let path = self.rules_path().ok_or_else(|| {
+            AxonError::localized(
+                ErrorKind::Unavailable,
+                "app.data_dir_unavailable",
+                serde_json::json!(null),
+            )
+        })?;
+        let store = ConsentRuleStore {
+            rules: self.rules.read().clone(),
+        };
+        json_store::save(&path, &store, "确认规则文件")
+    }
+
+    fn emit<S: serde::Serialize + Clone>(&self, event: &str, payload: S) {
+        if let Some(handle) = self.app_handle.read().as_ref() {
+            if let Err(e) = handle.emit(event, payload) {
+                warn!("发送确认事件失败 {}: {}", event, e);
+            }
+        }
+    }
+
+    /// 请求用户确认，阻塞直到用户回复或超时
+    ///
+    /// 命中"始终允许"规则时直接放行；前端未就绪时直接拒绝
+    pub async fn request(
+        &self,
+        action: ConsentAction,
+        tool: String,
+        summary: String,
+        project: Option<String>,
+        session_id: Option<String>,
+    ) -> ConsentOutcome {
+        let project = project
+            .map(|p| normalize_project(&p))
+            .filter(|p| !p.is_empty());
+        if project
+            .as_deref()
+            .is_some_and(|p| self.is_always_allowed(p, action))
+        {
+            debug!("命中始终允许规则: {:?} {}", action, summary);
+            return ConsentOutcome::Allowed;
+        }
+
+        let decision = self
+            .ask(action, tool, summary, project.clone(), session_id)
+            .await;
+        self.apply_decision(project, action, decision)
+    }
+
+    /// 将用户决定转换为确认结果，"始终允许"时记住项目规则
+    fn apply_decision(
+        &self,
+        project: Option<String>,
+        action: ConsentAction,
+        decision: Result<ConsentDecision, ConsentOutcome>,
+    ) -> ConsentOutcome {
+        match decision {
+            Ok(ConsentDecision::AllowOnce) => ConsentOutcome::Allowed,
+            Ok(ConsentDecision::AlwaysAllow) => {
+                if let Some(project) = project {
+                    self.add_rule(project, action);
+                }
+                ConsentOutcome::Allowed
+            }
+            Ok(ConsentDecision::Deny) => ConsentOutcome::Denied,
+            Err(outcome) => outcome,
+        }
+    }
+
+    /// 询问用户并返回其决定，不检查也不记录"始终允许"规则
+    ///
+    /// 由调用方决定如何记住授权（如 `run_command` 按程序名加入白名单）；
+    /// 前端未就绪时返回 `Err(Denied)`，超时返回 `Err(TimedOut)`
+    pub async fn ask(
+        &self,
+        action: ConsentAction,
+        tool: String,
+        summary: String,
+        project: Option<String>,
+        session_id: Option<String>,
+    ) -> Result<ConsentDecision, ConsentOutcome> {
+        if self.app_handle.read().is_none() {
+            warn!("前端未就绪，拒绝操作: {}", summary);
+            return Err(ConsentOutcome::Denied);
+        }
+        self.ask_with_timeout(action, tool, summary, project, session_id, REQUEST_TIMEOUT)
+            .await
+    }
+
+    async fn ask_with_timeout(
+        &self,
+        action: ConsentAction,
+        tool: String,
+        summary: String,
+        project: Option<String>,
+        session_id: Option<String>,
+        timeout: Duration,
+    ) -> Result<ConsentDecision, ConsentOutcome> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let request = ConsentRequest {
+            id: format!("consent-{:016x}", rand::thread_rng().gen::<u64>()),
+            action,
+            tool,
+            summary,
+            project,
+            session_id,
+            created_at: now,
+            expires_at: now + timeout.as_millis() as i64,
+        };
+        let id = request.id.clone();
+        let (responder, receiver) = oneshot::channel();
+        self.pending.lock().insert(
+            id.clone(),
+            PendingConsent {
+                request: request.clone(),
+                responder,
+            },
+        );
+        info!("等待用户确认: {} ({:?} {})", id, action, request.summary);
+        self.emit(EVENT_CONSENT_REQUESTED, &request);
+
+        let decision = match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(decision)) => Ok(decision),
+            Ok(Err(_)) => Err(ConsentOutcome::Denied),
+            Err(_) => {
+                self.pending.lock().remove(&id);
+                Err(ConsentOutcome::TimedOut)
+            }
+        };
+        let outcome = match decision {
+            Ok(ConsentDecision::Deny) => ConsentOutcome::Denied,
+            Ok(_) => ConsentOutcome::Allowed,
+            Err(outcome) => outcome,
+        };
+
+        info!("确认结束: {} -> {:?}", id, outcome);
+        self.emit(EVENT_CONSENT_RESOLVED, ConsentResolved { id, outcome });
+        decision
+    }
+
+    /// 回复待确认请求
+    pub fn respond(&self, id: &str, decision: ConsentDecision) -> Result<(), AxonError> {
+        let pending = self
+            .pending
+            .lock()
+            .remove(id)
+            .ok_or_else(|| AxonError::not_found(format!("确认请求不存在或已过期: {}", id)))?;
+        pending
+            .responder
+            .send(decision)
+            .map_err(|_| AxonError::cancelled(format!("确认请求已结束: {}", id)))
+    }
+
+    /// 所有待确认请求（按创建时间排序）
+    pub fn pending(&self) -> Vec<ConsentRequest> {
+        let mut requests: Vec<ConsentRequest> = self
+            .pending
+            .lock()
+            .values()
+            .map(|p| p.request.clone())
+            .collect();
+        requests.sort_by_key(|r| r.created_at);
+        requests
+    }
+
+    /// 所有"始终允许"规则
+    pub fn rules(&self) -> Vec<ConsentRule> {
+        self.rules.read().clone()
+    }
+
+    fn is_always_allowed(&self, project: &str, action: ConsentAction) -> bool {
+        self.rules
+            .read()
+            .iter()
+            .any(|r| r.action == action && r.project == project)
+    }
+
+    fn add_rule(&self, project: String, action: ConsentAction) {
+        if self.is_always_allowed(&project, action) {
+            return;
+        }
+        info!("记住始终允许规则: {} {:?}", project, action);
+        self.rules.write().push(ConsentRule {
+            project,
+            action,
+            created_at: chrono::Utc::now().timestamp_millis(),
+        });
+        if let Err(e) = self.save() {
+            warn!("保存确认规则失败: {}", e);
+        }
+    }
+
+    /// 删除规则，返回是否存在
+    pub fn remove_rule(&self, project: &str, action: ConsentAction) -> Result<bool, AxonError> {
+        let project = normalize_project(project);
+        let removed = {
+            let mut rules = self.rules.write();
+            let before = rules.len();
+            rules.retain(|r| !(r.action == action && r.project == project));
+            rules.len() != before
+        };
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+}
+
+/// 将插件的工具调用映射为需要确认的操作，非破坏性工具返回 None
+///
+/// 目前只有 bash 工具会执行命令；命令链或子命令中任一段是删除命令时视为删除文件
+pub fn classify_tool(tool: &str, args: &serde_json::Value) -> Option<(ConsentAction, String)> {
+    if tool != "bash" {
+        return None;
+    }
+
+    let command = args.get("command").and_then(|v| v.as_str())?.trim();
+    if command.is_empty() {
+        return None;
+    }
+
+    let deletes = command
+        .split(['\n', ';', '&', '|', '(', ')', '`'])
+        .any(segment_deletes);
+
+    let action = if deletes {
+        ConsentAction::FileDelete
+    } else {
+        ConsentAction::CommandExec
+    };
+    Some((action, command.to_string()))
+}
+
+/// 单段命令是否会删除文件
+///
+/// 跳过命令前缀、前缀的选项和环境变量赋值，取第一个词作为程序名
+fn segment_deletes(segment: &str) -> bool {
+    let mut words = segment
+        .split_whitespace()
+        .map(|word| word.trim_matches(['"', '\'', '{', '}']))
+        .filter(|word| !word.is_empty())
+        .skip_while(|word| {
+            COMMAND_PREFIXES.contains(word) || word.starts_with('-') || is_env_assignment(word)
+        });
+    let Some(program) = words.next() else {
+        return false;
+    };
+    match program_name(program).as_str() {
+        // find 通过 -delete 或 -exec rm 删除匹配的文件
+        "find" => words.any(|word| word == "-delete" || is_delete_program(word)),
+        _ => is_delete_program(program),
+    }
+}
+
+fn is_env_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+fn program_name(program: &str) -> String {
+    let name = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    let name = name.to_lowercase();
+    name.strip_suffix(".exe").map(String::from).unwrap_or(name)
+}
+
+fn is_delete_program(program: &str) -> bool {
+    DELETE_PROGRAMS.contains(&program_name(program).as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ask(broker: &ConsentBroker, timeout: Duration) -> Result<ConsentDecision, ConsentOutcome> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        runtime.block_on(broker.ask_with_timeout(
+            ConsentAction::FileDelete,
+            "bash".into(),
+            "rm -rf dist".into(),
+            Some("/work/app".into()),
+            None,
+            timeout,
+        ))
+    }
+
+    fn classify(command: &str) -> Option<ConsentAction> {
+        classify_tool("bash", &json!({ "command": command })).map(|(action, _)| action)
+    }
+
+    #[test]
+    fn respond_wakes_the_waiting_request() {
+        let broker = ConsentBroker::with_rules_path(None);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let (decision, request) = runtime.block_on(async {
+            let ask = broker.ask_with_timeout(
+                ConsentAction::FileDelete,
+                "bash".into(),
+                "rm -rf dist".into(),
+                Some("/work/app".into()),
+                Some("session-1".into()),
+                REQUEST_TIMEOUT,
+            );
+            let respond = async {
+                loop {
+                    if let Some(request) = broker.pending().pop() {
+                        broker
+                            .respond(&request.id, ConsentDecision::AllowOnce)
+                            .unwrap();
+                        break request;
+                    }
+                    tokio::task::yield_now().await;
+                }
+            };
+            tokio::join!(ask, respond)
+        });
+
+        assert_eq!(decision, Ok(ConsentDecision::AllowOnce));
+        assert_eq!(request.expires_at - request.created_at, 120_000);
+        assert!(broker.pending().is_empty());
+        assert!(broker.respond(&request.id, ConsentDecision::Deny).is_err());
+    }
+
+    #[test]
+    fn unanswered_requests_time_out_as_denied() {
+        let broker = ConsentBroker::with_rules_path(None);
+        assert_eq!(
+            ask(&broker, Duration::from_millis(20)),
+            Err(ConsentOutcome::TimedOut)
+        );
+        assert!(broker.pending().is_empty());
+        assert_eq!(
+            broker.apply_decision(
+                None,
+                ConsentAction::FileDelete,
+                Err(ConsentOutcome::TimedOut)
+            ),
+            ConsentOutcome::TimedOut
+        );
+
+        // 前端未就绪时不等待，直接拒绝
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let outcome = runtime.block_on(broker.request(
+            ConsentAction::FileDelete,
+            "bash".into(),
+            "rm -rf dist".into(),
+            Some("/work/app".into()),
+            None,
+        ));
+        assert_eq!(outcome, ConsentOutcome::Denied);
+    }
+
+    #[test]
+    fn always_allow_rules_persist_per_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(RULES_FILE);
+        let broker = ConsentBroker::with_rules_path(Some(path.clone()));
+        let outcome = broker.apply_decision(
+            Some("/work/app".into()),
+            ConsentAction::FileDelete,
+            Ok(ConsentDecision::AlwaysAllow),
+        );
+        assert_eq!(outcome, ConsentOutcome::Allowed);
+
+        let reloaded = ConsentBroker::with_rules_path(Some(path));
+        reloaded.load_rules();
+        assert_eq!(reloaded.rules().len(), 1);
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let request = |project: &str, action| {
+            runtime.block_on(reloaded.request(
+                action,
+                "bash".into(),
+                "rm -rf dist".into(),
+                Some(project.into()),
+                None,
+            ))
+        };
+        // 规则按规范化后的项目目录匹配，只对记住的操作类型和项目生效
+        assert_eq!(
+            request("/work/app/", ConsentAction::FileDelete),
+            ConsentOutcome::Allowed
+        );
+        assert_eq!(
+            request("/work/app", ConsentAction::CommandExec),
+            ConsentOutcome::Denied
+        );
+        assert_eq!(
+            request("/work/other", ConsentAction::FileDelete),
+            ConsentOutcome::Denied
+        );
+
+        assert!(reloaded
+            .remove_rule("/work/app/", ConsentAction::FileDelete)
+            .unwrap());
+        assert!(!reloaded
+            .remove_rule("/work/app", ConsentAction::FileDelete)
+            .unwrap());
+        broker.load_rules();
+        assert!(broker.rules().is_empty());
+    }
+
+    #[test]
+    fn classifies_every_segment_of_a_command() {
+        let delete = Some(ConsentAction::FileDelete);
+        let exec = Some(ConsentAction::CommandExec);
+
+        assert_eq!(classify("foo && rm -rf x"), delete);
+        assert_eq!(classify("make; /bin/rm build.log"), delete);
+        assert_eq!(classify("echo $(rm x)"), delete);
+        assert_eq!(classify("sudo rm -rf /tmp/x"), delete);
+        assert_eq!(classify("sudo -E env FOO=1 rm x"), delete);
+        assert_eq!(classify("\"rm\" -f x"), delete);
+        assert_eq!(classify("ls *.o | xargs rm"), delete);
+        assert_eq!(classify("find . -name '*.o' -delete"), delete);
+        assert_eq!(classify("find . -name '*.o' -exec rm {} \\;"), delete);
+        assert_eq!(classify("Remove-Item.exe -Recurse dist"), delete);
+
+        assert_eq!(classify("git commit -m \"rm old files\""), exec);
+        assert_eq!(classify("git rm --cached x"), exec);
+        assert_eq!(classify("find . -name '*.rs'"), exec);
+        assert_eq!(classify("cargo build"), exec);
+
+        assert_eq!(classify("   "), None);
+        assert_eq!(classify_tool("read", &json!({ "command": "rm x" })), None);
+    }
+}
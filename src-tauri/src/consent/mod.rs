@@ -0,0 +1,14 @@
+//! 破坏性操作确认模块
+//!
+//! OpenCode 插件在执行工具前通过 Plugin API 请求确认，
+//! 映射为破坏性操作（删除文件、执行命令）的请求会进入待确认队列：
+//! 向前端发送事件，阻塞 HTTP 响应直到用户允许 / 拒绝或超时。
+//!
+//! 用户选择"始终允许"时按项目目录和操作类型记住规则，
+//! 持久化到 consent_rules.json。
+
+mod broker;
+mod types;
+
+pub use broker::*;
+pub use types::*;
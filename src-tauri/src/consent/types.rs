@@ -0,0 +1,87 @@
+//! 操作确认类型定义
+
+use serde::{Deserialize, Serialize};
+
+/// 需要用户确认的操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentAction {
+    /// 删除文件或目录
+    FileDelete,
+    /// 执行命令
+    CommandExec,
+}
+
+/// 待确认请求
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsentRequest {
+    pub id: String,
+    pub action: ConsentAction,
+    /// 发起请求的工具名（如 bash）
+    pub tool: String,
+    /// 操作摘要（如完整命令行）
+    pub summary: String,
+    /// 项目目录（为空时无法记住"始终允许"）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// 创建时间（毫秒时间戳）
+    pub created_at: i64,
+    /// 超时时间（毫秒时间戳），超时后自动拒绝
+    pub expires_at: i64,
+}
+
+/// 用户决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentDecision {
+    /// 仅允许本次
+    AllowOnce,
+    /// 允许并记住（当前项目内同类操作不再询问）
+    AlwaysAllow,
+    /// 拒绝
+    Deny,
+}
+
+/// 确认结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentOutcome {
+    Allowed,
+    Denied,
+    TimedOut,
+}
+
+impl ConsentOutcome {
+    pub fn is_allowed(self) -> bool {
+        self == Self::Allowed
+    }
+}
+
+/// 请求结束事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsentResolved {
+    pub id: String,
+    pub outcome: ConsentOutcome,
+}
+
+/// "始终允许"规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsentRule {
+    /// 项目目录
+    pub project: String,
+    pub action: ConsentAction,
+    /// 创建时间（毫秒时间戳）
+    pub created_at: i64,
+}
+
+/// 规则持久化结构
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ConsentRuleStore {
+    #[serde(default)]
+    pub rules: Vec<ConsentRule>,
+}
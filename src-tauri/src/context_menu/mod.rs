@@ -0,0 +1,183 @@
+//! 原生右键菜单
+//!
+//! 文件浏览器的右键菜单改为系统原生菜单。弹出菜单后命令等待菜单事件，
+//! 用户选择的操作作为命令返回值交给前端执行。
+//!
+//! 部分平台关闭菜单时不会产生事件，因此等待有超时；
+//! 新菜单弹出时上一个未完成的等待直接返回空。
+
+use crate::error::AxonError;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::{AppHandle, Manager, Window, Wry};
+use tokio::sync::oneshot;
+use tracing::debug;
+
+/// 菜单项 ID 前缀，用于区分托盘等其他菜单的事件
+const MENU_ID_PREFIX: &str = "file-context:";
+
+/// 等待用户选择的最长时间
+const SELECTION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 右键菜单目标类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    File,
+    Directory,
+}
+
+/// 右键菜单操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileMenuAction {
+    Open,
+    Reveal,
+    CopyPath,
+    Rename,
+    Delete,
+    NewFile,
+    NewFolder,
+}
+
+impl FileMenuAction {
+    const ALL: [FileMenuAction; 7] = [
+        Self::Open,
+        Self::Reveal,
+        Self::CopyPath,
+        Self::Rename,
+        Self::Delete,
+        Self::NewFile,
+        Self::NewFolder,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Reveal => "reveal",
+            Self::CopyPath => "copy_path",
+            Self::Rename => "rename",
+            Self::Delete => "delete",
+            Self::NewFile => "new_file",
+            Self::NewFolder => "new_folder",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Open => "打开",
+            Self::Reveal => reveal_label(),
+            Self::CopyPath => "复制路径",
+            Self::Rename => "重命名",
+            Self::Delete => "删除",
+            Self::NewFile => "新建文件",
+            Self::NewFolder => "新建文件夹",
+        }
+    }
+
+    fn menu_id(self) -> String {
+        format!("{}{}", MENU_ID_PREFIX, self.as_str())
+    }
+
+    fn from_menu_id(id: &str) -> Option<Self> {
+        let name = id.strip_prefix(MENU_ID_PREFIX)?;
+        Self::ALL.into_iter().find(|action| action.as_str() == name)
+    }
+}
+
+/// 各平台文件管理器的叫法
+fn reveal_label() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "在访达中显示"
+    } else if cfg!(target_os = "windows") {
+        "在资源管理器中显示"
+    } else {
+        "在文件管理器中显示"
+    }
+}
+
+/// 原生右键菜单管理器
+pub struct ContextMenuManager {
+    /// 正在等待选择的菜单
+    pending: Mutex<Option<oneshot::Sender<FileMenuAction>>>,
+}
+
+impl ContextMenuManager {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            pending: Mutex::new(None),
+        })
+    }
+
+    /// 在窗口中弹出文件右键菜单，返回用户选择的操作（取消时为 None）
+    pub async fn show_file_menu(
+        &self,
+        window: &Window,
+        kind: FileKind,
+    ) -> Result<Option<FileMenuAction>, AxonError> {
+        let menu = build_file_menu(window.app_handle(), kind)
+            .map_err(|e| AxonError::internal(format!("创建右键菜单失败: {}", e)))?;
+
+        let (sender, receiver) = oneshot::channel();
+        // 替换掉上一个菜单的等待，使其立即返回
+        *self.pending.lock() = Some(sender);
+
+        window
+            .popup_menu(&menu)
+            .map_err(|e| AxonError::internal(format!("显示右键菜单失败: {}", e)))?;
+
+        let action = match tokio::time::timeout(SELECTION_TIMEOUT, receiver).await {
+            Ok(Ok(action)) => Some(action),
+            Ok(Err(_)) => None,
+            Err(_) => {
+                self.pending.lock().take();
+                None
+            }
+        };
+        debug!("右键菜单选择: {:?}", action);
+        Ok(action)
+    }
+
+    /// 处理菜单事件，非文件右键菜单的事件直接忽略
+    pub fn handle_menu_event(&self, event: &MenuEvent) {
+        let Some(action) = FileMenuAction::from_menu_id(event.id().as_ref()) else {
+            return;
+        };
+        if let Some(sender) = self.pending.lock().take() {
+            let _ = sender.send(action);
+        }
+    }
+}
+
+fn build_file_menu(app: &AppHandle, kind: FileKind) -> tauri::Result<Menu<Wry>> {
+    use FileMenuAction::*;
+
+    let groups: &[&[FileMenuAction]] = match kind {
+        FileKind::File => &[&[Open], &[Reveal, CopyPath], &[Rename, Delete]],
+        FileKind::Directory => &[
+            &[NewFile, NewFolder],
+            &[Reveal, CopyPath],
+            &[Rename, Delete],
+        ],
+    };
+
+    let menu = Menu::new(app)?;
+    for (index, group) in groups.iter().enumerate() {
+        if index > 0 {
+            menu.append(&PredefinedMenuItem::separator(app)?)?;
+        }
+        for action in group.iter() {
+            menu.append(&MenuItem::with_id(
+                app,
+                action.menu_id(),
+                action.label(),
+                true,
+                None::<&str>,
+            )?)?;
+        }
+    }
+    Ok(menu)
+}
@@ -0,0 +1,346 @@
+//! 固定到 Agent 上下文的文件
+//!
+//! 用户在项目中固定的文件（`<app_data_dir>/context_pins/<项目哈希>.json`）
+//! 由 Bridge 插件通过 Plugin API（`/context`）读取内容，自动加入 Agent 的系统提示。
+//! 读取时单个文件和总量都有大小上限，超出部分截断；二进制文件和已删除的文件只返回原因。
+
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::json_store;
+use crate::utils::paths::get_app_data_dir;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::debug;
+
+/// 存储目录（相对应用数据目录）
+const PINS_DIR: &str = "context_pins";
+
+/// 单个项目固定的文件数上限
+const MAX_PINS: usize = 50;
+
+/// 单个文件返回的最大字节数
+const MAX_FILE_BYTES: usize = 64 * 1024;
+
+/// 所有文件合计返回的最大字节数
+const MAX_TOTAL_BYTES: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextPin {
+    pub path: String,
+    /// Unix 毫秒
+    pub pinned_at: i64,
+}
+
+/// 固定文件的内容
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedFileContent {
+    pub path: String,
+    /// 文件内容，无法读取时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// 文件实际大小
+    pub size: u64,
+    /// 内容超过上限被截断
+    pub truncated: bool,
+    /// 无法读取的原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedContext {
+    pub files: Vec<PinnedFileContent>,
+    /// 返回的内容总字节数
+    pub total_bytes: usize,
+    /// 总量达到上限，后面的文件被截断或省略
+    pub truncated: bool,
+}
+
+/// 单个项目的持久化内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProjectPins {
+    project: String,
+    pins: Vec<ContextPin>,
+}
+
+/// 上下文固定文件存储
+#[derive(Debug)]
+pub struct ContextPinStore {
+    /// 存储目录，为空时使用应用数据目录下的 context_pins
+    dir: Option<PathBuf>,
+    /// 串行化读写
+    lock: Mutex<()>,
+}
+
+impl ContextPinStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            dir: None,
+            lock: Mutex::new(()),
+        })
+    }
+
+    #[cfg(test)]
+    fn with_dir(dir: PathBuf) -> Self {
+        Self {
+            dir: Some(dir),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 列出项目固定的文件（按固定顺序）
+    pub fn list(&self, project: &str) -> Result<Vec<ContextPin>, AxonError> {
+        let _guard = self.lock.lock();
+        Ok(self
+            .read_project(project)?
+            .map(|file| file.pins)
+            .unwrap_or_default())
+    }
+
+    /// 固定文件，已固定时直接返回原记录
+    pub fn pin(&self, project: &str, path: &str) -> Result<ContextPin, AxonError> {
+        let metadata =
+            std::fs::metadata(path).map_err(|e| AxonError::io("读取固定文件失败", &e))?;
+        if !metadata.is_file() {
+            return Err(AxonError::invalid_input("只能固定文件，不能固定目录"));
+        }
+
+        let _guard = self.lock.lock();
+        let mut file = self.read_project(project)?.unwrap_or_else(|| ProjectPins {
+            project: project.to_string(),
+            pins: Vec::new(),
+        });
+        if let Some(existing) = file.pins.iter().find(|pin| pin.path == path) {
+            return Ok(existing.clone());
+        }
+        if file.pins.len() >= MAX_PINS {
+            return Err(AxonError::invalid_input(format!(
+                "固定文件数超过上限（{} 个）",
+                MAX_PINS
+            )));
+        }
+
+        let pin = ContextPin {
+            path: path.to_string(),
+            pinned_at: chrono::Utc::now().timestamp_millis(),
+        };
+        file.pins.push(pin.clone());
+        self.write_project(&file)?;
+        debug!("已固定上下文文件: {} / {}", project, path);
+        Ok(pin)
+    }
+
+    /// 取消固定，返回文件是否曾被固定
+    pub fn unpin(&self, project: &str, path: &str) -> Result<bool, AxonError> {
+        let _guard = self.lock.lock();
+        let Some(mut file) = self.read_project(project)? else {
+            return Ok(false);
+        };
+        let before = file.pins.len();
+        file.pins.retain(|pin| pin.path != path);
+        if file.pins.len() == before {
+            return Ok(false);
+        }
+        self.write_project(&file)?;
+        Ok(true)
+    }
+
+    /// 读取固定文件的内容（受大小上限约束）
+    pub fn contents(&self, project: &str) -> Result<PinnedContext, AxonError> {
+        let pins = self.list(project)?;
+        let mut context = PinnedContext {
+            files: Vec::with_capacity(pins.len()),
+            total_bytes: 0,
+            truncated: false,
+        };
+        for pin in pins {
+            let budget = MAX_FILE_BYTES.min(MAX_TOTAL_BYTES - context.total_bytes);
+            if budget == 0 {
+                context.truncated = true;
+                break;
+            }
+            let file = read_pinned_file(pin.path, budget);
+            context.total_bytes += file.content.as_ref().map_or(0, String::len);
+            context.files.push(file);
+        }
+        if context.total_bytes >= MAX_TOTAL_BYTES {
+            context.truncated = true;
+        }
+        Ok(context)
+    }
+
+    fn dir(&self) -> Result<PathBuf, AxonError> {
+        self.dir
+            .clone()
+            .or_else(|| get_app_data_dir().map(|d| d.join(PINS_DIR)))
+            .ok_or_else(|| {
+                AxonError::localized(
+                    ErrorKind::Unavailable,
+                    "app.data_dir_unavailable",
+                    serde_json::json!(null),
+                )
+            })
+    }
+
+    fn project_path(&self, project: &str) -> Result<PathBuf, AxonError> {
+        let normalized = project.trim_end_matches(['/', '\\']);
+        let digest = Sha256::digest(normalized.as_bytes());
+        let name: String = digest
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Ok(self.dir()?.join(format!("{}.json", name)))
+    }
+
+    fn read_project(&self, project: &str) -> Result<Option<ProjectPins>, AxonError> {
+        json_store::load(&self.project_path(project)?, "固定文件列表")
+    }
+
+    fn write_project(&self, file: &ProjectPins) -> Result<(), AxonError> {
+        json_store::save(&self.project_path(&file.project)?, file, "固定文件列表")
+    }
+}
+
+/// 读取文件开头至多 `budget` 字节，截断在字符边界
+fn read_pinned_file(path: String, budget: usize) -> PinnedFileContent {
+    let unreadable = |path: String, size: u64, error: String| PinnedFileContent {
+        path,
+        content: None,
+        size,
+        truncated: false,
+        error: Some(error),
+    };
+    let mut file = match std::fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) => return unreadable(path, 0, format!("无法打开文件: {}", e)),
+    };
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut bytes = Vec::with_capacity(budget.min(size as usize));
+    if let Err(e) = (&mut file).take(budget as u64).read_to_end(&mut bytes) {
+        return unreadable(path, size, format!("读取文件失败: {}", e));
+    }
+    if bytes.contains(&0) {
+        return unreadable(path, size, "二进制文件".to_string());
+    }
+
+    let truncated = size > bytes.len() as u64;
+    let content = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        // 截断位置可能落在多字节字符中间
+        Err(e) if truncated && e.utf8_error().error_len().is_none() => {
+            let valid = e.utf8_error().valid_up_to();
+            let mut bytes = e.into_bytes();
+            bytes.truncate(valid);
+            String::from_utf8(bytes).unwrap_or_default()
+        }
+        Err(_) => return unreadable(path, size, "不是 UTF-8 文本".to_string()),
+    };
+    PinnedFileContent {
+        path,
+        content: Some(content),
+        size,
+        truncated,
+        error: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_files(dir: &std::path::Path, count: usize, content: &str) -> Vec<String> {
+        (0..count)
+            .map(|i| {
+                let path = dir.join(format!("{:02}.md", i));
+                std::fs::write(&path, content).unwrap();
+                path.to_string_lossy().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn keeps_pin_order_and_ignores_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ContextPinStore::with_dir(dir.path().join("store"));
+        let files = write_files(dir.path(), MAX_PINS, "# 约定\n");
+
+        let first = store.pin("/work/app", &files[0]).unwrap();
+        store.pin("/work/app", &files[1]).unwrap();
+        store.pin("/work/app", &files[2]).unwrap();
+        // 重复固定（项目路径末尾带分隔符）返回原记录，不改变顺序
+        let again = store.pin("/work/app/", &files[0]).unwrap();
+        assert_eq!(again.pinned_at, first.pinned_at);
+
+        assert!(store.unpin("/work/app", &files[1]).unwrap());
+        assert!(!store.unpin("/work/app", &files[1]).unwrap());
+        store.pin("/work/app", &files[1]).unwrap();
+        let order: Vec<_> = store
+            .list("/work/app")
+            .unwrap()
+            .into_iter()
+            .map(|pin| pin.path)
+            .collect();
+        assert_eq!(order, [&files[0], &files[2], &files[1]].map(String::clone));
+
+        for file in &files[3..] {
+            store.pin("/work/app", file).unwrap();
+        }
+        // 达到上限后不能固定新文件，重复固定已有文件不受影响
+        let extra = dir.path().join("extra.md");
+        std::fs::write(&extra, "x").unwrap();
+        assert!(store.pin("/work/app", &extra.to_string_lossy()).is_err());
+        assert!(store.pin("/work/app", &files[0]).is_ok());
+        assert!(store
+            .pin("/work/other", &dir.path().to_string_lossy())
+            .is_err());
+    }
+
+    #[test]
+    fn contents_stay_within_total_budget() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ContextPinStore::with_dir(dir.path().join("store"));
+        let per_file = MAX_TOTAL_BYTES / MAX_FILE_BYTES;
+        let files = write_files(dir.path(), per_file + 1, &"x".repeat(MAX_FILE_BYTES));
+        let missing = dir.path().join("deleted.md");
+        std::fs::write(&missing, "gone").unwrap();
+
+        store.pin("/work/app", &missing.to_string_lossy()).unwrap();
+        std::fs::remove_file(&missing).unwrap();
+        for file in &files {
+            store.pin("/work/app", file).unwrap();
+        }
+
+        let context = store.contents("/work/app").unwrap();
+        assert!(context.truncated);
+        assert_eq!(context.total_bytes, MAX_TOTAL_BYTES);
+        // 已删除的文件只返回原因，超出总量的文件被省略
+        assert!(context.files[0].content.is_none());
+        assert!(context.files[0].error.is_some());
+        assert_eq!(context.files.len(), 1 + per_file);
+    }
+
+    #[test]
+    fn truncates_large_and_skips_binary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let large = dir.path().join("large.txt");
+        std::fs::write(&large, "好".repeat(MAX_FILE_BYTES)).unwrap();
+        let file = read_pinned_file(large.to_string_lossy().to_string(), MAX_FILE_BYTES);
+        assert!(file.truncated);
+        let content = file.content.unwrap();
+        assert!(content.len() <= MAX_FILE_BYTES);
+        assert!(content.chars().all(|c| c == '好'));
+
+        let binary = dir.path().join("image.png");
+        std::fs::write(&binary, [0x89, b'P', b'N', b'G', 0, 0]).unwrap();
+        let file = read_pinned_file(binary.to_string_lossy().to_string(), MAX_FILE_BYTES);
+        assert!(file.content.is_none());
+        assert!(file.error.is_some());
+    }
+}
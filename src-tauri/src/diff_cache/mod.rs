@@ -0,0 +1,146 @@
+//! Diff 结果缓存
+//!
+//! 审查面板每次输入都会重新请求差异，大文件重复计算代价很高。
+//! 按两侧内容的哈希缓存逐行差异（与上下文行数无关，分组为 hunk 很快），
+//! 只有一侧变化时可以取最近一次同一侧的结果作为增量计算的基础。
+//! 缓存按最近使用排序，条目数和总大小都有上限。
+
+use crate::commands::DiffLine;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// 最多缓存的条目数
+const MAX_ENTRIES: usize = 16;
+
+/// 缓存的总大小上限（文本和差异行的估算大小）
+const MAX_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// 每个差异行除内容外的估算开销
+const LINE_OVERHEAD_BYTES: usize = 64;
+
+pub type ContentHash = [u8; 32];
+
+pub fn content_hash(text: &str) -> ContentHash {
+    Sha256::digest(text.as_bytes()).into()
+}
+
+/// 一次差异计算的结果
+#[derive(Debug)]
+pub struct CachedDiff {
+    pub old_hash: ContentHash,
+    pub new_hash: ContentHash,
+    pub old_text: String,
+    pub new_text: String,
+    pub lines: Vec<DiffLine>,
+}
+
+impl CachedDiff {
+    fn size(&self) -> usize {
+        self.old_text.len()
+            + self.new_text.len()
+            + self
+                .lines
+                .iter()
+                .map(|line| line.content.len() + LINE_OVERHEAD_BYTES)
+                .sum::<usize>()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DiffCache {
+    /// 最近使用的在前
+    entries: Mutex<VecDeque<Arc<CachedDiff>>>,
+}
+
+impl DiffCache {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// 查找两侧内容都相同的结果
+    pub fn get(&self, old_hash: &ContentHash, new_hash: &ContentHash) -> Option<Arc<CachedDiff>> {
+        let mut entries = self.entries.lock();
+        let index = entries
+            .iter()
+            .position(|e| e.old_hash == *old_hash && e.new_hash == *new_hash)?;
+        let entry = entries.remove(index)?;
+        entries.push_front(Arc::clone(&entry));
+        Some(entry)
+    }
+
+    /// 查找最近一次只有一侧内容相同的结果，作为增量计算的基础
+    pub fn find_base(
+        &self,
+        old_hash: &ContentHash,
+        new_hash: &ContentHash,
+    ) -> Option<Arc<CachedDiff>> {
+        self.entries
+            .lock()
+            .iter()
+            .find(|e| (e.old_hash == *old_hash) != (e.new_hash == *new_hash))
+            .cloned()
+    }
+
+    pub fn insert(&self, entry: CachedDiff) {
+        let size = entry.size();
+        if size > MAX_CACHE_BYTES {
+            return;
+        }
+        let mut entries = self.entries.lock();
+        entries.retain(|e| e.old_hash != entry.old_hash || e.new_hash != entry.new_hash);
+        entries.push_front(Arc::new(entry));
+
+        let mut total = 0;
+        let keep = entries
+            .iter()
+            .take(MAX_ENTRIES)
+            .take_while(|e| {
+                total += e.size();
+                total <= MAX_CACHE_BYTES
+            })
+            .count();
+        entries.truncate(keep);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(old: &str, new: &str) -> CachedDiff {
+        CachedDiff {
+            old_hash: content_hash(old),
+            new_hash: content_hash(new),
+            old_text: old.to_string(),
+            new_text: new.to_string(),
+            lines: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn finds_exact_and_base_entries() {
+        let cache = DiffCache::new();
+        cache.insert(entry("a", "b"));
+        cache.insert(entry("c", "d"));
+
+        assert!(cache.get(&content_hash("a"), &content_hash("b")).is_some());
+        assert!(cache.get(&content_hash("a"), &content_hash("d")).is_none());
+
+        let base = cache
+            .find_base(&content_hash("a"), &content_hash("b2"))
+            .unwrap();
+        assert_eq!(base.new_text, "b");
+        // 两侧都相同不算增量基础
+        assert!(cache
+            .find_base(&content_hash("c"), &content_hash("d"))
+            .is_none());
+
+        for i in 0..MAX_ENTRIES + 4 {
+            cache.insert(entry("x", &i.to_string()));
+        }
+        assert_eq!(cache.entries.lock().len(), MAX_ENTRIES);
+        assert!(cache.get(&content_hash("a"), &content_hash("b")).is_none());
+    }
+}
@@ -0,0 +1,87 @@
+//! 文件分块
+//!
+//! 按行切分文本，相邻块之间保留少量重叠行，避免语义在块边界处被截断。
+//! 过长的行会使块超过字符上限，此时提前结束当前块。
+
+/// 每块最多行数
+const CHUNK_LINES: usize = 60;
+
+/// 相邻块重叠行数
+const OVERLAP_LINES: usize = 10;
+
+/// 每块最多字符数（超出的部分截断）
+const MAX_CHUNK_CHARS: usize = 4000;
+
+/// 文本块
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// 起始行号（从 1 开始）
+    pub start_line: usize,
+    /// 结束行号（包含）
+    pub end_line: usize,
+    pub text: String,
+}
+
+/// 将文本切分为块，忽略只包含空白的块
+pub fn chunk_text(text: &str) -> Vec<Chunk> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < lines.len() {
+        let mut end = start;
+        let mut chars = 0;
+        while end < lines.len() && end - start < CHUNK_LINES {
+            chars += lines[end].chars().count() + 1;
+            end += 1;
+            if chars >= MAX_CHUNK_CHARS {
+                break;
+            }
+        }
+
+        let body = lines[start..end].join("\n");
+        if !body.trim().is_empty() {
+            chunks.push(Chunk {
+                start_line: start + 1,
+                end_line: end,
+                text: body.chars().take(MAX_CHUNK_CHARS).collect(),
+            });
+        }
+
+        if end >= lines.len() {
+            break;
+        }
+        // 保证向前推进
+        start = end.saturating_sub(OVERLAP_LINES).max(start + 1);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_overlap_and_cover_all_lines() {
+        let text = (1..=130)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let chunks = chunk_text(&text);
+        let ranges: Vec<_> = chunks.iter().map(|c| (c.start_line, c.end_line)).collect();
+        assert_eq!(ranges, vec![(1, 60), (51, 110), (101, 130)]);
+        assert!(chunks[0].text.starts_with("line 1\n"));
+        assert!(chunks[2].text.ends_with("line 130"));
+    }
+
+    #[test]
+    fn skips_blank_text_and_limits_long_lines() {
+        assert!(chunk_text("\n  \n\n").is_empty());
+
+        let long = "x".repeat(MAX_CHUNK_CHARS * 2);
+        let chunks = chunk_text(&format!("{}\nshort", long));
+        assert_eq!(chunks[0].end_line, 1);
+        assert_eq!(chunks[0].text.chars().count(), MAX_CHUNK_CHARS);
+        assert_eq!(chunks.last().unwrap().text, "short");
+    }
+}
@@ -0,0 +1,144 @@
+//! 嵌入接口客户端
+//!
+//! 直接调用服务商的嵌入接口：OpenAI 兼容服务商使用 `POST /embeddings`，
+//! Google 使用 `models/{model}:batchEmbedContents`。Anthropic 没有嵌入接口。
+
+use crate::commands::{ProviderApiStyle, ProviderEndpoint};
+use crate::error::AxonError;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+use tracing::debug;
+
+/// 单次请求最多的输入条数
+pub const MAX_BATCH_SIZE: usize = 64;
+
+/// 请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize)]
+struct OpenAiResponse {
+    data: Vec<OpenAiEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbedding {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct GoogleResponse {
+    embeddings: Vec<GoogleEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct GoogleEmbedding {
+    values: Vec<f32>,
+}
+
+/// 嵌入接口客户端
+pub struct EmbeddingClient {
+    http: reqwest::Client,
+    endpoint: ProviderEndpoint,
+    model: String,
+}
+
+impl EmbeddingClient {
+    pub fn new(endpoint: ProviderEndpoint, model: String) -> Result<Self, AxonError> {
+        if endpoint.style == ProviderApiStyle::Anthropic {
+            return Err(AxonError::unsupported(
+                "Anthropic 不提供嵌入接口，请选择其他服务商",
+            ));
+        }
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+        Ok(Self {
+            http,
+            endpoint,
+            model,
+        })
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// 获取一批文本的向量，返回顺序与输入一致
+    pub async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, AxonError> {
+        let mut vectors = Vec::with_capacity(inputs.len());
+        for batch in inputs.chunks(MAX_BATCH_SIZE) {
+            vectors.extend(self.embed_batch(batch).await?);
+        }
+        Ok(vectors)
+    }
+
+    async fn embed_batch(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, AxonError> {
+        let base = &self.endpoint.base_url;
+        let mut request = match self.endpoint.style {
+            ProviderApiStyle::Google => {
+                let model = format!("models/{}", self.model);
+                let requests: Vec<_> = inputs
+                    .iter()
+                    .map(|text| json!({ "model": &model, "content": { "parts": [{ "text": text }] } }))
+                    .collect();
+                self.http
+                    .post(format!("{}/{}:batchEmbedContents", base, model))
+                    .header("x-goog-api-key", &self.endpoint.credential)
+                    .json(&json!({ "requests": requests }))
+            }
+            _ => self
+                .http
+                .post(format!("{}/embeddings", base))
+                .bearer_auth(&self.endpoint.credential)
+                .json(&json!({ "model": &self.model, "input": inputs })),
+        };
+        for (name, value) in &self.endpoint.headers {
+            request = request.header(name, value);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let retryable = status.as_u16() == 429 || status.is_server_error();
+            return Err(
+                AxonError::external(format!("嵌入接口返回错误: HTTP {}", status.as_u16()))
+                    .with_details(json!({ "body": body.chars().take(500).collect::<String>() }))
+                    .with_retryable(retryable),
+            );
+        }
+
+        let vectors =
+            match self.endpoint.style {
+                ProviderApiStyle::Google => {
+                    let body: GoogleResponse = response.json().await.map_err(|e| {
+                        AxonError::invalid_data(format!("解析嵌入接口响应失败: {}", e))
+                    })?;
+                    body.embeddings
+                        .into_iter()
+                        .map(|e| e.values)
+                        .collect::<Vec<_>>()
+                }
+                _ => {
+                    let mut body: OpenAiResponse = response.json().await.map_err(|e| {
+                        AxonError::invalid_data(format!("解析嵌入接口响应失败: {}", e))
+                    })?;
+                    body.data.sort_by_key(|e| e.index);
+                    body.data.into_iter().map(|e| e.embedding).collect()
+                }
+            };
+
+        if vectors.len() != inputs.len() {
+            return Err(AxonError::invalid_data(format!(
+                "嵌入接口返回的向量数量不匹配: {} != {}",
+                vectors.len(),
+                inputs.len()
+            )));
+        }
+        debug!("获取嵌入向量: {} 条, 模型: {}", inputs.len(), self.model);
+        Ok(vectors)
+    }
+}
@@ -0,0 +1,412 @@
+//! 语义代码搜索
+//!
+//! 将项目文件分块后通过服务商的嵌入接口获取向量，保存在本地向量存储中。
+//! 构建索引在后台任务中进行（可通过 `cancel_job` 取消），内容未变化的文件直接复用
+//! 已有向量；中途取消或失败时已完成的部分同样会保存。空闲或电池供电导致后台任务
+//! 暂停时，构建在文件之间等待恢复。检索时对查询文本取向量，
+//! 按余弦相似度返回最相近的代码块。
+
+mod chunker;
+mod client;
+mod store;
+
+pub use client::EmbeddingClient;
+
+use crate::error::{AxonError, ErrorKind};
+use crate::jobs::JobHandle;
+use crate::power::PowerMonitor;
+use crate::utils::paths::get_app_data_dir;
+use chunker::Chunk;
+use parking_lot::RwLock;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use store::{EmbeddingStore, StoredChunk};
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+/// 语义索引构建进度事件
+pub const EVENT_EMBEDDINGS_PROGRESS: &str = "embeddings:progress";
+
+/// 进度事件最小发送间隔
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(300);
+
+/// 参与索引的最大文件大小
+const MAX_FILE_SIZE: u64 = 256 * 1024;
+
+/// 向量存储目录（相对应用数据目录）
+const EMBEDDINGS_DIR: &str = "embeddings";
+
+/// 默认返回的结果数
+const DEFAULT_TOP_K: usize = 10;
+
+/// 最多返回的结果数
+const MAX_TOP_K: usize = 100;
+
+/// 语义索引状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingIndexStatus {
+    pub root: Option<String>,
+    pub model: Option<String>,
+    pub file_count: usize,
+    pub chunk_count: usize,
+    /// 正在构建索引
+    pub indexing: bool,
+    /// 最近一次完成构建的时间（Unix 毫秒）
+    pub built_at: Option<i64>,
+}
+
+/// 语义索引构建进度
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingIndexProgress {
+    pub job_id: String,
+    pub files_total: usize,
+    pub files_processed: usize,
+    /// 本次新获取向量的块数
+    pub chunks_embedded: usize,
+    pub current_path: Option<String>,
+    /// 因节能设置暂停
+    pub paused: bool,
+}
+
+/// 语义搜索结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticMatch {
+    pub path: String,
+    pub relative_path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// 余弦相似度（-1 - 1）
+    pub score: f32,
+    /// 块内容（文件已变化时可能与索引时不同）
+    pub snippet: String,
+}
+
+/// 待获取向量的文件
+struct PendingFile {
+    relative: String,
+    hash: String,
+    chunks: Vec<Chunk>,
+}
+
+/// 语义索引管理器
+pub struct EmbeddingIndex {
+    store: RwLock<Option<EmbeddingStore>>,
+    indexing: AtomicBool,
+}
+
+impl EmbeddingIndex {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            store: RwLock::new(None),
+            indexing: AtomicBool::new(false),
+        })
+    }
+
+    pub fn status(&self) -> EmbeddingIndexStatus {
+        let store = self.store.read();
+        EmbeddingIndexStatus {
+            root: store.as_ref().map(|s| s.root.clone()),
+            model: store.as_ref().map(|s| s.model.clone()),
+            file_count: store.as_ref().map_or(0, |s| s.files.len()),
+            chunk_count: store.as_ref().map_or(0, |s| s.chunk_count()),
+            indexing: self.indexing.load(Ordering::SeqCst),
+            built_at: store.as_ref().and_then(|s| s.built_at),
+        }
+    }
+
+    /// 构建（或增量更新）项目的语义索引，`files` 为项目内文件的相对路径
+    pub async fn build(
+        &self,
+        app: &AppHandle,
+        job: &JobHandle,
+        client: &EmbeddingClient,
+        power: &PowerMonitor,
+        root: &Path,
+        files: Vec<String>,
+    ) -> Result<EmbeddingIndexStatus, AxonError> {
+        if self.indexing.swap(true, Ordering::SeqCst) {
+            return Err(AxonError::already_exists("语义索引正在构建"));
+        }
+        let result = self.build_inner(app, job, client, power, root, files).await;
+        self.indexing.store(false, Ordering::SeqCst);
+        result.map(|_| self.status())
+    }
+
+    async fn build_inner(
+        &self,
+        app: &AppHandle,
+        job: &JobHandle,
+        client: &EmbeddingClient,
+        power: &PowerMonitor,
+        root: &Path,
+        files: Vec<String>,
+    ) -> Result<(), AxonError> {
+        let started = Instant::now();
+        let dir = store_dir()?;
+        self.ensure_loaded(&dir, root, client.model());
+        let mut store = self
+            .store
+            .read()
+            .clone()
+            .unwrap_or_else(|| EmbeddingStore::new(root, client.model()));
+
+        // 移除已不存在的文件
+        let present: HashSet<&str> = files.iter().map(String::as_str).collect();
+        store.files.retain(|f, _| present.contains(f.as_str()));
+
+        let mut builder = IndexBuilder {
+            app,
+            job,
+            client,
+            power,
+            progress: EmbeddingIndexProgress {
+                job_id: job.id().to_string(),
+                files_total: files.len(),
+                files_processed: 0,
+                chunks_embedded: 0,
+                current_path: None,
+                paused: false,
+            },
+            pending: Vec::new(),
+            last_emit: Instant::now(),
+        };
+        let result = builder.run(&mut store, root, &files).await;
+        builder.emit();
+
+        // 取消或失败时同样保存已完成的部分
+        if result.is_ok() {
+            store.built_at = Some(chrono::Utc::now().timestamp_millis());
+        }
+        store.save(&dir)?;
+        info!(
+            "语义索引{}: {:?}, {} 个文件, {} 个块, 新增 {} 个块, 耗时 {:?}",
+            if result.is_ok() {
+                "已建立"
+            } else {
+                "构建中断"
+            },
+            root,
+            store.files.len(),
+            store.chunk_count(),
+            builder.progress.chunks_embedded,
+            started.elapsed()
+        );
+        *self.store.write() = Some(store);
+        result
+    }
+
+    /// 语义搜索
+    pub async fn search(
+        &self,
+        client: &EmbeddingClient,
+        root: &Path,
+        query: &str,
+        top_k: Option<usize>,
+    ) -> Result<Vec<SemanticMatch>, AxonError> {
+        let top_k = top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, MAX_TOP_K);
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.ensure_loaded(&store_dir()?, root, client.model());
+        if self
+            .store
+            .read()
+            .as_ref()
+            .is_none_or(|s| s.files.is_empty())
+        {
+            return Err(AxonError::unavailable("语义索引尚未建立，请先构建索引"));
+        }
+        let vector = client
+            .embed(&[query.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| AxonError::invalid_data("嵌入接口未返回向量"))?;
+
+        let hits: Vec<(String, usize, usize, f32)> = match self.store.read().as_ref() {
+            Some(store) => store
+                .search(&vector, top_k)
+                .into_iter()
+                .map(|(path, chunk, score)| {
+                    (path.to_string(), chunk.start_line, chunk.end_line, score)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut matches = Vec::with_capacity(hits.len());
+        for (relative, start_line, end_line, score) in hits {
+            let path = root.join(&relative);
+            let snippet = tokio::fs::read_to_string(&path)
+                .await
+                .map(|content| {
+                    content
+                        .lines()
+                        .skip(start_line - 1)
+                        .take(end_line + 1 - start_line)
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default();
+            matches.push(SemanticMatch {
+                path: path.to_string_lossy().to_string(),
+                relative_path: relative,
+                start_line,
+                end_line,
+                score,
+                snippet,
+            });
+        }
+        Ok(matches)
+    }
+
+    /// 确保内存中加载的是该项目、该模型的存储
+    fn ensure_loaded(&self, dir: &Path, root: &Path, model: &str) {
+        let root_str = root.to_string_lossy();
+        let loaded = matches!(
+            self.store.read().as_ref(),
+            Some(store) if store.root == root_str && store.model == model
+        );
+        if !loaded {
+            *self.store.write() = Some(EmbeddingStore::load(dir, root, model));
+        }
+    }
+}
+
+/// 单次构建过程（节流发送进度事件）
+struct IndexBuilder<'a> {
+    app: &'a AppHandle,
+    job: &'a JobHandle,
+    client: &'a EmbeddingClient,
+    power: &'a PowerMonitor,
+    progress: EmbeddingIndexProgress,
+    pending: Vec<PendingFile>,
+    last_emit: Instant,
+}
+
+impl IndexBuilder<'_> {
+    fn emit(&self) {
+        if let Err(e) = self.app.emit(EVENT_EMBEDDINGS_PROGRESS, &self.progress) {
+            warn!("发送语义索引进度失败: {}", e);
+        }
+    }
+
+    async fn run(
+        &mut self,
+        store: &mut EmbeddingStore,
+        root: &Path,
+        files: &[String],
+    ) -> Result<(), AxonError> {
+        for relative in files {
+            if self.power.is_paused() {
+                self.progress.paused = true;
+                self.emit();
+                self.power.wait_until_resumed(self.job).await;
+                self.progress.paused = false;
+                self.emit();
+            }
+            if self.job.is_cancelled() {
+                return Err(AxonError::cancelled("操作已取消"));
+            }
+            self.progress.files_processed += 1;
+            if self.last_emit.elapsed() >= PROGRESS_EMIT_INTERVAL {
+                self.progress.current_path = Some(relative.clone());
+                self.emit();
+                self.last_emit = Instant::now();
+            }
+
+            let Some(content) = read_text_file(&root.join(relative)).await else {
+                store.files.remove(relative);
+                continue;
+            };
+            let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+            if store.files.get(relative).is_some_and(|f| f.hash == hash) {
+                continue;
+            }
+
+            let chunks = chunker::chunk_text(&content);
+            if chunks.is_empty() {
+                store.files.remove(relative);
+                continue;
+            }
+            self.pending.push(PendingFile {
+                relative: relative.clone(),
+                hash,
+                chunks,
+            });
+            let pending_chunks: usize = self.pending.iter().map(|p| p.chunks.len()).sum();
+            if pending_chunks >= client::MAX_BATCH_SIZE {
+                self.flush(store).await?;
+            }
+        }
+        self.flush(store).await
+    }
+
+    /// 为待处理文件获取向量并写入存储
+    async fn flush(&mut self, store: &mut EmbeddingStore) -> Result<(), AxonError> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        // 块内容前加上文件路径，便于按文件名语义命中
+        let inputs: Vec<String> = self
+            .pending
+            .iter()
+            .flat_map(|p| {
+                p.chunks
+                    .iter()
+                    .map(move |c| format!("{}\n{}", p.relative, c.text))
+            })
+            .collect();
+        let mut vectors = self.client.embed(&inputs).await?.into_iter();
+
+        for file in std::mem::take(&mut self.pending) {
+            let chunks = file
+                .chunks
+                .iter()
+                .zip(vectors.by_ref())
+                .map(|(chunk, vector)| StoredChunk {
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    vector,
+                })
+                .collect();
+            store.insert(file.relative, file.hash, chunks)?;
+        }
+        self.progress.chunks_embedded += inputs.len();
+        Ok(())
+    }
+}
+
+fn store_dir() -> Result<PathBuf, AxonError> {
+    get_app_data_dir()
+        .map(|dir| dir.join(EMBEDDINGS_DIR))
+        .ok_or_else(|| {
+            AxonError::localized(
+                ErrorKind::Unavailable,
+                "app.data_dir_unavailable",
+                serde_json::json!(null),
+            )
+        })
+}
+
+/// 读取文本文件，过大、二进制或非 UTF-8 的文件返回 None
+async fn read_text_file(path: &Path) -> Option<String> {
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_FILE_SIZE {
+        return None;
+    }
+    let bytes = tokio::fs::read(path).await.ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
@@ -0,0 +1,235 @@
+//! 向量存储
+//!
+//! 每个项目一个 JSON 文件（`<app_data_dir>/embeddings/<目录哈希>.json`），按文件记录内容哈希
+//! 和各块的向量，内容未变化的文件在重建索引时直接复用。向量写入前归一化，
+//! 以 little-endian f32 的 base64 形式保存，检索时对全部块做点积（余弦相似度）排序。
+
+use crate::error::AxonError;
+use crate::utils::json_store;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// 单个块的向量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredChunk {
+    pub start_line: usize,
+    pub end_line: usize,
+    #[serde(with = "vector_base64")]
+    pub vector: Vec<f32>,
+}
+
+/// 单个文件的索引记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEntry {
+    /// 文件内容的 SHA-256
+    pub hash: String,
+    pub chunks: Vec<StoredChunk>,
+}
+
+/// 项目的向量存储
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingStore {
+    pub root: String,
+    pub model: String,
+    /// 向量维度（首次写入时确定）
+    pub dimensions: usize,
+    /// 相对路径 -> 索引记录
+    pub files: BTreeMap<String, FileEntry>,
+    /// 最近一次完成构建的时间（Unix 毫秒）
+    pub built_at: Option<i64>,
+}
+
+impl EmbeddingStore {
+    pub fn new(root: &Path, model: &str) -> Self {
+        Self {
+            root: root.to_string_lossy().to_string(),
+            model: model.to_string(),
+            dimensions: 0,
+            files: BTreeMap::new(),
+            built_at: None,
+        }
+    }
+
+    /// 读取项目的存储，不存在、无法解析或模型不同时返回空存储
+    pub fn load(dir: &Path, root: &Path, model: &str) -> Self {
+        let path = store_path(dir, root);
+        let loaded = json_store::load::<Self>(&path, "向量存储").unwrap_or_else(|e| {
+            warn!("{}，将重新构建", e.message);
+            None
+        });
+
+        match loaded {
+            Some(store) if store.model == model => {
+                debug!("已加载向量存储: {:?}, {} 个文件", path, store.files.len());
+                store
+            }
+            Some(store) => {
+                debug!(
+                    "嵌入模型已从 {} 切换为 {}，重新构建索引",
+                    store.model, model
+                );
+                Self::new(root, model)
+            }
+            None => Self::new(root, model),
+        }
+    }
+
+    /// 写入存储（先写临时文件再替换）
+    pub fn save(&self, dir: &Path) -> Result<(), AxonError> {
+        json_store::save_compact(&store_path(dir, Path::new(&self.root)), self, "向量存储")
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.files.values().map(|f| f.chunks.len()).sum()
+    }
+
+    /// 写入文件的块向量（向量会被归一化），维度与已有向量不一致时返回错误
+    pub fn insert(
+        &mut self,
+        relative: String,
+        hash: String,
+        mut chunks: Vec<StoredChunk>,
+    ) -> Result<(), AxonError> {
+        for chunk in &mut chunks {
+            if self.dimensions == 0 {
+                self.dimensions = chunk.vector.len();
+            }
+            if chunk.vector.len() != self.dimensions {
+                return Err(AxonError::invalid_data(format!(
+                    "嵌入向量维度不一致: {} != {}",
+                    chunk.vector.len(),
+                    self.dimensions
+                )));
+            }
+            normalize(&mut chunk.vector);
+        }
+        self.files.insert(relative, FileEntry { hash, chunks });
+        Ok(())
+    }
+
+    /// 按余弦相似度返回最相近的 `top_k` 个块
+    pub fn search(&self, query: &[f32], top_k: usize) -> Vec<(&str, &StoredChunk, f32)> {
+        if query.len() != self.dimensions {
+            return Vec::new();
+        }
+        let mut query = query.to_vec();
+        normalize(&mut query);
+
+        let mut scored: Vec<(&str, &StoredChunk, f32)> = self
+            .files
+            .iter()
+            .flat_map(|(path, entry)| entry.chunks.iter().map(move |c| (path.as_str(), c)))
+            .map(|(path, chunk)| (path, chunk, dot(&query, &chunk.vector)))
+            .collect();
+        scored.sort_by(|a, b| b.2.total_cmp(&a.2));
+        scored.truncate(top_k);
+        scored
+    }
+}
+
+/// 项目对应的存储文件路径
+fn store_path(dir: &Path, root: &Path) -> PathBuf {
+    let digest = Sha256::digest(root.to_string_lossy().as_bytes());
+    let name: String = digest
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    dir.join(format!("{}.json", name))
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// 向量以 little-endian f32 的 base64 序列化
+mod vector_base64 {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(vector: &[f32], serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes: Vec<u8> = vector.iter().flat_map(|v| v.to_le_bytes()).collect();
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f32>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(serde::de::Error::custom)?;
+        if bytes.len() % 4 != 0 {
+            return Err(serde::de::Error::custom("向量字节数不是 4 的倍数"));
+        }
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(line: usize, vector: Vec<f32>) -> StoredChunk {
+        StoredChunk {
+            start_line: line,
+            end_line: line,
+            vector,
+        }
+    }
+
+    #[test]
+    fn search_ranks_by_cosine_similarity() {
+        let mut store = EmbeddingStore::new(Path::new("/project"), "model");
+        store
+            .insert(
+                "a.rs".into(),
+                "h1".into(),
+                vec![chunk(1, vec![1.0, 0.0]), chunk(2, vec![3.0, 3.0])],
+            )
+            .unwrap();
+        store
+            .insert("b.rs".into(), "h2".into(), vec![chunk(1, vec![0.0, 5.0])])
+            .unwrap();
+        assert!(store
+            .insert("c.rs".into(), "h3".into(), vec![chunk(1, vec![1.0])])
+            .is_err());
+
+        let results = store.search(&[0.0, 2.0], 2);
+        assert_eq!(results.len(), 2);
+        assert_eq!((results[0].0, results[0].1.start_line), ("b.rs", 1));
+        assert_eq!((results[1].0, results[1].1.start_line), ("a.rs", 2));
+        assert!((results[0].2 - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn vectors_roundtrip_through_json() {
+        let mut store = EmbeddingStore::new(Path::new("/project"), "model");
+        store
+            .insert("a.rs".into(), "h".into(), vec![chunk(3, vec![3.0, -4.0])])
+            .unwrap();
+
+        let json = serde_json::to_string(&store).unwrap();
+        let loaded: EmbeddingStore = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            loaded.files["a.rs"].chunks[0].vector,
+            store.files["a.rs"].chunks[0].vector
+        );
+        assert!((loaded.files["a.rs"].chunks[0].vector[0] - 0.6).abs() < 1e-6);
+        assert_eq!(loaded.dimensions, 2);
+    }
+}
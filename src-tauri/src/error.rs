@@ -0,0 +1,254 @@
+//! 统一错误类型
+//!
+//! 所有 Tauri 命令返回 `Result<T, AxonError>`。错误序列化为
+//! `{ kind, message, details, retryable }`，前端可以按 `kind` 区分错误类别，
+//! 同时 `message` 保留原有的可读提示。
+//!
+//! 通过 `localized` 构造的错误额外带有 `code` 和 `params`，`message` 按当前语言
+//! 渲染（见 [`crate::i18n`]），前端也可以用消息码自行翻译。
+
+use serde::Serialize;
+use std::fmt::Display;
+
+/// 错误类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// 资源不存在（文件、会话、配置项等）
+    NotFound,
+    /// 权限不足或操作未被允许
+    PermissionDenied,
+    /// 参数无效
+    InvalidInput,
+    /// 资源已存在
+    AlreadyExists,
+    /// 操作超时
+    Timeout,
+    /// 网络请求失败
+    Network,
+    /// 文件系统或进程 IO 失败
+    Io,
+    /// 数据格式错误（解析、序列化失败）
+    InvalidData,
+    /// 操作被取消
+    Cancelled,
+    /// 依赖的服务或功能当前不可用
+    Unavailable,
+    /// 当前平台或配置不支持
+    Unsupported,
+    /// 外部服务返回错误
+    External,
+    /// 内部错误
+    Internal,
+}
+
+impl ErrorKind {
+    /// 该类别的错误默认是否可重试
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::Timeout | Self::Network | Self::Unavailable)
+    }
+}
+
+/// 命令错误
+#[derive(Debug, Clone, Serialize, thiserror::Error)]
+#[serde(rename_all = "camelCase")]
+#[error("{message}")]
+pub struct AxonError {
+    pub kind: ErrorKind,
+    /// 可读的错误信息
+    pub message: String,
+    /// 附加信息（如路径、状态码）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    /// 是否可以直接重试
+    pub retryable: bool,
+    /// 消息码（本地化消息）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// 消息模板参数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub params: Option<serde_json::Value>,
+}
+
+impl AxonError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            details: None,
+            retryable: kind.is_retryable(),
+            code: None,
+            params: None,
+        }
+    }
+
+    /// 本地化错误：按当前语言渲染消息码对应的模板
+    pub fn localized(kind: ErrorKind, code: &str, params: serde_json::Value) -> Self {
+        let mut err = Self::new(kind, crate::i18n::render(code, &params));
+        err.code = Some(code.to_string());
+        err.params = Some(params).filter(|p| !p.is_null());
+        err
+    }
+
+    /// 本地化 IO 错误，模板参数 `error` 为 IO 错误信息
+    pub fn localized_io(code: &str, err: &std::io::Error) -> Self {
+        Self::localized(io_kind(err), code, serde_json::json!({ "error": err.to_string() }))
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, message)
+    }
+
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::PermissionDenied, message)
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    pub fn already_exists(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::AlreadyExists, message)
+    }
+
+    pub fn timeout(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Timeout, message)
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Network, message)
+    }
+
+    pub fn invalid_data(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidData, message)
+    }
+
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Cancelled, message)
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Unavailable, message)
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Unsupported, message)
+    }
+
+    pub fn external(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::External, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Internal, message)
+    }
+
+    /// 由 IO 错误构造，按 IO 错误类型归类，消息为 "{context}: {err}"
+    pub fn io(context: impl Display, err: &std::io::Error) -> Self {
+        Self::new(io_kind(err), format!("{}: {}", context, err))
+    }
+
+    /// 附加详情
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// 覆盖默认的可重试标记
+    pub fn with_retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+}
+
+fn io_kind(err: &std::io::Error) -> ErrorKind {
+    use std::io::ErrorKind as Io;
+    match err.kind() {
+        Io::NotFound => ErrorKind::NotFound,
+        Io::PermissionDenied => ErrorKind::PermissionDenied,
+        Io::AlreadyExists => ErrorKind::AlreadyExists,
+        Io::InvalidInput => ErrorKind::InvalidInput,
+        Io::InvalidData | Io::UnexpectedEof => ErrorKind::InvalidData,
+        Io::TimedOut => ErrorKind::Timeout,
+        Io::Interrupted => ErrorKind::Cancelled,
+        Io::Unsupported => ErrorKind::Unsupported,
+        Io::ConnectionRefused
+        | Io::ConnectionReset
+        | Io::ConnectionAborted
+        | Io::NotConnected
+        | Io::AddrInUse
+        | Io::AddrNotAvailable
+        | Io::BrokenPipe => ErrorKind::Network,
+        _ => ErrorKind::Io,
+    }
+}
+
+/// 尚未细分类别的字符串错误统一视为内部错误
+impl From<String> for AxonError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for AxonError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<std::io::Error> for AxonError {
+    fn from(err: std::io::Error) -> Self {
+        Self::new(io_kind(&err), err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AxonError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::invalid_data(format!("JSON 解析失败: {}", err))
+    }
+}
+
+impl From<reqwest::Error> for AxonError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            return Self::timeout(format!("请求超时: {}", err));
+        }
+        if let Some(status) = err.status() {
+            return Self::external(format!("请求失败: {}", err))
+                .with_details(serde_json::json!({ "status": status.as_u16() }))
+                .with_retryable(status.is_server_error());
+        }
+        Self::network(format!("请求失败: {}", err))
+    }
+}
+
+impl From<tokio::task::JoinError> for AxonError {
+    fn from(err: tokio::task::JoinError) -> Self {
+        if err.is_cancelled() {
+            Self::cancelled(format!("任务已取消: {}", err))
+        } else {
+            Self::internal(format!("任务执行失败: {}", err))
+        }
+    }
+}
+
+impl From<tauri::Error> for AxonError {
+    fn from(err: tauri::Error) -> Self {
+        Self::internal(err.to_string())
+    }
+}
+
+impl From<crate::opencode::OpencodeError> for AxonError {
+    fn from(err: crate::opencode::OpencodeError) -> Self {
+        use crate::opencode::OpencodeError as E;
+        let message = err.to_string();
+        match err {
+            E::IoError(e) => Self::new(io_kind(&e), message),
+            E::RequestError(e) => Self::from(e),
+            E::DownloadError(_) | E::ConnectionError(_) => Self::network(message),
+            E::BinaryNotFound => Self::not_found(message),
+            E::ConfigError(_) => Self::invalid_input(message),
+            E::ExtractError(_) | E::ServiceStartError(_) => Self::internal(message),
+        }
+    }
+}
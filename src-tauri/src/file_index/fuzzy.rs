@@ -0,0 +1,123 @@
+//! 路径模糊匹配打分
+//!
+//! 查询字符需按顺序出现在路径中（子序列匹配，忽略大小写）。优先尝试只在文件名中
+//! 匹配，失败时再在整个路径中匹配；连续命中、命中单词开头和命中文件名都会加分，
+//! 跳过的字符和过长的路径会扣分。
+
+/// 每个命中字符的基础分
+const MATCH_SCORE: i64 = 16;
+
+/// 紧接上一个命中字符
+const CONSECUTIVE_BONUS: i64 = 24;
+
+/// 命中路径段、单词或驼峰的开头
+const BOUNDARY_BONUS: i64 = 20;
+
+/// 全部命中在文件名中
+const FILENAME_BONUS: i64 = 60;
+
+/// 两个命中字符之间每跳过一个字符的扣分（单处最多扣 GAP_PENALTY_MAX）
+const GAP_PENALTY: i64 = 2;
+const GAP_PENALTY_MAX: i64 = 20;
+
+/// 对路径打分，不匹配时返回 None；`query` 需已转为小写
+///
+/// 返回得分和命中字符在路径中的下标
+pub fn score(query: &[char], candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let chars: Vec<char> = candidate.chars().collect();
+    if query.len() > chars.len() {
+        return None;
+    }
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let filename_start = chars.iter().rposition(|c| *c == '/').map_or(0, |i| i + 1);
+
+    if let Some(positions) = match_from(query, &chars, &lower, filename_start) {
+        let score = rate(&chars, &positions) + FILENAME_BONUS;
+        return Some((score, positions));
+    }
+    let positions = match_from(query, &chars, &lower, 0)?;
+    Some((rate(&chars, &positions), positions))
+}
+
+/// 从 `start` 开始按顺序匹配查询字符，命中尽量选在单词开头
+fn match_from(query: &[char], chars: &[char], lower: &[char], start: usize) -> Option<Vec<usize>> {
+    let mut positions = Vec::with_capacity(query.len());
+    let mut from = start;
+    for q in query {
+        let first = (from..lower.len()).find(|&i| lower[i] == *q)?;
+        positions.push(first);
+        from = first + 1;
+    }
+
+    // 不连续的命中在不越过下一个命中的范围内换到单词开头
+    for i in 0..positions.len() {
+        let prev = if i == 0 { start } else { positions[i - 1] + 1 };
+        if i > 0 && positions[i] == prev {
+            continue;
+        }
+        let limit = positions.get(i + 1).copied().unwrap_or(lower.len());
+        if let Some(better) = (prev..limit).find(|&p| lower[p] == query[i] && is_boundary(chars, p))
+        {
+            positions[i] = better;
+        }
+    }
+    Some(positions)
+}
+
+fn is_boundary(chars: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+    let prev = chars[index - 1];
+    let current = chars[index];
+    matches!(prev, '/' | '\\' | '_' | '-' | '.' | ' ')
+        || (prev.is_lowercase() && current.is_uppercase())
+}
+
+fn rate(chars: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    for &pos in positions {
+        score += MATCH_SCORE;
+        if is_boundary(chars, pos) {
+            score += BOUNDARY_BONUS;
+        }
+        match previous {
+            Some(prev) if pos == prev + 1 => score += CONSECUTIVE_BONUS,
+            Some(prev) => score -= ((pos - prev - 1) as i64 * GAP_PENALTY).min(GAP_PENALTY_MAX),
+            None => {}
+        }
+        previous = Some(pos);
+    }
+    score - chars.len() as i64 / 8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn q(query: &str) -> Vec<char> {
+        query.chars().flat_map(char::to_lowercase).collect()
+    }
+
+    #[test]
+    fn rejects_non_subsequence() {
+        assert!(score(&q("xyz"), "src/main.rs").is_none());
+        assert!(score(&q("nm"), "main").is_none());
+    }
+
+    #[test]
+    fn filename_and_boundary_matches_rank_higher() {
+        let (in_name, positions) = score(&q("main"), "src/components/main.rs").unwrap();
+        assert_eq!(positions, vec![15, 16, 17, 18]);
+        let (scattered, _) = score(&q("main"), "src/my_app/index.rs").unwrap();
+        assert!(in_name > scattered);
+
+        let (camel, _) = score(&q("fb"), "src/FooBar.tsx").unwrap();
+        let (inner, _) = score(&q("fb"), "src/fxxxb.tsx").unwrap();
+        assert!(camel > inner);
+    }
+}
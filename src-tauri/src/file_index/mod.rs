@@ -0,0 +1,326 @@
+//! 项目文件模糊查找索引
+//!
+//! 切换项目目录时在后台线程遍历项目、建立相对路径列表，并通过文件监听增量更新
+//! （新增的路径加入索引，已不存在的路径连同其子路径一起移除）。
+//! `find` 在内存中对路径做子序列匹配打分，供命令面板"转到文件"使用。
+//! 遍历和增量更新都遵循项目的忽略规则（[`IgnoreMatcher`]），忽略文件变化时重新遍历。
+
+mod fuzzy;
+
+use crate::utils::ignore::{IgnoreMatcher, AXONIGNORE_FILE, GITIGNORE_FILE};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// 索引的最大文件数（超过后停止遍历）
+const MAX_INDEXED_FILES: usize = 200_000;
+
+/// 默认返回的结果数
+const DEFAULT_LIMIT: usize = 50;
+
+/// 模糊查找结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuzzyMatch {
+    pub path: String,
+    /// 相对项目目录的路径（`/` 分隔）
+    pub relative_path: String,
+    pub score: i64,
+    /// 命中的字符位置（相对路径中的字符下标），用于高亮
+    pub positions: Vec<usize>,
+}
+
+/// 索引状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIndexStatus {
+    pub root: Option<String>,
+    pub file_count: usize,
+    /// 正在后台构建索引
+    pub indexing: bool,
+    /// 文件数超过上限，索引不完整
+    pub truncated: bool,
+    /// 最近一次完成构建的时间（Unix 毫秒）
+    pub built_at: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+struct IndexData {
+    root: Option<PathBuf>,
+    files: BTreeSet<String>,
+    ignore: IgnoreMatcher,
+    indexing: bool,
+    truncated: bool,
+    built_at: Option<i64>,
+}
+
+/// 项目文件索引
+pub struct FileIndex {
+    data: Arc<RwLock<IndexData>>,
+    watcher: Mutex<Option<RecommendedWatcher>>,
+    /// 每次切换根目录递增，丢弃过期的构建结果
+    generation: Arc<AtomicU64>,
+}
+
+impl FileIndex {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            data: Arc::new(RwLock::new(IndexData::default())),
+            watcher: Mutex::new(None),
+            generation: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// 切换索引的项目目录，`None` 时清空索引并停止监听
+    pub fn set_root(&self, root: Option<PathBuf>) {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        *self.watcher.lock() = None;
+        let Some(root) = root.clone().filter(|r| r.is_dir()) else {
+            *self.data.write() = IndexData {
+                root,
+                ..Default::default()
+            };
+            return;
+        };
+        let ignore = IgnoreMatcher::for_project(&root);
+        *self.data.write() = IndexData {
+            root: Some(root.clone()),
+            ignore: ignore.clone(),
+            indexing: true,
+            ..Default::default()
+        };
+
+        self.start_watcher(&root, generation);
+
+        let data = Arc::clone(&self.data);
+        let current = Arc::clone(&self.generation);
+        std::thread::spawn(move || {
+            let started = std::time::Instant::now();
+            let mut files = BTreeSet::new();
+            let truncated = !collect_files(&root, &root, &ignore, &mut files);
+            if current.load(Ordering::SeqCst) != generation {
+                debug!("索引目录已切换，丢弃构建结果: {:?}", root);
+                return;
+            }
+
+            let mut data = data.write();
+            // 合并构建期间监听到的新增文件
+            files.extend(std::mem::take(&mut data.files));
+            info!(
+                "文件索引已建立: {:?}, {} 个文件, 耗时 {:?}",
+                root,
+                files.len(),
+                started.elapsed()
+            );
+            data.files = files;
+            data.indexing = false;
+            data.truncated = truncated;
+            data.built_at = Some(chrono::Utc::now().timestamp_millis());
+        });
+    }
+
+    fn start_watcher(&self, root: &Path, generation: u64) {
+        let data = Arc::clone(&self.data);
+        let current = Arc::clone(&self.generation);
+        let watch_root = root.to_path_buf();
+        let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if current.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            match res {
+                Ok(event) => apply_event(&data, &watch_root, &event.paths),
+                Err(e) => warn!("文件监听错误: {}", e),
+            }
+        });
+
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                warn!("创建文件监听失败，索引不会自动更新: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+            warn!("监听项目目录失败，索引不会自动更新: {}", e);
+            return;
+        }
+        *self.watcher.lock() = Some(watcher);
+    }
+
+    pub fn status(&self) -> FileIndexStatus {
+        let data = self.data.read();
+        FileIndexStatus {
+            root: data.root.as_ref().map(|r| r.to_string_lossy().to_string()),
+            file_count: data.files.len(),
+            indexing: data.indexing,
+            truncated: data.truncated,
+            built_at: data.built_at,
+        }
+    }
+
+    /// 当前项目目录和全部文件的相对路径，索引尚未建立完成时返回 None
+    pub fn snapshot(&self) -> Option<(PathBuf, Vec<String>)> {
+        let data = self.data.read();
+        if data.indexing {
+            return None;
+        }
+        let root = data.root.clone()?;
+        Some((root, data.files.iter().cloned().collect()))
+    }
+
+    /// 模糊查找文件，按得分从高到低返回
+    pub fn find(&self, query: &str, limit: Option<usize>) -> Vec<FuzzyMatch> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).max(1);
+        let query: Vec<char> = query
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect();
+
+        let data = self.data.read();
+        let Some(root) = data.root.as_ref() else {
+            return Vec::new();
+        };
+
+        let mut matches: Vec<(i64, &String, Vec<usize>)> = if query.is_empty() {
+            data.files
+                .iter()
+                .take(limit)
+                .map(|f| (0, f, Vec::new()))
+                .collect()
+        } else {
+            data.files
+                .iter()
+                .filter_map(|f| fuzzy::score(&query, f).map(|(score, pos)| (score, f, pos)))
+                .collect()
+        };
+        matches.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| a.1.len().cmp(&b.1.len()))
+                .then_with(|| a.1.cmp(b.1))
+        });
+
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(score, relative, positions)| FuzzyMatch {
+                path: root.join(relative).to_string_lossy().to_string(),
+                relative_path: relative.clone(),
+                score,
+                positions,
+            })
+            .collect()
+    }
+}
+
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    if relative.as_os_str().is_empty() {
+        return None;
+    }
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// 收集目录下的文件，达到上限时返回 false
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    ignore: &IgnoreMatcher,
+    files: &mut BTreeSet<String>,
+) -> bool {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return true;
+    };
+    for entry in read_dir.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        let Some(relative) = relative_path(root, &path) else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if entry.file_name().to_string_lossy().starts_with('.')
+                || ignore.is_ignored(&relative, true)
+            {
+                continue;
+            }
+            if !collect_files(root, &path, ignore, files) {
+                return false;
+            }
+        } else if file_type.is_file() {
+            if files.len() >= MAX_INDEXED_FILES {
+                return false;
+            }
+            if !ignore.is_ignored(&relative, false) {
+                files.insert(relative);
+            }
+        }
+    }
+    true
+}
+
+/// 路径或任一上级目录是隐藏目录
+fn in_hidden_dir(relative: &str, is_dir: bool) -> bool {
+    let mut parts: Vec<&str> = relative.split('/').collect();
+    if !is_dir {
+        parts.pop();
+    }
+    parts.iter().any(|part| part.starts_with('.'))
+}
+
+/// 忽略文件变化后按新规则重新遍历
+fn reload_ignore(data: &RwLock<IndexData>, root: &Path) {
+    let ignore = IgnoreMatcher::for_project(root);
+    let mut files = BTreeSet::new();
+    let truncated = !collect_files(root, root, &ignore, &mut files);
+    debug!(
+        "忽略规则已变化，重新遍历: {:?}, {} 个文件",
+        root,
+        files.len()
+    );
+
+    let mut data = data.write();
+    data.ignore = ignore;
+    if !data.indexing {
+        data.files = files;
+        data.truncated = truncated;
+        data.built_at = Some(chrono::Utc::now().timestamp_millis());
+    }
+}
+
+/// 按监听到的路径增量更新索引
+fn apply_event(data: &RwLock<IndexData>, root: &Path, paths: &[PathBuf]) {
+    for path in paths {
+        let Some(relative) = relative_path(root, path) else {
+            continue;
+        };
+        if relative == GITIGNORE_FILE || relative == AXONIGNORE_FILE {
+            reload_ignore(data, root);
+        }
+        let is_dir = path.is_dir();
+        let ignore = data.read().ignore.clone();
+        if in_hidden_dir(&relative, is_dir) || ignore.is_ignored(&relative, is_dir) {
+            continue;
+        }
+
+        if is_dir {
+            let mut added = BTreeSet::new();
+            collect_files(root, path, &ignore, &mut added);
+            data.write().files.extend(added);
+        } else if path.is_file() {
+            data.write().files.insert(relative);
+        } else {
+            // 路径已不存在：可能是文件，也可能是整个目录
+            let prefix = format!("{}/", relative);
+            data.write()
+                .files
+                .retain(|f| f != &relative && !f.starts_with(&prefix));
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! 代码语法高亮
+//!
+//! 基于 syntect 内置的语法和主题，在后端把代码转换为逐行的 HTML（内联样式的 `<span>`）
+//! 或 Token 列表，前端的 Diff 视图和文件预览无需加载语法定义。
+//! 语法和主题在首次使用时加载，之后常驻内存。
+
+use crate::error::AxonError;
+use crate::markdown::CodeHighlighter;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Color, FontStyle, Style, Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// 默认主题
+pub const DEFAULT_THEME: &str = "base16-ocean.dark";
+
+/// 超过该长度的行（通常是压缩代码）不做高亮，直接按纯文本输出
+const MAX_HIGHLIGHT_LINE_CHARS: usize = 10_000;
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightFormat {
+    /// 每行一个 HTML 片段
+    #[default]
+    Html,
+    /// 每行一组 Token
+    Tokens,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightToken {
+    pub text: String,
+    /// `#rrggbb`
+    pub color: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub bold: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub italic: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub underline: bool,
+}
+
+/// 一行高亮结果（不含换行符）
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum HighlightedLine {
+    Html(String),
+    Tokens(Vec<HighlightToken>),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightedCode {
+    /// 实际使用的语法名称，无法识别时为 "Plain Text"
+    pub language: String,
+    pub theme: String,
+    /// 主题的背景色和默认文字颜色（`#rrggbb`）
+    pub background: Option<String>,
+    pub foreground: Option<String>,
+    pub lines: Vec<HighlightedLine>,
+}
+
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn theme_set() -> &'static ThemeSet {
+    static THEMES: OnceLock<ThemeSet> = OnceLock::new();
+    THEMES.get_or_init(ThemeSet::load_defaults)
+}
+
+/// 可用的主题名称
+pub fn theme_names() -> Vec<String> {
+    theme_set().themes.keys().cloned().collect()
+}
+
+fn find_theme(name: Option<&str>) -> Result<(&'static str, &'static Theme), AxonError> {
+    let name = name.unwrap_or(DEFAULT_THEME);
+    theme_set()
+        .themes
+        .get_key_value(name)
+        .map(|(name, theme)| (name.as_str(), theme))
+        .ok_or_else(|| AxonError::invalid_input(format!("未知的高亮主题: {}", name)))
+}
+
+/// 按语言名称或扩展名查找语法，`file_name` 用于按扩展名兜底，最后按首行（如 shebang）识别
+pub fn find_syntax(
+    language: Option<&str>,
+    file_name: Option<&str>,
+    first_line: &str,
+) -> &'static SyntaxReference {
+    let syntaxes = syntax_set();
+    language
+        .filter(|language| !language.trim().is_empty())
+        .and_then(|language| syntaxes.find_syntax_by_token(language.trim()))
+        .or_else(|| {
+            file_name
+                .and_then(|name| name.rsplit_once('.'))
+                .and_then(|(_, extension)| syntaxes.find_syntax_by_extension(extension))
+        })
+        .or_else(|| syntaxes.find_syntax_by_first_line(first_line))
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text())
+}
+
+/// 逐行高亮器，保留跨行的解析状态（多行注释、字符串等）
+pub struct LineHighlighter {
+    inner: HighlightLines<'static>,
+    format: HighlightFormat,
+    pub language: &'static str,
+    pub theme: &'static str,
+    background: Option<Color>,
+    foreground: Option<Color>,
+}
+
+impl LineHighlighter {
+    pub fn new(
+        syntax: &'static SyntaxReference,
+        theme: Option<&str>,
+        format: HighlightFormat,
+    ) -> Result<Self, AxonError> {
+        let (theme_name, theme) = find_theme(theme)?;
+        Ok(Self {
+            inner: HighlightLines::new(syntax, theme),
+            format,
+            language: &syntax.name,
+            theme: theme_name,
+            background: theme.settings.background,
+            foreground: theme.settings.foreground,
+        })
+    }
+
+    /// 高亮一行（可带换行符，输出中会去掉）
+    pub fn line(&mut self, line: &str) -> Result<HighlightedLine, AxonError> {
+        let content = line.trim_end_matches(['\n', '\r']);
+        if content.chars().count() > MAX_HIGHLIGHT_LINE_CHARS {
+            let plain = Style {
+                foreground: self.foreground.unwrap_or(Color::BLACK),
+                ..Default::default()
+            };
+            return Ok(self.render(&[(plain, content)]));
+        }
+        let ranges = self
+            .inner
+            .highlight_line(line, syntax_set())
+            .map_err(|e| AxonError::internal(format!("语法高亮失败: {}", e)))?;
+        Ok(self.render(&ranges))
+    }
+
+    fn render(&self, ranges: &[(Style, &str)]) -> HighlightedLine {
+        let ranges = ranges.iter().filter_map(|(style, text)| {
+            let text = text.trim_end_matches(['\n', '\r']);
+            (!text.is_empty()).then_some((style, text))
+        });
+        match self.format {
+            HighlightFormat::Html => {
+                let mut html = String::new();
+                for (style, text) in ranges {
+                    push_span(&mut html, style, text);
+                }
+                HighlightedLine::Html(html)
+            }
+            HighlightFormat::Tokens => HighlightedLine::Tokens(
+                ranges
+                    .map(|(style, text)| HighlightToken {
+                        text: text.to_string(),
+                        color: hex(style.foreground),
+                        bold: style.font_style.contains(FontStyle::BOLD),
+                        italic: style.font_style.contains(FontStyle::ITALIC),
+                        underline: style.font_style.contains(FontStyle::UNDERLINE),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// 整理为完整结果
+    pub fn finish(self, lines: Vec<HighlightedLine>) -> HighlightedCode {
+        HighlightedCode {
+            language: self.language.to_string(),
+            theme: self.theme.to_string(),
+            background: self.background.map(hex),
+            foreground: self.foreground.map(hex),
+            lines,
+        }
+    }
+}
+
+/// 高亮一段代码
+pub fn highlight(
+    text: &str,
+    language: Option<&str>,
+    theme: Option<&str>,
+    format: HighlightFormat,
+) -> Result<HighlightedCode, AxonError> {
+    let first_line = text.lines().next().unwrap_or_default();
+    let syntax = find_syntax(language, None, first_line);
+    let mut highlighter = LineHighlighter::new(syntax, theme, format)?;
+    let lines = LinesWithEndings::from(text)
+        .map(|line| highlighter.line(line))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(highlighter.finish(lines))
+}
+
+/// Markdown 代码块高亮（只处理能识别语言的代码块）
+pub struct MarkdownHighlighter {
+    theme: &'static Theme,
+}
+
+impl MarkdownHighlighter {
+    pub fn new(theme: &str) -> Result<Self, AxonError> {
+        let (_, theme) = find_theme(Some(theme))?;
+        Ok(Self { theme })
+    }
+}
+
+impl CodeHighlighter for MarkdownHighlighter {
+    fn highlight(&self, code: &str, language: &str) -> Option<String> {
+        let syntax = syntax_set().find_syntax_by_token(language.trim())?;
+        let mut lines = HighlightLines::new(syntax, self.theme);
+        let mut html = String::new();
+        for line in LinesWithEndings::from(code) {
+            let ranges = lines.highlight_line(line, syntax_set()).ok()?;
+            for (style, text) in &ranges {
+                let content = text.trim_end_matches(['\n', '\r']);
+                if !content.is_empty() {
+                    push_span(&mut html, style, content);
+                }
+                html.push_str(&text[content.len()..]);
+            }
+        }
+        Some(html)
+    }
+}
+
+fn push_span(html: &mut String, style: &Style, text: &str) {
+    let _ = write!(html, "<span style=\"color:{}", hex(style.foreground));
+    if style.font_style.contains(FontStyle::BOLD) {
+        html.push_str(";font-weight:bold");
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        html.push_str(";font-style:italic");
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        html.push_str(";text-decoration:underline");
+    }
+    html.push_str("\">");
+    escape_html(html, text);
+    html.push_str("</span>");
+}
+
+fn escape_html(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            _ => output.push(c),
+        }
+    }
+}
+
+fn hex(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_by_language_and_escapes() {
+        let code = highlight(
+            "fn main() {\n    let s = \"<b>\";\n}\n",
+            Some("rs"),
+            None,
+            HighlightFormat::Html,
+        )
+        .unwrap();
+        assert_eq!(code.language, "Rust");
+        assert_eq!(code.lines.len(), 3);
+        let HighlightedLine::Html(line) = &code.lines[1] else {
+            panic!("expected html");
+        };
+        assert!(line.contains("&lt;b&gt;"));
+        assert!(line.contains("<span style=\"color:#"));
+    }
+
+    #[test]
+    fn falls_back_to_first_line_and_plain_text() {
+        let code = highlight(
+            "#!/bin/bash\necho hi\n",
+            None,
+            None,
+            HighlightFormat::Tokens,
+        )
+        .unwrap();
+        assert_eq!(code.language, "Bourne Again Shell (bash)");
+        let code = highlight("just text", Some("nope"), None, HighlightFormat::Tokens).unwrap();
+        assert_eq!(code.language, "Plain Text");
+        let HighlightedLine::Tokens(tokens) = &code.lines[0] else {
+            panic!("expected tokens");
+        };
+        assert_eq!(tokens[0].text, "just text");
+        assert!(highlight("x", None, Some("no-such-theme"), HighlightFormat::Html).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+//! 全局快捷键
+//!
+//! 通过 global-shortcut 插件注册系统级快捷键，绑定来自设置：
+//! - 唤起 / 聚焦主窗口
+//! - 打开置顶的快速提问窗口，提交内容发送到主窗口的当前会话
+
+use crate::error::AxonError;
+use crate::opencode::HotkeySettings;
+use crate::state::AppState;
+use crate::tray::show_main_window;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::{error, info, warn};
+
+/// 快速提问窗口标签
+pub const QUICK_PROMPT_WINDOW: &str = "quick-prompt";
+
+/// 快速提问提交事件（发送到主窗口），payload 为提问内容
+pub const EVENT_QUICK_PROMPT_SUBMIT: &str = "quick-prompt:submit";
+
+/// 快捷键对应的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyAction {
+    Summon,
+    QuickPrompt,
+}
+
+/// 已注册的快捷键
+struct Binding {
+    /// 设置中的原始写法，用于日志和错误信息
+    accelerator: String,
+    shortcut: Shortcut,
+    action: HotkeyAction,
+}
+
+/// 全局快捷键管理器
+pub struct HotkeyManager {
+    /// 当前已注册的绑定
+    bindings: RwLock<Vec<Binding>>,
+}
+
+impl HotkeyManager {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            bindings: RwLock::new(Vec::new()),
+        })
+    }
+
+    /// 按设置重新注册全部快捷键
+    ///
+    /// 任一快捷键注册失败（格式错误或被其他程序占用）时恢复之前的绑定并返回错误
+    pub fn apply(&self, app: &AppHandle, settings: &HotkeySettings) -> Result<(), AxonError> {
+        let next = parse_bindings(settings)?;
+        let shortcuts = app.global_shortcut();
+        let mut bindings = self.bindings.write();
+
+        for binding in bindings.iter() {
+            if let Err(e) = shortcuts.unregister(binding.shortcut) {
+                warn!("注销快捷键失败 {}: {}", binding.accelerator, e);
+            }
+        }
+
+        for (index, binding) in next.iter().enumerate() {
+            if let Err(e) = shortcuts.register(binding.shortcut) {
+                for done in &next[..index] {
+                    let _ = shortcuts.unregister(done.shortcut);
+                }
+                for previous in bindings.iter() {
+                    let _ = shortcuts.register(previous.shortcut);
+                }
+                return Err(AxonError::unavailable(format!(
+                    "注册快捷键 {} 失败（可能已被其他程序占用）: {}",
+                    binding.accelerator, e
+                )));
+            }
+            info!(
+                "已注册全局快捷键 {} -> {:?}",
+                binding.accelerator, binding.action
+            );
+        }
+
+        *bindings = next;
+        Ok(())
+    }
+
+    fn action_for(&self, shortcut: &Shortcut) -> Option<HotkeyAction> {
+        self.bindings
+            .read()
+            .iter()
+            .find(|binding| binding.shortcut == *shortcut)
+            .map(|binding| binding.action)
+    }
+}
+
+/// global-shortcut 插件，按下快捷键时分发到对应操作
+pub fn plugin() -> TauriPlugin<Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            let state: tauri::State<'_, AppState> = app.state();
+            match state.hotkeys.action_for(shortcut) {
+                Some(HotkeyAction::Summon) => show_main_window(app),
+                Some(HotkeyAction::QuickPrompt) => {
+                    if let Err(e) = show_quick_prompt(app) {
+                        error!("打开快速提问窗口失败: {}", e);
+                    }
+                }
+                None => {}
+            }
+        })
+        .build()
+}
+
+/// 解析并校验快捷键设置，不允许两个操作使用同一快捷键
+fn parse_bindings(settings: &HotkeySettings) -> Result<Vec<Binding>, AxonError> {
+    let entries = [
+        (&settings.summon, HotkeyAction::Summon),
+        (&settings.quick_prompt, HotkeyAction::QuickPrompt),
+    ];
+
+    let mut bindings: Vec<Binding> = Vec::new();
+    for (accelerator, action) in entries {
+        let Some(accelerator) = accelerator.as_deref().map(str::trim) else {
+            continue;
+        };
+        if accelerator.is_empty() {
+            continue;
+        }
+        let shortcut: Shortcut = accelerator.parse().map_err(|e| {
+            AxonError::invalid_input(format!("无效的快捷键 {}: {}", accelerator, e))
+        })?;
+        if bindings.iter().any(|binding| binding.shortcut == shortcut) {
+            return Err(AxonError::invalid_input(format!(
+                "快捷键重复: {}",
+                accelerator
+            )));
+        }
+        bindings.push(Binding {
+            accelerator: accelerator.to_string(),
+            shortcut,
+            action,
+        });
+    }
+    Ok(bindings)
+}
+
+/// 显示快速提问窗口（不存在时创建），失去焦点时自动隐藏
+pub fn show_quick_prompt(app: &AppHandle) -> Result<(), AxonError> {
+    if let Some(window) = app.get_webview_window(QUICK_PROMPT_WINDOW) {
+        let _ = window.center();
+        window
+            .show()
+            .map_err(|e| AxonError::internal(format!("显示快速提问窗口失败: {}", e)))?;
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    let window = tauri::WebviewWindowBuilder::new(
+        app,
+        QUICK_PROMPT_WINDOW,
+        tauri::WebviewUrl::App("index.html".into()),
+    )
+    .title("Axon")
+    .inner_size(640.0, 64.0)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .focused(true)
+    .build()
+    .map_err(|e| AxonError::internal(format!("创建快速提问窗口失败: {}", e)))?;
+
+    let window_for_event = window.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Focused(false) = event {
+            let _ = window_for_event.hide();
+        }
+    });
+    Ok(())
+}
+
+/// 隐藏快速提问窗口
+pub fn hide_quick_prompt(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(QUICK_PROMPT_WINDOW) {
+        let _ = window.hide();
+    }
+}
+
+/// 将快速提问发送到主窗口的当前会话
+pub fn submit_quick_prompt(app: &AppHandle, text: String) -> Result<(), AxonError> {
+    hide_quick_prompt(app);
+    app.emit_to("main", EVENT_QUICK_PROMPT_SUBMIT, text)
+        .map_err(|e| AxonError::internal(format!("发送快速提问失败: {}", e)))
+}
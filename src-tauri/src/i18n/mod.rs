@@ -0,0 +1,111 @@
+//! 后端消息本地化
+//!
+//! 错误提示等面向用户的消息以消息码标识，译文来自打包进二进制的
+//! `locales/<语言>.json`，模板中的 `{name}` 由参数替换。
+//! 当前语言来自设置，缺少译文时回退到中文，仍缺失时返回消息码本身。
+
+use crate::opencode::Language;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 当前语言
+static LANGUAGE: RwLock<Language> = RwLock::new(Language::Zh);
+
+/// 打包的语言文件
+const LOCALES: &[(Language, &str)] = &[
+    (Language::Zh, include_str!("locales/zh.json")),
+    (Language::En, include_str!("locales/en.json")),
+];
+
+type Catalog = HashMap<String, String>;
+
+fn catalogs() -> &'static HashMap<Language, Catalog> {
+    static CATALOGS: OnceLock<HashMap<Language, Catalog>> = OnceLock::new();
+    CATALOGS.get_or_init(|| {
+        LOCALES
+            .iter()
+            .map(|(language, content)| {
+                let catalog = serde_json::from_str(content).unwrap_or_else(|e| {
+                    tracing::error!("解析语言文件失败 {:?}: {}", language, e);
+                    Catalog::new()
+                });
+                (*language, catalog)
+            })
+            .collect()
+    })
+}
+
+/// 当前语言
+pub fn language() -> Language {
+    *LANGUAGE.read()
+}
+
+/// 切换语言（设置加载后和用户修改语言时调用）
+pub fn set_language(language: Language) {
+    *LANGUAGE.write() = language;
+}
+
+/// 按当前语言渲染消息
+///
+/// `params` 为 JSON 对象，字符串值原样替换，其他值按 JSON 文本替换
+pub fn render(code: &str, params: &serde_json::Value) -> String {
+    let catalogs = catalogs();
+    let template = catalogs
+        .get(&language())
+        .and_then(|c| c.get(code))
+        .or_else(|| catalogs.get(&Language::Zh).and_then(|c| c.get(code)));
+    let Some(template) = template else {
+        return code.to_string();
+    };
+
+    let mut message = template.clone();
+    if let Some(params) = params.as_object() {
+        for (name, value) in params {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            message = message.replace(&format!("{{{}}}", name), &value);
+        }
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locales_share_the_same_codes() {
+        let catalogs = catalogs();
+        let zh = &catalogs[&Language::Zh];
+        assert!(!zh.is_empty());
+        for (language, _) in LOCALES {
+            let catalog = &catalogs[language];
+            let mut missing: Vec<_> = zh.keys().filter(|k| !catalog.contains_key(*k)).collect();
+            missing.sort();
+            assert!(
+                missing.is_empty(),
+                "{:?} 缺少消息码: {:?}",
+                language,
+                missing
+            );
+            assert_eq!(catalog.len(), zh.len(), "{:?} 包含多余的消息码", language);
+        }
+    }
+
+    #[test]
+    fn render_substitutes_params_and_falls_back_to_code() {
+        let message = render(
+            "fs.file_not_found",
+            &serde_json::json!({ "path": "/tmp/a.txt" }),
+        );
+        assert!(message.ends_with("/tmp/a.txt"));
+        assert!(!message.contains("{path}"));
+        assert_eq!(
+            render("no.such.code", &serde_json::Value::Null),
+            "no.such.code"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! 后台任务管理模块
+//!
+//! 为耗时操作（批量文件操作等）提供统一的任务注册与取消机制。
+//! 任务 ID 由调用方（前端）生成，便于在任务返回前发起取消。
+
+use crate::error::AxonError;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::debug;
+
+/// 单个后台任务的句柄
+#[derive(Debug, Clone)]
+pub struct JobHandle {
+    id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobHandle {
+    /// 任务 ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 是否已被请求取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// 后台任务管理器
+pub struct JobManager {
+    jobs: RwLock<HashMap<String, JobHandle>>,
+}
+
+impl JobManager {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            jobs: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// 注册新任务，ID 已存在时返回错误
+    pub fn register(&self, id: &str) -> Result<JobHandle, AxonError> {
+        let mut jobs = self.jobs.write();
+        if jobs.contains_key(id) {
+            return Err(AxonError::already_exists(format!("任务已存在: {}", id)));
+        }
+
+        let handle = JobHandle {
+            id: id.to_string(),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+        jobs.insert(id.to_string(), handle.clone());
+        debug!("注册后台任务: {}", id);
+        Ok(handle)
+    }
+
+    /// 请求取消任务，任务不存在时返回 false
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.jobs.read().get(id) {
+            Some(handle) => {
+                handle.cancelled.store(true, Ordering::Relaxed);
+                debug!("已请求取消后台任务: {}", id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 任务结束后移除
+    pub fn finish(&self, id: &str) {
+        self.jobs.write().remove(id);
+        debug!("后台任务结束: {}", id);
+    }
+
+    /// 列出所有运行中的任务 ID
+    pub fn list(&self) -> Vec<String> {
+        self.jobs.read().keys().cloned().collect()
+    }
+}
+
+impl Default for JobManager {
+    fn default() -> Self {
+        Self {
+            jobs: RwLock::new(HashMap::new()),
+        }
+    }
+}
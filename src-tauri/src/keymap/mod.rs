@@ -0,0 +1,378 @@
+//! 快捷键映射
+//!
+//! 操作 ID → 快捷键，所有窗口、原生菜单和全局快捷键使用同一份映射：
+//! - 应用内操作的修改保存在设置的 `keymap` 中（只保存与默认值不同的项，`null` 表示解除绑定）
+//! - 全局操作（`app.summon`、`app.quickPrompt`）读写设置中的全局快捷键，修改后重新注册
+//! - `snippet.<片段 ID>` 为插入提示词片段的操作，与片段的快捷键 ID 对应，默认不绑定
+//!
+//! 快捷键写法与 Tauri 相同（如 `CmdOrCtrl+Shift+K`），比较时 `CmdOrCtrl` 在 macOS 上视为
+//! `Super`、其它平台视为 `Ctrl`，同一快捷键不能绑定两个操作。
+
+use crate::error::AxonError;
+use crate::opencode::HotkeySettings;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 映射变化事件（发送到所有窗口），负载为完整映射
+pub const EVENT_KEYMAP_CHANGED: &str = "keymap:changed";
+
+/// 片段操作的 ID 前缀
+pub const SNIPPET_ACTION_PREFIX: &str = "snippet.";
+
+/// 全局操作：唤起主窗口
+pub const ACTION_SUMMON: &str = "app.summon";
+
+/// 全局操作：快速提问
+pub const ACTION_QUICK_PROMPT: &str = "app.quickPrompt";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapScope {
+    /// 系统级，应用不在前台时也生效
+    Global,
+    /// 应用窗口内
+    App,
+}
+
+/// 内置操作
+struct ActionDef {
+    id: &'static str,
+    description: &'static str,
+    default: Option<&'static str>,
+}
+
+/// 应用内操作（全局操作的默认值来自 [`HotkeySettings`]）
+const APP_ACTIONS: &[ActionDef] = &[
+    ActionDef {
+        id: "session.new",
+        description: "新建会话",
+        default: Some("CmdOrCtrl+N"),
+    },
+    ActionDef {
+        id: "tab.close",
+        description: "关闭标签页",
+        default: Some("CmdOrCtrl+W"),
+    },
+    ActionDef {
+        id: "project.open",
+        description: "打开项目",
+        default: Some("CmdOrCtrl+O"),
+    },
+    ActionDef {
+        id: "settings.open",
+        description: "打开设置",
+        default: Some("CmdOrCtrl+,"),
+    },
+    ActionDef {
+        id: "view.toggleSidebar",
+        description: "显示 / 隐藏侧边栏",
+        default: Some("CmdOrCtrl+B"),
+    },
+    ActionDef {
+        id: "view.toggleTerminal",
+        description: "显示 / 隐藏终端",
+        default: Some("CmdOrCtrl+`"),
+    },
+];
+
+/// 映射中的一项
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeymapEntry {
+    pub action: String,
+    pub description: String,
+    pub scope: KeymapScope,
+    /// 当前快捷键（规范写法），未绑定时为空
+    pub accelerator: Option<String>,
+    pub default_accelerator: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Modifier {
+    CmdOrCtrl,
+    Ctrl,
+    Alt,
+    Shift,
+    Super,
+}
+
+impl Modifier {
+    fn name(self) -> &'static str {
+        match self {
+            Modifier::CmdOrCtrl => "CmdOrCtrl",
+            Modifier::Ctrl => "Ctrl",
+            Modifier::Alt => "Alt",
+            Modifier::Shift => "Shift",
+            Modifier::Super => "Super",
+        }
+    }
+
+    /// 当前平台上的实际修饰键
+    fn resolve(self) -> Modifier {
+        match self {
+            Modifier::CmdOrCtrl if cfg!(target_os = "macos") => Modifier::Super,
+            Modifier::CmdOrCtrl => Modifier::Ctrl,
+            other => other,
+        }
+    }
+}
+
+/// 支持的具名按键
+const NAMED_KEYS: &[&str] = &[
+    "Enter",
+    "Escape",
+    "Space",
+    "Tab",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "Up",
+    "Down",
+    "Left",
+    "Right",
+];
+
+/// 支持的符号键
+const SYMBOL_KEYS: &str = "`-=[]\\;',./";
+
+/// 解析后的快捷键
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Accelerator {
+    modifiers: BTreeSet<Modifier>,
+    key: String,
+}
+
+impl Accelerator {
+    fn parse(text: &str) -> Result<Self, AxonError> {
+        let invalid = |reason: &str| {
+            AxonError::invalid_input(format!("无效的快捷键 {}: {}", text.trim(), reason))
+        };
+        let parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let Some((key, modifiers)) = parts.split_last() else {
+            return Err(invalid("缺少按键"));
+        };
+
+        let mut parsed = BTreeSet::new();
+        for modifier in modifiers {
+            let modifier = match modifier.to_ascii_lowercase().as_str() {
+                "cmdorctrl" | "commandorcontrol" => Modifier::CmdOrCtrl,
+                "ctrl" | "control" => Modifier::Ctrl,
+                "alt" | "option" => Modifier::Alt,
+                "shift" => Modifier::Shift,
+                "super" | "cmd" | "command" | "meta" => Modifier::Super,
+                "" => return Err(invalid("格式错误")),
+                _ => return Err(invalid(&format!("未知的修饰键 {}", modifier))),
+            };
+            if !parsed.insert(modifier) {
+                return Err(invalid("修饰键重复"));
+            }
+        }
+
+        let key = normalize_key(key).ok_or_else(|| invalid(&format!("不支持的按键 {}", key)))?;
+        Ok(Self {
+            modifiers: parsed,
+            key,
+        })
+    }
+
+    /// 用于比较的形式（`CmdOrCtrl` 换成当前平台的修饰键）
+    fn resolved(&self) -> Self {
+        Self {
+            modifiers: self.modifiers.iter().map(|m| m.resolve()).collect(),
+            key: self.key.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{}+", modifier.name())?;
+        }
+        f.write_str(&self.key)
+    }
+}
+
+fn normalize_key(key: &str) -> Option<String> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return (c.is_ascii_alphanumeric() || SYMBOL_KEYS.contains(c))
+            .then(|| c.to_ascii_uppercase().to_string());
+    }
+    let lower = key.to_ascii_lowercase();
+    let alias = match lower.as_str() {
+        "esc" => "escape",
+        "return" => "enter",
+        "del" => "delete",
+        "arrowup" => "up",
+        "arrowdown" => "down",
+        "arrowleft" => "left",
+        "arrowright" => "right",
+        other => other,
+    };
+    if let Some(named) = NAMED_KEYS.iter().find(|k| k.eq_ignore_ascii_case(alias)) {
+        return Some(named.to_string());
+    }
+    let number: u8 = alias.strip_prefix('f')?.parse().ok()?;
+    (1..=24).contains(&number).then(|| format!("F{}", number))
+}
+
+/// 规范化快捷键写法，`scope` 为全局时要求带修饰键
+pub fn normalize_accelerator(text: &str, scope: KeymapScope) -> Result<String, AxonError> {
+    let accelerator = Accelerator::parse(text)?;
+    let has_modifier = accelerator.modifiers.iter().any(|m| *m != Modifier::Shift);
+    if scope == KeymapScope::Global && !has_modifier {
+        return Err(AxonError::invalid_input(format!(
+            "全局快捷键必须包含 Ctrl / Alt / Cmd 等修饰键: {}",
+            text.trim()
+        )));
+    }
+    Ok(accelerator.to_string())
+}
+
+/// 操作的作用范围，未知操作返回空
+pub fn action_scope(action: &str) -> Option<KeymapScope> {
+    if action == ACTION_SUMMON || action == ACTION_QUICK_PROMPT {
+        return Some(KeymapScope::Global);
+    }
+    let is_snippet = action
+        .strip_prefix(SNIPPET_ACTION_PREFIX)
+        .is_some_and(|id| !id.is_empty());
+    (is_snippet || APP_ACTIONS.iter().any(|a| a.id == action)).then_some(KeymapScope::App)
+}
+
+/// 合并默认值、应用内修改和全局快捷键设置，得到完整映射
+pub fn resolve(
+    overrides: &BTreeMap<String, Option<String>>,
+    hotkeys: &HotkeySettings,
+) -> Vec<KeymapEntry> {
+    let defaults = HotkeySettings::default();
+    let canonical =
+        |text: &str| Accelerator::parse(text).map_or_else(|_| text.to_string(), |a| a.to_string());
+    let mut entries = vec![
+        KeymapEntry {
+            action: ACTION_SUMMON.to_string(),
+            description: "唤起主窗口".to_string(),
+            scope: KeymapScope::Global,
+            accelerator: hotkeys.summon.as_deref().map(canonical),
+            default_accelerator: defaults.summon.as_deref().map(canonical),
+        },
+        KeymapEntry {
+            action: ACTION_QUICK_PROMPT.to_string(),
+            description: "快速提问".to_string(),
+            scope: KeymapScope::Global,
+            accelerator: hotkeys.quick_prompt.as_deref().map(canonical),
+            default_accelerator: defaults.quick_prompt.as_deref().map(canonical),
+        },
+    ];
+    for action in APP_ACTIONS {
+        let accelerator = match overrides.get(action.id) {
+            Some(custom) => custom.clone(),
+            None => action.default.map(str::to_string),
+        };
+        entries.push(KeymapEntry {
+            action: action.id.to_string(),
+            description: action.description.to_string(),
+            scope: KeymapScope::App,
+            accelerator,
+            default_accelerator: action.default.map(str::to_string),
+        });
+    }
+    for (action, accelerator) in overrides {
+        if action.starts_with(SNIPPET_ACTION_PREFIX) && accelerator.is_some() {
+            entries.push(KeymapEntry {
+                action: action.clone(),
+                description: "插入提示词片段".to_string(),
+                scope: KeymapScope::App,
+                accelerator: accelerator.clone(),
+                default_accelerator: None,
+            });
+        }
+    }
+    entries
+}
+
+/// 检查 `action` 使用 `accelerator` 是否与其它操作冲突
+pub fn check_conflict(
+    entries: &[KeymapEntry],
+    action: &str,
+    accelerator: &str,
+) -> Result<(), AxonError> {
+    let target = Accelerator::parse(accelerator)?.resolved();
+    let conflict = entries.iter().find(|entry| {
+        entry.action != action
+            && entry
+                .accelerator
+                .as_deref()
+                .and_then(|a| Accelerator::parse(a).ok())
+                .is_some_and(|a| a.resolved() == target)
+    });
+    match conflict {
+        Some(entry) => Err(AxonError::invalid_input(format!(
+            "快捷键 {} 已用于「{}」（{}）",
+            accelerator, entry.description, entry.action
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// 内置应用内操作的默认快捷键
+pub fn default_accelerator(action: &str) -> Option<&'static str> {
+    APP_ACTIONS
+        .iter()
+        .find(|a| a.id == action)
+        .and_then(|a| a.default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_accelerators() {
+        assert_eq!(
+            normalize_accelerator("shift+commandorcontrol+k", KeymapScope::App).unwrap(),
+            "CmdOrCtrl+Shift+K"
+        );
+        assert_eq!(
+            normalize_accelerator("Alt+esc", KeymapScope::App).unwrap(),
+            "Alt+Escape"
+        );
+        assert_eq!(
+            normalize_accelerator("ctrl+f12", KeymapScope::App).unwrap(),
+            "Ctrl+F12"
+        );
+        assert!(normalize_accelerator("Ctrl+Hyper+K", KeymapScope::App).is_err());
+        assert!(normalize_accelerator("Ctrl+", KeymapScope::App).is_err());
+        assert!(normalize_accelerator("Shift+K", KeymapScope::Global).is_err());
+    }
+
+    #[test]
+    fn detects_conflicts_across_scopes() {
+        let mut overrides = BTreeMap::new();
+        overrides.insert("view.toggleTerminal".to_string(), None);
+        overrides.insert(
+            "snippet.review".to_string(),
+            Some("CmdOrCtrl+Shift+R".to_string()),
+        );
+        let entries = resolve(&overrides, &HotkeySettings::default());
+        let terminal = entries
+            .iter()
+            .find(|e| e.action == "view.toggleTerminal")
+            .unwrap();
+        assert_eq!(terminal.accelerator, None);
+        assert!(entries.iter().any(|e| e.action == "snippet.review"));
+
+        // 与内置操作、全局快捷键和片段冲突
+        assert!(check_conflict(&entries, "project.open", "CmdOrCtrl+N").is_err());
+        assert!(check_conflict(&entries, "session.new", "CmdOrCtrl+Shift+Space").is_err());
+        assert!(check_conflict(&entries, "session.new", "Shift+CmdOrCtrl+R").is_err());
+        // 同一操作重新绑定原快捷键不算冲突
+        assert!(check_conflict(&entries, "session.new", "CmdOrCtrl+N").is_ok());
+        assert!(check_conflict(&entries, "session.new", "CmdOrCtrl+Shift+N").is_ok());
+    }
+}
@@ -3,29 +3,144 @@
 //! 这是 Axon Desktop 应用的主库入口。
 //! 负责初始化 Tauri 应用、设置窗口、管理 OpenCode 服务。
 
+mod activity;
+mod app_menu;
+mod app_update;
+mod audio;
+mod audit;
+mod bookmarks;
+mod bootstrap;
+mod branches;
+mod bridge_update;
 mod commands;
+mod consent;
+mod context_menu;
+mod context_pins;
+mod diff_cache;
+mod embeddings;
+mod error;
+mod file_index;
+mod highlight;
+mod hotkeys;
+mod i18n;
+mod jobs;
+mod keymap;
+mod logging;
+mod markdown;
+mod memory;
 mod models_registry;
+mod notebook;
+mod notifications;
+mod oauth;
 mod opencode;
 mod plugin_api;
+mod power;
+mod reviews;
+mod run_sandbox;
+mod scaffold;
 mod settings;
+mod snippets;
+mod spellcheck;
+mod startup;
 mod state;
+mod stats;
+mod team_sync;
+mod tools;
+mod tray;
+mod usage;
 mod utils;
+mod webhooks;
+mod workflow;
+mod workspace_stats;
 
+use bootstrap::BootstrapStage;
 use commands::*;
 use state::AppState;
+use serde::Serialize;
+use tauri::Emitter;
 use tauri::Listener;
 use tauri::Manager;
 use tauri::window::Color;
 use tauri_plugin_window_state::StateFlags;
 use tracing::info;
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use tracing_subscriber::{fmt, prelude::*};
 
 /// 初始化日志系统
+///
+/// 同时输出到 stderr 和日志文件，并安装崩溃报告 hook
 fn init_logging() {
     tracing_subscriber::registry()
+        .with(logging::filter_layer())
         .with(fmt::layer())
-        .with(EnvFilter::from_default_env().add_directive("axon_desktop=debug".parse().unwrap()))
+        .with(
+            fmt::layer()
+                .with_ansi(false)
+                .with_writer(|| logging::LogFileWriter),
+        )
         .init();
+    logging::install_panic_hook();
+}
+
+/// 等待前端发送 app-ready 的最长时间
+const FRONTEND_READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// 拖放文件夹打开项目事件
+const EVENT_PROJECT_OPENED: &str = "project:opened";
+
+/// 通过拖放打开的项目
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProjectOpened {
+    /// 项目目录
+    path: String,
+    /// 本地服务正在运行，需要重启才能使用新的工作目录
+    restart_recommended: bool,
+}
+
+/// 处理拖放到主窗口的路径：取第一个目录设为项目目录并通知前端
+///
+/// 是否重启 OpenCode 服务由前端确认后调用 restart_service
+fn handle_folder_drop(window: &tauri::Window, paths: &[std::path::PathBuf]) {
+    let Some(dir) = paths.iter().find(|p| p.is_dir()) else {
+        info!("拖放的内容不包含文件夹，忽略");
+        return;
+    };
+    // 拖放得到的是绝对路径，这里只确认目录可读
+    if let Err(e) = std::fs::read_dir(dir) {
+        tracing::warn!("无法读取拖放的文件夹 {:?}: {}", dir, e);
+        return;
+    }
+    let path = dir.to_string_lossy().to_string();
+
+    let state: tauri::State<'_, AppState> = window.state();
+    let audit_args = serde_json::json!({ "path": &path });
+    let result = state.audit.track_sync("open_dropped_project", audit_args, || {
+        state
+            .settings
+            .set_project_directory(Some(path.clone()))
+            .map_err(error::AxonError::from)
+    });
+    if let Err(e) = result {
+        tracing::error!("设置拖放的项目目录失败: {}", e);
+        return;
+    }
+    state.file_index.set_root(Some(dir.to_path_buf()));
+
+    let restart_recommended = matches!(
+        state.opencode.get_config().mode,
+        opencode::ServiceMode::Local
+    ) && matches!(
+        state.opencode.get_status(),
+        opencode::ServiceStatus::Running { .. }
+    );
+    info!("通过拖放打开项目: {}", path);
+    let payload = ProjectOpened {
+        path,
+        restart_recommended,
+    };
+    if let Err(e) = window.emit(EVENT_PROJECT_OPENED, &payload) {
+        tracing::warn!("发送项目打开事件失败: {}", e);
+    }
 }
 
 /// 获取 WebView2 优化参数（仅 Windows）
@@ -56,7 +171,10 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(hotkeys::plugin())
         .plugin(
             tauri_plugin_window_state::Builder::new()
                 .with_state_flags(
@@ -78,7 +196,14 @@ pub fn run() {
             start_service,
             stop_service,
             restart_service,
+            switch_project,
             get_service_endpoint,
+            list_service_instances,
+            start_service_instance,
+            stop_service_instance,
+            get_service_extra_env,
+            set_service_extra_env,
+            get_service_environment,
             // 版本管理命令
             get_version_info,
             check_for_update,
@@ -86,15 +211,37 @@ pub fn run() {
             // 应用更新命令
             check_app_update,
             install_app_update,
+            stage_app_update,
+            cancel_app_update,
+            get_staged_app_update,
+            discard_staged_app_update,
+            get_update_channel,
+            set_update_channel,
             get_app_version,
             // 应用设置命令
             get_app_settings,
             set_app_settings,
             set_auto_update,
+            set_minimize_to_tray,
+            get_settings_encryption,
+            enable_settings_encryption,
+            disable_settings_encryption,
+            reset_settings_encryption,
+            get_language,
+            set_language,
             set_custom_opencode_path,
             set_project_directory,
             get_project_directory,
             get_opencode_config_path,
+            // 终端 Shell 配置命令
+            list_shell_profiles,
+            save_shell_profile,
+            delete_shell_profile,
+            set_default_shell_profile,
+            resolve_shell_profile,
+            set_terminal_persistence,
+            save_terminal_sessions,
+            restore_terminals,
             // Provider 管理命令
             add_user_provider,
             update_user_provider,
@@ -103,6 +250,108 @@ pub fn run() {
             remove_provider_auth,
             get_provider_auth_status,
             get_all_provider_auth_status,
+            get_model_filters,
+            set_model_filters,
+            filter_allowed_models,
+            // 外部 OpenCode 配置导入命令
+            scan_external_opencode_config,
+            import_external_opencode_config,
+            // OAuth 授权命令
+            start_provider_oauth,
+            cancel_provider_oauth,
+            // 用量统计命令
+            get_usage_summary,
+            get_model_performance,
+            // Agent 活动命令
+            get_session_activity,
+            // 审计日志命令
+            read_audit_log,
+            // 日志命令
+            get_recent_logs,
+            open_logs_directory,
+            get_log_level,
+            set_log_level,
+            // 诊断命令
+            get_process_stats,
+            create_diagnostics_bundle,
+            get_startup_timings,
+            get_bootstrap_status,
+            // 操作确认命令
+            list_consent_requests,
+            respond_consent_request,
+            list_consent_rules,
+            remove_consent_rule,
+            // 记忆命令
+            list_memory_entries,
+            list_memory_scopes,
+            set_memory_entry,
+            delete_memory_entry,
+            clear_memory,
+            // OpenCode 插件管理命令
+            list_opencode_plugins,
+            add_opencode_plugin,
+            remove_opencode_plugin,
+            // Bridge 插件更新命令
+            check_bridge_update,
+            update_bridge_plugin,
+            get_bridge_update_repo,
+            set_bridge_update_repo,
+            // 插件 API 命令
+            get_plugin_api_metrics,
+            get_plugin_api_rate_limit,
+            set_plugin_api_rate_limit,
+            get_session_agent_overrides,
+            set_session_agent_override,
+            clear_session_agent_overrides,
+            // 自定义工具命令
+            list_tools,
+            get_tool,
+            save_tool,
+            delete_tool,
+            test_tool,
+            list_tool_secrets,
+            set_tool_secret,
+            delete_tool_secret,
+            // 运行沙箱命令
+            create_sandbox,
+            list_sandboxes,
+            get_sandbox_diff,
+            promote_sandbox,
+            discard_sandbox,
+            // 团队配置同步命令
+            get_team_sync_settings,
+            set_team_sync_settings,
+            get_team_sync_status,
+            sync_team_config,
+            // 项目模板命令
+            list_project_templates,
+            delete_project_template,
+            create_project_from_template,
+            // 空闲与电源命令
+            get_power_state,
+            get_power_settings,
+            set_power_settings,
+            // 系统通知命令
+            notify,
+            get_notification_settings,
+            set_notification_settings,
+            // Webhook 通知命令
+            get_webhooks,
+            set_webhooks,
+            test_webhook,
+            report_workflow_finished,
+            // 全局快捷键命令
+            get_hotkeys,
+            set_hotkeys,
+            show_quick_prompt,
+            hide_quick_prompt,
+            submit_quick_prompt,
+            // 快捷键映射命令
+            get_keymap,
+            set_binding,
+            reset_keymap,
+            // 应用菜单命令
+            set_menu_recent_projects,
             // 窗口命令
             window_minimize,
             window_maximize,
@@ -116,20 +365,130 @@ pub fn run() {
             read_file_content,
             read_file_binary,
             write_file_content,
+            detect_file_encoding,
+            convert_file,
             delete_path,
             rename_path,
             copy_path,
             move_path,
+            stat_path,
+            copy_paths_batch,
+            delete_paths_batch,
+            analyze_directory_size,
+            show_file_context_menu,
+            reveal_in_file_manager,
+            replace_in_files,
+            apply_replace_in_files,
+            fuzzy_find,
+            get_file_index_status,
+            // 语义搜索命令
+            get_embedding_settings,
+            set_embedding_settings,
+            build_embedding_index,
+            get_embedding_index_status,
+            semantic_search,
+            // 后台任务命令
+            cancel_job,
+            list_jobs,
+            // 命令执行
+            run_command,
+            revoke_command,
+            list_allowed_commands,
+            // 路径沙箱命令
+            get_path_sandbox,
+            set_path_sandbox_enabled,
+            grant_path_access,
+            revoke_path_access,
+            // 归档命令
+            create_archive,
+            extract_archive,
+            // 剪贴板命令
+            clipboard_read_text,
+            clipboard_write_text,
+            clipboard_read_image,
+            // 截图命令
+            capture_screenshot,
+            list_screenshot_windows,
+            // 录音命令
+            list_audio_input_devices,
+            start_recording,
+            stop_recording,
+            is_recording,
+            // 图片命令
+            get_image_info,
+            generate_thumbnail,
+            // 代码大纲命令
+            get_code_outline,
+            find_code_symbol,
+            // 项目信息命令
+            detect_project_info,
+            build_project_context,
+            // Token 计数命令
+            count_tokens,
+            count_tokens_batch,
             // Diff 计算命令
             compute_diff,
             compute_unified_diff,
             compute_diff_stats,
+            apply_selected_hunks,
+            compute_structural_diff,
+            compute_binary_diff_summary,
+            parse_unified_diff,
             texts_are_equal,
             // 工作区布局命令
             save_workspace_layout,
             load_workspace_layout,
             delete_workspace_layout,
             list_workspace_layouts,
+            save_project_window_state,
+            restore_project_window_state,
+            touch_recent_file,
+            list_recent_files,
+            // 书签命令
+            list_bookmarks,
+            add_bookmark,
+            remove_bookmark,
+            // 会话分支命令
+            list_branches,
+            create_branch,
+            delete_branch,
+            // 提示词片段命令
+            list_snippets,
+            save_snippet,
+            delete_snippet,
+            expand_snippet,
+            // 拼写检查命令
+            check_spelling,
+            add_to_dictionary,
+            list_spelling_languages,
+            // 上下文固定文件命令
+            list_context_pins,
+            pin_for_context,
+            unpin,
+            // 忽略规则命令
+            get_ignore_rules,
+            set_ignore_rules,
+            check_ignored_paths,
+            // 项目概览统计命令
+            get_workspace_stats,
+            // Markdown 渲染命令
+            render_markdown,
+            // 语法高亮命令
+            highlight_code,
+            highlight_file,
+            list_highlight_themes,
+            // Notebook 命令
+            read_notebook,
+            write_notebook,
+            // PDF 命令
+            extract_pdf_text,
+            // 审查会话命令
+            create_review,
+            list_reviews,
+            get_review,
+            set_review_decision,
+            finalize_review,
+            delete_review,
             // Agent 配置命令
             get_agents_directory,
             list_agents,
@@ -144,6 +503,12 @@ pub fn run() {
             save_workflow,
             delete_workflow,
             save_workflows_batch,
+            auto_layout_workflow,
+            plan_workflow_execution,
+            list_workflow_templates,
+            instantiate_workflow_template,
+            export_workflow_bundle,
+            import_workflow_bundle,
             // 编排组配置命令
             get_orchestrations_directory,
             list_orchestrations,
@@ -158,10 +523,17 @@ pub fn run() {
             get_models_registry_cache_info,
             refresh_models_registry,
             trigger_background_refresh,
+            import_models_registry,
+            list_user_models,
+            save_user_model,
+            delete_user_model,
         ])
         .setup(|app| {
             let setup_start = std::time::Instant::now();
             let handle = app.handle().clone();
+            let startup = std::sync::Arc::clone(&handle.state::<AppState>().startup);
+            let bootstrap = std::sync::Arc::clone(&handle.state::<AppState>().bootstrap);
+            bootstrap.set_app_handle(handle.clone());
 
             // 0. 创建优化的主窗口（使用 additional_browser_args 加速 WebView）
             let webview_args = get_webview_args();
@@ -173,24 +545,31 @@ pub fn run() {
             
             // 创建窗口时先隐藏，等 WebView 加载完成后再显示
             // 这样可以避免用户看到白屏闪烁
-            let main_window = tauri::WebviewWindowBuilder::new(
-                app,
-                "main",
-                tauri::WebviewUrl::App("index.html".into()),
-            )
-            .title("Axon")
-            .inner_size(1200.0, 800.0)
-            .min_inner_size(800.0, 600.0)
-            .decorations(false)
-            .visible(false)
-            .background_color(bg_color)
-            .additional_browser_args(webview_args)
-            .build()?;
+            let main_window = startup.measure("window", || {
+                tauri::WebviewWindowBuilder::new(
+                    app,
+                    "main",
+                    tauri::WebviewUrl::App("index.html".into()),
+                )
+                .title("Axon")
+                .inner_size(1200.0, 800.0)
+                .min_inner_size(800.0, 600.0)
+                .decorations(false)
+                .visible(false)
+                .background_color(bg_color)
+                .additional_browser_args(webview_args)
+                .build()
+            })?;
+
+            // window-state 插件恢复的位置可能在已断开的显示器上
+            utils::window_bounds::ensure_visible(&main_window);
 
             // 监听前端发送的 "app-ready" 事件，收到后显示窗口
             let window_for_event = main_window.clone();
+            let bootstrap_for_event = std::sync::Arc::clone(&bootstrap);
             main_window.listen("app-ready", move |_| {
                 info!("收到前端 app-ready 事件，显示窗口");
+                bootstrap_for_event.ready(BootstrapStage::Frontend);
                 if let Err(e) = window_for_event.show() {
                     tracing::error!("显示窗口失败: {}", e);
                 }
@@ -198,38 +577,108 @@ pub fn run() {
                 let _ = window_for_event.set_focus();
             });
 
-            // 设置超时保护：如果 3 秒内前端没有发送 ready 事件，强制显示窗口
-            // 避免因前端错误导致窗口永远不显示
-            let window_for_timeout = main_window.clone();
+            // 前端长时间未就绪时记为失败，并用原生对话框让用户选择显示窗口或退出
+            // （不直接显示可能已损坏的页面，也不让窗口一直隐藏）
+            let watchdog_handle = handle.clone();
             std::thread::spawn(move || {
-                std::thread::sleep(std::time::Duration::from_secs(3));
-                // 检查窗口是否仍然隐藏
-                if let Ok(visible) = window_for_timeout.is_visible() {
-                    if !visible {
-                        info!("超时保护：强制显示窗口");
-                        let _ = window_for_timeout.show();
-                        let _ = window_for_timeout.set_focus();
-                    }
+                std::thread::sleep(FRONTEND_READY_TIMEOUT);
+                let state: tauri::State<'_, AppState> = watchdog_handle.state();
+                if state.bootstrap.is_pending(BootstrapStage::Frontend) {
+                    let message =
+                        format!("{} 秒内未收到前端就绪事件", FRONTEND_READY_TIMEOUT.as_secs());
+                    state.bootstrap.fail(BootstrapStage::Frontend, message.clone());
+                    bootstrap::show_frontend_failure(&watchdog_handle, &message);
                 }
             });
 
+            // 文件右键菜单的选择结果和应用菜单的操作通过全局菜单事件返回
+            app.on_menu_event(|app, event| {
+                let state: tauri::State<'_, AppState> = app.state();
+                state.context_menu.handle_menu_event(&event);
+                state.app_menu.handle_menu_event(app, &event);
+            });
+
+            // 系统托盘（失败时不影响主窗口）
+            if let Err(e) = tray::init(&handle) {
+                tracing::warn!("创建系统托盘失败，关闭主窗口时将直接退出: {}", e);
+            }
+
             // 1. 首先初始化应用数据目录（其他操作依赖此路径）
             //    使用 Tauri API 获取正确的应用目录，与 identifier 一致
-            utils::paths::init_app_data_dir(&handle)
-                .map_err(|e| Box::new(std::io::Error::other(e)))?;
-
-            if let Err(e) = utils::plugin_installer::install_bundled_plugins(&handle) {
-                tracing::warn!("插件安装失败: {}，继续启动应用", e);
+            if let Err(e) = startup.measure("app_data_dir", || utils::paths::init_app_data_dir(&handle)) {
+                bootstrap.fail(BootstrapStage::Paths, e.clone());
+                return Err(Box::new(std::io::Error::other(e)));
             }
+            bootstrap.ready(BootstrapStage::Paths);
+
+            startup.measure("plugin_install", || {
+                if let Err(e) = utils::plugin_installer::install_bundled_plugins(&handle) {
+                    tracing::warn!("插件安装失败: {}，继续启动应用", e);
+                }
+            });
 
             // 2. 设置 app_handle 用于事件发送（必须在异步操作之前）
             {
                 let state: tauri::State<'_, AppState> = handle.state();
-                state.opencode.set_app_handle(handle.clone());
+                startup.measure("settings", || {
+                    state.settings.initialize();
+                    i18n::set_language(state.settings.get_language());
+                    let project_dir = state.settings.get_project_directory();
+                    state.file_index.set_root(project_dir.map(std::path::PathBuf::from));
+                    if let Err(e) = logging::apply(&state.settings.get_log_level()) {
+                        tracing::warn!("应用日志级别失败: {}", e);
+                    }
+                    if let Err(e) = state.hotkeys.apply(&handle, &state.settings.get_hotkeys()) {
+                        tracing::warn!("注册全局快捷键失败: {}", e);
+                    }
+                });
+
+                state.webhooks.initialize(&handle);
+                state.notifications.initialize(handle.clone());
+                state.services.set_app_handle(handle.clone());
+                state.opencode.watch_settings();
+                state.settings.forward_changes(handle.clone());
+                state.config_watcher.start(handle.clone());
+                state
+                    .plugin_api
+                    .read()
+                    .state()
+                    .rate_limiter
+                    .follow_settings(std::sync::Arc::clone(&state.settings));
+                state
+                    .opencode
+                    .set_plugin_api(state.plugin_api.read().state().clone());
                 info!("OpenCode 服务 app_handle 已设置");
 
-                state.models_registry.initialize();
+                startup.measure("models_registry", || {
+                    let resource_dir = handle.path().resource_dir().ok();
+                    state.models_registry.initialize(resource_dir.as_deref());
+                    state
+                        .models_registry
+                        .start_scheduler(handle.clone(), std::sync::Arc::clone(&state.power));
+                });
                 info!("模型注册表缓存已加载");
+                state
+                    .spellcheck
+                    .initialize(handle.path().resource_dir().ok().as_deref());
+
+                startup.measure("state_load", || {
+                    state.usage.initialize();
+                    state.consent.initialize(handle.clone());
+                    state.activity.initialize(handle.clone());
+                });
+
+                // 菜单项的快捷键来自设置中的快捷键映射，需在设置加载后安装
+                if let Err(e) = state.app_menu.install(&handle) {
+                    tracing::warn!("安装应用菜单失败: {}", e);
+                }
+
+                state.oauth.start_refresh_loop(handle.clone());
+                state.power.start(handle.clone());
+                state
+                    .team_sync
+                    .start(handle.clone(), std::sync::Arc::clone(&state.power));
+                state.stats.start(handle.clone());
             }
 
             info!("Setup 同步阶段完成，耗时: {:?}", setup_start.elapsed());
@@ -244,7 +693,8 @@ pub fn run() {
                 // 启动 Plugin API 服务器
                 let plugin_api = std::sync::Arc::clone(&state.plugin_api);
                 let opencode = std::sync::Arc::clone(&state.opencode);
-                let _ = tokio::task::spawn_blocking(move || {
+                let plugin_api_bootstrap = std::sync::Arc::clone(&bootstrap);
+                let _ = startup.measure_async("plugin_api", tokio::task::spawn_blocking(move || {
                     let rt = tokio::runtime::Handle::current();
                     let mut server = plugin_api.write();
                     rt.block_on(async {
@@ -252,36 +702,65 @@ pub fn run() {
                             Ok(port) => {
                                 info!("Plugin API 服务器启动成功，端口: {}", port);
                                 opencode.set_plugin_api_port(port);
+                                plugin_api_bootstrap.ready(BootstrapStage::PluginApi);
+                            }
+                            Err(e) => {
+                                tracing::error!("Plugin API 服务器启动失败: {}", e);
+                                plugin_api_bootstrap.fail(BootstrapStage::PluginApi, e.to_string());
                             }
-                            Err(e) => tracing::error!("Plugin API 服务器启动失败: {}", e),
                         }
                     });
-                }).await;
+                })).await;
 
                 // 初始化 OpenCode 服务（如需要会下载二进制）
-                match state.opencode.initialize().await {
+                match startup.measure_async("opencode_init", state.opencode.initialize()).await {
                     Ok(()) => {
                         info!("OpenCode 服务初始化成功");
                         // 如果配置了自动启动，则启动服务
                         let config = state.opencode.get_config();
                         if config.auto_start {
                             info!("自动启动 OpenCode 服务...");
-                            if let Err(e) = state.opencode.start().await {
-                                tracing::error!("自动启动 opencode 服务失败: {}", e);
+                            match startup.measure_async("opencode_start", state.opencode.start()).await {
+                                Ok(()) => bootstrap.ready(BootstrapStage::Opencode),
+                                Err(e) => {
+                                    tracing::error!("自动启动 opencode 服务失败: {}", e);
+                                    bootstrap.fail(BootstrapStage::Opencode, e.to_string());
+                                }
                             }
+                        } else {
+                            bootstrap.ready(BootstrapStage::Opencode);
                         }
                     }
                     Err(e) => {
                         tracing::error!("初始化 opencode 服务失败: {}", e);
+                        bootstrap.fail(BootstrapStage::Opencode, e.to_string());
                     }
                 }
 
-                state.models_registry.refresh_in_background().await;
+                startup.finish(&init_handle);
             });
 
             Ok(())
         })
         .on_window_event(|window, event| {
+            // 开启最小化到托盘时，关闭主窗口只隐藏窗口，服务继续在后台运行；
+            // 托盘创建失败时照常关闭，否则窗口隐藏后无法再找回
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                let state: tauri::State<'_, AppState> = window.state();
+                if window.label() == "main"
+                    && state.settings.get_minimize_to_tray()
+                    && tray::is_available(window.app_handle())
+                {
+                    info!("主窗口隐藏到托盘");
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                if window.label() == "main" {
+                    handle_folder_drop(window, paths);
+                }
+            }
             if let tauri::WindowEvent::Destroyed = event {
                 if window.label() == "main" {
                     info!("主窗口关闭，停止 Plugin API 服务器");
@@ -291,6 +770,13 @@ pub fn run() {
                 }
             }
         })
-        .run(tauri::generate_context!())
-        .expect("运行 Tauri 应用时发生错误");
+        .build(tauri::generate_context!())
+        .expect("运行 Tauri 应用时发生错误")
+        .run(|app, event| {
+            // 退出时安装已下载的更新（"立即下载、退出时安装"模式）
+            if let tauri::RunEvent::Exit = event {
+                let state: tauri::State<'_, AppState> = app.state();
+                state.app_update.install_staged();
+            }
+        });
 }
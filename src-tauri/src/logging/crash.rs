@@ -0,0 +1,116 @@
+//! 崩溃报告
+//!
+//! panic 时写入 `<app_data>/logs/crash-<时间>.log`，应用数据目录未初始化时写入系统临时目录。
+//! 报告包含版本、平台、panic 信息、调用栈和最近的日志，可直接附在问题反馈中。
+
+use crate::logging::{get_logs_dir, read_recent_lines};
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+
+/// 崩溃报告文件名前缀
+const CRASH_FILE_PREFIX: &str = "crash-";
+
+/// 保留的崩溃报告数量
+const MAX_CRASH_REPORTS: usize = 10;
+
+/// 崩溃报告中附带的最近日志行数
+const RECENT_LOG_LINES: usize = 200;
+
+/// 安装 panic hook（保留默认 hook 的 stderr 输出）
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = panic_message(info);
+        tracing::error!("程序崩溃: {}", message);
+
+        match write_crash_report(info, &message) {
+            Ok(path) => eprintln!("崩溃报告已写入: {}", path.display()),
+            Err(e) => eprintln!("写入崩溃报告失败: {}", e),
+        }
+
+        default_hook(info);
+    }));
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "未知 panic".to_string())
+}
+
+fn write_crash_report(info: &PanicHookInfo<'_>, message: &str) -> std::io::Result<PathBuf> {
+    let now = chrono::Local::now();
+    let thread = std::thread::current();
+
+    let mut report = String::new();
+    let _ = writeln!(report, "Axon 崩溃报告");
+    let _ = writeln!(report, "时间: {}", now.to_rfc3339());
+    let _ = writeln!(report, "版本: {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(
+        report,
+        "系统: {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let _ = writeln!(report, "线程: {}", thread.name().unwrap_or("<unnamed>"));
+    if let Some(location) = info.location() {
+        let _ = writeln!(report, "位置: {}", location);
+    }
+    let _ = writeln!(report, "信息: {}", message);
+    let _ = writeln!(report, "\n调用栈:\n{}", Backtrace::force_capture());
+
+    let _ = writeln!(report, "\n最近日志:");
+    for line in read_recent_lines(RECENT_LOG_LINES).unwrap_or_default() {
+        let _ = writeln!(report, "{}", line);
+    }
+
+    let dir = get_logs_dir().unwrap_or_else(std::env::temp_dir);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "{}{}.log",
+        CRASH_FILE_PREFIX,
+        now.format("%Y%m%d-%H%M%S")
+    ));
+    std::fs::write(&path, report)?;
+
+    prune_crash_reports(&dir);
+    Ok(path)
+}
+
+/// 日志目录中的崩溃报告（按时间倒序）
+pub fn list_crash_reports() -> Vec<PathBuf> {
+    get_logs_dir()
+        .map(|dir| crash_reports_in(&dir))
+        .unwrap_or_default()
+}
+
+/// 目录中的崩溃报告（文件名包含时间，按时间倒序）
+fn crash_reports_in(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(CRASH_FILE_PREFIX))
+        })
+        .collect();
+    reports.sort();
+    reports.reverse();
+    reports
+}
+
+/// 只保留最新的若干份崩溃报告
+fn prune_crash_reports(dir: &Path) {
+    for path in crash_reports_in(dir).iter().skip(MAX_CRASH_REPORTS) {
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,84 @@
+//! 运行时日志级别
+//!
+//! EnvFilter 通过 reload 层安装，调整级别无需重启。
+//! `RUST_LOG` 中的指令作为基础，设置中的级别覆盖对应 target。
+
+use crate::error::AxonError;
+use crate::opencode::LogLevelSettings;
+use std::sync::OnceLock;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// 支持的日志级别
+pub const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+/// 未指定 target 时作用的范围
+const DEFAULT_TARGET: &str = "axon_desktop";
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// 创建可重载的过滤层（初始化日志时调用一次）
+pub fn filter_layer() -> reload::Layer<EnvFilter, Registry> {
+    let filter = build_filter(&LogLevelSettings::default())
+        .unwrap_or_else(|_| EnvFilter::from_default_env());
+    let (layer, handle) = reload::Layer::new(filter);
+    let _ = FILTER_HANDLE.set(handle);
+    layer
+}
+
+/// 校验并规整日志级别设置
+pub fn normalize(settings: LogLevelSettings) -> Result<LogLevelSettings, AxonError> {
+    let level = settings.level.trim().to_lowercase();
+    if !LOG_LEVELS.contains(&level.as_str()) {
+        return Err(AxonError::invalid_input(format!(
+            "无效的日志级别: {}（可选: {}）",
+            settings.level,
+            LOG_LEVELS.join(", ")
+        )));
+    }
+
+    let mut targets = Vec::new();
+    for target in settings.targets {
+        let target = target.trim().to_string();
+        if target.is_empty() || targets.contains(&target) {
+            continue;
+        }
+        if target.contains(['=', ',', '[', ']', '{', '}']) || target.contains(char::is_whitespace) {
+            return Err(AxonError::invalid_input(format!(
+                "无效的日志 target: {}",
+                target
+            )));
+        }
+        targets.push(target);
+    }
+
+    Ok(LogLevelSettings { level, targets })
+}
+
+/// 应用日志级别
+pub fn apply(settings: &LogLevelSettings) -> Result<(), AxonError> {
+    let filter = build_filter(settings)?;
+    let handle = FILTER_HANDLE
+        .get()
+        .ok_or_else(|| AxonError::unavailable("日志系统未初始化"))?;
+    handle
+        .reload(filter)
+        .map_err(|e| AxonError::internal(format!("更新日志级别失败: {}", e)))
+}
+
+fn build_filter(settings: &LogLevelSettings) -> Result<EnvFilter, AxonError> {
+    let default_targets = [DEFAULT_TARGET.to_string()];
+    let targets = if settings.targets.is_empty() {
+        &default_targets[..]
+    } else {
+        &settings.targets[..]
+    };
+
+    let mut filter = EnvFilter::from_default_env();
+    for target in targets {
+        let directive = format!("{}={}", target, settings.level)
+            .parse()
+            .map_err(|e| AxonError::invalid_input(format!("无效的日志指令 {}: {}", target, e)))?;
+        filter = filter.add_directive(directive);
+    }
+    Ok(filter)
+}
@@ -0,0 +1,14 @@
+//! 日志文件与崩溃报告模块
+//!
+//! - tracing 输出同时写入 `<app_data>/logs/axon.log`，按大小轮转
+//! - 应用数据目录初始化前的日志先缓存在内存中，初始化后补写
+//! - 日志级别可在运行时调整，并持久化到设置
+//! - panic 时在日志目录写入 `crash-*.log` 崩溃报告（含调用栈和最近日志）
+
+mod crash;
+mod level;
+mod sink;
+
+pub use crash::{install_panic_hook, list_crash_reports};
+pub use level::*;
+pub use sink::*;
@@ -0,0 +1,140 @@
+//! 日志文件输出
+//!
+//! 作为 tracing 的 writer 使用。写入直接落盘（不做缓冲），
+//! 崩溃时无需额外 flush 就能读到最后的日志。
+
+use crate::error::AxonError;
+use crate::utils::paths::get_app_data_dir;
+use crate::utils::rotating_file::RotatingFile;
+use parking_lot::Mutex;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// 日志目录名
+pub const LOGS_DIR: &str = "logs";
+
+/// 日志文件名
+const LOG_FILE: &str = "axon.log";
+
+/// 单个日志文件大小上限（超过后轮转）
+const MAX_FILE_SIZE: u64 = 5 * 1024 * 1024;
+
+/// 保留的轮转文件数（不含当前文件）
+const MAX_ROTATED_FILES: usize = 5;
+
+/// 应用数据目录初始化前最多缓存的日志字节数
+const MAX_PENDING_BYTES: usize = 256 * 1024;
+
+/// 日志目录：`<app_data>/logs`
+pub fn get_logs_dir() -> Option<PathBuf> {
+    get_app_data_dir().map(|p| p.join(LOGS_DIR))
+}
+
+fn get_log_path() -> Option<PathBuf> {
+    get_logs_dir().map(|p| p.join(LOG_FILE))
+}
+
+fn get_log_file() -> Option<RotatingFile> {
+    get_log_path().map(|p| RotatingFile::new(p, MAX_ROTATED_FILES))
+}
+
+/// 文件输出状态（全局唯一）
+struct SinkState {
+    file: Option<File>,
+    size: u64,
+    /// 应用数据目录初始化前的日志
+    pending: Vec<u8>,
+}
+
+static SINK: Mutex<SinkState> = Mutex::new(SinkState {
+    file: None,
+    size: 0,
+    pending: Vec::new(),
+});
+
+impl SinkState {
+    fn write(&mut self, buf: &[u8]) {
+        if self.file.is_none() && !self.open() {
+            if self.pending.len() + buf.len() <= MAX_PENDING_BYTES {
+                self.pending.extend_from_slice(buf);
+            }
+            return;
+        }
+
+        if self.size + buf.len() as u64 > MAX_FILE_SIZE {
+            self.file = None;
+            if let Some(file) = get_log_file() {
+                let _ = file.rotate();
+            }
+            if !self.open() {
+                return;
+            }
+        }
+
+        if let Some(file) = self.file.as_mut() {
+            if file.write_all(buf).is_ok() {
+                self.size += buf.len() as u64;
+            }
+        }
+    }
+
+    /// 打开（或创建）当前日志文件，并补写缓存的日志
+    ///
+    /// 这里不能调用 tracing 宏，否则会递归写入
+    fn open(&mut self) -> bool {
+        let Some(path) = get_log_path() else {
+            return false;
+        };
+        if let Some(parent) = path.parent() {
+            if std::fs::create_dir_all(parent).is_err() {
+                return false;
+            }
+        }
+        let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+        else {
+            return false;
+        };
+
+        self.size = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if !self.pending.is_empty() {
+            let pending = std::mem::take(&mut self.pending);
+            if file.write_all(&pending).is_ok() {
+                self.size += pending.len() as u64;
+            }
+        }
+        self.file = Some(file);
+        true
+    }
+}
+
+/// tracing 日志文件 writer
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogFileWriter;
+
+impl Write for LogFileWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        SINK.lock().write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 读取最近的日志行（按时间正序），当前文件不足时继续读取轮转文件
+pub fn read_recent_lines(limit: usize) -> Result<Vec<String>, AxonError> {
+    let Some(file) = get_log_file() else {
+        return Ok(Vec::new());
+    };
+
+    let mut lines = file
+        .read_recent(limit, |line| Some(line.to_string()))
+        .map_err(|e| AxonError::io("读取日志文件失败", &e))?;
+    lines.reverse();
+    Ok(lines)
+}
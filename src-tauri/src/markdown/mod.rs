@@ -0,0 +1,181 @@
+//! Markdown 渲染
+//!
+//! 使用 comrak 把 Markdown 渲染为 HTML，再经 ammonia 清理（移除脚本、事件属性和
+//! 危险链接），前端可以直接插入结果。流式输出时前端每次用完整文本重新渲染，
+//! 因此整个流程只做一次解析和一次清理，不保留中间状态。
+//!
+//! 代码块通过 [`CodeHighlighter`] 接入语法高亮，未提供时按纯文本输出并带
+//! `language-xxx` 类名，由前端自行处理。
+
+use comrak::adapters::SyntaxHighlighterAdapter;
+use comrak::{markdown_to_html_with_plugins, Options, Plugins};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::OnceLock;
+
+/// 渲染选项
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MarkdownOptions {
+    /// 启用 GFM 扩展（表格、删除线、任务列表、自动链接）
+    pub gfm: bool,
+    /// 单个换行渲染为 `<br>`
+    pub hard_breaks: bool,
+    /// 保留 Markdown 中的原始 HTML（仍会经过清理）
+    pub allow_html: bool,
+    /// 代码块高亮主题，为空时不高亮
+    pub highlight_theme: Option<String>,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            gfm: true,
+            hard_breaks: false,
+            allow_html: false,
+            highlight_theme: None,
+        }
+    }
+}
+
+/// 代码块语法高亮
+pub trait CodeHighlighter: Send + Sync {
+    /// 返回高亮后的 HTML（不含外层 `<pre><code>`），不支持该语言时返回 None
+    fn highlight(&self, code: &str, language: &str) -> Option<String>;
+}
+
+/// 渲染 Markdown 并清理结果
+pub fn render(
+    text: &str,
+    options: &MarkdownOptions,
+    highlighter: Option<&dyn CodeHighlighter>,
+) -> String {
+    let mut comrak_options = Options::default();
+    comrak_options.extension.strikethrough = options.gfm;
+    comrak_options.extension.table = options.gfm;
+    comrak_options.extension.autolink = options.gfm;
+    comrak_options.extension.tasklist = options.gfm;
+    comrak_options.render.hardbreaks = options.hard_breaks;
+    comrak_options.render.unsafe_ = options.allow_html;
+
+    let adapter = CodeBlockAdapter { highlighter };
+    let mut plugins = Plugins::default();
+    plugins.render.codefence_syntax_highlighter = Some(&adapter);
+
+    let html = markdown_to_html_with_plugins(text, &comrak_options, &plugins);
+    sanitizer().clean(&html).to_string()
+}
+
+/// 清理规则：在 ammonia 默认白名单上放开代码块类名、高亮颜色和任务列表复选框
+fn sanitizer() -> &'static ammonia::Builder<'static> {
+    static SANITIZER: OnceLock<ammonia::Builder<'static>> = OnceLock::new();
+    SANITIZER.get_or_init(|| {
+        let mut builder = ammonia::Builder::default();
+        builder
+            .add_tags(["input"])
+            .add_tag_attributes("input", ["type", "checked", "disabled"])
+            .add_tag_attributes("pre", ["class"])
+            .add_tag_attributes("code", ["class"])
+            .add_tag_attributes("span", ["class", "style"])
+            .filter_style_properties(
+                ["color", "font-weight", "font-style", "text-decoration"].into(),
+            )
+            .add_tag_attributes("th", ["align"])
+            .add_tag_attributes("td", ["align"]);
+        builder
+    })
+}
+
+struct CodeBlockAdapter<'a> {
+    highlighter: Option<&'a dyn CodeHighlighter>,
+}
+
+impl SyntaxHighlighterAdapter for CodeBlockAdapter<'_> {
+    fn write_highlighted(
+        &self,
+        output: &mut dyn Write,
+        lang: Option<&str>,
+        code: &str,
+    ) -> io::Result<()> {
+        let highlighted = match (self.highlighter, lang) {
+            (Some(highlighter), Some(lang)) => highlighter.highlight(code, lang),
+            _ => None,
+        };
+        match highlighted {
+            Some(html) => output.write_all(html.as_bytes()),
+            None => comrak::html::escape(output, code.as_bytes()),
+        }
+    }
+
+    fn write_pre_tag(
+        &self,
+        output: &mut dyn Write,
+        attributes: HashMap<String, String>,
+    ) -> io::Result<()> {
+        comrak::html::write_opening_tag(output, "pre", attributes)
+    }
+
+    fn write_code_tag(
+        &self,
+        output: &mut dyn Write,
+        attributes: HashMap<String, String>,
+    ) -> io::Result<()> {
+        comrak::html::write_opening_tag(output, "code", attributes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Upper;
+
+    impl CodeHighlighter for Upper {
+        fn highlight(&self, code: &str, language: &str) -> Option<String> {
+            (language == "shout")
+                .then(|| format!("<span class=\"hl\">{}</span>", code.to_uppercase()))
+        }
+    }
+
+    #[test]
+    fn renders_gfm_and_code_blocks() {
+        let html = render(
+            "| a |\n|---|\n| ~~b~~ |\n\n- [x] done\n\n```rust\nfn main() {}\n```\n",
+            &MarkdownOptions::default(),
+            None,
+        );
+        assert!(html.contains("<table>"));
+        assert!(html.contains("<del>b</del>"));
+        assert!(html.contains("type=\"checkbox\""));
+        assert!(html.contains("<code class=\"language-rust\">fn main() {}"));
+    }
+
+    #[test]
+    fn sanitizes_raw_html() {
+        let text = "<script>alert(1)</script>\n\n<img src=x onerror=alert(1)>\n\n[link](javascript:alert(1))\n";
+        let escaped = render(text, &MarkdownOptions::default(), None);
+        assert!(!escaped.contains("<script"));
+
+        let options = MarkdownOptions {
+            allow_html: true,
+            ..Default::default()
+        };
+        let html = render(text, &options, None);
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains("<img src=\"x\">"));
+    }
+
+    #[test]
+    fn uses_highlighter_when_available() {
+        let html = render(
+            "```shout\nhi\n```\n\n```text\nhi\n```\n",
+            &MarkdownOptions::default(),
+            Some(&Upper),
+        );
+        assert!(html.contains("<span class=\"hl\">HI\n</span>"));
+        assert!(html.contains("<code class=\"language-text\">hi\n</code>"));
+    }
+}
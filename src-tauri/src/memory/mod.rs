@@ -0,0 +1,364 @@
+//! Agent 记忆存储
+//!
+//! 按 Agent 和项目划分作用域的键值存储，Agent 可通过 Plugin API 在会话之间保存笔记。
+//! 每个作用域一个 JSON 文件（`<app_data_dir>/memory/<作用域哈希>.json`），
+//! 文件内同时记录 Agent 名称和项目目录，便于列出所有作用域。
+//! 单条记录和单个作用域都有大小配额，超出时写入失败。
+
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::json_store;
+use crate::utils::paths::get_app_data_dir;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::debug;
+
+/// 存储目录（相对应用数据目录）
+const MEMORY_DIR: &str = "memory";
+
+/// 单条记录的最大字节数
+const MAX_VALUE_BYTES: usize = 64 * 1024;
+
+/// 单个作用域的最大总字节数（键 + 值）
+const MAX_SCOPE_BYTES: usize = 1024 * 1024;
+
+/// 单个作用域的最大记录数
+const MAX_SCOPE_ENTRIES: usize = 500;
+
+/// 键的最大长度（字符）
+const MAX_KEY_CHARS: usize = 128;
+
+/// 记忆作用域：Agent + 项目目录（为空时为该 Agent 的全局记忆）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryScope {
+    pub agent: String,
+    #[serde(default)]
+    pub project: Option<String>,
+}
+
+/// 记忆条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryEntry {
+    pub key: String,
+    pub value: String,
+    /// Unix 毫秒
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 作用域概要
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryScopeSummary {
+    #[serde(flatten)]
+    pub scope: MemoryScope,
+    pub entry_count: usize,
+    pub total_bytes: usize,
+    /// 允许的最大总字节数
+    pub quota_bytes: usize,
+}
+
+/// 单个作用域的持久化内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScopeFile {
+    scope: MemoryScope,
+    entries: BTreeMap<String, MemoryEntry>,
+}
+
+impl ScopeFile {
+    fn total_bytes(&self) -> usize {
+        self.entries
+            .values()
+            .map(|e| e.key.len() + e.value.len())
+            .sum()
+    }
+}
+
+/// 记忆存储
+#[derive(Debug)]
+pub struct MemoryStore {
+    /// 存储目录，为空时使用应用数据目录下的 memory
+    dir: Option<PathBuf>,
+    /// 串行化读写
+    lock: Mutex<()>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            dir: None,
+            lock: Mutex::new(()),
+        })
+    }
+
+    #[cfg(test)]
+    fn with_dir(dir: PathBuf) -> Self {
+        Self {
+            dir: Some(dir),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 列出作用域内的所有条目（按键排序）
+    pub fn list(&self, scope: &MemoryScope) -> Result<Vec<MemoryEntry>, AxonError> {
+        validate_scope(scope)?;
+        let _guard = self.lock.lock();
+        Ok(self
+            .read_scope(scope)?
+            .map(|file| file.entries.into_values().collect())
+            .unwrap_or_default())
+    }
+
+    pub fn get(&self, scope: &MemoryScope, key: &str) -> Result<Option<MemoryEntry>, AxonError> {
+        validate_scope(scope)?;
+        let _guard = self.lock.lock();
+        Ok(self
+            .read_scope(scope)?
+            .and_then(|mut file| file.entries.remove(key)))
+    }
+
+    /// 写入条目，超出配额时返回错误
+    pub fn set(
+        &self,
+        scope: &MemoryScope,
+        key: &str,
+        value: String,
+    ) -> Result<MemoryEntry, AxonError> {
+        validate_scope(scope)?;
+        validate_key(key)?;
+        if value.len() > MAX_VALUE_BYTES {
+            return Err(AxonError::invalid_input(format!(
+                "记忆内容超过 {} KB 上限",
+                MAX_VALUE_BYTES / 1024
+            )));
+        }
+
+        let _guard = self.lock.lock();
+        let mut file = self.read_scope(scope)?.unwrap_or_else(|| ScopeFile {
+            scope: scope.clone(),
+            entries: BTreeMap::new(),
+        });
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let created_at = file.entries.get(key).map_or(now, |e| e.created_at);
+        let entry = MemoryEntry {
+            key: key.to_string(),
+            value,
+            created_at,
+            updated_at: now,
+        };
+        file.entries.insert(key.to_string(), entry.clone());
+
+        if file.entries.len() > MAX_SCOPE_ENTRIES {
+            return Err(AxonError::invalid_input(format!(
+                "记忆条目数超过上限（{} 条）",
+                MAX_SCOPE_ENTRIES
+            )));
+        }
+        if file.total_bytes() > MAX_SCOPE_BYTES {
+            return Err(AxonError::invalid_input(format!(
+                "记忆总大小超过 {} KB 配额",
+                MAX_SCOPE_BYTES / 1024
+            )));
+        }
+
+        self.write_scope(&file)?;
+        debug!(
+            "已写入记忆: {} / {:?} / {}",
+            scope.agent, scope.project, key
+        );
+        Ok(entry)
+    }
+
+    /// 删除条目，返回条目是否存在
+    pub fn delete(&self, scope: &MemoryScope, key: &str) -> Result<bool, AxonError> {
+        validate_scope(scope)?;
+        let _guard = self.lock.lock();
+        let Some(mut file) = self.read_scope(scope)? else {
+            return Ok(false);
+        };
+        if file.entries.remove(key).is_none() {
+            return Ok(false);
+        }
+        self.write_scope(&file)?;
+        Ok(true)
+    }
+
+    /// 清空作用域
+    pub fn clear(&self, scope: &MemoryScope) -> Result<(), AxonError> {
+        validate_scope(scope)?;
+        let _guard = self.lock.lock();
+        let path = self.scope_path(scope)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AxonError::io("删除记忆文件失败", &e)),
+        }
+    }
+
+    /// 列出所有作用域
+    pub fn scopes(&self) -> Result<Vec<MemoryScopeSummary>, AxonError> {
+        let _guard = self.lock.lock();
+        let dir = self.dir()?;
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Ok(Vec::new());
+        };
+
+        let mut scopes: Vec<MemoryScopeSummary> = entries
+            .flatten()
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|e| {
+                json_store::load::<ScopeFile>(&e.path(), "记忆文件")
+                    .ok()
+                    .flatten()
+            })
+            .map(|file| MemoryScopeSummary {
+                entry_count: file.entries.len(),
+                total_bytes: file.total_bytes(),
+                quota_bytes: MAX_SCOPE_BYTES,
+                scope: file.scope,
+            })
+            .collect();
+        scopes.sort_by(|a, b| {
+            a.scope
+                .agent
+                .cmp(&b.scope.agent)
+                .then_with(|| a.scope.project.cmp(&b.scope.project))
+        });
+        Ok(scopes)
+    }
+
+    fn dir(&self) -> Result<PathBuf, AxonError> {
+        self.dir
+            .clone()
+            .or_else(|| get_app_data_dir().map(|d| d.join(MEMORY_DIR)))
+            .ok_or_else(|| {
+                AxonError::localized(
+                    ErrorKind::Unavailable,
+                    "app.data_dir_unavailable",
+                    serde_json::json!(null),
+                )
+            })
+    }
+
+    fn scope_path(&self, scope: &MemoryScope) -> Result<PathBuf, AxonError> {
+        let mut hasher = Sha256::new();
+        hasher.update(scope.agent.as_bytes());
+        hasher.update([0]);
+        hasher.update(scope.project.as_deref().unwrap_or("").as_bytes());
+        let name: String = hasher
+            .finalize()
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Ok(self.dir()?.join(format!("{}.json", name)))
+    }
+
+    fn read_scope(&self, scope: &MemoryScope) -> Result<Option<ScopeFile>, AxonError> {
+        json_store::load(&self.scope_path(scope)?, "记忆文件")
+    }
+
+    fn write_scope(&self, file: &ScopeFile) -> Result<(), AxonError> {
+        json_store::save(&self.scope_path(&file.scope)?, file, "记忆文件")
+    }
+}
+
+fn validate_scope(scope: &MemoryScope) -> Result<(), AxonError> {
+    let agent = scope.agent.trim();
+    if agent.is_empty() || agent.chars().count() > MAX_KEY_CHARS {
+        return Err(AxonError::invalid_input(
+            "Agent 名称不能为空且不超过 128 个字符",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_key(key: &str) -> Result<(), AxonError> {
+    if key.trim().is_empty()
+        || key.chars().count() > MAX_KEY_CHARS
+        || key.chars().any(char::is_control)
+    {
+        return Err(AxonError::invalid_input(
+            "记忆键不能为空、不能包含控制字符且不超过 128 个字符",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store() -> (TempDir, MemoryStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MemoryStore::with_dir(dir.path().to_path_buf());
+        (dir, store)
+    }
+
+    fn scope_of(agent: &str, project: Option<&str>) -> MemoryScope {
+        MemoryScope {
+            agent: agent.to_string(),
+            project: project.map(String::from),
+        }
+    }
+
+    #[test]
+    fn enforces_value_and_scope_byte_quota() {
+        let (_dir, store) = store();
+        let scope = scope_of("plan", Some("/work/app"));
+        assert!(store
+            .set(&scope, "big", "x".repeat(MAX_VALUE_BYTES + 1))
+            .is_err());
+
+        let value = "x".repeat(MAX_VALUE_BYTES);
+        let fits = MAX_SCOPE_BYTES / (MAX_VALUE_BYTES + 8);
+        for i in 0..fits {
+            store
+                .set(&scope, &format!("key-{:03}", i), value.clone())
+                .unwrap();
+        }
+        // 超出配额的写入不落盘
+        assert!(store.set(&scope, "overflow", value.clone()).is_err());
+        assert!(store.get(&scope, "overflow").unwrap().is_none());
+        // 覆盖已有条目按新值计算大小
+        store.set(&scope, "key-000", "short".into()).unwrap();
+        store.set(&scope, "overflow", "y".repeat(1024)).unwrap();
+
+        let summary = &store.scopes().unwrap()[0];
+        assert_eq!(summary.scope, scope);
+        assert_eq!(summary.entry_count, fits + 1);
+        assert!(summary.total_bytes <= summary.quota_bytes);
+        // 另一个作用域有独立的配额
+        store.set(&scope_of("plan", None), "k", value).unwrap();
+    }
+
+    #[test]
+    fn enforces_entry_count_and_key_rules() {
+        let (_dir, store) = store();
+        let scope = scope_of("build", None);
+        assert!(store.set(&scope, " ", "v".into()).is_err());
+        assert!(store.set(&scope, "a\nb", "v".into()).is_err());
+        assert!(store
+            .set(&scope, &"键".repeat(MAX_KEY_CHARS + 1), "v".into())
+            .is_err());
+        store
+            .set(&scope, &"键".repeat(MAX_KEY_CHARS), "v".into())
+            .unwrap();
+        assert!(store.list(&scope_of(" ", None)).is_err());
+
+        for i in 1..MAX_SCOPE_ENTRIES {
+            store.set(&scope, &format!("k{}", i), "v".into()).unwrap();
+        }
+        assert!(store.set(&scope, "one-more", "v".into()).is_err());
+        let updated = store.set(&scope, "k1", "v2".into()).unwrap();
+        assert_eq!(updated.value, "v2");
+        assert_eq!(store.list(&scope).unwrap().len(), MAX_SCOPE_ENTRIES);
+    }
+}
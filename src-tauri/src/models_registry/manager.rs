@@ -1,17 +1,25 @@
 //! 模型注册表管理器
 //!
-//! 负责下载、缓存、哈希校验 models.dev/api.json
+//! 负责下载、缓存、哈希校验 models.dev/api.json，
+//! 并将用户自定义模型合并到查询结果中
 
 use crate::models_registry::types::{
-    CachedModelsRegistry, ModelDefaults, ModelsRegistryData, ProviderInfo,
+    CachedModelsRegistry, ModelDefaults, ModelInfo, ModelsRegistryData, ProviderInfo,
+    RegistryUpdateSource, RegistryUpdatedEvent, UserModelEntry,
 };
+use crate::power::PowerMonitor;
 use crate::utils::paths::get_app_data_dir;
 use parking_lot::RwLock;
+use rand::Rng;
 use sha2::{Digest, Sha256};
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
-use tracing::{debug, error, info, warn};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, info, warn};
 
 /// 模型注册表 API URL
 const MODELS_REGISTRY_URL: &str = "https://models.dev/api.json";
@@ -19,12 +27,36 @@ const MODELS_REGISTRY_URL: &str = "https://models.dev/api.json";
 /// 缓存文件名
 const CACHE_FILE: &str = "models_registry.json";
 
+/// 用户自定义模型文件名
+const USER_MODELS_FILE: &str = "user_models.json";
+
+/// 打包的注册表快照（相对于资源目录，gzip 压缩的 api.json）
+const BUNDLED_SNAPSHOT_PATH: &str = "resources/models_registry.json.gz";
+
+/// 导入文件的最大大小：64MB
+const MAX_IMPORT_SIZE: u64 = 64 * 1024 * 1024;
+
 /// 缓存有效期：24 小时
 const CACHE_TTL_SECS: u64 = 24 * 60 * 60;
 
 /// 后台刷新间隔：6 小时
 const BACKGROUND_REFRESH_INTERVAL_SECS: u64 = 6 * 60 * 60;
 
+/// 定时任务首次检查前的延迟，避免与启动过程争抢网络
+const SCHEDULER_START_DELAY_SECS: u64 = 10;
+
+/// 刷新失败后的初始重试间隔：30 秒
+const RETRY_BASE_SECS: u64 = 30;
+
+/// 刷新失败后的最大重试间隔：1 小时
+const RETRY_MAX_SECS: u64 = 60 * 60;
+
+/// 节能暂停期间推迟刷新的间隔：1 分钟
+const PAUSED_DELAY_SECS: u64 = 60;
+
+/// 注册表数据变化事件
+pub const EVENT_MODELS_REGISTRY_UPDATED: &str = "models-registry:updated";
+
 /// 模型注册表管理器
 pub struct ModelsRegistryManager {
     /// 缓存的注册表数据
@@ -33,6 +65,15 @@ pub struct ModelsRegistryManager {
     client: reqwest::Client,
     /// 上次后台刷新时间
     last_background_refresh: RwLock<u64>,
+    /// 防止并发刷新
+    refresh_lock: tokio::sync::Mutex<()>,
+    /// 用于发送更新事件（定时任务启动时设置）
+    app_handle: RwLock<Option<AppHandle>>,
+    scheduler_started: AtomicBool,
+    /// 用户自定义模型
+    user_models: RwLock<Vec<UserModelEntry>>,
+    /// 注册表与用户自定义模型合并后的数据（查询使用）
+    merged: RwLock<ModelsRegistryData>,
 }
 
 impl ModelsRegistryManager {
@@ -48,6 +89,11 @@ impl ModelsRegistryManager {
             cache: RwLock::new(None),
             client,
             last_background_refresh: RwLock::new(0),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            app_handle: RwLock::new(None),
+            scheduler_started: AtomicBool::new(false),
+            user_models: RwLock::new(Vec::new()),
+            merged: RwLock::new(HashMap::new()),
         })
     }
 
@@ -132,15 +178,167 @@ impl ModelsRegistryManager {
         Ok(())
     }
 
+    /// 获取用户自定义模型文件路径
+    fn get_user_models_path() -> Option<PathBuf> {
+        get_app_data_dir().map(|p| p.join(USER_MODELS_FILE))
+    }
+
+    /// 从磁盘加载用户自定义模型
+    fn load_user_models() -> Vec<UserModelEntry> {
+        let Some(path) = Self::get_user_models_path() else {
+            return Vec::new();
+        };
+        if !path.exists() {
+            return Vec::new();
+        }
+
+        match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("解析用户自定义模型失败: {}", e);
+                Vec::new()
+            }),
+            Err(e) => {
+                warn!("读取用户自定义模型失败: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// 保存用户自定义模型到磁盘
+    fn save_user_models(models: &[UserModelEntry]) -> Result<(), String> {
+        let path = Self::get_user_models_path().ok_or("无法获取用户模型文件路径")?;
+        let content = serde_json::to_string_pretty(models)
+            .map_err(|e| format!("序列化用户自定义模型失败: {}", e))?;
+        std::fs::write(&path, content).map_err(|e| format!("写入用户自定义模型失败: {}", e))
+    }
+
+    /// 重新合并注册表数据与用户自定义模型
+    fn rebuild_merged(&self) {
+        let mut data = self
+            .cache
+            .read()
+            .as_ref()
+            .map(|c| c.data.clone())
+            .unwrap_or_default();
+
+        for entry in self.user_models.read().iter() {
+            let provider = data
+                .entry(entry.provider_id.clone())
+                .or_insert_with(|| ProviderInfo {
+                    id: entry.provider_id.clone(),
+                    env: Vec::new(),
+                    npm: None,
+                    api: entry.base_url.clone(),
+                    name: entry
+                        .provider_name
+                        .clone()
+                        .unwrap_or_else(|| entry.provider_id.clone()),
+                    doc: None,
+                    models: HashMap::new(),
+                });
+            provider
+                .models
+                .insert(entry.model_id.clone(), entry.to_model_info());
+        }
+
+        *self.merged.write() = data;
+    }
+
+    /// 用户自定义模型的完整 ID 集合
+    fn custom_model_ids(&self) -> HashSet<String> {
+        self.user_models
+            .read()
+            .iter()
+            .map(UserModelEntry::full_id)
+            .collect()
+    }
+
+    /// 构建模型默认参数并标记是否为自定义模型
+    fn to_defaults(
+        provider: &ProviderInfo,
+        model: &ModelInfo,
+        custom_ids: &HashSet<String>,
+    ) -> ModelDefaults {
+        let mut defaults = ModelDefaults::from_model_info(provider, model);
+        defaults.custom = custom_ids.contains(&defaults.model_id);
+        defaults
+    }
+
+    /// 解析注册表数据（支持 gzip 压缩），返回（哈希，数据）
+    ///
+    /// 哈希基于解压后的 JSON 计算，与远程获取的数据可直接比较
+    fn decode_registry(bytes: &[u8]) -> Result<(String, ModelsRegistryData), String> {
+        let json = if bytes.starts_with(&[0x1f, 0x8b]) {
+            let mut decoded = Vec::new();
+            flate2::read::GzDecoder::new(bytes)
+                .read_to_end(&mut decoded)
+                .map_err(|e| format!("解压注册表数据失败: {}", e))?;
+            decoded
+        } else {
+            bytes.to_vec()
+        };
+
+        let data: ModelsRegistryData =
+            serde_json::from_slice(&json).map_err(|e| format!("解析 JSON 失败: {}", e))?;
+        if data.is_empty() {
+            return Err("注册表数据为空".to_string());
+        }
+
+        Ok((Self::compute_hash(&json), data))
+    }
+
+    /// 加载打包的注册表快照（不写入磁盘缓存，联网后会被远程数据替换）
+    fn load_bundled_snapshot(resource_dir: &Path) -> Option<CachedModelsRegistry> {
+        let path = resource_dir.join(BUNDLED_SNAPSHOT_PATH);
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                debug!("打包的注册表快照不可用: {:?}, {}", path, e);
+                return None;
+            }
+        };
+
+        match Self::decode_registry(&bytes) {
+            Ok((hash, data)) => {
+                info!("已加载打包的注册表快照, 包含 {} 个 provider", data.len());
+                // 时间戳为 0，视为已过期
+                Some(CachedModelsRegistry {
+                    hash,
+                    timestamp: 0,
+                    data,
+                })
+            }
+            Err(e) => {
+                warn!("加载打包的注册表快照失败: {}", e);
+                None
+            }
+        }
+    }
+
     /// 初始化：加载缓存（首次启动时调用）
-    pub fn initialize(&self) {
+    ///
+    /// 磁盘缓存不存在时使用打包的快照作为初始数据，
+    /// 保证离线环境下注册表不为空；联网刷新成功后替换为最新数据
+    pub fn initialize(&self, resource_dir: Option<&Path>) {
         // 首先尝试从磁盘加载缓存
         if let Some(cached) = self.load_from_disk() {
+            // 以缓存时间作为上次刷新时间，缓存较新时定时任务会推迟首次刷新
+            *self.last_background_refresh.write() = cached.timestamp;
             *self.cache.write() = Some(cached);
             info!("模型注册表缓存已加载");
+        } else if let Some(bundled) = resource_dir.and_then(Self::load_bundled_snapshot) {
+            *self.cache.write() = Some(bundled);
+            info!("模型注册表缓存不存在，暂用打包快照，将在后台下载");
         } else {
             info!("模型注册表缓存不存在，将在后台下载");
         }
+
+        let user_models = Self::load_user_models();
+        if !user_models.is_empty() {
+            info!("已加载 {} 个用户自定义模型", user_models.len());
+        }
+        *self.user_models.write() = user_models;
+        self.rebuild_merged();
     }
 
     /// 从远程获取注册表数据
@@ -163,10 +361,7 @@ impl ModelsRegistryManager {
             .await
             .map_err(|e| format!("读取响应失败: {}", e))?;
 
-        let hash = Self::compute_hash(&bytes);
-
-        let data: ModelsRegistryData = serde_json::from_slice(&bytes)
-            .map_err(|e| format!("解析 JSON 失败: {}", e))?;
+        let (hash, data) = Self::decode_registry(&bytes)?;
 
         info!(
             "成功获取模型注册表, 包含 {} 个 provider, hash={}",
@@ -177,7 +372,135 @@ impl ModelsRegistryManager {
         Ok((hash, data))
     }
 
+    /// 拉取远程数据，数据变化时更新缓存并发送事件，返回数据是否变化
+    async fn refresh_now(&self) -> Result<bool, String> {
+        // 避免定时任务与手动刷新并发请求
+        let _guard = self.refresh_lock.lock().await;
+
+        // 更新刷新时间
+        *self.last_background_refresh.write() = Self::now();
+
+        let (new_hash, data) = self.fetch_remote().await?;
+        let current_hash = self.cache.read().as_ref().map(|c| c.hash.clone());
+
+        let cached = CachedModelsRegistry {
+            hash: new_hash.clone(),
+            timestamp: Self::now(),
+            data,
+        };
+
+        // 检查哈希是否变化
+        if current_hash.as_deref() == Some(new_hash.as_str()) {
+            debug!("模型注册表未变化，仅更新缓存时间");
+            if let Some(current) = self.cache.write().as_mut() {
+                current.timestamp = cached.timestamp;
+            }
+            self.save_to_disk(&cached)?;
+            return Ok(false);
+        }
+
+        let previous = current_hash.unwrap_or_default();
+        info!(
+            "模型注册表已更新 (hash: {} -> {})",
+            &previous[..8.min(previous.len())],
+            &new_hash[..8.min(new_hash.len())]
+        );
+        let previous_hash = Some(previous).filter(|h| !h.is_empty());
+        self.apply_update(cached, previous_hash, RegistryUpdateSource::Remote)?;
+        Ok(true)
+    }
+
+    /// 替换缓存数据、写入磁盘并通知前端
+    fn apply_update(
+        &self,
+        cached: CachedModelsRegistry,
+        previous_hash: Option<String>,
+        source: RegistryUpdateSource,
+    ) -> Result<(), String> {
+        let event = RegistryUpdatedEvent {
+            hash: cached.hash.clone(),
+            previous_hash,
+            provider_count: cached.data.len(),
+            model_count: cached.data.values().map(|p| p.models.len()).sum(),
+            source,
+        };
+
+        // 更新内存缓存
+        *self.cache.write() = Some(cached.clone());
+        self.rebuild_merged();
+
+        if let Some(app) = self.app_handle.read().as_ref() {
+            if let Err(e) = app.emit(EVENT_MODELS_REGISTRY_UPDATED, &event) {
+                warn!("发送注册表更新事件失败: {}", e);
+            }
+        }
+
+        // 保存到磁盘
+        self.save_to_disk(&cached)
+    }
+
+    /// 距下次定时刷新的时间
+    fn time_until_next_refresh(&self) -> Duration {
+        let elapsed = Self::now().saturating_sub(*self.last_background_refresh.read());
+        Duration::from_secs(BACKGROUND_REFRESH_INTERVAL_SECS.saturating_sub(elapsed))
+    }
+
+    /// 启动定时刷新任务（重复调用无效）
+    ///
+    /// 每 6 小时刷新一次；失败时按指数退避（带随机抖动）重试；
+    /// 空闲或电池供电导致后台任务暂停时推迟到恢复后刷新
+    pub fn start_scheduler(self: &Arc<Self>, app: AppHandle, power: Arc<PowerMonitor>) {
+        if self.scheduler_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        *self.app_handle.write() = Some(app);
+
+        let manager = Arc::clone(self);
+        tauri::async_runtime::spawn(async move {
+            let mut delay = manager
+                .time_until_next_refresh()
+                .max(Duration::from_secs(SCHEDULER_START_DELAY_SECS));
+            let mut failures: u32 = 0;
+
+            loop {
+                tokio::time::sleep(delay).await;
+
+                if power.is_paused() {
+                    delay = Duration::from_secs(PAUSED_DELAY_SECS);
+                    continue;
+                }
+
+                // 期间已通过其他途径刷新过时顺延（重试不受此限制）
+                if failures == 0 && !manager.should_background_refresh() {
+                    delay = manager.time_until_next_refresh().max(Duration::from_secs(1));
+                    continue;
+                }
+
+                match manager.refresh_now().await {
+                    Ok(changed) => {
+                        debug!("定时刷新模型注册表完成, 数据变化: {}", changed);
+                        failures = 0;
+                        delay = Duration::from_secs(BACKGROUND_REFRESH_INTERVAL_SECS);
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        delay = retry_delay(failures);
+                        warn!(
+                            "定时刷新模型注册表失败（第 {} 次）: {}，{} 秒后重试",
+                            failures,
+                            e,
+                            delay.as_secs()
+                        );
+                    }
+                }
+            }
+        });
+        info!("模型注册表定时刷新已启动");
+    }
+
     /// 后台刷新缓存（静默更新）
+    ///
+    /// 距上次刷新未超过刷新间隔时跳过
     pub async fn refresh_in_background(self: &Arc<Self>) {
         // 检查是否需要刷新
         if !self.should_background_refresh() {
@@ -185,76 +508,59 @@ impl ModelsRegistryManager {
             return;
         }
 
-        // 更新刷新时间
-        *self.last_background_refresh.write() = Self::now();
-
-        // 获取当前缓存的哈希
-        let current_hash = self
-            .cache
-            .read()
-            .as_ref()
-            .map(|c| c.hash.clone())
-            .unwrap_or_default();
-
         // 克隆 self 用于 async 移动
         let manager = Arc::clone(self);
 
         // 在后台执行刷新
         tokio::spawn(async move {
-            match manager.fetch_remote().await {
-                Ok((new_hash, data)) => {
-                    // 检查哈希是否变化
-                    if new_hash == current_hash {
-                        debug!("模型注册表未变化，跳过更新");
-                        return;
-                    }
-
-                    info!("模型注册表已更新 (hash: {} -> {})", &current_hash[..8.min(current_hash.len())], &new_hash[..8]);
-
-                    let cached = CachedModelsRegistry {
-                        hash: new_hash,
-                        timestamp: Self::now(),
-                        data,
-                    };
-
-                    // 更新内存缓存
-                    *manager.cache.write() = Some(cached.clone());
-
-                    // 保存到磁盘
-                    if let Err(e) = manager.save_to_disk(&cached) {
-                        error!("保存模型注册表缓存失败: {}", e);
-                    }
-                }
-                Err(e) => {
-                    warn!("后台刷新模型注册表失败: {}", e);
-                }
+            if let Err(e) = manager.refresh_now().await {
+                warn!("后台刷新模型注册表失败: {}", e);
             }
         });
     }
 
     /// 强制刷新（用户手动触发）
     pub async fn force_refresh(&self) -> Result<(), String> {
-        let (hash, data) = self.fetch_remote().await?;
+        self.refresh_now().await.map(|_| ())
+    }
+
+    /// 从本地 JSON 文件（models.dev api.json 导出，可为 gzip 压缩）导入注册表
+    ///
+    /// 导入的数据会覆盖当前缓存并写入磁盘，返回（provider 数量，模型数量）
+    pub fn import_from_file(&self, path: &Path) -> Result<(usize, usize), String> {
+        let size = std::fs::metadata(path)
+            .map_err(|e| format!("读取文件信息失败: {}", e))?
+            .len();
+        if size > MAX_IMPORT_SIZE {
+            return Err(format!("文件过大: {} 字节", size));
+        }
+
+        let bytes = std::fs::read(path).map_err(|e| format!("读取文件失败: {}", e))?;
+        let (hash, data) = Self::decode_registry(&bytes)?;
+        let provider_count = data.len();
+        let model_count = data.values().map(|p| p.models.len()).sum();
 
+        let previous_hash = self.cache.read().as_ref().map(|c| c.hash.clone());
         let cached = CachedModelsRegistry {
             hash,
             timestamp: Self::now(),
             data,
         };
+        *self.last_background_refresh.write() = cached.timestamp;
+        self.apply_update(cached, previous_hash, RegistryUpdateSource::Import)?;
 
-        // 更新内存缓存
-        *self.cache.write() = Some(cached.clone());
-
-        // 保存到磁盘
-        self.save_to_disk(&cached)?;
-
-        Ok(())
+        info!(
+            "已从 {:?} 导入模型注册表: {} 个 provider, {} 个模型",
+            path, provider_count, model_count
+        );
+        Ok((provider_count, model_count))
     }
 
-    /// 获取缓存的注册表数据
+    /// 获取合并后的注册表数据
     #[allow(dead_code)]
     pub fn get_registry(&self) -> Option<ModelsRegistryData> {
-        self.cache.read().as_ref().map(|c| c.data.clone())
+        let merged = self.merged.read();
+        (!merged.is_empty()).then(|| merged.clone())
     }
 
     /// 获取缓存信息（用于调试）
@@ -270,10 +576,6 @@ impl ModelsRegistryManager {
 
     /// 获取指定模型的默认参数
     pub fn get_model_defaults(&self, model_id: &str) -> Option<ModelDefaults> {
-        let cache = self.cache.read();
-        let registry = cache.as_ref()?.data.clone();
-        drop(cache);
-
         // 解析 model_id: "provider/model" 格式
         let parts: Vec<&str> = model_id.splitn(2, '/').collect();
         if parts.len() != 2 {
@@ -284,27 +586,27 @@ impl ModelsRegistryManager {
         let provider_id = parts[0];
         let model_id_only = parts[1];
 
+        let merged = self.merged.read();
+
         // 查找 provider
-        let provider = registry.get(provider_id)?;
+        let provider = merged.get(provider_id)?;
 
         // 查找模型
         let model = provider.models.get(model_id_only)?;
 
-        Some(ModelDefaults::from_model_info(provider, model))
+        Some(Self::to_defaults(provider, model, &self.custom_model_ids()))
     }
 
     /// 获取所有模型的默认参数列表
     pub fn get_all_model_defaults(&self) -> Vec<ModelDefaults> {
-        let cache = self.cache.read();
-        let Some(cached) = cache.as_ref() else {
-            return Vec::new();
-        };
+        let custom_ids = self.custom_model_ids();
+        let merged = self.merged.read();
 
         let mut defaults = Vec::new();
 
-        for provider in cached.data.values() {
+        for provider in merged.values() {
             for model in provider.models.values() {
-                defaults.push(ModelDefaults::from_model_info(provider, model));
+                defaults.push(Self::to_defaults(provider, model, &custom_ids));
             }
         }
 
@@ -328,31 +630,95 @@ impl ModelsRegistryManager {
     /// 按 provider 获取模型列表
     #[allow(dead_code)]
     pub fn get_models_by_provider(&self, provider_id: &str) -> Vec<ModelDefaults> {
-        let cache = self.cache.read();
-        let Some(cached) = cache.as_ref() else {
-            return Vec::new();
-        };
+        let custom_ids = self.custom_model_ids();
+        let merged = self.merged.read();
 
-        let Some(provider) = cached.data.get(provider_id) else {
+        let Some(provider) = merged.get(provider_id) else {
             return Vec::new();
         };
 
         provider
             .models
             .values()
-            .map(|m| ModelDefaults::from_model_info(provider, m))
+            .map(|m| Self::to_defaults(provider, m, &custom_ids))
             .collect()
     }
 
     /// 获取所有 provider 列表
     #[allow(dead_code)]
     pub fn get_providers(&self) -> Vec<ProviderInfo> {
-        let cache = self.cache.read();
-        cache
-            .as_ref()
-            .map(|c| c.data.values().cloned().collect())
-            .unwrap_or_default()
+        self.merged.read().values().cloned().collect()
     }
+
+    /// 获取用户自定义模型列表
+    pub fn get_user_models(&self) -> Vec<UserModelEntry> {
+        self.user_models.read().clone()
+    }
+
+    /// 添加或更新用户自定义模型（按 provider_id + model_id 匹配）
+    pub fn upsert_user_model(&self, mut entry: UserModelEntry) -> Result<UserModelEntry, String> {
+        entry.provider_id = entry.provider_id.trim().to_string();
+        entry.model_id = entry.model_id.trim().to_string();
+        if entry.provider_id.is_empty() || entry.model_id.is_empty() {
+            return Err("Provider ID 和模型 ID 不能为空".to_string());
+        }
+        if entry.provider_id.contains('/') {
+            return Err(format!("Provider ID 不能包含 '/': {}", entry.provider_id));
+        }
+        if entry.name.trim().is_empty() {
+            entry.name = entry.model_id.clone();
+        }
+
+        let now = chrono::Utc::now().to_rfc3339();
+        entry.updated_at = now.clone();
+
+        {
+            let mut models = self.user_models.write();
+            match models
+                .iter_mut()
+                .find(|m| m.provider_id == entry.provider_id && m.model_id == entry.model_id)
+            {
+                Some(existing) => {
+                    entry.created_at = existing.created_at.clone();
+                    *existing = entry.clone();
+                }
+                None => {
+                    entry.created_at = now;
+                    models.push(entry.clone());
+                }
+            }
+            Self::save_user_models(&models)?;
+        }
+
+        self.rebuild_merged();
+        info!("已保存用户自定义模型: {}", entry.full_id());
+        Ok(entry)
+    }
+
+    /// 删除用户自定义模型，不存在时返回 false
+    pub fn delete_user_model(&self, provider_id: &str, model_id: &str) -> Result<bool, String> {
+        {
+            let mut models = self.user_models.write();
+            let before = models.len();
+            models.retain(|m| !(m.provider_id == provider_id && m.model_id == model_id));
+            if models.len() == before {
+                return Ok(false);
+            }
+            Self::save_user_models(&models)?;
+        }
+
+        self.rebuild_merged();
+        info!("已删除用户自定义模型: {}/{}", provider_id, model_id);
+        Ok(true)
+    }
+}
+
+/// 第 `failures` 次失败后的重试间隔（指数退避，±20% 随机抖动）
+fn retry_delay(failures: u32) -> Duration {
+    let exponent = failures.saturating_sub(1).min(16);
+    let base = RETRY_BASE_SECS.saturating_mul(1 << exponent).min(RETRY_MAX_SECS);
+    let jitter = rand::thread_rng().gen_range(0.8..1.2);
+    Duration::from_secs_f64(base as f64 * jitter)
 }
 
 impl Default for ModelsRegistryManager {
@@ -361,6 +727,11 @@ impl Default for ModelsRegistryManager {
             cache: RwLock::new(None),
             client: reqwest::Client::new(),
             last_background_refresh: RwLock::new(0),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            app_handle: RwLock::new(None),
+            scheduler_started: AtomicBool::new(false),
+            user_models: RwLock::new(Vec::new()),
+            merged: RwLock::new(HashMap::new()),
         }
     }
 }
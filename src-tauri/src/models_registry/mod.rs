@@ -5,16 +5,18 @@
 //!
 //! ## 功能
 //!
-//! - 首次启动时从磁盘加载缓存
-//! - 后台静默刷新数据（每 6 小时检查一次）
+//! - 首次启动时从磁盘加载缓存，无缓存时使用打包的离线快照
+//! - 定时任务后台刷新数据（每 6 小时一次，失败时指数退避重试），
+//!   数据变化时发送 `models-registry:updated` 事件
 //! - 使用 SHA256 哈希校验数据变化
 //! - 提供模型默认参数查询接口
+//! - 合并用户自定义模型（user_models.json），用于 models.dev 未收录的自托管模型
 //!
 //! ## 使用
 //!
 //! ```rust
-//! // 初始化（启动时调用一次）
-//! manager.initialize();
+//! // 初始化（启动时调用一次，传入资源目录以便加载离线快照）
+//! manager.initialize(resource_dir.as_deref());
 //!
 //! // 获取模型默认参数
 //! if let Some(defaults) = manager.get_model_defaults("anthropic/claude-sonnet-4-5") {
@@ -22,12 +24,12 @@
 //!     println!("context_window: {}", defaults.context_window);
 //! }
 //!
-//! // 后台刷新（定期调用）
-//! manager.refresh_in_background().await;
+//! // 启动定时刷新（setup 阶段调用一次）
+//! manager.start_scheduler(app_handle);
 //! ```
 
 mod manager;
 mod types;
 
 pub use manager::ModelsRegistryManager;
-pub use types::ModelDefaults;
+pub use types::{ModelDefaults, UserModelEntry};
@@ -183,6 +183,9 @@ pub struct ModelDefaults {
     pub cost_input: f64,
     /// 输出成本 (每百万 token)
     pub cost_output: f64,
+    /// 是否为用户自定义模型
+    #[serde(default)]
+    pub custom: bool,
 }
 
 impl ModelDefaults {
@@ -209,6 +212,128 @@ impl ModelDefaults {
             default_max_tokens: default.and_then(|d| d.max_tokens),
             cost_input: cost.map(|c| c.input).unwrap_or(0.0),
             cost_output: cost.map(|c| c.output).unwrap_or(0.0),
+            custom: false,
         }
     }
 }
+
+/// 用户自定义模型（如自托管的 vLLM 模型）
+///
+/// 保存在 user_models.json 中，查询时合并到注册表数据，
+/// 与注册表中同 ID 的模型冲突时以用户定义为准
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserModelEntry {
+    /// Provider ID（可以是注册表中已有的 provider，也可以是新的）
+    pub provider_id: String,
+    /// Provider 显示名称（新 provider 时使用，默认为 provider_id）
+    #[serde(default)]
+    pub provider_name: Option<String>,
+    /// Provider API 端点（新 provider 时使用）
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// 模型 ID（不含 provider 前缀）
+    pub model_id: String,
+    /// 模型显示名称
+    pub name: String,
+    #[serde(default)]
+    pub family: Option<String>,
+    /// 上下文窗口大小
+    #[serde(default)]
+    pub context_window: u64,
+    /// 最大输出 tokens
+    #[serde(default)]
+    pub max_output_tokens: u64,
+    /// 输入成本 (每百万 token)
+    #[serde(default)]
+    pub cost_input: f64,
+    /// 输出成本 (每百万 token)
+    #[serde(default)]
+    pub cost_output: f64,
+    #[serde(default)]
+    pub supports_reasoning: bool,
+    #[serde(default)]
+    pub supports_tool_call: bool,
+    #[serde(default)]
+    pub supports_structured_output: bool,
+    #[serde(default)]
+    pub supports_attachment: bool,
+    #[serde(default = "default_true")]
+    pub supports_temperature: bool,
+    #[serde(default)]
+    pub default_temperature: Option<f64>,
+    #[serde(default)]
+    pub default_top_p: Option<f64>,
+    #[serde(default)]
+    pub default_max_tokens: Option<u64>,
+    #[serde(default)]
+    pub created_at: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+impl UserModelEntry {
+    /// 完整模型 ID (provider/model 格式)
+    pub fn full_id(&self) -> String {
+        format!("{}/{}", self.provider_id, self.model_id)
+    }
+
+    /// 转换为注册表中的模型信息
+    pub fn to_model_info(&self) -> ModelInfo {
+        let has_defaults = self.default_temperature.is_some()
+            || self.default_top_p.is_some()
+            || self.default_max_tokens.is_some();
+
+        ModelInfo {
+            id: self.model_id.clone(),
+            name: self.name.clone(),
+            family: self.family.clone(),
+            attachment: self.supports_attachment,
+            reasoning: self.supports_reasoning,
+            tool_call: self.supports_tool_call,
+            structured_output: self.supports_structured_output,
+            temperature: self.supports_temperature,
+            knowledge: None,
+            release_date: None,
+            last_updated: None,
+            modalities: None,
+            open_weights: false,
+            cost: Some(CostInfo {
+                input: self.cost_input,
+                output: self.cost_output,
+            }),
+            limit: Some(LimitInfo {
+                context: self.context_window,
+                output: self.max_output_tokens,
+            }),
+            default: has_defaults.then_some(DefaultParams {
+                temperature: self.default_temperature,
+                top_p: self.default_top_p,
+                max_tokens: self.default_max_tokens,
+            }),
+        }
+    }
+}
+
+/// 注册表更新来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistryUpdateSource {
+    /// 从 models.dev 拉取
+    Remote,
+    /// 从本地文件导入
+    Import,
+}
+
+/// `models-registry:updated` 事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryUpdatedEvent {
+    /// 新数据的哈希
+    pub hash: String,
+    /// 更新前的哈希（之前无数据时为空）
+    pub previous_hash: Option<String>,
+    pub provider_count: usize,
+    pub model_count: usize,
+    pub source: RegistryUpdateSource,
+}
@@ -0,0 +1,436 @@
+//! Jupyter Notebook（.ipynb）读写
+//!
+//! 把 nbformat 4 的 JSON 转换为按单元格组织的结构：多行文本（`source`、流输出、
+//! MIME 数据）合并为单个字符串，前端和 Agent 可以逐个单元格编辑。
+//! 写回时沿用文件原有的顶层元数据和 nbformat 版本，多行文本按 Jupyter 的习惯
+//! 拆回行数组，以 1 个空格缩进输出，尽量减少版本控制中的无关改动。
+
+use crate::error::AxonError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 新建 Notebook 使用的版本（4.5 起单元格必须带 id）
+const NBFORMAT: u64 = 4;
+const NBFORMAT_MINOR: u64 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CellType {
+    Code,
+    Markdown,
+    Raw,
+}
+
+/// 单元格输出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookOutput {
+    /// stream / display_data / execute_result / error
+    pub output_type: String,
+    /// 流名称（stdout / stderr）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 流输出文本
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// MIME 数据，JSON 类型保持原样，其余合并为字符串
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Map<String, Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_count: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ename: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evalue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traceback: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotebookCell {
+    /// 单元格 id，新单元格可以为空（写入 4.5 及以上版本时自动生成）
+    #[serde(default)]
+    pub id: Option<String>,
+    pub cell_type: CellType,
+    pub source: String,
+    #[serde(default = "empty_object")]
+    pub metadata: Value,
+    /// 仅代码单元格
+    #[serde(default)]
+    pub execution_count: Option<i64>,
+    /// 仅代码单元格
+    #[serde(default)]
+    pub outputs: Vec<NotebookOutput>,
+    /// Markdown / Raw 单元格的附件
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notebook {
+    pub nbformat: u64,
+    pub nbformat_minor: u64,
+    /// 内核语言（来自 kernelspec / language_info）
+    pub language: Option<String>,
+    pub metadata: Value,
+    pub cells: Vec<NotebookCell>,
+}
+
+fn empty_object() -> Value {
+    json!({})
+}
+
+/// 读取 Notebook 文件
+pub fn read(path: &Path) -> Result<Notebook, AxonError> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| AxonError::io("读取 Notebook 失败", &e))?;
+    parse(&text)
+}
+
+/// 写入单元格，文件已存在时保留其顶层元数据和版本
+pub fn write(path: &Path, cells: &[NotebookCell]) -> Result<Notebook, AxonError> {
+    let existing = match std::fs::read_to_string(path) {
+        Ok(text) => Some(
+            serde_json::from_str::<Value>(&text)
+                .map_err(|e| AxonError::invalid_data(format!("Notebook 不是有效的 JSON: {}", e)))?,
+        ),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(AxonError::io("读取 Notebook 失败", &e)),
+    };
+    let text = serialize(existing, cells)?;
+    std::fs::write(path, &text).map_err(|e| AxonError::io("写入 Notebook 失败", &e))?;
+    parse(&text)
+}
+
+/// 解析 Notebook JSON
+pub fn parse(text: &str) -> Result<Notebook, AxonError> {
+    let root: Value = serde_json::from_str(text)
+        .map_err(|e| AxonError::invalid_data(format!("Notebook 不是有效的 JSON: {}", e)))?;
+    let nbformat = root.get("nbformat").and_then(Value::as_u64).unwrap_or(0);
+    if nbformat != NBFORMAT {
+        return Err(AxonError::unsupported(format!(
+            "不支持的 Notebook 版本: {}（仅支持 nbformat 4）",
+            nbformat
+        )));
+    }
+    let metadata = root.get("metadata").cloned().unwrap_or_else(empty_object);
+    let language = metadata
+        .pointer("/kernelspec/language")
+        .or_else(|| metadata.pointer("/language_info/name"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let cells = root
+        .get("cells")
+        .and_then(Value::as_array)
+        .map(|cells| cells.iter().map(parse_cell).collect::<Result<Vec<_>, _>>())
+        .transpose()?
+        .unwrap_or_default();
+
+    Ok(Notebook {
+        nbformat,
+        nbformat_minor: root
+            .get("nbformat_minor")
+            .and_then(Value::as_u64)
+            .unwrap_or(0),
+        language,
+        metadata,
+        cells,
+    })
+}
+
+fn parse_cell(cell: &Value) -> Result<NotebookCell, AxonError> {
+    let cell_type = cell
+        .get("cell_type")
+        .cloned()
+        .and_then(|t| serde_json::from_value(t).ok())
+        .ok_or_else(|| AxonError::invalid_data("Notebook 单元格缺少有效的 cell_type"))?;
+    let outputs = cell
+        .get("outputs")
+        .and_then(Value::as_array)
+        .map(|outputs| outputs.iter().map(parse_output).collect())
+        .unwrap_or_default();
+    Ok(NotebookCell {
+        id: cell.get("id").and_then(Value::as_str).map(str::to_string),
+        cell_type,
+        source: join_text(cell.get("source")),
+        metadata: cell.get("metadata").cloned().unwrap_or_else(empty_object),
+        execution_count: cell.get("execution_count").and_then(Value::as_i64),
+        outputs,
+        attachments: cell.get("attachments").cloned(),
+    })
+}
+
+fn parse_output(output: &Value) -> NotebookOutput {
+    let string = |key: &str| output.get(key).and_then(Value::as_str).map(str::to_string);
+    let data = output.get("data").and_then(Value::as_object).map(|data| {
+        data.iter()
+            .map(|(mime, value)| {
+                let value = if is_json_mime(mime) {
+                    value.clone()
+                } else {
+                    Value::String(join_text(Some(value)))
+                };
+                (mime.clone(), value)
+            })
+            .collect()
+    });
+    NotebookOutput {
+        output_type: string("output_type").unwrap_or_default(),
+        name: string("name"),
+        text: output.get("text").map(|text| join_text(Some(text))),
+        data,
+        metadata: output.get("metadata").cloned(),
+        execution_count: output.get("execution_count").and_then(Value::as_i64),
+        ename: string("ename"),
+        evalue: string("evalue"),
+        traceback: output
+            .get("traceback")
+            .and_then(Value::as_array)
+            .map(|lines| {
+                lines
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            }),
+    }
+}
+
+/// 生成 Notebook JSON，`existing` 为文件原内容
+fn serialize(existing: Option<Value>, cells: &[NotebookCell]) -> Result<String, AxonError> {
+    let mut root = match existing {
+        Some(Value::Object(root)) => root,
+        Some(_) => return Err(AxonError::invalid_data("Notebook 顶层不是 JSON 对象")),
+        None => Map::from_iter([
+            ("metadata".to_string(), empty_object()),
+            ("nbformat".to_string(), json!(NBFORMAT)),
+            ("nbformat_minor".to_string(), json!(NBFORMAT_MINOR)),
+        ]),
+    };
+    let minor = root
+        .get("nbformat_minor")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+
+    let mut ids = HashSet::new();
+    let mut raw_cells = Vec::with_capacity(cells.len());
+    for cell in cells {
+        let mut raw = Map::new();
+        // 4.5 以下版本不允许 id 字段
+        if minor >= 5 {
+            let id = match &cell.id {
+                Some(id) if !id.is_empty() => id.clone(),
+                _ => new_cell_id(&ids),
+            };
+            if !ids.insert(id.clone()) {
+                return Err(AxonError::invalid_input(format!("单元格 id 重复: {}", id)));
+            }
+            raw.insert("id".to_string(), Value::String(id));
+        }
+        raw.insert(
+            "cell_type".to_string(),
+            serde_json::to_value(cell.cell_type).unwrap_or_default(),
+        );
+        raw.insert("metadata".to_string(), cell.metadata.clone());
+        raw.insert("source".to_string(), split_lines(&cell.source));
+        if cell.cell_type == CellType::Code {
+            raw.insert("execution_count".to_string(), json!(cell.execution_count));
+            raw.insert(
+                "outputs".to_string(),
+                Value::Array(cell.outputs.iter().map(output_to_raw).collect()),
+            );
+        } else if let Some(attachments) = &cell.attachments {
+            raw.insert("attachments".to_string(), attachments.clone());
+        }
+        raw_cells.push(Value::Object(raw));
+    }
+    root.insert("cells".to_string(), Value::Array(raw_cells));
+
+    let mut buffer = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    Value::Object(root)
+        .serialize(&mut serializer)
+        .map_err(|e| format!("序列化 Notebook 失败: {}", e))?;
+    buffer.push(b'\n');
+    String::from_utf8(buffer).map_err(|e| AxonError::internal(e.to_string()))
+}
+
+fn output_to_raw(output: &NotebookOutput) -> Value {
+    let mut raw = Map::new();
+    raw.insert("output_type".to_string(), json!(output.output_type));
+    let mut put = |key: &str, value: Option<Value>| {
+        if let Some(value) = value {
+            raw.insert(key.to_string(), value);
+        }
+    };
+    put("name", output.name.clone().map(Value::String));
+    put("text", output.text.as_deref().map(split_lines));
+    put(
+        "data",
+        output.data.as_ref().map(|data| {
+            Value::Object(
+                data.iter()
+                    .map(|(mime, value)| {
+                        let value = match value {
+                            Value::String(text) if !is_json_mime(mime) => split_lines(text),
+                            other => other.clone(),
+                        };
+                        (mime.clone(), value)
+                    })
+                    .collect(),
+            )
+        }),
+    );
+    put("metadata", output.metadata.clone());
+    if output.output_type == "execute_result" {
+        put("execution_count", Some(json!(output.execution_count)));
+    }
+    put("ename", output.ename.clone().map(Value::String));
+    put("evalue", output.evalue.clone().map(Value::String));
+    put(
+        "traceback",
+        output.traceback.as_ref().map(|lines| json!(lines)),
+    );
+    Value::Object(raw)
+}
+
+/// nbformat 的多行文本可以是字符串或字符串数组
+fn join_text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect(),
+        _ => String::new(),
+    }
+}
+
+/// 拆分为保留换行符的行数组（与 Jupyter 保存的格式一致）
+fn split_lines(text: &str) -> Value {
+    Value::Array(
+        text.split_inclusive('\n')
+            .map(|line| Value::String(line.to_string()))
+            .collect(),
+    )
+}
+
+fn is_json_mime(mime: &str) -> bool {
+    mime == "application/json" || (mime.starts_with("application/") && mime.ends_with("+json"))
+}
+
+fn new_cell_id(existing: &HashSet<String>) -> String {
+    loop {
+        let id = format!("{:08x}", rand::random::<u32>());
+        if !existing.contains(&id) {
+            return id;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "id": "intro",
+   "metadata": {},
+   "source": [
+    "# 标题\n",
+    "说明"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 1,
+   "id": "calc",
+   "metadata": {
+    "tags": ["setup"]
+   },
+   "outputs": [
+    {
+     "name": "stdout",
+     "output_type": "stream",
+     "text": [
+      "1\n",
+      "2\n"
+     ]
+    },
+    {
+     "data": {
+      "application/json": {"a": 1},
+      "text/plain": [
+       "3"
+      ]
+     },
+     "execution_count": 1,
+     "metadata": {},
+     "output_type": "execute_result"
+    }
+   ],
+   "source": [
+    "print(1)\n",
+    "print(2)\n",
+    "3"
+   ]
+  }
+ ],
+ "metadata": {
+  "kernelspec": {
+   "display_name": "Python 3",
+   "language": "python",
+   "name": "python3"
+  }
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5
+}
+"##;
+
+    #[test]
+    fn parses_cells_and_outputs() {
+        let notebook = parse(SAMPLE).unwrap();
+        assert_eq!(notebook.language.as_deref(), Some("python"));
+        assert_eq!(notebook.cells.len(), 2);
+        assert_eq!(notebook.cells[0].source, "# 标题\n说明");
+        let code = &notebook.cells[1];
+        assert_eq!(code.execution_count, Some(1));
+        assert_eq!(code.outputs[0].text.as_deref(), Some("1\n2\n"));
+        let data = code.outputs[1].data.as_ref().unwrap();
+        assert_eq!(data["text/plain"], json!("3"));
+        assert_eq!(data["application/json"], json!({"a": 1}));
+    }
+
+    #[test]
+    fn round_trips_and_preserves_metadata() {
+        let notebook = parse(SAMPLE).unwrap();
+        let existing: Value = serde_json::from_str(SAMPLE).unwrap();
+        let text = serialize(Some(existing.clone()), &notebook.cells).unwrap();
+        let written: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(written, existing);
+
+        let mut cells = notebook.cells;
+        cells[0].source = "改过的说明".to_string();
+        cells.push(NotebookCell {
+            id: None,
+            cell_type: CellType::Code,
+            source: String::new(),
+            metadata: empty_object(),
+            execution_count: None,
+            outputs: Vec::new(),
+            attachments: None,
+        });
+        let text = serialize(Some(existing), &cells).unwrap();
+        let reparsed = parse(&text).unwrap();
+        assert_eq!(reparsed.metadata["kernelspec"]["name"], json!("python3"));
+        assert_eq!(reparsed.cells[0].source, "改过的说明");
+        assert_eq!(reparsed.cells[2].id.as_ref().map(String::len), Some(8));
+    }
+}
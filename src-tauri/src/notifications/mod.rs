@@ -0,0 +1,103 @@
+//! 系统通知
+//!
+//! 封装 tauri-plugin-notification：`notify` 直接发送系统通知；
+//! 开启"任务完成通知"后，后台任务、下载和工作流执行结束时如果主窗口
+//! 未聚焦、已最小化或已隐藏到托盘，会自动发送通知。
+//! 警告和错误通知同时请求任务栏 / Dock 提醒。
+
+use crate::error::AxonError;
+use crate::settings::SettingsManager;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, UserAttentionType};
+use tauri_plugin_notification::NotificationExt;
+use tracing::{debug, warn};
+
+/// 通知类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationKind {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+/// 系统通知管理器
+pub struct NotificationManager {
+    settings: Arc<SettingsManager>,
+    app_handle: RwLock<Option<AppHandle>>,
+}
+
+impl NotificationManager {
+    pub fn new(settings: Arc<SettingsManager>) -> Arc<Self> {
+        Arc::new(Self {
+            settings,
+            app_handle: RwLock::new(None),
+        })
+    }
+
+    pub fn initialize(&self, handle: AppHandle) {
+        *self.app_handle.write() = Some(handle);
+    }
+
+    /// 发送系统通知
+    pub fn notify(&self, title: &str, body: &str, kind: NotificationKind) -> Result<(), AxonError> {
+        let handle = self
+            .app_handle
+            .read()
+            .clone()
+            .ok_or_else(|| AxonError::unavailable("通知服务尚未初始化"))?;
+
+        handle
+            .notification()
+            .builder()
+            .title(title)
+            .body(body)
+            .show()
+            .map_err(|e| AxonError::external(format!("发送系统通知失败: {}", e)))?;
+
+        let attention = match kind {
+            NotificationKind::Error => Some(UserAttentionType::Critical),
+            NotificationKind::Warning => Some(UserAttentionType::Informational),
+            _ => None,
+        };
+        if let (Some(attention), Some(window)) = (attention, handle.get_webview_window("main")) {
+            let _ = window.request_user_attention(Some(attention));
+        }
+        debug!("已发送系统通知: {}", title);
+        Ok(())
+    }
+
+    /// 任务结束时发送通知（需开启设置，且主窗口不在前台）
+    pub fn notify_task_finished(&self, title: &str, body: &str, success: bool) {
+        if !self.settings.get_notification_settings().task_completion || !self.window_inactive() {
+            return;
+        }
+        let kind = if success {
+            NotificationKind::Success
+        } else {
+            NotificationKind::Error
+        };
+        if let Err(e) = self.notify(title, body, kind) {
+            warn!("{}", e);
+        }
+    }
+
+    /// 主窗口未聚焦、已最小化或已隐藏
+    fn window_inactive(&self) -> bool {
+        let Some(window) = self
+            .app_handle
+            .read()
+            .as_ref()
+            .and_then(|handle| handle.get_webview_window("main"))
+        else {
+            return false;
+        };
+        !window.is_visible().unwrap_or(true)
+            || window.is_minimized().unwrap_or(false)
+            || !window.is_focused().unwrap_or(true)
+    }
+}
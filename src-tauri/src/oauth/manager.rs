@@ -0,0 +1,615 @@
+//! OAuth 授权管理器
+
+use super::types::*;
+use crate::opencode::auth::{read_auth_json, write_auth_json};
+use crate::opencode::ProviderAuth;
+use crate::settings::SettingsManager;
+use crate::utils::paths::get_app_data_dir;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use parking_lot::RwLock;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_opener::OpenerExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Notify;
+use tracing::{debug, error, info, warn};
+
+/// 已登记的 OAuth 客户端配置文件
+const CLIENTS_FILE: &str = "oauth_clients.json";
+
+/// 回调路径
+const CALLBACK_PATH: &str = "/callback";
+
+/// 等待浏览器回调的超时时间
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// token 端点请求超时时间
+const TOKEN_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 检查 token 是否即将过期的间隔
+const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 在过期前多久刷新（毫秒）
+const REFRESH_MARGIN_MS: i64 = 5 * 60 * 1000;
+
+/// 回调请求头的最大长度
+const MAX_CALLBACK_REQUEST: usize = 16 * 1024;
+
+/// 进行中的授权会话
+struct PendingSession {
+    provider_id: String,
+    cancel: Arc<Notify>,
+}
+
+/// 授权流程参数
+struct FlowContext {
+    session_id: String,
+    provider_id: String,
+    config: OAuthClientConfig,
+    redirect_uri: String,
+    state: String,
+    verifier: String,
+}
+
+/// OAuth 授权管理器
+pub struct OAuthManager {
+    settings: Arc<SettingsManager>,
+    sessions: RwLock<HashMap<String, PendingSession>>,
+    /// 刷新失败的服务商及失败时的过期时间，过期时间变化前不再重试
+    refresh_failures: RwLock<HashMap<String, i64>>,
+    refresh_started: AtomicBool,
+    http: reqwest::Client,
+}
+
+impl OAuthManager {
+    pub fn new(settings: Arc<SettingsManager>) -> Arc<Self> {
+        Arc::new(Self {
+            settings,
+            sessions: RwLock::new(HashMap::new()),
+            refresh_failures: RwLock::new(HashMap::new()),
+            refresh_started: AtomicBool::new(false),
+            http: reqwest::Client::new(),
+        })
+    }
+
+    /// 获取之前授权成功时保存的客户端配置
+    pub fn saved_client(&self, provider_id: &str) -> Option<OAuthClientConfig> {
+        read_clients().remove(provider_id)
+    }
+
+    /// 启动授权流程：监听回调端口、打开授权页，后台等待回调并换取 token
+    pub async fn start(
+        self: &Arc<Self>,
+        app: AppHandle,
+        provider_id: String,
+        config: OAuthClientConfig,
+    ) -> Result<OAuthSession, String> {
+        validate_config(&config)?;
+
+        // 同一服务商只保留一个进行中的授权
+        self.cancel_provider(&provider_id);
+
+        let listener = TcpListener::bind(("127.0.0.1", config.redirect_port.unwrap_or(0)))
+            .await
+            .map_err(|e| format!("监听 OAuth 回调端口失败: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("获取 OAuth 回调端口失败: {}", e))?
+            .port();
+
+        let flow = FlowContext {
+            session_id: format!("oauth-{}", random_token(9)),
+            provider_id: provider_id.clone(),
+            redirect_uri: format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH),
+            state: random_token(16),
+            verifier: random_token(32),
+            config,
+        };
+        let auth_url = build_auth_url(&flow)?;
+
+        let cancel = Arc::new(Notify::new());
+        self.sessions.write().insert(
+            flow.session_id.clone(),
+            PendingSession {
+                provider_id: provider_id.clone(),
+                cancel: Arc::clone(&cancel),
+            },
+        );
+
+        let session = OAuthSession {
+            session_id: flow.session_id.clone(),
+            provider_id,
+            auth_url: auth_url.clone(),
+            redirect_uri: flow.redirect_uri.clone(),
+        };
+        info!(
+            "启动 OAuth 授权: provider={}, session={}, redirect={}",
+            session.provider_id, session.session_id, session.redirect_uri
+        );
+
+        // 浏览器打开失败时前端仍可展示 auth_url 让用户手动打开
+        if let Err(e) = app.opener().open_url(&auth_url, None::<&str>) {
+            warn!("打开授权页失败: {}", e);
+        }
+        emit_progress(
+            &app,
+            Some(&flow.session_id),
+            &flow.provider_id,
+            OAuthStage::WaitingForCallback,
+            None,
+        );
+
+        let manager = Arc::clone(self);
+        tauri::async_runtime::spawn(async move {
+            manager.run_flow(app, flow, listener, cancel).await;
+        });
+
+        Ok(session)
+    }
+
+    /// 取消授权会话，会话不存在时返回 false
+    pub fn cancel(&self, session_id: &str) -> bool {
+        match self.sessions.read().get(session_id) {
+            Some(session) => {
+                debug!("取消 OAuth 授权: {}", session_id);
+                session.cancel.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn cancel_provider(&self, provider_id: &str) {
+        for session in self.sessions.read().values() {
+            if session.provider_id == provider_id {
+                session.cancel.notify_one();
+            }
+        }
+    }
+
+    async fn run_flow(
+        &self,
+        app: AppHandle,
+        flow: FlowContext,
+        listener: TcpListener,
+        cancel: Arc<Notify>,
+    ) {
+        let outcome = tokio::select! {
+            result = wait_for_callback(&listener, &flow.state) => result.map(Some),
+            _ = cancel.notified() => Ok(None),
+            _ = tokio::time::sleep(CALLBACK_TIMEOUT) => Err("等待授权回调超时".to_string()),
+        };
+        // 尽早释放回调端口
+        drop(listener);
+
+        let (stage, message) = match outcome {
+            Ok(None) => (OAuthStage::Cancelled, None),
+            Err(e) => (OAuthStage::Failed, Some(e)),
+            Ok(Some(code)) => {
+                emit_progress(
+                    &app,
+                    Some(&flow.session_id),
+                    &flow.provider_id,
+                    OAuthStage::ExchangingCode,
+                    None,
+                );
+                match self.complete(&flow, &code).await {
+                    Ok(()) => (OAuthStage::Completed, None),
+                    Err(e) => (OAuthStage::Failed, Some(e)),
+                }
+            }
+        };
+
+        match (&stage, &message) {
+            (OAuthStage::Failed, Some(e)) => {
+                error!("OAuth 授权失败: provider={}, {}", flow.provider_id, e)
+            }
+            _ => info!("OAuth 授权结束: provider={}, {:?}", flow.provider_id, stage),
+        }
+        emit_progress(
+            &app,
+            Some(&flow.session_id),
+            &flow.provider_id,
+            stage,
+            message,
+        );
+        self.sessions.write().remove(&flow.session_id);
+    }
+
+    /// 换取 token 并保存
+    async fn complete(&self, flow: &FlowContext, code: &str) -> Result<(), String> {
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", flow.redirect_uri.as_str()),
+            ("code_verifier", flow.verifier.as_str()),
+        ];
+        let tokens = self.request_tokens(&flow.config, &mut params).await?;
+
+        store_tokens(&flow.provider_id, &tokens, None)?;
+
+        let mut clients = read_clients();
+        clients.insert(flow.provider_id.clone(), flow.config.clone());
+        write_clients(&clients)?;
+
+        self.refresh_failures.write().remove(&flow.provider_id);
+        self.mark_connected(&flow.provider_id);
+        Ok(())
+    }
+
+    /// 将对应的用户服务商标记为已连接
+    fn mark_connected(&self, provider_id: &str) {
+        let mut settings = self.settings.get_settings();
+        let mut changed = false;
+        for provider in settings
+            .providers
+            .iter_mut()
+            .filter(|p| p.registry_id == provider_id)
+        {
+            if let ProviderAuth::OAuth { connected, .. } = &mut provider.auth {
+                *connected = true;
+                provider.updated_at = chrono::Utc::now().to_rfc3339();
+                changed = true;
+            }
+        }
+        if changed {
+            if let Err(e) = self.settings.set_settings(settings) {
+                warn!("更新服务商连接状态失败: {}", e);
+            }
+        }
+    }
+
+    /// 请求 token 端点
+    async fn request_tokens<'a>(
+        &self,
+        config: &'a OAuthClientConfig,
+        params: &mut Vec<(&'a str, &'a str)>,
+    ) -> Result<TokenResponse, String> {
+        params.push(("client_id", config.client_id.as_str()));
+        if let Some(secret) = &config.client_secret {
+            params.push(("client_secret", secret.as_str()));
+        }
+
+        let response = self
+            .http
+            .post(&config.token_url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(params)
+            .timeout(TOKEN_REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| format!("请求 token 端点失败: {}", e))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| format!("读取 token 响应失败: {}", e))?;
+        if !status.is_success() {
+            let detail: String = body.chars().take(200).collect();
+            return Err(format!(
+                "token 端点返回错误 ({}): {}",
+                status.as_u16(),
+                detail
+            ));
+        }
+
+        serde_json::from_str(&body).map_err(|e| format!("解析 token 响应失败: {}", e))
+    }
+
+    /// 启动后台刷新循环（重复调用无效）
+    pub fn start_refresh_loop(self: &Arc<Self>, app: AppHandle) {
+        if self.refresh_started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let manager = Arc::clone(self);
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(REFRESH_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                manager.refresh_expiring(&app).await;
+            }
+        });
+        debug!("OAuth token 自动刷新已启动");
+    }
+
+    /// 刷新即将过期的 token
+    async fn refresh_expiring(&self, app: &AppHandle) {
+        let clients = read_clients();
+        if clients.is_empty() {
+            return;
+        }
+        let auth_data = match read_auth_json() {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("读取 auth.json 失败，跳过 token 刷新: {}", e);
+                return;
+            }
+        };
+        let now = chrono::Utc::now().timestamp_millis();
+
+        for (provider_id, config) in clients {
+            let Some(entry) = auth_data.get(&provider_id) else {
+                continue;
+            };
+            if entry.get("type").and_then(|v| v.as_str()) != Some("oauth") {
+                continue;
+            }
+            let Some(refresh) = entry
+                .get("refresh")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+            else {
+                continue;
+            };
+            // 过期时间未知（0）时不主动刷新
+            let expires = entry.get("expires").and_then(|v| v.as_i64()).unwrap_or(0);
+            if expires == 0 || expires - now > REFRESH_MARGIN_MS {
+                continue;
+            }
+            if self.refresh_failures.read().get(&provider_id) == Some(&expires) {
+                continue;
+            }
+
+            let mut params = vec![("grant_type", "refresh_token"), ("refresh_token", refresh)];
+            let result = match self.request_tokens(&config, &mut params).await {
+                Ok(tokens) => store_tokens(&provider_id, &tokens, Some(refresh)),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(()) => {
+                    info!("已刷新 OAuth token: {}", provider_id);
+                    self.refresh_failures.write().remove(&provider_id);
+                    emit_progress(app, None, &provider_id, OAuthStage::Refreshed, None);
+                }
+                Err(e) => {
+                    warn!("刷新 OAuth token 失败: provider={}, {}", provider_id, e);
+                    self.refresh_failures
+                        .write()
+                        .insert(provider_id.clone(), expires);
+                    emit_progress(app, None, &provider_id, OAuthStage::Failed, Some(e));
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// 辅助函数
+// ============================================================================
+
+fn emit_progress(
+    app: &AppHandle,
+    session_id: Option<&str>,
+    provider_id: &str,
+    stage: OAuthStage,
+    message: Option<String>,
+) {
+    let payload = OAuthProgress {
+        session_id: session_id.map(String::from),
+        provider_id: provider_id.to_string(),
+        stage,
+        message,
+    };
+    if let Err(e) = app.emit(EVENT_OAUTH_PROGRESS, &payload) {
+        warn!("发送 OAuth 进度事件失败: {}", e);
+    }
+}
+
+fn validate_config(config: &OAuthClientConfig) -> Result<(), String> {
+    if config.client_id.trim().is_empty() {
+        return Err("OAuth client_id 不能为空".to_string());
+    }
+    for (label, url) in [("授权", &config.auth_url), ("token", &config.token_url)] {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| format!("无效的 OAuth {}地址 {}: {}", label, url, e))?;
+        if !matches!(parsed.scheme(), "https" | "http") {
+            return Err(format!("OAuth {}地址必须为 http(s): {}", label, url));
+        }
+    }
+    Ok(())
+}
+
+/// 生成 URL 安全的随机字符串
+fn random_token(bytes: usize) -> String {
+    let mut buffer = vec![0u8; bytes];
+    rand::thread_rng().fill_bytes(&mut buffer);
+    URL_SAFE_NO_PAD.encode(buffer)
+}
+
+/// 构建授权页地址（PKCE S256）
+fn build_auth_url(flow: &FlowContext) -> Result<String, String> {
+    let config = &flow.config;
+    let mut url = reqwest::Url::parse(&config.auth_url)
+        .map_err(|e| format!("无效的 OAuth 授权地址: {}", e))?;
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(flow.verifier.as_bytes()));
+
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &config.client_id)
+            .append_pair("redirect_uri", &flow.redirect_uri)
+            .append_pair("state", &flow.state)
+            .append_pair("code_challenge", &challenge)
+            .append_pair("code_challenge_method", "S256");
+        if !config.scopes.is_empty() {
+            query.append_pair("scope", &config.scopes.join(" "));
+        }
+        for (key, value) in &config.extra_params {
+            query.append_pair(key, value);
+        }
+    }
+
+    Ok(url.to_string())
+}
+
+/// 等待浏览器回调，返回授权码
+async fn wait_for_callback(listener: &TcpListener, expected_state: &str) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| format!("接受 OAuth 回调连接失败: {}", e))?;
+
+        let Some(target) = read_request_target(&mut stream).await else {
+            continue;
+        };
+        let Ok(url) = reqwest::Url::parse(&format!("http://127.0.0.1{}", target)) else {
+            respond(&mut stream, "400 Bad Request", "请求无效").await;
+            continue;
+        };
+        // 浏览器可能额外请求 favicon 等资源
+        if url.path() != CALLBACK_PATH {
+            respond(&mut stream, "404 Not Found", "页面不存在").await;
+            continue;
+        }
+
+        let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        if let Some(error) = params.get("error") {
+            let reason = params.get("error_description").unwrap_or(error);
+            respond(&mut stream, "200 OK", "授权未完成，可以关闭此页面。").await;
+            return Err(format!("授权被拒绝: {}", reason));
+        }
+        if params.get("state").map(String::as_str) != Some(expected_state) {
+            respond(
+                &mut stream,
+                "400 Bad Request",
+                "授权校验失败（state 不匹配）。",
+            )
+            .await;
+            return Err("OAuth 回调 state 不匹配".to_string());
+        }
+        let Some(code) = params.get("code").filter(|c| !c.is_empty()) else {
+            respond(&mut stream, "400 Bad Request", "回调缺少授权码。").await;
+            return Err("OAuth 回调缺少授权码".to_string());
+        };
+
+        respond(
+            &mut stream,
+            "200 OK",
+            "授权成功，可以关闭此页面并返回 Axon。",
+        )
+        .await;
+        return Ok(code.clone());
+    }
+}
+
+/// 读取 HTTP 请求行，返回请求目标（路径 + 查询）
+async fn read_request_target(stream: &mut TcpStream) -> Option<String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 2048];
+    while !buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = tokio::time::timeout(Duration::from_secs(10), stream.read(&mut chunk))
+            .await
+            .ok()?
+            .ok()?;
+        if read == 0 || buffer.len() + read > MAX_CALLBACK_REQUEST {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&buffer);
+    let mut parts = request.lines().next()?.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) if target.starts_with('/') => Some(target.to_string()),
+        _ => None,
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Axon</title></head>\
+         <body style=\"font-family: sans-serif; text-align: center; padding-top: 80px;\">\
+         <p>{}</p></body></html>",
+        message
+    );
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// 将 token 写入 auth.json（OpenCode 的 oauth 条目格式）
+fn store_tokens(
+    provider_id: &str,
+    tokens: &TokenResponse,
+    previous_refresh: Option<&str>,
+) -> Result<(), String> {
+    // 刷新响应可能不返回新的 refresh token，此时沿用旧值
+    let refresh = tokens
+        .refresh_token
+        .as_deref()
+        .or(previous_refresh)
+        .unwrap_or_default();
+    let expires = tokens
+        .expires_in
+        .map(|secs| chrono::Utc::now().timestamp_millis() + secs as i64 * 1000)
+        .unwrap_or(0);
+
+    let mut auth_data = read_auth_json()?;
+    if !auth_data.is_object() {
+        auth_data = serde_json::json!({});
+    }
+    if let Some(obj) = auth_data.as_object_mut() {
+        obj.insert(
+            provider_id.to_string(),
+            serde_json::json!({
+                "type": "oauth",
+                "refresh": refresh,
+                "access": tokens.access_token,
+                "expires": expires,
+            }),
+        );
+    }
+    write_auth_json(&auth_data)
+}
+
+fn clients_path() -> Option<PathBuf> {
+    get_app_data_dir().map(|dir| dir.join(CLIENTS_FILE))
+}
+
+fn read_clients() -> HashMap<String, OAuthClientConfig> {
+    let Some(path) = clients_path() else {
+        return HashMap::new();
+    };
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return HashMap::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        warn!("解析 {} 失败: {}", CLIENTS_FILE, e);
+        HashMap::new()
+    })
+}
+
+/// 写入客户端配置（可能包含 client_secret，权限与 auth.json 一致）
+fn write_clients(clients: &HashMap<String, OAuthClientConfig>) -> Result<(), String> {
+    let path = clients_path().ok_or_else(|| "应用数据目录未初始化".to_string())?;
+    let content = serde_json::to_string_pretty(clients)
+        .map_err(|e| format!("序列化 OAuth 客户端配置失败: {}", e))?;
+    std::fs::write(&path, content).map_err(|e| format!("写入 OAuth 客户端配置失败: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("设置 OAuth 客户端配置权限失败: {}", e))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+//! 服务商 OAuth 授权模块
+//!
+//! 实现授权码 + PKCE 流程，供 `ProviderAuth::OAuth` 类型的服务商登录使用。
+//!
+//! ## 流程
+//!
+//! 1. 在 127.0.0.1 上监听回调端口，生成 PKCE verifier 和 state
+//! 2. 在系统浏览器中打开服务商授权页
+//! 3. 收到回调后校验 state，用授权码换取 token
+//! 4. 将 token 写入 OpenCode 的 auth.json（仅所有者可读写），
+//!    客户端配置保存到 oauth_clients.json 以便后续刷新
+//! 5. 后台定期检查，在 access token 过期前使用 refresh token 自动刷新
+//!
+//! 各阶段通过 `oauth:progress` 事件通知前端。
+
+mod manager;
+mod types;
+
+pub use manager::OAuthManager;
+pub use types::*;
@@ -0,0 +1,78 @@
+//! OAuth 相关类型定义
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// OAuth 进度事件
+pub const EVENT_OAUTH_PROGRESS: &str = "oauth:progress";
+
+/// 服务商的 OAuth 客户端配置（授权码 + PKCE 流程）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthClientConfig {
+    /// 授权页地址
+    pub auth_url: String,
+    /// 换取 / 刷新 token 的地址
+    pub token_url: String,
+    pub client_id: String,
+    /// 机密客户端的密钥（公共客户端依赖 PKCE，无需设置）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// 附加到授权 URL 的额外参数
+    #[serde(default)]
+    pub extra_params: HashMap<String, String>,
+    /// 固定的回调端口（服务商要求预先登记回调地址时使用，默认随机端口）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_port: Option<u16>,
+}
+
+/// OAuth 流程阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuthStage {
+    /// 已打开授权页，等待浏览器回调
+    WaitingForCallback,
+    /// 已收到授权码，正在换取 token
+    ExchangingCode,
+    /// 授权完成，token 已保存
+    Completed,
+    /// token 已自动刷新
+    Refreshed,
+    Failed,
+    Cancelled,
+}
+
+/// OAuth 进度事件负载
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthProgress {
+    /// 自动刷新时为空
+    pub session_id: Option<String>,
+    pub provider_id: String,
+    pub stage: OAuthStage,
+    pub message: Option<String>,
+}
+
+/// 已启动的 OAuth 会话
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthSession {
+    pub session_id: String,
+    pub provider_id: String,
+    /// 授权页地址（浏览器未能自动打开时可由前端展示）
+    pub auth_url: String,
+    pub redirect_uri: String,
+}
+
+/// token 端点响应
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TokenResponse {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// 有效期（秒）
+    #[serde(default)]
+    pub expires_in: Option<u64>,
+}
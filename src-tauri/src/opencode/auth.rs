@@ -0,0 +1,110 @@
+//! OpenCode auth.json 读写
+//!
+//! auth.json 保存各服务商的认证信息（API Key、OAuth Token 等），
+//! 由 OpenCode 服务读取，Axon 负责写入。
+
+use crate::utils::paths::get_app_data_dir;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderAuthStatus {
+    /// Provider ID
+    pub provider_id: String,
+    /// 是否已认证
+    pub authenticated: bool,
+    /// 认证类型 (api/oauth/wellknown)
+    pub auth_type: Option<String>,
+}
+
+/// 获取 auth.json 文件路径
+pub fn get_auth_json_path() -> Result<std::path::PathBuf, String> {
+    let app_data_dir = get_app_data_dir()
+        .ok_or_else(|| "应用数据目录未初始化".to_string())?;
+    // OpenCode 的 auth.json 位于 <app_data_dir>/opencode/auth.json
+    // 因为 Axon 设置了 XDG_DATA_HOME=app_data_dir，
+    // xdg-basedir 会在其下创建 /opencode 子目录
+    Ok(app_data_dir.join("opencode").join("auth.json"))
+}
+
+/// 读取 auth.json 内容
+pub fn read_auth_json() -> Result<serde_json::Value, String> {
+    let auth_path = get_auth_json_path()?;
+    
+    if !auth_path.exists() {
+        debug!("auth.json 不存在，返回空对象");
+        return Ok(serde_json::json!({}));
+    }
+    
+    let content = std::fs::read_to_string(&auth_path)
+        .map_err(|e| format!("读取 auth.json 失败: {}", e))?;
+    
+    serde_json::from_str(&content)
+        .map_err(|e| format!("解析 auth.json 失败: {}", e))
+}
+
+/// 写入 auth.json 内容
+pub fn write_auth_json(data: &serde_json::Value) -> Result<(), String> {
+    let auth_path = get_auth_json_path()?;
+    
+    // 确保目录存在
+    if let Some(parent) = auth_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("创建 auth.json 目录失败: {}", e))?;
+    }
+    
+    let content = serde_json::to_string_pretty(data)
+        .map_err(|e| format!("序列化 auth.json 失败: {}", e))?;
+    
+    std::fs::write(&auth_path, content)
+        .map_err(|e| format!("写入 auth.json 失败: {}", e))?;
+    
+    // 设置文件权限为 600（仅所有者可读写）- 仅 Unix 系统
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let permissions = std::fs::Permissions::from_mode(0o600);
+        std::fs::set_permissions(&auth_path, permissions)
+            .map_err(|e| format!("设置 auth.json 权限失败: {}", e))?;
+    }
+    
+    Ok(())
+}
+
+/// 从 auth.json 内容计算所有已认证 provider 的状态
+pub fn provider_auth_statuses(auth_data: &serde_json::Value) -> Vec<ProviderAuthStatus> {
+    auth_data
+        .as_object()
+        .map(|obj| {
+            obj.iter()
+                .map(|(provider_id, auth_info)| ProviderAuthStatus {
+                    provider_id: provider_id.clone(),
+                    authenticated: true,
+                    auth_type: auth_info
+                        .get("type")
+                        .and_then(|v| v.as_str())
+                        .map(String::from),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_statuses_from_auth_json() {
+        let auth = serde_json::json!({
+            "openai": { "type": "api", "key": "sk-test" },
+            "anthropic": { "type": "oauth", "access": "token" },
+        });
+        let statuses = provider_auth_statuses(&auth);
+        assert_eq!(statuses.len(), 2);
+        assert!(statuses.iter().all(|s| s.authenticated));
+        let openai = statuses.iter().find(|s| s.provider_id == "openai").unwrap();
+        assert_eq!(openai.auth_type.as_deref(), Some("api"));
+        assert!(provider_auth_statuses(&serde_json::json!({})).is_empty());
+    }
+}
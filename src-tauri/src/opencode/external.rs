@@ -0,0 +1,542 @@
+//! 读取用户在 opencode CLI 中的全局配置
+//!
+//! Axon 启动 opencode 时把 XDG 目录指向应用数据目录，因此不会读取用户原有的配置。
+//! 这里按 opencode 的规则定位真实的配置目录（`$XDG_CONFIG_HOME/opencode`，默认
+//! `~/.config/opencode`）和数据目录（auth.json 所在的 `$XDG_DATA_HOME/opencode`），
+//! 列出其中的 Agent（配置文件 `agent` 字段和 `agent/`、`agents/` 目录下的 Markdown）
+//! 与服务商，并把 Agent 转换为 Axon 的 AgentDefinition 格式。
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// 配置文件名（按优先级从低到高合并）
+const CONFIG_FILES: &[&str] = &["config.json", "opencode.json", "opencode.jsonc"];
+
+/// Markdown Agent 所在目录
+const AGENT_DIRS: &[&str] = &["agent", "agents"];
+
+/// 导入后的 Agent ID 前缀
+const IMPORTED_AGENT_PREFIX: &str = "opencode-";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalOpencodeConfig {
+    /// 找到的配置目录，未找到时为空
+    pub config_dir: Option<String>,
+    /// 读取到的配置文件
+    pub config_files: Vec<String>,
+    /// auth.json 路径（存在时）
+    pub auth_file: Option<String>,
+    pub agents: Vec<ExternalAgent>,
+    pub providers: Vec<ExternalProvider>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalAgent {
+    pub name: String,
+    pub description: Option<String>,
+    pub mode: Option<String>,
+    pub model: Option<String>,
+    /// 定义所在文件
+    pub source: String,
+    /// 导入后在 Axon 中的 ID
+    pub import_id: String,
+    /// Axon 中已存在同 ID 的 Agent
+    pub already_imported: bool,
+    /// opencode 格式的原始定义（Markdown 的正文作为 `prompt`）
+    #[serde(skip)]
+    pub definition: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalProvider {
+    pub id: String,
+    pub name: Option<String>,
+    /// 认证方式（auth.json 中的 `type`，或配置中的 `apiKey`）
+    pub auth_type: Option<String>,
+    pub base_url: Option<String>,
+    /// Axon 中已配置同一服务商
+    pub already_configured: bool,
+    /// 配置文件中的 `provider.<id>` 字段
+    #[serde(skip)]
+    pub config: Map<String, Value>,
+    /// auth.json 中的认证信息
+    #[serde(skip)]
+    pub auth: Option<Value>,
+}
+
+impl ExternalProvider {
+    /// 可直接导入的 API Key（auth.json 中的 api 类型或配置中的 `options.apiKey`）
+    pub fn api_key(&self) -> Option<String> {
+        let from_auth = self
+            .auth
+            .as_ref()
+            .filter(|auth| auth.get("type").and_then(Value::as_str) == Some("api"))
+            .and_then(|auth| auth.get("key"))
+            .and_then(Value::as_str);
+        let from_config = self
+            .config
+            .get("options")
+            .and_then(|options| options.get("apiKey"))
+            .and_then(Value::as_str)
+            // `{env:OPENAI_API_KEY}` 形式的引用无法导入
+            .filter(|key| !key.starts_with('{'));
+        from_auth.or(from_config).map(String::from)
+    }
+}
+
+/// 扫描用户的 opencode 全局配置，`exclude` 为 Axon 自己的数据目录（避免读到隔离的配置）
+pub fn scan(exclude: Option<&Path>) -> ExternalOpencodeConfig {
+    let outside = |dir: &PathBuf| exclude.is_none_or(|excluded| !dir.starts_with(excluded));
+    let config_dir = config_dirs()
+        .into_iter()
+        .filter(outside)
+        .find(|dir| dir.is_dir());
+    let auth_path = data_dirs()
+        .into_iter()
+        .filter(outside)
+        .map(|dir| dir.join("auth.json"))
+        .find(|path| path.is_file());
+
+    let mut config = Map::new();
+    let mut config_files = Vec::new();
+    if let Some(dir) = &config_dir {
+        for name in CONFIG_FILES {
+            let path = dir.join(name);
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            match parse_jsonc(&content) {
+                Ok(Value::Object(object)) => {
+                    merge(&mut config, object);
+                    config_files.push(path.to_string_lossy().to_string());
+                }
+                Ok(_) => warn!("opencode 配置不是 JSON 对象: {:?}", path),
+                Err(e) => warn!("解析 opencode 配置失败 {:?}: {}", path, e),
+            }
+        }
+    }
+
+    let mut agents = BTreeMap::new();
+    if let Some(Value::Object(configured)) = config.get("agent") {
+        let source = config_files.last().cloned().unwrap_or_default();
+        for (name, definition) in configured {
+            if let Value::Object(definition) = definition {
+                agents.insert(name.clone(), (definition.clone(), source.clone()));
+            }
+        }
+    }
+    if let Some(dir) = &config_dir {
+        for path in AGENT_DIRS
+            .iter()
+            .filter_map(|sub| std::fs::read_dir(dir.join(sub)).ok())
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+        {
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            match std::fs::read_to_string(&path) {
+                Ok(content) => {
+                    let definition = parse_markdown_agent(&content);
+                    agents.insert(
+                        name.to_string(),
+                        (definition, path.to_string_lossy().to_string()),
+                    );
+                }
+                Err(e) => debug!("跳过无法读取的 Agent 文件 {:?}: {}", path, e),
+            }
+        }
+    }
+
+    let auth = auth_path
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|value| match value {
+            Value::Object(object) => Some(object),
+            _ => None,
+        })
+        .unwrap_or_default();
+    let configured_providers = match config.get("provider") {
+        Some(Value::Object(providers)) => providers.clone(),
+        _ => Map::new(),
+    };
+    let mut provider_ids: Vec<&String> = configured_providers.keys().chain(auth.keys()).collect();
+    provider_ids.sort();
+    provider_ids.dedup();
+
+    let providers = provider_ids
+        .into_iter()
+        .map(|id| {
+            let config = match configured_providers.get(id) {
+                Some(Value::Object(object)) => object.clone(),
+                _ => Map::new(),
+            };
+            let auth = auth.get(id).cloned();
+            let auth_type = auth
+                .as_ref()
+                .and_then(|auth| auth.get("type"))
+                .and_then(Value::as_str)
+                .map(String::from)
+                .or_else(|| {
+                    config
+                        .get("options")
+                        .and_then(|options| options.get("apiKey"))
+                        .map(|_| "api".to_string())
+                });
+            ExternalProvider {
+                id: id.clone(),
+                name: config.get("name").and_then(Value::as_str).map(String::from),
+                auth_type,
+                base_url: config
+                    .get("options")
+                    .and_then(|options| options.get("baseURL"))
+                    .and_then(Value::as_str)
+                    .map(String::from),
+                already_configured: false,
+                config,
+                auth,
+            }
+        })
+        .collect();
+
+    ExternalOpencodeConfig {
+        config_dir: config_dir.map(|dir| dir.to_string_lossy().to_string()),
+        config_files,
+        auth_file: auth_path.map(|path| path.to_string_lossy().to_string()),
+        agents: agents
+            .into_iter()
+            .map(|(name, (definition, source))| ExternalAgent {
+                description: string_field(&definition, "description"),
+                mode: string_field(&definition, "mode"),
+                model: string_field(&definition, "model"),
+                source,
+                import_id: imported_agent_id(&name),
+                already_imported: false,
+                name,
+                definition,
+            })
+            .collect(),
+        providers,
+    }
+}
+
+/// 把 opencode 格式的 Agent 转换为 Axon 的 AgentDefinition
+pub fn to_agent_definition(agent: &ExternalAgent, now: i64) -> Value {
+    let definition = &agent.definition;
+    let mut parameters = Map::new();
+    if let Some(temperature) = definition.get("temperature").and_then(Value::as_f64) {
+        parameters.insert("temperature".to_string(), json!(temperature));
+    }
+    if let Some(top_p) = definition.get("top_p").and_then(Value::as_f64) {
+        parameters.insert("topP".to_string(), json!(top_p));
+    }
+
+    let mut runtime = Map::new();
+    runtime.insert(
+        "mode".to_string(),
+        json!(agent.mode.as_deref().unwrap_or("all")),
+    );
+    if let Some(disabled) = definition.get("disable").and_then(Value::as_bool) {
+        runtime.insert("disabled".to_string(), json!(disabled));
+    }
+
+    // opencode 的 tools 是 { 工具名: 是否启用 }，转换为被禁用工具的黑名单
+    let disabled_tools: Vec<&String> = definition
+        .get("tools")
+        .and_then(Value::as_object)
+        .map(|tools| {
+            tools
+                .iter()
+                .filter(|(_, enabled)| enabled.as_bool() == Some(false))
+                .map(|(tool, _)| tool)
+                .collect()
+        })
+        .unwrap_or_default();
+    let tools = if disabled_tools.is_empty() {
+        json!({ "mode": "all", "list": [] })
+    } else {
+        json!({ "mode": "blacklist", "list": disabled_tools })
+    };
+
+    let permissions = definition
+        .get("permission")
+        .filter(|permission| permission.is_object())
+        .cloned()
+        .unwrap_or_else(|| json!({ "edit": "ask", "bash": "ask", "webfetch": "allow" }));
+
+    let mut prompt = Map::new();
+    if let Some(system) = string_field(definition, "prompt") {
+        prompt.insert("system".to_string(), json!(system));
+    }
+
+    let mut result = json!({
+        "id": agent.import_id,
+        "name": agent.name,
+        "description": agent.description.clone().unwrap_or_default(),
+        "model": { "modelId": agent.model.clone().unwrap_or_default() },
+        "parameters": parameters,
+        "runtime": runtime,
+        "tools": tools,
+        "permissions": permissions,
+        "prompt": prompt,
+        "metadata": {
+            "category": "utility",
+            "cost": "medium",
+            "triggers": [],
+            "useWhen": [],
+            "avoidWhen": [],
+        },
+        "subagents": [],
+        "delegationRuleset": { "rules": [], "defaultBehavior": "handle-self" },
+        "primaryPosition": { "x": 400, "y": 100 },
+        "createdAt": now,
+        "updatedAt": now,
+    });
+    if let Some(color) = string_field(definition, "color") {
+        result["color"] = json!(color);
+    }
+    result
+}
+
+/// 导入后的 Agent ID
+fn imported_agent_id(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{}{}", IMPORTED_AGENT_PREFIX, slug.trim_matches('-'))
+}
+
+fn string_field(object: &Map<String, Value>, key: &str) -> Option<String> {
+    object
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+}
+
+/// 候选配置目录（opencode 在所有平台上都使用 XDG 规则）
+fn config_dirs() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        candidates.push(PathBuf::from(xdg).join("opencode"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(home.join(".config").join("opencode"));
+    }
+    if let Some(config) = dirs::config_dir() {
+        candidates.push(config.join("opencode"));
+    }
+    candidates
+}
+
+/// 候选数据目录
+fn data_dirs() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(xdg) = std::env::var_os("XDG_DATA_HOME").filter(|v| !v.is_empty()) {
+        candidates.push(PathBuf::from(xdg).join("opencode"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        candidates.push(home.join(".local").join("share").join("opencode"));
+    }
+    if let Some(data) = dirs::data_dir() {
+        candidates.push(data.join("opencode"));
+    }
+    candidates
+}
+
+/// 深度合并，`overlay` 中的值优先
+fn merge(base: &mut Map<String, Value>, overlay: Map<String, Value>) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Object(existing)), Value::Object(incoming)) => merge(existing, incoming),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// 解析 JSONC（允许注释和尾随逗号）
+fn parse_jsonc(content: &str) -> serde_json::Result<Value> {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            (',', _) => {
+                // 尾随逗号：后面只有空白就是 `}` 或 `]`
+                let rest: String = chars.clone().take_while(|c| c.is_whitespace()).collect();
+                let after = chars.clone().nth(rest.chars().count());
+                if !matches!(after, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    serde_json::from_str(&out)
+}
+
+/// 解析 Markdown Agent：YAML frontmatter 中的简单字段和一层嵌套映射，正文作为提示词
+fn parse_markdown_agent(content: &str) -> Map<String, Value> {
+    let mut definition = Map::new();
+    let content = content.trim_start_matches('\u{feff}');
+    let body = match content
+        .strip_prefix("---")
+        .and_then(|rest| rest.split_once("\n---"))
+    {
+        Some((frontmatter, body)) => {
+            let mut section: Option<(String, Map<String, Value>)> = None;
+            for line in frontmatter.lines() {
+                if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                    continue;
+                }
+                let indented = line.starts_with([' ', '\t']);
+                let Some((key, value)) = line.trim().split_once(':') else {
+                    continue;
+                };
+                let (key, value) = (key.trim().to_string(), value.trim());
+                if indented {
+                    if let Some((_, map)) = section.as_mut() {
+                        map.insert(key, yaml_scalar(value));
+                    }
+                    continue;
+                }
+                if let Some((name, map)) = section.take() {
+                    definition.insert(name, Value::Object(map));
+                }
+                if value.is_empty() {
+                    section = Some((key, Map::new()));
+                } else {
+                    definition.insert(key, yaml_scalar(value));
+                }
+            }
+            if let Some((name, map)) = section {
+                definition.insert(name, Value::Object(map));
+            }
+            body.split_once('\n').map(|(_, body)| body).unwrap_or("")
+        }
+        None => content,
+    };
+    let prompt = body.trim();
+    if !prompt.is_empty() {
+        definition.insert("prompt".to_string(), json!(prompt));
+    }
+    definition
+}
+
+fn yaml_scalar(value: &str) -> Value {
+    let unquoted = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')));
+    if let Some(text) = unquoted {
+        return json!(text);
+    }
+    match value {
+        "true" => json!(true),
+        "false" => json!(false),
+        _ => value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .unwrap_or_else(|| json!(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_jsonc_with_comments_and_trailing_commas() {
+        let content = r#"{
+            // 注释
+            "agent": { "review": { "prompt": "see http://x // not a comment", }, },
+            /* 块注释 */
+            "list": [1, 2,],
+        }"#;
+        let value = parse_jsonc(content).unwrap();
+        assert_eq!(
+            value["agent"]["review"]["prompt"],
+            "see http://x // not a comment"
+        );
+        assert_eq!(value["list"], json!([1, 2]));
+    }
+
+    #[test]
+    fn parses_markdown_agent() {
+        let content = "---\ndescription: Reviews code\nmode: subagent\nmodel: anthropic/claude-sonnet-4\ntemperature: 0.1\ntools:\n  write: false\n  bash: true\n---\nYou are a reviewer.\n";
+        let definition = parse_markdown_agent(content);
+        assert_eq!(definition["description"], "Reviews code");
+        assert_eq!(definition["temperature"], json!(0.1));
+        assert_eq!(definition["tools"]["write"], json!(false));
+        assert_eq!(definition["prompt"], "You are a reviewer.");
+
+        let agent = ExternalAgent {
+            name: "Code Review".to_string(),
+            description: string_field(&definition, "description"),
+            mode: string_field(&definition, "mode"),
+            model: string_field(&definition, "model"),
+            source: String::new(),
+            import_id: imported_agent_id("Code Review"),
+            already_imported: false,
+            definition,
+        };
+        let converted = to_agent_definition(&agent, 1);
+        assert_eq!(converted["id"], "opencode-code-review");
+        assert_eq!(converted["runtime"]["mode"], "subagent");
+        assert_eq!(
+            converted["tools"],
+            json!({ "mode": "blacklist", "list": ["write"] })
+        );
+        assert_eq!(converted["prompt"]["system"], "You are a reviewer.");
+    }
+}
@@ -0,0 +1,193 @@
+//! OpenCode 多实例管理
+//!
+//! 默认实例随应用启动，使用设置中的项目目录；此外可以按名称启动附加实例，
+//! 每个实例有独立的工作目录和端口，用于并排打开多个项目或对比不同 Agent 的运行结果。
+//! 所有实例共享 opencode 二进制、配置目录和 Bridge 插件。
+//! 状态变化通过 `service:instance-status` 事件推送（默认实例同时保留 `service:status`）。
+
+use crate::error::AxonError;
+use crate::opencode::service::OpencodeService;
+use crate::opencode::types::{InstanceInfo, ServiceStatus, DEFAULT_INSTANCE};
+use crate::settings::SettingsManager;
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::AppHandle;
+use tracing::info;
+
+/// 实例名称最大长度
+const MAX_NAME_CHARS: usize = 32;
+
+/// 附加实例数量上限
+const MAX_INSTANCES: usize = 8;
+
+pub struct ServiceManager {
+    default: Arc<OpencodeService>,
+    instances: RwLock<BTreeMap<String, Arc<OpencodeService>>>,
+    settings: Arc<SettingsManager>,
+    app_handle: RwLock<Option<AppHandle>>,
+}
+
+impl ServiceManager {
+    pub fn new(default: Arc<OpencodeService>, settings: Arc<SettingsManager>) -> Arc<Self> {
+        Arc::new(Self {
+            default,
+            instances: RwLock::new(BTreeMap::new()),
+            settings,
+            app_handle: RwLock::new(None),
+        })
+    }
+
+    /// 设置事件发送句柄（同时应用到默认实例和之后启动的实例）
+    pub fn set_app_handle(&self, handle: AppHandle) {
+        self.default.set_app_handle(handle.clone());
+        for instance in self.instances.read().values() {
+            instance.set_app_handle(handle.clone());
+        }
+        *self.app_handle.write() = Some(handle);
+    }
+
+    /// 按名称获取实例
+    pub fn get(&self, name: &str) -> Result<Arc<OpencodeService>, AxonError> {
+        if name == DEFAULT_INSTANCE {
+            return Ok(Arc::clone(&self.default));
+        }
+        self.instances
+            .read()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| AxonError::not_found(format!("OpenCode 实例不存在: {}", name)))
+    }
+
+    /// 列出所有实例（默认实例在前）
+    pub fn list(&self) -> Vec<InstanceInfo> {
+        std::iter::once(Arc::clone(&self.default))
+            .chain(self.instances.read().values().cloned())
+            .map(|instance| instance_info(&instance))
+            .collect()
+    }
+
+    /// 启动附加实例，`port` 为 0 时自动分配
+    pub async fn start(
+        &self,
+        name: &str,
+        directory: &str,
+        port: u16,
+        plugin_api_port: u16,
+    ) -> Result<InstanceInfo, AxonError> {
+        validate_name(name)?;
+        let working_dir = Path::new(directory);
+        if !working_dir.is_dir() {
+            return Err(AxonError::not_found(format!(
+                "工作目录不存在: {}",
+                directory
+            )));
+        }
+
+        let instance = {
+            let mut instances = self.instances.write();
+            if let Some(existing) = instances.get(name) {
+                if is_active(&existing.get_status()) {
+                    return Err(AxonError::already_exists(format!(
+                        "OpenCode 实例已在运行: {}",
+                        name
+                    )));
+                }
+            } else if instances.len() >= MAX_INSTANCES {
+                return Err(AxonError::invalid_input(format!(
+                    "最多同时运行 {} 个附加实例",
+                    MAX_INSTANCES
+                )));
+            }
+            // 已停止的同名实例直接替换（工作目录和端口可能不同）
+            let instance = OpencodeService::named(
+                Arc::clone(&self.settings),
+                name,
+                working_dir.to_path_buf(),
+                port,
+            );
+            if let Some(handle) = self.app_handle.read().clone() {
+                instance.set_app_handle(handle);
+            }
+            instance.set_plugin_api_port(plugin_api_port);
+            instances.insert(name.to_string(), Arc::clone(&instance));
+            instance
+        };
+
+        if let Err(e) = instance.start().await {
+            self.instances.write().remove(name);
+            return Err(e.into());
+        }
+        info!("已启动 OpenCode 实例 {}: {}", name, directory);
+        Ok(instance_info(&instance))
+    }
+
+    /// 停止实例；附加实例停止后移除
+    pub async fn stop(&self, name: &str) -> Result<(), AxonError> {
+        let instance = self.get(name)?;
+        instance.stop().await?;
+        if name != DEFAULT_INSTANCE {
+            self.instances.write().remove(name);
+            info!("已停止并移除 OpenCode 实例: {}", name);
+        }
+        Ok(())
+    }
+}
+
+fn instance_info(instance: &OpencodeService) -> InstanceInfo {
+    InstanceInfo {
+        name: instance.name().to_string(),
+        directory: instance
+            .working_dir()
+            .map(|dir| dir.to_string_lossy().to_string()),
+        status: instance.get_status(),
+        endpoint: instance.get_endpoint(),
+        pid: instance.process_id(),
+    }
+}
+
+fn is_active(status: &ServiceStatus) -> bool {
+    matches!(
+        status,
+        ServiceStatus::Starting | ServiceStatus::Running { .. }
+    )
+}
+
+fn validate_name(name: &str) -> Result<(), AxonError> {
+    if name == DEFAULT_INSTANCE {
+        return Err(AxonError::invalid_input(
+            "default 是默认实例的保留名称，请使用 start_service 启动",
+        ));
+    }
+    let valid = name.chars().count() <= MAX_NAME_CHARS
+        && name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(AxonError::invalid_input(format!(
+            "无效的实例名称: {}（只能包含字母、数字、_ 和 -，不超过 {} 个字符）",
+            name, MAX_NAME_CHARS
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_instance_names() {
+        assert!(validate_name("project-b").is_ok());
+        assert!(validate_name("ab_test_2").is_ok());
+        assert!(validate_name(DEFAULT_INSTANCE).is_err());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../x").is_err());
+        assert!(validate_name(&"a".repeat(MAX_NAME_CHARS + 1)).is_err());
+    }
+}
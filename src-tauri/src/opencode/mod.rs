@@ -1,9 +1,16 @@
 //! OpenCode binary management and service control
 
+pub mod auth;
 mod downloader;
+pub mod external;
+mod manager;
 mod platform;
+pub mod plugins;
 mod service;
 mod types;
+mod watcher;
 
-pub use service::OpencodeService;
+pub use manager::ServiceManager;
+pub use service::{is_reserved_env, OpencodeService, EVENT_SERVICE_STATUS};
 pub use types::*;
+pub use watcher::ConfigWatcher;
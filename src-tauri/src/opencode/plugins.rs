@@ -0,0 +1,400 @@
+//! OpenCode 第三方插件管理
+//!
+//! 编辑 Axon 托管的 opencode.json 中的 `plugin` 数组。添加插件时校验文件 / URL，
+//! 并在 `plugins/third-party/<id>/` 下保留一份本地副本，配置中引用的是副本的
+//! `file://` 地址，原文件移动或网络不可用时插件仍能加载。修改在重启 OpenCode 后生效。
+
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::paths::{
+    ensure_dir_exists, get_axon_bridge_plugin_path, get_opencode_config_path,
+    get_opencode_plugins_dir,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+/// 第三方插件副本目录（位于 OpenCode 插件目录下）
+const THIRD_PARTY_DIR: &str = "third-party";
+
+/// 插件副本目录中的元数据文件
+const METADATA_FILE: &str = "plugin.json";
+
+/// 允许的插件文件扩展名
+const PLUGIN_EXTENSIONS: &[&str] = &["js", "mjs", "ts"];
+
+/// 插件文件大小上限
+const MAX_PLUGIN_SIZE: usize = 5 * 1024 * 1024;
+
+/// 下载超时
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 插件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PluginKind {
+    /// Axon Bridge（不可移除）
+    Bridge,
+    /// 通过 Axon 添加、有本地副本的插件
+    Managed,
+    /// 手动写入配置的其他条目（npm 包名等）
+    External,
+}
+
+/// opencode.json 中的一个插件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpencodePlugin {
+    pub id: String,
+    pub name: String,
+    pub kind: PluginKind,
+    /// 配置中的条目
+    pub entry: String,
+    /// 添加时的原始文件路径或 URL（仅 Managed）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// 本地副本的 SHA-256（仅 Managed）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// 条目指向的本地文件是否存在（非本地条目为 true）
+    pub available: bool,
+}
+
+/// 本地副本的元数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginMetadata {
+    id: String,
+    name: String,
+    source: String,
+    file_name: String,
+    sha256: String,
+    added_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 列出 opencode.json 中配置的插件
+pub fn list_plugins() -> Result<Vec<OpencodePlugin>, AxonError> {
+    let config = read_config()?;
+    Ok(plugin_entries(&config)
+        .iter()
+        .map(|entry| describe_entry(entry))
+        .collect())
+}
+
+/// 添加插件：校验并复制到本地后写入 opencode.json
+pub async fn add_plugin(url_or_path: &str) -> Result<OpencodePlugin, AxonError> {
+    let source = url_or_path.trim();
+    if source.is_empty() {
+        return Err(AxonError::invalid_input("插件地址不能为空"));
+    }
+
+    let (file_name, content) = if source.starts_with("http://") || source.starts_with("https://") {
+        fetch_remote(source).await?
+    } else {
+        read_local(source)?
+    };
+    validate_content(&content)?;
+
+    let id = plugin_id(&file_name, source);
+    let metadata = PluginMetadata {
+        name: file_stem(&file_name),
+        id: id.clone(),
+        source: source.to_string(),
+        file_name,
+        sha256: format!("{:x}", Sha256::digest(&content)),
+        added_at: chrono::Utc::now(),
+    };
+
+    tokio::task::spawn_blocking(move || install(metadata, &content)).await?
+}
+
+/// 移除插件：从 opencode.json 中删除条目并删除本地副本
+pub fn remove_plugin(id: &str) -> Result<(), AxonError> {
+    let mut config = read_config()?;
+    let entries = plugin_entries(&config);
+    let entry = entries
+        .iter()
+        .find(|entry| describe_entry(entry).id == id)
+        .ok_or_else(|| AxonError::not_found(format!("插件不存在: {}", id)))?;
+    let plugin = describe_entry(entry);
+    if plugin.kind == PluginKind::Bridge {
+        return Err(AxonError::invalid_input("Axon Bridge 插件不能移除"));
+    }
+
+    let remaining: Vec<String> = entries.iter().filter(|e| *e != entry).cloned().collect();
+    config["plugin"] = json!(remaining);
+    write_config(&config)?;
+
+    if plugin.kind == PluginKind::Managed {
+        let dir = third_party_dir()?.join(&plugin.id);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| AxonError::io("删除插件副本失败", &e))?;
+        }
+    }
+    info!("已移除 OpenCode 插件: {}", plugin.name);
+    Ok(())
+}
+
+fn install(metadata: PluginMetadata, content: &[u8]) -> Result<OpencodePlugin, AxonError> {
+    let mut config = read_config()?;
+    let mut entries = plugin_entries(&config);
+    if entries
+        .iter()
+        .any(|entry| describe_entry(entry).id == metadata.id)
+    {
+        return Err(AxonError::already_exists(format!(
+            "插件已添加: {}",
+            metadata.source
+        )));
+    }
+
+    let dir = third_party_dir()?.join(&metadata.id);
+    ensure_dir_exists(&dir).map_err(|e| AxonError::io("创建插件目录失败", &e))?;
+    let plugin_path = dir.join(&metadata.file_name);
+    std::fs::write(&plugin_path, content).map_err(|e| AxonError::io("写入插件文件失败", &e))?;
+    let metadata_json = serde_json::to_string_pretty(&metadata)
+        .map_err(|e| AxonError::internal(format!("序列化插件元数据失败: {}", e)))?;
+    std::fs::write(dir.join(METADATA_FILE), metadata_json)
+        .map_err(|e| AxonError::io("写入插件元数据失败", &e))?;
+
+    let entry = file_url(&plugin_path);
+    entries.push(entry.clone());
+    config["plugin"] = json!(entries);
+    if let Err(e) = write_config(&config) {
+        let _ = std::fs::remove_dir_all(&dir);
+        return Err(e);
+    }
+    info!(
+        "已添加 OpenCode 插件: {} ({})",
+        metadata.name, metadata.source
+    );
+    Ok(describe_entry(&entry))
+}
+
+async fn fetch_remote(url: &str) -> Result<(String, Vec<u8>), AxonError> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|_| AxonError::invalid_input(format!("无效的 URL: {}", url)))?;
+    let file_name = parsed
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| AxonError::invalid_input("URL 中缺少插件文件名"))?;
+    check_extension(&file_name)?;
+
+    let response = reqwest::Client::new()
+        .get(parsed)
+        .timeout(DOWNLOAD_TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?;
+    if response
+        .content_length()
+        .is_some_and(|len| len as usize > MAX_PLUGIN_SIZE)
+    {
+        return Err(AxonError::invalid_input("插件文件过大"));
+    }
+    Ok((file_name, response.bytes().await?.to_vec()))
+}
+
+fn read_local(path: &str) -> Result<(String, Vec<u8>), AxonError> {
+    let path = Path::new(path.strip_prefix("file://").unwrap_or(path));
+    if !path.is_absolute() {
+        return Err(AxonError::invalid_input("插件路径必须是绝对路径"));
+    }
+    if !path.is_file() {
+        return Err(AxonError::not_found(format!(
+            "插件文件不存在: {}",
+            path.display()
+        )));
+    }
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| AxonError::invalid_input("无效的插件路径"))?;
+    check_extension(&file_name)?;
+    let content = std::fs::read(path).map_err(|e| AxonError::io("读取插件文件失败", &e))?;
+    Ok((file_name, content))
+}
+
+fn check_extension(file_name: &str) -> Result<(), AxonError> {
+    let ext = Path::new(file_name)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !PLUGIN_EXTENSIONS.contains(&ext.as_str()) {
+        return Err(AxonError::invalid_input(format!(
+            "不支持的插件文件类型: {}（支持 {}）",
+            file_name,
+            PLUGIN_EXTENSIONS.join(" / ")
+        )));
+    }
+    Ok(())
+}
+
+fn validate_content(content: &[u8]) -> Result<(), AxonError> {
+    if content.is_empty() {
+        return Err(AxonError::invalid_input("插件文件为空"));
+    }
+    if content.len() > MAX_PLUGIN_SIZE {
+        return Err(AxonError::invalid_input("插件文件过大"));
+    }
+    if std::str::from_utf8(content).is_err() {
+        return Err(AxonError::invalid_input("插件文件不是有效的 UTF-8 文本"));
+    }
+    Ok(())
+}
+
+/// 插件 ID：文件名 + 来源哈希，同一来源重复添加会得到相同 ID
+fn plugin_id(file_name: &str, source: &str) -> String {
+    let stem: String = file_stem(file_name)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let hash = format!("{:x}", Sha256::digest(source.as_bytes()));
+    format!("{}-{}", stem.trim_matches('-'), &hash[..8])
+}
+
+fn file_stem(file_name: &str) -> String {
+    Path::new(file_name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_name.to_string())
+}
+
+fn describe_entry(entry: &str) -> OpencodePlugin {
+    let local_path = entry.strip_prefix("file://").map(PathBuf::from);
+    let bridge_path = get_axon_bridge_plugin_path();
+    if local_path.is_some() && local_path == bridge_path {
+        return OpencodePlugin {
+            id: "axon-bridge".to_string(),
+            name: "Axon Bridge".to_string(),
+            kind: PluginKind::Bridge,
+            entry: entry.to_string(),
+            source: None,
+            sha256: None,
+            available: local_path.as_ref().is_some_and(|p| p.exists()),
+        };
+    }
+
+    let metadata = local_path
+        .as_ref()
+        .and_then(|path| path.parent())
+        .and_then(|dir| std::fs::read_to_string(dir.join(METADATA_FILE)).ok())
+        .and_then(|content| serde_json::from_str::<PluginMetadata>(&content).ok());
+    let available = local_path.as_ref().is_none_or(|p| p.exists());
+    match metadata {
+        Some(metadata) => OpencodePlugin {
+            id: metadata.id,
+            name: metadata.name,
+            kind: PluginKind::Managed,
+            entry: entry.to_string(),
+            source: Some(metadata.source),
+            sha256: Some(metadata.sha256),
+            available,
+        },
+        None => {
+            let name = local_path
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| entry.to_string());
+            OpencodePlugin {
+                id: plugin_id(&name, entry),
+                name,
+                kind: PluginKind::External,
+                entry: entry.to_string(),
+                source: None,
+                sha256: None,
+                available,
+            }
+        }
+    }
+}
+
+fn file_url(path: &Path) -> String {
+    format!("file://{}", path.to_string_lossy().replace('\\', "/"))
+}
+
+fn third_party_dir() -> Result<PathBuf, AxonError> {
+    get_opencode_plugins_dir()
+        .map(|dir| dir.join(THIRD_PARTY_DIR))
+        .ok_or_else(data_dir_unavailable)
+}
+
+fn plugin_entries(config: &Value) -> Vec<String> {
+    config
+        .get("plugin")
+        .and_then(Value::as_array)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| entry.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn read_config() -> Result<Value, AxonError> {
+    let path = get_opencode_config_path().ok_or_else(data_dir_unavailable)?;
+    if !path.exists() {
+        return Ok(json!({ "$schema": "https://opencode.ai/config.json" }));
+    }
+    let content =
+        std::fs::read_to_string(&path).map_err(|e| AxonError::io("读取 opencode.json 失败", &e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AxonError::invalid_data(format!("解析 opencode.json 失败: {}", e)))
+}
+
+fn write_config(config: &Value) -> Result<(), AxonError> {
+    let path = get_opencode_config_path().ok_or_else(data_dir_unavailable)?;
+    if let Some(parent) = path.parent() {
+        ensure_dir_exists(parent).map_err(|e| AxonError::io("创建配置目录失败", &e))?;
+    }
+    let content = serde_json::to_string_pretty(config)
+        .map_err(|e| AxonError::internal(format!("序列化 opencode.json 失败: {}", e)))?;
+    std::fs::write(&path, content).map_err(|e| AxonError::io("写入 opencode.json 失败", &e))
+}
+
+fn data_dir_unavailable() -> AxonError {
+    AxonError::localized(
+        ErrorKind::Unavailable,
+        "app.data_dir_unavailable",
+        json!(null),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plugin_id_is_stable_per_source() {
+        let a = plugin_id("My Plugin.js", "https://example.com/My Plugin.js");
+        let b = plugin_id("My Plugin.js", "https://example.com/My Plugin.js");
+        let c = plugin_id("My Plugin.js", "/tmp/My Plugin.js");
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(a.starts_with("my-plugin-"));
+        assert_eq!(a.len(), "my-plugin-".len() + 8);
+    }
+
+    #[test]
+    fn rejects_unsupported_extensions_and_binary_content() {
+        assert!(check_extension("plugin.ts").is_ok());
+        assert!(check_extension("plugin.MJS").is_ok());
+        assert!(check_extension("plugin.exe").is_err());
+        assert!(check_extension("plugin").is_err());
+        assert!(validate_content(b"export const P = async () => ({})").is_ok());
+        assert!(validate_content(&[0xff, 0xfe, 0x00]).is_err());
+        assert!(validate_content(b"").is_err());
+    }
+}
@@ -5,21 +5,55 @@
 
 use crate::opencode::downloader::OpencodeDownloader;
 use crate::opencode::types::{
-    DownloadProgress, OpencodeError, ServiceConfig, ServiceMode, ServiceStatus, VersionInfo,
+    DownloadProgress, InstanceStatusEvent, OpencodeError, ProjectSwitchEvent, ProjectSwitchPhase,
+    ServiceConfig, ServiceEnvVar, ServiceMode, ServiceStatus, VersionInfo, DEFAULT_INSTANCE,
 };
+use crate::plugin_api::PluginApiState;
 use crate::settings::SettingsManager;
 use crate::utils::paths::{ensure_dir_exists, get_app_data_dir};
 use parking_lot::RwLock;
+use std::path::PathBuf;
 use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{AppHandle, Emitter};
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tracing::{debug, info, warn};
 
 /// Event names for frontend communication
 pub const EVENT_SERVICE_STATUS: &str = "service:status";
 /// Event for download progress updates
 pub const EVENT_DOWNLOAD_PROGRESS: &str = "service:download-progress";
+/// 各实例的状态事件（载荷带实例名称）
+pub const EVENT_INSTANCE_STATUS: &str = "service:instance-status";
+/// 切换项目目录的进度事件
+pub const EVENT_PROJECT_SWITCH: &str = "service:project-switch";
+/// 只在启动进程时读取的设置字段，服务运行期间变化时自动重启以生效
+const RESTART_SETTINGS: &[&str] = &["customOpencodePath", "extraEnv"];
+
+/// 本地进程存活检查间隔
+const EXIT_MONITOR_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 启动后等待 HTTP 服务就绪的最长时间
+const READINESS_TIMEOUT: Duration = Duration::from_secs(30);
+/// 就绪检查的首次间隔（之后按指数退避）
+const READINESS_INITIAL_DELAY: Duration = Duration::from_millis(100);
+/// 就绪检查的最大间隔
+const READINESS_MAX_DELAY: Duration = Duration::from_secs(2);
+/// 单次就绪检查请求的超时
+const READINESS_REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 读取启动失败进程的标准错误输出的超时
+const STDERR_READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 启动后的就绪检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Readiness {
+    Ready,
+    Exited,
+    TimedOut,
+}
 
 pub struct OpencodeService {
     config: RwLock<ServiceConfig>,
@@ -29,6 +63,16 @@ pub struct OpencodeService {
     app_handle: RwLock<Option<AppHandle>>,
     settings: Option<Arc<SettingsManager>>,
     plugin_api_port: RwLock<u16>,
+    /// Plugin API 状态（默认实例），用于读取实际端口和发布服务地址
+    plugin_api: RwLock<Option<PluginApiState>>,
+    /// 正在主动停止服务，此时进程退出不视为异常
+    stopping: AtomicBool,
+    /// 实例名称
+    name: String,
+    /// 工作目录，为空时使用设置中的项目目录
+    working_dir: Option<PathBuf>,
+    /// 切换项目目录时持有，避免并发切换
+    switch_lock: tokio::sync::Mutex<()>,
 }
 
 impl OpencodeService {
@@ -41,15 +85,72 @@ impl OpencodeService {
             app_handle: RwLock::new(None),
             settings: Some(settings),
             plugin_api_port: RwLock::new(0),
+            plugin_api: RwLock::new(None),
+            stopping: AtomicBool::new(false),
+            name: DEFAULT_INSTANCE.to_string(),
+            working_dir: None,
+            switch_lock: tokio::sync::Mutex::new(()),
         })
     }
 
+    /// 创建附加的命名实例（使用给定的工作目录和端口，端口为 0 时自动分配）
+    pub fn named(
+        settings: Arc<SettingsManager>,
+        name: &str,
+        working_dir: PathBuf,
+        port: u16,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            config: RwLock::new(ServiceConfig {
+                port,
+                ..ServiceConfig::default()
+            }),
+            status: RwLock::new(ServiceStatus::Ready),
+            process: RwLock::new(None),
+            downloader: OpencodeDownloader::new(),
+            app_handle: RwLock::new(None),
+            settings: Some(settings),
+            plugin_api_port: RwLock::new(0),
+            plugin_api: RwLock::new(None),
+            stopping: AtomicBool::new(false),
+            name: name.to_string(),
+            working_dir: Some(working_dir),
+            switch_lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// 实例名称，默认实例为 `default`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 实例的工作目录，默认实例为空（使用项目目录）
+    pub fn working_dir(&self) -> Option<&PathBuf> {
+        self.working_dir.as_ref()
+    }
+
+    fn is_default(&self) -> bool {
+        self.name == DEFAULT_INSTANCE
+    }
+
     pub fn set_plugin_api_port(&self, port: u16) {
         *self.plugin_api_port.write() = port;
     }
 
+    /// Plugin API 端口，已关联 Plugin API 时取其当前端口，避免使用过期的端口
     pub fn get_plugin_api_port(&self) -> u16 {
-        *self.plugin_api_port.read()
+        self.plugin_api
+            .read()
+            .as_ref()
+            .map(PluginApiState::get_port)
+            .filter(|port| *port != 0)
+            .unwrap_or_else(|| *self.plugin_api_port.read())
+    }
+
+    /// 关联 Plugin API，此后每次状态变化都会把当前服务地址发布给插件
+    pub fn set_plugin_api(&self, plugin_api: PluginApiState) {
+        plugin_api.set_opencode_endpoint(self.get_endpoint());
+        *self.plugin_api.write() = Some(plugin_api);
     }
 
     /// Set the app handle for event emission
@@ -61,6 +162,45 @@ impl OpencodeService {
         info!("App handle set, emitted initial status: {:?}", status);
     }
 
+    /// 订阅设置变化：影响进程启动的设置在本地服务运行期间变化时重启服务
+    ///
+    /// 其余设置在使用时读取（下载器和 Plugin API 不缓存设置），无需处理
+    pub fn watch_settings(self: &Arc<Self>) {
+        let Some(settings) = &self.settings else {
+            return;
+        };
+        let mut changes = settings.subscribe();
+        let service = Arc::clone(self);
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let change = match changes.recv().await {
+                    Ok(change) => change,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let keys: Vec<&str> = RESTART_SETTINGS
+                    .iter()
+                    .copied()
+                    .filter(|key| change.touches(key))
+                    .collect();
+                if keys.is_empty() {
+                    continue;
+                }
+                // 与切换项目互斥，避免两边同时启停进程
+                let _guard = service.switch_lock.lock().await;
+                let running = service.config.read().mode == ServiceMode::Local
+                    && matches!(service.get_status(), ServiceStatus::Running { .. });
+                if !running {
+                    continue;
+                }
+                info!("设置 {:?} 已变化，正在重启 OpenCode", keys);
+                if let Err(e) = service.restart().await {
+                    warn!("设置变化后重启 OpenCode 失败: {}", e);
+                }
+            }
+        });
+    }
+
     /// Emit event to frontend
     fn emit_event<S: serde::Serialize + Clone>(&self, event: &str, payload: S) {
         if let Some(handle) = self.app_handle.read().as_ref() {
@@ -83,7 +223,10 @@ impl OpencodeService {
             if self.is_process_running() {
                 // 尝试获取端口，只有在能确定端口时才修正状态
                 if let Some(port) = self.detect_running_port() {
-                    let corrected_status = ServiceStatus::Running { port };
+                    let corrected_status = ServiceStatus::Running {
+                        port,
+                        requested_port: None,
+                    };
                     info!("状态修正: {:?} -> {:?}", current_status, corrected_status);
                     *self.status.write() = corrected_status.clone();
                     return corrected_status;
@@ -102,7 +245,7 @@ impl OpencodeService {
     /// 返回 None 表示无法确定端口（例如使用动态端口但没有记录）
     fn detect_running_port(&self) -> Option<u16> {
         // 首先检查当前状态中是否已有端口信息
-        if let ServiceStatus::Running { port } = *self.status.read() {
+        if let ServiceStatus::Running { port, .. } = *self.status.read() {
             return Some(port);
         }
         
@@ -137,8 +280,20 @@ impl OpencodeService {
     fn update_status(&self, status: ServiceStatus) {
         info!("Updating service status: {:?}", status);
         *self.status.write() = status.clone();
+        if let Some(plugin_api) = self.plugin_api.read().as_ref() {
+            plugin_api.set_opencode_endpoint(self.get_endpoint());
+        }
         // Emit to frontend via Tauri events
-        self.emit_event(EVENT_SERVICE_STATUS, &status);
+        if self.is_default() {
+            self.emit_event(EVENT_SERVICE_STATUS, &status);
+        }
+        self.emit_event(
+            EVENT_INSTANCE_STATUS,
+            InstanceStatusEvent {
+                name: self.name.clone(),
+                status,
+            },
+        );
     }
 
     /// Emit download progress to frontend
@@ -195,17 +350,59 @@ impl OpencodeService {
         match config.mode {
             ServiceMode::Local => {
                 self.start_local_service(config.port).await?;
+                self.spawn_exit_monitor();
             }
             ServiceMode::Remote { url } => {
                 // For remote mode, just verify connectivity
                 self.verify_remote_connection(&url).await?;
-                self.update_status(ServiceStatus::Running { port: config.port });
+                self.update_status(ServiceStatus::Running {
+                    port: config.port,
+                    requested_port: None,
+                });
             }
         }
 
         Ok(())
     }
 
+    /// 监视本地进程，进程意外退出时切换为错误状态
+    fn spawn_exit_monitor(self: &Arc<Self>) {
+        let Some(pid) = self.process.read().as_ref().map(|child| child.id()) else {
+            return;
+        };
+        let service = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(EXIT_MONITOR_INTERVAL).await;
+                let exit_status = {
+                    let mut process = service.process.write();
+                    match process.as_mut() {
+                        Some(child) if child.id() == pid => match child.try_wait() {
+                            Ok(None) => continue,
+                            Ok(Some(status)) => status,
+                            Err(e) => {
+                                debug!("检查进程状态失败: {}", e);
+                                return;
+                            }
+                        },
+                        // 进程已被停止或替换
+                        _ => return,
+                    }
+                };
+                if service.stopping.load(Ordering::SeqCst) {
+                    return;
+                }
+
+                warn!("OpenCode 进程意外退出 (PID: {}): {}", pid, exit_status);
+                *service.process.write() = None;
+                service.update_status(ServiceStatus::Error {
+                    message: format!("进程意外退出（{}）", exit_status),
+                });
+                return;
+            }
+        });
+    }
+
     fn find_available_port() -> Result<u16, OpencodeError> {
         use std::net::TcpListener;
         let listener = TcpListener::bind("127.0.0.1:0")
@@ -217,19 +414,25 @@ impl OpencodeService {
         Ok(port)
     }
 
+    /// 端口当前能否绑定（启动前检查，避免连到占用该端口的其他服务）
+    fn is_port_free(port: u16) -> bool {
+        std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+    }
+
     async fn start_local_service(&self, port: u16) -> Result<(), OpencodeError> {
-        let actual_port = if port == 0 {
+        let mut actual_port = if port == 0 {
             Self::find_available_port()?
         } else {
             port
         };
+        let retry_attempts = self.config.read().port_retry_attempts;
+        // 每次启动时读取设置中的自定义路径，修改后无需重启应用
         let binary_path = self
             .downloader
-            .get_binary_path()
+            .get_binary_path_with_custom(self.get_custom_path().as_deref())
             .ok_or(OpencodeError::BinaryNotFound)?;
 
         self.update_status(ServiceStatus::Starting);
-        info!("启动 opencode serve，端口: {}", actual_port);
 
         // 获取应用数据目录
         let app_data_dir = get_app_data_dir().ok_or(OpencodeError::ConfigError(
@@ -249,22 +452,13 @@ impl OpencodeService {
             warn!("创建 opencode 配置目录失败: {}", e);
         }
 
-        let config_file = opencode_config_dir.join("opencode.json");
-        if config_file.exists() {
-            self.update_config_port(&config_file, actual_port);
-        } else {
-            let config_json = self.build_opencode_config(actual_port);
-            if let Err(e) = std::fs::write(&config_file, &config_json) {
-                warn!("写入 opencode 配置文件失败: {}", e);
-            } else {
-                info!("已创建 opencode 配置文件: {:?}", config_file);
-            }
-        }
-
         info!("opencode 配置目录: {:?}", opencode_config_dir);
 
-        // 确定工作目录：优先使用用户配置的项目目录，否则使用配置目录
-        let working_directory = if let Some(settings) = &self.settings {
+        // 确定工作目录：命名实例使用创建时指定的目录，
+        // 默认实例优先使用用户配置的项目目录，否则使用配置目录
+        let working_directory = if let Some(dir) = &self.working_dir {
+            dir.clone()
+        } else if let Some(settings) = &self.settings {
             settings.get_project_directory()
                 .and_then(|p| {
                     let path = std::path::Path::new(&p);
@@ -281,60 +475,264 @@ impl OpencodeService {
             opencode_config_dir.clone()
         };
 
-        info!("OpenCode 工作目录: {:?}", working_directory);
+        info!("OpenCode 实例 {} 工作目录: {:?}", self.name, working_directory);
+
+        let mut attempt = 0;
+        loop {
+            if !Self::is_port_free(actual_port) {
+                if attempt >= retry_attempts {
+                    let message = format!("端口 {} 已被占用", actual_port);
+                    self.update_status(ServiceStatus::Error {
+                        message: format!("服务启动失败：{}", message),
+                    });
+                    return Err(OpencodeError::ServiceStartError(message));
+                }
+                attempt += 1;
+                let next_port = Self::find_available_port()?;
+                warn!(
+                    "端口 {} 已被占用，改用端口 {}（第 {}/{} 次）",
+                    actual_port, next_port, attempt, retry_attempts
+                );
+                actual_port = next_port;
+                continue;
+            }
+
+            info!("启动 opencode serve，端口: {}", actual_port);
+            self.write_config_port(&opencode_config_dir, actual_port);
+
+            let mut cmd = std::process::Command::new(&binary_path);
+            cmd.args(["serve", "--port", &actual_port.to_string()])
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped())
+                // 设置工作目录
+                // - 如果用户配置了项目目录，使用项目目录（能够扫描到项目的 .opencode）
+                // - 否则使用 opencode 配置目录（保持原有行为）
+                .current_dir(&working_directory)
+                // 用户配置的额外环境变量在前，Axon 管理的变量不可被覆盖
+                .envs(self.extra_env())
+                .envs(self.managed_env(&app_data_dir, &cache_dir));
+
+            // Windows 平台：设置 CREATE_NO_WINDOW 标志，避免弹出 CMD 控制台窗口
+            #[cfg(target_os = "windows")]
+            {
+                use std::os::windows::process::CommandExt;
+                // CREATE_NO_WINDOW = 0x08000000
+                // 参考：https://learn.microsoft.com/en-us/windows/win32/procthread/process-creation-flags
+                const CREATE_NO_WINDOW: u32 = 0x08000000;
+                cmd.creation_flags(CREATE_NO_WINDOW);
+            }
+
+            let child = cmd
+                .spawn()
+                .map_err(|e| OpencodeError::ServiceStartError(e.to_string()))?;
+
+            *self.process.write() = Some(child);
+
+            // 轮询 HTTP 服务直到就绪、进程退出或超时
+            let readiness = self.wait_until_ready(actual_port).await;
+            if readiness == Readiness::TimedOut {
+                let _ = self.stop_inner().await;
+                let message = format!(
+                    "服务在 {} 秒内未就绪",
+                    READINESS_TIMEOUT.as_secs()
+                );
+                self.update_status(ServiceStatus::Error {
+                    message: format!("服务启动失败：{}", message),
+                });
+                return Err(OpencodeError::ServiceStartError(message));
+            }
+            if readiness == Readiness::Ready {
+                let requested_port = (port != 0 && actual_port != port).then_some(port);
+                if let Some(requested) = requested_port {
+                    warn!("端口 {} 被占用，OpenCode 改用端口 {}", requested, actual_port);
+                }
+                self.update_status(ServiceStatus::Running {
+                    port: actual_port,
+                    requested_port,
+                });
+                info!("OpenCode 服务启动成功，端口: {}", actual_port);
+                return Ok(());
+            }
+
+            let stderr = self.take_exited_stderr().await;
+            if is_port_in_use(&stderr) && attempt < retry_attempts {
+                attempt += 1;
+                let next_port = Self::find_available_port()?;
+                warn!(
+                    "端口 {} 已被占用，改用端口 {} 重试（第 {}/{} 次）",
+                    actual_port, next_port, attempt, retry_attempts
+                );
+                actual_port = next_port;
+                continue;
+            }
+
+            let message = if is_port_in_use(&stderr) {
+                format!("端口 {} 已被占用", actual_port)
+            } else {
+                match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+                    Some(line) => format!("进程立即退出: {}", line.trim()),
+                    None => "进程立即退出".to_string(),
+                }
+            };
+            self.update_status(ServiceStatus::Error {
+                message: format!("服务启动失败：{}", message),
+            });
+            return Err(OpencodeError::ServiceStartError(message));
+        }
+    }
 
-        let mut cmd = std::process::Command::new(&binary_path);
-        cmd.args(["serve", "--port", &actual_port.to_string()])
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
-            // 设置工作目录
-            // - 如果用户配置了项目目录，使用项目目录（能够扫描到项目的 .opencode）
-            // - 否则使用 opencode 配置目录（保持原有行为）
-            .current_dir(&working_directory)
+    /// 就绪检查：opencode 的 `/config` 接口返回 2xx 的 JSON 对象，且进程仍在运行
+    ///
+    /// 检查间隔按指数退避增长，进程退出或超过 [`READINESS_TIMEOUT`] 时结束
+    async fn wait_until_ready(&self, port: u16) -> Readiness {
+        let client = reqwest::Client::builder()
+            .timeout(READINESS_REQUEST_TIMEOUT)
+            .no_proxy()
+            .build()
+            .unwrap_or_default();
+        let config_url = format!("http://127.0.0.1:{}/config", port);
+        let deadline = tokio::time::Instant::now() + READINESS_TIMEOUT;
+        let mut delay = READINESS_INITIAL_DELAY;
+        loop {
+            tokio::time::sleep(delay).await;
+            if !self.is_process_running() {
+                return Readiness::Exited;
+            }
+            match client.get(&config_url).send().await {
+                Ok(response) if response.status().is_success() => {
+                    let is_config = response
+                        .json::<serde_json::Value>()
+                        .await
+                        .is_ok_and(|body| body.is_object());
+                    // 响应可能来自占用同一端口的其他进程，确认自己启动的进程仍在运行
+                    if !self.is_process_running() {
+                        return Readiness::Exited;
+                    }
+                    if is_config {
+                        debug!("就绪检查通过");
+                        return Readiness::Ready;
+                    }
+                    debug!("就绪检查未通过: 响应不是 opencode 配置");
+                }
+                Ok(response) => debug!("就绪检查未通过: HTTP {}", response.status()),
+                Err(e) => debug!("就绪检查未通过: {}", e),
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Readiness::TimedOut;
+            }
+            delay = (delay * 2).min(READINESS_MAX_DELAY);
+        }
+    }
+
+    /// Axon 为 opencode 进程设置的环境变量
+    fn managed_env(
+        &self,
+        app_data_dir: &std::path::Path,
+        cache_dir: &std::path::Path,
+    ) -> Vec<(&'static str, String)> {
+        let app_data = app_data_dir.to_string_lossy().to_string();
+        vec![
             // 设置 XDG 环境变量实现配置隔离
             // xdg-basedir 会自动在这些目录下创建 /opencode 子目录
-            .env("XDG_CONFIG_HOME", &app_data_dir)
-            .env("XDG_DATA_HOME", &app_data_dir)
-            .env("XDG_STATE_HOME", &app_data_dir)
-            .env("XDG_CACHE_HOME", &cache_dir)
+            ("XDG_CONFIG_HOME", app_data.clone()),
+            ("XDG_DATA_HOME", app_data.clone()),
+            ("XDG_STATE_HOME", app_data),
+            ("XDG_CACHE_HOME", cache_dir.to_string_lossy().to_string()),
             // 禁用自动更新（由 Axon 管理）
-            .env("OPENCODE_DISABLE_AUTOUPDATE", "true")
-            .env("AXON_RUNNING", "true")
-            .env("AXON_BRIDGE_PORT", self.get_plugin_api_port().to_string())
+            ("OPENCODE_DISABLE_AUTOUPDATE", "true".to_string()),
+            ("AXON_RUNNING", "true".to_string()),
+            ("AXON_BRIDGE_PORT", self.get_plugin_api_port().to_string()),
             // Agents 配置目录（编排页面创建的 agents 保存位置）
-            .env("AXON_AGENTS_DIR", app_data_dir.join("agents").to_string_lossy().to_string());
-
-        // Windows 平台：设置 CREATE_NO_WINDOW 标志，避免弹出 CMD 控制台窗口
-        #[cfg(target_os = "windows")]
-        {
-            use std::os::windows::process::CommandExt;
-            // CREATE_NO_WINDOW = 0x08000000
-            // 参考：https://learn.microsoft.com/en-us/windows/win32/procthread/process-creation-flags
-            const CREATE_NO_WINDOW: u32 = 0x08000000;
-            cmd.creation_flags(CREATE_NO_WINDOW);
-        }
-
-        let child = cmd
-            .spawn()
-            .map_err(|e| OpencodeError::ServiceStartError(e.to_string()))?;
+            (
+                "AXON_AGENTS_DIR",
+                app_data_dir.join("agents").to_string_lossy().to_string(),
+            ),
+        ]
+    }
 
-        *self.process.write() = Some(child);
+    /// 用户在设置中配置的额外环境变量（跳过 Axon 管理的变量）
+    fn extra_env(&self) -> Vec<(String, String)> {
+        let Some(settings) = &self.settings else {
+            return Vec::new();
+        };
+        settings
+            .get_extra_env()
+            .into_iter()
+            .filter(|(name, _)| {
+                let reserved = is_reserved_env(name);
+                if reserved {
+                    warn!("忽略额外环境变量 {}：由 Axon 管理", name);
+                }
+                !reserved
+            })
+            .collect()
+    }
 
-        // 等待服务启动
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    /// opencode 进程启动时的环境变量（Axon 管理的变量和额外变量，不含继承的系统变量）
+    pub fn environment(&self) -> Vec<ServiceEnvVar> {
+        let Some(app_data_dir) = get_app_data_dir() else {
+            return Vec::new();
+        };
+        let cache_dir = app_data_dir.join("cache");
+        let mut vars: Vec<ServiceEnvVar> = self
+            .extra_env()
+            .into_iter()
+            .map(|(name, value)| ServiceEnvVar {
+                name,
+                value,
+                managed: false,
+            })
+            .collect();
+        vars.extend(
+            self.managed_env(&app_data_dir, &cache_dir)
+                .into_iter()
+                .map(|(name, value)| ServiceEnvVar {
+                    name: name.to_string(),
+                    value,
+                    managed: true,
+                }),
+        );
+        vars
+    }
 
-        // 验证服务是否正在运行
-        if self.is_process_running() {
-            self.update_status(ServiceStatus::Running { port: actual_port });
-            info!("OpenCode 服务启动成功，端口: {}", actual_port);
-            Ok(())
+    /// 写入配置文件中的端口
+    ///
+    /// 配置文件由所有实例共享，端口以命令行参数为准，只由默认实例维护
+    fn write_config_port(&self, opencode_config_dir: &std::path::Path, port: u16) {
+        let config_file = opencode_config_dir.join("opencode.json");
+        if config_file.exists() {
+            if self.is_default() {
+                self.update_config_port(&config_file, port);
+            }
         } else {
-            self.update_status(ServiceStatus::Error {
-                message: "服务启动失败".to_string(),
-            });
-            Err(OpencodeError::ServiceStartError(
-                "进程立即退出".to_string(),
-            ))
+            let config_json = self.build_opencode_config(port);
+            if let Err(e) = std::fs::write(&config_file, &config_json) {
+                warn!("写入 opencode 配置文件失败: {}", e);
+            } else {
+                info!("已创建 opencode 配置文件: {:?}", config_file);
+            }
+        }
+    }
+
+    /// 取出已退出进程的标准错误输出
+    async fn take_exited_stderr(&self) -> String {
+        let Some(mut child) = self.process.write().take() else {
+            return String::new();
+        };
+        let Some(mut stderr) = child.stderr.take() else {
+            return String::new();
+        };
+        // 子进程派生的进程可能仍持有管道，读取设置超时
+        let read = tokio::task::spawn_blocking(move || {
+            use std::io::Read;
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output);
+            let _ = child.wait();
+            output
+        });
+        match tokio::time::timeout(STDERR_READ_TIMEOUT, read).await {
+            Ok(Ok(output)) => output,
+            _ => String::new(),
         }
     }
 
@@ -367,17 +765,21 @@ impl OpencodeService {
             });
         }
         
-        // 检查是否需要添加插件配置（如果存在符号链接但配置中没有）
-        let has_plugin_config = config.get("plugin").is_some();
-        if !has_plugin_config {
-            let plugin_path = get_app_data_dir()
-                .map(|p| p.join("opencode").join("plugins").join("opencode").join("dist").join("index.js"));
-            
-            let plugin_exists = plugin_path.as_ref().map(|p| p.exists()).unwrap_or(false);
-            
-            if plugin_exists {
-                if let Some(path) = plugin_path {
-                    let plugin_url = format!("file://{}", path.to_string_lossy().replace('\\', "/"));
+        // 确保 axon-bridge 插件在插件列表中（列表中可能还有用户添加的第三方插件）
+        let plugin_path = get_app_data_dir()
+            .map(|p| p.join("opencode").join("plugins").join("opencode").join("dist").join("index.js"));
+
+        if let Some(path) = plugin_path.filter(|p| p.exists()) {
+            let plugin_url = format!("file://{}", path.to_string_lossy().replace('\\', "/"));
+            match config.get_mut("plugin") {
+                Some(serde_json::Value::Array(plugins)) => {
+                    if !plugins.iter().any(|p| p.as_str() == Some(plugin_url.as_str())) {
+                        info!("检测到 axon-bridge 插件，添加到配置: {}", plugin_url);
+                        plugins.insert(0, serde_json::json!(plugin_url));
+                    }
+                }
+                Some(_) => warn!("opencode.json 中的 plugin 字段不是数组，跳过插件配置"),
+                None => {
                     info!("检测到 axon-bridge 插件，添加到配置: {}", plugin_url);
                     config["plugin"] = serde_json::json!([plugin_url]);
                 }
@@ -452,6 +854,11 @@ impl OpencodeService {
         }
     }
 
+    /// 本地 opencode 进程的 PID，未启动时为空
+    pub fn process_id(&self) -> Option<u32> {
+        self.process.read().as_ref().map(|child| child.id())
+    }
+
     /// Check if the local process is still running
     fn is_process_running(&self) -> bool {
         let mut process = self.process.write();
@@ -468,6 +875,13 @@ impl OpencodeService {
 
     /// Stop the service
     pub async fn stop(&self) -> Result<(), OpencodeError> {
+        self.stopping.store(true, Ordering::SeqCst);
+        let result = self.stop_inner().await;
+        self.stopping.store(false, Ordering::SeqCst);
+        result
+    }
+
+    async fn stop_inner(&self) -> Result<(), OpencodeError> {
         // 获取进程 PID 后立即释放锁，避免在异步等待时持有锁
         let pid_to_kill = {
             let process = self.process.read();
@@ -570,6 +984,87 @@ impl OpencodeService {
         self.start().await
     }
 
+    /// 切换项目目录：停止服务 → 更新设置 → 以新目录启动
+    ///
+    /// 服务未在本地运行时只更新设置。新目录启动失败时恢复原目录并重新启动，
+    /// 各阶段通过 `service:project-switch` 事件通知前端。
+    pub async fn switch_project(
+        self: &Arc<Self>,
+        project_dir: Option<String>,
+    ) -> Result<(), OpencodeError> {
+        let settings = match (&self.settings, &self.working_dir) {
+            (Some(settings), None) => Arc::clone(settings),
+            _ => {
+                return Err(OpencodeError::ConfigError(
+                    "只有默认实例可以切换项目目录".to_string(),
+                ))
+            }
+        };
+        if let Some(dir) = &project_dir {
+            if !std::path::Path::new(dir).is_dir() {
+                return Err(OpencodeError::ConfigError(format!(
+                    "项目目录不存在: {}",
+                    dir
+                )));
+            }
+        }
+
+        let _guard = self.switch_lock.lock().await;
+        let previous_dir = settings.get_project_directory();
+        let emit = |phase: ProjectSwitchPhase, message: Option<String>| {
+            self.emit_event(
+                EVENT_PROJECT_SWITCH,
+                ProjectSwitchEvent {
+                    phase,
+                    project_dir: project_dir.clone(),
+                    previous_dir: previous_dir.clone(),
+                    message,
+                },
+            );
+        };
+
+        let was_running = self.config.read().mode == ServiceMode::Local
+            && matches!(self.get_status(), ServiceStatus::Running { .. });
+        if was_running {
+            emit(ProjectSwitchPhase::Stopping, None);
+            self.stop().await?;
+        }
+        settings
+            .set_project_directory(project_dir.clone())
+            .map_err(OpencodeError::ConfigError)?;
+        if !was_running {
+            emit(ProjectSwitchPhase::Completed, None);
+            return Ok(());
+        }
+
+        emit(ProjectSwitchPhase::Starting, None);
+        let error = match self.start().await {
+            Ok(()) => {
+                info!("已切换项目目录: {:?} -> {:?}", previous_dir, project_dir);
+                emit(ProjectSwitchPhase::Completed, None);
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+
+        warn!("新项目目录启动失败，恢复原目录 {:?}: {}", previous_dir, error);
+        emit(ProjectSwitchPhase::RollingBack, Some(error.to_string()));
+        if let Err(e) = settings.set_project_directory(previous_dir.clone()) {
+            warn!("恢复原项目目录失败: {}", e);
+        }
+        match self.start().await {
+            Ok(()) => emit(ProjectSwitchPhase::RolledBack, Some(error.to_string())),
+            Err(e) => {
+                warn!("恢复原项目目录后启动失败: {}", e);
+                emit(ProjectSwitchPhase::Failed, Some(e.to_string()));
+            }
+        }
+        Err(OpencodeError::ServiceStartError(format!(
+            "新项目目录启动失败，已恢复原目录: {}",
+            error
+        )))
+    }
+
     /// Get the service endpoint URL
     pub fn get_endpoint(&self) -> Option<String> {
         let config = self.config.read();
@@ -577,7 +1072,7 @@ impl OpencodeService {
 
         match (&config.mode, &*status) {
             (ServiceMode::Remote { url }, ServiceStatus::Running { .. }) => Some(url.clone()),
-            (ServiceMode::Local, ServiceStatus::Running { port }) => {
+            (ServiceMode::Local, ServiceStatus::Running { port, .. }) => {
                 Some(format!("http://127.0.0.1:{}", port))
             }
             _ => None,
@@ -701,6 +1196,11 @@ impl Default for OpencodeService {
             app_handle: RwLock::new(None),
             settings: None,
             plugin_api_port: RwLock::new(0),
+            plugin_api: RwLock::new(None),
+            stopping: AtomicBool::new(false),
+            name: DEFAULT_INSTANCE.to_string(),
+            working_dir: None,
+            switch_lock: tokio::sync::Mutex::new(()),
         }
     }
 }
@@ -714,3 +1214,49 @@ impl Drop for OpencodeService {
         }
     }
 }
+
+/// 由 Axon 管理、不允许在额外环境变量中覆盖的变量
+const RESERVED_ENV_PREFIXES: &[&str] = &["XDG_", "AXON_", "OPENCODE_DISABLE_AUTOUPDATE"];
+
+/// 环境变量名是否由 Axon 管理
+pub fn is_reserved_env(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    RESERVED_ENV_PREFIXES
+        .iter()
+        .any(|prefix| upper.starts_with(prefix))
+}
+
+/// 根据标准错误输出判断是否因端口被占用而启动失败
+fn is_port_in_use(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    stderr.contains("eaddrinuse")
+        || stderr.contains("address already in use")
+        || stderr.contains("address in use")
+        || (stderr.contains("port") && stderr.contains("in use"))
+        // Windows: 通常每个套接字地址只允许使用一次
+        || stderr.contains("os error 10048")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_port_bind_failures() {
+        assert!(is_port_in_use(
+            "error: Failed to start server. Is port 4096 in use?"
+        ));
+        assert!(is_port_in_use("Error: listen EADDRINUSE: address already in use 127.0.0.1:4096"));
+        assert!(!is_port_in_use("error: Unexpected token in opencode.json"));
+        assert!(!is_port_in_use(""));
+    }
+
+    #[test]
+    fn reserves_axon_managed_env() {
+        assert!(is_reserved_env("XDG_CONFIG_HOME"));
+        assert!(is_reserved_env("axon_bridge_port"));
+        assert!(is_reserved_env("OPENCODE_DISABLE_AUTOUPDATE"));
+        assert!(!is_reserved_env("OPENAI_BASE_URL"));
+        assert!(!is_reserved_env("OPENCODE_CONFIG"));
+    }
+}
@@ -1,6 +1,7 @@
 //! Types and error definitions for opencode module
 
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use thiserror::Error;
 
 /// Errors that can occur in opencode operations
@@ -56,7 +57,16 @@ pub enum ServiceStatus {
     /// Service is starting
     Starting,
     /// Service is running
-    Running { port: u16 },
+    Running {
+        port: u16,
+        /// 配置的端口被占用、改用其它端口时为原端口
+        #[serde(
+            rename = "requestedPort",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        requested_port: Option<u16>,
+    },
     /// Service stopped
     Stopped,
     /// Error state
@@ -79,6 +89,13 @@ pub struct ServiceConfig {
     pub mode: ServiceMode,
     pub port: u16,
     pub auto_start: bool,
+    /// 固定端口被占用时改用随机端口重试的次数
+    #[serde(default = "default_port_retry_attempts")]
+    pub port_retry_attempts: u32,
+}
+
+fn default_port_retry_attempts() -> u32 {
+    3
 }
 
 impl Default for ServiceConfig {
@@ -88,6 +105,7 @@ impl Default for ServiceConfig {
             // 端口为 0 表示启动时自动分配可用随机端口
             port: 0,
             auto_start: true,
+            port_retry_attempts: default_port_retry_attempts(),
         }
     }
 }
@@ -114,6 +132,68 @@ impl Default for VersionInfo {
     }
 }
 
+/// 默认实例名称（应用启动时自动管理的实例）
+pub const DEFAULT_INSTANCE: &str = "default";
+
+/// 实例状态事件载荷
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceStatusEvent {
+    pub name: String,
+    pub status: ServiceStatus,
+}
+
+/// OpenCode 实例信息
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceInfo {
+    pub name: String,
+    /// 工作目录，默认实例为空（使用项目目录）
+    pub directory: Option<String>,
+    pub status: ServiceStatus,
+    pub endpoint: Option<String>,
+    pub pid: Option<u32>,
+}
+
+/// 切换项目目录的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProjectSwitchPhase {
+    /// 正在停止服务
+    Stopping,
+    /// 已更新项目目录，正在启动服务
+    Starting,
+    /// 切换完成
+    Completed,
+    /// 新目录启动失败，正在恢复原目录
+    RollingBack,
+    /// 已恢复原目录
+    RolledBack,
+    /// 恢复原目录后仍无法启动
+    Failed,
+}
+
+/// 切换项目目录的进度事件载荷
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectSwitchEvent {
+    pub phase: ProjectSwitchPhase,
+    pub project_dir: Option<String>,
+    pub previous_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// opencode 进程的环境变量（用于调试展示）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceEnvVar {
+    pub name: String,
+    pub value: String,
+    /// 是否由 Axon 管理（否则来自设置中的额外环境变量）
+    pub managed: bool,
+}
+
 /// 应用全局设置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -130,6 +210,373 @@ pub struct AppSettings {
     /// 用户添加的服务商配置
     #[serde(default)]
     pub providers: Vec<UserProviderConfig>,
+    /// 用户自定义的终端 Shell 配置
+    #[serde(default)]
+    pub shell_profiles: Vec<ShellProfile>,
+    /// 默认 Shell 配置 ID
+    #[serde(default)]
+    pub default_shell_profile_id: Option<String>,
+    /// 是否在重启后恢复终端会话
+    #[serde(default)]
+    pub persist_terminal_sessions: bool,
+    /// 允许 run_command 直接执行的命令（归一化后的命令名）
+    #[serde(default = "default_allowed_commands")]
+    pub allowed_commands: Vec<String>,
+    /// 文件系统命令的路径沙箱
+    #[serde(default)]
+    pub path_sandbox: PathSandboxSettings,
+    /// 日志级别（运行时可调整）
+    #[serde(default)]
+    pub log_level: LogLevelSettings,
+    /// 应用更新通道
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+    /// 关闭主窗口时最小化到系统托盘（服务保持运行）
+    #[serde(default)]
+    pub minimize_to_tray: bool,
+    /// 全局快捷键
+    #[serde(default)]
+    pub hotkeys: HotkeySettings,
+    /// 应用内快捷键的修改（操作 ID → 快捷键，`None` 表示解除绑定），见 [`crate::keymap`]
+    #[serde(default)]
+    pub keymap: BTreeMap<String, Option<String>>,
+    /// 后端消息（错误提示等）使用的语言
+    #[serde(default)]
+    pub language: Language,
+    /// 语义搜索使用的嵌入模型
+    #[serde(default)]
+    pub embedding: EmbeddingSettings,
+    /// 事件通知 Webhook
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    /// 系统通知
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+    /// 空闲 / 电池供电时暂停后台任务
+    #[serde(default)]
+    pub power: PowerSettings,
+    /// 插件 API 按路由限流
+    #[serde(default)]
+    pub plugin_api_rate_limit: RateLimitSettings,
+    /// 检查 Axon Bridge 插件更新的 GitHub 仓库（`owner/name`）
+    #[serde(default = "default_bridge_update_repo")]
+    pub bridge_update_repo: String,
+    /// 启动 opencode 时额外注入的环境变量（如 `OPENAI_BASE_URL`）
+    #[serde(default)]
+    pub extra_env: BTreeMap<String, String>,
+    /// 是否加密保存设置文件中的敏感字段
+    #[serde(default)]
+    pub settings_encryption: bool,
+    /// 按服务商过滤模型（键为 provider ID）
+    #[serde(default)]
+    pub model_filters: BTreeMap<String, ModelFilter>,
+    /// 团队共享配置仓库
+    #[serde(default)]
+    pub team_sync: TeamSyncSettings,
+}
+
+fn default_bridge_update_repo() -> String {
+    "code-yeongyu/axon-desktop".to_string()
+}
+
+/// 文件系统路径沙箱设置
+///
+/// 启用时文件系统命令只能访问项目目录、应用数据目录和 `allowed_paths` 中的目录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathSandboxSettings {
+    #[serde(default = "default_sandbox_enabled")]
+    pub enabled: bool,
+    /// 用户显式授权的目录（绝对路径）
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+}
+
+fn default_sandbox_enabled() -> bool {
+    true
+}
+
+impl Default for PathSandboxSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_sandbox_enabled(),
+            allowed_paths: Vec::new(),
+        }
+    }
+}
+
+/// 应用更新通道
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Zh,
+    En,
+}
+
+/// 嵌入模型设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingSettings {
+    /// 提供嵌入接口的服务商配置 ID，为空时使用第一个支持嵌入的服务商
+    #[serde(default)]
+    pub provider_id: Option<String>,
+    #[serde(default = "default_embedding_model")]
+    pub model: String,
+}
+
+fn default_embedding_model() -> String {
+    "text-embedding-3-small".to_string()
+}
+
+impl Default for EmbeddingSettings {
+    fn default() -> Self {
+        Self {
+            provider_id: None,
+            model: default_embedding_model(),
+        }
+    }
+}
+
+/// 系统通知设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationSettings {
+    /// 窗口未聚焦或已最小化时，后台任务、下载和工作流完成后发送系统通知
+    #[serde(default)]
+    pub task_completion: bool,
+}
+
+/// 节能设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerSettings {
+    /// 使用电池供电时暂停后台任务
+    #[serde(default)]
+    pub pause_on_battery: bool,
+    /// 用户空闲超过阈值时暂停后台任务
+    #[serde(default = "default_pause_when_idle")]
+    pub pause_when_idle: bool,
+    /// 空闲阈值（分钟）
+    #[serde(default = "default_idle_threshold_minutes")]
+    pub idle_threshold_minutes: u32,
+}
+
+fn default_pause_when_idle() -> bool {
+    true
+}
+
+fn default_idle_threshold_minutes() -> u32 {
+    10
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            pause_on_battery: false,
+            pause_when_idle: default_pause_when_idle(),
+            idle_threshold_minutes: default_idle_threshold_minutes(),
+        }
+    }
+}
+
+/// 团队配置同步设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TeamSyncSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Git 仓库地址
+    #[serde(default)]
+    pub repo_url: Option<String>,
+    /// 分支，为空时使用仓库默认分支
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// 自动刷新间隔（分钟），0 表示只手动同步
+    #[serde(default = "default_team_sync_interval_minutes")]
+    pub refresh_interval_minutes: u32,
+}
+
+fn default_team_sync_interval_minutes() -> u32 {
+    30
+}
+
+impl Default for TeamSyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            repo_url: None,
+            branch: None,
+            refresh_interval_minutes: default_team_sync_interval_minutes(),
+        }
+    }
+}
+
+/// 令牌桶限流规则
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitRule {
+    /// 桶容量（允许的突发请求数）
+    pub burst: u32,
+    /// 每秒补充的令牌数（持续请求速率）
+    pub per_second: u32,
+}
+
+impl Default for RateLimitRule {
+    fn default() -> Self {
+        Self {
+            burst: 100,
+            per_second: 50,
+        }
+    }
+}
+
+/// 插件 API 限流设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitSettings {
+    #[serde(default = "default_rate_limit_enabled")]
+    pub enabled: bool,
+    /// 未单独配置的路由使用的规则
+    #[serde(default)]
+    pub default_rule: RateLimitRule,
+    /// 按路由模板（如 `/api/plugin/events`）覆盖的规则
+    #[serde(default)]
+    pub routes: BTreeMap<String, RateLimitRule>,
+}
+
+fn default_rate_limit_enabled() -> bool {
+    true
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_rate_limit_enabled(),
+            default_rule: RateLimitRule::default(),
+            routes: BTreeMap::new(),
+        }
+    }
+}
+
+/// Webhook 消息格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    Slack,
+    Discord,
+    /// 通用 JSON
+    #[default]
+    Generic,
+}
+
+/// Webhook 通知的事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventKind {
+    /// 工作流执行结束
+    WorkflowFinished,
+    /// OpenCode 服务异常退出
+    ServiceCrashed,
+    /// 发现应用新版本
+    UpdateAvailable,
+}
+
+/// Webhook 配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookConfig {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub kind: WebhookKind,
+    /// 订阅的事件，为空时订阅全部事件
+    #[serde(default)]
+    pub events: Vec<WebhookEventKind>,
+    #[serde(default = "default_webhook_enabled")]
+    pub enabled: bool,
+}
+
+fn default_webhook_enabled() -> bool {
+    true
+}
+
+impl WebhookConfig {
+    /// 是否需要发送该事件
+    pub fn accepts(&self, event: WebhookEventKind) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+/// 日志级别设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLevelSettings {
+    /// error / warn / info / debug / trace
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    /// 应用该级别的 target（模块路径），为空时作用于整个应用
+    #[serde(default)]
+    pub targets: Vec<String>,
+}
+
+fn default_log_level() -> String {
+    "debug".to_string()
+}
+
+impl Default for LogLevelSettings {
+    fn default() -> Self {
+        Self {
+            level: default_log_level(),
+            targets: Vec::new(),
+        }
+    }
+}
+
+/// 全局快捷键设置（加速键格式如 `CommandOrControl+Shift+Space`，为空时不注册）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeySettings {
+    /// 唤起 / 聚焦主窗口
+    #[serde(default = "default_summon_hotkey")]
+    pub summon: Option<String>,
+    /// 打开快速提问窗口
+    #[serde(default = "default_quick_prompt_hotkey")]
+    pub quick_prompt: Option<String>,
+}
+
+fn default_summon_hotkey() -> Option<String> {
+    Some("CommandOrControl+Shift+A".to_string())
+}
+
+fn default_quick_prompt_hotkey() -> Option<String> {
+    Some("CommandOrControl+Shift+Space".to_string())
+}
+
+impl Default for HotkeySettings {
+    fn default() -> Self {
+        Self {
+            summon: default_summon_hotkey(),
+            quick_prompt: default_quick_prompt_hotkey(),
+        }
+    }
+}
+
+/// 默认命令白名单：只有 git
+///
+/// 解释器、包管理器和构建工具可以通过参数或项目脚本执行任意代码，需要用户自行加入白名单。
+fn default_allowed_commands() -> Vec<String> {
+    vec!["git".to_string()]
 }
 
 impl Default for AppSettings {
@@ -140,10 +587,65 @@ impl Default for AppSettings {
             installed_version: None,
             project_directory: None,
             providers: Vec::new(),
+            shell_profiles: Vec::new(),
+            default_shell_profile_id: None,
+            persist_terminal_sessions: false,
+            allowed_commands: default_allowed_commands(),
+            path_sandbox: PathSandboxSettings::default(),
+            log_level: LogLevelSettings::default(),
+            update_channel: UpdateChannel::default(),
+            minimize_to_tray: false,
+            hotkeys: HotkeySettings::default(),
+            keymap: BTreeMap::new(),
+            language: Language::default(),
+            embedding: EmbeddingSettings::default(),
+            webhooks: Vec::new(),
+            notifications: NotificationSettings::default(),
+            power: PowerSettings::default(),
+            plugin_api_rate_limit: RateLimitSettings::default(),
+            bridge_update_repo: default_bridge_update_repo(),
+            extra_env: BTreeMap::new(),
+            settings_encryption: false,
+            model_filters: BTreeMap::new(),
+            team_sync: TeamSyncSettings::default(),
         }
     }
 }
 
+/// 终端 Shell 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellProfile {
+    pub id: String,
+    pub name: String,
+    /// 可执行文件（绝对路径或 PATH 中的命令名）
+    pub executable: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 额外环境变量
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// 启动目录策略
+    #[serde(default)]
+    pub startup_directory: StartupDirectory,
+    /// 是否为系统检测到的内置配置（不持久化到用户配置中）
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+/// 终端启动目录策略
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StartupDirectory {
+    /// 当前项目目录（未设置项目时回退到用户主目录）
+    #[default]
+    Project,
+    /// 用户主目录
+    Home,
+    /// 固定目录
+    Custom { path: String },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserProviderConfig {
@@ -185,3 +687,73 @@ pub struct CustomConfig {
     #[serde(flatten)]
     pub extra: Option<serde_json::Map<String, serde_json::Value>>,
 }
+
+/// 单个服务商的模型过滤规则
+///
+/// 条目为不带 provider 前缀的模型 ID，以 `*` 结尾时按前缀匹配（如 `gpt-4*`）。
+/// `allow` 非空时只允许其中的模型；`deny` 优先于 `allow`。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelFilter {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl ModelFilter {
+    /// 模型是否允许使用
+    pub fn permits(&self, model_id: &str) -> bool {
+        let matches = |pattern: &String| match pattern.strip_suffix('*') {
+            Some(prefix) => model_id.starts_with(prefix),
+            None => pattern == model_id,
+        };
+        if self.deny.iter().any(matches) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(matches)
+    }
+}
+
+/// `provider/model` 格式的模型是否被过滤规则允许，没有 provider 前缀或没有对应规则时允许
+pub fn is_model_allowed(filters: &BTreeMap<String, ModelFilter>, model: &str) -> bool {
+    let Some((provider_id, model_id)) = model.split_once('/') else {
+        return true;
+    };
+    filters
+        .get(provider_id)
+        .is_none_or(|filter| filter.permits(model_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_models_per_provider() {
+        let filters = BTreeMap::from([
+            (
+                "openai".to_string(),
+                ModelFilter {
+                    allow: vec!["gpt-4o*".to_string(), "o3".to_string()],
+                    deny: vec!["gpt-4o-audio*".to_string()],
+                },
+            ),
+            (
+                "anthropic".to_string(),
+                ModelFilter {
+                    allow: Vec::new(),
+                    deny: vec!["claude-opus-4".to_string()],
+                },
+            ),
+        ]);
+        assert!(is_model_allowed(&filters, "openai/gpt-4o-mini"));
+        assert!(is_model_allowed(&filters, "openai/o3"));
+        assert!(!is_model_allowed(&filters, "openai/o3-pro"));
+        assert!(!is_model_allowed(&filters, "openai/gpt-4o-audio-preview"));
+        assert!(!is_model_allowed(&filters, "anthropic/claude-opus-4"));
+        assert!(is_model_allowed(&filters, "anthropic/claude-sonnet-4"));
+        assert!(is_model_allowed(&filters, "google/gemini-2.5-pro"));
+        assert!(is_model_allowed(&filters, "gpt-4"));
+    }
+}
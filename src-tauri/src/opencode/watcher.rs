@@ -0,0 +1,197 @@
+//! opencode.json / auth.json 外部修改监听
+//!
+//! 用户可能直接用编辑器修改配置目录中的 opencode.json 或 auth.json（例如手动添加服务商、
+//! 在终端执行 `opencode auth login`）。监听配置目录，文件内容变化时推送
+//! `opencode-config:changed` / `auth:changed` 事件，后者携带重新计算的服务商认证状态，
+//! 前端据此直接刷新缓存，无需再次调用 `get_all_provider_auth_status`。
+//!
+//! 编辑器保存时常常先写临时文件再重命名，因此监听的是目录而不是文件本身，
+//! 并在短暂防抖后按内容摘要判断是否真的发生了变化（Axon 自身写入相同内容不会重复推送）。
+
+use crate::opencode::auth::{
+    get_auth_json_path, provider_auth_statuses, read_auth_json, ProviderAuthStatus,
+};
+use crate::utils::paths::{ensure_dir_exists, get_opencode_config_path};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::Mutex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+pub const EVENT_OPENCODE_CONFIG_CHANGED: &str = "opencode-config:changed";
+pub const EVENT_AUTH_CHANGED: &str = "auth:changed";
+
+/// 合并连续文件事件的等待时间
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum WatchedFile {
+    OpencodeConfig,
+    Auth,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpencodeConfigChangedEvent {
+    pub path: String,
+    /// 文件是否已被删除
+    pub removed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthChangedEvent {
+    pub path: String,
+    /// 刷新后的服务商认证状态
+    pub providers: Vec<ProviderAuthStatus>,
+}
+
+pub struct ConfigWatcher {
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl ConfigWatcher {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            watcher: Mutex::new(None),
+        })
+    }
+
+    /// 开始监听配置目录，重复调用时替换之前的监听
+    pub fn start(&self, app_handle: AppHandle) {
+        let (Some(config_path), Ok(auth_path)) = (get_opencode_config_path(), get_auth_json_path())
+        else {
+            warn!("应用数据目录不可用，不监听 OpenCode 配置文件");
+            return;
+        };
+        let Some(dir) = config_path.parent().map(Path::to_path_buf) else {
+            return;
+        };
+        if let Err(e) = ensure_dir_exists(&dir) {
+            warn!("创建 OpenCode 配置目录失败，不监听配置文件: {}", e);
+            return;
+        }
+
+        let targets: HashMap<PathBuf, WatchedFile> = [
+            (config_path, WatchedFile::OpencodeConfig),
+            (auth_path, WatchedFile::Auth),
+        ]
+        .into_iter()
+        .collect();
+        let file_names: HashMap<_, _> = targets
+            .iter()
+            .filter_map(|(path, file)| Some((path.file_name()?.to_os_string(), *file)))
+            .collect();
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+                Ok(event) => {
+                    for path in &event.paths {
+                        if let Some(file) = path.file_name().and_then(|name| file_names.get(name)) {
+                            let _ = tx.send(*file);
+                        }
+                    }
+                }
+                Err(e) => warn!("配置文件监听错误: {}", e),
+            });
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                warn!("创建配置文件监听失败: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            warn!("监听 OpenCode 配置目录失败: {}", e);
+            return;
+        }
+        *self.watcher.lock() = Some(watcher);
+
+        let digests = targets
+            .iter()
+            .map(|(path, file)| (*file, digest(path)))
+            .collect();
+        let paths = targets
+            .into_iter()
+            .map(|(path, file)| (file, path))
+            .collect();
+        tauri::async_runtime::spawn(forward_events(app_handle, rx, paths, digests));
+        info!("已开始监听 OpenCode 配置目录: {:?}", dir);
+    }
+}
+
+/// 防抖后对比内容摘要，只为真正变化的文件推送事件
+async fn forward_events(
+    app_handle: AppHandle,
+    mut rx: mpsc::UnboundedReceiver<WatchedFile>,
+    paths: HashMap<WatchedFile, PathBuf>,
+    mut digests: HashMap<WatchedFile, Option<String>>,
+) {
+    while let Some(first) = rx.recv().await {
+        tokio::time::sleep(DEBOUNCE).await;
+        let mut pending = vec![first];
+        while let Ok(file) = rx.try_recv() {
+            if !pending.contains(&file) {
+                pending.push(file);
+            }
+        }
+
+        for file in pending {
+            let path = &paths[&file];
+            let current = digest(path);
+            if digests.get(&file) == Some(&current) {
+                continue;
+            }
+            digests.insert(file, current.clone());
+            debug!("检测到配置文件变化: {:?}", path);
+            emit_change(&app_handle, file, path, current.is_none());
+        }
+    }
+}
+
+fn emit_change(app_handle: &AppHandle, file: WatchedFile, path: &Path, removed: bool) {
+    let path_text = path.to_string_lossy().to_string();
+    let result = match file {
+        WatchedFile::OpencodeConfig => app_handle.emit(
+            EVENT_OPENCODE_CONFIG_CHANGED,
+            OpencodeConfigChangedEvent {
+                path: path_text,
+                removed,
+            },
+        ),
+        WatchedFile::Auth => {
+            // 文件被删除时 read_auth_json 返回空对象；解析失败（例如编辑到一半）时不推送
+            let auth_data = match read_auth_json() {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!("auth.json 已变化但无法解析: {}", e);
+                    return;
+                }
+            };
+            app_handle.emit(
+                EVENT_AUTH_CHANGED,
+                AuthChangedEvent {
+                    path: path_text,
+                    providers: provider_auth_statuses(&auth_data),
+                },
+            )
+        }
+    };
+    if let Err(e) = result {
+        warn!("发送配置变化事件失败: {}", e);
+    }
+}
+
+/// 文件内容摘要，文件不存在时返回 None
+fn digest(path: &Path) -> Option<String> {
+    std::fs::read(path)
+        .ok()
+        .map(|content| format!("{:x}", Sha256::digest(&content)))
+}
@@ -0,0 +1,806 @@
+//! 编排组委托规则引擎
+//!
+//! 根据任务描述（以及事件中的文本字段、声明需要的能力）评估编排组的 `delegationRuleset`，
+//! 选出应当接手的子代理：
+//!
+//! 1. 委托规则：`domain` 按关键词匹配（逗号、顿号或 `|` 分隔），`condition` 写成 `/正则/` 时
+//!    按正则匹配，另可在 `matchers` 中显式配置 keyword / regex / capability 匹配器。
+//!    关键词和正则任一命中即视为匹配，capability 匹配器要求请求声明了对应能力（全部满足）。
+//!    多条规则命中时按优先级、命中数、规则顺序选择。
+//! 2. 没有规则命中时，按子代理自身的触发条件（keyword / domain / condition / always）
+//!    和技能（作为能力）匹配。
+//! 3. 仍未命中时按 `defaultBehavior` 处理：自行处理、询问用户或委托给默认子代理。
+
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+/// 正则编译大小上限，避免规则中的超大正则拖慢请求
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// 委托优先级
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DelegationPriority {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+/// 没有规则命中时的默认行为
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DefaultBehavior {
+    #[default]
+    HandleSelf,
+    AskUser,
+    DelegateTo,
+}
+
+/// 匹配器类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatcherKind {
+    Keyword,
+    Regex,
+    Capability,
+}
+
+/// 显式配置的匹配器
+#[derive(Debug, Clone, Deserialize)]
+pub struct Matcher {
+    #[serde(rename = "type")]
+    pub kind: MatcherKind,
+    pub pattern: String,
+}
+
+/// 委托规则
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationRule {
+    #[serde(default)]
+    pub id: String,
+    pub subagent_id: String,
+    #[serde(default)]
+    pub domain: String,
+    #[serde(default)]
+    pub condition: String,
+    #[serde(default)]
+    pub priority: DelegationPriority,
+    #[serde(default)]
+    pub run_in_background: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub matchers: Vec<Matcher>,
+}
+
+/// 委托规则集
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationRuleset {
+    #[serde(default)]
+    pub rules: Vec<DelegationRule>,
+    #[serde(default)]
+    pub default_behavior: DefaultBehavior,
+    #[serde(default)]
+    pub default_subagent_id: Option<String>,
+}
+
+/// 子代理触发条件
+#[derive(Debug, Clone, Deserialize)]
+pub struct SubagentTrigger {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub pattern: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SubagentConfig {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub skills: Vec<String>,
+}
+
+/// 编排组中的子代理
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subagent {
+    pub id: String,
+    #[serde(default)]
+    pub config: SubagentConfig,
+    #[serde(default)]
+    pub triggers: Vec<SubagentTrigger>,
+    #[serde(default)]
+    pub run_in_background: bool,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 路由请求
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteRequest {
+    /// 任务描述
+    #[serde(default)]
+    pub task: String,
+    /// 触发路由的事件，其中的字符串字段一并参与匹配
+    #[serde(default)]
+    pub event: Option<Value>,
+    /// 任务需要的能力
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+/// 路由动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RouteAction {
+    Delegate,
+    HandleSelf,
+    AskUser,
+}
+
+/// 命中的子代理
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RoutedSubagent {
+    pub id: String,
+    pub name: String,
+}
+
+/// 路由结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteDecision {
+    pub action: RouteAction,
+    pub subagent: Option<RoutedSubagent>,
+    /// 命中的规则 ID（按触发条件或默认行为委托时为空）
+    pub rule_id: Option<String>,
+    pub run_in_background: bool,
+    /// 命中的匹配器（`keyword:前端`、`regex:^fix`、`capability:browser`）
+    pub matched: Vec<String>,
+    pub reason: String,
+}
+
+/// 待匹配的任务
+struct Task {
+    /// 小写后的文本
+    text: String,
+    /// 小写后的能力
+    capabilities: Vec<String>,
+}
+
+impl Task {
+    fn new(request: &RouteRequest) -> Self {
+        let mut text = request.task.clone();
+        if let Some(event) = &request.event {
+            collect_strings(event, &mut text);
+        }
+        Self {
+            text: text.to_lowercase(),
+            capabilities: request
+                .capabilities
+                .iter()
+                .map(|c| c.trim().to_lowercase())
+                .collect(),
+        }
+    }
+
+    fn has_capability(&self, capability: &str) -> bool {
+        let capability = capability.trim().to_lowercase();
+        self.capabilities.contains(&capability)
+    }
+}
+
+fn collect_strings(value: &Value, out: &mut String) {
+    match value {
+        Value::String(s) => {
+            out.push('\n');
+            out.push_str(s);
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect_strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| collect_strings(v, out)),
+        _ => {}
+    }
+}
+
+/// 一组匹配器的评估结果
+#[derive(Debug, Default)]
+struct Evaluation {
+    /// 命中的文本匹配器（关键词 / 正则）
+    text_hits: Vec<String>,
+    /// 是否配置了文本匹配器
+    has_text_matchers: bool,
+    /// 满足的能力要求
+    capability_hits: Vec<String>,
+    /// 所有能力要求都满足
+    capabilities_satisfied: bool,
+    /// 是否配置了能力匹配器
+    has_capability_matchers: bool,
+}
+
+impl Evaluation {
+    /// 文本匹配器任一命中（或没有文本匹配器但有能力匹配器），且能力要求全部满足
+    fn is_match(&self) -> bool {
+        let text_ok = if self.has_text_matchers {
+            !self.text_hits.is_empty()
+        } else {
+            self.has_capability_matchers
+        };
+        text_ok && self.capabilities_satisfied
+    }
+
+    fn score(&self) -> usize {
+        self.text_hits.len() + self.capability_hits.len()
+    }
+
+    fn into_matched(self) -> Vec<String> {
+        self.text_hits
+            .into_iter()
+            .chain(self.capability_hits)
+            .collect()
+    }
+}
+
+fn evaluate(matchers: &[Matcher], task: &Task) -> Evaluation {
+    let mut evaluation = Evaluation {
+        capabilities_satisfied: true,
+        ..Default::default()
+    };
+    for matcher in matchers {
+        let pattern = matcher.pattern.trim();
+        if pattern.is_empty() {
+            continue;
+        }
+        match matcher.kind {
+            MatcherKind::Keyword => {
+                evaluation.has_text_matchers = true;
+                if task.text.contains(&pattern.to_lowercase()) {
+                    evaluation.text_hits.push(format!("keyword:{}", pattern));
+                }
+            }
+            MatcherKind::Regex => {
+                evaluation.has_text_matchers = true;
+                if regex_matches(pattern, &task.text) {
+                    evaluation.text_hits.push(format!("regex:{}", pattern));
+                }
+            }
+            MatcherKind::Capability => {
+                evaluation.has_capability_matchers = true;
+                if task.has_capability(pattern) {
+                    evaluation
+                        .capability_hits
+                        .push(format!("capability:{}", pattern));
+                } else {
+                    evaluation.capabilities_satisfied = false;
+                }
+            }
+        }
+    }
+    evaluation
+}
+
+/// 大小写不敏感的正则匹配，无效正则视为不匹配
+fn regex_matches(pattern: &str, text: &str) -> bool {
+    match RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+    {
+        Ok(re) => re.is_match(text),
+        Err(e) => {
+            debug!("委托规则中的正则无效，已忽略: {}, 错误: {}", pattern, e);
+            false
+        }
+    }
+}
+
+/// 按逗号、顿号、分号或 `|` 拆分关键词列表
+fn split_keywords(text: &str) -> impl Iterator<Item = &str> {
+    text.split([',', '，', '、', ';', '；', '|'])
+        .map(str::trim)
+        .filter(|keyword| !keyword.is_empty())
+}
+
+/// `/pattern/` 形式的条件视为正则
+fn as_regex(condition: &str) -> Option<&str> {
+    let condition = condition.trim();
+    condition
+        .strip_prefix('/')
+        .and_then(|rest| rest.strip_suffix('/'))
+        .filter(|pattern| !pattern.is_empty())
+}
+
+impl DelegationRule {
+    /// 规则的全部匹配器：显式配置的 + 由 domain / condition 推导的
+    fn matchers(&self) -> Vec<Matcher> {
+        let mut matchers: Vec<Matcher> = split_keywords(&self.domain)
+            .map(|keyword| Matcher {
+                kind: MatcherKind::Keyword,
+                pattern: keyword.to_string(),
+            })
+            .collect();
+        if let Some(pattern) = as_regex(&self.condition) {
+            matchers.push(Matcher {
+                kind: MatcherKind::Regex,
+                pattern: pattern.to_string(),
+            });
+        }
+        matchers.extend(self.matchers.iter().cloned());
+        matchers
+    }
+}
+
+impl Subagent {
+    /// 触发条件和技能对应的匹配器，`always` 触发条件单独处理
+    fn matchers(&self) -> Vec<Matcher> {
+        let mut matchers = Vec::new();
+        for trigger in &self.triggers {
+            match trigger.kind.as_str() {
+                "keyword" | "domain" => {
+                    matchers.extend(split_keywords(&trigger.pattern).map(|keyword| Matcher {
+                        kind: MatcherKind::Keyword,
+                        pattern: keyword.to_string(),
+                    }))
+                }
+                "condition" => {
+                    if let Some(pattern) = as_regex(&trigger.pattern) {
+                        matchers.push(Matcher {
+                            kind: MatcherKind::Regex,
+                            pattern: pattern.to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        matchers
+    }
+
+    /// 触发条件任一命中即可，技能视为能力（不要求全部满足）
+    fn evaluate(&self, task: &Task) -> Evaluation {
+        let mut evaluation = evaluate(&self.matchers(), task);
+        evaluation.capability_hits.extend(
+            self.config
+                .skills
+                .iter()
+                .filter(|skill| task.has_capability(skill))
+                .map(|skill| format!("capability:{}", skill)),
+        );
+        evaluation
+    }
+
+    fn has_always_trigger(&self) -> bool {
+        self.triggers.iter().any(|t| t.kind == "always")
+    }
+
+    fn routed(&self) -> RoutedSubagent {
+        RoutedSubagent {
+            id: self.id.clone(),
+            name: self.config.name.clone(),
+        }
+    }
+}
+
+/// 评估规则集，返回路由结果
+pub fn route(
+    ruleset: &DelegationRuleset,
+    subagents: &[Subagent],
+    request: &RouteRequest,
+) -> RouteDecision {
+    let task = Task::new(request);
+    let active = |id: &str| subagents.iter().find(|s| s.id == id && s.enabled);
+
+    // 1. 委托规则：优先级 > 命中数 > 规则顺序（靠前优先）
+    let best_rule = ruleset
+        .rules
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| rule.enabled)
+        .filter_map(|(index, rule)| {
+            let subagent = active(&rule.subagent_id)?;
+            let evaluation = evaluate(&rule.matchers(), &task);
+            evaluation
+                .is_match()
+                .then_some((index, rule, subagent, evaluation))
+        })
+        .max_by(|a, b| {
+            a.1.priority
+                .cmp(&b.1.priority)
+                .then(a.3.score().cmp(&b.3.score()))
+                .then(b.0.cmp(&a.0))
+        });
+    if let Some((_, rule, subagent, evaluation)) = best_rule {
+        return RouteDecision {
+            action: RouteAction::Delegate,
+            subagent: Some(subagent.routed()),
+            rule_id: Some(rule.id.clone()),
+            run_in_background: rule.run_in_background || subagent.run_in_background,
+            matched: evaluation.into_matched(),
+            reason: format!("命中委托规则 {}", rule_label(rule)),
+        };
+    }
+
+    // 2. 子代理触发条件：命中数最多者，相同时取靠前的子代理
+    let candidates: Vec<(&Subagent, Evaluation)> = subagents
+        .iter()
+        .filter(|s| s.enabled)
+        .map(|subagent| (subagent, subagent.evaluate(&task)))
+        .filter(|(_, evaluation)| evaluation.score() > 0)
+        .collect();
+    let best_score = candidates.iter().map(|(_, e)| e.score()).max();
+    let best_trigger = best_score.and_then(|score| {
+        candidates
+            .into_iter()
+            .find(|(_, evaluation)| evaluation.score() == score)
+    });
+    if let Some((subagent, evaluation)) = best_trigger {
+        return RouteDecision {
+            action: RouteAction::Delegate,
+            subagent: Some(subagent.routed()),
+            rule_id: None,
+            run_in_background: subagent.run_in_background,
+            matched: evaluation.into_matched(),
+            reason: format!("命中子代理 {} 的触发条件", subagent.config.name),
+        };
+    }
+    if let Some(subagent) = subagents
+        .iter()
+        .find(|s| s.enabled && s.has_always_trigger())
+    {
+        return RouteDecision {
+            action: RouteAction::Delegate,
+            subagent: Some(subagent.routed()),
+            rule_id: None,
+            run_in_background: subagent.run_in_background,
+            matched: vec!["always".to_string()],
+            reason: format!("子代理 {} 配置为始终触发", subagent.config.name),
+        };
+    }
+
+    // 3. 默认行为
+    let fallback = |action: RouteAction, reason: &str| RouteDecision {
+        action,
+        subagent: None,
+        rule_id: None,
+        run_in_background: false,
+        matched: Vec::new(),
+        reason: reason.to_string(),
+    };
+    match ruleset.default_behavior {
+        DefaultBehavior::HandleSelf => fallback(
+            RouteAction::HandleSelf,
+            "没有匹配的委托规则，由主代理自行处理",
+        ),
+        DefaultBehavior::AskUser => {
+            fallback(RouteAction::AskUser, "没有匹配的委托规则，需要询问用户")
+        }
+        DefaultBehavior::DelegateTo => {
+            match ruleset.default_subagent_id.as_deref().and_then(active) {
+                Some(subagent) => RouteDecision {
+                    action: RouteAction::Delegate,
+                    subagent: Some(subagent.routed()),
+                    rule_id: None,
+                    run_in_background: subagent.run_in_background,
+                    matched: Vec::new(),
+                    reason: "没有匹配的委托规则，委托给默认子代理".to_string(),
+                },
+                None => fallback(
+                    RouteAction::HandleSelf,
+                    "没有匹配的委托规则，默认子代理不存在或已禁用，由主代理自行处理",
+                ),
+            }
+        }
+    }
+}
+
+fn rule_label(rule: &DelegationRule) -> &str {
+    if rule.domain.trim().is_empty() {
+        &rule.id
+    } else {
+        &rule.domain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn subagents() -> Vec<Subagent> {
+        serde_json::from_value(json!([
+            {
+                "id": "frontend",
+                "config": { "name": "前端工程师", "skills": ["browser"] },
+                "triggers": [{ "type": "keyword", "pattern": "React, CSS", "description": "" }],
+                "enabled": true
+            },
+            {
+                "id": "db",
+                "config": { "name": "数据库专家" },
+                "triggers": [{ "type": "condition", "pattern": "/\\b(sql|migration)\\b/", "description": "" }],
+                "runInBackground": true,
+                "enabled": true
+            },
+            {
+                "id": "docs",
+                "config": { "name": "文档助手" },
+                "triggers": [{ "type": "domain", "pattern": "文档、README", "description": "" }],
+                "enabled": false
+            },
+            {
+                "id": "reviewer",
+                "config": { "name": "代码审查" },
+                "triggers": [],
+                "enabled": true
+            }
+        ]))
+        .unwrap()
+    }
+
+    fn ruleset(value: Value) -> DelegationRuleset {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn request(task: &str) -> RouteRequest {
+        RouteRequest {
+            task: task.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn routed_id(decision: &RouteDecision) -> Option<&str> {
+        decision.subagent.as_ref().map(|s| s.id.as_str())
+    }
+
+    #[test]
+    fn matches_rule_domain_keywords_case_insensitively() {
+        let rules = ruleset(json!({
+            "rules": [{ "id": "r1", "subagentId": "reviewer", "domain": "code review，审查", "condition": "", "priority": "medium", "enabled": true }],
+            "defaultBehavior": "handle-self"
+        }));
+        let decision = route(&rules, &subagents(), &request("请帮我做一次 Code Review"));
+        assert_eq!(decision.action, RouteAction::Delegate);
+        assert_eq!(routed_id(&decision), Some("reviewer"));
+        assert_eq!(decision.rule_id.as_deref(), Some("r1"));
+        assert_eq!(decision.matched, vec!["keyword:code review"]);
+
+        let decision = route(&rules, &subagents(), &request("审查这个 PR"));
+        assert_eq!(routed_id(&decision), Some("reviewer"));
+    }
+
+    #[test]
+    fn matches_regex_conditions_and_explicit_matchers() {
+        let rules = ruleset(json!({
+            "rules": [
+                { "id": "regex", "subagentId": "db", "domain": "", "condition": "/^fix(es)?\\s+#\\d+/", "priority": "low", "enabled": true },
+                { "id": "explicit", "subagentId": "reviewer", "priority": "low", "enabled": true,
+                  "matchers": [{ "type": "regex", "pattern": "lint(ing)?" }] }
+            ]
+        }));
+        assert_eq!(
+            routed_id(&route(&rules, &subagents(), &request("Fix #42 in parser"))),
+            Some("db")
+        );
+        assert_eq!(
+            routed_id(&route(&rules, &subagents(), &request("run linting"))),
+            Some("reviewer")
+        );
+        assert_eq!(
+            route(&rules, &subagents(), &request("please fix #42")).action,
+            RouteAction::HandleSelf
+        );
+    }
+
+    #[test]
+    fn plain_conditions_are_not_treated_as_patterns() {
+        let rules = ruleset(json!({
+            "rules": [{ "id": "r", "subagentId": "reviewer", "domain": "", "condition": "当任务需要审查时", "enabled": true }]
+        }));
+        let decision = route(&rules, &subagents(), &request("当任务需要审查时"));
+        assert_eq!(decision.action, RouteAction::HandleSelf);
+    }
+
+    #[test]
+    fn capability_matchers_must_all_be_satisfied() {
+        let rules = ruleset(json!({
+            "rules": [{ "id": "ui", "subagentId": "frontend", "domain": "页面", "enabled": true,
+                        "matchers": [{ "type": "capability", "pattern": "browser" }, { "type": "capability", "pattern": "screenshot" }] }]
+        }));
+        let mut req = request("检查页面布局");
+        req.capabilities = vec!["Browser".to_string()];
+        assert_eq!(route(&rules, &subagents(), &req).rule_id, None);
+
+        req.capabilities.push("screenshot".to_string());
+        let decision = route(&rules, &subagents(), &req);
+        assert_eq!(decision.rule_id.as_deref(), Some("ui"));
+        assert_eq!(
+            decision.matched,
+            vec![
+                "keyword:页面",
+                "capability:browser",
+                "capability:screenshot"
+            ]
+        );
+    }
+
+    #[test]
+    fn capability_only_rules_match_without_text() {
+        let rules = ruleset(json!({
+            "rules": [{ "id": "cap", "subagentId": "db", "enabled": true,
+                        "matchers": [{ "type": "capability", "pattern": "postgres" }] }]
+        }));
+        let mut req = request("任意任务");
+        assert_eq!(route(&rules, &subagents(), &req).rule_id, None);
+        req.capabilities = vec!["postgres".to_string()];
+        assert_eq!(
+            route(&rules, &subagents(), &req).rule_id.as_deref(),
+            Some("cap")
+        );
+    }
+
+    #[test]
+    fn picks_highest_priority_then_most_hits_then_first_rule() {
+        let rules = ruleset(json!({
+            "rules": [
+                { "id": "low", "subagentId": "frontend", "domain": "react, css, ui", "priority": "low", "enabled": true },
+                { "id": "high", "subagentId": "reviewer", "domain": "react", "priority": "high", "enabled": true },
+                { "id": "high-more", "subagentId": "db", "domain": "react, css", "priority": "high", "enabled": true },
+                { "id": "high-more-later", "subagentId": "frontend", "domain": "css, react", "priority": "high", "enabled": true }
+            ]
+        }));
+        let decision = route(&rules, &subagents(), &request("React 组件的 CSS 动画"));
+        assert_eq!(decision.rule_id.as_deref(), Some("high-more"));
+
+        let decision = route(&rules, &subagents(), &request("只有 react"));
+        assert_eq!(decision.rule_id.as_deref(), Some("high"));
+    }
+
+    #[test]
+    fn critical_rules_outrank_better_matching_rules() {
+        let rules = ruleset(json!({
+            "rules": [
+                { "id": "many", "subagentId": "frontend", "domain": "a, b, c", "priority": "high", "enabled": true },
+                { "id": "critical", "subagentId": "reviewer", "domain": "a", "priority": "critical", "enabled": true }
+            ]
+        }));
+        assert_eq!(
+            route(&rules, &subagents(), &request("a b c"))
+                .rule_id
+                .as_deref(),
+            Some("critical")
+        );
+    }
+
+    #[test]
+    fn skips_disabled_rules_and_unknown_or_disabled_subagents() {
+        let rules = ruleset(json!({
+            "rules": [
+                { "id": "off", "subagentId": "reviewer", "domain": "文档", "enabled": false },
+                { "id": "missing", "subagentId": "ghost", "domain": "文档", "enabled": true },
+                { "id": "disabled-agent", "subagentId": "docs", "domain": "文档", "enabled": true }
+            ],
+            "defaultBehavior": "ask-user"
+        }));
+        let decision = route(&rules, &subagents(), &request("更新文档"));
+        assert_eq!(decision.action, RouteAction::AskUser);
+        assert!(decision.subagent.is_none());
+    }
+
+    #[test]
+    fn falls_back_to_subagent_triggers_and_skills() {
+        let rules = ruleset(json!({ "rules": [] }));
+
+        let decision = route(&rules, &subagents(), &request("add a SQL migration"));
+        assert_eq!(routed_id(&decision), Some("db"));
+        assert!(decision.run_in_background);
+        assert!(decision.rule_id.is_none());
+
+        let decision = route(&rules, &subagents(), &request("调整 css"));
+        assert_eq!(routed_id(&decision), Some("frontend"));
+
+        let mut req = request("截一张图");
+        req.capabilities = vec!["browser".to_string()];
+        let decision = route(&rules, &subagents(), &req);
+        assert_eq!(routed_id(&decision), Some("frontend"));
+        assert_eq!(decision.matched, vec!["capability:browser"]);
+
+        // 禁用子代理的触发条件不生效
+        assert_eq!(
+            route(&rules, &subagents(), &request("README")).action,
+            RouteAction::HandleSelf
+        );
+    }
+
+    #[test]
+    fn rules_take_precedence_over_triggers() {
+        let rules = ruleset(json!({
+            "rules": [{ "id": "r", "subagentId": "reviewer", "domain": "sql", "priority": "low", "enabled": true }]
+        }));
+        let decision = route(&rules, &subagents(), &request("review this sql migration"));
+        assert_eq!(routed_id(&decision), Some("reviewer"));
+        assert_eq!(decision.rule_id.as_deref(), Some("r"));
+    }
+
+    #[test]
+    fn uses_always_trigger_before_default_behavior() {
+        let mut agents = subagents();
+        agents[3].triggers =
+            serde_json::from_value(json!([{ "type": "always", "pattern": "" }])).unwrap();
+        let decision = route(&DelegationRuleset::default(), &agents, &request("随便聊聊"));
+        assert_eq!(routed_id(&decision), Some("reviewer"));
+        assert_eq!(decision.matched, vec!["always"]);
+    }
+
+    #[test]
+    fn applies_default_behaviors() {
+        let agents = subagents();
+        let task = request("与任何规则都无关");
+
+        let decision = route(&DelegationRuleset::default(), &agents, &task);
+        assert_eq!(decision.action, RouteAction::HandleSelf);
+
+        let rules =
+            ruleset(json!({ "defaultBehavior": "delegate-to", "defaultSubagentId": "reviewer" }));
+        let decision = route(&rules, &agents, &task);
+        assert_eq!(decision.action, RouteAction::Delegate);
+        assert_eq!(routed_id(&decision), Some("reviewer"));
+
+        let rules =
+            ruleset(json!({ "defaultBehavior": "delegate-to", "defaultSubagentId": "docs" }));
+        assert_eq!(
+            route(&rules, &agents, &task).action,
+            RouteAction::HandleSelf
+        );
+    }
+
+    #[test]
+    fn matches_text_inside_events() {
+        let rules = ruleset(json!({
+            "rules": [{ "id": "r", "subagentId": "reviewer", "domain": "pull request", "enabled": true }]
+        }));
+        let mut req = request("");
+        req.event = Some(
+            json!({ "type": "github", "payload": { "title": "New Pull Request opened", "number": 3 } }),
+        );
+        assert_eq!(
+            route(&rules, &subagents(), &req).rule_id.as_deref(),
+            Some("r")
+        );
+    }
+
+    #[test]
+    fn ignores_invalid_regex() {
+        let rules = ruleset(json!({
+            "rules": [{ "id": "bad", "subagentId": "reviewer", "condition": "/([unclosed/", "enabled": true }]
+        }));
+        assert_eq!(
+            route(&rules, &subagents(), &request("([unclosed")).action,
+            RouteAction::HandleSelf
+        );
+    }
+
+    #[test]
+    fn parses_frontend_ruleset_format() {
+        let rules = ruleset(json!({
+            "rules": [{ "id": "rule-1", "subagentId": "frontend", "domain": "UI", "condition": "涉及界面",
+                        "priority": "critical", "runInBackground": true, "enabled": true }],
+            "defaultBehavior": "delegate-to",
+            "defaultSubagentId": "reviewer",
+            "customGuidelines": "优先委托"
+        }));
+        assert_eq!(rules.rules[0].priority, DelegationPriority::Critical);
+        assert_eq!(rules.default_behavior, DefaultBehavior::DelegateTo);
+        let decision = route(&rules, &subagents(), &request("ui polish"));
+        assert!(decision.run_in_background);
+    }
+}
@@ -1,19 +1,28 @@
 //! Plugin API HTTP 处理函数
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    response::sse::{Event, KeepAlive, Sse},
     Json,
 };
 use chrono::Utc;
+use futures_util::{stream, Stream, StreamExt};
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::path::PathBuf;
+use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
 
 use super::{
+    delegation::{self, DelegationRuleset, RouteDecision, RouteRequest, Subagent},
     types::*,
-    PluginApiState,
+    PluginApiState, MIN_PLUGIN_API_VERSION, PLUGIN_API_VERSION,
 };
 use serde::Serialize;
+use crate::consent::{self, ConsentAction, ConsentOutcome};
+use crate::context_pins::PinnedContext;
+use crate::memory::{MemoryEntry, MemoryScope};
+use crate::tools::{ToolDefinition, ToolExecution, ToolSpec};
 use crate::utils::paths::get_app_data_dir;
 
 /// 健康检查
@@ -21,9 +30,17 @@ pub async fn health_check() -> Json<ApiResponse<&'static str>> {
     Json(ApiResponse::success("ok"))
 }
 
+/// 运行指标（含各路由的限流计数）
+pub async fn get_metrics(
+    State(state): State<PluginApiState>,
+) -> Json<ApiResponse<PluginApiMetrics>> {
+    Json(ApiResponse::success(state.metrics(true)))
+}
+
 /// 获取配置（包含从文件系统和编排组加载的 agents）
 pub async fn get_config(
     State(state): State<PluginApiState>,
+    Query(query): Query<ConfigQuery>,
 ) -> Json<PluginConfigResponse> {
     let mut agents = state.get_agents();
     
@@ -39,16 +56,97 @@ pub async fn get_config(
         }
     }
     
+    let filtered = state.enforce_model_filters(&mut agents);
+    if !filtered.is_empty() {
+        warn!("以下 Agent 配置的模型已被模型过滤规则禁止，改用默认模型: {:?}", filtered);
+    }
+
     let disabled_agents = state.get_disabled_agents();
 
+    // 会话级覆盖只作用于本次响应，不修改保存的配置
+    let agent_overrides = query
+        .session_id
+        .map(|id| state.get_session_overrides(&id))
+        .unwrap_or_default();
+    for (name, overrides) in &agent_overrides {
+        if let Some(agent) = agents.get_mut(name) {
+            overrides.apply(agent);
+        }
+    }
+
     Json(PluginConfigResponse {
         port: state.get_port(),
         dev_mode: cfg!(debug_assertions),
         agents,
         disabled_agents,
+        api_version: PLUGIN_API_VERSION,
+        min_api_version: MIN_PLUGIN_API_VERSION,
+        agent_overrides,
     })
 }
 
+/// 获取会话的 Agent 参数覆盖
+pub async fn get_session_overrides(
+    State(state): State<PluginApiState>,
+    Path(session_id): Path<String>,
+) -> Json<ApiResponse<HashMap<String, AgentOverride>>> {
+    Json(ApiResponse::success(state.get_session_overrides(&session_id)))
+}
+
+/// 设置会话中某个 Agent 的参数覆盖（只在内存中生效）
+pub async fn set_session_override(
+    State(state): State<PluginApiState>,
+    Path(session_id): Path<String>,
+    Json(req): Json<SetAgentOverrideRequest>,
+) -> Json<ApiResponse<HashMap<String, AgentOverride>>> {
+    if let Err(e) = state.set_session_override(&session_id, &req.agent, req.overrides) {
+        return Json(ApiResponse::error(e));
+    }
+    info!("设置会话 {} 的 Agent 参数覆盖: {}", session_id, req.agent);
+    Json(ApiResponse::success(state.get_session_overrides(&session_id)))
+}
+
+/// 清除会话的 Agent 参数覆盖
+pub async fn clear_session_overrides(
+    State(state): State<PluginApiState>,
+    Path(session_id): Path<String>,
+) -> Json<ApiResponse<bool>> {
+    Json(ApiResponse::success(state.clear_session_overrides(&session_id)))
+}
+
+/// 当前的 OpenCode 服务地址和 Plugin API 端口
+pub async fn get_endpoint(
+    State(state): State<PluginApiState>,
+) -> Json<ApiResponse<EndpointInfo>> {
+    Json(ApiResponse::success(state.endpoint()))
+}
+
+/// 端点变化事件流（SSE），连接后先推送一次当前端点
+pub async fn endpoint_events(
+    State(state): State<PluginApiState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let current = state.endpoint();
+    let changes = stream::unfold(state.subscribe_endpoint(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(endpoint) => return Some((endpoint, receiver)),
+                // 落后时跳过旧的变化，下一条就是较新的端点
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    let events = stream::once(async move { current })
+        .chain(changes)
+        .map(|endpoint| {
+            Ok(Event::default()
+                .event("endpoint")
+                .json_data(&endpoint)
+                .unwrap_or_default())
+        });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
 /// 获取所有 Agent 配置
 /// 
 /// 从三个来源合并 Agent 配置：
@@ -71,7 +169,8 @@ pub async fn get_agents(
             agents.entry(name).or_insert(config);
         }
     }
-    
+
+    state.enforce_model_filters(&mut agents);
     Json(agents)
 }
 
@@ -453,6 +552,12 @@ pub async fn set_agent(
     State(state): State<PluginApiState>,
     Json(req): Json<SetAgentRequest>,
 ) -> Json<ApiResponse<AgentConfig>> {
+    if let Some(model) = req.agent.model.as_deref() {
+        if !state.is_model_allowed(model) {
+            warn!("拒绝设置 Agent {}: 模型 {} 已被模型过滤规则禁止", req.agent.name, model);
+            return Json(ApiResponse::error(format!("模型 {} 已被禁止使用", model)));
+        }
+    }
     let name = req.agent.name.clone();
     state.set_agent(name.clone(), req.agent.clone());
     info!("已设置 Agent: {}", name);
@@ -496,6 +601,148 @@ pub async fn receive_event(
     Json(ApiResponse::success("ok"))
 }
 
+/// 请求执行工具
+///
+/// 映射为破坏性操作的工具调用会等待用户在前端确认（或超时拒绝）后才返回
+pub async fn request_tool_consent(
+    State(state): State<PluginApiState>,
+    Json(req): Json<ToolConsentRequest>,
+) -> Json<ApiResponse<ToolConsentResponse>> {
+    let Some((action, summary)) = consent::classify_tool(&req.tool, &req.args) else {
+        return Json(ApiResponse::success(ToolConsentResponse {
+            allowed: true,
+            outcome: ConsentOutcome::Allowed,
+            action: None,
+        }));
+    };
+
+    let outcome = state
+        .consent
+        .request(action, req.tool, summary, req.directory, req.session_id)
+        .await;
+    Json(ApiResponse::success(ToolConsentResponse {
+        allowed: outcome.is_allowed(),
+        outcome,
+        action: Some(action),
+    }))
+}
+
+/// 列出 Agent 记忆（查询参数 `agent`、`project`）
+pub async fn list_memory(
+    State(state): State<PluginApiState>,
+    Query(scope): Query<MemoryScope>,
+) -> Json<ApiResponse<Vec<MemoryEntry>>> {
+    Json(match state.memory.list(&scope) {
+        Ok(entries) => ApiResponse::success(entries),
+        Err(e) => ApiResponse::error(e.message),
+    })
+}
+
+/// 读取单条记忆，不存在时 data 为 null
+pub async fn get_memory(
+    State(state): State<PluginApiState>,
+    Path(key): Path<String>,
+    Query(scope): Query<MemoryScope>,
+) -> Json<ApiResponse<Option<MemoryEntry>>> {
+    Json(match state.memory.get(&scope, &key) {
+        Ok(entry) => ApiResponse::success(entry),
+        Err(e) => ApiResponse::error(e.message),
+    })
+}
+
+/// 写入记忆（超出配额时返回错误）
+pub async fn set_memory(
+    State(state): State<PluginApiState>,
+    Path(key): Path<String>,
+    Json(req): Json<SetMemoryRequest>,
+) -> Json<ApiResponse<MemoryEntry>> {
+    Json(match state.memory.set(&req.scope, &key, req.value) {
+        Ok(entry) => {
+            debug!("Agent {} 写入记忆: {}", req.scope.agent, key);
+            ApiResponse::success(entry)
+        }
+        Err(e) => {
+            warn!("Agent {} 写入记忆失败: {}", req.scope.agent, e);
+            ApiResponse::error(e.message)
+        }
+    })
+}
+
+/// 删除记忆，data 为条目是否存在
+pub async fn delete_memory(
+    State(state): State<PluginApiState>,
+    Path(key): Path<String>,
+    Query(scope): Query<MemoryScope>,
+) -> Json<ApiResponse<bool>> {
+    Json(match state.memory.delete(&scope, &key) {
+        Ok(existed) => ApiResponse::success(existed),
+        Err(e) => ApiResponse::error(e.message),
+    })
+}
+
+/// 列出已启用的自定义工具，供插件注册到 OpenCode
+pub async fn list_tools(State(state): State<PluginApiState>) -> Json<ApiResponse<Vec<ToolSpec>>> {
+    Json(match state.tools.specs() {
+        Ok(specs) => ApiResponse::success(specs),
+        Err(e) => ApiResponse::error(e.message),
+    })
+}
+
+/// 执行自定义工具
+///
+/// Node / Python 脚本工具不受权限限制，执行前等待用户确认
+pub async fn execute_tool(
+    State(state): State<PluginApiState>,
+    Path(name): Path<String>,
+    Json(req): Json<ExecuteToolRequest>,
+) -> Json<ApiResponse<ToolExecution>> {
+    debug!("执行工具 {}（会话 {:?}）", name, req.session_id);
+    let tool = match state.tools.get(&name) {
+        Ok(tool) => tool,
+        Err(e) => return Json(ApiResponse::error(e.message)),
+    };
+    if tool.as_ref().is_some_and(ToolDefinition::requires_consent) {
+        let summary = format!("{} {}", name, req.args);
+        let outcome = state
+            .consent
+            .request(
+                ConsentAction::CommandExec,
+                name.clone(),
+                summary,
+                req.directory,
+                req.session_id,
+            )
+            .await;
+        if !outcome.is_allowed() {
+            return Json(ApiResponse::error(format!(
+                "用户未允许执行工具 {}（{:?}）",
+                name, outcome
+            )));
+        }
+    }
+    Json(match state.tools.execute(&name, req.args).await {
+        Ok(execution) => ApiResponse::success(execution),
+        Err(e) => {
+            warn!("工具 {} 执行失败: {}", name, e);
+            ApiResponse::error(e.message)
+        }
+    })
+}
+
+/// 读取项目固定到上下文的文件内容（查询参数 `project`）
+pub async fn get_pinned_context(
+    State(state): State<PluginApiState>,
+    Query(query): Query<PinnedContextQuery>,
+) -> Json<ApiResponse<PinnedContext>> {
+    let pins = state.context_pins.clone();
+    let result = tokio::task::spawn_blocking(move || pins.contents(&query.project)).await;
+    Json(match result {
+        Ok(Ok(context)) => ApiResponse::success(context),
+        Ok(Err(e)) => ApiResponse::error(e.message),
+        Err(e) => ApiResponse::error(format!("读取固定文件失败: {}", e)),
+    })
+}
+
 /// 编排组响应结构
 #[derive(Debug, Clone, Serialize)]
 pub struct OrchestrationGroupResponse {
@@ -566,3 +813,49 @@ pub async fn get_orchestrations() -> Json<Vec<OrchestrationGroupResponse>> {
     info!("返回 {} 个编排组配置", groups.len());
     Json(groups)
 }
+
+/// 按 ID 读取编排组文件
+fn load_orchestration(id: &str) -> Option<serde_json::Value> {
+    let entries = std::fs::read_dir(get_orchestrations_dir_path()?).ok()?;
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|e| e == "json"))
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .filter_map(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .find(|json| json.get("id").and_then(|v| v.as_str()) == Some(id))
+}
+
+/// 按编排组的委托规则为任务选择子代理
+pub async fn route_orchestration_task(
+    Path(id): Path<String>,
+    Json(req): Json<RouteRequest>,
+) -> Json<ApiResponse<RouteDecision>> {
+    let Some(group) = load_orchestration(&id) else {
+        return Json(ApiResponse::error(format!("编排组不存在: {}", id)));
+    };
+
+    let ruleset: DelegationRuleset = match group.get("delegationRuleset") {
+        Some(value) if !value.is_null() => match serde_json::from_value(value.clone()) {
+            Ok(ruleset) => ruleset,
+            Err(e) => return Json(ApiResponse::error(format!("委托规则无法解析: {}", e))),
+        },
+        _ => DelegationRuleset::default(),
+    };
+    let subagents: Vec<Subagent> = match group.get("subagents") {
+        Some(value) => match serde_json::from_value(value.clone()) {
+            Ok(subagents) => subagents,
+            Err(e) => return Json(ApiResponse::error(format!("子代理配置无法解析: {}", e))),
+        },
+        None => Vec::new(),
+    };
+
+    let decision = delegation::route(&ruleset, &subagents, &req);
+    debug!(
+        "编排组 {} 路由结果: {:?} -> {:?}",
+        id,
+        decision.action,
+        decision.subagent.as_ref().map(|s| &s.name)
+    );
+    Json(ApiResponse::success(decision))
+}
@@ -3,23 +3,44 @@
 //! 提供 HTTP API 供 OpenCode 插件与 Axon 后端通信。
 //! 支持以下功能：
 //! - Agent 动态配置管理
-//! - 事件接收和处理
+//! - 事件接收和处理（用量统计、Agent 活动时间线）
 //! - 编排工作流执行
+//! - 破坏性工具调用的用户确认
+//! - Agent 跨会话记忆
+//! - 用户固定到上下文的文件内容
+//! - 会话级 Agent 参数覆盖（不写入磁盘）
+//! - 当前服务端点查询与变化推送（OpenCode 换端口重启后插件据此更新地址）
+//!
+//! 所有路由按路由模板限流，见 [`rate_limit`]；路由带版本号，见 [`version`]。
 
+mod delegation;
 mod handlers;
+mod rate_limit;
 mod types;
+mod version;
 
+pub use rate_limit::RateLimiter;
 pub use types::*;
+pub use version::{MIN_PLUGIN_API_VERSION, PLUGIN_API_VERSION};
 
+use crate::activity::ActivityFeed;
+use crate::consent::ConsentBroker;
+use crate::context_pins::ContextPinStore;
+use crate::memory::MemoryStore;
+use crate::opencode::is_model_allowed;
+use crate::settings::SettingsManager;
+use crate::tools::ToolRegistry;
+use crate::usage::UsageTracker;
 use axum::{
+    middleware,
     routing::{get, post},
     Router,
 };
 use parking_lot::RwLock;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, oneshot};
 use tracing::{error, info};
 
 /// 插件 API 状态
@@ -33,23 +54,60 @@ pub struct PluginApiState {
     pub events: Arc<RwLock<Vec<PluginEvent>>>,
     /// 服务端口（启动后会更新为实际分配的端口）
     pub port: Arc<RwLock<u16>>,
+    /// 用量统计（从事件中提取 token 用量）
+    pub usage: Arc<UsageTracker>,
+    /// Agent 活动时间线（从事件中合并工具调用）
+    pub activity: Arc<ActivityFeed>,
+    /// 破坏性操作确认
+    pub consent: Arc<ConsentBroker>,
+    /// Agent 记忆
+    pub memory: Arc<MemoryStore>,
+    /// 按路由限流
+    pub rate_limiter: Arc<RateLimiter>,
+    /// 自定义工具
+    pub tools: Arc<ToolRegistry>,
+    /// 应用设置（模型过滤规则）
+    pub settings: Arc<SettingsManager>,
+    /// 固定到上下文的文件
+    pub context_pins: Arc<ContextPinStore>,
+    /// 当前 OpenCode 服务地址（由 OpencodeService 在状态变化时更新）
+    pub opencode_endpoint: Arc<RwLock<Option<String>>>,
+    /// 端点变化通知
+    endpoint_changes: broadcast::Sender<EndpointInfo>,
+    /// 会话级 Agent 参数覆盖（会话 ID → Agent 名称 → 覆盖）
+    pub session_overrides: Arc<RwLock<HashMap<String, HashMap<String, AgentOverride>>>>,
 }
 
-impl Default for PluginApiState {
-    fn default() -> Self {
+impl PluginApiState {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        usage: Arc<UsageTracker>,
+        activity: Arc<ActivityFeed>,
+        consent: Arc<ConsentBroker>,
+        memory: Arc<MemoryStore>,
+        rate_limiter: Arc<RateLimiter>,
+        tools: Arc<ToolRegistry>,
+        settings: Arc<SettingsManager>,
+        context_pins: Arc<ContextPinStore>,
+    ) -> Self {
         Self {
             agents: Arc::new(RwLock::new(HashMap::new())),
             disabled_agents: Arc::new(RwLock::new(Vec::new())),
             events: Arc::new(RwLock::new(Vec::new())),
             port: Arc::new(RwLock::new(0)),
+            usage,
+            activity,
+            consent,
+            memory,
+            rate_limiter,
+            tools,
+            settings,
+            context_pins,
+            opencode_endpoint: Arc::new(RwLock::new(None)),
+            endpoint_changes: broadcast::channel(16).0,
+            session_overrides: Arc::new(RwLock::new(HashMap::new())),
         }
     }
-}
-
-impl PluginApiState {
-    pub fn new() -> Self {
-        Self::default()
-    }
 
     pub fn get_port(&self) -> u16 {
         *self.port.read()
@@ -57,6 +115,37 @@ impl PluginApiState {
 
     pub fn set_port(&self, port: u16) {
         *self.port.write() = port;
+        self.notify_endpoint();
+    }
+
+    /// 插件当前应连接的端点
+    pub fn endpoint(&self) -> EndpointInfo {
+        EndpointInfo {
+            opencode_endpoint: self.opencode_endpoint.read().clone(),
+            plugin_api_port: self.get_port(),
+        }
+    }
+
+    /// 更新 OpenCode 服务地址，变化时通知订阅者
+    pub fn set_opencode_endpoint(&self, endpoint: Option<String>) {
+        {
+            let mut current = self.opencode_endpoint.write();
+            if *current == endpoint {
+                return;
+            }
+            *current = endpoint;
+        }
+        self.notify_endpoint();
+    }
+
+    /// 订阅端点变化
+    pub fn subscribe_endpoint(&self) -> broadcast::Receiver<EndpointInfo> {
+        self.endpoint_changes.subscribe()
+    }
+
+    fn notify_endpoint(&self) {
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.endpoint_changes.send(self.endpoint());
     }
 
     /// 添加或更新 Agent 配置
@@ -74,6 +163,74 @@ impl PluginApiState {
         self.agents.read().clone()
     }
 
+    /// 模型是否被设置中的模型过滤规则允许
+    pub fn is_model_allowed(&self, model: &str) -> bool {
+        is_model_allowed(&self.settings.get_model_filters(), model)
+    }
+
+    /// 去掉使用了被禁止模型的 Agent 的模型配置（回退到默认模型），返回受影响的 Agent 名称
+    pub fn enforce_model_filters(&self, agents: &mut HashMap<String, AgentConfig>) -> Vec<String> {
+        let filters = self.settings.get_model_filters();
+        if filters.is_empty() {
+            return Vec::new();
+        }
+        let mut affected = Vec::new();
+        for (name, agent) in agents.iter_mut() {
+            if agent
+                .model
+                .as_deref()
+                .is_some_and(|model| !is_model_allowed(&filters, model))
+            {
+                agent.model = None;
+                affected.push(name.clone());
+            }
+        }
+        affected
+    }
+
+    /// 设置会话中某个 Agent 的参数覆盖，覆盖为空时移除
+    pub fn set_session_override(
+        &self,
+        session_id: &str,
+        agent: &str,
+        overrides: AgentOverride,
+    ) -> Result<(), String> {
+        if let Some(model) = overrides.model.as_deref() {
+            if !self.is_model_allowed(model) {
+                return Err(format!("模型 {} 已被模型过滤规则禁止", model));
+            }
+        }
+        let mut sessions = self.session_overrides.write();
+        if overrides.is_empty() {
+            if let Some(agents) = sessions.get_mut(session_id) {
+                agents.remove(agent);
+                if agents.is_empty() {
+                    sessions.remove(session_id);
+                }
+            }
+        } else {
+            sessions
+                .entry(session_id.to_string())
+                .or_default()
+                .insert(agent.to_string(), overrides);
+        }
+        Ok(())
+    }
+
+    /// 会话的 Agent 参数覆盖
+    pub fn get_session_overrides(&self, session_id: &str) -> HashMap<String, AgentOverride> {
+        self.session_overrides
+            .read()
+            .get(session_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// 清除会话的 Agent 参数覆盖，返回是否存在覆盖
+    pub fn clear_session_overrides(&self, session_id: &str) -> bool {
+        self.session_overrides.write().remove(session_id).is_some()
+    }
+
     /// 禁用默认 Agent
     #[allow(dead_code)]
     pub fn disable_agent(&self, name: String) {
@@ -96,6 +253,21 @@ impl PluginApiState {
 
     /// 记录事件
     pub fn record_event(&self, event: PluginEvent) {
+        self.usage
+            .record_event(&event.event_type, event.properties.as_ref());
+        self.activity
+            .record_event(&event.event_type, event.properties.as_ref());
+        if event.event_type == "session.deleted" {
+            let session_id = event
+                .properties
+                .as_ref()
+                .and_then(|p| p.pointer("/info/id"))
+                .and_then(|id| id.as_str());
+            if let Some(session_id) = session_id {
+                self.clear_session_overrides(session_id);
+            }
+        }
+
         let mut events = self.events.write();
         // 只保留最近 100 个事件
         if events.len() >= 100 {
@@ -103,6 +275,28 @@ impl PluginApiState {
         }
         events.push(event);
     }
+
+    /// 运行指标
+    pub fn metrics(&self, running: bool) -> PluginApiMetrics {
+        let events = self.events.read();
+        let mut recent_events_by_type = BTreeMap::new();
+        for event in events.iter() {
+            *recent_events_by_type
+                .entry(event.event_type.clone())
+                .or_insert(0) += 1;
+        }
+
+        PluginApiMetrics {
+            port: self.get_port(),
+            running,
+            agent_count: self.agents.read().len(),
+            disabled_agent_count: self.disabled_agents.read().len(),
+            recent_event_count: events.len(),
+            recent_events_by_type,
+            last_event_at: events.last().map(|e| e.received_at),
+            rate_limits: self.rate_limiter.counters(),
+        }
+    }
 }
 
 /// 插件 API 服务器
@@ -112,9 +306,28 @@ pub struct PluginApiServer {
 }
 
 impl PluginApiServer {
-    pub fn new() -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        usage: Arc<UsageTracker>,
+        activity: Arc<ActivityFeed>,
+        consent: Arc<ConsentBroker>,
+        memory: Arc<MemoryStore>,
+        rate_limiter: Arc<RateLimiter>,
+        tools: Arc<ToolRegistry>,
+        settings: Arc<SettingsManager>,
+        context_pins: Arc<ContextPinStore>,
+    ) -> Self {
         Self {
-            state: PluginApiState::new(),
+            state: PluginApiState::new(
+                usage,
+                activity,
+                consent,
+                memory,
+                rate_limiter,
+                tools,
+                settings,
+                context_pins,
+            ),
             shutdown_tx: None,
         }
     }
@@ -148,14 +361,48 @@ impl PluginApiServer {
         let state = self.state.clone();
 
         // 构建路由
+        let routes = Router::new()
+            .route("/health", get(handlers::health_check))
+            .route("/metrics", get(handlers::get_metrics))
+            .route("/config", get(handlers::get_config))
+            .route("/endpoint", get(handlers::get_endpoint))
+            .route("/endpoint/events", get(handlers::endpoint_events))
+            .route("/agents", get(handlers::get_agents))
+            .route("/agents", post(handlers::set_agent))
+            .route("/agents/{name}", axum::routing::delete(handlers::delete_agent))
+            .route("/events", post(handlers::receive_event))
+            .route("/orchestrations", get(handlers::get_orchestrations))
+            .route(
+                "/orchestration/{id}/route",
+                post(handlers::route_orchestration_task),
+            )
+            .route("/consent", post(handlers::request_tool_consent))
+            .route("/memory", get(handlers::list_memory))
+            .route(
+                "/memory/{key}",
+                get(handlers::get_memory)
+                    .put(handlers::set_memory)
+                    .delete(handlers::delete_memory),
+            )
+            .route("/tools", get(handlers::list_tools))
+            .route("/tools/{name}/execute", post(handlers::execute_tool))
+            .route("/context", get(handlers::get_pinned_context))
+            .route(
+                "/sessions/{id}/agent-overrides",
+                get(handlers::get_session_overrides)
+                    .post(handlers::set_session_override)
+                    .delete(handlers::clear_session_overrides),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                rate_limit::rate_limit,
+            ));
+
         let app = Router::new()
-            .route("/api/plugin/health", get(handlers::health_check))
-            .route("/api/plugin/config", get(handlers::get_config))
-            .route("/api/plugin/agents", get(handlers::get_agents))
-            .route("/api/plugin/agents", post(handlers::set_agent))
-            .route("/api/plugin/agents/{name}", axum::routing::delete(handlers::delete_agent))
-            .route("/api/plugin/events", post(handlers::receive_event))
-            .route("/api/plugin/orchestrations", get(handlers::get_orchestrations))
+            .nest(&version::versioned_prefix(), routes.clone())
+            // 兼容旧版插件的无版本号路径（按 v1 处理）
+            .nest(version::LEGACY_PREFIX, routes)
+            .layer(middleware::from_fn(version::negotiate))
             .with_state(state);
 
         info!("Plugin API 服务器启动于 http://127.0.0.1:{}", actual_port);
@@ -183,10 +430,14 @@ impl PluginApiServer {
     }
 
     /// 检查服务器是否在运行
-    #[allow(dead_code)]
     pub fn is_running(&self) -> bool {
         self.shutdown_tx.is_some()
     }
+
+    /// 运行指标
+    pub fn metrics(&self) -> PluginApiMetrics {
+        self.state.metrics(self.is_running())
+    }
 }
 
 impl Drop for PluginApiServer {
@@ -0,0 +1,273 @@
+//! Plugin API 按路由限流
+//!
+//! 每个路由模板（如 `/api/plugin/memory/{key}`，新旧版本路径共用）一个令牌桶，超出速率的请求直接返回
+//! 429 和 `Retry-After`，避免异常插件高频调用拖慢应用。各路由的放行 / 拒绝计数
+//! 通过运行指标对外提供。
+//!
+//! 工具执行确认路由不限流：确认请求由工具调用触发，被限流会让插件无法完成确认。
+
+use super::{version, ApiResponse, PluginApiState, RateLimitCounters};
+use crate::opencode::{RateLimitRule, RateLimitSettings};
+use crate::settings::SettingsManager;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use parking_lot::{Mutex, RwLock};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+/// 不限流的路由（只计数）
+const EXEMPT_ROUTES: &[&str] = &["/api/plugin/consent"];
+
+/// 令牌桶
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    rule: RateLimitRule,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rule: RateLimitRule, now: Instant) -> Self {
+        Self {
+            rule,
+            tokens: f64::from(rule.burst),
+            updated: now,
+        }
+    }
+
+    /// 规则变化时保留已有令牌（不超过新容量）
+    fn set_rule(&mut self, rule: RateLimitRule) {
+        self.rule = rule;
+        self.tokens = self.tokens.min(f64::from(rule.burst));
+    }
+
+    /// 取一个令牌，不足时返回需要等待的时间
+    fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        let rate = f64::from(self.rule.per_second);
+        self.tokens = (self.tokens + elapsed * rate).min(f64::from(self.rule.burst));
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        if rate <= 0.0 {
+            return Err(Duration::from_secs(60));
+        }
+        Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+    }
+}
+
+#[derive(Debug)]
+struct RouteLimit {
+    bucket: TokenBucket,
+    counters: RateLimitCounters,
+    /// 正处于被限流状态（只在进入限流时记一次日志）
+    limiting: bool,
+}
+
+/// 按路由限流器
+#[derive(Debug)]
+pub struct RateLimiter {
+    settings: RwLock<RateLimitSettings>,
+    routes: Mutex<HashMap<String, RouteLimit>>,
+}
+
+impl RateLimiter {
+    pub fn new(settings: RateLimitSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            routes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 更新限流设置，已有计数保留
+    pub fn configure(&self, settings: RateLimitSettings) {
+        for (route, limit) in self.routes.lock().iter_mut() {
+            limit.bucket.set_rule(rule_for(&settings, route));
+        }
+        info!(
+            "插件 API 限流设置已更新: 启用 {}, 默认 {}/s (突发 {})",
+            settings.enabled, settings.default_rule.per_second, settings.default_rule.burst
+        );
+        *self.settings.write() = settings;
+    }
+
+    /// 设置中的限流配置变化时自动更新
+    pub fn follow_settings(self: &Arc<Self>, settings: Arc<SettingsManager>) {
+        let limiter = Arc::clone(self);
+        let mut changes = settings.subscribe();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                match changes.recv().await {
+                    Ok(change) if change.touches("pluginApiRateLimit") => {
+                        limiter.configure(settings.get_plugin_api_rate_limit());
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// 记录一次请求，超出限制时返回建议的重试等待时间
+    pub fn check(&self, route: &str) -> Result<(), Duration> {
+        let settings = self.settings.read();
+        let now = Instant::now();
+        let mut routes = self.routes.lock();
+        let limit = routes
+            .entry(route.to_string())
+            .or_insert_with(|| RouteLimit {
+                bucket: TokenBucket::new(rule_for(&settings, route), now),
+                counters: RateLimitCounters::default(),
+                limiting: false,
+            });
+
+        let result = if settings.enabled && !EXEMPT_ROUTES.contains(&route) {
+            limit.bucket.try_acquire(now)
+        } else {
+            Ok(())
+        };
+        match result {
+            Ok(()) => {
+                limit.counters.allowed += 1;
+                limit.limiting = false;
+            }
+            Err(_) => {
+                limit.counters.limited += 1;
+                limit.counters.last_limited_at = Some(chrono::Utc::now());
+                if !limit.limiting {
+                    limit.limiting = true;
+                    warn!("插件 API 请求过于频繁，已限流: {}", route);
+                }
+            }
+        }
+        result
+    }
+
+    /// 各路由的放行 / 拒绝计数
+    pub fn counters(&self) -> BTreeMap<String, RateLimitCounters> {
+        self.routes
+            .lock()
+            .iter()
+            .map(|(route, limit)| (route.clone(), limit.counters.clone()))
+            .collect()
+    }
+}
+
+fn rule_for(settings: &RateLimitSettings, route: &str) -> RateLimitRule {
+    settings
+        .routes
+        .get(route)
+        .copied()
+        .unwrap_or(settings.default_rule)
+}
+
+/// 限流中间件（需通过 `route_layer` 挂载才能取到路由模板）
+pub async fn rate_limit(
+    State(state): State<PluginApiState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| version::canonical_route(path.as_str()))
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    match state.rate_limiter.check(&route) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after.to_string())],
+                Json(ApiResponse::<()>::error(format!(
+                    "请求过于频繁，请 {} 秒后重试",
+                    retry_after
+                ))),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_allows_burst_then_refills() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(
+            RateLimitRule {
+                burst: 3,
+                per_second: 2,
+            },
+            start,
+        );
+        for _ in 0..3 {
+            assert!(bucket.try_acquire(start).is_ok());
+        }
+        let wait = bucket.try_acquire(start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+
+        assert!(bucket
+            .try_acquire(start + Duration::from_millis(500))
+            .is_ok());
+        assert!(bucket
+            .try_acquire(start + Duration::from_millis(500))
+            .is_err());
+        // 长时间空闲后不超过桶容量
+        let later = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert!(bucket.try_acquire(later).is_ok());
+        }
+        assert!(bucket.try_acquire(later).is_err());
+    }
+
+    #[test]
+    fn limits_routes_independently() {
+        let limiter = RateLimiter::new(RateLimitSettings {
+            enabled: true,
+            default_rule: RateLimitRule {
+                burst: 1,
+                per_second: 1,
+            },
+            routes: BTreeMap::from([(
+                "/api/plugin/events".to_string(),
+                RateLimitRule {
+                    burst: 2,
+                    per_second: 1,
+                },
+            )]),
+        });
+
+        assert!(limiter.check("/api/plugin/health").is_ok());
+        assert!(limiter.check("/api/plugin/health").is_err());
+        assert!(limiter.check("/api/plugin/events").is_ok());
+        assert!(limiter.check("/api/plugin/events").is_ok());
+        assert!(limiter.check("/api/plugin/events").is_err());
+
+        // 确认路由不受默认规则限制
+        for _ in 0..5 {
+            assert!(limiter.check("/api/plugin/consent").is_ok());
+        }
+
+        let counters = limiter.counters();
+        assert_eq!(counters["/api/plugin/consent"].allowed, 5);
+        assert_eq!(counters["/api/plugin/health"].allowed, 1);
+        assert_eq!(counters["/api/plugin/health"].limited, 1);
+        assert_eq!(counters["/api/plugin/events"].allowed, 2);
+        assert_eq!(counters["/api/plugin/events"].limited, 1);
+    }
+}
@@ -1,7 +1,9 @@
 //! Plugin API 类型定义
 
+use crate::consent::{ConsentAction, ConsentOutcome};
+use crate::memory::MemoryScope;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Agent 运行模式
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -64,6 +66,50 @@ pub struct PluginEvent {
     pub received_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// Plugin API 运行指标（用于诊断）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginApiMetrics {
+    pub port: u16,
+    pub running: bool,
+    /// 通过 API 动态添加的 Agent 数量
+    pub agent_count: usize,
+    pub disabled_agent_count: usize,
+    /// 最近保留的事件数量
+    pub recent_event_count: usize,
+    /// 最近事件按类型计数
+    pub recent_events_by_type: BTreeMap<String, usize>,
+    /// 最后一次收到事件的时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_event_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// 按路由模板统计的限流计数
+    pub rate_limits: BTreeMap<String, RateLimitCounters>,
+}
+
+/// 插件需要连接的服务端点
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointInfo {
+    /// OpenCode 服务地址，服务未运行时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opencode_endpoint: Option<String>,
+    /// Plugin API 端口
+    pub plugin_api_port: u16,
+}
+
+/// 单个路由的限流计数
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitCounters {
+    /// 放行的请求数
+    pub allowed: u64,
+    /// 因超出速率被拒绝（429）的请求数
+    pub limited: u64,
+    /// 最后一次被限流的时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_limited_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
 /// Plugin API 配置响应
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginConfigResponse {
@@ -75,6 +121,74 @@ pub struct PluginConfigResponse {
     pub agents: HashMap<String, AgentConfig>,
     /// 禁用的默认 Agent 列表
     pub disabled_agents: Vec<String>,
+    /// 后端当前的 Plugin API 版本
+    pub api_version: u32,
+    /// 后端仍兼容的最低 Plugin API 版本，插件版本不在此范围内时应停止通信
+    pub min_api_version: u32,
+    /// 请求指定会话时，该会话的 Agent 参数覆盖（已应用到 `agents`）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub agent_overrides: HashMap<String, AgentOverride>,
+}
+
+/// 读取配置的查询参数
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigQuery {
+    /// 会话 ID，指定时应用该会话的 Agent 参数覆盖
+    pub session_id: Option<String>,
+}
+
+/// 会话级 Agent 参数覆盖
+///
+/// 只保存在内存中，不修改已保存的 Agent 配置，会话删除或应用退出后失效
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentOverride {
+    /// 使用的模型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 温度参数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Top P 参数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// 工具开关，与 Agent 配置中的工具配置合并
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<HashMap<String, bool>>,
+}
+
+impl AgentOverride {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 应用到 Agent 配置
+    pub fn apply(&self, agent: &mut AgentConfig) {
+        if let Some(model) = &self.model {
+            agent.model = Some(model.clone());
+        }
+        if let Some(temperature) = self.temperature {
+            agent.temperature = Some(temperature);
+        }
+        if let Some(top_p) = self.top_p {
+            agent.top_p = Some(top_p);
+        }
+        if let Some(tools) = &self.tools {
+            agent
+                .tools
+                .get_or_insert_with(HashMap::new)
+                .extend(tools.iter().map(|(name, enabled)| (name.clone(), *enabled)));
+        }
+    }
+}
+
+/// 设置会话级 Agent 参数覆盖请求
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetAgentOverrideRequest {
+    /// Agent 名称
+    pub agent: String,
+    #[serde(flatten)]
+    pub overrides: AgentOverride,
 }
 
 /// 设置 Agent 请求
@@ -84,6 +198,67 @@ pub struct SetAgentRequest {
     pub agent: AgentConfig,
 }
 
+/// 工具执行确认请求
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolConsentRequest {
+    /// 工具名
+    pub tool: String,
+    /// 工具参数
+    #[serde(default)]
+    pub args: serde_json::Value,
+    /// 会话 ID
+    pub session_id: Option<String>,
+    /// 会话所在的项目目录
+    pub directory: Option<String>,
+}
+
+/// 工具执行确认响应
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolConsentResponse {
+    /// 是否允许执行
+    pub allowed: bool,
+    pub outcome: ConsentOutcome,
+    /// 映射到的操作类型（非破坏性工具为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<ConsentAction>,
+}
+
+/// 写入记忆请求
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetMemoryRequest {
+    /// Agent 名称和项目目录
+    #[serde(flatten)]
+    pub scope: MemoryScope,
+    pub value: String,
+}
+
+/// 执行自定义工具请求
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteToolRequest {
+    /// 工具参数
+    #[serde(default = "empty_args")]
+    pub args: serde_json::Value,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// 项目目录，用于匹配"始终允许"规则
+    #[serde(default)]
+    pub directory: Option<String>,
+}
+
+/// 读取固定文件内容的查询参数
+#[derive(Debug, Clone, Deserialize)]
+pub struct PinnedContextQuery {
+    /// 项目目录
+    pub project: String,
+}
+
+fn empty_args() -> serde_json::Value {
+    serde_json::json!({})
+}
+
 /// API 通用响应
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiResponse<T> {
@@ -108,7 +283,6 @@ impl<T> ApiResponse<T> {
     }
 
     /// 创建错误响应
-    #[allow(dead_code)]
     pub fn error(msg: impl Into<String>) -> Self {
         Self {
             success: false,
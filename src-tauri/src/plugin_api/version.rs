@@ -0,0 +1,156 @@
+//! Plugin API 版本协商
+//!
+//! 路由挂载在 `/api/plugin/v{N}/…` 下，旧的 `/api/plugin/…` 路径保留为 v1 的兼容入口。
+//! 插件通过 `X-Axon-Plugin-Api-Version` 请求头声明自己使用的版本，版本超出后端支持范围、
+//! 或与路径中的版本不一致时直接返回版本不匹配错误，避免双方按不同结构解析请求体。
+
+use super::ApiResponse;
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// 当前 Plugin API 版本
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// 仍兼容的最低版本
+pub const MIN_PLUGIN_API_VERSION: u32 = 1;
+
+/// 插件声明版本 / 后端返回版本的请求头
+pub const API_VERSION_HEADER: &str = "x-axon-plugin-api-version";
+
+/// 旧版（未带版本号）路由前缀
+pub const LEGACY_PREFIX: &str = "/api/plugin";
+
+/// 当前版本的路由前缀
+pub fn versioned_prefix() -> String {
+    format!("{}/v{}", LEGACY_PREFIX, PLUGIN_API_VERSION)
+}
+
+/// 路由模板去掉版本号（`/api/plugin/v1/events` → `/api/plugin/events`），
+/// 新旧路径共用同一套限流规则和计数
+pub fn canonical_route(route: &str) -> String {
+    match path_version(route) {
+        Some((_, rest)) => format!("{}{}", LEGACY_PREFIX, rest),
+        None => route.to_string(),
+    }
+}
+
+/// 解析路径中的版本号，返回版本和版本号之后的部分
+fn path_version(path: &str) -> Option<(u32, &str)> {
+    let rest = path.strip_prefix(LEGACY_PREFIX)?.strip_prefix("/v")?;
+    let end = rest.find('/').unwrap_or(rest.len());
+    let version = rest[..end].parse().ok()?;
+    Some((version, &rest[end..]))
+}
+
+fn is_supported(version: u32) -> bool {
+    (MIN_PLUGIN_API_VERSION..=PLUGIN_API_VERSION).contains(&version)
+}
+
+/// 检查插件声明的版本是否受支持、是否与路径版本一致，返回错误信息
+fn check(path: &str, declared: Option<&str>) -> Result<(), String> {
+    let declared = match declared {
+        Some(value) => Some(
+            value
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("无效的 Plugin API 版本: {}", value))?,
+        ),
+        None => None,
+    };
+    let path_version = path_version(path).map(|(version, _)| version);
+
+    if let Some(version) = declared.or(path_version) {
+        if !is_supported(version) {
+            return Err(mismatch_message(version));
+        }
+    }
+    if let (Some(declared), Some(path_version)) = (declared, path_version) {
+        if declared != path_version {
+            return Err(format!(
+                "Plugin API 版本不匹配：请求头声明 v{}，但请求路径为 v{}",
+                declared, path_version
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn mismatch_message(version: u32) -> String {
+    format!(
+        "Plugin API 版本不匹配：插件使用 v{}，当前 Axon 支持 v{} - v{}，请更新{}",
+        version,
+        MIN_PLUGIN_API_VERSION,
+        PLUGIN_API_VERSION,
+        if version > PLUGIN_API_VERSION {
+            " Axon"
+        } else {
+            "插件"
+        }
+    )
+}
+
+fn mismatch_response(message: String) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiResponse::<()>::error(message)),
+    )
+        .into_response()
+}
+
+fn with_version_header(mut response: Response) -> Response {
+    response.headers_mut().insert(
+        HeaderName::from_static(API_VERSION_HEADER),
+        HeaderValue::from(PLUGIN_API_VERSION),
+    );
+    response
+}
+
+/// 版本协商中间件，所有响应都带上后端当前的版本号
+pub async fn negotiate(request: Request, next: Next) -> Response {
+    let declared = request
+        .headers()
+        .get(API_VERSION_HEADER)
+        .map(|value| value.to_str().unwrap_or_default().to_string());
+
+    let response = match check(request.uri().path(), declared.as_deref()) {
+        Ok(()) => next.run(request).await,
+        Err(message) => mismatch_response(message),
+    };
+    with_version_header(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_version_from_routes() {
+        assert_eq!(
+            canonical_route("/api/plugin/v1/events"),
+            "/api/plugin/events"
+        );
+        assert_eq!(
+            canonical_route("/api/plugin/v1/memory/{key}"),
+            "/api/plugin/memory/{key}"
+        );
+        assert_eq!(canonical_route("/api/plugin/events"), "/api/plugin/events");
+        assert_eq!(canonical_route("/api/plugin/vault"), "/api/plugin/vault");
+    }
+
+    #[test]
+    fn rejects_unsupported_or_conflicting_versions() {
+        assert!(check("/api/plugin/events", None).is_ok());
+        assert!(check("/api/plugin/events", Some("1")).is_ok());
+        assert!(check("/api/plugin/v1/events", Some("1")).is_ok());
+
+        let newer = check("/api/plugin/events", Some("2")).unwrap_err();
+        assert!(newer.contains("版本不匹配"));
+        assert!(check("/api/plugin/v2/events", None).is_err());
+        assert!(check("/api/plugin/events", Some("abc")).is_err());
+    }
+}
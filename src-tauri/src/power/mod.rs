@@ -0,0 +1,233 @@
+//! 空闲与电源检测
+//!
+//! 定期检测用户空闲时长和是否使用电池供电，按节能设置决定是否暂停后台任务
+//! （模型注册表定时刷新、语义索引构建等）。暂停状态变化时发送 `power:state` 事件，
+//! 前端据此暂停定时工作流等自身的后台工作。
+
+use crate::jobs::JobHandle;
+use crate::opencode::PowerSettings;
+use crate::settings::SettingsManager;
+use parking_lot::RwLock;
+use serde::Serialize;
+use starship_battery::units::ratio::percent;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, info, warn};
+
+/// 电源状态变化事件
+pub const EVENT_POWER_STATE: &str = "power:state";
+
+/// 检测间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 暂停期间的检测间隔（尽快发现用户回来或接通电源）
+const PAUSED_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 等待恢复时检查暂停状态和取消请求的间隔
+const RESUME_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 暂停原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PauseReason {
+    Battery,
+    Idle,
+}
+
+/// 电源与空闲状态
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerState {
+    /// 使用电池供电（无法检测时为 false）
+    pub on_battery: bool,
+    /// 电池电量（0 - 100），没有电池时为空
+    pub battery_percent: Option<f32>,
+    /// 用户空闲秒数，无法检测时为空
+    pub idle_seconds: Option<u64>,
+    /// 后台任务是否已暂停
+    pub paused: bool,
+    pub pause_reason: Option<PauseReason>,
+}
+
+/// 电源状态监视器
+pub struct PowerMonitor {
+    settings: Arc<SettingsManager>,
+    state: RwLock<PowerState>,
+    app_handle: RwLock<Option<AppHandle>>,
+    started: AtomicBool,
+}
+
+impl PowerMonitor {
+    pub fn new(settings: Arc<SettingsManager>) -> Arc<Self> {
+        Arc::new(Self {
+            settings,
+            state: RwLock::new(PowerState::default()),
+            app_handle: RwLock::new(None),
+            started: AtomicBool::new(false),
+        })
+    }
+
+    /// 启动后台检测
+    pub fn start(self: &Arc<Self>, app: AppHandle) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        *self.app_handle.write() = Some(app);
+
+        let monitor = Arc::clone(self);
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let sampler = Arc::clone(&monitor);
+                if let Err(e) = tokio::task::spawn_blocking(move || sampler.refresh()).await {
+                    warn!("检测电源状态失败: {}", e);
+                }
+                let interval = if monitor.is_paused() {
+                    PAUSED_POLL_INTERVAL
+                } else {
+                    POLL_INTERVAL
+                };
+                tokio::time::sleep(interval).await;
+            }
+        });
+        info!("空闲与电源检测已启动");
+    }
+
+    /// 后台任务是否应暂停
+    pub fn is_paused(&self) -> bool {
+        self.state.read().paused
+    }
+
+    /// 重新检测空闲时长和电池状态（阻塞调用）
+    pub fn refresh(&self) -> PowerState {
+        let (on_battery, battery_percent) = battery_status();
+        let idle_seconds = match user_idle::UserIdle::get_time() {
+            Ok(idle) => Some(idle.as_seconds()),
+            Err(e) => {
+                debug!("获取空闲时长失败: {}", e);
+                None
+            }
+        };
+        self.update(|state| {
+            state.on_battery = on_battery;
+            state.battery_percent = battery_percent;
+            state.idle_seconds = idle_seconds;
+        })
+    }
+
+    /// 节能设置变化后按最近一次检测结果重新判断
+    pub fn reevaluate(&self) -> PowerState {
+        self.update(|_| {})
+    }
+
+    /// 后台任务暂停期间等待恢复，任务被取消时提前返回
+    pub async fn wait_until_resumed(&self, job: &JobHandle) {
+        if !self.is_paused() {
+            return;
+        }
+        debug!("后台任务已暂停: {}", job.id());
+        while self.is_paused() && !job.is_cancelled() {
+            tokio::time::sleep(RESUME_CHECK_INTERVAL).await;
+        }
+        debug!("后台任务已恢复: {}", job.id());
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut PowerState)) -> PowerState {
+        let settings = self.settings.get_power_settings();
+        let (previous, current) = {
+            let mut state = self.state.write();
+            let previous = state.clone();
+            apply(&mut state);
+            state.pause_reason = pause_reason(&settings, state.on_battery, state.idle_seconds);
+            state.paused = state.pause_reason.is_some();
+            (previous, state.clone())
+        };
+
+        if previous.paused != current.paused
+            || previous.pause_reason != current.pause_reason
+            || previous.on_battery != current.on_battery
+        {
+            info!(
+                "电源状态变化: 电池供电 {}, 暂停 {:?}",
+                current.on_battery, current.pause_reason
+            );
+            if let Some(handle) = self.app_handle.read().as_ref() {
+                if let Err(e) = handle.emit(EVENT_POWER_STATE, &current) {
+                    warn!("发送电源状态事件失败: {}", e);
+                }
+            }
+        }
+        current
+    }
+}
+
+/// 按节能设置判断暂停原因，电池优先
+fn pause_reason(
+    settings: &PowerSettings,
+    on_battery: bool,
+    idle_seconds: Option<u64>,
+) -> Option<PauseReason> {
+    if settings.pause_on_battery && on_battery {
+        return Some(PauseReason::Battery);
+    }
+    let threshold = u64::from(settings.idle_threshold_minutes) * 60;
+    if settings.pause_when_idle && threshold > 0 && idle_seconds.is_some_and(|s| s >= threshold) {
+        return Some(PauseReason::Idle);
+    }
+    None
+}
+
+/// 是否使用电池供电及电量（取第一块电池），没有电池或无法检测时视为接通电源
+fn battery_status() -> (bool, Option<f32>) {
+    let batteries = starship_battery::Manager::new().and_then(|manager| manager.batteries());
+    let batteries = match batteries {
+        Ok(batteries) => batteries,
+        Err(e) => {
+            debug!("获取电池信息失败: {}", e);
+            return (false, None);
+        }
+    };
+
+    let mut on_battery = false;
+    let mut level = None;
+    for battery in batteries.flatten() {
+        on_battery |= battery.state() == starship_battery::State::Discharging;
+        level.get_or_insert(battery.state_of_charge().get::<percent>());
+    }
+    (on_battery, level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn battery_takes_precedence_over_idle() {
+        let settings = PowerSettings {
+            pause_on_battery: true,
+            pause_when_idle: true,
+            idle_threshold_minutes: 10,
+        };
+        assert_eq!(
+            pause_reason(&settings, true, Some(3600)),
+            Some(PauseReason::Battery)
+        );
+        assert_eq!(
+            pause_reason(&settings, false, Some(600)),
+            Some(PauseReason::Idle)
+        );
+        assert_eq!(pause_reason(&settings, false, Some(599)), None);
+        assert_eq!(pause_reason(&settings, false, None), None);
+    }
+
+    #[test]
+    fn disabled_settings_never_pause() {
+        let settings = PowerSettings {
+            pause_on_battery: false,
+            pause_when_idle: false,
+            idle_threshold_minutes: 1,
+        };
+        assert_eq!(pause_reason(&settings, true, Some(3600)), None);
+    }
+}
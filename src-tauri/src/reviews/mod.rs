@@ -0,0 +1,705 @@
+//! Diff 审查会话
+//!
+//! 把一组文件修改前后的快照保存为一次审查（`<app_data_dir>/reviews/<id>.json`），
+//! 用户逐文件或逐 hunk 接受 / 拒绝，最后一次性定稿：接受的 hunk 写入文件，拒绝的丢弃。
+//! hunk 按 `compute_diff` 的默认上下文划分，前端展示的序号可直接用于决策。
+//! 定稿时文件必须仍是修改前或修改后的内容，否则视为冲突；替换中途失败时回滚已写入的文件。
+
+use crate::commands::{apply_line_hunks, compute_line_diff};
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::json_store;
+use crate::utils::paths::get_app_data_dir;
+use parking_lot::Mutex;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// 存储目录（相对应用数据目录）
+const REVIEWS_DIR: &str = "reviews";
+
+/// 单次审查的文件数上限
+const MAX_REVIEW_FILES: usize = 200;
+
+/// 单个快照的最大字节数
+const MAX_SNAPSHOT_BYTES: usize = 2 * 1024 * 1024;
+
+/// 划分 hunk 使用的上下文行数（与 `compute_diff` 默认值一致）
+const REVIEW_CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewDecision {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewStatus {
+    Open,
+    Finalized,
+}
+
+/// 创建审查时提交的文件快照
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewFileInput {
+    pub path: String,
+    /// 修改前的内容，为空表示新建文件
+    #[serde(default)]
+    pub before: Option<String>,
+    /// 修改后的内容，为空表示删除文件
+    #[serde(default)]
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewReview {
+    #[serde(default)]
+    pub title: Option<String>,
+    /// 产生这些修改的 Agent 会话
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub project_dir: Option<String>,
+    pub files: Vec<ReviewFileInput>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewFile {
+    pub path: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    /// 每个 hunk 的决策，序号与 `compute_diff(before, after)` 的 hunks 对应
+    pub hunks: Vec<ReviewDecision>,
+}
+
+impl ReviewFile {
+    fn pending_hunks(&self) -> usize {
+        self.hunks
+            .iter()
+            .filter(|d| **d == ReviewDecision::Pending)
+            .count()
+    }
+
+    /// 按决策计算定稿后的内容，`None` 表示文件不应存在
+    fn resolve(&self) -> Result<Option<String>, AxonError> {
+        if self.hunks.iter().all(|d| *d == ReviewDecision::Accepted) {
+            return Ok(self.after.clone());
+        }
+        if self.hunks.iter().all(|d| *d == ReviewDecision::Rejected) {
+            return Ok(self.before.clone());
+        }
+        let accepted = self
+            .hunks
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| **d == ReviewDecision::Accepted)
+            .map(|(i, _)| i)
+            .collect();
+        let result = apply_line_hunks(
+            self.before.as_deref().unwrap_or_default(),
+            self.after.as_deref().unwrap_or_default(),
+            accepted,
+            Some(REVIEW_CONTEXT_LINES),
+        )?;
+        Ok(Some(result.content))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Review {
+    pub id: String,
+    pub title: Option<String>,
+    pub session_id: Option<String>,
+    pub project_dir: Option<String>,
+    pub status: ReviewStatus,
+    pub files: Vec<ReviewFile>,
+    /// Unix 毫秒
+    pub created_at: i64,
+    pub updated_at: i64,
+    #[serde(default)]
+    pub finalized_at: Option<i64>,
+}
+
+/// 列表中的审查摘要（不含快照内容）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewSummary {
+    pub id: String,
+    pub title: Option<String>,
+    pub session_id: Option<String>,
+    pub project_dir: Option<String>,
+    pub status: ReviewStatus,
+    pub file_count: usize,
+    /// 尚未决定的 hunk 数
+    pub pending_hunks: usize,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl From<&Review> for ReviewSummary {
+    fn from(review: &Review) -> Self {
+        Self {
+            id: review.id.clone(),
+            title: review.title.clone(),
+            session_id: review.session_id.clone(),
+            project_dir: review.project_dir.clone(),
+            status: review.status,
+            file_count: review.files.len(),
+            pending_hunks: review.files.iter().map(ReviewFile::pending_hunks).sum(),
+            created_at: review.created_at,
+            updated_at: review.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FinalizeAction {
+    Written,
+    Deleted,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalizedFile {
+    pub path: String,
+    pub action: FinalizeAction,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalizeResult {
+    pub review: Review,
+    pub files: Vec<FinalizedFile>,
+}
+
+/// 定稿时对单个文件的操作
+struct PlannedChange {
+    path: PathBuf,
+    /// 定稿前磁盘上的内容，用于回滚
+    original: Option<String>,
+    target: Option<String>,
+}
+
+/// 审查会话存储
+#[derive(Debug)]
+pub struct ReviewStore {
+    /// 存储目录，为空时使用应用数据目录下的 reviews
+    dir: Option<PathBuf>,
+    /// 串行化读写
+    lock: Mutex<()>,
+}
+
+impl ReviewStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            dir: None,
+            lock: Mutex::new(()),
+        })
+    }
+
+    #[cfg(test)]
+    fn with_dir(dir: PathBuf) -> Self {
+        Self {
+            dir: Some(dir),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 创建审查，所有 hunk 初始为待定
+    pub fn create(&self, input: NewReview) -> Result<Review, AxonError> {
+        if input.files.is_empty() {
+            return Err(AxonError::invalid_input("审查至少需要包含一个文件"));
+        }
+        if input.files.len() > MAX_REVIEW_FILES {
+            return Err(AxonError::invalid_input(format!(
+                "审查文件数超过上限（{} 个）",
+                MAX_REVIEW_FILES
+            )));
+        }
+
+        let mut seen = HashSet::new();
+        let mut files = Vec::with_capacity(input.files.len());
+        for file in input.files {
+            if !seen.insert(file.path.clone()) {
+                return Err(AxonError::invalid_input(format!(
+                    "审查中包含重复的文件: {}",
+                    file.path
+                )));
+            }
+            if !Path::new(&file.path).is_absolute() {
+                return Err(AxonError::invalid_input(format!(
+                    "审查文件必须是绝对路径: {}",
+                    file.path
+                )));
+            }
+            let too_large = [&file.before, &file.after]
+                .into_iter()
+                .flatten()
+                .any(|text| text.len() > MAX_SNAPSHOT_BYTES);
+            if too_large {
+                return Err(AxonError::invalid_input(format!(
+                    "文件快照过大（超过 {} MB）: {}",
+                    MAX_SNAPSHOT_BYTES / 1024 / 1024,
+                    file.path
+                )));
+            }
+            let hunk_count = compute_line_diff(
+                file.before.as_deref().unwrap_or_default(),
+                file.after.as_deref().unwrap_or_default(),
+                None,
+                Some(REVIEW_CONTEXT_LINES),
+            )
+            .hunks
+            .len();
+            files.push(ReviewFile {
+                path: file.path,
+                before: file.before,
+                after: file.after,
+                hunks: vec![ReviewDecision::Pending; hunk_count],
+            });
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let review = Review {
+            id: format!("review-{:016x}", rand::thread_rng().gen::<u64>()),
+            title: input.title.filter(|title| !title.trim().is_empty()),
+            session_id: input.session_id,
+            project_dir: input.project_dir,
+            status: ReviewStatus::Open,
+            files,
+            created_at: now,
+            updated_at: now,
+            finalized_at: None,
+        };
+
+        let _guard = self.lock.lock();
+        self.write_review(&review)?;
+        debug!("已创建审查: {}, {} 个文件", review.id, review.files.len());
+        Ok(review)
+    }
+
+    /// 列出审查（最近更新的在前），可按项目过滤
+    pub fn list(&self, project_dir: Option<&str>) -> Result<Vec<ReviewSummary>, AxonError> {
+        let _guard = self.lock.lock();
+        let dir = self.dir()?;
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AxonError::io("读取审查目录失败", &e)),
+        };
+
+        let mut summaries: Vec<ReviewSummary> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| match read_review_file(&path) {
+                Ok(review) => Some(review),
+                Err(e) => {
+                    warn!("审查文件无法读取，已跳过: {:?}, 错误: {}", path, e);
+                    None
+                }
+            })
+            .filter(|review| {
+                project_dir.is_none_or(|project| {
+                    review
+                        .project_dir
+                        .as_deref()
+                        .is_some_and(|dir| same_project(dir, project))
+                })
+            })
+            .map(|review| ReviewSummary::from(&review))
+            .collect();
+        summaries.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
+        Ok(summaries)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Review, AxonError> {
+        let _guard = self.lock.lock();
+        self.read_review(id)
+    }
+
+    /// 更新决策，`hunk_indices` 为空时作用于整个文件
+    pub fn decide(
+        &self,
+        id: &str,
+        path: &str,
+        hunk_indices: Option<&[usize]>,
+        decision: ReviewDecision,
+    ) -> Result<Review, AxonError> {
+        let _guard = self.lock.lock();
+        let mut review = self.read_review(id)?;
+        ensure_open(&review)?;
+
+        let file = review
+            .files
+            .iter_mut()
+            .find(|file| file.path == path)
+            .ok_or_else(|| AxonError::not_found(format!("审查中没有该文件: {}", path)))?;
+        match hunk_indices {
+            None => file.hunks.fill(decision),
+            Some(indices) => {
+                if let Some(&invalid) = indices.iter().find(|&&i| i >= file.hunks.len()) {
+                    return Err(AxonError::invalid_input(format!(
+                        "hunk 序号超出范围: {}（共 {} 个）",
+                        invalid,
+                        file.hunks.len()
+                    )));
+                }
+                for &index in indices {
+                    file.hunks[index] = decision;
+                }
+            }
+        }
+
+        review.updated_at = chrono::Utc::now().timestamp_millis();
+        self.write_review(&review)?;
+        Ok(review)
+    }
+
+    /// 定稿：写入接受的 hunk，丢弃拒绝的 hunk
+    ///
+    /// 所有 hunk 都必须已决定；任一文件冲突时不修改任何文件
+    pub fn finalize(&self, id: &str) -> Result<FinalizeResult, AxonError> {
+        let _guard = self.lock.lock();
+        let mut review = self.read_review(id)?;
+        ensure_open(&review)?;
+
+        let pending: usize = review.files.iter().map(ReviewFile::pending_hunks).sum();
+        if pending > 0 {
+            return Err(AxonError::invalid_input(format!(
+                "还有 {} 个 hunk 未决定",
+                pending
+            )));
+        }
+
+        let mut plans = Vec::with_capacity(review.files.len());
+        let mut conflicts = Vec::new();
+        for file in &review.files {
+            let original = read_current(Path::new(&file.path))?;
+            if original != file.before && original != file.after {
+                conflicts.push(file.path.clone());
+                continue;
+            }
+            plans.push(PlannedChange {
+                path: PathBuf::from(&file.path),
+                original,
+                target: file.resolve()?,
+            });
+        }
+        if !conflicts.is_empty() {
+            return Err(AxonError::invalid_data("文件在审查期间被修改，无法定稿")
+                .with_details(serde_json::json!({ "conflicts": conflicts })));
+        }
+
+        let files = plans
+            .iter()
+            .map(|plan| FinalizedFile {
+                path: plan.path.to_string_lossy().to_string(),
+                action: match &plan.target {
+                    _ if plan.original == plan.target => FinalizeAction::Unchanged,
+                    Some(_) => FinalizeAction::Written,
+                    None => FinalizeAction::Deleted,
+                },
+            })
+            .collect();
+        plans.retain(|plan| plan.original != plan.target);
+        commit(&plans)?;
+
+        let now = chrono::Utc::now().timestamp_millis();
+        review.status = ReviewStatus::Finalized;
+        review.updated_at = now;
+        review.finalized_at = Some(now);
+        self.write_review(&review)?;
+        debug!("审查已定稿: {}, 修改 {} 个文件", review.id, plans.len());
+        Ok(FinalizeResult { review, files })
+    }
+
+    /// 删除审查，返回审查是否存在
+    pub fn delete(&self, id: &str) -> Result<bool, AxonError> {
+        let _guard = self.lock.lock();
+        match std::fs::remove_file(self.review_path(id)?) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(AxonError::io("删除审查失败", &e)),
+        }
+    }
+
+    fn dir(&self) -> Result<PathBuf, AxonError> {
+        self.dir
+            .clone()
+            .or_else(|| get_app_data_dir().map(|d| d.join(REVIEWS_DIR)))
+            .ok_or_else(|| {
+                AxonError::localized(
+                    ErrorKind::Unavailable,
+                    "app.data_dir_unavailable",
+                    serde_json::json!(null),
+                )
+            })
+    }
+
+    fn review_path(&self, id: &str) -> Result<PathBuf, AxonError> {
+        let valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(AxonError::invalid_input(format!("无效的审查 ID: {}", id)));
+        }
+        Ok(self.dir()?.join(format!("{}.json", id)))
+    }
+
+    fn read_review(&self, id: &str) -> Result<Review, AxonError> {
+        let path = self.review_path(id)?;
+        if !path.exists() {
+            return Err(AxonError::not_found(format!("审查不存在: {}", id)));
+        }
+        read_review_file(&path)
+    }
+
+    fn write_review(&self, review: &Review) -> Result<(), AxonError> {
+        json_store::save(&self.review_path(&review.id)?, review, "审查文件")
+    }
+}
+
+fn read_review_file(path: &Path) -> Result<Review, AxonError> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| AxonError::io("读取审查文件失败", &e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| AxonError::invalid_data(format!("审查文件格式错误: {}", e)))
+}
+
+fn ensure_open(review: &Review) -> Result<(), AxonError> {
+    if review.status == ReviewStatus::Finalized {
+        return Err(AxonError::invalid_input(format!(
+            "审查已定稿: {}",
+            review.id
+        )));
+    }
+    Ok(())
+}
+
+fn same_project(a: &str, b: &str) -> bool {
+    a.trim_end_matches(['/', '\\']) == b.trim_end_matches(['/', '\\'])
+}
+
+/// 读取文件当前内容，不存在时为 `None`
+fn read_current(path: &Path) -> Result<Option<String>, AxonError> {
+    match std::fs::read(path) {
+        Ok(bytes) => String::from_utf8(bytes).map(Some).map_err(|_| {
+            AxonError::invalid_data(format!("文件不是 UTF-8 文本: {}", path.display()))
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(AxonError::io("读取审查文件内容失败", &e)),
+    }
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".axon-review.tmp");
+    path.with_file_name(name)
+}
+
+/// 先把所有新内容写入临时文件，全部成功后再逐个替换；替换失败时回滚已完成的文件
+fn commit(plans: &[PlannedChange]) -> Result<(), AxonError> {
+    let mut temps: Vec<Option<PathBuf>> = Vec::with_capacity(plans.len());
+    for plan in plans {
+        let Some(content) = &plan.target else {
+            temps.push(None);
+            continue;
+        };
+        let temp = temp_path(&plan.path);
+        let written = plan
+            .path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&temp, content));
+        if let Err(e) = written {
+            remove_temps(temps.iter().flatten());
+            return Err(AxonError::io(
+                format!("写入 {} 失败", plan.path.display()),
+                &e,
+            ));
+        }
+        temps.push(Some(temp));
+    }
+
+    for (done, (plan, temp)) in plans.iter().zip(&temps).enumerate() {
+        let result = match temp {
+            Some(temp) => std::fs::rename(temp, &plan.path),
+            None => match std::fs::remove_file(&plan.path) {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                other => other,
+            },
+        };
+        if let Err(e) = result {
+            rollback(&plans[..done]);
+            remove_temps(temps[done..].iter().flatten());
+            return Err(AxonError::io(
+                format!("替换 {} 失败，已回滚", plan.path.display()),
+                &e,
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn rollback(plans: &[PlannedChange]) {
+    for plan in plans {
+        let result = match &plan.original {
+            Some(content) => std::fs::write(&plan.path, content),
+            None => std::fs::remove_file(&plan.path),
+        };
+        if let Err(e) = result {
+            warn!("回滚文件失败: {:?}, 错误: {}", plan.path, e);
+        }
+    }
+}
+
+fn remove_temps<'a>(temps: impl Iterator<Item = &'a PathBuf>) {
+    for temp in temps {
+        let _ = std::fs::remove_file(temp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, ReviewStore, PathBuf) {
+        let root = tempfile::tempdir().unwrap();
+        let work = root.path().join("work");
+        std::fs::create_dir_all(&work).unwrap();
+        let store = ReviewStore::with_dir(root.path().join("store"));
+        (root, store, work)
+    }
+
+    fn input(path: &Path, before: Option<&str>, after: Option<&str>) -> ReviewFileInput {
+        ReviewFileInput {
+            path: path.to_string_lossy().to_string(),
+            before: before.map(str::to_string),
+            after: after.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn finalizes_accepted_hunks_only() {
+        let (_root, store, work) = setup();
+        let before = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+        let after = "1\nX\n3\n4\n5\n6\n7\n8\n9\n10\nY\n12\n";
+        let edited = work.join("edited.txt");
+        let created = work.join("created.txt");
+        std::fs::write(&edited, after).unwrap();
+        std::fs::write(&created, "new\n").unwrap();
+
+        let review = store
+            .create(NewReview {
+                title: Some("重构".to_string()),
+                session_id: None,
+                project_dir: Some(work.to_string_lossy().to_string()),
+                files: vec![
+                    input(&edited, Some(before), Some(after)),
+                    input(&created, None, Some("new\n")),
+                ],
+            })
+            .unwrap();
+        assert_eq!(review.files[0].hunks.len(), 2);
+        assert!(store.finalize(&review.id).is_err());
+
+        let edited_path = edited.to_string_lossy();
+        store
+            .decide(&review.id, &edited_path, None, ReviewDecision::Rejected)
+            .unwrap();
+        store
+            .decide(
+                &review.id,
+                &edited_path,
+                Some(&[1]),
+                ReviewDecision::Accepted,
+            )
+            .unwrap();
+        store
+            .decide(
+                &review.id,
+                &created.to_string_lossy(),
+                None,
+                ReviewDecision::Rejected,
+            )
+            .unwrap();
+        assert!(store
+            .decide(
+                &review.id,
+                &edited_path,
+                Some(&[5]),
+                ReviewDecision::Accepted
+            )
+            .is_err());
+
+        let listed = store.list(Some(&format!("{}/", work.display()))).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].pending_hunks, 0);
+
+        let result = store.finalize(&review.id).unwrap();
+        assert_eq!(result.review.status, ReviewStatus::Finalized);
+        assert_eq!(result.files[0].action, FinalizeAction::Written);
+        assert_eq!(result.files[1].action, FinalizeAction::Deleted);
+        assert_eq!(
+            std::fs::read_to_string(&edited).unwrap(),
+            "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\nY\n12\n"
+        );
+        assert!(!created.exists());
+        assert!(store
+            .decide(&review.id, &edited_path, None, ReviewDecision::Accepted)
+            .is_err());
+    }
+
+    #[test]
+    fn refuses_to_finalize_conflicting_files() {
+        let (_root, store, work) = setup();
+        let a = work.join("a.txt");
+        let b = work.join("b.txt");
+        std::fs::write(&a, "new a\n").unwrap();
+        std::fs::write(&b, "edited elsewhere\n").unwrap();
+
+        let review = store
+            .create(NewReview {
+                title: None,
+                session_id: Some("ses_1".to_string()),
+                project_dir: None,
+                files: vec![
+                    input(&a, Some("old a\n"), Some("new a\n")),
+                    input(&b, Some("old b\n"), Some("new b\n")),
+                ],
+            })
+            .unwrap();
+        for path in [&a, &b] {
+            store
+                .decide(
+                    &review.id,
+                    &path.to_string_lossy(),
+                    None,
+                    ReviewDecision::Rejected,
+                )
+                .unwrap();
+        }
+
+        assert!(store.finalize(&review.id).is_err());
+        // 冲突时不修改任何文件
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "new a\n");
+        assert_eq!(store.get(&review.id).unwrap().status, ReviewStatus::Open);
+        assert!(store.delete(&review.id).unwrap());
+        assert!(store.get(&review.id).is_err());
+        assert!(store.get("../escape").is_err());
+    }
+}
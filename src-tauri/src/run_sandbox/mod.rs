@@ -0,0 +1,548 @@
+//! Agent 运行沙箱
+//!
+//! 在项目的一次性副本中运行工作流 / Agent，确认结果后再把改动应用回项目：
+//! - Git 仓库用 `git worktree add --detach` 检出 HEAD，再同步未提交的改动和未跟踪文件
+//!   （项目是仓库的子目录时，Agent 的工作目录是 worktree 中对应的子目录）；
+//! - 其它目录复制到临时区域（跳过依赖和构建目录），并初始化一个仅供比较的 Git 仓库。
+//!
+//! 两种方式都会在沙箱中提交一次基线，之后沙箱中的全部改动都相对基线比较。
+//! 创建沙箱后以沙箱目录启动一个同名的附加 OpenCode 实例（见 [`crate::opencode::ServiceManager`]），
+//! Agent 在该实例中运行，不会读写原项目。
+//! 应用改动时可以只选择部分文件，补丁先以 `git apply --check` 检查，冲突时不修改项目。
+
+use crate::error::AxonError;
+use crate::utils::paths::get_app_data_dir;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{info, warn};
+
+/// 沙箱索引文件（相对应用数据目录）
+const INDEX_FILE: &str = "run-sandboxes.json";
+
+/// 沙箱根目录名（位于系统临时目录下）
+const SANDBOX_ROOT: &str = "axon-sandboxes";
+
+/// 复制非 Git 项目时跳过的目录
+const SKIPPED_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    ".venv",
+    "venv",
+    "__pycache__",
+    "dist",
+    "build",
+    ".next",
+];
+
+/// 基线提交使用的作者信息
+const BASELINE_AUTHOR: [&str; 4] = ["-c", "user.name=Axon", "-c", "user.email=axon@localhost"];
+
+/// 沙箱创建方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SandboxKind {
+    /// Git worktree
+    Worktree,
+    /// 目录复制
+    Copy,
+}
+
+/// 运行沙箱
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSandbox {
+    pub id: String,
+    /// 原项目目录
+    pub project_dir: String,
+    /// 应用改动的根目录（Git 仓库顶层或项目目录）
+    pub project_root: String,
+    /// 沙箱根目录（worktree 或复制出的目录）
+    pub sandbox_root: String,
+    /// Agent 的工作目录
+    pub directory: String,
+    /// 在沙箱目录中运行 Agent 的 OpenCode 实例名称（与沙箱 ID 相同）
+    #[serde(default)]
+    pub instance: String,
+    /// 项目目录相对仓库顶层的路径（`packages/app/`），非子目录时为空
+    pub prefix: String,
+    pub kind: SandboxKind,
+    /// 基线提交
+    pub baseline: String,
+    /// Unix 毫秒
+    pub created_at: i64,
+}
+
+/// 沙箱中改动的文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxFileChange {
+    pub path: String,
+    /// 二进制文件没有行数统计
+    pub additions: Option<u32>,
+    pub deletions: Option<u32>,
+}
+
+/// 沙箱相对基线的改动
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxDiff {
+    pub sandbox_id: String,
+    pub files: Vec<SandboxFileChange>,
+    /// 统一 diff 格式的补丁
+    pub patch: String,
+}
+
+/// 运行沙箱管理器
+pub struct SandboxManager {
+    sandboxes: RwLock<HashMap<String, RunSandbox>>,
+}
+
+impl SandboxManager {
+    pub fn new() -> Arc<Self> {
+        let sandboxes = load_index()
+            .into_iter()
+            // 临时目录可能已被系统清理
+            .filter(|sandbox| Path::new(&sandbox.sandbox_root).is_dir())
+            .map(|sandbox| (sandbox.id.clone(), sandbox))
+            .collect();
+        Arc::new(Self {
+            sandboxes: RwLock::new(sandboxes),
+        })
+    }
+
+    /// 列出沙箱（按创建时间排序）
+    pub fn list(&self) -> Vec<RunSandbox> {
+        let mut sandboxes: Vec<RunSandbox> = self.sandboxes.read().values().cloned().collect();
+        sandboxes.sort_by_key(|sandbox| sandbox.created_at);
+        sandboxes
+    }
+
+    pub fn get(&self, id: &str) -> Result<RunSandbox, AxonError> {
+        self.sandboxes
+            .read()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AxonError::not_found(format!("沙箱不存在: {}", id)))
+    }
+
+    /// 为项目创建沙箱
+    pub async fn create(&self, project_dir: &str) -> Result<RunSandbox, AxonError> {
+        let project = canonical_project_dir(project_dir)?;
+        let now = chrono::Utc::now();
+        let id = format!(
+            "sbx-{}-{:04x}",
+            now.timestamp_millis(),
+            rand::random::<u16>()
+        );
+        let sandbox_root = std::env::temp_dir().join(SANDBOX_ROOT).join(&id);
+        if let Some(parent) = sandbox_root.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| AxonError::io("创建沙箱目录失败", &e))?;
+        }
+
+        let repo = git_repo_info(&project).await;
+        let kind = if repo.is_some() {
+            SandboxKind::Worktree
+        } else {
+            SandboxKind::Copy
+        };
+        let (project_root, prefix) = repo.unwrap_or_else(|| (project.clone(), String::new()));
+        let directory = match prefix.trim_end_matches('/') {
+            "" => sandbox_root.clone(),
+            prefix => sandbox_root.join(prefix),
+        };
+        let result = match kind {
+            SandboxKind::Worktree => create_worktree(&project_root, &sandbox_root).await,
+            SandboxKind::Copy => create_copy(&project_root, &sandbox_root).await,
+        };
+        let baseline = match result {
+            Ok(()) => commit_baseline(&sandbox_root).await,
+            Err(e) => Err(e),
+        };
+        let baseline = match baseline {
+            Ok(baseline) => baseline,
+            Err(e) => {
+                // 创建失败时清理半成品
+                remove_sandbox_dir(&project_root, &sandbox_root, kind).await;
+                return Err(e);
+            }
+        };
+
+        let sandbox = RunSandbox {
+            id: id.clone(),
+            project_dir: project.to_string_lossy().to_string(),
+            project_root: project_root.to_string_lossy().to_string(),
+            sandbox_root: sandbox_root.to_string_lossy().to_string(),
+            directory: directory.to_string_lossy().to_string(),
+            instance: id.clone(),
+            prefix,
+            kind,
+            baseline,
+            created_at: now.timestamp_millis(),
+        };
+        self.sandboxes.write().insert(id, sandbox.clone());
+        self.save_index();
+        info!(
+            "已创建运行沙箱: {} ({:?}) -> {}",
+            sandbox.project_dir, kind, sandbox.directory
+        );
+        Ok(sandbox)
+    }
+
+    /// 沙箱相对基线的改动，`paths` 为空时包含全部文件
+    pub async fn diff(&self, id: &str, paths: &[String]) -> Result<SandboxDiff, AxonError> {
+        let sandbox = self.get(id)?;
+        let root = Path::new(&sandbox.sandbox_root);
+        // 新文件需要加入暂存区才会出现在 diff 中
+        git(root, &["add", "-A"]).await?;
+
+        // 只比较项目目录内的改动；路径相对沙箱根目录
+        let pathspecs: Vec<&str> = if paths.is_empty() {
+            vec![if sandbox.prefix.is_empty() {
+                "."
+            } else {
+                sandbox.prefix.as_str()
+            }]
+        } else {
+            paths.iter().map(String::as_str).collect()
+        };
+        let base = ["diff", "--cached", "--no-renames"];
+        let mut numstat_args = base.to_vec();
+        numstat_args.extend(["--numstat", "-z", sandbox.baseline.as_str(), "--"]);
+        numstat_args.extend(&pathspecs);
+        let mut patch_args = base.to_vec();
+        patch_args.extend(["--binary", sandbox.baseline.as_str(), "--"]);
+        patch_args.extend(&pathspecs);
+
+        let files = parse_numstat(&git(root, &numstat_args).await?);
+        let patch = git(root, &patch_args).await?;
+        Ok(SandboxDiff {
+            sandbox_id: sandbox.id,
+            files,
+            patch,
+        })
+    }
+
+    /// 把沙箱中的改动应用回项目，`paths` 为空时应用全部改动
+    pub async fn promote(&self, id: &str, paths: &[String]) -> Result<SandboxDiff, AxonError> {
+        let sandbox = self.get(id)?;
+        let diff = self.diff(id, paths).await?;
+        if diff.patch.trim().is_empty() {
+            return Err(AxonError::invalid_input("沙箱中没有可应用的改动"));
+        }
+
+        let project = Path::new(&sandbox.project_root);
+        if let Err(e) =
+            git_with_input(project, &["apply", "--check", "--binary", "-"], &diff.patch).await
+        {
+            return Err(AxonError::invalid_input(format!(
+                "沙箱改动与项目当前内容冲突，未应用任何改动: {}",
+                e.message
+            )));
+        }
+        git_with_input(project, &["apply", "--binary", "-"], &diff.patch).await?;
+        info!(
+            "已将沙箱 {} 的 {} 个文件改动应用到 {}",
+            id,
+            diff.files.len(),
+            sandbox.project_dir
+        );
+        Ok(diff)
+    }
+
+    /// 删除沙箱及其目录
+    pub async fn discard(&self, id: &str) -> Result<(), AxonError> {
+        let sandbox = self.get(id)?;
+        remove_sandbox_dir(
+            Path::new(&sandbox.project_root),
+            Path::new(&sandbox.sandbox_root),
+            sandbox.kind,
+        )
+        .await;
+        self.sandboxes.write().remove(id);
+        self.save_index();
+        info!("已删除运行沙箱: {}", id);
+        Ok(())
+    }
+
+    fn save_index(&self) {
+        let Some(path) = get_app_data_dir().map(|dir| dir.join(INDEX_FILE)) else {
+            return;
+        };
+        let sandboxes = self.list();
+        match serde_json::to_string_pretty(&sandboxes) {
+            Ok(content) => {
+                if let Err(e) = std::fs::write(&path, content) {
+                    warn!("保存沙箱索引失败: {}", e);
+                }
+            }
+            Err(e) => warn!("序列化沙箱索引失败: {}", e),
+        }
+    }
+}
+
+fn load_index() -> Vec<RunSandbox> {
+    get_app_data_dir()
+        .map(|dir| dir.join(INDEX_FILE))
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 规整项目路径（Windows 上去掉 `\\?\` 前缀，避免 git 无法识别）
+fn canonical_project_dir(project_dir: &str) -> Result<PathBuf, AxonError> {
+    let path = Path::new(project_dir);
+    if !path.is_dir() {
+        return Err(AxonError::not_found(format!(
+            "项目目录不存在: {}",
+            project_dir
+        )));
+    }
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| AxonError::io("解析项目目录失败", &e))?;
+    let text = canonical.to_string_lossy();
+    Ok(match text.strip_prefix(r"\\?\") {
+        Some(stripped) => PathBuf::from(stripped),
+        None => canonical,
+    })
+}
+
+/// 项目所在 Git 仓库的顶层目录和项目相对顶层的前缀，不在仓库中时返回 None
+async fn git_repo_info(dir: &Path) -> Option<(PathBuf, String)> {
+    let output = git(dir, &["rev-parse", "--show-toplevel", "--show-prefix"])
+        .await
+        .ok()?;
+    let mut lines = output.lines();
+    let toplevel = PathBuf::from(lines.next()?.trim());
+    let prefix = lines.next().unwrap_or_default().trim().to_string();
+    Some((toplevel, prefix))
+}
+
+/// 检出 HEAD 到 worktree，并同步未提交的改动和未跟踪文件（`project` 为仓库顶层）
+async fn create_worktree(project: &Path, directory: &Path) -> Result<(), AxonError> {
+    let target = directory.to_string_lossy().to_string();
+    git(
+        project,
+        &["worktree", "add", "--detach", "--quiet", &target, "HEAD"],
+    )
+    .await?;
+
+    let pending = git(project, &["diff", "HEAD", "--binary"]).await?;
+    if !pending.trim().is_empty() {
+        git_with_input(directory, &["apply", "--binary", "-"], &pending).await?;
+    }
+
+    let untracked = git(
+        project,
+        &["ls-files", "--others", "--exclude-standard", "-z"],
+    )
+    .await?;
+    for file in untracked.split('\0').filter(|f| !f.is_empty()) {
+        let dest = directory.join(file);
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| AxonError::io("复制未跟踪文件失败", &e))?;
+        }
+        tokio::fs::copy(project.join(file), &dest)
+            .await
+            .map_err(|e| AxonError::io("复制未跟踪文件失败", &e))?;
+    }
+    Ok(())
+}
+
+/// 复制项目目录并初始化用于比较的 Git 仓库
+async fn create_copy(project: &Path, directory: &Path) -> Result<(), AxonError> {
+    let (project, target) = (project.to_path_buf(), directory.to_path_buf());
+    tokio::task::spawn_blocking(move || copy_dir(&project, &target)).await??;
+    git(directory, &["init", "--quiet"]).await?;
+    Ok(())
+}
+
+fn copy_dir(source: &Path, target: &Path) -> Result<(), AxonError> {
+    std::fs::create_dir_all(target).map_err(|e| AxonError::io("创建沙箱目录失败", &e))?;
+    let entries = std::fs::read_dir(source).map_err(|e| AxonError::io("读取项目目录失败", &e))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let dest = target.join(&name);
+        if file_type.is_dir() {
+            if SKIPPED_DIRS.contains(&name.to_string_lossy().as_ref()) {
+                continue;
+            }
+            copy_dir(&entry.path(), &dest)?;
+        } else if file_type.is_file() {
+            std::fs::copy(entry.path(), &dest)
+                .map_err(|e| AxonError::io("复制项目文件失败", &e))?;
+        }
+        // 符号链接不复制，避免指向项目外部的内容被修改
+    }
+    Ok(())
+}
+
+/// 提交基线，返回提交哈希
+async fn commit_baseline(directory: &Path) -> Result<String, AxonError> {
+    git(directory, &["add", "-A"]).await?;
+    let mut args = BASELINE_AUTHOR.to_vec();
+    args.extend([
+        "commit",
+        "--quiet",
+        "--no-verify",
+        "--no-gpg-sign",
+        "--allow-empty",
+        "-m",
+        "Axon sandbox baseline",
+    ]);
+    git(directory, &args).await?;
+    Ok(git(directory, &["rev-parse", "HEAD"])
+        .await?
+        .trim()
+        .to_string())
+}
+
+async fn remove_sandbox_dir(project: &Path, directory: &Path, kind: SandboxKind) {
+    if kind == SandboxKind::Worktree {
+        let target = directory.to_string_lossy().to_string();
+        if let Err(e) = git(project, &["worktree", "remove", "--force", &target]).await {
+            warn!("移除 worktree 失败，改为直接删除目录: {}", e);
+        }
+    }
+    if directory.exists() {
+        if let Err(e) = tokio::fs::remove_dir_all(directory).await {
+            warn!("删除沙箱目录失败: {:?}, 错误: {}", directory, e);
+        }
+    }
+    if kind == SandboxKind::Worktree {
+        let _ = git(project, &["worktree", "prune"]).await;
+    }
+}
+
+/// 解析 `git diff --numstat -z` 输出
+fn parse_numstat(output: &str) -> Vec<SandboxFileChange> {
+    output
+        .split('\0')
+        .filter(|record| !record.is_empty())
+        .filter_map(|record| {
+            let mut parts = record.splitn(3, '\t');
+            let additions = parts.next()?;
+            let deletions = parts.next()?;
+            let path = parts.next()?;
+            Some(SandboxFileChange {
+                path: path.trim_start_matches('\n').to_string(),
+                additions: additions.parse().ok(),
+                deletions: deletions.parse().ok(),
+            })
+        })
+        .collect()
+}
+
+fn git_command(dir: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new("git");
+    command
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    // Windows 平台：避免弹出控制台窗口
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    command
+}
+
+async fn git(dir: &Path, args: &[&str]) -> Result<String, AxonError> {
+    let output = git_command(dir, args)
+        .output()
+        .await
+        .map_err(git_spawn_error)?;
+    git_output(args, output)
+}
+
+async fn git_with_input(dir: &Path, args: &[&str], input: &str) -> Result<String, AxonError> {
+    let mut command = git_command(dir, args);
+    command.stdin(Stdio::piped());
+    let mut child = command.spawn().map_err(git_spawn_error)?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .await
+            .map_err(|e| AxonError::io("写入 git 输入失败", &e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| AxonError::io("等待 git 结束失败", &e))?;
+    git_output(args, output)
+}
+
+fn git_spawn_error(e: std::io::Error) -> AxonError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        AxonError::unavailable("未找到 git，运行沙箱需要安装 Git")
+    } else {
+        AxonError::io("启动 git 失败", &e)
+    }
+}
+
+fn git_output(args: &[&str], output: std::process::Output) -> Result<String, AxonError> {
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+    Err(AxonError::external(format!(
+        "git {} 失败: {}",
+        args.first().copied().unwrap_or_default(),
+        String::from_utf8_lossy(&output.stderr).trim()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numstat_records() {
+        let output = [
+            "3\t1\tsrc/main.rs",
+            "-\t-\tassets/logo.png",
+            "0\t5\tdocs/旧文档.md",
+            "",
+        ]
+        .join("\0");
+        let files = parse_numstat(&output);
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "src/main.rs");
+        assert_eq!((files[0].additions, files[0].deletions), (Some(3), Some(1)));
+        assert_eq!(files[1].additions, None);
+        assert_eq!(files[2].path, "docs/旧文档.md");
+    }
+
+    #[test]
+    fn copies_project_without_dependency_dirs() {
+        let root = tempfile::tempdir().unwrap();
+        let (source, target) = (root.path().join("project"), root.path().join("sandbox"));
+        std::fs::create_dir_all(source.join("src")).unwrap();
+        std::fs::create_dir_all(source.join("node_modules/pkg")).unwrap();
+        std::fs::write(source.join("src/index.ts"), "export {}").unwrap();
+        std::fs::write(source.join("node_modules/pkg/index.js"), "").unwrap();
+
+        copy_dir(&source, &target).unwrap();
+        assert!(target.join("src/index.ts").is_file());
+        assert!(!target.join("node_modules").exists());
+    }
+}
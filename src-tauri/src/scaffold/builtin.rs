@@ -0,0 +1,77 @@
+//! 内置项目模板
+
+use super::TemplateVariable;
+
+/// 内置模板（文件内容中的 `{{变量}}` 在创建项目时替换）
+pub struct BuiltinTemplate {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub variables: &'static [(&'static str, &'static str, &'static str)],
+    pub files: &'static [(&'static str, &'static str)],
+}
+
+impl BuiltinTemplate {
+    /// 模板变量（名称、说明、默认值）
+    pub fn variables(&self) -> Vec<TemplateVariable> {
+        self.variables
+            .iter()
+            .map(|(name, description, default)| TemplateVariable {
+                name: name.to_string(),
+                description: Some(description.to_string()),
+                default: Some(default.to_string()),
+                required: false,
+            })
+            .collect()
+    }
+}
+
+const GITIGNORE: &str = "node_modules/\n.DS_Store\n*.log\n.env\n";
+
+pub const BUILTIN_TEMPLATES: &[BuiltinTemplate] = &[
+    BuiltinTemplate {
+        id: "empty",
+        name: "空项目",
+        description: "只包含 README 和 .gitignore",
+        variables: &[],
+        files: &[
+            ("README.md", "# {{projectName}}\n"),
+            (".gitignore", GITIGNORE),
+        ],
+    },
+    BuiltinTemplate {
+        id: "agent-playground",
+        name: "Agent 试验场",
+        description: "用于调试 Agent 提示词和工具的独立项目，包含 AGENTS.md 和 opencode.json",
+        variables: &[(
+            "goal",
+            "试验目标，写入 AGENTS.md",
+            "在这里描述希望 Agent 完成的任务",
+        )],
+        files: &[
+            (
+                "README.md",
+                "# {{projectName}}\n\n创建于 {{date}}，用于试验 Agent 的行为。\n\n\
+                 - `AGENTS.md`：项目级指令，OpenCode 会自动读取\n\
+                 - `opencode.json`：项目级 OpenCode 配置\n\
+                 - `sandbox/`：供 Agent 自由读写的目录\n",
+            ),
+            (
+                "AGENTS.md",
+                "# {{projectName}}\n\n## 目标\n\n{{goal}}\n\n## 约定\n\n\
+                 - 只修改 `sandbox/` 目录下的文件\n\
+                 - 完成后总结做了哪些修改\n",
+            ),
+            (
+                "opencode.json",
+                "{\n  \"$schema\": \"https://opencode.ai/config.json\",\n  \"instructions\": [\"AGENTS.md\"]\n}\n",
+            ),
+            ("sandbox/.gitkeep", ""),
+            (".gitignore", GITIGNORE),
+        ],
+    },
+];
+
+pub fn find(id: &str) -> Option<&'static BuiltinTemplate> {
+    BUILTIN_TEMPLATES.iter().find(|template| template.id == id)
+}
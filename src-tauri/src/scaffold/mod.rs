@@ -0,0 +1,534 @@
+//! 项目模板
+//!
+//! 内置模板见 [`builtin`]；用户模板保存在 `<app_data_dir>/templates/<ID>/`，
+//! 其中 `template.json` 描述名称和变量，其余文件（不含 `.git`）作为项目文件复制。
+//! 创建项目时文件内容和路径中的 `{{变量}}` 被替换，未定义的占位符保持原样
+//! （例如 GitHub Actions 的 `${{ github.sha }}`），非 UTF-8 文件原样复制。
+//! 除模板声明的变量外，还可以使用 `projectName`（目标目录名）、`date` 和 `year`。
+
+mod builtin;
+
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::paths::get_app_data_dir;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::process::Command;
+use tracing::{info, warn};
+
+/// 存储目录（相对应用数据目录）
+const TEMPLATES_DIR: &str = "templates";
+
+/// 用户模板的描述文件
+const MANIFEST_FILE: &str = "template.json";
+
+/// 单个模板的文件数上限
+const MAX_TEMPLATE_FILES: usize = 5000;
+
+/// 模板变量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateVariable {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub default: Option<String>,
+    /// 为 true 且没有默认值时必须提供
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateSource {
+    Builtin,
+    User,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub source: TemplateSource,
+    pub variables: Vec<TemplateVariable>,
+}
+
+/// 用户模板的 template.json
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TemplateManifest {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    variables: Vec<TemplateVariable>,
+}
+
+/// 模板文件（相对路径使用 `/` 分隔）
+struct TemplateFile {
+    path: String,
+    content: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedProject {
+    pub path: String,
+    pub template_id: String,
+    /// 写入的文件（相对路径）
+    pub files: Vec<String>,
+    pub git_initialized: bool,
+    /// Git 初始化失败的原因（项目文件已创建）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_error: Option<String>,
+}
+
+/// 项目模板管理
+#[derive(Debug)]
+pub struct ProjectTemplates {
+    /// 用户模板目录，为空时使用应用数据目录下的 templates
+    dir: Option<PathBuf>,
+}
+
+impl ProjectTemplates {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::with_dir(None))
+    }
+
+    fn with_dir(dir: Option<PathBuf>) -> Self {
+        Self { dir }
+    }
+
+    /// 列出内置模板和用户模板
+    pub fn list(&self) -> Result<Vec<ProjectTemplate>, AxonError> {
+        let mut templates: Vec<ProjectTemplate> = builtin::BUILTIN_TEMPLATES
+            .iter()
+            .map(|template| ProjectTemplate {
+                id: template.id.to_string(),
+                name: template.name.to_string(),
+                description: Some(template.description.to_string()),
+                source: TemplateSource::Builtin,
+                variables: template.variables(),
+            })
+            .collect();
+
+        let Ok(entries) = std::fs::read_dir(self.dir()?) else {
+            return Ok(templates);
+        };
+        let mut user_templates: Vec<ProjectTemplate> = entries
+            .flatten()
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .filter_map(|e| {
+                let id = e.file_name().to_str()?.to_string();
+                if validate_id(&id).is_err() {
+                    return None;
+                }
+                if builtin::find(&id).is_some() {
+                    warn!("用户模板与内置模板同名，已跳过: {}", id);
+                    return None;
+                }
+                match read_manifest(&e.path()) {
+                    Ok(manifest) => Some(ProjectTemplate {
+                        name: manifest.name.unwrap_or_else(|| id.clone()),
+                        id,
+                        description: manifest.description,
+                        source: TemplateSource::User,
+                        variables: manifest.variables,
+                    }),
+                    Err(err) => {
+                        warn!("模板描述无法解析，已跳过: {:?}, 错误: {}", e.path(), err);
+                        None
+                    }
+                }
+            })
+            .collect();
+        user_templates.sort_by(|a, b| a.id.cmp(&b.id));
+        templates.extend(user_templates);
+        Ok(templates)
+    }
+
+    /// 删除用户模板，返回模板是否存在
+    pub fn delete(&self, id: &str) -> Result<bool, AxonError> {
+        validate_id(id)?;
+        if builtin::find(id).is_some() {
+            return Err(AxonError::invalid_input(format!(
+                "内置模板不能删除: {}",
+                id
+            )));
+        }
+        match std::fs::remove_dir_all(self.dir()?.join(id)) {
+            Ok(()) => {
+                info!("已删除项目模板: {}", id);
+                Ok(true)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(AxonError::io("删除项目模板失败", &e)),
+        }
+    }
+
+    /// 把模板文件写入目标目录（不存在或为空目录），返回写入的相对路径
+    pub fn instantiate(
+        &self,
+        id: &str,
+        dest: &Path,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<Vec<String>, AxonError> {
+        let (specs, files) = self.load(id)?;
+        let project_name = dest
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let values = resolve_variables(&specs, variables, &project_name)?;
+
+        // 先渲染全部路径，非法路径不会留下半成品目录
+        let rendered = files
+            .into_iter()
+            .map(|file| Ok((render_path(&file.path, &values)?, file.content)))
+            .collect::<Result<Vec<_>, AxonError>>()?;
+
+        let created = prepare_destination(dest)?;
+        let result = write_files(dest, rendered, &values);
+        if result.is_err() && created {
+            let _ = std::fs::remove_dir_all(dest);
+        }
+        let written = result?;
+        info!("已从模板 {} 创建项目: {:?}", id, dest);
+        Ok(written)
+    }
+
+    /// 读取模板的变量声明和文件
+    fn load(&self, id: &str) -> Result<(Vec<TemplateVariable>, Vec<TemplateFile>), AxonError> {
+        validate_id(id)?;
+        if let Some(template) = builtin::find(id) {
+            let files = template
+                .files
+                .iter()
+                .map(|(path, content)| TemplateFile {
+                    path: path.to_string(),
+                    content: content.as_bytes().to_vec(),
+                })
+                .collect();
+            return Ok((template.variables(), files));
+        }
+
+        let root = self.dir()?.join(id);
+        if !root.is_dir() {
+            return Err(AxonError::not_found(format!("项目模板不存在: {}", id)));
+        }
+        let manifest = read_manifest(&root)?;
+        let mut files = Vec::new();
+        collect_files(&root, &root, &mut files)?;
+        Ok((manifest.variables, files))
+    }
+
+    fn dir(&self) -> Result<PathBuf, AxonError> {
+        self.dir
+            .clone()
+            .or_else(|| get_app_data_dir().map(|d| d.join(TEMPLATES_DIR)))
+            .ok_or_else(|| {
+                AxonError::localized(
+                    ErrorKind::Unavailable,
+                    "app.data_dir_unavailable",
+                    serde_json::json!(null),
+                )
+            })
+    }
+}
+
+/// 模板 ID 同时用作目录名：字母或数字开头，只含字母、数字、`_` 和 `-`
+fn validate_id(id: &str) -> Result<(), AxonError> {
+    let valid = id.len() <= 64
+        && id.chars().next().is_some_and(|c| c.is_ascii_alphanumeric())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(AxonError::invalid_input(format!("无效的模板 ID: {}", id)));
+    }
+    Ok(())
+}
+
+/// 读取 template.json，文件不存在时使用默认值
+fn read_manifest(root: &Path) -> Result<TemplateManifest, AxonError> {
+    match std::fs::read_to_string(root.join(MANIFEST_FILE)) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| AxonError::invalid_data(format!("模板描述无法解析: {}", e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TemplateManifest::default()),
+        Err(e) => Err(AxonError::io("读取模板描述失败", &e)),
+    }
+}
+
+/// 递归收集模板文件，跳过描述文件、`.git` 和符号链接
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<TemplateFile>) -> Result<(), AxonError> {
+    let entries = std::fs::read_dir(dir).map_err(|e| AxonError::io("读取模板目录失败", &e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        let relative = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if file_type.is_dir() {
+            if entry.file_name() != ".git" {
+                collect_files(root, &path, files)?;
+            }
+        } else if file_type.is_file() && relative != MANIFEST_FILE {
+            if files.len() >= MAX_TEMPLATE_FILES {
+                return Err(AxonError::invalid_data(format!(
+                    "模板文件过多（上限 {} 个）",
+                    MAX_TEMPLATE_FILES
+                )));
+            }
+            let content =
+                std::fs::read(&path).map_err(|e| AxonError::io("读取模板文件失败", &e))?;
+            files.push(TemplateFile {
+                path: relative,
+                content,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// 合并内置变量、模板默认值和用户提供的值
+fn resolve_variables(
+    specs: &[TemplateVariable],
+    provided: &BTreeMap<String, String>,
+    project_name: &str,
+) -> Result<BTreeMap<String, String>, AxonError> {
+    let today = chrono::Local::now();
+    let mut values = BTreeMap::from([
+        ("projectName".to_string(), project_name.to_string()),
+        ("date".to_string(), today.format("%Y-%m-%d").to_string()),
+        ("year".to_string(), today.format("%Y").to_string()),
+    ]);
+
+    let mut missing = Vec::new();
+    for spec in specs {
+        let value = provided
+            .get(&spec.name)
+            .filter(|value| !value.is_empty())
+            .or(spec.default.as_ref());
+        match value {
+            Some(value) => {
+                values.insert(spec.name.clone(), value.clone());
+            }
+            None if spec.required => missing.push(spec.name.as_str()),
+            None => {
+                values.insert(spec.name.clone(), String::new());
+            }
+        }
+    }
+    if !missing.is_empty() {
+        return Err(AxonError::invalid_input(format!(
+            "缺少模板变量: {}",
+            missing.join(", ")
+        )));
+    }
+    for (name, value) in provided {
+        values.entry(name.clone()).or_insert_with(|| value.clone());
+    }
+    Ok(values)
+}
+
+/// 替换 `{{ 变量 }}`，未定义的占位符保持原样
+pub(crate) fn render(text: &str, values: &BTreeMap<String, String>) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + len + 2;
+        output.push_str(&rest[..start]);
+        match values.get(rest[start + 2..end - 2].trim()) {
+            Some(value) => output.push_str(value),
+            None => output.push_str(&rest[start..end]),
+        }
+        rest = &rest[end..];
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 渲染相对路径，替换后仍不能跳出目标目录
+fn render_path(path: &str, values: &BTreeMap<String, String>) -> Result<PathBuf, AxonError> {
+    let rendered = PathBuf::from(render(path, values));
+    let valid = rendered.components().next().is_some()
+        && rendered
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !valid {
+        return Err(AxonError::invalid_input(format!(
+            "模板文件路径无效: {}",
+            rendered.display()
+        )));
+    }
+    Ok(rendered)
+}
+
+/// 检查目标目录不存在或为空，返回是否新建了目录
+fn prepare_destination(dest: &Path) -> Result<bool, AxonError> {
+    if !dest.is_absolute() {
+        return Err(AxonError::invalid_input("目标目录必须是绝对路径"));
+    }
+    match std::fs::read_dir(dest) {
+        Ok(mut entries) => {
+            if entries.next().is_some() {
+                return Err(AxonError::already_exists(format!(
+                    "目标目录已存在且不为空: {}",
+                    dest.display()
+                )));
+            }
+            Ok(false)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            std::fs::create_dir_all(dest).map_err(|e| AxonError::io("创建项目目录失败", &e))?;
+            Ok(true)
+        }
+        Err(e) => Err(AxonError::io("读取目标目录失败", &e)),
+    }
+}
+
+fn write_files(
+    dest: &Path,
+    files: Vec<(PathBuf, Vec<u8>)>,
+    values: &BTreeMap<String, String>,
+) -> Result<Vec<String>, AxonError> {
+    let mut written = Vec::with_capacity(files.len());
+    for (relative, content) in files {
+        let path = dest.join(&relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AxonError::io("创建项目目录失败", &e))?;
+        }
+        let content = match String::from_utf8(content) {
+            Ok(text) => render(&text, values).into_bytes(),
+            Err(e) => e.into_bytes(),
+        };
+        std::fs::write(&path, content).map_err(|e| AxonError::io("写入项目文件失败", &e))?;
+        written.push(relative.to_string_lossy().replace('\\', "/"));
+    }
+    written.sort();
+    Ok(written)
+}
+
+/// 在项目目录中执行 `git init` 并暂存全部文件（不创建提交，避免依赖用户的 Git 身份配置）
+pub async fn init_git(dir: &Path) -> Result<(), AxonError> {
+    for args in [&["init"][..], &["add", "-A"][..]] {
+        let mut command = Command::new("git");
+        command
+            .args(args)
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        // Windows 平台：避免弹出控制台窗口
+        #[cfg(target_os = "windows")]
+        {
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        let output = command.output().await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                AxonError::unavailable("未找到 git")
+            } else {
+                AxonError::io("启动 git 失败", &e)
+            }
+        })?;
+        if !output.status.success() {
+            return Err(AxonError::external(format!(
+                "git {} 失败: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_known_placeholders_only() {
+        let values = BTreeMap::from([("name".to_string(), "demo".to_string())]);
+        assert_eq!(
+            render("# {{ name }} ${{ github.sha }} {{name", &values),
+            "# demo ${{ github.sha }} {{name"
+        );
+    }
+
+    #[test]
+    fn requires_declared_variables() {
+        let specs = vec![TemplateVariable {
+            name: "owner".to_string(),
+            description: None,
+            default: None,
+            required: true,
+        }];
+        assert!(resolve_variables(&specs, &BTreeMap::new(), "demo").is_err());
+
+        let provided = BTreeMap::from([("owner".to_string(), "axon".to_string())]);
+        let values = resolve_variables(&specs, &provided, "demo").unwrap();
+        assert_eq!(values["owner"], "axon");
+        assert_eq!(values["projectName"], "demo");
+    }
+
+    #[test]
+    fn instantiates_user_template() {
+        let temp = tempfile::tempdir().unwrap();
+        let templates_dir = temp.path().join("templates");
+        let template = templates_dir.join("service");
+        std::fs::create_dir_all(template.join("src")).unwrap();
+        std::fs::write(
+            template.join(MANIFEST_FILE),
+            r#"{ "name": "服务", "variables": [{ "name": "module", "default": "core" }] }"#,
+        )
+        .unwrap();
+        std::fs::write(template.join("src/{{module}}.rs"), "// {{projectName}}\n").unwrap();
+
+        let templates = ProjectTemplates::with_dir(Some(templates_dir));
+        let listed = templates.list().unwrap();
+        assert!(listed
+            .iter()
+            .any(|t| t.id == "service" && t.source == TemplateSource::User));
+
+        let dest = temp.path().join("dest").join("my-app");
+        let files = templates
+            .instantiate("service", &dest, &BTreeMap::new())
+            .unwrap();
+        assert_eq!(files, vec!["src/core.rs"]);
+        assert_eq!(
+            std::fs::read_to_string(dest.join("src/core.rs")).unwrap(),
+            "// my-app\n"
+        );
+
+        // 目标目录非空时拒绝覆盖
+        assert!(templates
+            .instantiate("empty", &dest, &BTreeMap::new())
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_paths_escaping_destination() {
+        let values = BTreeMap::from([("dir".to_string(), "..".to_string())]);
+        assert!(render_path("{{dir}}/secret", &values).is_err());
+        assert!(render_path("docs/{{missing}}.md", &values).is_ok());
+    }
+}
@@ -0,0 +1,151 @@
+//! 设置文件加密
+//!
+//! 启用后 settings.json 中的敏感字段（服务商配置、Webhook、额外环境变量）
+//! 合并为一个 JSON 对象，用 AES-256-GCM 加密后保存在 `encrypted` 字段中，其余字段保持明文。
+//! 密钥随机生成并保存在系统钥匙串，文件本身不包含任何解密所需的信息。
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rand::RngCore;
+use serde_json::{json, Map, Value};
+
+/// 钥匙串服务名和条目名
+const KEYRING_SERVICE: &str = "axon-desktop.settings";
+const KEYRING_USER: &str = "settings-key";
+
+/// 加密内容所在字段
+pub const ENCRYPTED_FIELD: &str = "encrypted";
+
+/// 需要加密的顶层字段
+pub const SENSITIVE_SECTIONS: &[&str] = &["providers", "webhooks", "extraEnv"];
+
+/// 加密格式版本
+const FORMAT_VERSION: u64 = 1;
+
+pub type SettingsKey = [u8; 32];
+
+/// 设置文件是否包含加密内容
+pub fn is_sealed(settings: &Value) -> bool {
+    settings.get(ENCRYPTED_FIELD).is_some()
+}
+
+/// 把敏感字段移入加密字段
+pub fn seal(settings: &mut Value, key: &SettingsKey) -> Result<(), String> {
+    let Some(object) = settings.as_object_mut() else {
+        return Err("设置不是 JSON 对象".to_string());
+    };
+    let mut sections = Map::new();
+    for section in SENSITIVE_SECTIONS {
+        if let Some(value) = object.remove(*section) {
+            sections.insert(section.to_string(), value);
+        }
+    }
+    let plaintext = serde_json::to_vec(&sections).map_err(|e| format!("序列化失败: {}", e))?;
+
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = Aes256Gcm::new(key.into())
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|_| "加密设置失败".to_string())?;
+    object.insert(
+        ENCRYPTED_FIELD.to_string(),
+        json!({
+            "version": FORMAT_VERSION,
+            "nonce": STANDARD.encode(nonce),
+            "data": STANDARD.encode(ciphertext),
+        }),
+    );
+    Ok(())
+}
+
+/// 解密加密字段并把敏感字段放回原位置
+pub fn unseal(settings: &mut Value, key: &SettingsKey) -> Result<(), String> {
+    let Some(object) = settings.as_object_mut() else {
+        return Err("设置不是 JSON 对象".to_string());
+    };
+    let Some(sealed) = object.remove(ENCRYPTED_FIELD) else {
+        return Ok(());
+    };
+    let version = sealed.get("version").and_then(Value::as_u64);
+    if version != Some(FORMAT_VERSION) {
+        return Err(format!("不支持的加密格式版本: {:?}", version));
+    }
+    let decode = |field: &str| {
+        sealed
+            .get(field)
+            .and_then(Value::as_str)
+            .and_then(|text| STANDARD.decode(text).ok())
+            .ok_or_else(|| format!("加密内容缺少字段: {}", field))
+    };
+    let nonce = decode("nonce")?;
+    if nonce.len() != 12 {
+        return Err("加密内容的 nonce 长度无效".to_string());
+    }
+    let plaintext = Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(&nonce), decode("data")?.as_slice())
+        .map_err(|_| "解密设置失败：密钥不匹配或文件已损坏".to_string())?;
+    let sections: Map<String, Value> =
+        serde_json::from_slice(&plaintext).map_err(|e| format!("解析加密内容失败: {}", e))?;
+    object.extend(sections);
+    Ok(())
+}
+
+/// 从钥匙串读取密钥，`create` 为 true 时不存在则生成新密钥
+pub fn load_key(create: bool) -> Result<Option<SettingsKey>, String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| format!("无法访问系统钥匙串: {}", e))?;
+    match entry.get_password() {
+        Ok(encoded) => {
+            let bytes = STANDARD
+                .decode(encoded.trim())
+                .map_err(|_| "钥匙串中的设置密钥格式无效".to_string())?;
+            let key: SettingsKey = bytes
+                .try_into()
+                .map_err(|_| "钥匙串中的设置密钥长度无效".to_string())?;
+            Ok(Some(key))
+        }
+        Err(keyring::Error::NoEntry) if create => {
+            let mut key = [0u8; 32];
+            rand::thread_rng().fill_bytes(&mut key);
+            entry
+                .set_password(&STANDARD.encode(key))
+                .map_err(|e| format!("保存设置密钥到钥匙串失败: {}", e))?;
+            Ok(Some(key))
+        }
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("读取设置密钥失败: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seals_only_sensitive_sections() {
+        let key = [7u8; 32];
+        let original = json!({
+            "autoUpdate": true,
+            "providers": [{ "id": "openai", "apiKey": "sk-test" }],
+            "extraEnv": { "OPENAI_BASE_URL": "https://proxy.local" },
+        });
+        let mut settings = original.clone();
+        seal(&mut settings, &key).unwrap();
+        assert!(is_sealed(&settings));
+        assert_eq!(settings["autoUpdate"], true);
+        assert!(settings.get("providers").is_none());
+        assert!(!settings.to_string().contains("sk-test"));
+
+        unseal(&mut settings, &key).unwrap();
+        assert_eq!(settings, original);
+    }
+
+    #[test]
+    fn rejects_wrong_key() {
+        let mut settings = json!({ "providers": [] });
+        seal(&mut settings, &[1u8; 32]).unwrap();
+        assert!(unseal(&mut settings, &[2u8; 32]).is_err());
+    }
+}
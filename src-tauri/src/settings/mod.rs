@@ -1,69 +1,351 @@
 //! 应用设置持久化模块
+//!
+//! 每次保存后比较顶层字段，通过广播通道通知订阅者哪些字段发生了变化，
+//! 同时向前端发送 `settings:changed` 事件（只包含字段名，不包含值）。
+//! 启用设置加密后，敏感字段在写入磁盘前加密（见 [`crypto`]）。
 
-use crate::opencode::AppSettings;
+mod crypto;
+
+use crate::opencode::{
+    AppSettings, EmbeddingSettings, HotkeySettings, Language, LogLevelSettings, ModelFilter,
+    NotificationSettings, PathSandboxSettings, PowerSettings, RateLimitSettings, ShellProfile,
+    TeamSyncSettings, UpdateChannel, WebhookConfig,
+};
 use crate::utils::paths::get_app_data_dir;
-use parking_lot::RwLock;
+use crypto::SettingsKey;
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
 use tracing::{debug, info, warn};
 
 const SETTINGS_FILE: &str = "settings.json";
 
+/// 设置变化事件
+pub const EVENT_SETTINGS_CHANGED: &str = "settings:changed";
+
+/// 变化通知通道容量（订阅者落后时只会丢失中间通知）
+const CHANGE_CHANNEL_CAPACITY: usize = 32;
+
+/// 设置变化通知
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChange {
+    /// 发生变化的顶层字段（与 settings.json 中的字段名一致，如 `pluginApiRateLimit`）
+    pub keys: Vec<String>,
+}
+
+impl SettingsChange {
+    /// 是否包含指定字段的变化
+    pub fn touches(&self, key: &str) -> bool {
+        self.keys.iter().any(|k| k == key)
+    }
+}
+
+/// 设置加密状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsEncryptionStatus {
+    pub enabled: bool,
+    /// 设置文件已加密但无法解密（钥匙串中没有对应密钥），此时不会写入设置，
+    /// 直到密钥恢复或通过 [`SettingsManager::reset_encryption`] 丢弃加密内容
+    pub locked: bool,
+    /// 加密的字段
+    pub sections: Vec<String>,
+}
+
+/// 从磁盘加载的设置
+struct LoadedSettings {
+    settings: AppSettings,
+    key: Option<SettingsKey>,
+    locked: bool,
+}
+
 pub struct SettingsManager {
     settings: RwLock<AppSettings>,
+    /// 设置加密密钥（首次使用时从钥匙串读取）
+    key: Mutex<Option<SettingsKey>>,
+    /// 加密内容无法解密，禁止保存以免覆盖
+    locked: AtomicBool,
+    /// 最近一次保存 / 加载的设置（用于计算变化的字段）
+    snapshot: RwLock<Value>,
+    changes: broadcast::Sender<SettingsChange>,
+}
+
+/// 不输出设置内容和加密密钥
+impl std::fmt::Debug for SettingsManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SettingsManager")
+            .field("locked", &self.locked)
+            .finish_non_exhaustive()
+    }
 }
 
 impl SettingsManager {
     pub fn new() -> Arc<Self> {
-        let settings = Self::load_settings().unwrap_or_default();
-        info!("Settings loaded: auto_update={}, custom_path={:?}",
-            settings.auto_update,
-            settings.custom_opencode_path
-        );
+        let manager = Self::default();
+        if let Some(loaded) = Self::load_settings() {
+            manager.apply_loaded(loaded);
+        }
+        {
+            let settings = manager.settings.read();
+            info!("Settings loaded: auto_update={}, custom_path={:?}",
+                settings.auto_update,
+                settings.custom_opencode_path
+            );
+        }
+
+        Arc::new(manager)
+    }
 
-        Arc::new(Self {
+    fn apply_loaded(&self, loaded: LoadedSettings) {
+        *self.settings.write() = loaded.settings;
+        *self.key.lock() = loaded.key;
+        self.locked.store(loaded.locked, Ordering::SeqCst);
+        self.notify_changes();
+    }
+
+    fn with_settings(settings: AppSettings) -> Self {
+        let (changes, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+        Self {
+            snapshot: RwLock::new(serde_json::to_value(&settings).unwrap_or_default()),
             settings: RwLock::new(settings),
-        })
+            key: Mutex::new(None),
+            locked: AtomicBool::new(false),
+            changes,
+        }
+    }
+
+    /// 订阅设置变化
+    pub fn subscribe(&self) -> broadcast::Receiver<SettingsChange> {
+        self.changes.subscribe()
+    }
+
+    /// 把设置变化转发为前端事件
+    pub fn forward_changes(&self, handle: AppHandle) {
+        let mut changes = self.subscribe();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                match changes.recv().await {
+                    Ok(change) => {
+                        if let Err(e) = handle.emit(EVENT_SETTINGS_CHANGED, &change) {
+                            warn!("发送设置变化事件失败: {}", e);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// 与上次快照比较并广播变化的字段
+    fn notify_changes(&self) {
+        let Ok(current) = serde_json::to_value(&*self.settings.read()) else {
+            return;
+        };
+        let keys = {
+            let mut snapshot = self.snapshot.write();
+            let keys = changed_keys(&snapshot, &current);
+            *snapshot = current;
+            keys
+        };
+        if keys.is_empty() {
+            return;
+        }
+        debug!("设置已变化: {:?}", keys);
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.changes.send(SettingsChange { keys });
+    }
+
+    /// 从磁盘重新加载设置（应用数据目录初始化后调用）
+    ///
+    /// `new()` 在 Tauri setup 之前执行，此时应用数据目录尚不可用
+    pub fn initialize(&self) {
+        if let Some(loaded) = Self::load_settings() {
+            info!("Settings reloaded from disk");
+            self.apply_loaded(loaded);
+        }
     }
 
     fn get_settings_path() -> Option<PathBuf> {
         get_app_data_dir().map(|p| p.join(SETTINGS_FILE))
     }
 
-    fn load_settings() -> Option<AppSettings> {
+    fn load_settings() -> Option<LoadedSettings> {
         let path = Self::get_settings_path()?;
         if !path.exists() {
             debug!("Settings file not found, using defaults");
             return None;
         }
 
-        match std::fs::read_to_string(&path) {
+        let mut value: Value = match std::fs::read_to_string(&path) {
             Ok(content) => match serde_json::from_str(&content) {
-                Ok(settings) => Some(settings),
+                Ok(value) => value,
                 Err(e) => {
                     warn!("Failed to parse settings file: {}", e);
-                    None
+                    return None;
                 }
             },
             Err(e) => {
                 warn!("Failed to read settings file: {}", e);
+                return None;
+            }
+        };
+
+        let (mut key, mut locked) = (None, false);
+        if crypto::is_sealed(&value) {
+            let unsealed = crypto::load_key(false).and_then(|loaded| {
+                let loaded = loaded.ok_or_else(|| "钥匙串中没有设置密钥".to_string())?;
+                crypto::unseal(&mut value, &loaded)?;
+                Ok(loaded)
+            });
+            match unsealed {
+                Ok(loaded) => key = Some(loaded),
+                Err(e) => {
+                    // 敏感字段使用默认值，并禁止保存以免覆盖加密内容
+                    warn!("无法解密设置文件中的敏感字段: {}", e);
+                    if let Some(object) = value.as_object_mut() {
+                        object.remove(crypto::ENCRYPTED_FIELD);
+                    }
+                    locked = true;
+                }
+            }
+        }
+
+        match serde_json::from_value(value) {
+            Ok(settings) => Some(LoadedSettings {
+                settings,
+                key,
+                locked,
+            }),
+            Err(e) => {
+                warn!("Failed to parse settings file: {}", e);
                 None
             }
         }
     }
 
+    /// 加密密钥（缓存，`create` 为 true 时钥匙串中不存在则生成）
+    fn encryption_key(&self, create: bool) -> Result<SettingsKey, String> {
+        let mut cached = self.key.lock();
+        if let Some(key) = *cached {
+            return Ok(key);
+        }
+        let key = crypto::load_key(create)?.ok_or_else(|| "钥匙串中没有设置密钥".to_string())?;
+        *cached = Some(key);
+        Ok(key)
+    }
+
     fn save_settings(&self) -> Result<(), String> {
         let path = Self::get_settings_path()
             .ok_or_else(|| "Cannot determine settings path".to_string())?;
 
-        let settings = self.settings.read();
-        let content = serde_json::to_string_pretty(&*settings)
+        if self.locked.load(Ordering::SeqCst) && !self.try_unlock() {
+            return Err("设置文件中的加密内容无法解密，为避免覆盖已停止保存设置".to_string());
+        }
+
+        let (mut value, encrypt) = {
+            let settings = self.settings.read();
+            let value = serde_json::to_value(&*settings)
+                .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+            (value, settings.settings_encryption)
+        };
+        if encrypt {
+            crypto::seal(&mut value, &self.encryption_key(true)?)?;
+        }
+        let content = serde_json::to_string_pretty(&value)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
         std::fs::write(&path, content)
             .map_err(|e| format!("Failed to write settings file: {}", e))?;
 
         debug!("Settings saved to {:?}", path);
+        self.notify_changes();
+        Ok(())
+    }
+
+    /// 锁定状态下重新读取设置文件和密钥，能够解密时解除锁定
+    ///
+    /// 锁定期间未修改过的敏感字段恢复为文件中的内容，已修改的保留当前值
+    fn try_unlock(&self) -> bool {
+        let Some(loaded) = Self::load_settings() else {
+            return false;
+        };
+        if loaded.locked {
+            return false;
+        }
+        let to_object = |settings: &AppSettings| match serde_json::to_value(settings) {
+            Ok(Value::Object(object)) => object,
+            _ => Default::default(),
+        };
+        let stored = to_object(&loaded.settings);
+        let defaults = to_object(&AppSettings::default());
+        let mut current = to_object(&self.settings.read());
+        for section in crypto::SENSITIVE_SECTIONS {
+            if current.get(*section) == defaults.get(*section) {
+                if let Some(value) = stored.get(*section) {
+                    current.insert(section.to_string(), value.clone());
+                }
+            }
+        }
+        match serde_json::from_value(Value::Object(current)) {
+            Ok(settings) => *self.settings.write() = settings,
+            Err(e) => {
+                warn!("恢复加密字段失败: {}", e);
+                return false;
+            }
+        }
+        *self.key.lock() = loaded.key;
+        self.locked.store(false, Ordering::SeqCst);
+        info!("设置密钥已恢复，解除设置锁定");
+        true
+    }
+
+    /// 丢弃无法解密的加密内容并解除锁定，敏感字段保留当前值（通常为默认值）
+    pub fn reset_encryption(&self) -> Result<(), String> {
+        if !self.locked.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+        // 原密钥已不可用，重新读取或生成
+        *self.key.lock() = None;
+        if let Err(e) = self.save_settings() {
+            self.locked.store(true, Ordering::SeqCst);
+            return Err(e);
+        }
+        warn!("已丢弃无法解密的设置字段: {:?}", crypto::SENSITIVE_SECTIONS);
+        Ok(())
+    }
+
+    /// 设置加密状态
+    pub fn encryption_status(&self) -> SettingsEncryptionStatus {
+        SettingsEncryptionStatus {
+            enabled: self.settings.read().settings_encryption,
+            locked: self.locked.load(Ordering::SeqCst),
+            sections: crypto::SENSITIVE_SECTIONS
+                .iter()
+                .map(|section| section.to_string())
+                .collect(),
+        }
+    }
+
+    /// 启用或关闭设置加密，立即按新方式重写设置文件（可能触发钥匙串授权）
+    pub fn set_encryption(&self, enabled: bool) -> Result<(), String> {
+        if enabled {
+            // 先确认密钥可用，避免设置了标记却无法保存
+            self.encryption_key(true)?;
+        }
+        let previous = std::mem::replace(&mut self.settings.write().settings_encryption, enabled);
+        if let Err(e) = self.save_settings() {
+            self.settings.write().settings_encryption = previous;
+            return Err(e);
+        }
+        info!("设置加密已{}", if enabled { "启用" } else { "关闭" });
         Ok(())
     }
 
@@ -71,8 +353,12 @@ impl SettingsManager {
         self.settings.read().clone()
     }
 
-    pub fn set_settings(&self, settings: AppSettings) -> Result<(), String> {
-        *self.settings.write() = settings;
+    /// 整体替换设置（加密开关只能通过 [`Self::set_encryption`] 修改）
+    pub fn set_settings(&self, mut settings: AppSettings) -> Result<(), String> {
+        let mut current = self.settings.write();
+        settings.settings_encryption = current.settings_encryption;
+        *current = settings;
+        drop(current);
         self.save_settings()
     }
 
@@ -107,12 +393,288 @@ impl SettingsManager {
     pub fn get_project_directory(&self) -> Option<String> {
         self.settings.read().project_directory.clone()
     }
+
+    pub fn get_shell_profiles(&self) -> Vec<ShellProfile> {
+        self.settings.read().shell_profiles.clone()
+    }
+
+    /// 新增或更新 Shell 配置（按 id 匹配）
+    pub fn upsert_shell_profile(&self, profile: ShellProfile) -> Result<(), String> {
+        {
+            let mut settings = self.settings.write();
+            match settings
+                .shell_profiles
+                .iter_mut()
+                .find(|p| p.id == profile.id)
+            {
+                Some(existing) => *existing = profile,
+                None => settings.shell_profiles.push(profile),
+            }
+        }
+        self.save_settings()
+    }
+
+    pub fn delete_shell_profile(&self, id: &str) -> Result<(), String> {
+        {
+            let mut settings = self.settings.write();
+            settings.shell_profiles.retain(|p| p.id != id);
+            if settings.default_shell_profile_id.as_deref() == Some(id) {
+                settings.default_shell_profile_id = None;
+            }
+        }
+        self.save_settings()
+    }
+
+    pub fn get_default_shell_profile_id(&self) -> Option<String> {
+        self.settings.read().default_shell_profile_id.clone()
+    }
+
+    pub fn set_default_shell_profile_id(&self, id: Option<String>) -> Result<(), String> {
+        self.settings.write().default_shell_profile_id = id;
+        self.save_settings()
+    }
+
+    pub fn get_persist_terminal_sessions(&self) -> bool {
+        self.settings.read().persist_terminal_sessions
+    }
+
+    pub fn set_persist_terminal_sessions(&self, enabled: bool) -> Result<(), String> {
+        self.settings.write().persist_terminal_sessions = enabled;
+        self.save_settings()
+    }
+
+    pub fn get_allowed_commands(&self) -> Vec<String> {
+        self.settings.read().allowed_commands.clone()
+    }
+
+    pub fn add_allowed_command(&self, program: &str) -> Result<(), String> {
+        {
+            let mut settings = self.settings.write();
+            if settings.allowed_commands.iter().any(|c| c == program) {
+                return Ok(());
+            }
+            settings.allowed_commands.push(program.to_string());
+        }
+        self.save_settings()
+    }
+
+    pub fn remove_allowed_command(&self, program: &str) -> Result<(), String> {
+        self.settings.write().allowed_commands.retain(|c| c != program);
+        self.save_settings()
+    }
+
+    pub fn get_path_sandbox(&self) -> PathSandboxSettings {
+        self.settings.read().path_sandbox.clone()
+    }
+
+    pub fn set_path_sandbox_enabled(&self, enabled: bool) -> Result<(), String> {
+        self.settings.write().path_sandbox.enabled = enabled;
+        self.save_settings()
+    }
+
+    /// 添加授权目录，已存在时不重复添加
+    pub fn add_allowed_path(&self, path: &str) -> Result<(), String> {
+        {
+            let mut settings = self.settings.write();
+            if settings.path_sandbox.allowed_paths.iter().any(|p| p == path) {
+                return Ok(());
+            }
+            settings.path_sandbox.allowed_paths.push(path.to_string());
+        }
+        self.save_settings()
+    }
+
+    pub fn remove_allowed_path(&self, path: &str) -> Result<(), String> {
+        self.settings
+            .write()
+            .path_sandbox
+            .allowed_paths
+            .retain(|p| p != path);
+        self.save_settings()
+    }
+
+    pub fn get_log_level(&self) -> LogLevelSettings {
+        self.settings.read().log_level.clone()
+    }
+
+    pub fn set_log_level(&self, log_level: LogLevelSettings) -> Result<(), String> {
+        self.settings.write().log_level = log_level;
+        self.save_settings()
+    }
+
+    pub fn get_update_channel(&self) -> UpdateChannel {
+        self.settings.read().update_channel
+    }
+
+    pub fn set_update_channel(&self, channel: UpdateChannel) -> Result<(), String> {
+        self.settings.write().update_channel = channel;
+        self.save_settings()
+    }
+
+    pub fn get_language(&self) -> Language {
+        self.settings.read().language
+    }
+
+    pub fn set_language(&self, language: Language) -> Result<(), String> {
+        self.settings.write().language = language;
+        self.save_settings()
+    }
+
+    pub fn get_embedding_settings(&self) -> EmbeddingSettings {
+        self.settings.read().embedding.clone()
+    }
+
+    pub fn set_embedding_settings(&self, embedding: EmbeddingSettings) -> Result<(), String> {
+        self.settings.write().embedding = embedding;
+        self.save_settings()
+    }
+
+    pub fn get_webhooks(&self) -> Vec<WebhookConfig> {
+        self.settings.read().webhooks.clone()
+    }
+
+    pub fn set_webhooks(&self, webhooks: Vec<WebhookConfig>) -> Result<(), String> {
+        self.settings.write().webhooks = webhooks;
+        self.save_settings()
+    }
+
+    pub fn get_notification_settings(&self) -> NotificationSettings {
+        self.settings.read().notifications.clone()
+    }
+
+    pub fn set_notification_settings(
+        &self,
+        notifications: NotificationSettings,
+    ) -> Result<(), String> {
+        self.settings.write().notifications = notifications;
+        self.save_settings()
+    }
+
+    pub fn get_power_settings(&self) -> PowerSettings {
+        self.settings.read().power.clone()
+    }
+
+    pub fn set_power_settings(&self, power: PowerSettings) -> Result<(), String> {
+        self.settings.write().power = power;
+        self.save_settings()
+    }
+
+    pub fn get_plugin_api_rate_limit(&self) -> RateLimitSettings {
+        self.settings.read().plugin_api_rate_limit.clone()
+    }
+
+    pub fn set_plugin_api_rate_limit(&self, rate_limit: RateLimitSettings) -> Result<(), String> {
+        self.settings.write().plugin_api_rate_limit = rate_limit;
+        self.save_settings()
+    }
+
+    pub fn get_bridge_update_repo(&self) -> String {
+        self.settings.read().bridge_update_repo.clone()
+    }
+
+    pub fn set_bridge_update_repo(&self, repo: String) -> Result<(), String> {
+        self.settings.write().bridge_update_repo = repo;
+        self.save_settings()
+    }
+
+    pub fn get_extra_env(&self) -> BTreeMap<String, String> {
+        self.settings.read().extra_env.clone()
+    }
+
+    pub fn set_extra_env(&self, extra_env: BTreeMap<String, String>) -> Result<(), String> {
+        self.settings.write().extra_env = extra_env;
+        self.save_settings()
+    }
+
+    pub fn get_model_filters(&self) -> BTreeMap<String, ModelFilter> {
+        self.settings.read().model_filters.clone()
+    }
+
+    pub fn set_model_filters(&self, filters: BTreeMap<String, ModelFilter>) -> Result<(), String> {
+        self.settings.write().model_filters = filters;
+        self.save_settings()
+    }
+
+    pub fn get_team_sync(&self) -> TeamSyncSettings {
+        self.settings.read().team_sync.clone()
+    }
+
+    pub fn set_team_sync(&self, team_sync: TeamSyncSettings) -> Result<(), String> {
+        self.settings.write().team_sync = team_sync;
+        self.save_settings()
+    }
+
+    pub fn get_hotkeys(&self) -> HotkeySettings {
+        self.settings.read().hotkeys.clone()
+    }
+
+    pub fn set_hotkeys(&self, hotkeys: HotkeySettings) -> Result<(), String> {
+        self.settings.write().hotkeys = hotkeys;
+        self.save_settings()
+    }
+
+    pub fn get_keymap(&self) -> BTreeMap<String, Option<String>> {
+        self.settings.read().keymap.clone()
+    }
+
+    pub fn set_keymap(&self, keymap: BTreeMap<String, Option<String>>) -> Result<(), String> {
+        self.settings.write().keymap = keymap;
+        self.save_settings()
+    }
+
+    pub fn get_minimize_to_tray(&self) -> bool {
+        self.settings.read().minimize_to_tray
+    }
+
+    pub fn set_minimize_to_tray(&self, enabled: bool) -> Result<(), String> {
+        self.settings.write().minimize_to_tray = enabled;
+        self.save_settings()
+    }
 }
 
 impl Default for SettingsManager {
     fn default() -> Self {
-        Self {
-            settings: RwLock::new(AppSettings::default()),
-        }
+        Self::with_settings(AppSettings::default())
+    }
+}
+
+/// 两份设置之间值不同的顶层字段
+fn changed_keys(before: &Value, after: &Value) -> Vec<String> {
+    let (Some(before), Some(after)) = (before.as_object(), after.as_object()) else {
+        return Vec::new();
+    };
+    let mut keys: Vec<String> = after
+        .iter()
+        .filter(|(key, value)| before.get(*key) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .chain(
+            before
+                .keys()
+                .filter(|key| !after.contains_key(*key))
+                .cloned(),
+        )
+        .collect();
+    keys.sort();
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_changed_top_level_keys() {
+        let manager = SettingsManager::default();
+        let mut changes = manager.subscribe();
+        manager.settings.write().minimize_to_tray = true;
+        manager.settings.write().bridge_update_repo = "owner/repo".to_string();
+        manager.notify_changes();
+        let change = changes.try_recv().unwrap();
+        assert_eq!(change.keys, vec!["bridgeUpdateRepo", "minimizeToTray"]);
+        assert!(change.touches("minimizeToTray"));
+
+        // 没有变化时不通知
+        manager.notify_changes();
+        assert!(changes.try_recv().is_err());
     }
 }
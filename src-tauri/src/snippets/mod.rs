@@ -0,0 +1,346 @@
+//! 提示词片段
+//!
+//! 每个片段一个 JSON 文件（`<app_data_dir>/snippets/<id>.json`），聊天输入框和终端
+//! 都从这里插入常用提示词。内容中的 `{{ 变量 }}` 在展开时替换，未提供的变量保持原样。
+//! 片段可固定到列表顶部，也可关联快捷键 ID，由界面按此绑定插入操作。
+
+use crate::error::{AxonError, ErrorKind};
+use crate::scaffold::render;
+use crate::utils::json_store;
+use crate::utils::paths::get_app_data_dir;
+use parking_lot::Mutex;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::debug;
+
+/// 存储目录（相对应用数据目录）
+const SNIPPETS_DIR: &str = "snippets";
+
+/// 片段数上限
+const MAX_SNIPPETS: usize = 500;
+
+/// 名称的最大长度（字符）
+const MAX_NAME_CHARS: usize = 100;
+
+/// 内容的最大长度（字节）
+const MAX_CONTENT_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub content: String,
+    /// 内容中引用的变量（按首次出现顺序）
+    #[serde(default)]
+    pub variables: Vec<String>,
+    /// 快捷键 ID，同一 ID 只能关联一个片段
+    #[serde(default)]
+    pub shortcut: Option<String>,
+    /// 固定到列表顶部
+    #[serde(default)]
+    pub pinned: bool,
+    /// Unix 毫秒
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 新建或更新片段的内容
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetInput {
+    /// 为空时新建
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub content: String,
+    #[serde(default)]
+    pub shortcut: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// 片段存储
+#[derive(Debug)]
+pub struct SnippetStore {
+    /// 存储目录，为空时使用应用数据目录下的 snippets
+    dir: Option<PathBuf>,
+    /// 串行化读写
+    lock: Mutex<()>,
+}
+
+impl SnippetStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            dir: None,
+            lock: Mutex::new(()),
+        })
+    }
+
+    #[cfg(test)]
+    fn with_dir(dir: PathBuf) -> Self {
+        Self {
+            dir: Some(dir),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// 列出片段，固定的在前，其余按名称排序
+    pub fn list(&self) -> Result<Vec<Snippet>, AxonError> {
+        let _guard = self.lock.lock();
+        let mut snippets = self.read_all()?;
+        snippets.sort_by(|a, b| b.pinned.cmp(&a.pinned).then_with(|| a.name.cmp(&b.name)));
+        Ok(snippets)
+    }
+
+    /// 新建或更新片段
+    pub fn save(&self, input: SnippetInput) -> Result<Snippet, AxonError> {
+        let name = input.name.trim().to_string();
+        if name.is_empty() {
+            return Err(AxonError::invalid_input("片段名称不能为空"));
+        }
+        if name.chars().count() > MAX_NAME_CHARS {
+            return Err(AxonError::invalid_input(format!(
+                "片段名称不能超过 {} 个字符",
+                MAX_NAME_CHARS
+            )));
+        }
+        if input.content.trim().is_empty() {
+            return Err(AxonError::invalid_input("片段内容不能为空"));
+        }
+        if input.content.len() > MAX_CONTENT_BYTES {
+            return Err(AxonError::invalid_input(format!(
+                "片段内容不能超过 {} KB",
+                MAX_CONTENT_BYTES / 1024
+            )));
+        }
+        let description = input
+            .description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+        let shortcut = input
+            .shortcut
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        let _guard = self.lock.lock();
+        let snippets = self.read_all()?;
+        let existing = match input.id.as_deref() {
+            Some(id) => Some(
+                snippets
+                    .iter()
+                    .find(|s| s.id == id)
+                    .ok_or_else(|| AxonError::not_found(format!("片段不存在: {}", id)))?,
+            ),
+            None if snippets.len() >= MAX_SNIPPETS => {
+                return Err(AxonError::invalid_input(format!(
+                    "片段数超过上限（{} 个）",
+                    MAX_SNIPPETS
+                )));
+            }
+            None => None,
+        };
+        let id = existing.map_or_else(new_snippet_id, |s| s.id.clone());
+        if let Some(shortcut) = &shortcut {
+            if let Some(other) = snippets
+                .iter()
+                .find(|s| s.id != id && s.shortcut.as_ref() == Some(shortcut))
+            {
+                return Err(AxonError::invalid_input(format!(
+                    "快捷键 {} 已用于片段「{}」",
+                    shortcut, other.name
+                )));
+            }
+        }
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let snippet = Snippet {
+            id,
+            name,
+            description,
+            variables: variables(&input.content),
+            content: input.content,
+            shortcut,
+            pinned: input.pinned,
+            created_at: existing.map_or(now, |s| s.created_at),
+            updated_at: now,
+        };
+        self.write(&snippet)?;
+        debug!("已保存片段: {} ({})", snippet.name, snippet.id);
+        Ok(snippet)
+    }
+
+    /// 删除片段，返回片段是否存在
+    pub fn delete(&self, id: &str) -> Result<bool, AxonError> {
+        let _guard = self.lock.lock();
+        match std::fs::remove_file(self.snippet_path(id)?) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(AxonError::io("删除片段失败", &e)),
+        }
+    }
+
+    /// 展开片段，替换提供的变量
+    pub fn expand(&self, id: &str, values: &BTreeMap<String, String>) -> Result<String, AxonError> {
+        let _guard = self.lock.lock();
+        let snippet = self
+            .read(&self.snippet_path(id)?)?
+            .ok_or_else(|| AxonError::not_found(format!("片段不存在: {}", id)))?;
+        Ok(render(&snippet.content, values))
+    }
+
+    fn dir(&self) -> Result<PathBuf, AxonError> {
+        self.dir
+            .clone()
+            .or_else(|| get_app_data_dir().map(|d| d.join(SNIPPETS_DIR)))
+            .ok_or_else(|| {
+                AxonError::localized(
+                    ErrorKind::Unavailable,
+                    "app.data_dir_unavailable",
+                    serde_json::json!(null),
+                )
+            })
+    }
+
+    fn snippet_path(&self, id: &str) -> Result<PathBuf, AxonError> {
+        let valid = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(AxonError::invalid_input(format!("无效的片段 ID: {}", id)));
+        }
+        Ok(self.dir()?.join(format!("{}.json", id)))
+    }
+
+    fn read_all(&self) -> Result<Vec<Snippet>, AxonError> {
+        let entries = match std::fs::read_dir(self.dir()?) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AxonError::io("读取片段目录失败", &e)),
+        };
+        let mut snippets = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                snippets.extend(self.read(&path)?);
+            }
+        }
+        Ok(snippets)
+    }
+
+    fn read(&self, path: &Path) -> Result<Option<Snippet>, AxonError> {
+        json_store::load(path, "片段文件")
+    }
+
+    fn write(&self, snippet: &Snippet) -> Result<(), AxonError> {
+        json_store::save(&self.snippet_path(&snippet.id)?, snippet, "片段文件")
+    }
+}
+
+fn new_snippet_id() -> String {
+    format!("snippet-{:016x}", rand::thread_rng().gen::<u64>())
+}
+
+/// 内容中 `{{ 变量 }}` 引用的变量名（按首次出现顺序）
+fn variables(content: &str) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    let mut result = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + len].trim();
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-' || c == '.');
+        if valid && seen.insert(name) {
+            result.push(name.to_string());
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store() -> (TempDir, SnippetStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnippetStore::with_dir(dir.path().to_path_buf());
+        (dir, store)
+    }
+
+    fn input(name: &str, content: &str, shortcut: Option<&str>) -> SnippetInput {
+        SnippetInput {
+            id: None,
+            name: name.to_string(),
+            description: None,
+            content: content.to_string(),
+            shortcut: shortcut.map(str::to_string),
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn collects_variables_in_first_use_order() {
+        assert_eq!(
+            variables("{{ b }} {{a}} {{ b }} {{ 文件 }} {{ user.name }} {{ task-id }}"),
+            ["b", "a", "文件", "user.name", "task-id"]
+        );
+        // 空名称、含非法字符的名称和未闭合的占位符都不是变量
+        assert!(variables("{{}} {{ a b }} {{ $x }} {{ open").is_empty());
+        assert_eq!(variables("${{ github.sha }}"), ["github.sha"]);
+    }
+
+    #[test]
+    fn expands_provided_values_only() {
+        let (_dir, store) = store();
+        let snippet = store
+            .save(input(
+                "代码审查",
+                "审查 {{ file }}，重点关注{{focus}}。{{file}} {{ open",
+                None,
+            ))
+            .unwrap();
+        assert_eq!(snippet.variables, ["file", "focus"]);
+
+        // 缺少的变量保留原样；替换值中的占位符不会再次展开
+        let values = BTreeMap::from([("file".to_string(), "{{focus}}.rs".to_string())]);
+        assert_eq!(
+            store.expand(&snippet.id, &values).unwrap(),
+            "审查 {{focus}}.rs，重点关注{{focus}}。{{focus}}.rs {{ open"
+        );
+        assert!(store.expand("snippet-missing", &values).is_err());
+        assert!(store.expand("../settings", &values).is_err());
+    }
+
+    #[test]
+    fn keeps_shortcuts_unique_across_updates() {
+        let (_dir, store) = store();
+        let first = store.save(input("b", "B", Some("snippet.1"))).unwrap();
+        assert!(store.save(input("a", "A", Some(" snippet.1 "))).is_err());
+
+        // 更新自身时保留原快捷键不算冲突
+        let mut update = input("b", "B2", Some("snippet.1"));
+        update.id = Some(first.id.clone());
+        update.pinned = true;
+        let updated = store.save(update).unwrap();
+        assert_eq!(updated.created_at, first.created_at);
+
+        store.save(input("a", "A", Some("snippet.2"))).unwrap();
+        let names: Vec<_> = store.list().unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["b", "a"]);
+    }
+}
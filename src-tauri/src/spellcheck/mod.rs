@@ -0,0 +1,392 @@
+//! 拼写检查
+//!
+//! 使用 Hunspell 格式的词典（`<语言>.aff` + `<语言>.dic`）检查提示词输入框的文本，
+//! 不依赖各平台 WebView 行为不一致的内置拼写检查。词典按以下顺序查找：
+//! 1. `<app_data_dir>/dictionaries`（用户自行放入）
+//! 2. 系统词典目录（Linux 的 hunspell / myspell 目录，macOS 的 Spelling 目录）
+//! 3. 随应用打包的词典（`resources/dictionaries`，至少包含 `en_US`，Windows 没有系统词典）
+//!
+//! 用户词典（`<app_data_dir>/dictionaries/user_dictionary.txt`，每行一个词）对所有语言生效。
+//! 含数字或下划线的词、全大写缩写、驼峰标识符和中日韩文字不检查。
+
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::paths::get_app_data_dir;
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use spellbook::Dictionary;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// 词典目录（相对应用数据目录）
+const DICTIONARIES_DIR: &str = "dictionaries";
+
+/// 打包的词典目录（相对资源目录）
+const BUNDLED_DICTIONARIES_DIR: &str = "resources/dictionaries";
+
+/// 用户词典文件名
+const USER_DICTIONARY_FILE: &str = "user_dictionary.txt";
+
+/// 单次检查的最大文本长度（字节）
+const MAX_TEXT_BYTES: usize = 100 * 1024;
+
+/// 每个拼写错误的建议数上限
+const MAX_SUGGESTIONS: usize = 5;
+
+/// 用户词典单个词的最大长度（字符）
+const MAX_WORD_CHARS: usize = 100;
+
+/// 系统词典目录
+#[cfg(target_os = "linux")]
+const SYSTEM_DICTIONARY_DIRS: &[&str] = &[
+    "/usr/share/hunspell",
+    "/usr/share/myspell",
+    "/usr/share/myspell/dicts",
+];
+#[cfg(target_os = "macos")]
+const SYSTEM_DICTIONARY_DIRS: &[&str] = &["/Library/Spelling"];
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+const SYSTEM_DICTIONARY_DIRS: &[&str] = &[];
+
+/// 拼写错误
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpellingIssue {
+    pub word: String,
+    /// 在文本中的位置（UTF-16 代码单元，与 JavaScript 字符串下标一致）
+    pub start: usize,
+    pub end: usize,
+    pub suggestions: Vec<String>,
+}
+
+/// 拼写检查服务
+pub struct SpellChecker {
+    /// 已加载的词典（语言 → 词典）
+    dictionaries: RwLock<HashMap<String, Arc<Dictionary>>>,
+    /// 用户词典，首次使用时加载
+    user_words: Mutex<Option<BTreeSet<String>>>,
+    /// 打包的词典目录（资源目录可用后设置）
+    bundled_dir: RwLock<Option<PathBuf>>,
+}
+
+impl SpellChecker {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            dictionaries: RwLock::new(HashMap::new()),
+            user_words: Mutex::new(None),
+            bundled_dir: RwLock::new(None),
+        })
+    }
+
+    /// 设置资源目录（Tauri setup 中调用）
+    pub fn initialize(&self, resource_dir: Option<&Path>) {
+        *self.bundled_dir.write() = resource_dir.map(|dir| dir.join(BUNDLED_DICTIONARIES_DIR));
+    }
+
+    /// 检查文本，返回拼写错误（按出现顺序）
+    pub fn check(&self, text: &str, language: &str) -> Result<Vec<SpellingIssue>, AxonError> {
+        if text.len() > MAX_TEXT_BYTES {
+            return Err(AxonError::invalid_input(format!(
+                "文本过长，拼写检查最多支持 {} KB",
+                MAX_TEXT_BYTES / 1024
+            )));
+        }
+        let dictionary = self.dictionary(language)?;
+        let user_words = self.user_words()?;
+
+        let mut issues = Vec::new();
+        for word in words(text) {
+            if user_words.contains(word.text)
+                || user_words.contains(&word.text.to_lowercase())
+                || dictionary.check(word.text)
+            {
+                continue;
+            }
+            let mut suggestions = Vec::new();
+            dictionary.suggest(word.text, &mut suggestions);
+            suggestions.truncate(MAX_SUGGESTIONS);
+            issues.push(SpellingIssue {
+                word: word.text.to_string(),
+                start: word.start,
+                end: word.end,
+                suggestions,
+            });
+        }
+        Ok(issues)
+    }
+
+    /// 添加到用户词典，返回是否新增
+    pub fn add_word(&self, word: &str) -> Result<bool, AxonError> {
+        let word = word.trim();
+        if word.is_empty() || word.chars().any(char::is_whitespace) {
+            return Err(AxonError::invalid_input("用户词典的词不能为空或包含空白"));
+        }
+        if word.chars().count() > MAX_WORD_CHARS {
+            return Err(AxonError::invalid_input(format!(
+                "单个词不能超过 {} 个字符",
+                MAX_WORD_CHARS
+            )));
+        }
+
+        let mut guard = self.user_words.lock();
+        let words = match guard.as_mut() {
+            Some(words) => words,
+            None => guard.insert(Self::load_user_words()?),
+        };
+        if !words.insert(word.to_string()) {
+            return Ok(false);
+        }
+
+        let path = Self::user_dictionary_path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AxonError::io("创建词典目录失败", &e))?;
+        }
+        let content: String = words.iter().map(|w| format!("{}\n", w)).collect();
+        let temp = path.with_extension("txt.tmp");
+        std::fs::write(&temp, content).map_err(|e| AxonError::io("写入用户词典失败", &e))?;
+        std::fs::rename(&temp, &path).map_err(|e| AxonError::io("写入用户词典失败", &e))?;
+        debug!("已添加到用户词典: {}", word);
+        Ok(true)
+    }
+
+    /// 可用的词典语言（已排序）
+    pub fn languages(&self) -> Vec<String> {
+        let mut languages = BTreeSet::new();
+        for dir in self.dictionary_dirs() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "dic")
+                    && path.with_extension("aff").is_file()
+                {
+                    if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                        languages.insert(stem.to_string());
+                    }
+                }
+            }
+        }
+        languages.into_iter().collect()
+    }
+
+    /// 获取语言的词典，首次使用时加载
+    fn dictionary(&self, language: &str) -> Result<Arc<Dictionary>, AxonError> {
+        let language = normalize_language(language)?;
+        if let Some(dictionary) = self.dictionaries.read().get(&language) {
+            return Ok(Arc::clone(dictionary));
+        }
+
+        let (aff_path, dic_path) =
+            find_dictionary(&self.dictionary_dirs(), &language).ok_or_else(|| {
+                AxonError::not_found(format!(
+                    "未找到 {} 的词典，请将 {}.aff 和 {}.dic 放入应用数据目录的 {} 目录",
+                    language, language, language, DICTIONARIES_DIR
+                ))
+            })?;
+        let aff = std::fs::read_to_string(&aff_path)
+            .map_err(|e| AxonError::io("读取词典文件失败", &e))?;
+        let dic = std::fs::read_to_string(&dic_path)
+            .map_err(|e| AxonError::io("读取词典文件失败", &e))?;
+        let dictionary = Dictionary::new(&aff, &dic).map_err(|e| {
+            AxonError::invalid_data(format!("解析词典失败 {}: {}", aff_path.display(), e))
+        })?;
+        info!("已加载词典: {} ({})", language, dic_path.display());
+
+        let dictionary = Arc::new(dictionary);
+        self.dictionaries
+            .write()
+            .insert(language, Arc::clone(&dictionary));
+        Ok(dictionary)
+    }
+
+    /// 词典目录（按优先级）
+    fn dictionary_dirs(&self) -> Vec<PathBuf> {
+        let mut result: Vec<PathBuf> = get_app_data_dir()
+            .map(|d| d.join(DICTIONARIES_DIR))
+            .into_iter()
+            .collect();
+        #[cfg(target_os = "macos")]
+        result.extend(dirs::home_dir().map(|home| home.join("Library/Spelling")));
+        result.extend(SYSTEM_DICTIONARY_DIRS.iter().map(PathBuf::from));
+        result.extend(self.bundled_dir.read().clone());
+        result
+    }
+
+    fn user_words(&self) -> Result<BTreeSet<String>, AxonError> {
+        let mut guard = self.user_words.lock();
+        if let Some(words) = guard.as_ref() {
+            return Ok(words.clone());
+        }
+        Ok(guard.insert(Self::load_user_words()?).clone())
+    }
+
+    fn load_user_words() -> Result<BTreeSet<String>, AxonError> {
+        let path = Self::user_dictionary_path()?;
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Ok(content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeSet::new()),
+            Err(e) => {
+                warn!("读取用户词典失败: {}", e);
+                Err(AxonError::io("读取用户词典失败", &e))
+            }
+        }
+    }
+
+    fn user_dictionary_path() -> Result<PathBuf, AxonError> {
+        get_app_data_dir()
+            .map(|d| d.join(DICTIONARIES_DIR).join(USER_DICTIONARY_FILE))
+            .ok_or_else(|| {
+                AxonError::localized(
+                    ErrorKind::Unavailable,
+                    "app.data_dir_unavailable",
+                    serde_json::json!(null),
+                )
+            })
+    }
+}
+
+/// 查找词典文件，只给出语言（如 `en`）时使用该语言的第一个地区词典
+fn find_dictionary(dirs: &[PathBuf], language: &str) -> Option<(PathBuf, PathBuf)> {
+    let exact = dirs.iter().map(|dir| dir.join(format!("{}.aff", language)));
+    let regional = dirs.iter().flat_map(|dir| {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension().is_some_and(|ext| ext == "aff")
+                    && path
+                        .file_stem()
+                        .and_then(|s| s.to_str())
+                        .is_some_and(|stem| stem.starts_with(&format!("{}_", language)))
+            })
+            .collect();
+        paths.sort();
+        paths
+    });
+    exact
+        .chain(regional)
+        .map(|aff| (aff.clone(), aff.with_extension("dic")))
+        .find(|(aff, dic)| aff.is_file() && dic.is_file())
+}
+
+/// `en-us` → `en_US`；语言代码用于拼接文件名，只允许字母和 `_`
+fn normalize_language(language: &str) -> Result<String, AxonError> {
+    let language = language.trim().replace('-', "_");
+    if language.is_empty()
+        || !language
+            .chars()
+            .all(|c| c.is_ascii_alphabetic() || c == '_')
+    {
+        return Err(AxonError::invalid_input(format!(
+            "无效的语言代码: {}",
+            language
+        )));
+    }
+    Ok(match language.split_once('_') {
+        Some((lang, region)) => format!("{}_{}", lang.to_lowercase(), region.to_uppercase()),
+        None => language.to_lowercase(),
+    })
+}
+
+/// 待检查的词
+#[derive(Debug, PartialEq)]
+struct Word<'t> {
+    text: &'t str,
+    /// UTF-16 位置
+    start: usize,
+    end: usize,
+}
+
+/// 中日韩文字及之后的区段不参与拼写检查
+fn is_word_char(c: char) -> bool {
+    c.is_alphabetic() && (c as u32) < 0x2E80
+}
+
+/// 切分待检查的词（词内可含撇号），跳过不适合拼写检查的词
+fn words(text: &str) -> Vec<Word<'_>> {
+    let mut result = Vec::new();
+    // (字节位置, UTF-16 位置)
+    let mut start: Option<(usize, usize)> = None;
+    let mut utf16 = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        let next_is_word = chars.peek().is_some_and(|&(_, next)| is_word_char(next));
+        let in_word = is_word_char(c)
+            || c.is_ascii_digit()
+            || c == '_'
+            || (start.is_some() && matches!(c, '\'' | '’') && next_is_word);
+        match (in_word, start) {
+            (true, None) => start = Some((index, utf16)),
+            (false, Some((byte_start, utf16_start))) => {
+                result.extend(checkable(&text[byte_start..index], utf16_start, utf16));
+                start = None;
+            }
+            _ => {}
+        }
+        utf16 += c.len_utf16();
+    }
+    if let Some((byte_start, utf16_start)) = start {
+        result.extend(checkable(&text[byte_start..], utf16_start, utf16));
+    }
+    result
+}
+
+fn checkable(text: &str, start: usize, end: usize) -> Option<Word<'_>> {
+    let mut chars = text.chars();
+    let first = chars.next()?;
+    let skip = text.chars().count() < 2
+        || text.chars().any(|c| c.is_ascii_digit() || c == '_')
+        // 全大写缩写（如 API）
+        || text.chars().all(|c| !c.is_lowercase())
+        // 驼峰标识符（如 useState、OpenCode）
+        || chars
+            .zip(text.chars().skip(2))
+            .any(|(a, b)| a.is_lowercase() && b.is_uppercase())
+        || (first.is_lowercase() && text.chars().skip(1).any(char::is_uppercase));
+    (!skip).then_some(Word { text, start, end })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(text: &str) -> Vec<&str> {
+        words(text).into_iter().map(|w| w.text).collect()
+    }
+
+    #[test]
+    fn splits_checkable_words() {
+        assert_eq!(
+            texts("Please don't refactr the API, see useState and v2 in my_file."),
+            ["Please", "don't", "refactr", "the", "see", "and", "in"]
+        );
+        assert_eq!(texts("检查 teh 拼写"), ["teh"]);
+        assert_eq!(texts("'quoted' OpenCode"), ["quoted"]);
+    }
+
+    #[test]
+    fn reports_utf16_offsets() {
+        let words = words("😀 帮我 fix typoo");
+        assert_eq!(words[1].text, "typoo");
+        // 😀 占两个 UTF-16 代码单元
+        assert_eq!((words[1].start, words[1].end), (10, 15));
+    }
+
+    #[test]
+    fn normalizes_language_tags() {
+        assert_eq!(normalize_language("en-us").unwrap(), "en_US");
+        assert_eq!(normalize_language("DE").unwrap(), "de");
+        assert!(normalize_language("../en_US").is_err());
+        assert!(normalize_language("en/US").is_err());
+        assert!(normalize_language("").is_err());
+    }
+}
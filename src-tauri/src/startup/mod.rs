@@ -0,0 +1,110 @@
+//! 启动耗时统计模块
+//!
+//! 记录 setup 各阶段（窗口创建、插件安装、Plugin API 启动、OpenCode 初始化、
+//! 模型注册表加载等）的耗时。每个阶段包在 `startup` span 中，
+//! 全部完成后发送 `startup:timings` 事件，之后也可通过命令查询。
+
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, info_span, warn, Instrument};
+
+/// 启动完成事件
+pub const EVENT_STARTUP_TIMINGS: &str = "startup:timings";
+
+/// 单个启动阶段
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupPhase {
+    pub name: String,
+    /// 相对进程启动的开始时间（毫秒）
+    pub start_ms: u64,
+    pub duration_ms: u64,
+}
+
+/// 启动耗时汇总
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupTimings {
+    /// 按开始时间排序的阶段
+    pub phases: Vec<StartupPhase>,
+    /// 启动完成时的总耗时（毫秒），未完成时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_ms: Option<u64>,
+    pub completed: bool,
+}
+
+/// 启动耗时记录器
+#[derive(Debug)]
+pub struct StartupProfiler {
+    /// 进程启动时间（AppState 创建时）
+    started: Instant,
+    phases: RwLock<Vec<StartupPhase>>,
+    total_ms: RwLock<Option<u64>>,
+}
+
+impl StartupProfiler {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            started: Instant::now(),
+            phases: RwLock::new(Vec::new()),
+            total_ms: RwLock::new(None),
+        })
+    }
+
+    /// 记录同步阶段
+    pub fn measure<T>(&self, phase: &str, task: impl FnOnce() -> T) -> T {
+        let _span = info_span!("startup", phase).entered();
+        let begin = Instant::now();
+        let result = task();
+        self.record(phase, begin);
+        result
+    }
+
+    /// 记录异步阶段
+    pub async fn measure_async<T, F>(&self, phase: &str, task: F) -> T
+    where
+        F: Future<Output = T>,
+    {
+        let begin = Instant::now();
+        let result = task.instrument(info_span!("startup", phase)).await;
+        self.record(phase, begin);
+        result
+    }
+
+    fn record(&self, phase: &str, begin: Instant) {
+        let entry = StartupPhase {
+            name: phase.to_string(),
+            start_ms: begin.duration_since(self.started).as_millis() as u64,
+            duration_ms: begin.elapsed().as_millis() as u64,
+        };
+        info!("启动阶段 {} 耗时 {}ms", entry.name, entry.duration_ms);
+        self.phases.write().push(entry);
+    }
+
+    /// 标记启动完成并发送汇总事件
+    pub fn finish(&self, app: &AppHandle) {
+        let total_ms = self.started.elapsed().as_millis() as u64;
+        *self.total_ms.write() = Some(total_ms);
+        info!("启动完成，总耗时 {}ms", total_ms);
+
+        if let Err(e) = app.emit(EVENT_STARTUP_TIMINGS, self.timings()) {
+            warn!("发送启动耗时事件失败: {}", e);
+        }
+    }
+
+    /// 当前的耗时汇总
+    pub fn timings(&self) -> StartupTimings {
+        let mut phases = self.phases.read().clone();
+        phases.sort_by_key(|p| p.start_ms);
+        let total_ms = *self.total_ms.read();
+        StartupTimings {
+            phases,
+            total_ms,
+            completed: total_ms.is_some(),
+        }
+    }
+}
@@ -1,28 +1,174 @@
 //! Application state management
 
+use crate::activity::ActivityFeed;
+use crate::app_menu::AppMenu;
+use crate::app_update::AppUpdateManager;
+use crate::audio::AudioRecorder;
+use crate::audit::AuditLog;
+use crate::bookmarks::BookmarkStore;
+use crate::bootstrap::BootstrapTracker;
+use crate::branches::BranchStore;
+use crate::bridge_update::BridgeUpdater;
+use crate::hotkeys::HotkeyManager;
+use crate::consent::ConsentBroker;
+use crate::context_menu::ContextMenuManager;
+use crate::context_pins::ContextPinStore;
+use crate::diff_cache::DiffCache;
+use crate::embeddings::EmbeddingIndex;
+use crate::file_index::FileIndex;
+use crate::jobs::JobManager;
+use crate::memory::MemoryStore;
 use crate::models_registry::ModelsRegistryManager;
-use crate::opencode::OpencodeService;
-use crate::plugin_api::PluginApiServer;
+use crate::notifications::NotificationManager;
+use crate::oauth::OAuthManager;
+use crate::opencode::{ConfigWatcher, OpencodeService, ServiceManager};
+use crate::plugin_api::{PluginApiServer, RateLimiter};
+use crate::power::PowerMonitor;
+use crate::reviews::ReviewStore;
+use crate::run_sandbox::SandboxManager;
+use crate::scaffold::ProjectTemplates;
 use crate::settings::SettingsManager;
+use crate::snippets::SnippetStore;
+use crate::spellcheck::SpellChecker;
+use crate::startup::StartupProfiler;
+use crate::stats::StatsMonitor;
+use crate::team_sync::TeamSyncManager;
+use crate::tools::ToolRegistry;
+use crate::usage::UsageTracker;
+use crate::webhooks::WebhookManager;
+use crate::workspace_stats::WorkspaceStatsCache;
 use parking_lot::RwLock;
 use std::sync::Arc;
 
 pub struct AppState {
+    /// 默认 OpenCode 实例
     pub opencode: Arc<OpencodeService>,
+    /// 所有 OpenCode 实例（默认实例和按名称启动的附加实例）
+    pub services: Arc<ServiceManager>,
     pub settings: Arc<SettingsManager>,
     pub plugin_api: Arc<RwLock<PluginApiServer>>,
     pub models_registry: Arc<ModelsRegistryManager>,
+    pub jobs: Arc<JobManager>,
+    pub oauth: Arc<OAuthManager>,
+    pub usage: Arc<UsageTracker>,
+    /// Agent 活动时间线
+    pub activity: Arc<ActivityFeed>,
+    pub audit: Arc<AuditLog>,
+    pub consent: Arc<ConsentBroker>,
+    pub startup: Arc<StartupProfiler>,
+    pub bootstrap: Arc<BootstrapTracker>,
+    pub app_update: Arc<AppUpdateManager>,
+    pub hotkeys: Arc<HotkeyManager>,
+    pub context_menu: Arc<ContextMenuManager>,
+    /// macOS 菜单栏
+    pub app_menu: Arc<AppMenu>,
+    pub file_index: Arc<FileIndex>,
+    pub audio: Arc<AudioRecorder>,
+    pub embeddings: Arc<EmbeddingIndex>,
+    pub memory: Arc<MemoryStore>,
+    pub webhooks: Arc<WebhookManager>,
+    pub notifications: Arc<NotificationManager>,
+    pub power: Arc<PowerMonitor>,
+    pub stats: Arc<StatsMonitor>,
+    pub bridge_update: Arc<BridgeUpdater>,
+    /// 自定义工具
+    pub tools: Arc<ToolRegistry>,
+    /// 运行沙箱
+    pub run_sandboxes: Arc<SandboxManager>,
+    /// opencode.json / auth.json 外部修改监听
+    pub config_watcher: Arc<ConfigWatcher>,
+    /// 团队配置同步
+    pub team_sync: Arc<TeamSyncManager>,
+    /// 项目模板
+    pub templates: Arc<ProjectTemplates>,
+    /// 项目书签
+    pub bookmarks: Arc<BookmarkStore>,
+    /// 会话分支
+    pub branches: Arc<BranchStore>,
+    /// 提示词片段
+    pub snippets: Arc<SnippetStore>,
+    /// 拼写检查
+    pub spellcheck: Arc<SpellChecker>,
+    /// 固定到 Agent 上下文的文件
+    pub context_pins: Arc<ContextPinStore>,
+    /// 项目概览统计缓存
+    pub workspace_stats: Arc<WorkspaceStatsCache>,
+    /// Diff 审查会话
+    pub reviews: Arc<ReviewStore>,
+    /// Diff 结果缓存
+    pub diff_cache: Arc<DiffCache>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         let settings = SettingsManager::new();
         let models_registry = ModelsRegistryManager::new();
+        let oauth = OAuthManager::new(Arc::clone(&settings));
+        let usage = UsageTracker::new();
+        let activity = ActivityFeed::new();
+        let consent = ConsentBroker::new();
+        let memory = MemoryStore::new();
+        let rate_limiter = Arc::new(RateLimiter::new(settings.get_plugin_api_rate_limit()));
+        let webhooks = WebhookManager::new(Arc::clone(&settings));
+        let notifications = NotificationManager::new(Arc::clone(&settings));
+        let power = PowerMonitor::new(Arc::clone(&settings));
+        let bridge_update = BridgeUpdater::new(Arc::clone(&settings));
+        let tools = ToolRegistry::new();
+        let context_pins = ContextPinStore::new();
+        let team_sync = TeamSyncManager::new(Arc::clone(&settings));
+        let opencode = OpencodeService::with_settings(Arc::clone(&settings));
+        let stats = StatsMonitor::new(Arc::clone(&opencode));
+        let services = ServiceManager::new(Arc::clone(&opencode), Arc::clone(&settings));
+        let plugin_api = Arc::new(RwLock::new(PluginApiServer::new(
+            Arc::clone(&usage),
+            Arc::clone(&activity),
+            Arc::clone(&consent),
+            Arc::clone(&memory),
+            rate_limiter,
+            Arc::clone(&tools),
+            Arc::clone(&settings),
+            Arc::clone(&context_pins),
+        )));
         Self {
-            opencode: OpencodeService::with_settings(Arc::clone(&settings)),
+            opencode,
+            services,
             settings,
-            plugin_api: Arc::new(RwLock::new(PluginApiServer::new())),
+            plugin_api,
             models_registry,
+            jobs: JobManager::new(),
+            oauth,
+            usage,
+            activity,
+            audit: AuditLog::new(),
+            consent,
+            startup: StartupProfiler::new(),
+            bootstrap: BootstrapTracker::new(),
+            app_update: AppUpdateManager::new(),
+            hotkeys: HotkeyManager::new(),
+            context_menu: ContextMenuManager::new(),
+            app_menu: AppMenu::new(),
+            file_index: FileIndex::new(),
+            audio: AudioRecorder::new(),
+            embeddings: EmbeddingIndex::new(),
+            memory,
+            webhooks,
+            notifications,
+            power,
+            stats,
+            bridge_update,
+            tools,
+            run_sandboxes: SandboxManager::new(),
+            config_watcher: ConfigWatcher::new(),
+            team_sync,
+            templates: ProjectTemplates::new(),
+            bookmarks: BookmarkStore::new(),
+            branches: BranchStore::new(),
+            snippets: SnippetStore::new(),
+            spellcheck: SpellChecker::new(),
+            context_pins,
+            workspace_stats: WorkspaceStatsCache::new(),
+            reviews: ReviewStore::new(),
+            diff_cache: DiffCache::new(),
         }
     }
 }
@@ -0,0 +1,212 @@
+//! 进程资源占用监控
+//!
+//! 定期采样 Axon 自身、opencode 进程及其子进程（终端 Shell 等）的 CPU 和内存占用，
+//! 保留最近一段时间的历史，每次采样后发送 `stats:update` 事件供性能面板展示。
+
+use crate::opencode::OpencodeService;
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+/// 采样结果事件
+pub const EVENT_STATS_UPDATE: &str = "stats:update";
+
+/// 采样间隔
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(3);
+
+/// 保留的历史采样数（约 5 分钟）
+const HISTORY_SIZE: usize = 100;
+
+/// 识别为终端 Shell 的进程名（不含扩展名，小写）
+const SHELL_NAMES: &[&str] = &[
+    "bash",
+    "zsh",
+    "fish",
+    "sh",
+    "dash",
+    "ksh",
+    "nu",
+    "pwsh",
+    "powershell",
+    "cmd",
+];
+
+/// 进程类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessKind {
+    /// Axon 自身
+    App,
+    Opencode,
+    /// opencode 启动的终端 Shell
+    Shell,
+    /// opencode 的其他子进程（语言服务器等）
+    Child,
+}
+
+/// 单个进程的资源占用
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessStat {
+    pub pid: u32,
+    pub name: String,
+    pub kind: ProcessKind,
+    /// CPU 占用（单核百分比，多核时可超过 100）
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+}
+
+/// 一次采样
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsSample {
+    /// Unix 毫秒
+    pub timestamp: i64,
+    pub processes: Vec<ProcessStat>,
+    pub total_cpu_percent: f32,
+    pub total_memory_bytes: u64,
+}
+
+/// 采样历史
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessStats {
+    pub interval_ms: u64,
+    /// 按时间顺序，最后一项为最新采样
+    pub samples: Vec<StatsSample>,
+}
+
+/// 进程资源占用监控
+pub struct StatsMonitor {
+    opencode: Arc<OpencodeService>,
+    system: Mutex<System>,
+    history: RwLock<VecDeque<StatsSample>>,
+    started: AtomicBool,
+}
+
+impl StatsMonitor {
+    pub fn new(opencode: Arc<OpencodeService>) -> Arc<Self> {
+        Arc::new(Self {
+            opencode,
+            system: Mutex::new(System::new()),
+            history: RwLock::new(VecDeque::with_capacity(HISTORY_SIZE)),
+            started: AtomicBool::new(false),
+        })
+    }
+
+    /// 启动定期采样（重复调用无效）
+    pub fn start(self: &Arc<Self>, app: AppHandle) {
+        if self.started.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let monitor = Arc::clone(self);
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let sampler = Arc::clone(&monitor);
+                match tokio::task::spawn_blocking(move || sampler.sample()).await {
+                    Ok(sample) => {
+                        if let Err(e) = app.emit(EVENT_STATS_UPDATE, &sample) {
+                            warn!("发送资源占用事件失败: {}", e);
+                        }
+                    }
+                    Err(e) => warn!("采样资源占用失败: {}", e),
+                }
+                tokio::time::sleep(SAMPLE_INTERVAL).await;
+            }
+        });
+        info!("进程资源占用监控已启动");
+    }
+
+    pub fn history(&self) -> ProcessStats {
+        ProcessStats {
+            interval_ms: SAMPLE_INTERVAL.as_millis() as u64,
+            samples: self.history.read().iter().cloned().collect(),
+        }
+    }
+
+    /// 采样一次并加入历史（阻塞调用）
+    fn sample(&self) -> StatsSample {
+        let mut system = self.system.lock();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+
+        let mut processes = Vec::new();
+        let mut push = |pid: Pid, kind: ProcessKind| {
+            if let Some(process) = system.process(pid) {
+                processes.push(ProcessStat {
+                    pid: pid.as_u32(),
+                    name: process.name().to_string_lossy().to_string(),
+                    kind,
+                    cpu_percent: process.cpu_usage(),
+                    memory_bytes: process.memory(),
+                });
+            }
+        };
+
+        if let Ok(pid) = sysinfo::get_current_pid() {
+            push(pid, ProcessKind::App);
+        }
+        if let Some(opencode) = self.opencode.process_id().map(Pid::from_u32) {
+            push(opencode, ProcessKind::Opencode);
+            let mut children: Vec<(Pid, ProcessKind)> = system
+                .processes()
+                .iter()
+                .filter(|(_, process)| process.parent() == Some(opencode))
+                .map(|(pid, process)| (*pid, classify_child(&process.name().to_string_lossy())))
+                .collect();
+            children.sort_by_key(|(pid, _)| *pid);
+            for (pid, kind) in children {
+                push(pid, kind);
+            }
+        }
+        drop(system);
+
+        let sample = StatsSample {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            total_cpu_percent: processes.iter().map(|p| p.cpu_percent).sum(),
+            total_memory_bytes: processes.iter().map(|p| p.memory_bytes).sum(),
+            processes,
+        };
+        let mut history = self.history.write();
+        if history.len() >= HISTORY_SIZE {
+            history.pop_front();
+        }
+        history.push_back(sample.clone());
+        sample
+    }
+}
+
+/// 按进程名区分终端 Shell 和其他子进程
+fn classify_child(name: &str) -> ProcessKind {
+    let name = name.to_lowercase();
+    let stem = name.strip_suffix(".exe").unwrap_or(&name);
+    if SHELL_NAMES.contains(&stem) {
+        ProcessKind::Shell
+    } else {
+        ProcessKind::Child
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_shells_by_name() {
+        assert_eq!(classify_child("zsh"), ProcessKind::Shell);
+        assert_eq!(classify_child("PowerShell.exe"), ProcessKind::Shell);
+        assert_eq!(classify_child("pwsh.exe"), ProcessKind::Shell);
+        assert_eq!(classify_child("rust-analyzer"), ProcessKind::Child);
+        assert_eq!(classify_child("bashful"), ProcessKind::Child);
+    }
+}
@@ -0,0 +1,318 @@
+//! HTTP 工具执行
+//!
+//! 按模板（见 [`super::template`]）生成请求地址、请求头和请求体，密钥在发送前从钥匙串读取；
+//! 响应可以按路径提取 JSON 字段、再用输出模板整理成给 Agent 的文本。
+//! 返回给 Agent 的内容中出现的密钥值会被替换为占位文本。
+
+use super::secrets::read_secret;
+use super::template::{self, Escape, TemplateContext};
+use super::HttpToolHandler;
+use crate::error::AxonError;
+use crate::utils::redact::REDACTED;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::debug;
+
+/// 请求超时
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 少于该长度的密钥不做输出替换（避免误伤普通文本）
+const MIN_REDACT_CHARS: usize = 4;
+
+/// 保存前检查模板和地址
+pub fn validate(handler: &HttpToolHandler) -> Result<(), AxonError> {
+    parse_method(&handler.method)?;
+    template::validate(&handler.url, false)?;
+    // 主机部分必须是模板中的字面文本，占位符替换为示例值后必须是 http(s) 地址
+    let sample = template::render(
+        &handler.url,
+        &TemplateContext {
+            args: &Value::Null,
+            secrets: &sample_secrets(&handler.url),
+            response: None,
+            status: None,
+        },
+        Escape::Url,
+    )?;
+    let valid_url =
+        reqwest::Url::parse(&sample).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+    if !valid_url || literal_authority(&handler.url).is_none() {
+        return Err(AxonError::invalid_input(format!(
+            "无效的工具地址: {}",
+            handler.url
+        )));
+    }
+    for (name, value) in &handler.headers {
+        reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| AxonError::invalid_input(format!("无效的请求头名称: {}", name)))?;
+        template::validate(value, false)?;
+    }
+    if let Some(body) = &handler.body {
+        template::validate(body, false)?;
+    }
+    if let Some(mapping) = &handler.response {
+        if let Some(output) = &mapping.template {
+            template::validate(output, true)?;
+        }
+    }
+    Ok(())
+}
+
+/// 模板中 `://` 与第一个 `/`、`?` 或 `#` 之间的主机部分，不允许包含占位符
+fn literal_authority(url: &str) -> Option<&str> {
+    let (scheme, rest) = url.split_once("://")?;
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return None;
+    }
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let authority = &rest[..end];
+    (!authority.is_empty() && !authority.contains("{{")).then_some(authority)
+}
+
+/// 发送请求并按映射整理响应
+pub async fn execute(
+    client: &reqwest::Client,
+    handler: &HttpToolHandler,
+    args: &Value,
+) -> Result<String, AxonError> {
+    let secrets = load_secrets(handler).await?;
+    let ctx = TemplateContext {
+        args,
+        secrets: &secrets,
+        response: None,
+        status: None,
+    };
+
+    let method = parse_method(&handler.method)?;
+    let url = template::render(&handler.url, &ctx, Escape::Url)?;
+    let mut request = client.request(method.clone(), &url).timeout(HTTP_TIMEOUT);
+    let mut has_content_type = false;
+    for (name, value) in &handler.headers {
+        has_content_type |= name.eq_ignore_ascii_case("content-type");
+        request = request.header(name, template::render(value, &ctx, Escape::Raw)?);
+    }
+
+    request = match &handler.body {
+        Some(body) => {
+            let body = template::render(body, &ctx, Escape::Raw)?;
+            if !has_content_type {
+                let content_type = if serde_json::from_str::<Value>(&body).is_ok() {
+                    "application/json"
+                } else {
+                    "text/plain; charset=utf-8"
+                };
+                request = request.header(reqwest::header::CONTENT_TYPE, content_type);
+            }
+            request.body(body)
+        }
+        // 没有请求体模板时沿用默认行为：GET 且地址中没有占位符时参数作为查询参数，其它方法发送参数 JSON
+        None if method == reqwest::Method::GET => {
+            if handler.url.contains("{{") {
+                request
+            } else {
+                request.query(&query_pairs(args))
+            }
+        }
+        None => request.json(args),
+    };
+
+    debug!("HTTP 工具请求: {} {}", method, redact(&url, &secrets));
+    let response = request.send().await?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        let body: String = body.chars().take(2000).collect();
+        return Err(AxonError::external(format!(
+            "工具接口返回错误: HTTP {}: {}",
+            status.as_u16(),
+            redact(&body, &secrets)
+        ))
+        .with_details(serde_json::json!({ "status": status.as_u16() })));
+    }
+
+    let output = match &handler.response {
+        Some(mapping) => map_response(mapping, &body, status.as_u16(), &ctx)?,
+        None => body,
+    };
+    Ok(redact(&output, &secrets))
+}
+
+/// 按响应映射提取 / 格式化响应
+fn map_response(
+    mapping: &super::ResponseMapping,
+    body: &str,
+    status: u16,
+    ctx: &TemplateContext<'_>,
+) -> Result<String, AxonError> {
+    let parsed: Value = match serde_json::from_str(body) {
+        Ok(value) => value,
+        // 非 JSON 响应在模板中作为字符串使用
+        Err(_) if mapping.path.is_none() => Value::String(body.to_string()),
+        Err(e) => {
+            return Err(AxonError::invalid_data(format!(
+                "工具接口返回的不是 JSON，无法按路径提取: {}",
+                e
+            )))
+        }
+    };
+    let extracted = match &mapping.path {
+        Some(path) => template::get_path(&parsed, path)
+            .cloned()
+            .unwrap_or(Value::Null),
+        None => parsed,
+    };
+
+    match &mapping.template {
+        Some(output) => template::render(
+            output,
+            &TemplateContext {
+                response: Some(&extracted),
+                status: Some(status),
+                ..*ctx
+            },
+            Escape::Raw,
+        ),
+        None => Ok(match extracted {
+            Value::String(text) => text,
+            other => serde_json::to_string_pretty(&other).unwrap_or_default(),
+        }),
+    }
+}
+
+/// 从钥匙串读取模板中引用的密钥
+async fn load_secrets(handler: &HttpToolHandler) -> Result<HashMap<String, String>, AxonError> {
+    let mut names: Vec<String> = template::secret_names(&handler.url);
+    for value in handler.headers.values() {
+        names.extend(template::secret_names(value));
+    }
+    if let Some(body) = &handler.body {
+        names.extend(template::secret_names(body));
+    }
+    names.sort();
+    names.dedup();
+    if names.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    // 钥匙串访问可能阻塞（Secret Service 走 D-Bus）
+    tokio::task::spawn_blocking(move || {
+        names
+            .into_iter()
+            .map(|name| read_secret(&name).map(|value| (name, value)))
+            .collect()
+    })
+    .await?
+}
+
+fn parse_method(method: &str) -> Result<reqwest::Method, AxonError> {
+    reqwest::Method::from_bytes(method.to_uppercase().as_bytes())
+        .map_err(|_| AxonError::invalid_input(format!("无效的 HTTP 方法: {}", method)))
+}
+
+fn query_pairs(args: &Value) -> Vec<(String, String)> {
+    args.as_object()
+        .map(|args| {
+            args.iter()
+                .map(|(k, v)| {
+                    (
+                        k.clone(),
+                        v.as_str().map_or_else(|| v.to_string(), str::to_string),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn sample_secrets(template: &str) -> HashMap<String, String> {
+    template::secret_names(template)
+        .into_iter()
+        .map(|name| (name, "secret".to_string()))
+        .collect()
+}
+
+/// 替换文本中出现的密钥值
+fn redact(text: &str, secrets: &HashMap<String, String>) -> String {
+    secrets
+        .values()
+        .filter(|secret| secret.chars().count() >= MIN_REDACT_CHARS)
+        .fold(text.to_string(), |text, secret| {
+            text.replace(secret.as_str(), REDACTED)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ResponseMapping;
+    use super::*;
+    use serde_json::json;
+
+    fn handler(url: &str) -> HttpToolHandler {
+        serde_json::from_value(json!({ "url": url })).unwrap()
+    }
+
+    #[test]
+    fn validates_templated_urls() {
+        assert!(validate(&handler("https://api.internal/tickets/{{args.id}}")).is_ok());
+        assert!(validate(&handler("https://api.internal/x?key={{secrets.KEY}}")).is_ok());
+        assert!(validate(&handler("{{args.url}}")).is_err());
+        assert!(validate(&handler("ftp://api.internal/")).is_err());
+        assert!(validate(&handler("https://api.internal/{{env.HOME}}")).is_err());
+    }
+
+    #[test]
+    fn rejects_templated_hosts() {
+        assert!(validate(&handler("https://{{args.host}}/tickets")).is_err());
+        assert!(validate(&handler("https://api.{{args.domain}}/x")).is_err());
+        assert!(validate(&handler("https://api.internal:{{args.port}}/x")).is_err());
+        assert!(validate(&handler("https://{{secrets.USER}}@api.internal/")).is_err());
+        assert!(validate(&handler("http{{args.s}}://api.internal/")).is_err());
+        assert_eq!(
+            literal_authority("https://api.internal:8443?q={{args.q}}"),
+            Some("api.internal:8443")
+        );
+    }
+
+    #[test]
+    fn maps_json_responses() {
+        let secrets = HashMap::new();
+        let args = json!({});
+        let ctx = TemplateContext {
+            args: &args,
+            secrets: &secrets,
+            response: None,
+            status: None,
+        };
+        let body = r#"{"data": {"items": [{"title": "修复登录"}, {"title": "升级依赖"}]}}"#;
+        let mapping = ResponseMapping {
+            path: Some("data.items.0".to_string()),
+            template: Some("[{{status}}] {{response.title}}".to_string()),
+        };
+        assert_eq!(
+            map_response(&mapping, body, 200, &ctx).unwrap(),
+            "[200] 修复登录"
+        );
+
+        let mapping = ResponseMapping {
+            path: Some("data.items".to_string()),
+            template: None,
+        };
+        assert!(map_response(&mapping, body, 200, &ctx)
+            .unwrap()
+            .contains("升级依赖"));
+        assert!(map_response(&mapping, "not json", 200, &ctx).is_err());
+    }
+
+    #[test]
+    fn redacts_secret_values() {
+        let secrets = HashMap::from([
+            ("TOKEN".to_string(), "abcd1234".to_string()),
+            ("PIN".to_string(), "12".to_string()),
+        ]);
+        assert_eq!(
+            redact("token=abcd1234 pin=12", &secrets),
+            format!("token={} pin=12", REDACTED)
+        );
+    }
+}
@@ -0,0 +1,586 @@
+//! 自定义工具注册表
+//!
+//! 用户定义的工具（名称、描述、参数 JSON Schema、处理方式）保存在
+//! `<app_data_dir>/tools/<名称>.json`。Bridge 插件启动时通过 Plugin API 获取工具列表
+//! 并注册到 OpenCode，Agent 调用工具时插件再通过 Plugin API 回到 Axon 执行，
+//! 处理方式中的地址、请求头等细节不会暴露给插件和模型。
+//! HTTP 工具按模板发送请求（见 [`http`]），脚本工具在临时目录中执行（见 [`script`]）。
+
+mod http;
+mod script;
+mod secrets;
+mod template;
+
+use crate::error::{AxonError, ErrorKind};
+use crate::utils::json_store;
+use crate::utils::paths::get_app_data_dir;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, info};
+
+/// 存储目录（相对应用数据目录）
+const TOOLS_DIR: &str = "tools";
+
+/// 工具名称最大长度
+const MAX_NAME_CHARS: usize = 64;
+
+/// 返回给 Agent 的输出上限
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// OpenCode 内置工具名，自定义工具不能覆盖
+const RESERVED_NAMES: &[&str] = &[
+    "bash",
+    "edit",
+    "write",
+    "read",
+    "grep",
+    "glob",
+    "list",
+    "patch",
+    "todowrite",
+    "todoread",
+    "webfetch",
+    "task",
+    "lsp",
+];
+
+/// 脚本运行时
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptRuntime {
+    Node,
+    Python,
+    Deno,
+}
+
+/// HTTP 处理方式
+///
+/// 地址、请求头和请求体都是模板，可以引用参数（`{{args.id}}`）和钥匙串中的密钥
+/// （`{{secrets.API_TOKEN}}`）。没有请求体模板时，参数作为 JSON 请求体发送
+/// （GET 且地址中没有占位符时作为查询参数）。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpToolHandler {
+    #[serde(default = "default_method")]
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// 请求体模板
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// 响应映射，为空时原样返回响应文本
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<ResponseMapping>,
+}
+
+/// 响应映射
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseMapping {
+    /// 从 JSON 响应中提取的字段路径（`data.items.0`），为空时使用整个响应
+    #[serde(default)]
+    pub path: Option<String>,
+    /// 输出模板，可以引用 `response`（提取后的值）和 `status`
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+/// 脚本处理方式：参数以 JSON 形式从标准输入传入，标准输出作为结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptToolHandler {
+    pub runtime: ScriptRuntime,
+    /// 脚本源码
+    pub source: String,
+    /// 执行时限（秒），为空时使用默认值
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// 工具处理方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ToolHandler {
+    Http(HttpToolHandler),
+    Script(ScriptToolHandler),
+}
+
+/// 工具定义
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    /// 参数的 JSON Schema（`type: object`）
+    #[serde(default = "default_parameters")]
+    pub parameters: Value,
+    pub handler: ToolHandler,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Unix 毫秒
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+impl ToolDefinition {
+    /// Agent 调用前是否需要用户确认
+    ///
+    /// Node / Python 脚本不受权限限制，可以读写任意文件、访问网络；
+    /// Deno 脚本以无权限模式运行，HTTP 工具只能访问定义中的地址
+    pub fn requires_consent(&self) -> bool {
+        match &self.handler {
+            ToolHandler::Script(handler) => handler.runtime != ScriptRuntime::Deno,
+            ToolHandler::Http(_) => false,
+        }
+    }
+}
+
+fn default_parameters() -> Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 提供给 Bridge 插件的工具描述（不含处理方式）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    pub parameters: Value,
+}
+
+/// 工具执行结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolExecution {
+    pub output: String,
+    /// 脚本的标准错误输出（HTTP 工具为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
+    /// 输出超过上限被截断
+    pub truncated: bool,
+    pub duration_ms: u64,
+}
+
+/// 自定义工具注册表
+#[derive(Debug)]
+pub struct ToolRegistry {
+    /// 存储目录，为空时使用应用数据目录下的 tools
+    dir: Option<PathBuf>,
+    /// 串行化读写
+    lock: Mutex<()>,
+    http: reqwest::Client,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::with_dir(None))
+    }
+
+    fn with_dir(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            lock: Mutex::new(()),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// 列出所有工具（按名称排序）
+    pub fn list(&self) -> Result<Vec<ToolDefinition>, AxonError> {
+        let _guard = self.lock.lock();
+        let Ok(entries) = std::fs::read_dir(self.dir()?) else {
+            return Ok(Vec::new());
+        };
+        let mut tools: Vec<ToolDefinition> = entries
+            .flatten()
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+            // 跳过密钥索引等非工具文件
+            .filter(|e| {
+                e.path()
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .is_some_and(|stem| validate_name(stem).is_ok())
+            })
+            .filter_map(|e| json_store::load(&e.path(), "工具定义").ok().flatten())
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(tools)
+    }
+
+    /// 已启用工具的描述，供 Bridge 插件注册
+    pub fn specs(&self) -> Result<Vec<ToolSpec>, AxonError> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|tool| tool.enabled)
+            .map(|tool| ToolSpec {
+                name: tool.name,
+                description: tool.description,
+                parameters: tool.parameters,
+            })
+            .collect())
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<ToolDefinition>, AxonError> {
+        validate_name(name)?;
+        let _guard = self.lock.lock();
+        let path = self.tool_path(name)?;
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(AxonError::io("读取工具定义失败", &e)),
+        };
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| AxonError::invalid_data(format!("工具定义无法解析: {}", e)))
+    }
+
+    /// 创建或更新工具
+    pub fn save(&self, mut tool: ToolDefinition) -> Result<ToolDefinition, AxonError> {
+        validate_definition(&tool)?;
+        let existing = self.get(&tool.name)?;
+
+        let _guard = self.lock.lock();
+        let now = chrono::Utc::now().timestamp_millis();
+        tool.created_at = existing.map_or(now, |t| t.created_at);
+        tool.updated_at = now;
+
+        json_store::save(&self.tool_path(&tool.name)?, &tool, "工具定义")?;
+        info!("已保存工具: {}", tool.name);
+        Ok(tool)
+    }
+
+    /// 删除工具，返回工具是否存在
+    pub fn delete(&self, name: &str) -> Result<bool, AxonError> {
+        validate_name(name)?;
+        let _guard = self.lock.lock();
+        match std::fs::remove_file(self.tool_path(name)?) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(AxonError::io("删除工具定义失败", &e)),
+        }
+    }
+
+    /// 执行工具
+    pub async fn execute(&self, name: &str, args: Value) -> Result<ToolExecution, AxonError> {
+        let tool = self
+            .get(name)?
+            .ok_or_else(|| AxonError::not_found(format!("工具不存在: {}", name)))?;
+        if !tool.enabled {
+            return Err(AxonError::unavailable(format!("工具已禁用: {}", name)));
+        }
+        validate_args(&tool.parameters, &args)?;
+
+        let started = Instant::now();
+        let (output, stderr, truncated) = match &tool.handler {
+            ToolHandler::Http(handler) => (
+                http::execute(&self.http, handler, &args).await?,
+                None,
+                false,
+            ),
+            ToolHandler::Script(handler) => {
+                let result = script::run_script(handler, &args).await?;
+                if result.timed_out {
+                    return Err(AxonError::timeout(format!(
+                        "工具 {} 执行超时（{} 秒）",
+                        name,
+                        handler.timeout_secs.unwrap_or(script::DEFAULT_TIMEOUT_SECS)
+                    )));
+                }
+                if !result.success() {
+                    let (stderr, _) = truncate_output(result.stderr);
+                    return Err(AxonError::external(format!(
+                        "工具 {} 执行失败（退出码 {:?}）: {}",
+                        name,
+                        result.exit_code,
+                        stderr.trim()
+                    ))
+                    .with_details(serde_json::json!({ "exitCode": result.exit_code })));
+                }
+                let stderr = Some(result.stderr).filter(|s| !s.trim().is_empty());
+                (result.stdout, stderr, result.truncated)
+            }
+        };
+        debug!("工具 {} 执行完成，耗时 {:?}", name, started.elapsed());
+
+        let (output, output_truncated) = truncate_output(output);
+        let (stderr, stderr_truncated) = match stderr {
+            Some(stderr) => {
+                let (stderr, truncated) = truncate_output(stderr);
+                (Some(stderr), truncated)
+            }
+            None => (None, false),
+        };
+        Ok(ToolExecution {
+            output,
+            stderr,
+            truncated: truncated || output_truncated || stderr_truncated,
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+
+    fn dir(&self) -> Result<PathBuf, AxonError> {
+        self.dir
+            .clone()
+            .or_else(|| get_app_data_dir().map(|d| d.join(TOOLS_DIR)))
+            .ok_or_else(|| {
+                AxonError::localized(
+                    ErrorKind::Unavailable,
+                    "app.data_dir_unavailable",
+                    serde_json::json!(null),
+                )
+            })
+    }
+
+    fn tool_path(&self, name: &str) -> Result<PathBuf, AxonError> {
+        Ok(self.dir()?.join(format!("{}.json", name)))
+    }
+
+    /// 已保存的密钥名称
+    pub fn list_secrets(&self) -> Result<Vec<String>, AxonError> {
+        let _guard = self.lock.lock();
+        self.read_secret_index()
+    }
+
+    /// 保存密钥到系统钥匙串
+    pub fn set_secret(&self, name: &str, value: &str) -> Result<(), AxonError> {
+        validate_secret_name(name)?;
+        if value.is_empty() {
+            return Err(AxonError::invalid_input("密钥不能为空"));
+        }
+        let _guard = self.lock.lock();
+        secrets::write_secret(name, value)?;
+        let mut names = self.read_secret_index()?;
+        if !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+            names.sort();
+            self.write_secret_index(&names)?;
+        }
+        info!("已保存工具密钥: {}", name);
+        Ok(())
+    }
+
+    /// 从钥匙串删除密钥
+    pub fn delete_secret(&self, name: &str) -> Result<(), AxonError> {
+        validate_secret_name(name)?;
+        let _guard = self.lock.lock();
+        secrets::remove_secret(name)?;
+        let mut names = self.read_secret_index()?;
+        names.retain(|n| n != name);
+        self.write_secret_index(&names)
+    }
+
+    fn read_secret_index(&self) -> Result<Vec<String>, AxonError> {
+        let path = self.dir()?.join(secrets::INDEX_FILE);
+        Ok(json_store::load(&path, "密钥索引")?.unwrap_or_default())
+    }
+
+    fn write_secret_index(&self, names: &[String]) -> Result<(), AxonError> {
+        json_store::save(&self.dir()?.join(secrets::INDEX_FILE), &names, "密钥索引")
+    }
+}
+
+/// 字母开头，只含字母、数字、`_` 和 `-`
+fn is_identifier(name: &str) -> bool {
+    name.chars().count() <= MAX_NAME_CHARS
+        && name.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// 工具名称同时用作文件名
+fn validate_name(name: &str) -> Result<(), AxonError> {
+    if !is_identifier(name) {
+        return Err(AxonError::invalid_input(format!(
+            "无效的工具名称: {}（字母开头，只能包含字母、数字、_ 和 -，不超过 {} 个字符）",
+            name, MAX_NAME_CHARS
+        )));
+    }
+    Ok(())
+}
+
+fn validate_definition(tool: &ToolDefinition) -> Result<(), AxonError> {
+    validate_name(&tool.name)?;
+    if RESERVED_NAMES.contains(&tool.name.to_lowercase().as_str()) {
+        return Err(AxonError::invalid_input(format!(
+            "工具名称与 OpenCode 内置工具冲突: {}",
+            tool.name
+        )));
+    }
+    if tool.description.trim().is_empty() {
+        return Err(AxonError::invalid_input("工具描述不能为空"));
+    }
+    let schema_type = tool.parameters.get("type").and_then(Value::as_str);
+    if !tool.parameters.is_object() || schema_type.is_some_and(|t| t != "object") {
+        return Err(AxonError::invalid_input(
+            "工具参数必须是 type 为 object 的 JSON Schema",
+        ));
+    }
+    match &tool.handler {
+        ToolHandler::Http(handler) => http::validate(handler)?,
+        ToolHandler::Script(handler) => {
+            if handler.source.trim().is_empty() {
+                return Err(AxonError::invalid_input("脚本内容不能为空"));
+            }
+            if handler
+                .timeout_secs
+                .is_some_and(|secs| secs == 0 || secs > script::MAX_TIMEOUT_SECS)
+            {
+                return Err(AxonError::invalid_input(format!(
+                    "脚本执行时限必须在 1 - {} 秒之间",
+                    script::MAX_TIMEOUT_SECS
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_secret_name(name: &str) -> Result<(), AxonError> {
+    if !is_identifier(name) {
+        return Err(AxonError::invalid_input(format!(
+            "无效的密钥名称: {}（字母开头，只能包含字母、数字、_ 和 -，不超过 {} 个字符）",
+            name, MAX_NAME_CHARS
+        )));
+    }
+    Ok(())
+}
+
+/// 检查参数是对象且包含 Schema 中的必填字段
+fn validate_args(schema: &Value, args: &Value) -> Result<(), AxonError> {
+    let Some(args) = args.as_object() else {
+        return Err(AxonError::invalid_input("工具参数必须是 JSON 对象"));
+    };
+    let missing: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .filter(|field| !args.contains_key(*field))
+        .collect();
+    if !missing.is_empty() {
+        return Err(AxonError::invalid_input(format!(
+            "缺少必填参数: {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+/// 按字节上限截断输出（不截断在字符中间）
+fn truncate_output(mut output: String) -> (String, bool) {
+    if output.len() <= MAX_OUTPUT_BYTES {
+        return (output, false);
+    }
+    let mut end = MAX_OUTPUT_BYTES;
+    while !output.is_char_boundary(end) {
+        end -= 1;
+    }
+    output.truncate(end);
+    (output, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn registry() -> (TempDir, ToolRegistry) {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = ToolRegistry::with_dir(Some(dir.path().to_path_buf()));
+        (dir, registry)
+    }
+
+    fn http_tool(name: &str) -> ToolDefinition {
+        serde_json::from_value(json!({
+            "name": name,
+            "description": "查询工单",
+            "parameters": {
+                "type": "object",
+                "properties": { "id": { "type": "string" } },
+                "required": ["id"]
+            },
+            "handler": { "type": "http", "url": "https://example.com/tickets" }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn saves_lists_and_deletes_tools() {
+        let (_dir, registry) = registry();
+        let saved = registry.save(http_tool("get_ticket")).unwrap();
+        assert!(saved.created_at > 0);
+        assert!(saved.enabled);
+
+        let tools = registry.list().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert!(matches!(tools[0].handler, ToolHandler::Http(ref h) if h.method == "POST"));
+        assert_eq!(registry.specs().unwrap()[0].name, "get_ticket");
+
+        assert!(registry.delete("get_ticket").unwrap());
+        assert!(!registry.delete("get_ticket").unwrap());
+        assert!(registry.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_definitions() {
+        let (_dir, registry) = registry();
+        assert!(registry.save(http_tool("bash")).is_err());
+        assert!(registry.save(http_tool("../escape")).is_err());
+        assert!(registry.save(http_tool("1st")).is_err());
+
+        let mut tool = http_tool("ok");
+        tool.parameters = json!({ "type": "string" });
+        assert!(registry.save(tool).is_err());
+    }
+
+    #[test]
+    fn requires_consent_for_unrestricted_scripts() {
+        let script = |runtime: &str| -> ToolDefinition {
+            serde_json::from_value(json!({
+                "name": "script",
+                "description": "脚本",
+                "handler": { "type": "script", "runtime": runtime, "source": "" }
+            }))
+            .unwrap()
+        };
+        assert!(script("node").requires_consent());
+        assert!(script("python").requires_consent());
+        assert!(!script("deno").requires_consent());
+        assert!(!http_tool("t").requires_consent());
+    }
+
+    #[test]
+    fn checks_required_arguments() {
+        let schema = http_tool("t").parameters;
+        assert!(validate_args(&schema, &json!({ "id": "42" })).is_ok());
+        assert!(validate_args(&schema, &json!({})).is_err());
+        assert!(validate_args(&schema, &json!("42")).is_err());
+    }
+
+    #[test]
+    fn truncates_on_char_boundary() {
+        let (output, truncated) = truncate_output("好".repeat(MAX_OUTPUT_BYTES));
+        assert!(truncated);
+        assert!(output.len() <= MAX_OUTPUT_BYTES);
+        assert!(output.chars().all(|c| c == '好'));
+    }
+}
@@ -0,0 +1,300 @@
+//! 脚本工具执行
+//!
+//! 每次执行都在独立的临时目录中启动解释器：清空继承的环境变量（只保留 PATH 和少量
+//! 运行所需变量，HOME / TMPDIR 指向临时目录），参数以 JSON 从标准输入传入，
+//! 标准输出 / 标准错误分别捕获并限制大小，超过时限直接终止进程。
+//!
+//! 这不是沙箱：只有 Deno 以无权限模式运行，Node 和 Python（隔离模式 `-I` 只忽略
+//! 环境变量和用户 site-packages）仍可访问文件系统、网络和子进程，
+//! 因此 Agent 调用这两类工具前需要用户确认（见 [`super::ToolDefinition::requires_consent`]）。
+
+use super::{ScriptRuntime, ScriptToolHandler};
+use crate::error::AxonError;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// 默认执行时限
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// 允许配置的最长执行时限
+pub const MAX_TIMEOUT_SECS: u64 = 300;
+
+/// 每个输出流保留的最大字节数
+const MAX_STREAM_BYTES: usize = 256 * 1024;
+
+/// 进程结束后等待输出流关闭的时间（子进程可能仍持有管道）
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 从父进程保留的环境变量
+const INHERITED_ENV: &[&str] = &["PATH", "SYSTEMROOT", "WINDIR", "PATHEXT", "COMSPEC"];
+
+/// 区分同一进程内的多个工作目录
+static RUN_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// 脚本执行结果
+#[derive(Debug, Clone)]
+pub struct ScriptOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    /// 任一输出流超过上限被截断
+    pub truncated: bool,
+}
+
+impl ScriptOutput {
+    pub fn success(&self) -> bool {
+        !self.timed_out && self.exit_code == Some(0)
+    }
+}
+
+impl ScriptRuntime {
+    fn program(self) -> &'static str {
+        match self {
+            ScriptRuntime::Node => "node",
+            ScriptRuntime::Python if cfg!(target_os = "windows") => "python",
+            ScriptRuntime::Python => "python3",
+            ScriptRuntime::Deno => "deno",
+        }
+    }
+
+    fn script_file(self) -> &'static str {
+        match self {
+            ScriptRuntime::Node => "tool.js",
+            ScriptRuntime::Python => "tool.py",
+            ScriptRuntime::Deno => "tool.ts",
+        }
+    }
+
+    /// 解释器参数（脚本文件名之前）
+    fn args(self) -> &'static [&'static str] {
+        match self {
+            ScriptRuntime::Node => &[],
+            // 隔离模式：忽略 PYTHON* 环境变量和用户 site-packages，不写 .pyc
+            ScriptRuntime::Python => &["-I", "-B"],
+            // 不授予任何权限，也不在运行时弹出授权提示
+            ScriptRuntime::Deno => &["run", "--quiet", "--no-prompt"],
+        }
+    }
+}
+
+/// 在临时工作目录中执行脚本
+pub async fn run_script(
+    handler: &ScriptToolHandler,
+    args: &Value,
+) -> Result<ScriptOutput, AxonError> {
+    let workdir = create_workdir()?;
+    let result = run_in(&workdir, handler, args).await;
+    if let Err(e) = tokio::fs::remove_dir_all(&workdir).await {
+        warn!("清理脚本工作目录失败: {:?}, 错误: {}", workdir, e);
+    }
+    result
+}
+
+async fn run_in(
+    workdir: &Path,
+    handler: &ScriptToolHandler,
+    args: &Value,
+) -> Result<ScriptOutput, AxonError> {
+    let runtime = handler.runtime;
+    let script = workdir.join(runtime.script_file());
+    tokio::fs::write(&script, &handler.source)
+        .await
+        .map_err(|e| AxonError::io("写入脚本失败", &e))?;
+
+    let mut command = build_command(runtime, workdir);
+    let mut child = command.spawn().map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            AxonError::unavailable(format!("未找到脚本运行时: {}", runtime.program()))
+        } else {
+            AxonError::io("启动脚本失败", &e)
+        }
+    })?;
+
+    let stdout_task = child.stdout.take().map(spawn_capture);
+    let stderr_task = child.stderr.take().map(spawn_capture);
+    if let Some(mut stdin) = child.stdin.take() {
+        // 脚本可能不读取标准输入，在后台写入，写完后关闭管道
+        let input = args.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = stdin.write_all(input.as_bytes()).await {
+                debug!("写入脚本标准输入失败: {}", e);
+            }
+        });
+    }
+
+    let timeout = Duration::from_secs(
+        handler
+            .timeout_secs
+            .unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS),
+    );
+    let (exit_code, timed_out) = match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(status) => (
+            status
+                .map_err(|e| AxonError::io("等待脚本结束失败", &e))?
+                .code(),
+            false,
+        ),
+        Err(_) => {
+            debug!("脚本执行超时（{:?}），终止进程", timeout);
+            if let Err(e) = child.kill().await {
+                warn!("终止脚本进程失败: {}", e);
+            }
+            (None, true)
+        }
+    };
+
+    let (stdout, stdout_truncated) = collect(stdout_task).await;
+    let (stderr, stderr_truncated) = collect(stderr_task).await;
+    Ok(ScriptOutput {
+        stdout,
+        stderr,
+        exit_code,
+        timed_out,
+        truncated: stdout_truncated || stderr_truncated,
+    })
+}
+
+/// 构建受限的解释器命令
+fn build_command(runtime: ScriptRuntime, workdir: &Path) -> Command {
+    let mut command = Command::new(runtime.program());
+    command
+        .args(runtime.args())
+        .arg(runtime.script_file())
+        .current_dir(workdir)
+        .env_clear()
+        .envs(script_env(workdir))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    // Windows 平台：避免弹出控制台窗口
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    command
+}
+
+/// 脚本环境变量：用户目录和临时目录都指向工作目录
+fn script_env(workdir: &Path) -> Vec<(String, String)> {
+    let workdir = workdir.to_string_lossy().to_string();
+    let mut env: Vec<(String, String)> = INHERITED_ENV
+        .iter()
+        .filter_map(|key| Some((key.to_string(), std::env::var(key).ok()?)))
+        .collect();
+    for key in ["HOME", "USERPROFILE", "TMPDIR", "TEMP", "TMP"] {
+        env.push((key.to_string(), workdir.clone()));
+    }
+    env.extend(
+        [
+            ("LANG", "C.UTF-8"),
+            ("PYTHONIOENCODING", "utf-8"),
+            ("NO_COLOR", "1"),
+            ("DENO_NO_UPDATE_CHECK", "1"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string())),
+    );
+    env
+}
+
+fn create_workdir() -> Result<PathBuf, AxonError> {
+    let dir = std::env::temp_dir().join(format!(
+        "axon-tool-{}-{}-{}",
+        std::process::id(),
+        chrono::Utc::now().timestamp_millis(),
+        RUN_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&dir).map_err(|e| AxonError::io("创建脚本工作目录失败", &e))?;
+    Ok(dir)
+}
+
+/// 读取输出流，只保留前 `MAX_STREAM_BYTES` 字节，其余读出后丢弃以免子进程阻塞
+fn spawn_capture<R>(reader: R) -> JoinHandle<(Vec<u8>, bool)>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(read_capped(reader, MAX_STREAM_BYTES))
+}
+
+async fn read_capped<R>(mut reader: R, limit: usize) -> (Vec<u8>, bool)
+where
+    R: AsyncRead + Unpin,
+{
+    let mut captured = Vec::new();
+    let mut truncated = false;
+    let mut buf = [0u8; 8192];
+    loop {
+        match reader.read(&mut buf).await {
+            Ok(0) => break,
+            Ok(n) => {
+                let room = limit.saturating_sub(captured.len());
+                captured.extend_from_slice(&buf[..n.min(room)]);
+                truncated |= n > room;
+            }
+            Err(e) => {
+                debug!("读取脚本输出失败: {}", e);
+                break;
+            }
+        }
+    }
+    (captured, truncated)
+}
+
+async fn collect(task: Option<JoinHandle<(Vec<u8>, bool)>>) -> (String, bool) {
+    let Some(task) = task else {
+        return (String::new(), false);
+    };
+    match tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, task).await {
+        Ok(Ok((bytes, truncated))) => (String::from_utf8_lossy(&bytes).into_owned(), truncated),
+        _ => (String::new(), true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isolates_environment_to_workdir() {
+        let workdir = Path::new("/tmp/axon-tool-test");
+        let env = script_env(workdir);
+        let get = |key: &str| env.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+        assert_eq!(get("HOME"), Some("/tmp/axon-tool-test"));
+        assert_eq!(get("TMPDIR"), Some("/tmp/axon-tool-test"));
+        assert_eq!(get("PATH").map(str::to_string), std::env::var("PATH").ok());
+        assert!(env.iter().all(|(k, _)| k != "AXON_RUNNING" && k != "USER"));
+    }
+
+    #[test]
+    fn caps_captured_output() {
+        let data = vec![b'x'; 20_000];
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let (bytes, truncated) = runtime.block_on(read_capped(&data[..], 10_000));
+        assert_eq!(bytes.len(), 10_000);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn runs_deno_without_permissions() {
+        assert!(ScriptRuntime::Deno.args().contains(&"--no-prompt"));
+        assert!(!ScriptRuntime::Deno
+            .args()
+            .iter()
+            .any(|a| a.starts_with("--allow")));
+        assert_eq!(ScriptRuntime::Python.args(), &["-I", "-B"]);
+    }
+}
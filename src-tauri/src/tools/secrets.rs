@@ -0,0 +1,47 @@
+//! 工具密钥
+//!
+//! 密钥值保存在系统钥匙串（macOS Keychain / Windows 凭据管理器 / Secret Service），
+//! 只能在 HTTP 工具模板中通过 `{{secrets.名称}}` 引用，不会出现在提示词和工具定义中。
+//! 钥匙串无法枚举条目，工具目录下的 `.secrets.json` 只记录已保存的密钥名称。
+
+use crate::error::AxonError;
+
+/// 钥匙串服务名
+const KEYRING_SERVICE: &str = "axon-desktop.tools";
+
+/// 密钥名称索引文件
+pub(super) const INDEX_FILE: &str = ".secrets.json";
+
+fn entry(name: &str) -> Result<keyring::Entry, AxonError> {
+    keyring::Entry::new(KEYRING_SERVICE, name).map_err(keyring_error)
+}
+
+/// 读取密钥
+pub fn read_secret(name: &str) -> Result<String, AxonError> {
+    entry(name)?.get_password().map_err(|e| match e {
+        keyring::Error::NoEntry => AxonError::not_found(format!("密钥不存在: {}", name)),
+        other => keyring_error(other),
+    })
+}
+
+/// 写入密钥
+pub fn write_secret(name: &str, value: &str) -> Result<(), AxonError> {
+    entry(name)?.set_password(value).map_err(keyring_error)
+}
+
+/// 删除密钥，不存在时忽略
+pub fn remove_secret(name: &str) -> Result<(), AxonError> {
+    match entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(keyring_error(e)),
+    }
+}
+
+fn keyring_error(e: keyring::Error) -> AxonError {
+    match e {
+        keyring::Error::NoStorageAccess(_) | keyring::Error::PlatformFailure(_) => {
+            AxonError::unavailable(format!("无法访问系统钥匙串: {}", e))
+        }
+        other => AxonError::internal(format!("钥匙串操作失败: {}", other)),
+    }
+}
@@ -0,0 +1,268 @@
+//! HTTP 工具请求模板
+//!
+//! 占位符写作 `{{ 表达式 }}` 或 `{{ 表达式 | 过滤器 }}`：
+//! - `args`、`args.路径`：工具参数（路径用 `.` 分隔，数组用下标）
+//! - `secrets.名称`：钥匙串中保存的密钥
+//! - `response`、`response.路径`、`status`：仅在响应映射模板中可用
+//!
+//! 过滤器 `json` 输出 JSON 编码，`url` 做百分号编码，`raw` 原样输出（字符串不加引号，
+//! 其它值输出 JSON）。URL 中的占位符默认按 `url` 编码，其它位置默认 `raw`。
+//! 模板只展开一层，参数值中的 `{{` 不会再被解析，Agent 无法借此读取密钥。
+
+use crate::error::AxonError;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 占位符的默认编码方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    Raw,
+    Url,
+    Json,
+}
+
+/// 模板变量
+pub struct TemplateContext<'a> {
+    pub args: &'a Value,
+    pub secrets: &'a HashMap<String, String>,
+    pub response: Option<&'a Value>,
+    pub status: Option<u16>,
+}
+
+/// 解析后的占位符
+struct Placeholder<'t> {
+    start: usize,
+    end: usize,
+    expr: &'t str,
+    filter: Option<&'t str>,
+}
+
+/// 渲染模板
+pub fn render(
+    template: &str,
+    ctx: &TemplateContext<'_>,
+    default: Escape,
+) -> Result<String, AxonError> {
+    let mut output = String::with_capacity(template.len());
+    let mut cursor = 0;
+    for placeholder in placeholders(template)? {
+        output.push_str(&template[cursor..placeholder.start]);
+        let value = lookup(ctx, placeholder.expr)?;
+        let escape = match placeholder.filter {
+            Some(filter) => parse_filter(filter)?,
+            None => default,
+        };
+        output.push_str(&format_value(&value, escape));
+        cursor = placeholder.end;
+    }
+    output.push_str(&template[cursor..]);
+    Ok(output)
+}
+
+/// 检查模板语法和表达式，`allow_response` 为 false 时不允许引用响应
+pub fn validate(template: &str, allow_response: bool) -> Result<(), AxonError> {
+    for placeholder in placeholders(template)? {
+        if let Some(filter) = placeholder.filter {
+            parse_filter(filter)?;
+        }
+        let root = placeholder.expr.split('.').next().unwrap_or_default();
+        let valid = match root {
+            "args" => true,
+            "secrets" => placeholder
+                .expr
+                .strip_prefix("secrets.")
+                .is_some_and(|name| !name.is_empty() && !name.contains('.')),
+            "response" | "status" => allow_response,
+            _ => false,
+        };
+        if !valid {
+            return Err(AxonError::invalid_input(format!(
+                "模板中无效的占位符: {{{{ {} }}}}",
+                placeholder.expr
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// 模板中引用的密钥名称
+pub fn secret_names(template: &str) -> Vec<String> {
+    placeholders(template)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|p| p.expr.strip_prefix("secrets.").map(str::to_string))
+        .collect()
+}
+
+fn placeholders(template: &str) -> Result<Vec<Placeholder<'_>>, AxonError> {
+    let mut result = Vec::new();
+    let mut offset = 0;
+    while let Some(open) = template[offset..].find("{{") {
+        let start = offset + open;
+        let close = template[start + 2..]
+            .find("}}")
+            .ok_or_else(|| AxonError::invalid_input("模板中的 {{ 缺少对应的 }}"))?;
+        let end = start + 2 + close + 2;
+        let inner = &template[start + 2..end - 2];
+        let (expr, filter) = match inner.split_once('|') {
+            Some((expr, filter)) => (expr.trim(), Some(filter.trim())),
+            None => (inner.trim(), None),
+        };
+        if expr.is_empty() {
+            return Err(AxonError::invalid_input("模板中存在空占位符"));
+        }
+        result.push(Placeholder {
+            start,
+            end,
+            expr,
+            filter,
+        });
+        offset = end;
+    }
+    Ok(result)
+}
+
+fn parse_filter(filter: &str) -> Result<Escape, AxonError> {
+    match filter {
+        "raw" => Ok(Escape::Raw),
+        "url" => Ok(Escape::Url),
+        "json" => Ok(Escape::Json),
+        _ => Err(AxonError::invalid_input(format!(
+            "未知的模板过滤器: {}（支持 raw、url、json）",
+            filter
+        ))),
+    }
+}
+
+fn lookup(ctx: &TemplateContext<'_>, expr: &str) -> Result<Value, AxonError> {
+    let (root, path) = match expr.split_once('.') {
+        Some((root, path)) => (root, Some(path)),
+        None => (expr, None),
+    };
+    let value = match root {
+        "args" => Some(ctx.args),
+        "response" => ctx.response,
+        "status" => return Ok(ctx.status.map_or(Value::Null, Value::from)),
+        "secrets" => {
+            let name = path.unwrap_or_default();
+            return ctx
+                .secrets
+                .get(name)
+                .map(|secret| Value::String(secret.clone()))
+                .ok_or_else(|| AxonError::not_found(format!("密钥不存在: {}", name)));
+        }
+        _ => {
+            return Err(AxonError::invalid_input(format!(
+                "模板中无效的占位符: {{{{ {} }}}}",
+                expr
+            )))
+        }
+    };
+    Ok(value
+        .and_then(|value| match path {
+            Some(path) => get_path(value, path),
+            None => Some(value),
+        })
+        .cloned()
+        .unwrap_or(Value::Null))
+}
+
+/// 按 `a.b.0.c` 形式的路径取值
+pub fn get_path<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |value, segment| match value {
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            Value::Object(map) => map.get(segment),
+            _ => None,
+        })
+}
+
+fn format_value(value: &Value, escape: Escape) -> String {
+    let text = match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    match escape {
+        Escape::Raw => text,
+        Escape::Url => url_encode(&text),
+        Escape::Json => value.to_string(),
+    }
+}
+
+/// 百分号编码（保留 RFC 3986 非保留字符）
+fn url_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render_with(template: &str, args: Value, escape: Escape) -> Result<String, AxonError> {
+        let secrets = HashMap::from([("TOKEN".to_string(), "s3cr3t".to_string())]);
+        let ctx = TemplateContext {
+            args: &args,
+            secrets: &secrets,
+            response: None,
+            status: None,
+        };
+        render(template, &ctx, escape)
+    }
+
+    #[test]
+    fn renders_args_secrets_and_filters() {
+        let args = json!({ "q": "a b/c", "filter": { "ids": [7, 8] } });
+        assert_eq!(
+            render_with(
+                "https://x/search?q={{args.q}}&id={{ args.filter.ids.1 }}",
+                args.clone(),
+                Escape::Url
+            )
+            .unwrap(),
+            "https://x/search?q=a%20b%2Fc&id=8"
+        );
+        assert_eq!(
+            render_with(r#"{"query": {{ args.q | json }}, "ids": {{args.filter.ids}}, "missing": {{args.nope|json}}}"#, args.clone(), Escape::Raw)
+                .unwrap(),
+            r#"{"query": "a b/c", "ids": [7,8], "missing": null}"#
+        );
+        assert_eq!(
+            render_with("Bearer {{secrets.TOKEN}}", args, Escape::Raw).unwrap(),
+            "Bearer s3cr3t"
+        );
+    }
+
+    #[test]
+    fn does_not_expand_placeholders_inside_arguments() {
+        let args = json!({ "q": "{{secrets.TOKEN}}" });
+        assert_eq!(
+            render_with("{{args.q}}", args, Escape::Raw).unwrap(),
+            "{{secrets.TOKEN}}"
+        );
+    }
+
+    #[test]
+    fn validates_template_syntax() {
+        assert!(validate("{{args.a}} {{secrets.KEY | url}}", false).is_ok());
+        assert!(validate("{{response.data}}", false).is_err());
+        assert!(validate("{{response.data}} {{status}}", true).is_ok());
+        assert!(validate("{{env.HOME}}", true).is_err());
+        assert!(validate("{{args.a | upper}}", true).is_err());
+        assert!(validate("{{args.a", true).is_err());
+        assert_eq!(
+            secret_names("{{secrets.A}}-{{args.b}}-{{ secrets.B | url }}"),
+            vec!["A", "B"]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! 系统托盘
+//!
+//! 托盘菜单提供启动 / 停止服务、打开项目、检查更新和退出等快捷操作。
+//! 开启"最小化到托盘"后，关闭主窗口只会隐藏窗口，OpenCode 服务和
+//! Plugin API 继续在后台运行，直到通过托盘菜单退出。
+
+use crate::state::AppState;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{error, info, warn};
+
+/// 需要前端处理的托盘操作事件，payload 为操作名
+pub const EVENT_TRAY_ACTION: &str = "tray:action";
+
+/// 托盘图标 ID
+const TRAY_ID: &str = "main";
+
+const MENU_SHOW: &str = "show";
+const MENU_START_SERVICE: &str = "start_service";
+const MENU_STOP_SERVICE: &str = "stop_service";
+const MENU_OPEN_PROJECT: &str = "open_project";
+const MENU_CHECK_UPDATES: &str = "check_updates";
+const MENU_QUIT: &str = "quit";
+
+/// 创建托盘图标和菜单
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let menu = Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(app, MENU_SHOW, "显示 Axon", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, MENU_START_SERVICE, "启动服务", true, None::<&str>)?,
+            &MenuItem::with_id(app, MENU_STOP_SERVICE, "停止服务", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, MENU_OPEN_PROJECT, "打开项目...", true, None::<&str>)?,
+            &MenuItem::with_id(app, MENU_CHECK_UPDATES, "检查更新", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, MENU_QUIT, "退出", true, None::<&str>)?,
+        ],
+    )?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Axon")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(handle_menu_event)
+        .on_tray_icon_event(handle_tray_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    info!("系统托盘已创建");
+    Ok(())
+}
+
+/// 托盘图标是否已创建（创建失败时关闭主窗口不能只隐藏，否则无法再找回窗口）
+pub fn is_available(app: &AppHandle) -> bool {
+    app.tray_by_id(TRAY_ID).is_some()
+}
+
+/// 显示并聚焦主窗口
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        if let Err(e) = window.show() {
+            error!("显示窗口失败: {}", e);
+        }
+        let _ = window.set_focus();
+    }
+}
+
+fn handle_tray_event(tray: &TrayIcon, event: TrayIconEvent) {
+    // 左键单击托盘图标时恢复主窗口，右键显示菜单
+    if let TrayIconEvent::Click {
+        button: MouseButton::Left,
+        button_state: MouseButtonState::Up,
+        ..
+    } = event
+    {
+        show_main_window(tray.app_handle());
+    }
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        MENU_SHOW => show_main_window(app),
+        MENU_START_SERVICE => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state: tauri::State<'_, AppState> = app.state();
+                let plugin_api_port = state.plugin_api.read().state().get_port();
+                state.opencode.set_plugin_api_port(plugin_api_port);
+                match state.opencode.start().await {
+                    Ok(()) => info!("已通过托盘启动 OpenCode 服务"),
+                    Err(e) => error!("通过托盘启动 OpenCode 服务失败: {}", e),
+                }
+            });
+        }
+        MENU_STOP_SERVICE => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state: tauri::State<'_, AppState> = app.state();
+                match state.opencode.stop().await {
+                    Ok(()) => info!("已通过托盘停止 OpenCode 服务"),
+                    Err(e) => error!("通过托盘停止 OpenCode 服务失败: {}", e),
+                }
+            });
+        }
+        // 打开项目和检查更新需要界面交互，显示窗口后交给前端处理
+        action @ (MENU_OPEN_PROJECT | MENU_CHECK_UPDATES) => {
+            show_main_window(app);
+            if let Err(e) = app.emit(EVENT_TRAY_ACTION, action) {
+                warn!("发送托盘操作事件失败: {}", e);
+            }
+        }
+        MENU_QUIT => {
+            info!("通过托盘退出应用");
+            app.exit(0);
+        }
+        // 全局菜单事件也会分发到这里（如文件右键菜单），忽略非托盘菜单项
+        _ => {}
+    }
+}
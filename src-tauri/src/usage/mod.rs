@@ -0,0 +1,18 @@
+//! 服务商用量统计模块
+//!
+//! 记录各服务商 / 模型的请求次数和 token 用量，按天聚合并持久化，
+//! 供设置页查看用量汇总；同时记录每次请求的排队耗时、首 token 延迟和输出速度，
+//! 用于比较各服务商的实际表现。
+//!
+//! ## 数据来源
+//!
+//! OpenCode 插件将 `message.updated` 事件转发到 Plugin API，
+//! 助手消息完成时（`time.completed` 存在）按消息 ID 去重后计入当天用量。
+//! 性能样本的时间点来自消息的 `time.created` 和 `message.part.updated` 中的第一个输出 part。
+
+mod performance;
+mod tracker;
+mod types;
+
+pub use tracker::UsageTracker;
+pub use types::*;
@@ -0,0 +1,211 @@
+//! 模型性能记录
+//!
+//! 在助手消息的生命周期中记录时间点：
+//! - 排队耗时：用户消息创建 → 助手消息创建
+//! - 首 token 延迟：助手消息创建 → 第一个输出 part（文本、推理或工具调用）
+//! - 输出速度：输出与推理 token 数 / (完成时间 − 首个输出 part 时间)
+//!
+//! 消息完成时由 [`UsageTracker`](super::UsageTracker) 生成样本并持久化。
+
+use crate::usage::types::{MetricStats, PerformanceSample, PerformanceStats};
+use std::collections::{HashMap, VecDeque};
+
+/// 记住的未完成消息数量上限（中途中断的消息不会收到完成事件）
+const MAX_PENDING_MESSAGES: usize = 500;
+
+/// 输出时长短于此值时不计算速度（结果不稳定）
+const MIN_STREAM_MS: i64 = 200;
+
+/// 视为模型输出的 part 类型
+pub(super) const OUTPUT_PART_TYPES: &[&str] = &["text", "reasoning", "tool"];
+
+/// 进行中的助手消息
+#[derive(Debug)]
+struct InFlight {
+    created: i64,
+    queue_ms: Option<u64>,
+    first_output: Option<i64>,
+}
+
+/// 按插入顺序淘汰的映射
+#[derive(Debug)]
+struct BoundedMap<V> {
+    values: HashMap<String, V>,
+    order: VecDeque<String>,
+}
+
+impl<V> Default for BoundedMap<V> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+}
+
+impl<V> BoundedMap<V> {
+    fn insert(&mut self, key: &str, value: V) {
+        if self.values.insert(key.to_string(), value).is_none() {
+            self.order.push_back(key.to_string());
+        }
+        while self.order.len() > MAX_PENDING_MESSAGES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.values.remove(&oldest);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &str) -> Option<V> {
+        let value = self.values.remove(key)?;
+        self.order.retain(|k| k != key);
+        Some(value)
+    }
+}
+
+/// 未完成消息的时间点
+#[derive(Debug, Default)]
+pub(super) struct PerformanceRecorder {
+    /// 用户消息 ID → 创建时间
+    user_messages: BoundedMap<i64>,
+    /// 助手消息 ID → 时间点
+    in_flight: BoundedMap<InFlight>,
+}
+
+impl PerformanceRecorder {
+    pub(super) fn user_message(&mut self, id: &str, created: i64) {
+        if !self.user_messages.values.contains_key(id) {
+            self.user_messages.insert(id, created);
+        }
+    }
+
+    pub(super) fn assistant_message(&mut self, id: &str, parent_id: Option<&str>, created: i64) {
+        if self.in_flight.values.contains_key(id) {
+            return;
+        }
+        let queue_ms = parent_id
+            .and_then(|parent| self.user_messages.remove(parent))
+            .and_then(|sent| u64::try_from(created - sent).ok());
+        self.in_flight.insert(
+            id,
+            InFlight {
+                created,
+                queue_ms,
+                first_output: None,
+            },
+        );
+    }
+
+    /// 助手消息收到输出 part
+    pub(super) fn output(&mut self, message_id: &str, at: i64) {
+        if let Some(message) = self.in_flight.values.get_mut(message_id) {
+            message.first_output.get_or_insert(at);
+        }
+    }
+
+    /// 消息完成，返回样本（消息开始时间未知时为空）
+    pub(super) fn finish(
+        &mut self,
+        id: &str,
+        provider_id: &str,
+        model_id: &str,
+        completed: i64,
+        output_tokens: u64,
+    ) -> Option<PerformanceSample> {
+        let message = self.in_flight.remove(id)?;
+        let first_token_ms = message
+            .first_output
+            .and_then(|first| u64::try_from(first - message.created).ok());
+        let tokens_per_second = message
+            .first_output
+            .map(|first| completed - first)
+            .filter(|&streaming| streaming >= MIN_STREAM_MS && output_tokens > 0)
+            .map(|streaming| output_tokens as f64 * 1000.0 / streaming as f64);
+        Some(PerformanceSample {
+            provider_id: provider_id.to_string(),
+            model_id: model_id.to_string(),
+            completed_at: completed,
+            queue_ms: message.queue_ms,
+            first_token_ms,
+            tokens_per_second,
+        })
+    }
+}
+
+/// 汇总样本
+pub(super) fn stats<'a>(samples: impl Iterator<Item = &'a PerformanceSample>) -> PerformanceStats {
+    let mut requests = 0;
+    let (mut queue, mut first_token, mut speed) = (Vec::new(), Vec::new(), Vec::new());
+    for sample in samples {
+        requests += 1;
+        queue.extend(sample.queue_ms.map(|v| v as f64));
+        first_token.extend(sample.first_token_ms.map(|v| v as f64));
+        speed.extend(sample.tokens_per_second);
+    }
+    PerformanceStats {
+        requests,
+        queue_ms: metric_stats(queue),
+        first_token_ms: metric_stats(first_token),
+        tokens_per_second: metric_stats(speed),
+    }
+}
+
+fn metric_stats(mut values: Vec<f64>) -> Option<MetricStats> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    // 最近秩法
+    let percentile = |p: f64| values[((p * values.len() as f64).ceil() as usize).max(1) - 1];
+    Some(MetricStats {
+        count: values.len() as u64,
+        mean: values.iter().sum::<f64>() / values.len() as f64,
+        p50: percentile(0.5),
+        p90: percentile(0.9),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_message_timings() {
+        let mut recorder = PerformanceRecorder::default();
+        recorder.user_message("msg_user", 1_000);
+        recorder.assistant_message("msg_1", Some("msg_user"), 1_300);
+        recorder.output("msg_1", 2_100);
+        recorder.output("msg_1", 2_500);
+        let sample = recorder
+            .finish("msg_1", "anthropic", "claude", 4_100, 100)
+            .unwrap();
+        assert_eq!(sample.queue_ms, Some(300));
+        assert_eq!(sample.first_token_ms, Some(800));
+        assert_eq!(sample.tokens_per_second, Some(50.0));
+
+        // 开始时间未知的消息不产生样本
+        assert!(recorder
+            .finish("msg_2", "anthropic", "claude", 5_000, 10)
+            .is_none());
+    }
+
+    #[test]
+    fn computes_percentiles() {
+        let samples: Vec<PerformanceSample> = (1..=10)
+            .map(|i| PerformanceSample {
+                provider_id: "openai".to_string(),
+                model_id: "gpt".to_string(),
+                completed_at: i,
+                queue_ms: None,
+                first_token_ms: Some(i as u64 * 100),
+                tokens_per_second: None,
+            })
+            .collect();
+        let stats = stats(samples.iter());
+        assert_eq!(stats.requests, 10);
+        assert!(stats.queue_ms.is_none());
+        let first_token = stats.first_token_ms.unwrap();
+        assert_eq!(first_token.mean, 550.0);
+        assert_eq!(first_token.p50, 500.0);
+        assert_eq!(first_token.p90, 900.0);
+    }
+}
@@ -0,0 +1,545 @@
+//! 用量统计管理器
+//!
+//! 从插件转发的 `message.updated` 事件中提取助手消息的 token 用量，
+//! 按本地日期和 "provider/model" 聚合后持久化到 usage.json；
+//! 同时记录每次请求的耗时样本（见 [`performance`](super::performance)），持久化到 model_performance.json。
+
+use crate::usage::performance::{self, PerformanceRecorder, OUTPUT_PART_TYPES};
+use crate::usage::types::{
+    DailyPerformance, DailyUsage, ModelPerformance, ModelUsage, PerformanceStore,
+    ProviderPerformance, ProviderUsage, UsageRange, UsageStore, UsageSummary, UsageTotals,
+};
+use crate::utils::paths::get_app_data_dir;
+use chrono::{Days, Local, NaiveDate, TimeZone};
+use parking_lot::{Mutex, RwLock};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// 持久化文件名
+const USAGE_FILE: &str = "usage.json";
+
+/// 性能样本持久化文件名
+const PERFORMANCE_FILE: &str = "model_performance.json";
+
+/// 聚合数据保留天数
+const RETENTION_DAYS: u64 = 365;
+
+/// 性能样本保留天数
+const PERFORMANCE_RETENTION_DAYS: u64 = 90;
+
+/// 性能样本数量上限
+const MAX_PERFORMANCE_SAMPLES: usize = 5000;
+
+/// 记住的已计入消息数量上限
+const MAX_RECORDED_MESSAGES: usize = 5000;
+
+/// 日期格式
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// `message.updated` 事件中的消息信息（仅解析需要的字段）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageInfo {
+    id: String,
+    role: String,
+    #[serde(rename = "parentID")]
+    parent_id: Option<String>,
+    #[serde(rename = "providerID")]
+    provider_id: Option<String>,
+    #[serde(rename = "modelID")]
+    model_id: Option<String>,
+    #[serde(default)]
+    tokens: MessageTokens,
+    #[serde(default)]
+    cost: f64,
+    #[serde(default)]
+    time: MessageTime,
+    /// 请求失败时的错误
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MessageTokens {
+    #[serde(default)]
+    input: u64,
+    #[serde(default)]
+    output: u64,
+    #[serde(default)]
+    reasoning: u64,
+    #[serde(default)]
+    cache: CacheTokens,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CacheTokens {
+    #[serde(default)]
+    read: u64,
+    #[serde(default)]
+    write: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MessageTime {
+    /// 创建时间（毫秒时间戳）
+    created: Option<i64>,
+    /// 完成时间（毫秒时间戳），流式输出期间为空
+    completed: Option<i64>,
+}
+
+/// `message.part.updated` 事件中的 part（仅解析需要的字段）
+#[derive(Debug, Deserialize)]
+struct MessagePart {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(rename = "messageID")]
+    message_id: String,
+    #[serde(default)]
+    time: PartTime,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PartTime {
+    start: Option<i64>,
+}
+
+/// 已计入的消息 ID（`message.updated` 在流式输出期间会多次发送）
+#[derive(Debug, Default)]
+struct RecordedMessages {
+    ids: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl RecordedMessages {
+    /// 标记为已计入，之前已计入时返回 false
+    fn insert(&mut self, id: &str) -> bool {
+        if !self.ids.insert(id.to_string()) {
+            return false;
+        }
+        self.order.push_back(id.to_string());
+        if self.order.len() > MAX_RECORDED_MESSAGES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// 用量统计管理器
+#[derive(Debug)]
+pub struct UsageTracker {
+    store: RwLock<UsageStore>,
+    recorded: Mutex<RecordedMessages>,
+    performance: RwLock<PerformanceStore>,
+    recorder: Mutex<PerformanceRecorder>,
+}
+
+impl UsageTracker {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            store: RwLock::new(UsageStore::default()),
+            recorded: Mutex::new(RecordedMessages::default()),
+            performance: RwLock::new(PerformanceStore::default()),
+            recorder: Mutex::new(PerformanceRecorder::default()),
+        })
+    }
+
+    fn get_store_path(file_name: &str) -> Option<PathBuf> {
+        get_app_data_dir().map(|p| p.join(file_name))
+    }
+
+    /// 初始化：从磁盘加载聚合数据和性能样本（应用数据目录初始化后调用）
+    pub fn initialize(&self) {
+        if let Some(mut store) = Self::load::<UsageStore>(USAGE_FILE, "用量数据") {
+            prune(&mut store);
+            info!("已加载 {} 天的用量数据", store.days.len());
+            *self.store.write() = store;
+        }
+        if let Some(mut performance) = Self::load::<PerformanceStore>(PERFORMANCE_FILE, "性能样本")
+        {
+            prune_performance(&mut performance);
+            info!("已加载 {} 个性能样本", performance.samples.len());
+            *self.performance.write() = performance;
+        }
+    }
+
+    fn load<T: DeserializeOwned>(file_name: &str, label: &str) -> Option<T> {
+        let path = Self::get_store_path(file_name)?;
+        if !path.exists() {
+            debug!("{}文件不存在，使用空数据", label);
+            return None;
+        }
+
+        let loaded = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str::<T>(&content).map_err(|e| e.to_string()));
+        match loaded {
+            Ok(store) => Some(store),
+            Err(e) => {
+                warn!("加载{}失败: {}", label, e);
+                None
+            }
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let path = Self::get_store_path(USAGE_FILE).ok_or("应用数据目录未初始化")?;
+        let content = serde_json::to_string_pretty(&*self.store.read())
+            .map_err(|e| format!("序列化用量数据失败: {}", e))?;
+        std::fs::write(&path, content).map_err(|e| format!("写入用量数据失败: {}", e))
+    }
+
+    fn save_performance(&self) -> Result<(), String> {
+        let path = Self::get_store_path(PERFORMANCE_FILE).ok_or("应用数据目录未初始化")?;
+        // 样本较多，不做格式化
+        let content = serde_json::to_string(&*self.performance.read())
+            .map_err(|e| format!("序列化性能样本失败: {}", e))?;
+        std::fs::write(&path, content).map_err(|e| format!("写入性能样本失败: {}", e))
+    }
+
+    /// 处理插件事件：已完成的助手消息计入用量，消息和输出 part 的时间点用于性能统计
+    pub fn record_event(&self, event_type: &str, properties: Option<&serde_json::Value>) {
+        match event_type {
+            "message.updated" => {
+                let Some(info) = properties.and_then(|p| p.get("info")) else {
+                    return;
+                };
+                if let Ok(message) = serde_json::from_value::<MessageInfo>(info.clone()) {
+                    self.record_message(message);
+                }
+            }
+            "message.part.updated" => {
+                let Some(part) = properties.and_then(|p| p.get("part")) else {
+                    return;
+                };
+                let Ok(part) = serde_json::from_value::<MessagePart>(part.clone()) else {
+                    return;
+                };
+                if OUTPUT_PART_TYPES.contains(&part.kind.as_str()) {
+                    let at = part
+                        .time
+                        .start
+                        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+                    self.recorder.lock().output(&part.message_id, at);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn record_message(&self, message: MessageInfo) {
+        if message.role == "user" {
+            if let Some(created) = message.time.created {
+                self.recorder.lock().user_message(&message.id, created);
+            }
+            return;
+        }
+        if message.role != "assistant" {
+            return;
+        }
+        if let Some(created) = message.time.created {
+            self.recorder.lock().assistant_message(
+                &message.id,
+                message.parent_id.as_deref(),
+                created,
+            );
+        }
+        let (Some(provider_id), Some(model_id), Some(completed)) = (
+            message.provider_id.as_deref(),
+            message.model_id.as_deref(),
+            message.time.completed,
+        ) else {
+            return;
+        };
+        if !self.recorded.lock().insert(&message.id) {
+            return;
+        }
+        self.record_performance(&message, provider_id, model_id, completed);
+
+        let date = local_date(completed);
+        let usage = UsageTotals {
+            requests: 1,
+            input_tokens: message.tokens.input,
+            output_tokens: message.tokens.output,
+            reasoning_tokens: message.tokens.reasoning,
+            cache_read_tokens: message.tokens.cache.read,
+            cache_write_tokens: message.tokens.cache.write,
+            cost: message.cost,
+        };
+        debug!(
+            "记录用量: {}/{} {} tokens",
+            provider_id,
+            model_id,
+            usage.total_tokens()
+        );
+
+        {
+            let mut store = self.store.write();
+            let is_new_day = !store.days.contains_key(&date);
+            store
+                .days
+                .entry(date)
+                .or_default()
+                .entry(format!("{}/{}", provider_id, model_id))
+                .or_default()
+                .add(&usage);
+            if is_new_day {
+                prune(&mut store);
+            }
+        }
+
+        if let Err(e) = self.save() {
+            warn!("保存用量数据失败: {}", e);
+        }
+    }
+
+    /// 生成完成消息的性能样本（失败的请求不计入）
+    fn record_performance(
+        &self,
+        message: &MessageInfo,
+        provider_id: &str,
+        model_id: &str,
+        completed: i64,
+    ) {
+        let sample = self.recorder.lock().finish(
+            &message.id,
+            provider_id,
+            model_id,
+            completed,
+            message.tokens.output + message.tokens.reasoning,
+        );
+        let Some(sample) = sample.filter(|_| message.error.is_none()) else {
+            return;
+        };
+        debug!(
+            "记录性能: {}/{} 首 token {:?} ms",
+            provider_id, model_id, sample.first_token_ms
+        );
+
+        {
+            let mut performance = self.performance.write();
+            performance.samples.push_back(sample);
+            prune_performance(&mut performance);
+        }
+
+        if let Err(e) = self.save_performance() {
+            warn!("保存性能样本失败: {}", e);
+        }
+    }
+
+    /// 汇总指定范围内模型的性能
+    ///
+    /// `model_id` 为 "provider/model" 时只统计该服务商，否则统计提供该模型的所有服务商
+    pub fn performance(
+        &self,
+        model_id: &str,
+        range: &UsageRange,
+    ) -> Result<ModelPerformance, String> {
+        let (start, end) = resolve_range(range)?;
+        let store = self.performance.read();
+
+        let mut by_date: BTreeMap<String, Vec<_>> = BTreeMap::new();
+        for sample in &store.samples {
+            let matches = sample.model_id == model_id
+                || model_id
+                    .split_once('/')
+                    .is_some_and(|(p, m)| sample.provider_id == p && sample.model_id == m);
+            if !matches {
+                continue;
+            }
+            let date = local_date(sample.completed_at);
+            if start.as_ref().is_some_and(|s| date < *s) || end.as_ref().is_some_and(|e| date > *e)
+            {
+                continue;
+            }
+            by_date.entry(date).or_default().push(sample);
+        }
+
+        let samples = || by_date.values().flatten().copied();
+        let mut by_provider: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+        for sample in samples() {
+            by_provider
+                .entry(sample.provider_id.as_str())
+                .or_default()
+                .push(sample);
+        }
+        let mut providers: Vec<ProviderPerformance> = by_provider
+            .into_iter()
+            .map(|(provider_id, samples)| ProviderPerformance {
+                provider_id: provider_id.to_string(),
+                stats: performance::stats(samples.into_iter()),
+            })
+            .collect();
+        providers.sort_by_key(|p| std::cmp::Reverse(p.stats.requests));
+
+        let daily = by_date
+            .iter()
+            .map(|(date, samples)| DailyPerformance {
+                date: date.clone(),
+                stats: performance::stats(samples.iter().copied()),
+            })
+            .collect();
+
+        // 全部范围时以实际数据的首尾日期作为范围
+        let start = start.or_else(|| by_date.keys().next().cloned());
+        let end = end.or_else(|| by_date.keys().next_back().cloned());
+
+        Ok(ModelPerformance {
+            model_id: model_id.to_string(),
+            start,
+            end,
+            stats: performance::stats(samples()),
+            providers,
+            daily,
+        })
+    }
+
+    /// 汇总指定范围内的用量
+    pub fn summary(&self, range: &UsageRange) -> Result<UsageSummary, String> {
+        let (start, end) = resolve_range(range)?;
+        let store = self.store.read();
+
+        let days: Vec<(&String, &BTreeMap<String, UsageTotals>)> = store
+            .days
+            .iter()
+            .filter(|(date, _)| {
+                start.as_ref().is_none_or(|s| *date >= s) && end.as_ref().is_none_or(|e| *date <= e)
+            })
+            .collect();
+
+        let mut totals = UsageTotals::default();
+        let mut daily = Vec::with_capacity(days.len());
+        let mut by_provider: BTreeMap<&str, BTreeMap<&str, UsageTotals>> = BTreeMap::new();
+
+        for (date, models) in &days {
+            let mut day_totals = UsageTotals::default();
+            for (key, usage) in models.iter() {
+                day_totals.add(usage);
+                let (provider_id, model_id) = key.split_once('/').unwrap_or((key.as_str(), ""));
+                by_provider
+                    .entry(provider_id)
+                    .or_default()
+                    .entry(model_id)
+                    .or_default()
+                    .add(usage);
+            }
+            totals.add(&day_totals);
+            daily.push(DailyUsage {
+                date: date.to_string(),
+                totals: day_totals,
+            });
+        }
+
+        let mut providers: Vec<ProviderUsage> = by_provider
+            .into_iter()
+            .map(|(provider_id, models)| {
+                let mut provider_totals = UsageTotals::default();
+                let mut models: Vec<ModelUsage> = models
+                    .into_iter()
+                    .map(|(model_id, usage)| {
+                        provider_totals.add(&usage);
+                        ModelUsage {
+                            model_id: model_id.to_string(),
+                            totals: usage,
+                        }
+                    })
+                    .collect();
+                models.sort_by_key(|m| std::cmp::Reverse(m.totals.total_tokens()));
+                ProviderUsage {
+                    provider_id: provider_id.to_string(),
+                    totals: provider_totals,
+                    models,
+                }
+            })
+            .collect();
+        providers.sort_by_key(|p| std::cmp::Reverse(p.totals.total_tokens()));
+
+        // 全部范围时以实际数据的首尾日期作为范围
+        let start = start.or_else(|| days.first().map(|(date, _)| date.to_string()));
+        let end = end.or_else(|| days.last().map(|(date, _)| date.to_string()));
+
+        Ok(UsageSummary {
+            start,
+            end,
+            totals,
+            providers,
+            daily,
+        })
+    }
+}
+
+/// 解析查询范围为（起始日期，结束日期），None 表示不限
+fn resolve_range(range: &UsageRange) -> Result<(Option<String>, Option<String>), String> {
+    let today = Local::now().date_naive();
+    let days_before = |days: u64| {
+        today
+            .checked_sub_days(Days::new(days))
+            .unwrap_or(today)
+            .format(DATE_FORMAT)
+            .to_string()
+    };
+    let today_str = today.format(DATE_FORMAT).to_string();
+
+    match range {
+        UsageRange::Today => Ok((Some(today_str.clone()), Some(today_str))),
+        UsageRange::Last7Days => Ok((Some(days_before(6)), Some(today_str))),
+        UsageRange::Last30Days => Ok((Some(days_before(29)), Some(today_str))),
+        UsageRange::All => Ok((None, None)),
+        UsageRange::Custom { start, end } => {
+            let parse = |value: &str| {
+                NaiveDate::parse_from_str(value, DATE_FORMAT)
+                    .map_err(|_| format!("无效的日期: {}（应为 YYYY-MM-DD）", value))
+            };
+            let (start, end) = (parse(start)?, parse(end)?);
+            if start > end {
+                return Err("起始日期不能晚于结束日期".to_string());
+            }
+            Ok((
+                Some(start.format(DATE_FORMAT).to_string()),
+                Some(end.format(DATE_FORMAT).to_string()),
+            ))
+        }
+    }
+}
+
+/// 删除超过保留期的数据
+fn prune(store: &mut UsageStore) {
+    let Some(cutoff) = Local::now()
+        .date_naive()
+        .checked_sub_days(Days::new(RETENTION_DAYS))
+    else {
+        return;
+    };
+    let cutoff = cutoff.format(DATE_FORMAT).to_string();
+    store.days.retain(|date, _| *date >= cutoff);
+}
+
+/// 删除超过保留期或超出数量上限的性能样本
+fn prune_performance(store: &mut PerformanceStore) {
+    let cutoff = Local::now()
+        .checked_sub_days(Days::new(PERFORMANCE_RETENTION_DAYS))
+        .map(|cutoff| cutoff.timestamp_millis());
+    while store.samples.len() > MAX_PERFORMANCE_SAMPLES
+        || store
+            .samples
+            .front()
+            .zip(cutoff)
+            .is_some_and(|(oldest, cutoff)| oldest.completed_at < cutoff)
+    {
+        store.samples.pop_front();
+    }
+}
+
+/// 毫秒时间戳对应的本地日期
+fn local_date(timestamp_ms: i64) -> String {
+    Local
+        .timestamp_millis_opt(timestamp_ms)
+        .single()
+        .unwrap_or_else(Local::now)
+        .format(DATE_FORMAT)
+        .to_string()
+}
@@ -0,0 +1,179 @@
+//! 用量统计类型定义
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+/// 用量累计值
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageTotals {
+    /// 请求次数（已完成的助手消息数）
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub reasoning_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    /// 费用（美元，由 OpenCode 按模型价格计算）
+    pub cost: f64,
+}
+
+impl UsageTotals {
+    pub fn add(&mut self, other: &UsageTotals) {
+        self.requests += other.requests;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.reasoning_tokens += other.reasoning_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+        self.cost += other.cost;
+    }
+
+    /// 总 token 数
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens
+            + self.output_tokens
+            + self.reasoning_tokens
+            + self.cache_read_tokens
+            + self.cache_write_tokens
+    }
+}
+
+/// 持久化的每日聚合数据
+///
+/// 日期（本地时区 YYYY-MM-DD） -> "provider/model" -> 累计值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageStore {
+    pub days: BTreeMap<String, BTreeMap<String, UsageTotals>>,
+}
+
+/// 查询范围
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum UsageRange {
+    Today,
+    Last7Days,
+    Last30Days,
+    All,
+    /// 自定义日期范围（YYYY-MM-DD，包含两端）
+    Custom {
+        start: String,
+        end: String,
+    },
+}
+
+/// 模型用量
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelUsage {
+    pub model_id: String,
+    pub totals: UsageTotals,
+}
+
+/// 服务商用量
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderUsage {
+    pub provider_id: String,
+    pub totals: UsageTotals,
+    /// 按总 token 数降序
+    pub models: Vec<ModelUsage>,
+}
+
+/// 单日用量
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyUsage {
+    pub date: String,
+    pub totals: UsageTotals,
+}
+
+/// 用量汇总
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageSummary {
+    /// 范围起始日期（无数据且范围为全部时为空）
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub totals: UsageTotals,
+    /// 按总 token 数降序
+    pub providers: Vec<ProviderUsage>,
+    /// 按日期升序，仅包含有数据的日期
+    pub daily: Vec<DailyUsage>,
+}
+
+/// 一次已完成请求的性能样本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceSample {
+    pub provider_id: String,
+    pub model_id: String,
+    /// 完成时间（Unix 毫秒）
+    pub completed_at: i64,
+    /// 排队耗时：用户消息创建 → 助手消息创建
+    pub queue_ms: Option<u64>,
+    /// 首 token 延迟：助手消息创建 → 第一个输出 part
+    pub first_token_ms: Option<u64>,
+    /// 输出速度（输出与推理 token / 秒）
+    pub tokens_per_second: Option<f64>,
+}
+
+/// 持久化的性能样本（按完成时间升序）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PerformanceStore {
+    pub samples: VecDeque<PerformanceSample>,
+}
+
+/// 单项指标的统计
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricStats {
+    /// 有该指标的样本数
+    pub count: u64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+}
+
+/// 性能统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceStats {
+    pub requests: u64,
+    pub queue_ms: Option<MetricStats>,
+    pub first_token_ms: Option<MetricStats>,
+    pub tokens_per_second: Option<MetricStats>,
+}
+
+/// 服务商的性能
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderPerformance {
+    pub provider_id: String,
+    pub stats: PerformanceStats,
+}
+
+/// 单日性能
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyPerformance {
+    pub date: String,
+    pub stats: PerformanceStats,
+}
+
+/// 模型性能汇总
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPerformance {
+    /// 查询的模型（"provider/model" 或不带服务商的模型 ID）
+    pub model_id: String,
+    /// 范围起始日期（无数据且范围为全部时为空）
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// 所有服务商合计
+    pub stats: PerformanceStats,
+    /// 按请求数降序
+    pub providers: Vec<ProviderPerformance>,
+    /// 按日期升序，仅包含有数据的日期
+    pub daily: Vec<DailyPerformance>,
+}
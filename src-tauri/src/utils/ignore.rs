@@ -0,0 +1,188 @@
+//! 项目忽略规则
+//!
+//! 搜索替换、文件索引（语义索引使用它的文件列表）和项目上下文构建共用同一套规则：
+//! 1. 内置的依赖 / 构建目录（[`IGNORED_DIRS`]）
+//! 2. 项目根目录的 `.gitignore`
+//! 3. 项目根目录的 `.axonignore`（只影响 Axon，不影响 Git）
+//!
+//! 语法为 .gitignore 的常用子集：`#` 注释、`!` 取反、结尾 `/` 只匹配目录、
+//! 含 `/` 的模式相对项目根目录，否则匹配任意层级；靠后的规则优先。
+//! 与 Git 相同，目录被忽略后其中的文件无法再用 `!` 恢复。
+//! 只读取根目录下的忽略文件，子目录中的 .gitignore 不生效。
+
+use globset::{GlobBuilder, GlobMatcher};
+use std::path::Path;
+
+pub const GITIGNORE_FILE: &str = ".gitignore";
+pub const AXONIGNORE_FILE: &str = ".axonignore";
+
+/// 始终跳过的目录
+pub const IGNORED_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    "out",
+    ".next",
+    ".nuxt",
+    ".venv",
+    "venv",
+    "__pycache__",
+    ".idea",
+    ".vscode",
+    "coverage",
+    "vendor",
+];
+
+#[derive(Debug, Clone)]
+struct Rule {
+    glob: GlobMatcher,
+    negated: bool,
+    dir_only: bool,
+}
+
+/// 忽略规则匹配器
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreMatcher {
+    rules: Vec<Rule>,
+}
+
+impl IgnoreMatcher {
+    /// 内置规则加上项目根目录的 .gitignore 和 .axonignore（文件不存在或规则无效时跳过）
+    pub fn for_project(root: &Path) -> Self {
+        let mut matcher = Self::builtin();
+        for name in [GITIGNORE_FILE, AXONIGNORE_FILE] {
+            if let Ok(text) = std::fs::read_to_string(root.join(name)) {
+                matcher
+                    .rules
+                    .extend(parse_rules(&text).into_iter().filter_map(Result::ok));
+            }
+        }
+        matcher
+    }
+
+    /// 只包含内置目录规则
+    pub fn builtin() -> Self {
+        let rules = IGNORED_DIRS
+            .iter()
+            .filter_map(|dir| compile_rule(&format!("{}/", dir)).ok().flatten())
+            .collect();
+        Self { rules }
+    }
+
+    /// 路径（相对项目根目录，`/` 分隔）是否被忽略，任一上级目录被忽略时同样视为忽略
+    pub fn is_ignored(&self, relative: &str, is_dir: bool) -> bool {
+        let relative = relative.trim_matches('/');
+        if relative.is_empty() {
+            return false;
+        }
+        let ancestor_ignored = relative
+            .match_indices('/')
+            .any(|(end, _)| self.matches(&relative[..end], true));
+        ancestor_ignored || self.matches(relative, is_dir)
+    }
+
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            if rule.glob.is_match(relative) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+}
+
+/// 检查忽略文件内容，返回无效的模式及原因
+pub fn invalid_patterns(text: &str) -> Vec<String> {
+    parse_rules(text)
+        .into_iter()
+        .filter_map(Result::err)
+        .collect()
+}
+
+fn parse_rules(text: &str) -> Vec<Result<Rule, String>> {
+    text.lines()
+        .filter_map(|line| compile_rule(line).transpose())
+        .collect()
+}
+
+/// 编译一行规则，空行和注释返回 None
+fn compile_rule(line: &str) -> Result<Option<Rule>, String> {
+    let mut pattern = line.trim_end();
+    if pattern.is_empty() || pattern.starts_with('#') {
+        return Ok(None);
+    }
+    let negated = pattern.starts_with('!');
+    // `!` 表示取反，`\#`、`\!` 转义为普通字符
+    if negated || pattern.starts_with("\\#") || pattern.starts_with("\\!") {
+        pattern = &pattern[1..];
+    }
+    let dir_only = pattern.ends_with('/');
+    let pattern = pattern.trim_end_matches('/');
+    if pattern.is_empty() {
+        return Ok(None);
+    }
+
+    // 含 `/` 的模式相对根目录，否则匹配任意层级
+    let glob = if pattern.contains('/') {
+        pattern.trim_start_matches('/').to_string()
+    } else {
+        format!("**/{}", pattern)
+    };
+    let glob = GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .backslash_escape(true)
+        .build()
+        .map_err(|e| format!("{}: {}", line.trim(), e))?
+        .compile_matcher();
+    Ok(Some(Rule {
+        glob,
+        negated,
+        dir_only,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(text: &str) -> IgnoreMatcher {
+        let mut matcher = IgnoreMatcher::builtin();
+        matcher
+            .rules
+            .extend(parse_rules(text).into_iter().map(Result::unwrap));
+        matcher
+    }
+
+    #[test]
+    fn matches_gitignore_style_patterns() {
+        let matcher = matcher("# 生成文件\n*.log\n/docs/api/\nfixtures\n!keep.log\n");
+        assert!(matcher.is_ignored("node_modules", true));
+        assert!(matcher.is_ignored("web/node_modules/react/index.js", false));
+        assert!(matcher.is_ignored("logs/server.log", false));
+        assert!(!matcher.is_ignored("logs/keep.log", false));
+        assert!(matcher.is_ignored("docs/api/index.md", false));
+        assert!(!matcher.is_ignored("src/docs/api/index.md", false));
+        assert!(matcher.is_ignored("tests/fixtures/a.json", false));
+        assert!(!matcher.is_ignored("src/main.rs", false));
+    }
+
+    #[test]
+    fn dir_only_rules_skip_files() {
+        let matcher = matcher("cache/\n");
+        assert!(matcher.is_ignored("cache", true));
+        assert!(matcher.is_ignored("cache/data.bin", false));
+        assert!(!matcher.is_ignored("cache", false));
+    }
+
+    #[test]
+    fn reports_invalid_patterns() {
+        assert!(invalid_patterns("*.log\nsrc/\n").is_empty());
+        assert_eq!(invalid_patterns("[unclosed\n").len(), 1);
+    }
+}
@@ -0,0 +1,71 @@
+//! JSON 文件读写
+//!
+//! 书签、片段、记忆等小型数据各自保存为 JSON 文件，统一按以下方式读写：
+//! - 读取：文件不存在时返回 `None`；内容无法解析时记录警告并同样视为不存在，
+//!   避免单个损坏的文件让整个功能不可用
+//! - 写入：创建所在目录，先写 `.json.tmp` 再重命名，写入中途失败不会留下不完整的文件
+
+use crate::error::AxonError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+use tracing::warn;
+
+/// 读取 JSON 文件，`label` 用于错误和日志（如 `书签文件`）
+pub fn load<T: DeserializeOwned>(path: &Path, label: &str) -> Result<Option<T>, AxonError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(AxonError::io(format!("读取{}失败", label), &e)),
+    };
+    match serde_json::from_str(&content) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => {
+            warn!("{}无法解析，视为空: {:?}, 错误: {}", label, path, e);
+            Ok(None)
+        }
+    }
+}
+
+/// 格式化后原子写入
+pub fn save<T: Serialize>(path: &Path, value: &T, label: &str) -> Result<(), AxonError> {
+    let content =
+        serde_json::to_vec_pretty(value).map_err(|e| format!("序列化{}失败: {}", label, e))?;
+    write_atomic(path, &content, label)
+}
+
+/// 不格式化直接原子写入（用于体积较大的数据）
+pub fn save_compact<T: Serialize>(path: &Path, value: &T, label: &str) -> Result<(), AxonError> {
+    let content = serde_json::to_vec(value).map_err(|e| format!("序列化{}失败: {}", label, e))?;
+    write_atomic(path, &content, label)
+}
+
+fn write_atomic(path: &Path, content: &[u8], label: &str) -> Result<(), AxonError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AxonError::io(format!("创建{}所在目录失败", label), &e))?;
+    }
+    let temp = path.with_extension("json.tmp");
+    let write = |e: std::io::Error| AxonError::io(format!("写入{}失败", label), &e);
+    std::fs::write(&temp, content).map_err(write)?;
+    std::fs::rename(&temp, path).map_err(write)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_tolerates_corrupt_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("data.json");
+
+        assert_eq!(load::<Vec<u32>>(&path, "测试文件").unwrap(), None);
+        save(&path, &vec![1, 2, 3], "测试文件").unwrap();
+        assert_eq!(load(&path, "测试文件").unwrap(), Some(vec![1, 2, 3]));
+        assert!(!path.with_extension("json.tmp").exists());
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert_eq!(load::<Vec<u32>>(&path, "测试文件").unwrap(), None);
+    }
+}
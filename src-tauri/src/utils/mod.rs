@@ -1,4 +1,12 @@
 //! Utility functions and helpers
 
+pub mod ignore;
+pub mod json_store;
+pub mod path_sandbox;
 pub mod paths;
 pub mod plugin_installer;
+pub mod redact;
+pub mod rotating_file;
+pub mod text_encoding;
+pub mod tokens;
+pub mod window_bounds;
@@ -0,0 +1,201 @@
+//! 文件系统路径沙箱
+//!
+//! 文件系统命令只允许访问以下根目录内的路径：
+//! - 当前项目目录
+//! - 应用数据目录
+//! - 用户显式授权的目录（设置中的 `pathSandbox.allowedPaths`）
+//!
+//! 路径逐级解析：已存在的部分先解析符号链接，`..` 再作用于解析后的真实路径，
+//! 避免通过 `链接/..` 或符号链接逃逸出允许的根目录。命令应使用
+//! [`PathSandbox::check`] 返回的路径，而不是原始参数。
+
+use crate::error::{AxonError, ErrorKind};
+use crate::settings::SettingsManager;
+use crate::utils::paths::get_app_data_dir;
+use std::path::{Component, Path, PathBuf};
+
+/// 路径沙箱
+#[derive(Debug, Clone)]
+pub struct PathSandbox {
+    enabled: bool,
+    roots: Vec<PathBuf>,
+}
+
+impl PathSandbox {
+    /// 根据当前设置构建沙箱
+    pub fn from_settings(settings: &SettingsManager) -> Self {
+        let config = settings.get_path_sandbox();
+        let roots = settings
+            .get_project_directory()
+            .map(PathBuf::from)
+            .into_iter()
+            .chain(get_app_data_dir())
+            .chain(config.allowed_paths.iter().map(PathBuf::from))
+            .filter_map(|root| resolve(&root).ok())
+            .collect();
+
+        Self {
+            enabled: config.enabled,
+            roots,
+        }
+    }
+
+    /// 允许访问的根目录（已规整）
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// 检查路径是否允许访问，返回命令应使用的路径
+    ///
+    /// 返回的路径中父目录已解析为真实路径，最后一级保持原名（不跟随符号链接，
+    /// 删除、重命名等操作作用于链接本身）；沙箱开启时最后一级跟随符号链接后
+    /// 也必须在允许的根目录内。
+    pub fn check(&self, path: impl AsRef<Path>) -> Result<PathBuf, AxonError> {
+        let path = path.as_ref();
+        if !self.enabled {
+            return Ok(path.to_path_buf());
+        }
+
+        let target = resolve_components(path, false)?;
+        let resolved = resolve_components(&target, true)?;
+        if self.roots.iter().any(|root| resolved.starts_with(root)) {
+            return Ok(target);
+        }
+
+        Err(AxonError::localized(
+            ErrorKind::PermissionDenied,
+            "fs.outside_sandbox",
+            serde_json::json!({ "path": path.display().to_string() }),
+        )
+        .with_details(serde_json::json!({
+            "path": path.to_string_lossy(),
+            "resolved": resolved.to_string_lossy(),
+        })))
+    }
+}
+
+/// 规整路径：绝对路径校验，逐级解析符号链接并消除 `.` / `..`
+pub fn resolve(path: &Path) -> Result<PathBuf, AxonError> {
+    resolve_components(path, true)
+}
+
+/// 逐级解析路径，`follow_last` 为 false 时最后一级（非 `.` / `..`）不解析符号链接
+fn resolve_components(path: &Path, follow_last: bool) -> Result<PathBuf, AxonError> {
+    if !path.is_absolute() {
+        return Err(AxonError::invalid_input(format!(
+            "路径必须是绝对路径: {}",
+            path.display()
+        )));
+    }
+
+    let components: Vec<Component> = path.components().collect();
+    let mut resolved = PathBuf::new();
+    for (index, component) in components.iter().enumerate() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => resolved.push(component),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(part) => {
+                resolved.push(part);
+                if !follow_last && index == components.len() - 1 {
+                    break;
+                }
+                // 已存在的部分立即解析，后续的 `..` 作用于真实路径；不存在的部分按原样拼接
+                if let Ok(canonical) = resolved.canonicalize() {
+                    resolved = strip_verbatim(canonical);
+                }
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// Windows 上去掉 canonicalize 产生的 `\\?\` 前缀，返回给前端的路径保持常规写法
+fn strip_verbatim(path: PathBuf) -> PathBuf {
+    match path.to_str().and_then(|text| text.strip_prefix(r"\\?\")) {
+        Some(stripped) if !stripped.starts_with("UNC") => PathBuf::from(stripped),
+        _ => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// 返回临时目录（需保持存活）和其规范化路径，下含 project/ 与 outside/
+    fn temp_root() -> (TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("project")).unwrap();
+        std::fs::create_dir_all(dir.path().join("outside")).unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        (dir, root)
+    }
+
+    fn sandbox(root: &Path) -> PathSandbox {
+        PathSandbox {
+            enabled: true,
+            roots: vec![root.join("project")],
+        }
+    }
+
+    #[test]
+    fn rejects_relative_paths() {
+        assert!(resolve(Path::new("project/file.txt")).is_err());
+        let (_dir, root) = temp_root();
+        assert!(sandbox(&root).check("project/file.txt").is_err());
+    }
+
+    #[test]
+    fn resolves_missing_paths_under_existing_ancestor() {
+        let (_dir, root) = temp_root();
+        let sandbox = sandbox(&root);
+        let target = root.join("project").join("new").join("..").join("a.txt");
+        assert_eq!(
+            sandbox.check(&target).unwrap(),
+            root.join("project").join("a.txt")
+        );
+        assert!(sandbox
+            .check(
+                root.join("project")
+                    .join("..")
+                    .join("outside")
+                    .join("b.txt")
+            )
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parent_dir_after_symlink_uses_real_path() {
+        let (_dir, root) = temp_root();
+        std::fs::create_dir_all(root.join("outside").join("nested")).unwrap();
+        std::fs::write(root.join("outside").join("secret"), "x").unwrap();
+        std::fs::write(root.join("project").join("secret"), "y").unwrap();
+        std::os::unix::fs::symlink(
+            root.join("outside").join("nested"),
+            root.join("project").join("link"),
+        )
+        .unwrap();
+        let sandbox = sandbox(&root);
+
+        // link/.. 实际指向 outside，不能按词法规整成 project/secret
+        let escape = root.join("project").join("link").join("..").join("secret");
+        assert_eq!(
+            resolve(&escape).unwrap(),
+            root.join("outside").join("secret")
+        );
+        assert!(sandbox.check(&escape).is_err());
+
+        // 指向根目录外的链接本身也不能访问
+        assert!(sandbox.check(root.join("project").join("link")).is_err());
+        assert_eq!(
+            sandbox
+                .check(root.join("project").join(".").join("secret"))
+                .unwrap(),
+            root.join("project").join("secret")
+        );
+    }
+}
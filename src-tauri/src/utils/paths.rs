@@ -45,6 +45,12 @@ pub fn get_bin_dir() -> Option<PathBuf> {
     get_app_data_dir().map(|p| p.join("bin"))
 }
 
+/// 获取附件目录（截图、录音等）
+/// 路径: <app_data_dir>/attachments
+pub fn get_attachments_dir() -> Option<PathBuf> {
+    get_app_data_dir().map(|p| p.join("attachments"))
+}
+
 /// 获取 opencode 二进制文件路径
 /// Windows: <app_data_dir>/bin/opencode.exe
 /// Unix: <app_data_dir>/bin/opencode
@@ -70,6 +76,17 @@ pub fn get_opencode_config_path() -> Option<PathBuf> {
     get_opencode_config_dir().map(|p| p.join("opencode.json"))
 }
 
+/// 规范化项目目录：去掉首尾空白和末尾分隔符，避免同一项目出现两种写法
+pub fn normalize_project(project: &str) -> String {
+    let trimmed = project.trim();
+    let stripped = trimmed.trim_end_matches(['/', '\\']);
+    if stripped.is_empty() {
+        trimmed.to_string()
+    } else {
+        stripped.to_string()
+    }
+}
+
 /// 确保目录存在
 pub fn ensure_dir_exists(path: &Path) -> Result<(), std::io::Error> {
     if !path.exists() {
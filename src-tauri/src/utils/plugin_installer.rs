@@ -1,14 +1,100 @@
 //! 插件安装模块
 //!
-//! 负责将打包的插件从应用资源目录安装到 app_data_dir
+//! 负责将打包的插件从应用资源目录安装到 app_data_dir。
+//! 安装时在插件目录写入清单（版本、来源、SHA-256），从 GitHub 发布更新过的插件
+//! 不会在下次启动时被较旧的打包版本覆盖。
 
 use crate::utils::paths::{ensure_dir_exists, get_axon_bridge_plugin_dir, get_axon_bridge_plugin_path};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::path::PathBuf;
 use tauri::{AppHandle, Manager};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 const BUNDLED_PLUGIN_PATH: &str = "plugins/opencode/index.js";
 
+/// 插件目录中的安装清单
+const MANIFEST_FILE: &str = "axon-bridge.json";
+
+/// 打包插件的 package.json（用于获取打包版本）
+const BUNDLED_PACKAGE_JSON: &str = include_str!("../../../plugins/opencode/package.json");
+
+/// 已安装插件的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginSource {
+    /// 随应用打包
+    Bundled,
+    /// 从 GitHub 发布下载
+    Release,
+}
+
+/// 已安装插件信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledPlugin {
+    pub version: String,
+    pub source: PluginSource,
+    pub sha256: String,
+    pub installed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 打包插件的版本
+pub fn bundled_plugin_version() -> String {
+    serde_json::from_str::<serde_json::Value>(BUNDLED_PACKAGE_JSON)
+        .ok()
+        .and_then(|pkg| pkg.get("version")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| "0.0.0".to_string())
+}
+
+/// 读取已安装插件的清单（旧版本安装的插件没有清单）
+pub fn read_installed_plugin() -> Option<InstalledPlugin> {
+    let path = get_axon_bridge_plugin_dir()?.join(MANIFEST_FILE);
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 写入插件文件和清单（先写临时文件再替换，避免 OpenCode 读到半个文件）
+pub fn install_plugin(
+    content: &[u8],
+    version: &str,
+    source: PluginSource,
+) -> Result<InstalledPlugin, String> {
+    let target_dir = get_axon_bridge_plugin_dir()
+        .ok_or_else(|| "无法获取插件目标目录".to_string())?;
+    let target_path = get_axon_bridge_plugin_path()
+        .ok_or_else(|| "无法获取插件目标路径".to_string())?;
+    ensure_dir_exists(&target_dir)
+        .map_err(|e| format!("创建插件目录失败: {}", e))?;
+
+    let temp_path = target_path.with_extension("js.tmp");
+    std::fs::write(&temp_path, content)
+        .map_err(|e| format!("写入插件文件失败: {}", e))?;
+    std::fs::rename(&temp_path, &target_path)
+        .map_err(|e| format!("替换插件文件失败: {}", e))?;
+
+    let installed = InstalledPlugin {
+        version: version.to_string(),
+        source,
+        sha256: format!("{:x}", Sha256::digest(content)),
+        installed_at: chrono::Utc::now(),
+    };
+    let manifest = serde_json::to_string_pretty(&installed)
+        .map_err(|e| format!("序列化插件清单失败: {}", e))?;
+    std::fs::write(target_dir.join(MANIFEST_FILE), manifest)
+        .map_err(|e| format!("写入插件清单失败: {}", e))?;
+    Ok(installed)
+}
+
+/// 比较版本号（允许 `v` 前缀），无法解析时视为不更新
+pub fn is_newer_version(candidate: &str, current: &str) -> bool {
+    let parse = |v: &str| semver::Version::parse(v.trim().trim_start_matches('v')).ok();
+    match (parse(candidate), parse(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
 fn get_bundled_plugin_path(handle: &AppHandle) -> Option<PathBuf> {
     handle
         .path()
@@ -31,18 +117,27 @@ pub fn install_bundled_plugins(handle: &AppHandle) -> Result<(), String> {
         return Ok(());
     }
 
-    let target_dir = get_axon_bridge_plugin_dir()
-        .ok_or_else(|| "无法获取插件目标目录".to_string())?;
-    
     let target_path = get_axon_bridge_plugin_path()
         .ok_or_else(|| "无法获取插件目标路径".to_string())?;
 
-    ensure_dir_exists(&target_dir)
-        .map_err(|e| format!("创建插件目录失败: {}", e))?;
+    let bundled_version = bundled_plugin_version();
+    if let Some(installed) = read_installed_plugin() {
+        // 从 GitHub 更新的插件不低于打包版本时保留
+        if installed.source == PluginSource::Release
+            && !is_newer_version(&bundled_version, &installed.version)
+            && target_path.exists()
+        {
+            debug!(
+                "保留已更新的插件 {}（打包版本 {}）",
+                installed.version, bundled_version
+            );
+            return Ok(());
+        }
+    }
 
+    let bundled_content = std::fs::read(&bundled_path)
+        .map_err(|e| format!("读取打包插件失败: {}", e))?;
     let should_install = if target_path.exists() {
-        let bundled_content = std::fs::read(&bundled_path)
-            .map_err(|e| format!("读取打包插件失败: {}", e))?;
         let installed_content = std::fs::read(&target_path)
             .map_err(|e| format!("读取已安装插件失败: {}", e))?;
         bundled_content != installed_content
@@ -52,12 +147,34 @@ pub fn install_bundled_plugins(handle: &AppHandle) -> Result<(), String> {
 
     if should_install {
         info!("安装 Axon Bridge 插件: {:?} -> {:?}", bundled_path, target_path);
-        std::fs::copy(&bundled_path, &target_path)
-            .map_err(|e| format!("复制插件文件失败: {}", e))?;
+        install_plugin(&bundled_content, &bundled_version, PluginSource::Bundled)?;
         info!("Axon Bridge 插件安装完成");
     } else {
+        if read_installed_plugin().is_none() {
+            if let Err(e) = install_plugin(&bundled_content, &bundled_version, PluginSource::Bundled) {
+                warn!("写入插件清单失败: {}", e);
+            }
+        }
         debug!("插件已是最新版本，跳过安装");
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions_with_optional_prefix() {
+        assert!(is_newer_version("v0.2.0", "0.1.9"));
+        assert!(is_newer_version("1.0.0", "1.0.0-beta.1"));
+        assert!(!is_newer_version("0.1.0", "v0.1.0"));
+        assert!(!is_newer_version("latest", "0.1.0"));
+    }
+
+    #[test]
+    fn reads_bundled_version_from_package_json() {
+        assert!(semver::Version::parse(&bundled_plugin_version()).is_ok());
+    }
+}
@@ -0,0 +1,124 @@
+//! 按大小轮转的日志文件
+//!
+//! 当前文件写满后依次改名：`axon.log` -> `axon.log.1` -> ... -> `axon.log.N`，
+//! 编号越大越旧，超出保留数的文件被删除。应用日志和审计日志共用。
+//!
+//! 这里不调用 tracing 宏：应用日志的 writer 会在写入时调用 [`RotatingFile::rotate`]，
+//! 记录日志会递归写入，错误统一返回给调用方处理。
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 带轮转的日志文件
+#[derive(Debug, Clone)]
+pub struct RotatingFile {
+    path: PathBuf,
+    /// 保留的轮转文件数（不含当前文件）
+    max_rotated: usize,
+}
+
+impl RotatingFile {
+    pub fn new(path: PathBuf, max_rotated: usize) -> Self {
+        Self { path, max_rotated }
+    }
+
+    /// 当前文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 第 index 个轮转文件路径（0 为当前文件）
+    pub fn rotated_path(&self, index: usize) -> PathBuf {
+        if index == 0 {
+            return self.path.clone();
+        }
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".{}", index));
+        self.path.with_file_name(name)
+    }
+
+    /// 轮转文件，之后写入会创建新的当前文件
+    ///
+    /// 单个文件失败不影响其余文件的轮转，返回最后一个错误
+    pub fn rotate(&self) -> io::Result<()> {
+        let mut result = Ok(());
+        let oldest = self.rotated_path(self.max_rotated);
+        if oldest.exists() {
+            if let Err(e) = std::fs::remove_file(&oldest) {
+                result = Err(e);
+            }
+        }
+        for index in (0..self.max_rotated).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                if let Err(e) = std::fs::rename(&from, self.rotated_path(index + 1)) {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    /// 从最新的行开始倒序读取，当前文件不足时继续读取轮转文件
+    ///
+    /// `parse` 返回 `None` 的行被跳过；最多返回 `limit` 条，顺序为从新到旧
+    pub fn read_recent<T>(
+        &self,
+        limit: usize,
+        mut parse: impl FnMut(&str) -> Option<T>,
+    ) -> io::Result<Vec<T>> {
+        let mut items = Vec::new();
+        if limit == 0 {
+            return Ok(items);
+        }
+        for index in 0..=self.max_rotated {
+            let content = match std::fs::read(self.rotated_path(index)) {
+                Ok(content) => content,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+
+            for line in String::from_utf8_lossy(&content).lines().rev() {
+                if let Some(item) = parse(line) {
+                    items.push(item);
+                    if items.len() >= limit {
+                        return Ok(items);
+                    }
+                }
+            }
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_and_reads_newest_lines_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = RotatingFile::new(dir.path().join("app.log"), 2);
+        assert_eq!(file.rotated_path(2), dir.path().join("app.log.2"));
+        assert!(file
+            .read_recent(10, |l| Some(l.to_string()))
+            .unwrap()
+            .is_empty());
+
+        for batch in ["1\n2\n", "3\n4\n", "5\n6\n"] {
+            file.rotate().unwrap();
+            std::fs::write(file.path(), batch).unwrap();
+        }
+        // 第四次轮转删除最旧的文件
+        file.rotate().unwrap();
+        std::fs::write(file.path(), "7\n").unwrap();
+        assert!(!file.rotated_path(3).exists());
+
+        let lines = file.read_recent(10, |l| Some(l.to_string())).unwrap();
+        assert_eq!(lines, ["7", "6", "5", "4", "3"]);
+        let odd = file
+            .read_recent(2, |l| l.parse::<u32>().ok().filter(|n| n % 2 == 1))
+            .unwrap();
+        assert_eq!(odd, [7, 5]);
+    }
+}
@@ -0,0 +1,182 @@
+//! 文本编码与换行符工具
+//!
+//! 负责识别文件的编码、BOM 与换行符风格，并在写回时保持原格式
+
+use encoding_rs::{Encoding, GBK, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use serde::Serialize;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// 换行符风格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+    /// 同一文件中混用多种换行符
+    Mixed,
+    /// 文件中没有换行符
+    None,
+}
+
+impl LineEnding {
+    /// 从字符串解析目标换行符（仅支持 lf / crlf / cr）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "lf" | "\n" => Some(Self::Lf),
+            "crlf" | "\r\n" => Some(Self::Crlf),
+            "cr" | "\r" => Some(Self::Cr),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> Option<&'static str> {
+        match self {
+            Self::Lf => Some("\n"),
+            Self::Crlf => Some("\r\n"),
+            Self::Cr => Some("\r"),
+            Self::Mixed | Self::None => None,
+        }
+    }
+}
+
+/// 文本格式（编码 + BOM + 换行符）
+#[derive(Debug, Clone, Copy)]
+pub struct TextFormat {
+    pub encoding: &'static Encoding,
+    pub has_bom: bool,
+    pub line_ending: LineEnding,
+}
+
+impl Default for TextFormat {
+    fn default() -> Self {
+        Self {
+            encoding: UTF_8,
+            has_bom: false,
+            line_ending: LineEnding::None,
+        }
+    }
+}
+
+/// 根据标签查找编码（如 utf-8 / gbk / shift_jis / utf-16le）
+pub fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+}
+
+/// 检测字节内容的编码、BOM 和换行符
+///
+/// 优先识别 BOM；无 BOM 时依次尝试 UTF-8、GBK，最后回退到 Windows-1252
+pub fn detect_format(bytes: &[u8]) -> TextFormat {
+    let (encoding, has_bom) = if bytes.starts_with(UTF8_BOM) {
+        (UTF_8, true)
+    } else if bytes.starts_with(UTF16LE_BOM) {
+        (UTF_16LE, true)
+    } else if bytes.starts_with(UTF16BE_BOM) {
+        (UTF_16BE, true)
+    } else if std::str::from_utf8(bytes).is_ok() {
+        (UTF_8, false)
+    } else if GBK.decode_without_bom_handling_and_without_replacement(bytes).is_some() {
+        (GBK, false)
+    } else {
+        (WINDOWS_1252, false)
+    };
+
+    let (text, _) = encoding.decode_without_bom_handling(strip_bom(bytes, encoding, has_bom));
+    TextFormat {
+        encoding,
+        has_bom,
+        line_ending: detect_line_ending(&text),
+    }
+}
+
+/// 检测文本的换行符风格
+pub fn detect_line_ending(text: &str) -> LineEnding {
+    let bytes = text.as_bytes();
+    let (mut lf, mut crlf, mut cr) = (0usize, 0usize, 0usize);
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => {
+                crlf += 1;
+                i += 1;
+            }
+            b'\r' => cr += 1,
+            b'\n' => lf += 1,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    match (lf > 0, crlf > 0, cr > 0) {
+        (false, false, false) => LineEnding::None,
+        (true, false, false) => LineEnding::Lf,
+        (false, true, false) => LineEnding::Crlf,
+        (false, false, true) => LineEnding::Cr,
+        _ => LineEnding::Mixed,
+    }
+}
+
+/// 将文本的换行符统一为目标风格；`Mixed`/`None` 时原样返回
+pub fn normalize_line_endings(text: &str, target: LineEnding) -> String {
+    let Some(separator) = target.as_str() else {
+        return text.to_string();
+    };
+
+    let unified = text.replace("\r\n", "\n").replace('\r', "\n");
+    if separator == "\n" {
+        unified
+    } else {
+        unified.replace('\n', separator)
+    }
+}
+
+/// 按指定格式解码字节（去除 BOM）
+pub fn decode(bytes: &[u8], format: &TextFormat) -> String {
+    let (text, _) = format
+        .encoding
+        .decode_without_bom_handling(strip_bom(bytes, format.encoding, format.has_bom));
+    text.into_owned()
+}
+
+/// 按指定格式编码文本（根据需要写入 BOM）
+///
+/// 返回编码后的字节，以及是否有字符无法用目标编码表示
+pub fn encode(text: &str, format: &TextFormat) -> (Vec<u8>, bool) {
+    let mut output = Vec::with_capacity(text.len() + 3);
+
+    // encoding_rs 不支持编码为 UTF-16，需要单独处理
+    if format.encoding == UTF_16LE || format.encoding == UTF_16BE {
+        let little_endian = format.encoding == UTF_16LE;
+        if format.has_bom {
+            output.extend_from_slice(if little_endian { UTF16LE_BOM } else { UTF16BE_BOM });
+        }
+        for unit in text.encode_utf16() {
+            let bytes = if little_endian {
+                unit.to_le_bytes()
+            } else {
+                unit.to_be_bytes()
+            };
+            output.extend_from_slice(&bytes);
+        }
+        return (output, false);
+    }
+
+    if format.has_bom && format.encoding == UTF_8 {
+        output.extend_from_slice(UTF8_BOM);
+    }
+    let (bytes, _, had_unmappable) = format.encoding.encode(text);
+    output.extend_from_slice(&bytes);
+    (output, had_unmappable)
+}
+
+fn strip_bom<'a>(bytes: &'a [u8], encoding: &'static Encoding, has_bom: bool) -> &'a [u8] {
+    if !has_bom {
+        return bytes;
+    }
+    let bom_len = if encoding == UTF_8 { UTF8_BOM.len() } else { 2 };
+    bytes.get(bom_len..).unwrap_or_default()
+}
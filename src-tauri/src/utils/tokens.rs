@@ -0,0 +1,142 @@
+//! Token 计数工具
+//!
+//! - OpenAI 系模型使用对应的 tiktoken 编码精确计数
+//! - 其他模型使用 cl100k 编码近似计数
+//! - 编码表加载失败时回退到启发式估算：ASCII 文本约 4 个字符一个 token，
+//!   CJK 等非 ASCII 字符约 1 个字符一个 token
+
+use serde::Serialize;
+use std::sync::OnceLock;
+use tiktoken_rs::CoreBPE;
+use tracing::warn;
+
+/// 计数方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CountMethod {
+    /// 使用模型对应的分词器
+    Exact,
+    /// 使用相近的分词器近似
+    Approximate,
+    /// 启发式估算
+    Estimate,
+}
+
+/// 可用的 tiktoken 编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    O200k,
+    Cl100k,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Self::O200k => "o200k_base",
+            Self::Cl100k => "cl100k_base",
+        }
+    }
+
+    fn load(self) -> Option<CoreBPE> {
+        let result = match self {
+            Self::O200k => tiktoken_rs::o200k_base(),
+            Self::Cl100k => tiktoken_rs::cl100k_base(),
+        };
+        result
+            .map_err(|e| warn!("加载 {} 编码失败: {}", self.name(), e))
+            .ok()
+    }
+
+    /// 获取编码器（首次使用时加载并缓存）
+    fn bpe(self) -> Option<&'static CoreBPE> {
+        static O200K: OnceLock<Option<CoreBPE>> = OnceLock::new();
+        static CL100K: OnceLock<Option<CoreBPE>> = OnceLock::new();
+
+        let cell = match self {
+            Self::O200k => &O200K,
+            Self::Cl100k => &CL100K,
+        };
+        cell.get_or_init(|| self.load()).as_ref()
+    }
+}
+
+/// 根据模型 ID 选择编码，返回（编码，是否为该模型的原生编码）
+fn encoding_for_model(model_id: Option<&str>) -> (Encoding, bool) {
+    let Some(model_id) = model_id else {
+        return (Encoding::Cl100k, false);
+    };
+
+    // 兼容 "provider/model" 格式
+    let model = model_id
+        .rsplit('/')
+        .next()
+        .unwrap_or(model_id)
+        .to_lowercase();
+
+    let o200k_prefixes = [
+        "gpt-4o",
+        "gpt-4.1",
+        "gpt-4.5",
+        "gpt-5",
+        "o1",
+        "o3",
+        "o4",
+        "chatgpt-4o",
+    ];
+    let cl100k_prefixes = ["gpt-4", "gpt-3.5", "text-embedding-3", "text-embedding-ada"];
+
+    if o200k_prefixes.iter().any(|p| model.starts_with(p)) {
+        (Encoding::O200k, true)
+    } else if cl100k_prefixes.iter().any(|p| model.starts_with(p)) {
+        (Encoding::Cl100k, true)
+    } else {
+        (Encoding::Cl100k, false)
+    }
+}
+
+/// Token 计数结果
+#[derive(Debug, Clone)]
+pub struct TokenCountResult {
+    pub tokens: usize,
+    pub method: CountMethod,
+    /// 使用的编码名称（启发式估算时为 None）
+    pub tokenizer: Option<&'static str>,
+}
+
+/// 按模型计数文本 token 数
+pub fn count_tokens(text: &str, model_id: Option<&str>) -> TokenCountResult {
+    let (encoding, native) = encoding_for_model(model_id);
+
+    match encoding.bpe() {
+        Some(bpe) => TokenCountResult {
+            tokens: bpe.encode_with_special_tokens(text).len(),
+            method: if native {
+                CountMethod::Exact
+            } else {
+                CountMethod::Approximate
+            },
+            tokenizer: Some(encoding.name()),
+        },
+        None => TokenCountResult {
+            tokens: estimate_tokens(text),
+            method: CountMethod::Estimate,
+            tokenizer: None,
+        },
+    }
+}
+
+/// 估算文本的 token 数
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut ascii = 0usize;
+    let mut other = 0usize;
+
+    for ch in text.chars() {
+        if ch.is_ascii() {
+            ascii += 1;
+        } else {
+            other += 1;
+        }
+    }
+
+    ascii.div_ceil(4) + other
+}
@@ -0,0 +1,127 @@
+//! 窗口位置校验
+//!
+//! 保存的窗口位置可能落在已断开的显示器上（如笔记本拔掉外接屏），
+//! 恢复前需要确认窗口仍有足够区域在某个显示器内，否则移回主显示器。
+
+use tauri::{PhysicalPosition, PhysicalSize, WebviewWindow};
+use tracing::{info, warn};
+
+/// 窗口与显示器的重叠宽高都至少为该值（物理像素）才算可见
+const MIN_VISIBLE_PIXELS: u32 = 64;
+
+/// 屏幕坐标系中的矩形（物理像素）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    fn right(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+
+    fn bottom(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+
+    /// 与另一个矩形重叠部分的宽高
+    fn overlap(&self, other: &Rect) -> (i64, i64) {
+        let width = self.right().min(other.right()) - (self.x as i64).max(other.x as i64);
+        let height = self.bottom().min(other.bottom()) - (self.y as i64).max(other.y as i64);
+        (width.max(0), height.max(0))
+    }
+
+    fn is_visible_on(&self, monitor: &Rect) -> bool {
+        let (width, height) = self.overlap(monitor);
+        let min = MIN_VISIBLE_PIXELS as i64;
+        width >= min.min(self.width as i64) && height >= min.min(self.height as i64)
+    }
+}
+
+/// 将窗口矩形调整到显示器范围内
+///
+/// `monitors` 的第一个视为主显示器。窗口在任一显示器上可见时原样返回，
+/// 否则缩小到不超过主显示器并居中；没有显示器信息时原样返回。
+pub fn fit_to_monitors(window: Rect, monitors: &[Rect]) -> Rect {
+    if monitors.iter().any(|m| window.is_visible_on(m)) {
+        return window;
+    }
+    let Some(primary) = monitors.first() else {
+        return window;
+    };
+
+    let width = window.width.min(primary.width);
+    let height = window.height.min(primary.height);
+    Rect {
+        x: primary.x + ((primary.width - width) / 2) as i32,
+        y: primary.y + ((primary.height - height) / 2) as i32,
+        width,
+        height,
+    }
+}
+
+/// 当前所有显示器的范围（主显示器在前）
+pub fn monitor_rects(window: &WebviewWindow) -> Vec<Rect> {
+    let to_rect = |m: &tauri::Monitor| Rect {
+        x: m.position().x,
+        y: m.position().y,
+        width: m.size().width,
+        height: m.size().height,
+    };
+
+    let mut rects: Vec<Rect> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(to_rect)
+        .collect();
+    if let Ok(Some(primary)) = window.primary_monitor() {
+        let primary = to_rect(&primary);
+        rects.retain(|r| *r != primary);
+        rects.insert(0, primary);
+    }
+    rects
+}
+
+/// 将窗口移动 / 缩放到指定矩形（会先校验显示器范围），返回实际使用的矩形
+pub fn apply_rect(window: &WebviewWindow, rect: Rect) -> Rect {
+    let fitted = fit_to_monitors(rect, &monitor_rects(window));
+    if fitted != rect {
+        info!(
+            "窗口位置不在任何显示器内，已移回主显示器: {:?} -> {:?}",
+            rect, fitted
+        );
+    }
+    if let Err(e) = window.set_size(PhysicalSize::new(fitted.width, fitted.height)) {
+        warn!("设置窗口大小失败: {}", e);
+    }
+    if let Err(e) = window.set_position(PhysicalPosition::new(fitted.x, fitted.y)) {
+        warn!("设置窗口位置失败: {}", e);
+    }
+    fitted
+}
+
+/// 窗口当前的位置和内部大小
+pub fn current_rect(window: &WebviewWindow) -> Option<Rect> {
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(Rect {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+/// 确保窗口可见（启动时 window-state 插件恢复位置后调用）
+pub fn ensure_visible(window: &WebviewWindow) {
+    let Some(rect) = current_rect(window) else {
+        return;
+    };
+    if fit_to_monitors(rect, &monitor_rects(window)) != rect {
+        apply_rect(window, rect);
+    }
+}
@@ -0,0 +1,232 @@
+//! Webhook 事件通知
+//!
+//! 用户在设置中配置 Webhook（Slack / Discord / 通用 JSON），工作流执行结束、
+//! OpenCode 服务异常退出、发现应用新版本时向订阅了该事件的 Webhook 发送消息。
+//! 发送在后台任务中进行，网络错误、429 和 5xx 响应按指数退避重试。
+
+mod payload;
+
+use crate::error::AxonError;
+use crate::opencode::{ServiceStatus, WebhookConfig, WebhookEventKind, EVENT_SERVICE_STATUS};
+use crate::settings::SettingsManager;
+use parking_lot::Mutex;
+use payload::build_payload;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Listener};
+use tracing::{debug, info, warn};
+
+/// 单次请求超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 最多尝试次数（含首次）
+const MAX_ATTEMPTS: u32 = 4;
+
+/// 首次重试前的等待时间，之后每次翻倍
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+
+/// 待发送的事件
+#[derive(Debug, Clone)]
+pub struct WebhookEvent {
+    pub kind: WebhookEventKind,
+    pub title: String,
+    pub message: String,
+    /// 通用 Webhook 附带的结构化数据
+    pub data: Value,
+    /// Unix 毫秒
+    pub timestamp: i64,
+}
+
+impl WebhookEvent {
+    fn new(kind: WebhookEventKind, title: &str, message: String, data: Value) -> Self {
+        Self {
+            kind,
+            title: title.to_string(),
+            message,
+            data,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn workflow_finished(
+        workflow_id: &str,
+        workflow_name: &str,
+        success: bool,
+        summary: Option<&str>,
+    ) -> Self {
+        let outcome = if success {
+            "执行完成"
+        } else {
+            "执行失败"
+        };
+        let mut message = format!("工作流「{}」{}", workflow_name, outcome);
+        if let Some(summary) = summary.filter(|s| !s.is_empty()) {
+            message.push_str(&format!("：{}", summary));
+        }
+        Self::new(
+            WebhookEventKind::WorkflowFinished,
+            "工作流执行结束",
+            message,
+            json!({
+                "workflowId": workflow_id,
+                "workflowName": workflow_name,
+                "success": success,
+                "summary": summary,
+            }),
+        )
+    }
+
+    pub fn service_crashed(reason: &str) -> Self {
+        Self::new(
+            WebhookEventKind::ServiceCrashed,
+            "OpenCode 服务异常",
+            format!("OpenCode 服务已停止运行：{}", reason),
+            json!({ "reason": reason }),
+        )
+    }
+
+    pub fn update_available(version: &str, notes: Option<&str>) -> Self {
+        Self::new(
+            WebhookEventKind::UpdateAvailable,
+            "发现新版本",
+            format!("Axon {} 可以更新", version),
+            json!({ "version": version, "notes": notes }),
+        )
+    }
+
+    fn test() -> Self {
+        Self::new(
+            WebhookEventKind::WorkflowFinished,
+            "Axon Webhook 测试",
+            "这是一条测试消息，收到说明 Webhook 配置正确。".to_string(),
+            json!({ "test": true }),
+        )
+    }
+}
+
+/// Webhook 通知管理器
+pub struct WebhookManager {
+    settings: Arc<SettingsManager>,
+    http: reqwest::Client,
+    /// 已通知过的新版本，避免每次检查更新都重复通知
+    notified_version: Mutex<Option<String>>,
+}
+
+impl WebhookManager {
+    pub fn new(settings: Arc<SettingsManager>) -> Arc<Self> {
+        Arc::new(Self {
+            settings,
+            http: reqwest::Client::new(),
+            notified_version: Mutex::new(None),
+        })
+    }
+
+    /// 订阅 OpenCode 服务状态事件，服务进入错误状态时发送通知
+    pub fn initialize(self: &Arc<Self>, handle: &AppHandle) {
+        let manager = Arc::clone(self);
+        handle.listen(EVENT_SERVICE_STATUS, move |event| {
+            if let Ok(ServiceStatus::Error { message }) =
+                serde_json::from_str::<ServiceStatus>(event.payload())
+            {
+                manager.dispatch(WebhookEvent::service_crashed(&message));
+            }
+        });
+    }
+
+    /// 在后台向所有订阅了该事件的 Webhook 发送消息
+    pub fn dispatch(&self, event: WebhookEvent) {
+        let targets: Vec<WebhookConfig> = self
+            .settings
+            .get_webhooks()
+            .into_iter()
+            .filter(|w| w.accepts(event.kind))
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+
+        debug!(
+            "发送 Webhook 事件 {:?} 到 {} 个目标",
+            event.kind,
+            targets.len()
+        );
+        for webhook in targets {
+            let http = self.http.clone();
+            let event = event.clone();
+            tauri::async_runtime::spawn(async move {
+                match deliver(&http, &webhook, &event, MAX_ATTEMPTS).await {
+                    Ok(_) => info!("Webhook「{}」已发送: {:?}", webhook.name, event.kind),
+                    Err(e) => warn!("Webhook「{}」发送失败: {}", webhook.name, e),
+                }
+            });
+        }
+    }
+
+    /// 通知发现新版本（同一版本只通知一次）
+    pub fn notify_update_available(&self, version: &str, notes: Option<&str>) {
+        {
+            let mut notified = self.notified_version.lock();
+            if notified.as_deref() == Some(version) {
+                return;
+            }
+            *notified = Some(version.to_string());
+        }
+        self.dispatch(WebhookEvent::update_available(version, notes));
+    }
+
+    /// 立即向指定 Webhook 发送测试消息（不重试），返回 HTTP 状态码
+    pub async fn test(&self, id: &str) -> Result<u16, AxonError> {
+        let webhook = self
+            .settings
+            .get_webhooks()
+            .into_iter()
+            .find(|w| w.id == id)
+            .ok_or_else(|| AxonError::not_found(format!("Webhook 不存在: {}", id)))?;
+        deliver(&self.http, &webhook, &WebhookEvent::test(), 1).await
+    }
+}
+
+/// 发送消息，可重试的错误按指数退避重试，返回最终的 HTTP 状态码
+async fn deliver(
+    http: &reqwest::Client,
+    webhook: &WebhookConfig,
+    event: &WebhookEvent,
+    max_attempts: u32,
+) -> Result<u16, AxonError> {
+    let body = build_payload(webhook.kind, event);
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        let error = match http
+            .post(&webhook.url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&body)
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => {
+                return Ok(response.status().as_u16());
+            }
+            Ok(response) => {
+                let status = response.status();
+                let retryable = status.as_u16() == 429 || status.is_server_error();
+                AxonError::external(format!("Webhook 返回错误: HTTP {}", status.as_u16()))
+                    .with_details(json!({ "status": status.as_u16() }))
+                    .with_retryable(retryable)
+            }
+            Err(e) => AxonError::from(e),
+        };
+
+        if !error.retryable || attempt >= max_attempts {
+            return Err(error);
+        }
+        debug!(
+            "Webhook「{}」第 {} 次发送失败，{:?} 后重试: {}",
+            webhook.name, attempt, backoff, error
+        );
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+        attempt += 1;
+    }
+}
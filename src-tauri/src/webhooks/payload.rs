@@ -0,0 +1,64 @@
+//! Webhook 消息体
+//!
+//! Slack 和 Discord 的 incoming webhook 只接受各自的消息格式，
+//! 通用 Webhook 发送完整的事件 JSON。
+
+use super::WebhookEvent;
+use crate::opencode::WebhookKind;
+use serde_json::{json, Value};
+
+/// 构建指定格式的消息体
+pub fn build_payload(kind: WebhookKind, event: &WebhookEvent) -> Value {
+    match kind {
+        WebhookKind::Slack => json!({
+            "text": format!("*{}*\n{}", event.title, event.message),
+        }),
+        WebhookKind::Discord => json!({
+            "content": format!("**{}**\n{}", event.title, event.message),
+        }),
+        WebhookKind::Generic => json!({
+            "source": "axon",
+            "event": event.kind,
+            "title": &event.title,
+            "message": &event.message,
+            "data": &event.data,
+            "timestamp": event.timestamp,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opencode::WebhookEventKind;
+
+    fn event() -> WebhookEvent {
+        WebhookEvent {
+            kind: WebhookEventKind::UpdateAvailable,
+            title: "发现新版本".to_string(),
+            message: "Axon 1.2.0 可用".to_string(),
+            data: json!({ "version": "1.2.0" }),
+            timestamp: 1_700_000_000_000,
+        }
+    }
+
+    #[test]
+    fn chat_payloads_use_platform_fields() {
+        assert_eq!(
+            build_payload(WebhookKind::Slack, &event()),
+            json!({ "text": "*发现新版本*\nAxon 1.2.0 可用" })
+        );
+        assert_eq!(
+            build_payload(WebhookKind::Discord, &event()),
+            json!({ "content": "**发现新版本**\nAxon 1.2.0 可用" })
+        );
+    }
+
+    #[test]
+    fn generic_payload_carries_event_data() {
+        let payload = build_payload(WebhookKind::Generic, &event());
+        assert_eq!(payload["event"], "update_available");
+        assert_eq!(payload["data"]["version"], "1.2.0");
+        assert_eq!(payload["timestamp"], 1_700_000_000_000_i64);
+    }
+}
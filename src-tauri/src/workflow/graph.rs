@@ -0,0 +1,55 @@
+//! Workflow 图结构
+//!
+//! 节点为主 Agent（ID 固定为 `primary`）和各子 Agent。定义中有 `edges`
+//! （与编排组相同的 `source` / `target` 连线）时按连线建图，否则与画布一致，
+//! 视为主 Agent 到每个子 Agent 各一条委托连线。
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 主 Agent 节点 ID（与前端画布一致）
+pub const PRIMARY_NODE_ID: &str = "primary";
+
+#[derive(Debug, Clone, Default)]
+pub struct WorkflowGraph {
+    /// 节点 ID，主 Agent 在最前
+    pub nodes: Vec<String>,
+    /// 连线（节点下标），已去掉指向不存在节点的连线
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl WorkflowGraph {
+    pub fn from_definition(workflow: &Value) -> Self {
+        let mut nodes = vec![PRIMARY_NODE_ID.to_string()];
+        nodes.extend(
+            workflow["subagents"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|s| s["id"].as_str())
+                .map(str::to_string),
+        );
+        let index: HashMap<&str, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.as_str(), i))
+            .collect();
+
+        let declared: Vec<(usize, usize)> = workflow["edges"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|edge| {
+                let source = index.get(edge["source"].as_str()?)?;
+                let target = index.get(edge["target"].as_str()?)?;
+                Some((*source, *target))
+            })
+            .collect();
+        let edges = if declared.is_empty() {
+            (1..nodes.len()).map(|i| (0, i)).collect()
+        } else {
+            declared
+        };
+        Self { nodes, edges }
+    }
+}
@@ -0,0 +1,344 @@
+//! 分层布局（Sugiyama）
+//!
+//! 1. 反转 DFS 中的回边去掉环
+//! 2. 按最长路径分层，跨多层的连线插入虚拟节点
+//! 3. 上下交替按重心排序减少连线交叉，保留交叉数最少的顺序
+//! 4. 每层在保持顺序和最小间距的前提下尽量靠近相邻层节点的平均位置
+//!    （保序回归），主 Agent 因此居中于其子节点之上
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// 相邻层的间距
+const LAYER_SPACING: f64 = 200.0;
+
+/// 同层节点的间距（画布节点宽约 220）
+const NODE_SPACING: f64 = 260.0;
+
+/// 左上角留白
+const MARGIN: f64 = 100.0;
+
+/// 排序的上下扫描次数
+const ORDERING_SWEEPS: usize = 8;
+
+/// 坐标调整的上下扫描次数
+const POSITIONING_SWEEPS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct NodePosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// 计算节点位置，返回值与节点下标一一对应
+pub fn layered_layout(node_count: usize, edges: &[(usize, usize)]) -> Vec<NodePosition> {
+    if node_count == 0 {
+        return Vec::new();
+    }
+    let edges = acyclic_edges(node_count, edges);
+    let ranks = longest_path_ranks(node_count, &edges);
+    let graph = LayeredGraph::new(&ranks, &edges);
+    let order = graph.minimize_crossings();
+    let xs = graph.assign_x(&order);
+
+    let min_x = xs.iter().copied().fold(f64::INFINITY, f64::min);
+    (0..node_count)
+        .map(|node| NodePosition {
+            x: (xs[node] - min_x + MARGIN).round(),
+            y: MARGIN + ranks[node] as f64 * LAYER_SPACING,
+        })
+        .collect()
+}
+
+/// 去掉自环和重复连线，反转 DFS 回边
+fn acyclic_edges(node_count: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut successors = vec![Vec::new(); node_count];
+    for &(source, target) in edges {
+        if source != target && source < node_count && target < node_count {
+            successors[source].push(target);
+        }
+    }
+
+    // 0: 未访问, 1: 在栈中, 2: 已完成
+    let mut state = vec![0u8; node_count];
+    let mut result = BTreeSet::new();
+    for root in 0..node_count {
+        if state[root] != 0 {
+            continue;
+        }
+        state[root] = 1;
+        let mut stack = vec![(root, 0)];
+        while let Some((node, next)) = stack.last_mut() {
+            let node = *node;
+            let Some(&target) = successors[node].get(*next) else {
+                state[node] = 2;
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+            match state[target] {
+                0 => {
+                    result.insert((node, target));
+                    state[target] = 1;
+                    stack.push((target, 0));
+                }
+                1 => {
+                    result.insert((target, node));
+                }
+                _ => {
+                    result.insert((node, target));
+                }
+            }
+        }
+    }
+    result.into_iter().collect()
+}
+
+/// 每个节点的层号：所有前驱层号的最大值加一
+fn longest_path_ranks(node_count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut successors = vec![Vec::new(); node_count];
+    let mut in_degree = vec![0; node_count];
+    for &(source, target) in edges {
+        successors[source].push(target);
+        in_degree[target] += 1;
+    }
+    let mut ranks = vec![0; node_count];
+    let mut queue: Vec<usize> = (0..node_count).filter(|&n| in_degree[n] == 0).collect();
+    while let Some(node) = queue.pop() {
+        for &target in &successors[node] {
+            ranks[target] = ranks[target].max(ranks[node] + 1);
+            in_degree[target] -= 1;
+            if in_degree[target] == 0 {
+                queue.push(target);
+            }
+        }
+    }
+    ranks
+}
+
+/// 只有相邻层连线的分层图（含虚拟节点）
+struct LayeredGraph {
+    rank: Vec<usize>,
+    predecessors: Vec<Vec<usize>>,
+    successors: Vec<Vec<usize>>,
+    layer_count: usize,
+}
+
+impl LayeredGraph {
+    fn new(ranks: &[usize], edges: &[(usize, usize)]) -> Self {
+        let mut graph = Self {
+            rank: ranks.to_vec(),
+            predecessors: vec![Vec::new(); ranks.len()],
+            successors: vec![Vec::new(); ranks.len()],
+            layer_count: ranks.iter().max().map_or(0, |r| r + 1),
+        };
+        for &(source, target) in edges {
+            let mut previous = source;
+            for rank in ranks[source] + 1..ranks[target] {
+                let dummy = graph.rank.len();
+                graph.rank.push(rank);
+                graph.predecessors.push(Vec::new());
+                graph.successors.push(Vec::new());
+                graph.link(previous, dummy);
+                previous = dummy;
+            }
+            graph.link(previous, target);
+        }
+        graph
+    }
+
+    fn link(&mut self, source: usize, target: usize) {
+        self.successors[source].push(target);
+        self.predecessors[target].push(source);
+    }
+
+    /// 重心法排序，返回各层从左到右的节点
+    fn minimize_crossings(&self) -> Vec<Vec<usize>> {
+        let mut order = vec![Vec::new(); self.layer_count];
+        for node in 0..self.rank.len() {
+            order[self.rank[node]].push(node);
+        }
+        let mut best = order.clone();
+        let mut best_crossings = self.crossings(&order);
+
+        for sweep in 0..ORDERING_SWEEPS {
+            if best_crossings == 0 {
+                break;
+            }
+            let downward = sweep % 2 == 0;
+            let layers: Vec<usize> = if downward {
+                (1..self.layer_count).collect()
+            } else {
+                (0..self.layer_count.saturating_sub(1)).rev().collect()
+            };
+            for layer in layers {
+                let adjacent = if downward { layer - 1 } else { layer + 1 };
+                let position = positions(&order[adjacent], self.rank.len());
+                let neighbors = if downward {
+                    &self.predecessors
+                } else {
+                    &self.successors
+                };
+                let current = positions(&order[layer], self.rank.len());
+                let mut keyed: Vec<(f64, usize)> = order[layer]
+                    .iter()
+                    .map(|&node| {
+                        let key = mean(neighbors[node].iter().map(|&n| position[n] as f64))
+                            .unwrap_or(current[node] as f64);
+                        (key, node)
+                    })
+                    .collect();
+                keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+                order[layer] = keyed.into_iter().map(|(_, node)| node).collect();
+            }
+            let crossings = self.crossings(&order);
+            if crossings < best_crossings {
+                best_crossings = crossings;
+                best = order.clone();
+            }
+        }
+        best
+    }
+
+    /// 相邻层之间的连线交叉数
+    fn crossings(&self, order: &[Vec<usize>]) -> usize {
+        let mut total = 0;
+        for layer in 0..order.len().saturating_sub(1) {
+            let upper = positions(&order[layer], self.rank.len());
+            let lower = positions(&order[layer + 1], self.rank.len());
+            let edges: Vec<(usize, usize)> = order[layer]
+                .iter()
+                .flat_map(|&s| self.successors[s].iter().map(move |&t| (s, t)))
+                .map(|(s, t)| (upper[s], lower[t]))
+                .collect();
+            for (i, a) in edges.iter().enumerate() {
+                total += edges[i + 1..]
+                    .iter()
+                    .filter(|b| (a.0 < b.0 && a.1 > b.1) || (a.0 > b.0 && a.1 < b.1))
+                    .count();
+            }
+        }
+        total
+    }
+
+    /// 横坐标：每层在保持顺序和间距的前提下靠近相邻层邻居的平均位置
+    fn assign_x(&self, order: &[Vec<usize>]) -> Vec<f64> {
+        let mut xs = vec![0.0; self.rank.len()];
+        for layer in order {
+            for (i, &node) in layer.iter().enumerate() {
+                xs[node] = i as f64 * NODE_SPACING;
+            }
+        }
+        for sweep in 0..POSITIONING_SWEEPS * 2 {
+            let downward = sweep % 2 == 0;
+            let neighbors = if downward {
+                &self.predecessors
+            } else {
+                &self.successors
+            };
+            let layers: Vec<&Vec<usize>> = if downward {
+                order.iter().collect()
+            } else {
+                order.iter().rev().collect()
+            };
+            for layer in layers {
+                let desired: Vec<f64> = layer
+                    .iter()
+                    .map(|&node| mean(neighbors[node].iter().map(|&n| xs[n])).unwrap_or(xs[node]))
+                    .collect();
+                for (&node, x) in layer.iter().zip(place_in_order(&desired)) {
+                    xs[node] = x;
+                }
+            }
+        }
+        xs
+    }
+}
+
+/// 节点在所在层中的序号
+fn positions(layer: &[usize], node_count: usize) -> Vec<usize> {
+    let mut position = vec![0; node_count];
+    for (i, &node) in layer.iter().enumerate() {
+        position[node] = i;
+    }
+    position
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// 保持顺序、间距不小于 `NODE_SPACING` 时离期望位置平方误差最小的坐标
+///
+/// 令 `q[i] = x[i] - i·间距`，约束变为 `q` 非递减，即对 `desired[i] - i·间距` 做保序回归
+fn place_in_order(desired: &[f64]) -> Vec<f64> {
+    // (和, 个数)
+    let mut blocks: Vec<(f64, usize)> = Vec::new();
+    for (i, d) in desired.iter().enumerate() {
+        blocks.push((d - i as f64 * NODE_SPACING, 1));
+        while let [.., (sum_a, count_a), (sum_b, count_b)] = blocks[..] {
+            if sum_a / count_a as f64 <= sum_b / count_b as f64 {
+                break;
+            }
+            blocks.pop();
+            blocks.pop();
+            blocks.push((sum_a + sum_b, count_a + count_b));
+        }
+    }
+    let mut result = Vec::with_capacity(desired.len());
+    for (sum, count) in blocks {
+        let value = sum / count as f64;
+        for _ in 0..count {
+            result.push(value + result.len() as f64 * NODE_SPACING);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn centers_primary_above_subagents() {
+        let positions = layered_layout(4, &[(0, 1), (0, 2), (0, 3)]);
+        assert_eq!(positions[0].y, MARGIN);
+        assert!(positions[1..].iter().all(|p| p.y == MARGIN + LAYER_SPACING));
+        assert_eq!(positions[1].x, MARGIN);
+        assert_eq!(positions[2].x - positions[1].x, NODE_SPACING);
+        assert_eq!(positions[0].x, positions[2].x);
+    }
+
+    #[test]
+    fn breaks_cycles_and_removes_crossings() {
+        // 0 → 1 → 2 → 0 的环，以及会交叉的 3 → 5、4 → 6 / 3 → 6
+        let edges = [(0, 1), (1, 2), (2, 0), (3, 6), (4, 5), (3, 5)];
+        let positions = layered_layout(7, &edges);
+        assert!(positions[0].y < positions[1].y && positions[1].y < positions[2].y);
+
+        let graph = LayeredGraph::new(
+            &longest_path_ranks(7, &acyclic_edges(7, &edges)),
+            &acyclic_edges(7, &edges),
+        );
+        assert_eq!(graph.crossings(&graph.minimize_crossings()), 0);
+
+        // 同层节点不重叠
+        for a in 0..7 {
+            for b in a + 1..7 {
+                if positions[a].y == positions[b].y {
+                    assert!((positions[a].x - positions[b].x).abs() >= NODE_SPACING);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn routes_long_edges_through_dummy_nodes() {
+        // 0 → 1 → 2 和 0 → 2：0 → 2 跨两层
+        let edges = acyclic_edges(3, &[(0, 1), (1, 2), (0, 2)]);
+        let graph = LayeredGraph::new(&longest_path_ranks(3, &edges), &edges);
+        assert_eq!(graph.rank.len(), 4);
+        assert_eq!(graph.rank[3], 1);
+    }
+}
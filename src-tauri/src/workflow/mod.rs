@@ -0,0 +1,13 @@
+//! Workflow 定义的图结构和布局
+//!
+//! Workflow 以 JSON 文件保存（见 `commands::workflow`），前端负责编辑。
+//! 这里把定义解析为节点和连线组成的有向图（[`graph`]），
+//! 供后端计算画布布局（[`layout`]）和推演执行计划（[`plan`]）。
+
+mod graph;
+mod layout;
+mod plan;
+
+pub use graph::{WorkflowGraph, PRIMARY_NODE_ID};
+pub use layout::{layered_layout, NodePosition};
+pub use plan::{plan_execution, ExecutionPlan};
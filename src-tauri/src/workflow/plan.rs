@@ -0,0 +1,460 @@
+//! Workflow 执行计划（试运行）
+//!
+//! 不调用任何模型，按主 Agent → 子 Agent 委托 → 主 Agent 汇总的顺序推演一次执行。
+//! 关键词触发器和「总是」触发器可以直接对输入求值；领域、条件触发器和委托规则
+//! 需要主 Agent 判断，标记为可能执行。
+//!
+//! Token 数只是粗略估算：输入按提示词和输入文本计数，输出按固定预算，
+//! 不包含工具调用和多轮对话带来的额外消耗。
+
+use super::graph::PRIMARY_NODE_ID;
+use crate::utils::tokens::count_tokens;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 没有设置 maxTokens 时每次回复的输出预算
+const DEFAULT_OUTPUT_TOKENS: u64 = 1000;
+
+/// 主 Agent 每次委托（调用 task 工具）的输出预算
+const DELEGATION_OUTPUT_TOKENS: u64 = 300;
+
+/// 委托任务描述在输入之外的额外开销
+const TASK_OVERHEAD_TOKENS: u64 = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanStepKind {
+    /// 主 Agent 接收输入并决定是否委托
+    Receive,
+    /// 子 Agent 执行委托的任务
+    Delegate,
+    /// 主 Agent 汇总子 Agent 的结果
+    Synthesize,
+}
+
+/// 步骤是否会执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanCertainty {
+    /// 必然执行（总是触发或关键词命中）
+    Certain,
+    /// 取决于主 Agent 的判断
+    Possible,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanStep {
+    /// 画布节点 ID（主 Agent 为 `primary`）
+    pub node_id: String,
+    pub kind: PlanStepKind,
+    pub certainty: PlanCertainty,
+    /// 执行原因（命中的触发器、规则等）
+    pub reason: String,
+    pub agent_id: Option<String>,
+    pub agent_name: String,
+    /// 为空时使用 OpenCode 默认模型
+    pub model_id: Option<String>,
+    /// 工具访问模式（all / whitelist / blacklist）
+    pub tool_mode: String,
+    pub tools: Vec<String>,
+    pub run_in_background: bool,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// 估算费用（美元），模型没有价格信息时为空
+    pub cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPlan {
+    pub steps: Vec<PlanStep>,
+    /// 不会执行的子 Agent（节点 ID）
+    pub skipped: Vec<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// 有价格信息的步骤的费用之和
+    pub cost: f64,
+    /// 有步骤缺少价格信息，`cost` 偏低
+    pub cost_incomplete: bool,
+    pub warnings: Vec<String>,
+}
+
+/// 推演执行计划
+///
+/// `agents` 为已加载的 Agent 定义（按 ID），`pricing` 返回模型每百万 token 的输入、输出价格
+pub fn plan_execution(
+    workflow: &Value,
+    agents: &HashMap<String, Value>,
+    input: &str,
+    pricing: impl Fn(&str) -> Option<(f64, f64)>,
+) -> ExecutionPlan {
+    let mut warnings = Vec::new();
+    let ruleset = &workflow["delegationRuleset"];
+
+    // 主 Agent：内联定义或引用的 Agent
+    let primary_config = &workflow["primaryAgent"];
+    let (primary_id, primary) = if primary_config["mode"] == "inline" {
+        (None, Some(&primary_config["inline"]))
+    } else {
+        let id = primary_config["agentId"].as_str().unwrap_or_default();
+        let agent = agents.get(id);
+        if agent.is_none() {
+            warnings.push(format!("找不到主 Agent 定义: {}", id));
+        }
+        (Some(id.to_string()), agent)
+    };
+    let primary_name = primary
+        .and_then(|a| a["name"].as_str())
+        .or(primary_id.as_deref())
+        .unwrap_or("主 Agent")
+        .to_string();
+    let primary_prompt = [
+        primary.map_or("", |a| text(&a["prompt"]["system"])),
+        primary.map_or("", |a| text(&a["prompt"]["append"])),
+        text(&ruleset["customGuidelines"]),
+    ]
+    .join("\n");
+    let primary_model = primary.and_then(model_of);
+
+    // 子 Agent
+    let mut delegations = Vec::new();
+    let mut skipped = Vec::new();
+    let subagents = workflow["subagents"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    for (order, subagent) in subagents.iter().enumerate() {
+        let node_id = text(&subagent["id"]).to_string();
+        if subagent["enabled"] == false {
+            skipped.push(node_id);
+            continue;
+        }
+        match resolve_trigger(subagent, ruleset, &node_id, input) {
+            Some((certainty, priority, reason)) => {
+                delegations.push((certainty, priority, order, reason))
+            }
+            None => skipped.push(node_id),
+        }
+    }
+    if delegations.is_empty() {
+        match text(&ruleset["defaultBehavior"]) {
+            "delegate-to" => {
+                let target = text(&ruleset["defaultSubagentId"]);
+                match subagents.iter().position(|s| s["id"] == target) {
+                    Some(order) => {
+                        skipped.retain(|id| id != target);
+                        delegations.push((
+                            PlanCertainty::Certain,
+                            0,
+                            order,
+                            "没有匹配的触发器，使用默认委托目标".to_string(),
+                        ));
+                    }
+                    None => warnings.push(format!("默认委托目标不存在: {}", target)),
+                }
+            }
+            "ask-user" => warnings.push("没有匹配的触发器，主 Agent 将询问用户".to_string()),
+            _ => {}
+        }
+    }
+    // 必然执行的在前，其次按优先级，再按画布中的顺序
+    delegations.sort_by_key(|(certainty, priority, order, _)| {
+        (
+            *certainty != PlanCertainty::Certain,
+            std::cmp::Reverse(*priority),
+            *order,
+        )
+    });
+
+    let input_tokens = tokens(input, primary_model.as_deref());
+    let mut steps = vec![PlanStep {
+        node_id: PRIMARY_NODE_ID.to_string(),
+        kind: PlanStepKind::Receive,
+        certainty: PlanCertainty::Certain,
+        reason: "接收输入".to_string(),
+        agent_id: primary_id,
+        agent_name: primary_name,
+        model_id: primary_model.clone(),
+        tool_mode: tool_mode(primary),
+        tools: tool_list(primary),
+        run_in_background: false,
+        input_tokens: tokens(&primary_prompt, primary_model.as_deref()) + input_tokens,
+        output_tokens: if delegations.is_empty() {
+            output_budget(primary)
+        } else {
+            DELEGATION_OUTPUT_TOKENS * delegations.len() as u64
+        },
+        cost: None,
+    }];
+
+    let mut delegated_output = 0;
+    for (certainty, _, order, reason) in delegations {
+        let subagent = &subagents[order];
+        let agent_id = text(&subagent["agentId"]).to_string();
+        let agent = agents.get(&agent_id);
+        if agent.is_none() {
+            warnings.push(format!("找不到子 Agent 定义: {}", agent_id));
+        }
+        let model_id = subagent["overrides"]["model"]["modelId"]
+            .as_str()
+            .filter(|m| !m.is_empty())
+            .map(str::to_string)
+            .or_else(|| agent.and_then(model_of));
+        let prompt = subagent["overrides"]["systemPrompt"]
+            .as_str()
+            .or_else(|| agent.and_then(|a| a["prompt"]["system"].as_str()))
+            .unwrap_or_default();
+        let output_tokens = output_budget(agent);
+        delegated_output += output_tokens;
+        steps.push(PlanStep {
+            node_id: text(&subagent["id"]).to_string(),
+            kind: PlanStepKind::Delegate,
+            certainty,
+            reason,
+            agent_name: subagent["name"]
+                .as_str()
+                .or_else(|| agent.and_then(|a| a["name"].as_str()))
+                .unwrap_or(&agent_id)
+                .to_string(),
+            agent_id: Some(agent_id),
+            input_tokens: tokens(prompt, model_id.as_deref()) + input_tokens + TASK_OVERHEAD_TOKENS,
+            model_id,
+            tool_mode: tool_mode(agent),
+            tools: tool_list(agent),
+            run_in_background: subagent["runInBackground"] == true,
+            output_tokens,
+            cost: None,
+        });
+    }
+
+    if steps.len() > 1 {
+        let certain = steps[1..]
+            .iter()
+            .any(|s| s.certainty == PlanCertainty::Certain);
+        let receive = &steps[0];
+        let synthesize = PlanStep {
+            kind: PlanStepKind::Synthesize,
+            certainty: if certain {
+                PlanCertainty::Certain
+            } else {
+                PlanCertainty::Possible
+            },
+            reason: "汇总子 Agent 的结果".to_string(),
+            input_tokens: receive.input_tokens + receive.output_tokens + delegated_output,
+            output_tokens: output_budget(primary),
+            ..receive.clone()
+        };
+        steps.push(synthesize);
+    }
+
+    let mut plan = ExecutionPlan {
+        skipped,
+        input_tokens: 0,
+        output_tokens: 0,
+        cost: 0.0,
+        cost_incomplete: false,
+        warnings,
+        steps: Vec::new(),
+    };
+    for mut step in steps {
+        step.cost = step
+            .model_id
+            .as_deref()
+            .and_then(&pricing)
+            .map(|(input, output)| {
+                (step.input_tokens as f64 * input + step.output_tokens as f64 * output) / 1e6
+            });
+        plan.input_tokens += step.input_tokens;
+        plan.output_tokens += step.output_tokens;
+        match step.cost {
+            Some(cost) => plan.cost += cost,
+            None => plan.cost_incomplete = true,
+        }
+        plan.steps.push(step);
+    }
+    plan
+}
+
+/// 子 Agent 是否会被委托：(确定性, 优先级, 原因)，不会执行时为 None
+fn resolve_trigger(
+    subagent: &Value,
+    ruleset: &Value,
+    node_id: &str,
+    input: &str,
+) -> Option<(PlanCertainty, u8, String)> {
+    let input = input.to_lowercase();
+    let mut possible = None;
+    for trigger in subagent["triggers"].as_array().into_iter().flatten() {
+        let pattern = text(&trigger["pattern"]);
+        match text(&trigger["type"]) {
+            "always" => return Some((PlanCertainty::Certain, 0, "总是触发".to_string())),
+            "keyword" => {
+                let matched = pattern
+                    .split([',', '，', '|'])
+                    .map(str::trim)
+                    .find(|k| !k.is_empty() && input.contains(&k.to_lowercase()));
+                if let Some(keyword) = matched {
+                    return Some((
+                        PlanCertainty::Certain,
+                        0,
+                        format!("命中关键词「{}」", keyword),
+                    ));
+                }
+            }
+            kind => {
+                let label = if kind == "domain" { "领域" } else { "条件" };
+                possible.get_or_insert((
+                    0,
+                    format!("{}触发器「{}」需要主 Agent 判断", label, pattern),
+                ));
+            }
+        }
+    }
+
+    // 委托规则都需要主 Agent 判断，取最高优先级
+    let rule = ruleset["rules"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|r| r["subagentId"] == node_id && r["enabled"] != false)
+        .max_by_key(|r| priority(text(&r["priority"])));
+    if let Some(rule) = rule {
+        let rule_priority = priority(text(&rule["priority"]));
+        if possible.as_ref().is_none_or(|(p, _)| rule_priority > *p) {
+            let condition = [text(&rule["domain"]), text(&rule["condition"])]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(": ");
+            possible = Some((
+                rule_priority,
+                format!("委托规则「{}」需要主 Agent 判断", condition),
+            ));
+        }
+    }
+    possible.map(|(priority, reason)| (PlanCertainty::Possible, priority, reason))
+}
+
+fn priority(value: &str) -> u8 {
+    match value {
+        "critical" => 4,
+        "high" => 3,
+        "medium" => 2,
+        "low" => 1,
+        _ => 0,
+    }
+}
+
+fn text(value: &Value) -> &str {
+    value.as_str().unwrap_or_default()
+}
+
+fn model_of(agent: &Value) -> Option<String> {
+    agent["model"]["modelId"]
+        .as_str()
+        .filter(|m| !m.is_empty())
+        .map(str::to_string)
+}
+
+fn tool_mode(agent: Option<&Value>) -> String {
+    agent
+        .and_then(|a| a["tools"]["mode"].as_str())
+        .unwrap_or("all")
+        .to_string()
+}
+
+fn tool_list(agent: Option<&Value>) -> Vec<String> {
+    agent
+        .and_then(|a| a["tools"]["list"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|t| t.as_str().map(str::to_string))
+        .collect()
+}
+
+fn output_budget(agent: Option<&Value>) -> u64 {
+    agent
+        .and_then(|a| a["parameters"]["maxTokens"].as_u64())
+        .unwrap_or(DEFAULT_OUTPUT_TOKENS)
+}
+
+fn tokens(text: &str, model_id: Option<&str>) -> u64 {
+    if text.trim().is_empty() {
+        return 0;
+    }
+    count_tokens(text, model_id).tokens as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn workflow() -> Value {
+        json!({
+            "primaryAgent": { "mode": "inline", "inline": { "name": "Lead", "model": { "modelId": "a/big" } } },
+            "subagents": [
+                { "id": "docs", "agentId": "writer", "enabled": true,
+                  "triggers": [{ "type": "keyword", "pattern": "文档, readme" }] },
+                { "id": "tests", "agentId": "tester", "enabled": true, "runInBackground": true,
+                  "triggers": [{ "type": "domain", "pattern": "testing" }] },
+                { "id": "review", "agentId": "reviewer", "enabled": true,
+                  "overrides": { "model": { "modelId": "b/small" } },
+                  "triggers": [{ "type": "always", "pattern": "" }] },
+                { "id": "off", "agentId": "reviewer", "enabled": false, "triggers": [] },
+            ],
+            "delegationRuleset": {
+                "rules": [{ "id": "r", "subagentId": "tests", "domain": "qa", "condition": "改动了代码",
+                            "priority": "high", "enabled": true }],
+                "defaultBehavior": "handle-self",
+            },
+        })
+    }
+
+    #[test]
+    fn orders_steps_by_certainty_and_priority() {
+        let agents = HashMap::from([
+            (
+                "writer".to_string(),
+                json!({ "name": "Writer", "model": { "modelId": "a/big" } }),
+            ),
+            (
+                "reviewer".to_string(),
+                json!({ "name": "Reviewer", "model": { "modelId": "a/big" } }),
+            ),
+        ]);
+        let plan = plan_execution(&workflow(), &agents, "Update the README", |model| {
+            (model == "a/big").then_some((3.0, 15.0))
+        });
+
+        let nodes: Vec<_> = plan.steps.iter().map(|s| s.node_id.as_str()).collect();
+        assert_eq!(nodes, ["primary", "docs", "review", "tests", "primary"]);
+        assert_eq!(plan.steps[1].reason, "命中关键词「readme」");
+        assert_eq!(plan.steps[2].model_id.as_deref(), Some("b/small"));
+        assert_eq!(plan.steps[3].certainty, PlanCertainty::Possible);
+        assert!(plan.steps[3].reason.contains("改动了代码"));
+        assert!(plan.steps[3].run_in_background);
+        assert_eq!(plan.steps[4].kind, PlanStepKind::Synthesize);
+        assert_eq!(plan.skipped, ["off"]);
+        assert!(plan.cost_incomplete);
+        assert!(plan.cost > 0.0);
+        assert_eq!(plan.warnings, ["找不到子 Agent 定义: tester"]);
+    }
+
+    #[test]
+    fn falls_back_to_default_subagent() {
+        let mut workflow = workflow();
+        workflow["subagents"] = json!([{ "id": "docs", "agentId": "writer", "enabled": true,
+            "triggers": [{ "type": "keyword", "pattern": "文档" }] }]);
+        workflow["delegationRuleset"] = json!({
+            "rules": [], "defaultBehavior": "delegate-to", "defaultSubagentId": "docs",
+        });
+        let plan = plan_execution(&workflow, &HashMap::new(), "fix the bug", |_| None);
+        assert_eq!(plan.steps.len(), 3);
+        assert_eq!(plan.steps[1].certainty, PlanCertainty::Certain);
+        assert!(plan.skipped.is_empty());
+        assert_eq!(plan.cost, 0.0);
+    }
+}
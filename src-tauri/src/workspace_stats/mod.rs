@@ -0,0 +1,445 @@
+//! 项目概览统计
+//!
+//! 遍历项目目录（遵循忽略规则）统计各语言的文件数、行数和体积，列出最大的文件；
+//! 项目是 Git 仓库时再根据最近的提交记录统计改动最频繁的文件。
+//! 统计结果按项目目录缓存一段时间，项目概览面板重复打开时直接返回。
+
+use crate::error::AxonError;
+use crate::jobs::JobHandle;
+use crate::utils::ignore::IgnoreMatcher;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tracing::{debug, warn};
+
+/// 缓存有效期
+const CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// 统计的最大文件数（超过后停止遍历）
+const MAX_FILES: usize = 200_000;
+
+/// 超过该大小的文件只计体积，不计行数
+const MAX_COUNTED_BYTES: u64 = 20 * 1024 * 1024;
+
+/// 返回的最大文件数
+const LARGEST_FILES: usize = 20;
+
+/// 返回的改动热点数
+const HOTSPOTS: usize = 20;
+
+/// 统计改动热点时读取的提交范围
+const HOTSPOT_SINCE: &str = "90 days ago";
+const HOTSPOT_MAX_COMMITS: &str = "2000";
+
+/// 单个语言的统计
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageStats {
+    pub language: String,
+    pub files: u64,
+    pub lines: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LargeFile {
+    /// 相对项目目录的路径（`/` 分隔）
+    pub path: String,
+    pub bytes: u64,
+}
+
+/// 最近改动频繁的文件
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeHotspot {
+    /// 相对项目目录的路径（`/` 分隔）
+    pub path: String,
+    /// 统计范围内修改过该文件的提交数
+    pub commits: u64,
+    /// 最近一次修改的提交时间（Unix 毫秒）
+    pub last_changed: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceStats {
+    pub project_dir: String,
+    pub total_files: u64,
+    /// 文本文件总行数（二进制文件和超大文件不计）
+    pub total_lines: u64,
+    pub total_bytes: u64,
+    /// 按行数降序，无法识别语言的文件归入 "Other"
+    pub languages: Vec<LanguageStats>,
+    pub largest_files: Vec<LargeFile>,
+    /// 不是 Git 仓库或 git 不可用时为空
+    pub hotspots: Option<Vec<ChangeHotspot>>,
+    /// 文件数超过上限，统计不完整
+    pub truncated: bool,
+    /// 统计完成时间（Unix 毫秒）
+    pub computed_at: i64,
+    pub elapsed_ms: u64,
+    /// 结果来自缓存
+    pub cached: bool,
+}
+
+/// 按项目目录缓存的统计结果
+#[derive(Default)]
+pub struct WorkspaceStatsCache {
+    entries: Mutex<HashMap<String, (Instant, WorkspaceStats)>>,
+}
+
+impl WorkspaceStatsCache {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// 未过期的缓存结果
+    pub fn get(&self, project_dir: &str) -> Option<WorkspaceStats> {
+        let mut entries = self.entries.lock();
+        entries.retain(|_, (at, _)| at.elapsed() < CACHE_TTL);
+        entries
+            .get(&cache_key(project_dir))
+            .map(|(_, stats)| WorkspaceStats {
+                cached: true,
+                ..stats.clone()
+            })
+    }
+
+    pub fn insert(&self, project_dir: &str, stats: WorkspaceStats) {
+        self.entries
+            .lock()
+            .insert(cache_key(project_dir), (Instant::now(), stats));
+    }
+}
+
+fn cache_key(project_dir: &str) -> String {
+    project_dir.trim_end_matches(['/', '\\']).to_string()
+}
+
+/// 遍历项目目录统计文件（不含改动热点），可通过任务句柄取消
+pub fn scan(root: &Path, job: &JobHandle) -> Result<WorkspaceStats, AxonError> {
+    let started = Instant::now();
+    let mut scanner = Scanner {
+        root,
+        job,
+        ignore: IgnoreMatcher::for_project(root),
+        languages: HashMap::new(),
+        files: Vec::new(),
+        total_lines: 0,
+        truncated: false,
+    };
+    scanner.walk(root)?;
+
+    let mut languages: Vec<LanguageStats> = scanner.languages.into_values().collect();
+    languages.sort_by(|a, b| {
+        b.lines
+            .cmp(&a.lines)
+            .then_with(|| b.bytes.cmp(&a.bytes))
+            .then_with(|| a.language.cmp(&b.language))
+    });
+    let total_bytes = scanner.files.iter().map(|f| f.bytes).sum();
+    let total_files = scanner.files.len() as u64;
+    let mut largest_files = scanner.files;
+    largest_files.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    largest_files.truncate(LARGEST_FILES);
+
+    debug!(
+        "项目统计完成: {:?}, {} 个文件, 耗时 {:?}",
+        root,
+        total_files,
+        started.elapsed()
+    );
+    Ok(WorkspaceStats {
+        project_dir: root.to_string_lossy().to_string(),
+        total_files,
+        total_lines: scanner.total_lines,
+        total_bytes,
+        languages,
+        largest_files,
+        hotspots: None,
+        truncated: scanner.truncated,
+        computed_at: chrono::Utc::now().timestamp_millis(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        cached: false,
+    })
+}
+
+struct Scanner<'a> {
+    root: &'a Path,
+    job: &'a JobHandle,
+    ignore: IgnoreMatcher,
+    languages: HashMap<&'static str, LanguageStats>,
+    files: Vec<LargeFile>,
+    total_lines: u64,
+    truncated: bool,
+}
+
+impl Scanner<'_> {
+    fn walk(&mut self, dir: &Path) -> Result<(), AxonError> {
+        if self.job.is_cancelled() {
+            return Err(AxonError::cancelled("操作已取消"));
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Ok(());
+        };
+        for entry in entries.flatten() {
+            if self.truncated {
+                return Ok(());
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let path = entry.path();
+            let Ok(relative) = path.strip_prefix(self.root) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            if file_type.is_dir() {
+                if entry.file_name().to_string_lossy().starts_with('.')
+                    || self.ignore.is_ignored(&relative, true)
+                {
+                    continue;
+                }
+                self.walk(&path)?;
+            } else if file_type.is_file() && !self.ignore.is_ignored(&relative, false) {
+                if self.files.len() >= MAX_FILES {
+                    self.truncated = true;
+                    return Ok(());
+                }
+                let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                self.record(&path, relative, bytes);
+            }
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, path: &Path, relative: String, bytes: u64) {
+        let lines = if bytes <= MAX_COUNTED_BYTES {
+            count_lines(path).unwrap_or(0)
+        } else {
+            0
+        };
+        let language = language_of(&relative);
+        let stats = self
+            .languages
+            .entry(language)
+            .or_insert_with(|| LanguageStats {
+                language: language.to_string(),
+                files: 0,
+                lines: 0,
+                bytes: 0,
+            });
+        stats.files += 1;
+        stats.lines += lines;
+        stats.bytes += bytes;
+        self.total_lines += lines;
+        self.files.push(LargeFile {
+            path: relative,
+            bytes,
+        });
+    }
+}
+
+/// 统计换行符数量（最后一行没有换行符时也计入），二进制文件返回 None
+fn count_lines(path: &Path) -> Option<u64> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut lines = 0u64;
+    let mut last = b'\n';
+    let mut first_chunk = true;
+    loop {
+        let read = file.read(&mut buffer).ok()?;
+        if read == 0 {
+            break;
+        }
+        let chunk = &buffer[..read];
+        // 与 Git 相同，根据开头是否含 NUL 判断二进制
+        if first_chunk && chunk[..read.min(8000)].contains(&0) {
+            return None;
+        }
+        first_chunk = false;
+        lines += chunk.iter().filter(|&&b| b == b'\n').count() as u64;
+        last = chunk[read - 1];
+    }
+    if last != b'\n' {
+        lines += 1;
+    }
+    Some(lines)
+}
+
+/// 根据文件名识别语言
+fn language_of(relative: &str) -> &'static str {
+    let name = relative.rsplit('/').next().unwrap_or(relative);
+    match name {
+        "Dockerfile" => return "Dockerfile",
+        "Makefile" | "makefile" | "GNUmakefile" => return "Makefile",
+        _ => {}
+    }
+    let Some((_, extension)) = name.rsplit_once('.') else {
+        return "Other";
+    };
+    match extension.to_ascii_lowercase().as_str() {
+        "rs" => "Rust",
+        "ts" | "mts" | "cts" => "TypeScript",
+        "tsx" => "TSX",
+        "js" | "mjs" | "cjs" => "JavaScript",
+        "jsx" => "JSX",
+        "py" | "pyi" => "Python",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "swift" => "Swift",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" | "hh" | "hxx" => "C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "lua" => "Lua",
+        "dart" => "Dart",
+        "scala" => "Scala",
+        "zig" => "Zig",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "html" | "htm" => "HTML",
+        "css" => "CSS",
+        "scss" | "sass" => "SCSS",
+        "less" => "Less",
+        "sh" | "bash" | "zsh" | "fish" => "Shell",
+        "ps1" | "psm1" => "PowerShell",
+        "sql" => "SQL",
+        "md" | "mdx" => "Markdown",
+        "json" | "jsonc" => "JSON",
+        "yaml" | "yml" => "YAML",
+        "toml" => "TOML",
+        "xml" => "XML",
+        "proto" => "Protocol Buffers",
+        _ => "Other",
+    }
+}
+
+/// 统计最近提交中改动最频繁的文件（只保留仍存在且未被忽略的文件）
+///
+/// 不是 Git 仓库或 git 不可用时返回 None
+pub async fn git_hotspots(root: &Path) -> Option<Vec<ChangeHotspot>> {
+    let mut command = Command::new("git");
+    command
+        .args([
+            "log",
+            "--no-merges",
+            "--since",
+            HOTSPOT_SINCE,
+            "-n",
+            HOTSPOT_MAX_COMMITS,
+            "--format=%x00%ct",
+            "--name-only",
+        ])
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+
+    // Windows 平台：避免弹出控制台窗口
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = match command.output().await {
+        Ok(output) if output.status.success() => output,
+        Ok(_) => return None,
+        Err(e) => {
+            warn!("执行 git log 失败，跳过改动热点统计: {}", e);
+            return None;
+        }
+    };
+
+    let ignore = IgnoreMatcher::for_project(root);
+    let mut hotspots = parse_git_log(&String::from_utf8_lossy(&output.stdout));
+    hotspots.retain(|h| !ignore.is_ignored(&h.path, false) && root.join(&h.path).is_file());
+    hotspots.truncate(HOTSPOTS);
+    Some(hotspots)
+}
+
+/// 解析 `git log --format=%x00%ct --name-only` 的输出，按提交数降序
+fn parse_git_log(output: &str) -> Vec<ChangeHotspot> {
+    let mut counts: HashMap<&str, (u64, i64)> = HashMap::new();
+    let mut commit_time = 0i64;
+    for line in output.lines() {
+        if let Some(timestamp) = line.strip_prefix('\0') {
+            commit_time = timestamp.trim().parse::<i64>().unwrap_or(0) * 1000;
+            continue;
+        }
+        let path = line.trim();
+        if path.is_empty() {
+            continue;
+        }
+        let entry = counts.entry(path).or_insert((0, commit_time));
+        entry.0 += 1;
+        entry.1 = entry.1.max(commit_time);
+    }
+
+    let mut hotspots: Vec<ChangeHotspot> = counts
+        .into_iter()
+        .map(|(path, (commits, last_changed))| ChangeHotspot {
+            path: path.to_string(),
+            commits,
+            last_changed,
+        })
+        .collect();
+    hotspots.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then_with(|| b.last_changed.cmp(&a.last_changed))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    hotspots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::JobManager;
+
+    #[test]
+    fn scans_languages_and_lines() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join("node_modules/pkg")).unwrap();
+        std::fs::write(dir.join("src/main.rs"), "fn main() {\n}\n").unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "pub mod a;").unwrap();
+        std::fs::write(dir.join("README.md"), "# demo\n\nsome text\n").unwrap();
+        std::fs::write(dir.join("logo.png"), [0x89, b'P', b'N', b'G', 0, 0]).unwrap();
+        std::fs::write(dir.join("node_modules/pkg/index.js"), "x\n").unwrap();
+
+        let jobs = JobManager::new();
+        let job = jobs.register("stats").unwrap();
+        let stats = scan(dir, &job).unwrap();
+        assert_eq!(stats.total_files, 4);
+        assert_eq!(stats.total_lines, 6);
+        assert_eq!(stats.languages[0].language, "Rust");
+        assert_eq!(stats.languages[0].files, 2);
+        assert_eq!(stats.languages[0].lines, 3);
+        assert!(stats.languages.iter().all(|l| l.language != "JavaScript"));
+        assert_eq!(stats.largest_files[0].path, "README.md");
+    }
+
+    #[test]
+    fn parses_git_log_hotspots() {
+        let log = "\u{0}1700000000\n\nsrc/a.rs\nsrc/b.rs\n\u{0}1700000100\n\nsrc/a.rs\n";
+        let hotspots = parse_git_log(log);
+        assert_eq!(hotspots[0].path, "src/a.rs");
+        assert_eq!(hotspots[0].commits, 2);
+        assert_eq!(hotspots[0].last_changed, 1_700_000_100_000);
+        assert_eq!(hotspots[1].commits, 1);
+    }
+}
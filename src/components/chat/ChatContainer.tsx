@@ -12,6 +12,7 @@ import { QuickPrompts } from "./QuickPrompts";
 import { TodoListCompact } from "./TodoList";
 import { AutoAcceptToggle } from "./PermissionPrompt";
 import { FloatingPermissionPrompt } from "./FloatingPermissionPrompt";
+import { ConsentPrompt } from "./ConsentPrompt";
 import { LspStatusBadge } from "./LspStatusBadge";
 import type { Message, Session, Agent } from "@/types/chat";
 import type { Provider } from "@/stores/chat";
@@ -334,6 +335,8 @@ export function ChatContainer({
                 <FloatingPermissionPrompt sessionId={activeSessionId} />
               </div>
             )}
+            {/* Agent 发起的破坏性操作确认 */}
+            <ConsentPrompt className="px-6 pb-3" />
           </div>
         </div>
 
@@ -44,6 +44,7 @@ import { VariantSelector } from "./VariantSelector";
 import { AgentSelector } from "./AgentSelector";
 import type { Provider } from "@/stores/chat";
 import type { Session, Agent } from "@/types/chat";
+import { clipboard } from "@/services/tauri";
 import {
   useAttachments,
   useTriggerDetection,
@@ -396,9 +397,7 @@ export function ChatInputCard({
   const handlePaste = useCallback(
     async (e: ClipboardEvent<HTMLTextAreaElement>) => {
       const items = e.clipboardData?.items;
-      if (!items) return;
-
-      for (const item of items) {
+      for (const item of items ?? []) {
         if (isSupportedAttachmentType(item.type)) {
           e.preventDefault();
           const blob = item.getAsFile();
@@ -408,6 +407,18 @@ export function ChatInputCard({
           return;
         }
       }
+
+      // 粘贴文本时走默认行为；webview 拿不到图片数据时（剪贴板 API 受限）从后端读取系统剪贴板
+      if (e.clipboardData?.getData("text/plain")) return;
+      try {
+        const image = await clipboard.readImage();
+        if (image) {
+          const blob = await (await fetch(`data:${image.mimeType};base64,${image.data}`)).blob();
+          await addAttachmentFromBlob(blob);
+        }
+      } catch (error) {
+        console.error("[ChatInput] 读取剪贴板图片失败:", error);
+      }
     },
     [addAttachmentFromBlob]
   );
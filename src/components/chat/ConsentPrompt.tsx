@@ -0,0 +1,181 @@
+/**
+ * 破坏性操作确认组件
+ *
+ * 显示 Agent 通过插件发起的删除文件、执行命令等待确认请求，
+ * 后端在用户回复前会阻塞对应的工具调用，超时后自动拒绝
+ */
+
+import { useState, useEffect, useCallback } from "react";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { Button } from "@/components/ui/button";
+import { cn } from "@/lib/utils";
+import { ShieldAlert, Check, X, Zap } from "lucide-react";
+import { consent } from "@/services/tauri";
+import {
+  EVENT_CONSENT_REQUESTED,
+  EVENT_CONSENT_RESOLVED,
+  type ConsentDecision,
+  type ConsentRequest,
+  type ConsentResolved,
+} from "@/types/consent";
+
+interface ConsentPromptProps {
+  className?: string;
+}
+
+/**
+ * 破坏性操作确认组件
+ * 挂载时补齐已有的待确认请求，之后通过事件增删
+ */
+export function ConsentPrompt({ className }: ConsentPromptProps) {
+  const [requests, setRequests] = useState<ConsentRequest[]>([]);
+
+  useEffect(() => {
+    let isMounted = true;
+    const unlisteners: UnlistenFn[] = [];
+
+    const setup = async () => {
+      unlisteners.push(
+        await listen<ConsentRequest>(EVENT_CONSENT_REQUESTED, (event) => {
+          if (!isMounted) return;
+          setRequests((prev) =>
+            prev.some((r) => r.id === event.payload.id) ? prev : [...prev, event.payload]
+          );
+        }),
+        await listen<ConsentResolved>(EVENT_CONSENT_RESOLVED, (event) => {
+          if (!isMounted) return;
+          setRequests((prev) => prev.filter((r) => r.id !== event.payload.id));
+        })
+      );
+
+      try {
+        const pending = await consent.listRequests();
+        if (isMounted) {
+          setRequests(pending);
+        }
+      } catch (error) {
+        console.error("[Consent] 获取待确认请求失败:", error);
+      }
+    };
+
+    setup();
+
+    return () => {
+      isMounted = false;
+      unlisteners.forEach((unlisten) => unlisten());
+    };
+  }, []);
+
+  const handleRespond = useCallback(async (request: ConsentRequest, decision: ConsentDecision) => {
+    try {
+      await consent.respond(request.id, decision);
+    } catch (error) {
+      console.error("[Consent] 回复失败:", error);
+    } finally {
+      setRequests((prev) => prev.filter((r) => r.id !== request.id));
+    }
+  }, []);
+
+  if (requests.length === 0) {
+    return null;
+  }
+
+  return (
+    <div className={cn("space-y-2", className)}>
+      {requests.map((request) => (
+        <ConsentCard
+          key={request.id}
+          request={request}
+          onRespond={(decision) => handleRespond(request, decision)}
+        />
+      ))}
+    </div>
+  );
+}
+
+interface ConsentCardProps {
+  request: ConsentRequest;
+  onRespond: (decision: ConsentDecision) => void;
+}
+
+/**
+ * 单个确认请求卡片
+ */
+function ConsentCard({ request, onRespond }: ConsentCardProps) {
+  const [isResponding, setIsResponding] = useState(false);
+
+  const handleRespond = useCallback((decision: ConsentDecision) => {
+    if (isResponding) return;
+    setIsResponding(true);
+    onRespond(decision);
+  }, [isResponding, onRespond]);
+
+  const title = request.action === "file_delete" ? "Agent 请求删除文件" : "Agent 请求执行命令";
+
+  return (
+    <div
+      className={cn(
+        "flex flex-col gap-2 p-3",
+        "bg-destructive/10 border border-destructive/30 rounded-lg",
+        "animate-in fade-in slide-in-from-bottom-2 duration-200"
+      )}
+    >
+      {/* 标题和命令 */}
+      <div className="flex items-start gap-2">
+        <ShieldAlert className="h-5 w-5 text-destructive shrink-0 mt-0.5" />
+        <div className="flex-1 min-w-0">
+          <div className="text-sm font-medium text-foreground">
+            {title}
+          </div>
+          <pre className="text-xs text-muted-foreground mt-1 whitespace-pre-wrap break-all font-mono">
+            {request.summary}
+          </pre>
+        </div>
+      </div>
+
+      {/* 操作按钮 */}
+      <div className="flex items-center gap-1.5 justify-end">
+        {/* 拒绝 */}
+        <Button
+          variant="ghost"
+          size="sm"
+          className="h-7 px-2.5 text-xs text-muted-foreground hover:text-destructive hover:bg-destructive/10"
+          onClick={() => handleRespond("deny")}
+          disabled={isResponding}
+        >
+          <X className="h-3.5 w-3.5 mr-1" />
+          拒绝
+        </Button>
+
+        {/* 始终允许（仅在能确定项目时可记住） */}
+        {request.project && (
+          <Button
+            variant="outline"
+            size="sm"
+            className="h-7 px-2.5 text-xs"
+            title={`在 ${request.project} 中不再询问同类操作`}
+            onClick={() => handleRespond("always_allow")}
+            disabled={isResponding}
+          >
+            <Zap className="h-3.5 w-3.5 mr-1" />
+            始终允许
+          </Button>
+        )}
+
+        {/* 允许一次 */}
+        <Button
+          variant="default"
+          size="sm"
+          className="h-7 px-2.5 text-xs"
+          onClick={() => handleRespond("allow_once")}
+          disabled={isResponding}
+        >
+          <Check className="h-3.5 w-3.5 mr-1" />
+          允许一次
+        </Button>
+      </div>
+    </div>
+  );
+}
+
+export default ConsentPrompt;
@@ -13,8 +13,10 @@
 import { useState, useEffect, useMemo } from "react";
 import { cn } from "@/lib/utils";
 import { computeDiff } from "./api";
+import { jobs } from "@/services/tauri";
 import type { DiffResult, DiffLine, DiffHunk } from "./types";
 import { Loader2 } from "lucide-react";
+import { getErrorMessage } from "@/types/error";
 
 // ============== 类型定义 ==============
 
@@ -56,19 +58,20 @@ export function DiffViewer({
   // 计算差异
   useEffect(() => {
     let cancelled = false;
+    const jobId = `diff-${Date.now()}-${Math.random().toString(36).slice(2, 9)}`;
 
     async function calculate() {
       setLoading(true);
       setError(null);
 
       try {
-        const result = await computeDiff(oldText, newText, fileName, contextLines);
+        const result = await computeDiff(oldText, newText, fileName, contextLines, jobId);
         if (!cancelled) {
           setDiffResult(result);
         }
       } catch (e) {
         if (!cancelled) {
-          setError(e instanceof Error ? e.message : "计算差异时发生错误");
+          setError(getErrorMessage(e, "计算差异时发生错误"));
         }
       } finally {
         if (!cancelled) {
@@ -81,6 +84,8 @@ export function DiffViewer({
 
     return () => {
       cancelled = true;
+      // 内容已变化，取消仍在进行的计算
+      void jobs.cancel(jobId).catch(() => {});
     };
   }, [oldText, newText, fileName, contextLines]);
 
@@ -3,7 +3,13 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import type { DiffResult, DiffStats } from "./types";
+import type {
+  BinaryDiffSummary,
+  DiffResult,
+  DiffStats,
+  PartialApplyResult,
+  StructuralDiffResult,
+} from "./types";
 
 /**
  * 计算两个文本之间的差异
@@ -12,19 +18,22 @@ import type { DiffResult, DiffStats } from "./types";
  * @param newText - 新文本内容
  * @param fileName - 可选的文件名
  * @param contextLines - 上下文行数（默认3行）
+ * @param jobId - 可选的任务 ID，可通过 jobs.cancel(jobId) 取消计算
  * @returns 差异结果
  */
 export async function computeDiff(
   oldText: string,
   newText: string,
   fileName?: string,
-  contextLines?: number
+  contextLines?: number,
+  jobId?: string
 ): Promise<DiffResult> {
   return invoke<DiffResult>("compute_diff", {
     oldText,
     newText,
     fileName: fileName ?? null,
     contextLines: contextLines ?? null,
+    jobId: jobId ?? null,
   });
 }
 
@@ -71,6 +80,85 @@ export async function computeDiffStats(
   });
 }
 
+/**
+ * 按语法块对比两个文本
+ *
+ * 语言不支持、文本过大或存在语法错误时回退到行级对比（mode 为 "line"）
+ *
+ * @param oldText - 旧文本内容
+ * @param newText - 新文本内容
+ * @param fileName - 文件名，用于识别语言
+ * @param contextLines - 块内行级差异的上下文行数（默认3行）
+ * @returns 结构化对比结果
+ */
+export async function computeStructuralDiff(
+  oldText: string,
+  newText: string,
+  fileName?: string,
+  contextLines?: number
+): Promise<StructuralDiffResult> {
+  return invoke<StructuralDiffResult>("compute_structural_diff", {
+    oldText,
+    newText,
+    fileName: fileName ?? null,
+    contextLines: contextLines ?? null,
+  });
+}
+
+/**
+ * 计算二进制文件的差异摘要
+ *
+ * 用于无法按文本对比的文件，一侧不存在时视为新增或删除
+ *
+ * @param oldPath - 旧文件路径
+ * @param newPath - 新文件路径
+ * @returns 大小变化、变化字节占比及图片尺寸 / 格式变化
+ */
+export async function computeBinaryDiffSummary(
+  oldPath: string,
+  newPath: string
+): Promise<BinaryDiffSummary> {
+  return invoke<BinaryDiffSummary>("compute_binary_diff_summary", {
+    oldPath,
+    newPath,
+  });
+}
+
+/**
+ * 解析 unified diff 文本（git 输出或 LLM 生成的补丁）
+ *
+ * @param patchText - unified diff 文本
+ * @returns 每个文件一个差异结果，可直接交给 DiffViewer 渲染
+ */
+export async function parseUnifiedDiff(
+  patchText: string
+): Promise<DiffResult[]> {
+  return invoke<DiffResult[]>("parse_unified_diff", { patchText });
+}
+
+/**
+ * 只应用选中的 hunk
+ *
+ * @param oldText - 旧文本内容
+ * @param newText - 新文本内容
+ * @param hunkIndices - 要应用的 hunk 序号（从 0 开始，对应 computeDiff 返回的 hunks）
+ * @param contextLines - 上下文行数（默认3行），需与 computeDiff 一致
+ * @returns 部分应用后的内容及其反向结果
+ */
+export async function applySelectedHunks(
+  oldText: string,
+  newText: string,
+  hunkIndices: number[],
+  contextLines?: number
+): Promise<PartialApplyResult> {
+  return invoke<PartialApplyResult>("apply_selected_hunks", {
+    oldText,
+    newText,
+    hunkIndices,
+    contextLines: contextLines ?? null,
+  });
+}
+
 /**
  * 快速检查两个文本是否相同
  *
@@ -9,7 +9,16 @@
 export { DiffViewer, DiffStatsDisplay } from "./DiffViewer";
 
 // API
-export { computeDiff, computeUnifiedDiff, computeDiffStats, textsAreEqual } from "./api";
+export {
+  computeDiff,
+  computeUnifiedDiff,
+  computeDiffStats,
+  applySelectedHunks,
+  computeStructuralDiff,
+  computeBinaryDiffSummary,
+  parseUnifiedDiff,
+  textsAreEqual,
+} from "./api";
 
 // 类型
 export type {
@@ -18,4 +27,12 @@ export type {
   DiffHunk,
   DiffResult,
   DiffStats,
+  PartialApplyResult,
+  DiffMode,
+  BlockChangeKind,
+  BlockChange,
+  LineRange,
+  StructuralDiffResult,
+  BinaryImageInfo,
+  BinaryDiffSummary,
 } from "./types";
@@ -55,3 +55,90 @@ export interface DiffStats {
   /** 是否有变更 */
   hasChanges: boolean;
 }
+
+/** 部分应用 hunk 的结果 */
+export interface PartialApplyResult {
+  /** 在旧文本上只应用选中 hunk 后的内容 */
+  content: string;
+  /** 在新文本上只撤销选中 hunk 后的内容 */
+  inverseContent: string;
+  /** hunk 总数 */
+  hunkCount: number;
+}
+
+/** 对比模式 */
+export type DiffMode = "structural" | "line";
+
+/** 语法块的变化类型 */
+export type BlockChangeKind =
+  | "unchanged"
+  | "reformatted"
+  | "renamed"
+  | "modified"
+  | "added"
+  | "removed";
+
+/** 行范围（从 1 开始，包含结束行） */
+export interface LineRange {
+  start: number;
+  end: number;
+}
+
+/** 单个语法块的变化 */
+export interface BlockChange {
+  kind: BlockChangeKind;
+  /** tree-sitter 节点类型，如 function_item */
+  nodeKind: string;
+  oldName: string | null;
+  newName: string | null;
+  oldRange: LineRange | null;
+  newRange: LineRange | null;
+  /** 与其他块的相对顺序发生了变化 */
+  moved: boolean;
+  /** 重命名 / 修改的块内的行级差异 */
+  hunks: DiffHunk[];
+  /** 容器块内部按成员的变化 */
+  children: BlockChange[];
+}
+
+/** 结构化对比结果 */
+export interface StructuralDiffResult {
+  /** 实际使用的对比模式 */
+  mode: DiffMode;
+  language: string | null;
+  /** structural 模式下的块变化 */
+  blocks: BlockChange[];
+  /** line 模式下的行级差异 */
+  lineDiff: DiffResult | null;
+  /** 回退到行级对比的原因 */
+  fallbackReason: string | null;
+  hasChanges: boolean;
+}
+
+/** 图片信息（与 get_image_info 一致） */
+export interface BinaryImageInfo {
+  width: number;
+  height: number;
+  format: string;
+  mime_type: string;
+  file_size: number;
+}
+
+/** 二进制文件的差异摘要 */
+export interface BinaryDiffSummary {
+  /** 文件大小，文件不存在时为空 */
+  oldSize: number | null;
+  newSize: number | null;
+  /** 新文件相对旧文件的大小变化 */
+  sizeDelta: number;
+  /** 内容完全相同 */
+  identical: boolean;
+  /** 变化字节占比（0-100） */
+  changedPercent: number;
+  oldImage: BinaryImageInfo | null;
+  newImage: BinaryImageInfo | null;
+  /** 两侧都是图片时尺寸是否变化 */
+  dimensionsChanged: boolean | null;
+  /** 两侧都是图片时格式是否变化 */
+  formatChanged: boolean | null;
+}
@@ -48,6 +48,7 @@ import {
 } from "@/utils/fileType";
 import { MonacoViewer } from "./MonacoViewer";
 import { ImageViewer } from "./ImageViewer";
+import { getErrorMessage } from "@/types/error";
 
 // 懒加载文档预览组件，优化性能
 const PdfViewer = lazy(() => import("./PdfViewer").then(m => ({ default: m.PdfViewer })));
@@ -456,7 +457,7 @@ const {
         // 刷新完成后清除外部修改标记
         clearExternallyModified(path);
       } catch (error) {
-        setFileError(path, String(error));
+        setFileError(path, getErrorMessage(error));
       } finally {
         loadingPathsRef.current.delete(path);
       }
@@ -508,7 +509,7 @@ const {
       saveRetryCountRef.current.set(path, retryCount + 1);
       
       const fileName = path.split(/[/\\]/).pop() || path;
-      toast.error(t("editor.saveFailed", { name: fileName, error: String(error) }));
+      toast.error(t("editor.saveFailed", { name: fileName, error: getErrorMessage(error) }));
     }
   }, [setFileSaving, markAsSaved, t]);
 
@@ -0,0 +1,91 @@
+/**
+ * 快速提问窗口
+ *
+ * 由全局快捷键唤起的置顶小窗口，回车后把内容发送到主窗口的当前会话，
+ * Esc 或失去焦点时隐藏
+ */
+
+import { useState, useEffect, useRef, useCallback, type KeyboardEvent } from "react";
+import { getCurrentWindow } from "@tauri-apps/api/window";
+import { Sparkles, Loader2 } from "lucide-react";
+import { hotkeys } from "@/services/tauri";
+import { getErrorMessage } from "@/types/error";
+import { cn } from "@/lib/utils";
+
+export function QuickPromptWindow() {
+  const [text, setText] = useState("");
+  const [isSending, setIsSending] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  // 每次窗口获得焦点时清空并聚焦输入框
+  useEffect(() => {
+    inputRef.current?.focus();
+    const unlisten = getCurrentWindow().onFocusChanged(({ payload: focused }) => {
+      if (focused) {
+        setError(null);
+        inputRef.current?.focus();
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleSubmit = useCallback(async () => {
+    const content = text.trim();
+    if (!content || isSending) return;
+
+    setIsSending(true);
+    try {
+      await hotkeys.submitQuickPrompt(content);
+      setText("");
+      setError(null);
+    } catch (err) {
+      setError(getErrorMessage(err));
+    } finally {
+      setIsSending(false);
+    }
+  }, [text, isSending]);
+
+  const handleKeyDown = useCallback((e: KeyboardEvent<HTMLInputElement>) => {
+    if (e.key === "Enter" && !e.nativeEvent.isComposing) {
+      e.preventDefault();
+      handleSubmit();
+    } else if (e.key === "Escape") {
+      e.preventDefault();
+      hotkeys.hideQuickPrompt();
+    }
+  }, [handleSubmit]);
+
+  return (
+    <div
+      className={cn(
+        "flex h-screen items-center gap-3 px-4",
+        "bg-background border border-border rounded-lg"
+      )}
+      data-tauri-drag-region
+    >
+      {isSending ? (
+        <Loader2 className="h-5 w-5 shrink-0 animate-spin text-muted-foreground" />
+      ) : (
+        <Sparkles className="h-5 w-5 shrink-0 text-primary" />
+      )}
+      <input
+        ref={inputRef}
+        value={text}
+        onChange={(e) => setText(e.target.value)}
+        onKeyDown={handleKeyDown}
+        placeholder={error ?? "向 Axon 提问，回车发送到当前会话"}
+        className={cn(
+          "flex-1 bg-transparent text-base outline-none",
+          error ? "placeholder:text-destructive" : "placeholder:text-muted-foreground"
+        )}
+        disabled={isSending}
+        autoFocus
+      />
+    </div>
+  );
+}
+
+export default QuickPromptWindow;
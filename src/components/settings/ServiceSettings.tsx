@@ -244,6 +244,17 @@ export function ServiceSettings() {
     }
   };
 
+  const handleMinimizeToTrayChange = async (enabled: boolean) => {
+    try {
+      await tauriSettings.setMinimizeToTray(enabled);
+      setAppSettings((prev) => prev ? { ...prev, minimizeToTray: enabled } : null);
+      toast.success(t("notifications.settingsSaved"));
+    } catch (error) {
+      console.error("Failed to save minimize to tray setting:", error);
+      toast.error(t("errors.unknownError"));
+    }
+  };
+
   return (
     <div className="space-y-6">
       {/* 页面标题 */}
@@ -500,6 +511,20 @@ export function ServiceSettings() {
                 onCheckedChange={handleAutoUpdateChange}
               />
             </div>
+
+            {/* 最小化到托盘 */}
+            <div className="flex items-center justify-between rounded-lg border border-border/50 p-3.5 bg-surface-1/50">
+              <div className="space-y-0.5">
+                <p className="text-sm font-medium">{t("settings.serviceSettings.minimizeToTray")}</p>
+                <p className="text-xs text-muted-foreground/70">
+                  {t("settings.serviceSettings.minimizeToTrayDescription")}
+                </p>
+              </div>
+              <Switch
+                checked={appSettings?.minimizeToTray ?? false}
+                onCheckedChange={handleMinimizeToTrayChange}
+              />
+            </div>
           </CardContent>
         </Card>
       )}
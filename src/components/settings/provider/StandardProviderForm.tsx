@@ -19,6 +19,7 @@ import { cn } from "@/lib/utils";
 import type { UserProviderConfig, CustomConfig, ProviderAuthMethod, OAuthAuthorization } from "@/types/provider";
 import type { OpencodeClient } from "@/services/opencode/types";
 import { openUrl } from "@tauri-apps/plugin-opener";
+import { getErrorMessage } from "@/types/error";
 
 interface StandardProviderFormProps {
   registryId: string;
@@ -176,7 +177,7 @@ export function StandardProviderForm({
           } catch (error) {
             console.error("OAuth callback 失败:", error);
             setOAuthStatus("error");
-            setOAuthErrorMessage(String(error));
+            setOAuthErrorMessage(getErrorMessage(error));
           }
         }
       } else {
@@ -186,7 +187,7 @@ export function StandardProviderForm({
     } catch (error) {
       console.error("OAuth 授权失败:", error);
       setOAuthStatus("error");
-      setOAuthErrorMessage(String(error));
+      setOAuthErrorMessage(getErrorMessage(error));
     }
   };
 
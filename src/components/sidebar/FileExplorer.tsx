@@ -39,6 +39,7 @@ import {
   FilePlus,
   FolderPlus,
 } from "lucide-react";
+import { getErrorMessage } from "@/types/error";
 
 // ============== 类型定义 ==============
 
@@ -480,7 +481,7 @@ export function FileExplorer({
       setLoadedChildren(new Map([[rootPath, children]]));
       setExpandedPaths(new Set([rootPath]));
     } catch (e) {
-      setError(String(e));
+      setError(getErrorMessage(e));
     } finally {
       setIsLoading(false);
     }
@@ -30,13 +30,22 @@ const DEFAULT_TERMINAL_COLORS = {
 };
 
 export function TerminalPanel({ className }: TerminalPanelProps) {
-  const { isVisible, isLoading, error, tabs, createTab, updateTab } =
-    useTerminal();
+  const {
+    isVisible,
+    isLoading,
+    error,
+    tabs,
+    createTab,
+    updateTab,
+    persistSessions,
+    restoreTabs,
+  } = useTerminal();
   const { tab } = useActiveTerminal();
   const { endpoint, client } = useTerminalConnection();
   const { isDark } = useTheme();
 
   const isAutoCreatingRef = useRef(false);
+  const hasTriedRestoreRef = useRef(false);
 
   const terminalBackground = isDark
     ? DEFAULT_TERMINAL_COLORS.dark.background
@@ -51,16 +60,32 @@ export function TerminalPanel({ className }: TerminalPanelProps) {
         cols: updatedTab.cols,
         scrollY: updatedTab.scrollY,
       });
+      persistSessions();
     },
-    [updateTab]
+    [updateTab, persistSessions]
   );
 
+  // 关闭窗口前保存终端会话
+  useEffect(() => {
+    const handleBeforeUnload = () => {
+      persistSessions();
+    };
+    window.addEventListener("beforeunload", handleBeforeUnload);
+    return () => window.removeEventListener("beforeunload", handleBeforeUnload);
+  }, [persistSessions]);
+
   // 当终端面板可见但没有终端时，自动创建一个
   useEffect(() => {
     if (isAutoCreatingRef.current) return;
     if (isVisible && tabs.length === 0 && !isLoading && endpoint && client) {
       isAutoCreatingRef.current = true;
-      createTab()
+      // 首次打开时优先恢复上次的会话
+      const restore = hasTriedRestoreRef.current
+        ? Promise.resolve(0)
+        : restoreTabs();
+      hasTriedRestoreRef.current = true;
+      restore
+        .then((restored) => (restored > 0 ? undefined : createTab()))
         .catch((e) => {
           console.error("[TerminalPanel] 自动创建终端失败:", e);
         })
@@ -68,7 +93,7 @@ export function TerminalPanel({ className }: TerminalPanelProps) {
           isAutoCreatingRef.current = false;
         });
     }
-  }, [isVisible, tabs.length, isLoading, createTab, endpoint, client]);
+  }, [isVisible, tabs.length, isLoading, createTab, restoreTabs, endpoint, client]);
 
   if (!isVisible) {
     return null;
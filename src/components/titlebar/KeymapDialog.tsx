@@ -0,0 +1,59 @@
+/**
+ * 快捷键列表弹窗（菜单栏「帮助 > 快捷键」）
+ */
+
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogHeader,
+  DialogTitle,
+} from "@/components/ui/dialog";
+import { keymap, type KeymapEntry } from "@/services/tauri";
+
+interface KeymapDialogProps {
+  open: boolean;
+  onOpenChange: (open: boolean) => void;
+}
+
+export function KeymapDialog({ open, onOpenChange }: KeymapDialogProps) {
+  const { t } = useTranslation();
+  const [entries, setEntries] = useState<KeymapEntry[]>([]);
+
+  // 每次打开时重新读取，反映最新的修改
+  useEffect(() => {
+    if (!open) return;
+    keymap
+      .get()
+      .then(setEntries)
+      .catch((error) => {
+        console.error("[KeymapDialog] 读取快捷键失败:", error);
+      });
+  }, [open]);
+
+  return (
+    <Dialog open={open} onOpenChange={onOpenChange}>
+      <DialogContent className="max-w-lg max-h-[80vh] overflow-hidden flex flex-col">
+        <DialogHeader>
+          <DialogTitle>{t("titlebar.keymap.title")}</DialogTitle>
+          <DialogDescription>{t("titlebar.keymap.description")}</DialogDescription>
+        </DialogHeader>
+        <div className="flex-1 overflow-y-auto">
+          {entries.map((entry) => (
+            <div
+              key={entry.action}
+              className="flex items-center justify-between gap-4 py-1.5 text-sm"
+            >
+              <span className="truncate">{entry.description}</span>
+              <kbd className="shrink-0 rounded border border-border bg-muted px-1.5 py-0.5 font-mono text-xs text-muted-foreground">
+                {entry.accelerator ?? t("titlebar.keymap.unbound")}
+              </kbd>
+            </div>
+          ))}
+        </div>
+      </DialogContent>
+    </Dialog>
+  );
+}
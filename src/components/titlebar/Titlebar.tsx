@@ -8,11 +8,15 @@
 
 import { useState, useCallback, useEffect } from "react";
 import { useTranslation } from "react-i18next";
+import { listen } from "@tauri-apps/api/event";
+import { useNavigate } from "@tanstack/react-router";
+import { toast } from "sonner";
 import { FolderOpen, ChevronDown, PanelRight } from "lucide-react";
 import { WindowControls } from "./WindowControls";
 import { ThemeToggle } from "./ThemeToggle";
 import { ServiceStatus } from "./ServiceStatus";
 import { ProjectPicker } from "./ProjectPicker";
+import { KeymapDialog } from "./KeymapDialog";
 import { AxonLogo } from "@/components/icons";
 import { Separator } from "@/components/ui/separator";
 import {
@@ -22,20 +26,34 @@ import {
   TooltipTrigger,
 } from "@/components/ui/tooltip";
 import { useChat } from "@/providers/ChatProvider";
+import { useAppUpdater } from "@/hooks/useAppUpdater";
+import {
+  opencode as tauriOpencode,
+  appMenu,
+  EVENT_MENU_ACTION,
+  type MenuAction,
+} from "@/services/tauri";
 import { useWorkspace } from "@/stores/workspace";
 import { useProjectContext } from "@/providers/ProjectProvider";
 import { useSubagentPanelStore } from "@/stores/subagentPanel";
+import { useActivityBar } from "@/stores/activityBar";
+import { useTerminal } from "@/stores/terminal";
+import { useEditor } from "@/stores/editor";
 import { cn } from "@/lib/utils";
 
 export function Titlebar() {
   const { t } = useTranslation();
-  const { activeSession, createNewSession } = useChat();
+  const navigate = useNavigate();
+  const { activeSession, createNewSession, selectAgent } = useChat();
   const { getDisplayPath, state: workspaceState, openDirectoryPicker } = useWorkspace();
   const { projects, openProject } = useProjectContext();
   const { isOpen: isPanelOpen, togglePanel } = useSubagentPanelStore();
+  const { checkUpdate, stageUpdate } = useAppUpdater();
 
   // 项目选择器状态
   const [pickerOpen, setPickerOpen] = useState(false);
+  // 快捷键列表弹窗状态
+  const [keymapOpen, setKeymapOpen] = useState(false);
 
 
 
@@ -61,6 +79,41 @@ export function Titlebar() {
     return () => window.removeEventListener("keydown", handleKeyDown);
   }, []);
 
+  // 检查更新（托盘菜单和菜单栏共用）
+  const handleCheckUpdates = useCallback(async () => {
+    const info = await checkUpdate();
+    if (!info) {
+      toast.error(t("titlebar.tray.checkFailed"));
+    } else if (!info.available) {
+      toast.success(t("titlebar.tray.upToDate"));
+    } else {
+      toast.info(t("titlebar.tray.updateAvailable", { version: info.new_version }), {
+        action: {
+          label: t("titlebar.tray.installOnExit"),
+          onClick: async () => {
+            if (await stageUpdate()) {
+              toast.success(t("titlebar.tray.updateStaged"));
+            }
+          },
+        },
+      });
+    }
+  }, [t, checkUpdate, stageUpdate]);
+
+  // 托盘菜单中需要界面交互的操作
+  useEffect(() => {
+    const unlisten = listen<string>("tray:action", (event) => {
+      if (event.payload === "open_project") {
+        setPickerOpen(true);
+      } else if (event.payload === "check_updates") {
+        handleCheckUpdates();
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [handleCheckUpdates]);
+
   // 处理选择项目
   // OpenCode 采用 API 级别的 directory 参数设计，不需要重启服务
   // 每个 API 调用都会传入 directory，OpenCode 会自动加载对应目录的配置
@@ -73,6 +126,99 @@ export function Titlebar() {
     await createNewSession(directory);
   }, [openProject, createNewSession]);
 
+  // 菜单栏的「最近项目」与项目列表保持一致
+  useEffect(() => {
+    const recent = [...projects]
+      .sort((a, b) => b.updatedAt - a.updatedAt)
+      .map(({ name, directory }) => ({ name, directory }));
+    appMenu.setRecentProjects(recent).catch((error) => {
+      console.error("[Titlebar] 同步菜单栏最近项目失败:", error);
+    });
+  }, [projects]);
+
+  // 菜单栏操作（与标题栏按钮、活动栏和各面板走同一处理）
+  useEffect(() => {
+    const unlisten = listen<MenuAction>(EVENT_MENU_ACTION, async (event) => {
+      const { action, argument } = event.payload;
+      switch (action) {
+        case "project.open":
+          setPickerOpen(true);
+          break;
+        case "project.openRecent":
+          if (argument) handleSelectProject(argument);
+          break;
+        case "session.new":
+          createNewSession();
+          break;
+        case "agent.open":
+          // argument 为 Agent 名称：切换 Agent 后在聊天页新建会话
+          if (argument) {
+            navigate({ to: "/" });
+            selectAgent(argument);
+            await createNewSession();
+          }
+          break;
+        case "agent.new":
+          navigate({ to: "/orchestration", search: { create: true } });
+          break;
+        case "agent.manage":
+          navigate({ to: "/orchestration" });
+          break;
+        case "settings.open":
+          navigate({ to: "/settings" });
+          break;
+        case "tab.close": {
+          const { activeTabPath, closeTab } = useEditor.getState();
+          if (activeTabPath) closeTab(activeTabPath);
+          break;
+        }
+        case "view.toggleSidebar":
+          useActivityBar.getState().toggleSidebarVisible();
+          break;
+        case "view.toggleTerminal":
+          useTerminal.getState().toggleVisible();
+          break;
+        case "help.keymap":
+          setKeymapOpen(true);
+          break;
+        case "help.checkUpdates":
+          handleCheckUpdates();
+          break;
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [navigate, handleSelectProject, createNewSession, selectAgent, handleCheckUpdates]);
+
+  // 拖放文件夹到窗口时，后端已保存项目目录，这里切换到该项目
+  useEffect(() => {
+    const unlisten = listen<{ path: string; restartRecommended: boolean }>(
+      "project:opened",
+      async (event) => {
+        const { path, restartRecommended } = event.payload;
+        await handleSelectProject(path);
+        if (restartRecommended) {
+          toast.info(t("titlebar.projectDropped", { path: getDisplayPath(path) }), {
+            description: t("titlebar.restartServiceHint"),
+            action: {
+              label: t("titlebar.restartService"),
+              onClick: () => {
+                tauriOpencode.restart().catch((error) => {
+                  console.error("[Titlebar] 重启服务失败:", error);
+                  toast.error(t("errors.unknownError"));
+                });
+              },
+            },
+          });
+        }
+      }
+    );
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [t, handleSelectProject, getDisplayPath]);
+
   // 处理打开目录选择器
   const handleOpenDirectoryPicker = useCallback(async () => {
     const directory = await openDirectoryPicker();
@@ -174,6 +320,9 @@ export function Titlebar() {
         onSelectProject={handleSelectProject}
         onOpenDirectoryPicker={handleOpenDirectoryPicker}
       />
+
+      {/* 快捷键列表（菜单栏「帮助 > 快捷键」） */}
+      <KeymapDialog open={keymapOpen} onOpenChange={setKeymapOpen} />
     </>
   );
 }
@@ -13,6 +13,7 @@ import {
   ReactFlow,
   Background,
   Controls,
+  ControlButton,
   MiniMap,
   useNodesState,
   useEdgesState,
@@ -26,6 +27,7 @@ import {
   Panel,
 } from "@xyflow/react";
 import "@xyflow/react/dist/style.css";
+import { Network } from "lucide-react";
 
 import { cn } from "@/lib/utils";
 import { useWorkflowStore } from "@/stores/workflow";
@@ -177,6 +179,7 @@ export function WorkflowCanvas({
     updateSubagentPosition,
     toggleSubagentEnabled,
     updateViewport,
+    autoLayoutCurrentWorkflow,
   } = useWorkflowStore();
   
   const { agents } = useOrchestrationStore();
@@ -351,7 +354,13 @@ export function WorkflowCanvas({
             "[&>button]:!bg-background [&>button]:!border-border/50",
             "[&>button]:!text-muted-foreground [&>button:hover]:!bg-accent"
           )}
-        />
+        >
+          {!readOnly && (
+            <ControlButton onClick={() => autoLayoutCurrentWorkflow()} title="自动布局">
+              <Network />
+            </ControlButton>
+          )}
+        </Controls>
         
         {/* 小地图 */}
         <MiniMap
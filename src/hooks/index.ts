@@ -1,4 +1,5 @@
 export { useServiceStatus } from "./useServiceStatus";
+export { useBootstrapStatus } from "./useBootstrapStatus";
 export { useOpencode } from "./useOpencode";
 export {
   useLspStatus,
@@ -1,7 +1,22 @@
 // 应用更新检查 Hook
 
-import { useState, useCallback } from 'react';
+import { useState, useCallback, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { getErrorMessage } from "@/types/error";
+
+export type UpdateChannel = 'stable' | 'beta';
+
+/** 更新下载进度事件 */
+export const EVENT_APP_UPDATE_PROGRESS = 'app-update:progress';
+
+export interface AppUpdateProgress {
+  version: string;
+  downloaded: number;
+  total?: number;
+  percentage?: number;
+  finished: boolean;
+}
 
 export interface UpdateInfo {
   available: boolean;
@@ -9,12 +24,17 @@ export interface UpdateInfo {
   new_version?: string;
   update_notes?: string;
   download_progress: number;
+  channel: UpdateChannel;
 }
 
 interface UseAppUpdaterState {
   updateInfo: UpdateInfo | null;
   isChecking: boolean;
   isInstalling: boolean;
+  isStaging: boolean;
+  /** 已下载、退出时安装的版本 */
+  stagedVersion: string | null;
+  progress: AppUpdateProgress | null;
   error: string | null;
 }
 
@@ -23,9 +43,22 @@ export const useAppUpdater = () => {
     updateInfo: null,
     isChecking: false,
     isInstalling: false,
+    isStaging: false,
+    stagedVersion: null,
+    progress: null,
     error: null,
   });
 
+  // 监听下载进度
+  useEffect(() => {
+    const unlisten = listen<AppUpdateProgress>(EVENT_APP_UPDATE_PROGRESS, (event) => {
+      setState(prev => ({ ...prev, progress: event.payload }));
+    });
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
   // 检查更新
   const checkUpdate = useCallback(async () => {
     setState(prev => ({ ...prev, isChecking: true, error: null }));
@@ -38,7 +71,7 @@ export const useAppUpdater = () => {
       }));
       return info;
     } catch (err) {
-      const error = err instanceof Error ? err.message : String(err);
+      const error = getErrorMessage(err);
       setState(prev => ({
         ...prev,
         isChecking: false,
@@ -61,7 +94,7 @@ export const useAppUpdater = () => {
       await invoke<void>('install_app_update');
       return true;
     } catch (err) {
-      const error = err instanceof Error ? err.message : String(err);
+      const error = getErrorMessage(err);
       setState(prev => ({
         ...prev,
         isInstalling: false,
@@ -72,6 +105,87 @@ export const useAppUpdater = () => {
     }
   }, [state.updateInfo]);
 
+  // 立即下载，退出应用时安装
+  const stageUpdate = useCallback(async () => {
+    setState(prev => ({ ...prev, isStaging: true, error: null }));
+    try {
+      const version = await invoke<string>('stage_app_update');
+      setState(prev => ({ ...prev, isStaging: false, stagedVersion: version }));
+      return version;
+    } catch (err) {
+      const error = getErrorMessage(err);
+      setState(prev => ({
+        ...prev,
+        isStaging: false,
+        error,
+      }));
+      console.error('下载更新失败:', error);
+      return null;
+    }
+  }, []);
+
+  // 取消进行中的下载
+  const cancelDownload = useCallback(async () => {
+    try {
+      const cancelled = await invoke<boolean>('cancel_app_update');
+      if (cancelled) {
+        setState(prev => ({ ...prev, isInstalling: false, isStaging: false, progress: null }));
+      }
+      return cancelled;
+    } catch (err) {
+      console.error('取消更新下载失败:', err);
+      return false;
+    }
+  }, []);
+
+  // 刷新已下载的更新
+  const refreshStagedUpdate = useCallback(async () => {
+    try {
+      const version = await invoke<string | null>('get_staged_app_update');
+      setState(prev => ({ ...prev, stagedVersion: version }));
+      return version;
+    } catch (err) {
+      console.error('获取已下载更新失败:', err);
+      return null;
+    }
+  }, []);
+
+  // 放弃已下载的更新
+  const discardStagedUpdate = useCallback(async () => {
+    try {
+      await invoke<boolean>('discard_staged_app_update');
+      setState(prev => ({ ...prev, stagedVersion: null }));
+      return true;
+    } catch (err) {
+      console.error('放弃已下载更新失败:', err);
+      return false;
+    }
+  }, []);
+
+  // 获取更新通道
+  const getChannel = useCallback(async () => {
+    try {
+      return await invoke<UpdateChannel>('get_update_channel');
+    } catch (err) {
+      console.error('获取更新通道失败:', err);
+      return null;
+    }
+  }, []);
+
+  // 切换更新通道
+  const setChannel = useCallback(async (channel: UpdateChannel) => {
+    try {
+      await invoke<void>('set_update_channel', { channel });
+      setState(prev => ({ ...prev, updateInfo: null }));
+      return true;
+    } catch (err) {
+      const error = getErrorMessage(err);
+      setState(prev => ({ ...prev, error }));
+      console.error('切换更新通道失败:', error);
+      return false;
+    }
+  }, []);
+
   // 获取当前版本
   const getCurrentVersion = useCallback(async () => {
     try {
@@ -107,6 +221,12 @@ export const useAppUpdater = () => {
     ...state,
     checkUpdate,
     installUpdate,
+    stageUpdate,
+    cancelDownload,
+    refreshStagedUpdate,
+    discardStagedUpdate,
+    getChannel,
+    setChannel,
     cancelUpdate,
     getCurrentVersion,
     startAutoCheck,
@@ -0,0 +1,49 @@
+/**
+ * 启动进度 Hook
+ *
+ * 订阅后端的 bootstrap:* 事件，挂载时先查询一次当前状态，
+ * 避免错过挂载前已经发送的事件
+ */
+
+import { useEffect, useState } from "react";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import {
+  diagnostics,
+  BOOTSTRAP_EVENTS,
+  type BootstrapStatus,
+} from "@/services/tauri";
+
+export function useBootstrapStatus(): BootstrapStatus | null {
+  const [status, setStatus] = useState<BootstrapStatus | null>(null);
+
+  useEffect(() => {
+    let isMounted = true;
+    const unlisteners: UnlistenFn[] = [];
+
+    const setup = async () => {
+      for (const event of BOOTSTRAP_EVENTS) {
+        unlisteners.push(
+          await listen<BootstrapStatus>(event, (e) => {
+            if (isMounted) setStatus(e.payload);
+          })
+        );
+      }
+
+      try {
+        const current = await diagnostics.getBootstrapStatus();
+        if (isMounted) setStatus(current);
+      } catch (error) {
+        console.error("[Bootstrap] 获取启动状态失败:", error);
+      }
+    };
+
+    setup();
+
+    return () => {
+      isMounted = false;
+      unlisteners.forEach((unlisten) => unlisten());
+    };
+  }, []);
+
+  return status;
+}
@@ -1,6 +1,17 @@
 import { useState, useEffect, useCallback, useMemo } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { ModelDefaults, ModelsRegistryCacheInfo } from "@/types/modelsRegistry";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  ModelDefaults,
+  ModelsRegistryCacheInfo,
+  ModelsRegistryImportResult,
+  RegistryUpdatedEvent,
+  UserModelEntry,
+} from "@/types/modelsRegistry";
+import { getErrorMessage } from "@/types/error";
+
+// 事件名（与 Rust 常量一致）
+const EVENT_MODELS_REGISTRY_UPDATED = "models-registry:updated";
 
 export interface UseModelsRegistryReturn {
   getModelDefaults: (modelId: string) => Promise<ModelDefaults | null>;
@@ -11,6 +22,10 @@ export interface UseModelsRegistryReturn {
   cacheInfo: ModelsRegistryCacheInfo | null;
   refresh: () => Promise<void>;
   triggerBackgroundRefresh: () => Promise<void>;
+  importRegistry: (path: string) => Promise<ModelsRegistryImportResult | null>;
+  userModels: UserModelEntry[];
+  saveUserModel: (entry: UserModelEntry) => Promise<UserModelEntry | null>;
+  deleteUserModel: (providerId: string, modelId: string) => Promise<boolean>;
 }
 
 export function useModelsRegistry(): UseModelsRegistryReturn {
@@ -18,6 +33,7 @@ export function useModelsRegistry(): UseModelsRegistryReturn {
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [cacheInfo, setCacheInfo] = useState<ModelsRegistryCacheInfo | null>(null);
+  const [userModels, setUserModels] = useState<UserModelEntry[]>([]);
 
   const modelsMap = useMemo(() => {
     const map = new Map<string, ModelDefaults>();
@@ -33,6 +49,7 @@ export function useModelsRegistry(): UseModelsRegistryReturn {
     try {
       const models = await invoke<ModelDefaults[]>("get_all_model_defaults");
       setAllModels(models);
+      setUserModels(await invoke<UserModelEntry[]>("list_user_models"));
 
       const info = await invoke<[string, number, boolean] | null>("get_models_registry_cache_info");
       if (info) {
@@ -43,7 +60,7 @@ export function useModelsRegistry(): UseModelsRegistryReturn {
         });
       }
     } catch (e) {
-      const message = e instanceof Error ? e.message : String(e);
+      const message = getErrorMessage(e);
       setError(message);
       console.error("加载模型注册表失败:", e);
     } finally {
@@ -55,6 +72,27 @@ export function useModelsRegistry(): UseModelsRegistryReturn {
     loadAllModels();
   }, [loadAllModels]);
 
+  // 后台定时刷新或导入导致数据变化时重新加载
+  useEffect(() => {
+    let unlisten: UnlistenFn | undefined;
+    let disposed = false;
+
+    listen<RegistryUpdatedEvent>(EVENT_MODELS_REGISTRY_UPDATED, () => {
+      loadAllModels();
+    }).then((fn) => {
+      if (disposed) {
+        fn();
+      } else {
+        unlisten = fn;
+      }
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [loadAllModels]);
+
   const getModelDefaults = useCallback(async (modelId: string): Promise<ModelDefaults | null> => {
     const cached = modelsMap.get(modelId);
     if (cached) {
@@ -81,7 +119,7 @@ export function useModelsRegistry(): UseModelsRegistryReturn {
       await invoke("refresh_models_registry");
       await loadAllModels();
     } catch (e) {
-      const message = e instanceof Error ? e.message : String(e);
+      const message = getErrorMessage(e);
       setError(message);
       console.error("刷新模型注册表失败:", e);
     } finally {
@@ -97,6 +135,46 @@ export function useModelsRegistry(): UseModelsRegistryReturn {
     }
   }, []);
 
+  const importRegistry = useCallback(async (path: string): Promise<ModelsRegistryImportResult | null> => {
+    setIsLoading(true);
+    setError(null);
+    try {
+      const result = await invoke<ModelsRegistryImportResult>("import_models_registry", { path });
+      await loadAllModels();
+      return result;
+    } catch (e) {
+      const message = getErrorMessage(e);
+      setError(message);
+      console.error("导入模型注册表失败:", e);
+      return null;
+    } finally {
+      setIsLoading(false);
+    }
+  }, [loadAllModels]);
+
+  const saveUserModel = useCallback(async (entry: UserModelEntry): Promise<UserModelEntry | null> => {
+    try {
+      const saved = await invoke<UserModelEntry>("save_user_model", { entry });
+      await loadAllModels();
+      return saved;
+    } catch (e) {
+      console.error(`保存自定义模型失败: ${entry.providerId}/${entry.modelId}`, e);
+      setError(getErrorMessage(e));
+      return null;
+    }
+  }, [loadAllModels]);
+
+  const deleteUserModel = useCallback(async (providerId: string, modelId: string): Promise<boolean> => {
+    try {
+      const deleted = await invoke<boolean>("delete_user_model", { providerId, modelId });
+      await loadAllModels();
+      return deleted;
+    } catch (e) {
+      console.error(`删除自定义模型失败: ${providerId}/${modelId}`, e);
+      return false;
+    }
+  }, [loadAllModels]);
+
   return {
     getModelDefaults,
     getCachedModelDefaults,
@@ -106,5 +184,9 @@ export function useModelsRegistry(): UseModelsRegistryReturn {
     cacheInfo,
     refresh,
     triggerBackgroundRefresh,
+    importRegistry,
+    userModels,
+    saveUserModel,
+    deleteUserModel,
   };
 }
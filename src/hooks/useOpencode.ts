@@ -15,6 +15,7 @@ import {
   type OpencodeClient,
   type ServiceMode,
 } from "@/services/opencode";
+import { getErrorMessage } from "@/types/error";
 
 interface UseOpencodeReturn {
   // State
@@ -69,7 +70,7 @@ export function useOpencode(): UseOpencodeReturn {
       globalInitialized = true;
       service.initialize().catch((e) => {
         console.error("Failed to initialize service:", e);
-        setError(e instanceof Error ? e.message : "Initialization failed");
+        setError(getErrorMessage(e, "Initialization failed"));
         // 初始化失败时重置标记，允许重试
         globalInitialized = false;
       });
@@ -100,7 +101,7 @@ export function useOpencode(): UseOpencodeReturn {
     try {
       await service.connect();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "Connection failed");
+      setError(getErrorMessage(e, "Connection failed"));
     } finally {
       setIsLoading(false);
     }
@@ -116,7 +117,7 @@ export function useOpencode(): UseOpencodeReturn {
     try {
       await service.setMode(mode);
     } catch (e) {
-      setError(e instanceof Error ? e.message : "Failed to change mode");
+      setError(getErrorMessage(e, "Failed to change mode"));
     } finally {
       setIsLoading(false);
     }
@@ -129,7 +130,7 @@ export function useOpencode(): UseOpencodeReturn {
     try {
       await service.initializeBackend();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "Backend initialization failed");
+      setError(getErrorMessage(e, "Backend initialization failed"));
     } finally {
       setIsLoading(false);
     }
@@ -141,7 +142,7 @@ export function useOpencode(): UseOpencodeReturn {
     try {
       await service.startBackend();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "Failed to start backend");
+      setError(getErrorMessage(e, "Failed to start backend"));
     } finally {
       setIsLoading(false);
     }
@@ -153,7 +154,7 @@ export function useOpencode(): UseOpencodeReturn {
     try {
       await service.stopBackend();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "Failed to stop backend");
+      setError(getErrorMessage(e, "Failed to stop backend"));
     } finally {
       setIsLoading(false);
     }
@@ -165,7 +166,7 @@ export function useOpencode(): UseOpencodeReturn {
     try {
       await service.restartBackend();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "Failed to restart backend");
+      setError(getErrorMessage(e, "Failed to restart backend"));
     } finally {
       setIsLoading(false);
     }
@@ -5,6 +5,7 @@
 import { useEffect, useState, useCallback } from "react";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { opencode, type ServiceStatus, type ServiceConfig } from "@/services/tauri";
+import { getErrorMessage } from "@/types/error";
 
 // Event names (must match Rust constants)
 const EVENT_SERVICE_STATUS = "service:status";
@@ -51,7 +52,7 @@ export function useServiceStatus(): UseServiceStatusReturn {
       setConfig(newConfig);
       setError(null);
     } catch (e) {
-      setError(e instanceof Error ? e.message : "Failed to fetch status");
+      setError(getErrorMessage(e, "Failed to fetch status"));
     }
   }, []);
 
@@ -63,7 +64,7 @@ export function useServiceStatus(): UseServiceStatusReturn {
       await opencode.initialize();
       await refresh();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "Failed to initialize");
+      setError(getErrorMessage(e, "Failed to initialize"));
     } finally {
       setIsLoading(false);
     }
@@ -77,7 +78,7 @@ export function useServiceStatus(): UseServiceStatusReturn {
       await opencode.start();
       await refresh();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "Failed to start service");
+      setError(getErrorMessage(e, "Failed to start service"));
     } finally {
       setIsLoading(false);
     }
@@ -91,7 +92,7 @@ export function useServiceStatus(): UseServiceStatusReturn {
       await opencode.stop();
       await refresh();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "Failed to stop service");
+      setError(getErrorMessage(e, "Failed to stop service"));
     } finally {
       setIsLoading(false);
     }
@@ -105,7 +106,7 @@ export function useServiceStatus(): UseServiceStatusReturn {
       await opencode.restart();
       await refresh();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "Failed to restart service");
+      setError(getErrorMessage(e, "Failed to restart service"));
     } finally {
       setIsLoading(false);
     }
@@ -1,6 +1,7 @@
 import i18n from "i18next";
 import { initReactI18next } from "react-i18next";
 import LanguageDetector from "i18next-browser-languagedetector";
+import { settings } from "@/services/tauri";
 
 // 只同步加载默认语言（中文），其他语言按需加载
 import zhTranslation from "./locales/zh.json";
@@ -59,6 +60,14 @@ export const changeLanguage = async (lang: SupportedLanguage): Promise<void> =>
   }
   await i18n.changeLanguage(lang);
   localStorage.setItem("axon-language", lang);
+  syncBackendLanguage(lang);
+};
+
+// 同步后端消息语言（错误提示等），失败不影响界面切换
+const syncBackendLanguage = (lang: SupportedLanguage) => {
+  settings.setLanguage(lang).catch((error) => {
+    console.error("[i18n] 同步后端语言失败:", error);
+  });
 };
 
 // 预加载英文语言包（可选，用于提升切换体验）
@@ -70,6 +79,9 @@ export const preloadLanguage = async (lang: SupportedLanguage): Promise<void> =>
 
 // 检测用户偏好语言并预加载
 const savedLang = localStorage.getItem("axon-language") as SupportedLanguage | null;
+if (savedLang) {
+  syncBackendLanguage(savedLang);
+}
 if (savedLang === "en") {
   // 如果用户之前选择了英文，异步加载英文语言包
   loadEnglish().then(() => {
@@ -18,6 +18,7 @@ import { OpencodeProvider } from "@/providers";
 import { ChatProvider } from "@/providers/ChatProvider";
 import { ProjectProvider } from "@/providers/ProjectProvider";
 import { AppLoader } from "@/components/AppLoader";
+import { QuickPromptWindow } from "@/components/quick-prompt/QuickPromptWindow";
 import { Toaster } from "@/components/ui/sonner";
 import { router } from "./router";
 
@@ -79,7 +80,20 @@ async function notifyAppReady(): Promise<void> {
   }
 }
 
+// 快速提问窗口只渲染输入框，不初始化服务和聊天状态
+const isQuickPromptWindow = getCurrentWindow().label === "quick-prompt";
+
 startTransition(() => {
+  if (isQuickPromptWindow) {
+    root.render(
+      <StrictMode>
+        <QuickPromptWindow />
+      </StrictMode>
+    );
+    hideAppLoading();
+    return;
+  }
+
   root.render(
     <StrictMode>
       <QueryClientProvider client={queryClient}>
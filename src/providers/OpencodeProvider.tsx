@@ -26,6 +26,7 @@ import {
 import { useTerminal } from "@/stores/terminal";
 import { useServiceStore } from "@/stores/service";
 import { hideAppLoading } from "@/main";
+import { getErrorMessage } from "@/types/error";
 
 interface OpencodeContextValue {
   // 状态
@@ -199,7 +200,7 @@ export function OpencodeProvider({
       } catch (e) {
         console.error("[OpencodeProvider] Failed to initialize:", e);
         if (mounted) {
-          setError(e instanceof Error ? e.message : "初始化失败");
+          setError(getErrorMessage(e, "初始化失败"));
           completeInitialization();
         }
       }
@@ -259,7 +260,7 @@ export function OpencodeProvider({
     try {
       await service.connect();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "连接失败");
+      setError(getErrorMessage(e, "连接失败"));
     }
   }, [service]);
 
@@ -274,7 +275,7 @@ export function OpencodeProvider({
     try {
       await service.setMode(mode);
     } catch (e) {
-      setError(e instanceof Error ? e.message : "模式切换失败");
+      setError(getErrorMessage(e, "模式切换失败"));
     }
   }, [service]);
 
@@ -284,7 +285,7 @@ export function OpencodeProvider({
     try {
       await service.startBackend();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "启动服务失败");
+      setError(getErrorMessage(e, "启动服务失败"));
     }
   }, [service]);
 
@@ -294,7 +295,7 @@ export function OpencodeProvider({
     try {
       await service.stopBackend();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "停止服务失败");
+      setError(getErrorMessage(e, "停止服务失败"));
     }
   }, [service]);
 
@@ -304,7 +305,7 @@ export function OpencodeProvider({
     try {
       await service.restartBackend();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "重启服务失败");
+      setError(getErrorMessage(e, "重启服务失败"));
     }
   }, [service]);
 
@@ -320,7 +321,7 @@ export function OpencodeProvider({
       // 启动服务
       await service.startBackend();
     } catch (e) {
-      setError(e instanceof Error ? e.message : "重试失败");
+      setError(getErrorMessage(e, "重试失败"));
     } finally {
       setIsInitializing(false);
     }
@@ -1,5 +1,6 @@
 import { useState, useCallback, useMemo, useEffect, useRef } from "react";
 import { createFileRoute, useNavigate } from "@tanstack/react-router";
+import { listen } from "@tauri-apps/api/event";
 import { ChatContainer } from "@/components/chat";
 import { WorkspaceSidebar } from "@/components/sidebar";
 import { FilePreviewPanel } from "@/components/editor";
@@ -20,6 +21,7 @@ import {
 } from "@/components/ui/resizable";
 import type { PanelSize } from "react-resizable-panels";
 import { normalizeDirectory } from "@/types/project";
+import { EVENT_QUICK_PROMPT_SUBMIT } from "@/services/tauri";
 
 // 侧边栏面板配置（像素值）
 // react-resizable-panels: 数字 = 像素，字符串如 "15%" = 百分比
@@ -117,6 +119,16 @@ function HomePage() {
   // 项目状态
   const { projects, getProjectByDirectory } = useProjectContext();
 
+  // 全局快捷键的快速提问发送到当前会话
+  useEffect(() => {
+    const unlisten = listen<string>(EVENT_QUICK_PROMPT_SUBMIT, (event) => {
+      sendMessage(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [sendMessage]);
+
   const lastActiveSessionDirectoryRef = useRef<string | null>(null);
   useEffect(() => {
     if (activeSession?.directory) {
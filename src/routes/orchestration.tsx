@@ -1,5 +1,5 @@
 import { useCallback, useEffect, useState } from "react";
-import { createFileRoute } from "@tanstack/react-router";
+import { createFileRoute, useNavigate } from "@tanstack/react-router";
 import { Plus, Workflow, Save, Trash2, X, Loader2 } from "lucide-react";
 import { toast } from "sonner";
 import { cn } from "@/lib/utils";
@@ -32,14 +32,23 @@ import {
   TooltipTrigger,
 } from "@/components/ui/tooltip";
 import type { OrchestrationGroup, EmbeddedSubagent, AgentConfig } from "@/types/orchestration";
+import { getErrorMessage } from "@/types/error";
 
 const PANEL_CONFIG = {
   list: { defaultSize: 220, minSize: 180, maxSize: 320 },
   config: { defaultSize: 360, minSize: 280, maxSize: 480 },
 };
 
+export interface OrchestrationSearch {
+  /** 进入页面后立即新建编排组（菜单栏「新建 Agent」） */
+  create?: boolean;
+}
+
 export const Route = createFileRoute("/orchestration")({
   component: OrchestrationRoute,
+  validateSearch: (search: Record<string, unknown>): OrchestrationSearch => ({
+    create: search.create === true || search.create === "true" ? true : undefined,
+  }),
 });
 
 function OrchestrationRoute() {
@@ -67,6 +76,15 @@ function OrchestrationRoute() {
     setShowConfigPanel(true);
   }, [createGroup, selectGroup]);
 
+  // 菜单栏「新建 Agent」：处理后清除参数，避免刷新或返回时重复创建
+  const { create } = Route.useSearch();
+  const navigate = useNavigate();
+  useEffect(() => {
+    if (!create) return;
+    navigate({ to: "/orchestration", search: {}, replace: true });
+    handleCreateGroup();
+  }, [create, navigate, handleCreateGroup]);
+
   const handleSelectGroup = useCallback(
     (group: OrchestrationGroup) => {
       selectGroup(group.id);
@@ -84,7 +102,7 @@ function OrchestrationRoute() {
       toast.success("编排组已保存");
     } catch (error) {
       console.error("保存编排组失败:", error);
-      toast.error(`保存失败: ${error instanceof Error ? error.message : "未知错误"}`);
+      toast.error(`保存失败: ${getErrorMessage(error, "未知错误")}`);
     } finally {
       setIsSaving(false);
     }
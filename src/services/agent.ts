@@ -21,6 +21,8 @@ export interface AgentSummary {
   modelId: string;
   builtin?: boolean;
   updatedAt: number;
+  /** 来源，团队配置仓库中的定义只读 */
+  scope?: "local" | "team";
 }
 
 // ============================================================================
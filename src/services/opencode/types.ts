@@ -115,6 +115,10 @@ export interface AppSettings {
   autoUpdate: boolean;
   customOpencodePath: string | null;
   installedVersion: string | null;
+  /** 关闭主窗口时最小化到系统托盘 */
+  minimizeToTray?: boolean;
+  /** 后端消息（错误提示等）使用的语言 */
+  language?: "zh" | "en";
 }
 
 export const DEFAULT_APP_SETTINGS: AppSettings = {
@@ -4,6 +4,14 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import type { AuditEntry, AuditLogFilter } from "@/types/audit";
+import type {
+  ConsentAction,
+  ConsentDecision,
+  ConsentRequest,
+  ConsentRule,
+} from "@/types/consent";
+import type { ModelPerformance, UsageRange, UsageSummary } from "@/types/usage";
 
 // Types matching Rust definitions
 export type ServiceMode =
@@ -15,14 +23,49 @@ export type ServiceStatus =
   | { type: "downloading"; progress: number }
   | { type: "ready" }
   | { type: "starting" }
-  | { type: "running"; port: number }
+  /** requestedPort：配置的端口被占用、改用其它端口时为原端口 */
+  | { type: "running"; port: number; requestedPort?: number }
   | { type: "stopped" }
   | { type: "error"; message: string };
 
+/** OpenCode 实例（默认实例名称为 "default"） */
+export interface InstanceInfo {
+  name: string;
+  /** 工作目录，默认实例为 null（使用项目目录） */
+  directory: string | null;
+  status: ServiceStatus;
+  endpoint: string | null;
+  pid: number | null;
+}
+
+/** service:project-switch 事件载荷 */
+export interface ProjectSwitchEvent {
+  phase: "stopping" | "starting" | "completed" | "rollingBack" | "rolledBack" | "failed";
+  projectDir: string | null;
+  previousDir: string | null;
+  message?: string;
+}
+
+/** opencode 进程的环境变量（敏感值已脱敏） */
+export interface ServiceEnvVar {
+  name: string;
+  value: string;
+  /** 是否由 Axon 管理（否则来自额外环境变量） */
+  managed: boolean;
+}
+
+/** service:instance-status 事件载荷 */
+export interface InstanceStatusEvent {
+  name: string;
+  status: ServiceStatus;
+}
+
 export interface ServiceConfig {
   mode: ServiceMode;
   port: number;
   autoStart: boolean;
+  /** 固定端口被占用时改用随机端口重试的次数（默认 3） */
+  portRetryAttempts?: number;
 }
 
 export interface VersionInfo {
@@ -31,6 +74,84 @@ export interface VersionInfo {
   updateAvailable: boolean;
 }
 
+export interface SettingsEncryptionStatus {
+  enabled: boolean;
+  /** 设置文件已加密但钥匙串中没有可用密钥，此时不会保存设置 */
+  locked: boolean;
+  /** 加密的字段 */
+  sections: string[];
+}
+
+/** settings:changed 事件载荷（只包含发生变化的顶层字段名） */
+export interface SettingsChangedEvent {
+  keys: string[];
+}
+
+/** 服务商认证状态 */
+export interface ProviderAuthStatus {
+  provider_id: string;
+  authenticated: boolean;
+  auth_type: string | null;
+}
+
+/** 单个服务商的模型过滤规则（模型 ID 不带 provider 前缀，以 * 结尾时按前缀匹配） */
+export interface ModelFilter {
+  /** 非空时只允许这些模型 */
+  allow: string[];
+  /** 禁止的模型，优先于 allow */
+  deny: string[];
+}
+
+/** opencode CLI 全局配置中找到的 Agent */
+export interface ExternalAgent {
+  name: string;
+  description: string | null;
+  mode: string | null;
+  model: string | null;
+  /** 定义所在文件 */
+  source: string;
+  /** 导入后在 Axon 中的 ID */
+  importId: string;
+  alreadyImported: boolean;
+}
+
+/** opencode CLI 全局配置中找到的服务商 */
+export interface ExternalProvider {
+  id: string;
+  name: string | null;
+  authType: string | null;
+  baseUrl: string | null;
+  alreadyConfigured: boolean;
+}
+
+export interface ExternalOpencodeConfig {
+  /** 找到的配置目录，未找到时为 null */
+  configDir: string | null;
+  configFiles: string[];
+  authFile: string | null;
+  agents: ExternalAgent[];
+  providers: ExternalProvider[];
+}
+
+export interface ExternalImportReport {
+  importedAgents: string[];
+  importedProviders: string[];
+  skipped: { name: string; reason: string }[];
+}
+
+/** opencode-config:changed 事件载荷（opencode.json 被外部修改） */
+export interface OpencodeConfigChangedEvent {
+  path: string;
+  /** 文件是否已被删除 */
+  removed: boolean;
+}
+
+/** auth:changed 事件载荷（auth.json 被外部修改，附带刷新后的认证状态） */
+export interface AuthChangedEvent {
+  path: string;
+  providers: ProviderAuthStatus[];
+}
+
 export interface AppSettings {
   autoUpdate: boolean;
   customOpencodePath: string | null;
@@ -38,6 +159,930 @@ export interface AppSettings {
   projectDirectory: string | null;
 }
 
+export interface PathSandboxStatus {
+  enabled: boolean;
+  /** 用户显式授权的目录 */
+  allowedPaths: string[];
+  /** 当前生效的全部根目录（项目目录、应用数据目录和授权目录） */
+  roots: string[];
+}
+
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+export interface LogLevelSettings {
+  level: LogLevel;
+  /** 应用该级别的模块，为空时作用于整个应用 */
+  targets: string[];
+}
+
+/** 启动完成事件 */
+export const EVENT_STARTUP_TIMINGS = "startup:timings";
+
+export interface StartupPhase {
+  name: string;
+  /** 相对进程启动的开始时间（毫秒） */
+  startMs: number;
+  durationMs: number;
+}
+
+export interface StartupTimings {
+  phases: StartupPhase[];
+  /** 启动完成时的总耗时（毫秒） */
+  totalMs?: number;
+  completed: boolean;
+}
+
+export interface HotkeySettings {
+  /** 唤起主窗口，为空时不注册 */
+  summon: string | null;
+  /** 打开快速提问窗口，为空时不注册 */
+  quickPrompt: string | null;
+}
+
+/** 快速提问提交事件（主窗口接收），payload 为提问内容 */
+export const EVENT_QUICK_PROMPT_SUBMIT = "quick-prompt:submit";
+
+/** 快捷键映射变化事件（所有窗口接收），payload 为 KeymapEntry[] */
+export const EVENT_KEYMAP_CHANGED = "keymap:changed";
+
+/**
+ * 快捷键映射中的一项
+ *
+ * 内置操作：app.summon / app.quickPrompt（全局）、session.new、tab.close、project.open、
+ * settings.open、view.toggleSidebar、view.toggleTerminal；snippet.<片段 ID> 为插入片段
+ */
+export interface KeymapEntry {
+  action: string;
+  description: string;
+  scope: "global" | "app";
+  /** 规范写法（如 CmdOrCtrl+Shift+K），未绑定时为 null */
+  accelerator: string | null;
+  defaultAccelerator: string | null;
+}
+
+/** macOS 菜单栏操作事件（主窗口接收），payload 为 MenuAction */
+export const EVENT_MENU_ACTION = "menu:action";
+
+/**
+ * 菜单栏中选择的操作
+ *
+ * action 与 KeymapEntry.action 一致，另有 project.openRecent（argument 为项目目录）、
+ * agent.open（argument 为 Agent 名称）、agent.new、agent.manage、help.keymap、help.checkUpdates
+ */
+export interface MenuAction {
+  action: string;
+  argument: string | null;
+}
+
+/** 菜单栏「最近项目」中的一项 */
+export interface MenuRecentProject {
+  name: string;
+  directory: string;
+}
+
+export type FileKind = "file" | "directory";
+
+/** 原生右键菜单中用户选择的操作 */
+export type FileMenuAction =
+  | "open"
+  | "reveal"
+  | "copy_path"
+  | "rename"
+  | "delete"
+  | "new_file"
+  | "new_folder";
+
+/** 启动阶段事件（payload 均为 BootstrapStatus） */
+export const BOOTSTRAP_EVENTS = [
+  "bootstrap:paths-ready",
+  "bootstrap:plugin-api-ready",
+  "bootstrap:opencode-ready",
+  "bootstrap:frontend-ready",
+  "bootstrap:failed",
+] as const;
+
+export type BootstrapStage = "paths" | "plugin_api" | "opencode" | "frontend";
+
+export interface BootstrapStageState {
+  stage: BootstrapStage;
+  status: "pending" | "ready" | "failed";
+  /** 失败原因 */
+  message?: string;
+  /** 阶段结束时相对进程启动的时间（毫秒） */
+  elapsedMs?: number;
+}
+
+export interface BootstrapStatus {
+  stages: BootstrapStageState[];
+  /** 所有阶段都已就绪 */
+  ready: boolean;
+  /** 存在失败的阶段 */
+  failed: boolean;
+}
+
+export interface ReplaceOptions {
+  /** 搜索根目录，为空时使用当前项目目录 */
+  root?: string;
+  /** 按正则解析，替换文本支持 $1 / ${name} 捕获组 */
+  regex?: boolean;
+  caseSensitive?: boolean;
+  wholeWord?: boolean;
+  /** 只处理匹配这些 glob 的文件（相对根目录） */
+  include?: string[];
+  /** 排除匹配这些 glob 的文件或目录（相对根目录） */
+  exclude?: string[];
+}
+
+export interface ReplaceMatch {
+  line: number;
+  column: number;
+  matched: string;
+  replacement: string;
+  lineBefore: string;
+  lineAfter: string;
+}
+
+export interface FileReplacePreview {
+  path: string;
+  relativePath: string;
+  /** 预览时的内容哈希，确认替换时回传 */
+  hash: string;
+  matchCount: number;
+  matches: ReplaceMatch[];
+  stats: { additions: number; deletions: number; hasChanges: boolean };
+}
+
+export interface ReplacePreview {
+  root: string;
+  files: FileReplacePreview[];
+  totalMatches: number;
+  truncated: boolean;
+}
+
+export interface ReplaceResult {
+  applied: string[];
+  skipped: { path: string; reason: string }[];
+  totalReplacements: number;
+}
+
+export interface FuzzyMatch {
+  path: string;
+  /** 相对项目目录的路径（/ 分隔） */
+  relativePath: string;
+  score: number;
+  /** 命中的字符下标，用于高亮 */
+  positions: number[];
+}
+
+export interface FileIndexStatus {
+  root: string | null;
+  fileCount: number;
+  indexing: boolean;
+  truncated: boolean;
+  builtAt: number | null;
+}
+
+/** 目录大小分析进度事件 */
+export const EVENT_FS_SIZE_PROGRESS = "fs:size-progress";
+
+export interface DirectorySize {
+  name: string;
+  path: string;
+  isDirectory: boolean;
+  /** 总字节数（包含所有子项） */
+  size: number;
+  fileCount: number;
+  /** 展开层数内的子项（按大小降序） */
+  children: DirectorySize[];
+  omittedChildren: number;
+}
+
+export interface DirectorySizeReport {
+  root: DirectorySize;
+  /** 无法读取而跳过的条目数 */
+  skipped: number;
+  elapsedMs: number;
+}
+
+export interface DirectorySizeProgress {
+  jobId: string;
+  filesScanned: number;
+  bytesScanned: number;
+  currentPath: string | null;
+}
+
+export interface ClipboardImage {
+  /** base64 编码的 PNG 数据 */
+  data: string;
+  mimeType: string;
+  width: number;
+  height: number;
+}
+
+export type ScreenshotMode = "full" | "window" | "region";
+
+/** 截图区域（屏幕坐标，物理像素） */
+export interface ScreenshotRegion {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+export interface ScreenshotWindow {
+  id: number;
+  title: string;
+  appName: string;
+  width: number;
+  height: number;
+}
+
+export interface Screenshot {
+  /** 保存的 PNG 文件路径 */
+  path: string;
+  width: number;
+  height: number;
+  preview: {
+    /** base64 编码的 PNG 缩略图 */
+    data: string;
+    mime_type: string;
+    width: number;
+    height: number;
+    original_width: number;
+    original_height: number;
+  };
+}
+
+export interface AudioInputDevice {
+  name: string;
+  isDefault: boolean;
+}
+
+/** audio:level 事件负载 */
+export interface AudioLevel {
+  /** 均方根音量（0 - 1） */
+  rms: number;
+  /** 峰值音量（0 - 1） */
+  peak: number;
+  elapsedMs: number;
+}
+
+export interface Recording {
+  /** WAV 文件路径 */
+  path: string;
+  device: string;
+  sampleRate: number;
+  channels: number;
+  durationMs: number;
+}
+
+export interface EmbeddingSettings {
+  /** 提供嵌入接口的服务商配置 ID，为空时使用第一个支持嵌入的服务商 */
+  providerId: string | null;
+  model: string;
+}
+
+export interface EmbeddingIndexStatus {
+  root: string | null;
+  model: string | null;
+  fileCount: number;
+  chunkCount: number;
+  indexing: boolean;
+  /** Unix 毫秒 */
+  builtAt: number | null;
+}
+
+/** embeddings:progress 事件负载 */
+export interface EmbeddingIndexProgress {
+  jobId: string;
+  filesTotal: number;
+  filesProcessed: number;
+  chunksEmbedded: number;
+  currentPath: string | null;
+  /** 因节能设置暂停 */
+  paused: boolean;
+}
+
+export interface SemanticMatch {
+  path: string;
+  relativePath: string;
+  startLine: number;
+  endLine: number;
+  /** 余弦相似度 */
+  score: number;
+  snippet: string;
+}
+
+export interface MemoryEntry {
+  key: string;
+  value: string;
+  /** Unix 毫秒 */
+  createdAt: number;
+  updatedAt: number;
+}
+
+export interface MemoryScopeSummary {
+  agent: string;
+  /** 项目目录，为空时为该 Agent 的全局记忆 */
+  project: string | null;
+  entryCount: number;
+  totalBytes: number;
+  quotaBytes: number;
+}
+
+/** Agent 活动记录新增或变化事件，负载为 ActivityEntry */
+export const EVENT_ACTIVITY_UPDATED = "activity:updated";
+
+export type ActivityStatus = "pending" | "running" | "completed" | "error";
+
+/** 一次工具调用（同一调用的多次状态更新合并为一条） */
+export interface ActivityEntry {
+  /** 工具调用 ID */
+  id: string;
+  sessionId: string;
+  messageId: string;
+  tool: string;
+  status: ActivityStatus;
+  /** 工具给出的标题，没有时取自参数（命令、文件路径等） */
+  summary: string | null;
+  error: string | null;
+  /** Unix 毫秒 */
+  startedAt: number | null;
+  endedAt: number | null;
+  durationMs: number | null;
+}
+
+/** 资源占用采样事件 */
+export const EVENT_STATS_UPDATE = "stats:update";
+
+export type ProcessKind = "app" | "opencode" | "shell" | "child";
+
+export interface ProcessStat {
+  pid: number;
+  name: string;
+  kind: ProcessKind;
+  /** CPU 占用（单核百分比，多核时可超过 100） */
+  cpuPercent: number;
+  memoryBytes: number;
+}
+
+/** stats:update 事件负载 */
+export interface StatsSample {
+  /** Unix 毫秒 */
+  timestamp: number;
+  processes: ProcessStat[];
+  totalCpuPercent: number;
+  totalMemoryBytes: number;
+}
+
+export interface ProcessStats {
+  intervalMs: number;
+  /** 按时间顺序，最后一项为最新采样 */
+  samples: StatsSample[];
+}
+
+/** 电源状态变化事件 */
+export const EVENT_POWER_STATE = "power:state";
+
+export type PauseReason = "battery" | "idle";
+
+export interface PowerState {
+  /** 使用电池供电（无法检测时为 false） */
+  onBattery: boolean;
+  /** 电池电量（0 - 100），没有电池时为空 */
+  batteryPercent: number | null;
+  /** 用户空闲秒数，无法检测时为空 */
+  idleSeconds: number | null;
+  /** 后台任务是否已暂停 */
+  paused: boolean;
+  pauseReason: PauseReason | null;
+}
+
+/** 团队配置同步状态变化事件 */
+export const EVENT_TEAM_SYNC_STATUS = "team-sync:status";
+
+export interface TeamSyncSettings {
+  enabled: boolean;
+  repoUrl: string | null;
+  /** 为空时使用仓库默认分支 */
+  branch: string | null;
+  /** 自动刷新间隔（分钟），0 表示只手动同步 */
+  refreshIntervalMinutes: number;
+}
+
+export interface TeamConflict {
+  /** 相对仓库根目录的路径 */
+  path: string;
+  reason: "shadowedByLocal" | "locallyModified" | "invalidDefinition";
+}
+
+export type BookmarkColor = "red" | "orange" | "yellow" | "green" | "blue" | "purple" | "gray";
+
+/** 项目书签 */
+export interface Bookmark {
+  path: string;
+  kind: "file" | "directory";
+  note: string | null;
+  color: BookmarkColor | null;
+  createdAt: number;
+  updatedAt: number;
+}
+
+/** 会话分支（分支点和名称，消息本身由 OpenCode 保存） */
+export interface Branch {
+  id: string;
+  name: string;
+  sessionId: string;
+  /** 分支点：从该消息开始分叉 */
+  messageId: string;
+  /** 分叉出的会话 */
+  branchSessionId: string | null;
+  createdAt: number;
+}
+
+/** 提示词片段 */
+export interface Snippet {
+  id: string;
+  name: string;
+  description: string | null;
+  content: string;
+  /** 内容中引用的变量（按首次出现顺序） */
+  variables: string[];
+  /** 快捷键 ID，同一 ID 只能关联一个片段 */
+  shortcut: string | null;
+  /** 固定到列表顶部 */
+  pinned: boolean;
+  createdAt: number;
+  updatedAt: number;
+}
+
+/** 新建（id 为空）或更新片段 */
+export interface SnippetInput {
+  id?: string;
+  name: string;
+  description?: string;
+  content: string;
+  shortcut?: string;
+  pinned?: boolean;
+}
+
+/** 拼写错误 */
+export interface SpellingIssue {
+  word: string;
+  /** 在文本中的位置（UTF-16，与字符串下标一致） */
+  start: number;
+  end: number;
+  suggestions: string[];
+}
+
+/** 固定到 Agent 上下文的文件 */
+export interface ContextPin {
+  path: string;
+  pinnedAt: number;
+}
+
+/** 项目概览统计 */
+export interface LanguageStats {
+  language: string;
+  files: number;
+  lines: number;
+  bytes: number;
+}
+
+export interface ChangeHotspot {
+  /** 相对项目目录的路径 */
+  path: string;
+  /** 近 90 天修改过该文件的提交数 */
+  commits: number;
+  lastChanged: number;
+}
+
+export interface WorkspaceStats {
+  projectDir: string;
+  totalFiles: number;
+  totalLines: number;
+  totalBytes: number;
+  /** 按行数降序 */
+  languages: LanguageStats[];
+  largestFiles: { path: string; bytes: number }[];
+  /** 不是 Git 仓库时为 null */
+  hotspots: ChangeHotspot[] | null;
+  truncated: boolean;
+  computedAt: number;
+  elapsedMs: number;
+  cached: boolean;
+}
+
+/** Markdown 渲染选项 */
+export interface MarkdownOptions {
+  /** 表格、删除线、任务列表、自动链接（默认开启） */
+  gfm?: boolean;
+  /** 单个换行渲染为 <br> */
+  hardBreaks?: boolean;
+  /** 保留原始 HTML（仍会经过清理） */
+  allowHtml?: boolean;
+  /** 代码块高亮主题，为空时不高亮 */
+  highlightTheme?: string;
+}
+
+/** Notebook 单元格输出 */
+export interface NotebookOutput {
+  /** stream / display_data / execute_result / error */
+  outputType: string;
+  name?: string;
+  text?: string;
+  /** MIME 数据，JSON 类型保持原样，其余为字符串 */
+  data?: Record<string, unknown>;
+  metadata?: unknown;
+  executionCount?: number | null;
+  ename?: string;
+  evalue?: string;
+  traceback?: string[];
+}
+
+export interface NotebookCell {
+  /** 新单元格可以为空，写入时自动生成 */
+  id?: string | null;
+  cellType: "code" | "markdown" | "raw";
+  source: string;
+  metadata?: Record<string, unknown>;
+  executionCount?: number | null;
+  outputs?: NotebookOutput[];
+  attachments?: unknown;
+}
+
+export interface Notebook {
+  nbformat: number;
+  nbformatMinor: number;
+  /** 内核语言 */
+  language: string | null;
+  metadata: Record<string, unknown>;
+  cells: NotebookCell[];
+}
+
+/** PDF 单页文本 */
+export interface PdfPageText {
+  /** 页码（从 1 开始） */
+  page: number;
+  text: string;
+  error?: string;
+}
+
+export interface PdfText {
+  path: string;
+  pageCount: number;
+  pages: PdfPageText[];
+  /** 文本量达到上限，后面的页未提取 */
+  truncated: boolean;
+}
+
+export type ReviewDecision = "pending" | "accepted" | "rejected";
+export type ReviewStatus = "open" | "finalized";
+
+/** 创建审查时提交的文件快照 */
+export interface ReviewFileInput {
+  /** 绝对路径 */
+  path: string;
+  /** 修改前的内容，为空表示新建文件 */
+  before?: string | null;
+  /** 修改后的内容，为空表示删除文件 */
+  after?: string | null;
+}
+
+export interface NewReview {
+  title?: string;
+  /** 产生这些修改的 Agent 会话 */
+  sessionId?: string;
+  projectDir?: string;
+  files: ReviewFileInput[];
+}
+
+export interface ReviewFile {
+  path: string;
+  before: string | null;
+  after: string | null;
+  /** 每个 hunk 的决策，序号与 computeDiff(before, after) 的 hunks 对应 */
+  hunks: ReviewDecision[];
+}
+
+/** Diff 审查会话 */
+export interface Review {
+  id: string;
+  title: string | null;
+  sessionId: string | null;
+  projectDir: string | null;
+  status: ReviewStatus;
+  files: ReviewFile[];
+  createdAt: number;
+  updatedAt: number;
+  finalizedAt: number | null;
+}
+
+export interface ReviewSummary {
+  id: string;
+  title: string | null;
+  sessionId: string | null;
+  projectDir: string | null;
+  status: ReviewStatus;
+  fileCount: number;
+  /** 尚未决定的 hunk 数 */
+  pendingHunks: number;
+  createdAt: number;
+  updatedAt: number;
+}
+
+export interface FinalizeReviewResult {
+  review: Review;
+  files: { path: string; action: "written" | "deleted" | "unchanged" }[];
+}
+
+/** 分块高亮事件 */
+export const EVENT_HIGHLIGHT_CHUNK = "highlight:chunk";
+
+export type HighlightFormat = "html" | "tokens";
+
+export interface HighlightToken {
+  text: string;
+  color: string;
+  bold?: boolean;
+  italic?: boolean;
+  underline?: boolean;
+}
+
+/** 一行高亮结果：html 格式为 HTML 片段，tokens 格式为 Token 列表 */
+export type HighlightedLine = string | HighlightToken[];
+
+export interface HighlightedCode {
+  /** 实际使用的语法，无法识别时为 "Plain Text" */
+  language: string;
+  theme: string;
+  background: string | null;
+  foreground: string | null;
+  lines: HighlightedLine[];
+}
+
+export interface HighlightChunk {
+  jobId: string;
+  /** 本块第一行的行号（从 0 开始） */
+  startLine: number;
+  lines: HighlightedLine[];
+}
+
+export interface HighlightedFile {
+  language: string;
+  theme: string;
+  background: string | null;
+  foreground: string | null;
+  totalLines: number;
+}
+
+/** 忽略文件 */
+export type IgnoreFile = "gitignore" | "axonignore";
+
+/** 项目的忽略规则（搜索替换、文件索引、项目上下文共用） */
+export interface IgnoreRules {
+  projectDir: string;
+  /** 始终跳过的内置目录 */
+  builtin: string[];
+  gitignore: string | null;
+  axonignore: string | null;
+}
+
+/** 项目模板变量 */
+export interface TemplateVariable {
+  name: string;
+  description: string | null;
+  default: string | null;
+  /** 为 true 且没有默认值时必须提供 */
+  required: boolean;
+}
+
+export interface ProjectTemplate {
+  id: string;
+  name: string;
+  description: string | null;
+  source: "builtin" | "user";
+  variables: TemplateVariable[];
+}
+
+export interface CreatedProject {
+  path: string;
+  templateId: string;
+  /** 写入的文件（相对路径） */
+  files: string[];
+  gitInitialized: boolean;
+  /** Git 初始化失败的原因（项目文件已创建） */
+  gitError?: string;
+}
+
+export interface TeamSyncStatus {
+  enabled: boolean;
+  /** 仓库地址（已去掉凭据） */
+  repoUrl: string | null;
+  branch: string | null;
+  directory: string | null;
+  syncing: boolean;
+  lastSyncedAt: number | null;
+  lastCommit: string | null;
+  lastError: string | null;
+  conflicts: TeamConflict[];
+}
+
+export interface PowerSettings {
+  pauseOnBattery: boolean;
+  pauseWhenIdle: boolean;
+  /** 空闲阈值（分钟） */
+  idleThresholdMinutes: number;
+}
+
+export type NotificationKind = "info" | "success" | "warning" | "error";
+
+export interface NotificationSettings {
+  /** 窗口未聚焦或已最小化时，后台任务、下载和工作流完成后发送系统通知 */
+  taskCompletion: boolean;
+}
+
+export type WebhookKind = "slack" | "discord" | "generic";
+
+export type WebhookEventKind = "workflow_finished" | "service_crashed" | "update_available";
+
+export interface WebhookConfig {
+  id: string;
+  name: string;
+  url: string;
+  kind: WebhookKind;
+  /** 订阅的事件，为空时订阅全部事件 */
+  events: WebhookEventKind[];
+  enabled: boolean;
+}
+
+export interface RateLimitRule {
+  /** 允许的突发请求数 */
+  burst: number;
+  /** 每秒补充的令牌数 */
+  perSecond: number;
+}
+
+export interface RateLimitSettings {
+  enabled: boolean;
+  defaultRule: RateLimitRule;
+  /** 按路由模板（如 `/api/plugin/events`）覆盖的规则 */
+  routes: Record<string, RateLimitRule>;
+}
+
+export interface RateLimitCounters {
+  allowed: number;
+  /** 被拒绝（429）的请求数 */
+  limited: number;
+  lastLimitedAt?: string;
+}
+
+export interface PluginApiMetrics {
+  port: number;
+  running: boolean;
+  agentCount: number;
+  disabledAgentCount: number;
+  recentEventCount: number;
+  recentEventsByType: Record<string, number>;
+  lastEventAt?: string;
+  /** 按路由模板统计的限流计数 */
+  rateLimits: Record<string, RateLimitCounters>;
+}
+
+/** 会话级 Agent 参数覆盖（只在内存中生效，不修改保存的 Agent 配置） */
+export interface AgentOverride {
+  model?: string;
+  temperature?: number;
+  top_p?: number;
+  /** 工具开关，与 Agent 配置中的工具配置合并 */
+  tools?: Record<string, boolean>;
+}
+
+export type OpencodePluginKind = "bridge" | "managed" | "external";
+
+export interface OpencodePlugin {
+  id: string;
+  name: string;
+  kind: OpencodePluginKind;
+  /** opencode.json 中的条目 */
+  entry: string;
+  /** 添加时的原始文件路径或 URL（仅 managed） */
+  source?: string;
+  sha256?: string;
+  /** 条目指向的本地文件是否存在 */
+  available: boolean;
+}
+
+export type BridgePluginSource = "bundled" | "release";
+
+export interface BridgeUpdateInfo {
+  /** 检查的 GitHub 仓库 */
+  repo: string;
+  currentVersion: string;
+  /** 没有安装清单时为空 */
+  currentSource: BridgePluginSource | null;
+  latestVersion: string | null;
+  available: boolean;
+  releaseUrl: string | null;
+  notes: string | null;
+}
+
+export interface InstalledBridgePlugin {
+  version: string;
+  source: BridgePluginSource;
+  sha256: string;
+  installedAt: string;
+}
+
+export interface BridgeUpdateResult {
+  installed: InstalledBridgePlugin;
+  /** 是否已重启 OpenCode 以加载新插件 */
+  restarted: boolean;
+}
+
+export type ScriptRuntime = "node" | "python" | "deno";
+
+export interface ToolResponseMapping {
+  /** 从 JSON 响应中提取的字段路径（如 data.items.0） */
+  path?: string;
+  /** 输出模板，可引用 {{response}}、{{status}} */
+  template?: string;
+}
+
+/** HTTP 工具的地址、请求头和请求体可以引用 {{args.x}} 和 {{secrets.NAME}} */
+export interface HttpToolHandler {
+  type: "http";
+  method?: string;
+  url: string;
+  headers?: Record<string, string>;
+  /** 请求体模板 */
+  body?: string;
+  response?: ToolResponseMapping;
+}
+
+export type ToolHandler =
+  | HttpToolHandler
+  | { type: "script"; runtime: ScriptRuntime; source: string; timeoutSecs?: number };
+
+export interface ToolDefinition {
+  name: string;
+  description: string;
+  /** 参数的 JSON Schema（type 为 object） */
+  parameters: Record<string, unknown>;
+  handler: ToolHandler;
+  enabled: boolean;
+  createdAt?: number;
+  updatedAt?: number;
+}
+
+export interface ToolExecution {
+  output: string;
+  /** 脚本的标准错误输出 */
+  stderr?: string;
+  truncated: boolean;
+  durationMs: number;
+}
+
+export interface RunSandbox {
+  id: string;
+  projectDir: string;
+  /** 应用改动的根目录（Git 仓库顶层或项目目录） */
+  projectRoot: string;
+  sandboxRoot: string;
+  /** Agent 的工作目录 */
+  directory: string;
+  /** 在沙箱目录中运行 Agent 的 OpenCode 实例名称（状态和地址见 service:instance-status） */
+  instance: string;
+  /** 项目目录相对仓库顶层的路径，非子目录时为空 */
+  prefix: string;
+  kind: "worktree" | "copy";
+  baseline: string;
+  createdAt: number;
+}
+
+export interface SandboxFileChange {
+  /** 相对仓库顶层（或项目目录）的路径 */
+  path: string;
+  /** 二进制文件为 null */
+  additions: number | null;
+  deletions: number | null;
+}
+
+export interface SandboxDiff {
+  sandboxId: string;
+  files: SandboxFileChange[];
+  patch: string;
+}
+
+export interface DiagnosticsBundle {
+  /** zip 文件路径 */
+  path: string;
+  /** zip 文件大小（字节） */
+  size: number;
+  /** 包内文件列表 */
+  files: string[];
+}
+
 // OpenCode service commands
 export const opencode = {
   getStatus: () => invoke<ServiceStatus>("get_service_status"),
@@ -48,7 +1093,16 @@ export const opencode = {
   start: () => invoke("start_service"),
   stop: () => invoke("stop_service"),
   restart: () => invoke("restart_service"),
+  /** 切换项目目录并重启服务，启动失败时自动恢复原目录 */
+  switchProject: (projectDir: string | null) => invoke("switch_project", { projectDir }),
   getEndpoint: () => invoke<string | null>("get_service_endpoint"),
+  listInstances: () => invoke<InstanceInfo[]>("list_service_instances"),
+  startInstance: (name: string, directory: string, port?: number) =>
+    invoke<InstanceInfo>("start_service_instance", { name, directory, port }),
+  stopInstance: (name: string) => invoke("stop_service_instance", { name }),
+  getExtraEnv: () => invoke<Record<string, string>>("get_service_extra_env"),
+  setExtraEnv: (env: Record<string, string>) => invoke("set_service_extra_env", { env }),
+  getEnvironment: () => invoke<ServiceEnvVar[]>("get_service_environment"),
   getVersionInfo: () => invoke<VersionInfo>("get_version_info"),
   checkForUpdate: () => invoke<VersionInfo>("check_for_update"),
   updateOpencode: () => invoke("update_opencode"),
@@ -59,12 +1113,68 @@ export const settings = {
   get: () => invoke<AppSettings>("get_app_settings"),
   set: (settings: AppSettings) => invoke("set_app_settings", { settings }),
   setAutoUpdate: (enabled: boolean) => invoke("set_auto_update", { enabled }),
+  setMinimizeToTray: (enabled: boolean) => invoke("set_minimize_to_tray", { enabled }),
+  getEncryption: () => invoke<SettingsEncryptionStatus>("get_settings_encryption"),
+  enableEncryption: () => invoke<SettingsEncryptionStatus>("enable_settings_encryption"),
+  disableEncryption: () => invoke<SettingsEncryptionStatus>("disable_settings_encryption"),
+  /**
+   * 丢弃无法解密的敏感设置并恢复保存
+   *
+   * 会永久丢弃服务商配置、Webhook 和环境变量；调用方须先弹出确认对话框，
+   * 仅在用户明确确认后传入 `confirm: true`，否则后端拒绝执行
+   */
+  resetEncryption: (confirm: boolean) =>
+    invoke<SettingsEncryptionStatus>("reset_settings_encryption", { confirm }),
+  getLanguage: () => invoke<"zh" | "en">("get_language"),
+  setLanguage: (language: "zh" | "en") => invoke("set_language", { language }),
   setCustomOpencodePath: (path: string | null) => invoke("set_custom_opencode_path", { path }),
   setProjectDirectory: (path: string | null) => invoke("set_project_directory", { path }),
   getProjectDirectory: () => invoke<string | null>("get_project_directory"),
   getOpencodeConfigPath: () => invoke<string>("get_opencode_config_path"),
 };
 
+// Model filter commands
+export const modelFilters = {
+  get: () => invoke<Record<string, ModelFilter>>("get_model_filters"),
+  set: (filters: Record<string, ModelFilter>) => invoke("set_model_filters", { filters }),
+  /** 筛选 provider/model 格式的模型列表 */
+  filterAllowed: (models: string[]) => invoke<string[]>("filter_allowed_models", { models }),
+};
+
+// External opencode config import commands
+export const externalConfig = {
+  scan: () => invoke<ExternalOpencodeConfig>("scan_external_opencode_config"),
+  /** 导入后需要调用 client.instance.dispose() 让 OpenCode 重新读取配置 */
+  import: (agents: string[], providers: string[], overwrite?: boolean) =>
+    invoke<ExternalImportReport>("import_external_opencode_config", { agents, providers, overwrite }),
+};
+
+// Global hotkey commands
+export const hotkeys = {
+  get: () => invoke<HotkeySettings>("get_hotkeys"),
+  set: (hotkeys: HotkeySettings) => invoke("set_hotkeys", { hotkeys }),
+  showQuickPrompt: () => invoke("show_quick_prompt"),
+  hideQuickPrompt: () => invoke("hide_quick_prompt"),
+  submitQuickPrompt: (text: string) => invoke("submit_quick_prompt", { text }),
+};
+
+// Keymap commands
+export const keymap = {
+  get: () => invoke<KeymapEntry[]>("get_keymap"),
+  /** accelerator 为空时解除绑定，与其它操作冲突时报错 */
+  setBinding: (action: string, accelerator: string | null) =>
+    invoke<KeymapEntry[]>("set_binding", { action, accelerator }),
+  /** action 为空时恢复全部默认值 */
+  reset: (action?: string) => invoke<KeymapEntry[]>("reset_keymap", { action }),
+};
+
+// Application menu commands（仅 macOS 安装菜单栏，其他平台调用无效果）
+export const appMenu = {
+  /** 按最近使用排序，菜单中最多显示 10 个 */
+  setRecentProjects: (projects: MenuRecentProject[]) =>
+    invoke<void>("set_menu_recent_projects", { projects }),
+};
+
 // Window control commands
 export const window = {
   minimize: () => invoke("window_minimize"),
@@ -77,5 +1187,379 @@ export const window = {
 // File system commands
 export const fs = {
   readFileContent: (path: string) => invoke<string>("read_file_content", { path }),
-  writeFileContent: (path: string, content: string) => invoke("write_file_content", { path, content }),
+  writeFileContent: (path: string, content: string, preserveFormat?: boolean) =>
+    invoke("write_file_content", { path, content, preserveFormat }),
+  /** 弹出原生右键菜单，取消时返回 null */
+  showContextMenu: (path: string, kind: FileKind) =>
+    invoke<FileMenuAction | null>("show_file_context_menu", { path, kind }),
+  revealInFileManager: (path: string) => invoke("reveal_in_file_manager", { path }),
+  /** 查找替换预览（不修改文件） */
+  previewReplace: (query: string, replacement: string, options?: ReplaceOptions) =>
+    invoke<ReplacePreview>("replace_in_files", { query, replacement, options }),
+  /** 对确认的文件执行替换，参数需与预览一致 */
+  applyReplace: (
+    query: string,
+    replacement: string,
+    files: Pick<FileReplacePreview, "path" | "hash">[],
+    options?: ReplaceOptions
+  ) => invoke<ReplaceResult>("apply_replace_in_files", { query, replacement, options, files }),
+  /** 在项目文件索引中模糊查找（命令面板"转到文件"） */
+  fuzzyFind: (query: string, limit?: number) =>
+    invoke<FuzzyMatch[]>("fuzzy_find", { query, limit }),
+  getIndexStatus: () => invoke<FileIndexStatus>("get_file_index_status"),
+  /** 分析目录大小（后台任务，可用 jobs.cancel(jobId) 取消） */
+  analyzeDirectorySize: (jobId: string, path: string, depth?: number) =>
+    invoke<DirectorySizeReport>("analyze_directory_size", { jobId, path, depth }),
+};
+
+// Semantic search commands
+export const embeddings = {
+  getSettings: () => invoke<EmbeddingSettings>("get_embedding_settings"),
+  setSettings: (embedding: EmbeddingSettings) =>
+    invoke("set_embedding_settings", { embedding }),
+  /** 构建语义索引（后台任务，可用 jobs.cancel(jobId) 取消） */
+  buildIndex: (jobId: string) => invoke<EmbeddingIndexStatus>("build_embedding_index", { jobId }),
+  getStatus: () => invoke<EmbeddingIndexStatus>("get_embedding_index_status"),
+  search: (query: string, topK?: number) =>
+    invoke<SemanticMatch[]>("semantic_search", { query, topK }),
+};
+
+// Background job commands
+export const jobs = {
+  cancel: (jobId: string) => invoke<boolean>("cancel_job", { jobId }),
+  list: () => invoke<string[]>("list_jobs"),
+};
+
+// Clipboard commands
+export const clipboard = {
+  readText: () => invoke<string>("clipboard_read_text"),
+  writeText: (text: string) => invoke("clipboard_write_text", { text }),
+  /** 剪贴板中没有图片时返回 null */
+  readImage: () => invoke<ClipboardImage | null>("clipboard_read_image"),
+};
+
+// Screenshot commands
+export const screenshot = {
+  capture: (mode: ScreenshotMode, options?: { region?: ScreenshotRegion; windowId?: number }) =>
+    invoke<Screenshot>("capture_screenshot", {
+      mode,
+      region: options?.region,
+      windowId: options?.windowId,
+    }),
+  listWindows: () => invoke<ScreenshotWindow[]>("list_screenshot_windows"),
+};
+
+// Audio recording commands
+export const audio = {
+  listInputDevices: () => invoke<AudioInputDevice[]>("list_audio_input_devices"),
+  /** 返回录音文件路径 */
+  startRecording: (device?: string) => invoke<string>("start_recording", { device }),
+  stopRecording: () => invoke<Recording>("stop_recording"),
+  isRecording: () => invoke<boolean>("is_recording"),
+};
+
+// Path sandbox commands
+export const sandbox = {
+  get: () => invoke<PathSandboxStatus>("get_path_sandbox"),
+  setEnabled: (enabled: boolean) => invoke("set_path_sandbox_enabled", { enabled }),
+  grantPath: (path: string) => invoke<string>("grant_path_access", { path }),
+  revokePath: (path: string) => invoke("revoke_path_access", { path }),
+};
+
+// Usage tracking commands
+export const usage = {
+  getSummary: (range: UsageRange) => invoke<UsageSummary>("get_usage_summary", { range }),
+  /** modelId 为 "provider/model" 时只统计该服务商，否则按服务商分别统计 */
+  getModelPerformance: (modelId: string, range: UsageRange) =>
+    invoke<ModelPerformance>("get_model_performance", { modelId, range }),
+};
+
+// Agent activity commands
+export const activity = {
+  /** 会话最近的工具调用（按开始顺序），limit 为空时返回全部保留的记录 */
+  getSessionActivity: (sessionId: string, limit?: number) =>
+    invoke<ActivityEntry[]>("get_session_activity", { sessionId, limit }),
+};
+
+// Audit log commands
+export const audit = {
+  readLog: (filter?: AuditLogFilter) => invoke<AuditEntry[]>("read_audit_log", { filter }),
+};
+
+// Application log commands
+export const logs = {
+  getRecent: (lines?: number) => invoke<string[]>("get_recent_logs", { lines }),
+  openDirectory: () => invoke("open_logs_directory"),
+  getLevel: () => invoke<LogLevelSettings>("get_log_level"),
+  setLevel: (level: LogLevel, targets?: string[]) =>
+    invoke<LogLevelSettings>("set_log_level", { level, targets }),
+};
+
+// Diagnostics commands
+export const diagnostics = {
+  createBundle: (outputPath?: string) =>
+    invoke<DiagnosticsBundle>("create_diagnostics_bundle", { outputPath }),
+  getStartupTimings: () => invoke<StartupTimings>("get_startup_timings"),
+  getBootstrapStatus: () => invoke<BootstrapStatus>("get_bootstrap_status"),
+};
+
+// Destructive action consent commands
+export const consent = {
+  listRequests: () => invoke<ConsentRequest[]>("list_consent_requests"),
+  respond: (id: string, decision: ConsentDecision) =>
+    invoke("respond_consent_request", { id, decision }),
+  listRules: () => invoke<ConsentRule[]>("list_consent_rules"),
+  removeRule: (project: string, action: ConsentAction) =>
+    invoke<boolean>("remove_consent_rule", { project, action }),
+};
+
+// Agent memory commands
+export const memory = {
+  listEntries: (agent: string, project?: string) =>
+    invoke<MemoryEntry[]>("list_memory_entries", { agent, project }),
+  listScopes: () => invoke<MemoryScopeSummary[]>("list_memory_scopes"),
+  setEntry: (agent: string, project: string | undefined, key: string, value: string) =>
+    invoke<MemoryEntry>("set_memory_entry", { agent, project, key, value }),
+  deleteEntry: (agent: string, project: string | undefined, key: string) =>
+    invoke<boolean>("delete_memory_entry", { agent, project, key }),
+  clear: (agent: string, project?: string) => invoke("clear_memory", { agent, project }),
+};
+
+// Process resource usage commands
+export const stats = {
+  getProcessStats: () => invoke<ProcessStats>("get_process_stats"),
+};
+
+// OpenCode plugin management commands（修改后需重启 OpenCode 生效）
+export const opencodePlugins = {
+  list: () => invoke<OpencodePlugin[]>("list_opencode_plugins"),
+  add: (urlOrPath: string) => invoke<OpencodePlugin>("add_opencode_plugin", { urlOrPath }),
+  remove: (id: string) => invoke("remove_opencode_plugin", { id }),
+};
+
+// Bridge plugin update commands
+export const bridgeUpdate = {
+  check: () => invoke<BridgeUpdateInfo>("check_bridge_update"),
+  update: () => invoke<BridgeUpdateResult>("update_bridge_plugin"),
+  getRepo: () => invoke<string>("get_bridge_update_repo"),
+  setRepo: (repo: string) => invoke("set_bridge_update_repo", { repo }),
+};
+
+// Plugin API commands
+export const pluginApi = {
+  getMetrics: () => invoke<PluginApiMetrics>("get_plugin_api_metrics"),
+  getRateLimit: () => invoke<RateLimitSettings>("get_plugin_api_rate_limit"),
+  setRateLimit: (rateLimit: RateLimitSettings) =>
+    invoke("set_plugin_api_rate_limit", { rateLimit }),
+  getSessionOverrides: (sessionId: string) =>
+    invoke<Record<string, AgentOverride>>("get_session_agent_overrides", {
+      sessionId,
+    }),
+  setSessionOverride: (
+    sessionId: string,
+    agent: string,
+    overrides: AgentOverride
+  ) =>
+    invoke<Record<string, AgentOverride>>("set_session_agent_override", {
+      sessionId,
+      agent,
+      overrides,
+    }),
+  clearSessionOverrides: (sessionId: string) =>
+    invoke<boolean>("clear_session_agent_overrides", { sessionId }),
+};
+
+// Custom tool commands（修改后需重启 OpenCode 生效）
+export const tools = {
+  list: () => invoke<ToolDefinition[]>("list_tools"),
+  get: (name: string) => invoke<ToolDefinition | null>("get_tool", { name }),
+  save: (tool: ToolDefinition) => invoke<ToolDefinition>("save_tool", { tool }),
+  delete: (name: string) => invoke<boolean>("delete_tool", { name }),
+  test: (name: string, args: Record<string, unknown>) =>
+    invoke<ToolExecution>("test_tool", { name, args }),
+  listSecrets: () => invoke<string[]>("list_tool_secrets"),
+  setSecret: (name: string, value: string) => invoke("set_tool_secret", { name, value }),
+  deleteSecret: (name: string) => invoke("delete_tool_secret", { name }),
+};
+
+// Run sandbox commands
+export const runSandbox = {
+  create: (projectDir: string) => invoke<RunSandbox>("create_sandbox", { projectDir }),
+  list: () => invoke<RunSandbox[]>("list_sandboxes"),
+  getDiff: (sandboxId: string, paths?: string[]) =>
+    invoke<SandboxDiff>("get_sandbox_diff", { sandboxId, paths }),
+  promote: (sandboxId: string, paths?: string[]) =>
+    invoke<SandboxDiff>("promote_sandbox", { sandboxId, paths }),
+  discard: (sandboxId: string) => invoke("discard_sandbox", { sandboxId }),
+};
+
+// Team config sync commands
+export const teamSync = {
+  getSettings: () => invoke<TeamSyncSettings>("get_team_sync_settings"),
+  setSettings: (teamSync: TeamSyncSettings) =>
+    invoke<TeamSyncStatus>("set_team_sync_settings", { teamSync }),
+  getStatus: () => invoke<TeamSyncStatus>("get_team_sync_status"),
+  sync: () => invoke<TeamSyncStatus>("sync_team_config"),
+};
+
+// Bookmark commands
+export const bookmarks = {
+  list: (projectDir: string) => invoke<Bookmark[]>("list_bookmarks", { projectDir }),
+  /** 路径已收藏时更新备注和颜色 */
+  add: (projectDir: string, path: string, note?: string, color?: BookmarkColor) =>
+    invoke<Bookmark>("add_bookmark", { projectDir, path, note, color }),
+  remove: (projectDir: string, path: string) =>
+    invoke<boolean>("remove_bookmark", { projectDir, path }),
+};
+
+// Conversation branch commands
+export const branches = {
+  list: (sessionId: string) => invoke<Branch[]>("list_branches", { sessionId }),
+  /** 同一会话内名称不能重复；branchSessionId 为 OpenCode 分叉出的会话 */
+  create: (sessionId: string, messageId: string, name: string, branchSessionId?: string) =>
+    invoke<Branch>("create_branch", { sessionId, messageId, name, branchSessionId }),
+  /** 不删除分叉出的会话 */
+  delete: (sessionId: string, branchId: string) =>
+    invoke<boolean>("delete_branch", { sessionId, branchId }),
+};
+
+// Prompt snippet commands
+export const snippets = {
+  /** 固定的在前，其余按名称排序 */
+  list: () => invoke<Snippet[]>("list_snippets"),
+  save: (snippet: SnippetInput) => invoke<Snippet>("save_snippet", { snippet }),
+  delete: (id: string) => invoke<boolean>("delete_snippet", { id }),
+  /** 未提供的变量保持 {{ 变量 }} 原样 */
+  expand: (id: string, variables?: Record<string, string>) =>
+    invoke<string>("expand_snippet", { id, variables }),
+};
+
+// Spellcheck commands（词典为 Hunspell 格式，放在应用数据目录的 dictionaries 下或使用系统词典）
+export const spellcheck = {
+  /** language 如 en_US、en-US 或 en */
+  check: (text: string, language: string) =>
+    invoke<SpellingIssue[]>("check_spelling", { text, language }),
+  /** 对所有语言生效，返回是否新增 */
+  addToDictionary: (word: string) => invoke<boolean>("add_to_dictionary", { word }),
+  languages: () => invoke<string[]>("list_spelling_languages"),
+};
+
+// Context pin commands（projectDir 为空时使用当前项目目录）
+export const contextPins = {
+  list: (projectDir?: string) => invoke<ContextPin[]>("list_context_pins", { projectDir }),
+  pin: (path: string, projectDir?: string) =>
+    invoke<ContextPin>("pin_for_context", { path, projectDir }),
+  unpin: (path: string, projectDir?: string) => invoke<boolean>("unpin", { path, projectDir }),
+};
+
+// Workspace stats commands（后台任务，可用 jobs.cancel(jobId) 取消；结果缓存 5 分钟）
+export const workspaceStats = {
+  get: (jobId: string, projectDir?: string, refresh?: boolean) =>
+    invoke<WorkspaceStats>("get_workspace_stats", { jobId, projectDir, refresh }),
+};
+
+// Ignore rule commands（projectDir 为空时使用当前项目目录）
+export const ignoreRules = {
+  get: (projectDir?: string) => invoke<IgnoreRules>("get_ignore_rules", { projectDir }),
+  set: (file: IgnoreFile, content: string, projectDir?: string) =>
+    invoke<void>("set_ignore_rules", { file, content, projectDir }),
+  /** 返回被忽略的路径（相对项目目录） */
+  check: (paths: string[], projectDir?: string) =>
+    invoke<string[]>("check_ignored_paths", { paths, projectDir }),
+};
+
+// Markdown commands
+export const markdown = {
+  /** 渲染为已清理的 HTML，可直接插入页面 */
+  render: (text: string, options?: MarkdownOptions) =>
+    invoke<string>("render_markdown", { text, options }),
+};
+
+// Syntax highlight commands
+export const highlight = {
+  /** language 可以是语言名称或扩展名 */
+  code: (text: string, language?: string, theme?: string, format?: HighlightFormat) =>
+    invoke<HighlightedCode>("highlight_code", { text, language, theme, format }),
+  /** 分块高亮大文件，行内容通过 EVENT_HIGHLIGHT_CHUNK 事件发送（可用 jobs.cancel(jobId) 取消） */
+  file: (jobId: string, path: string, language?: string, theme?: string, format?: HighlightFormat) =>
+    invoke<HighlightedFile>("highlight_file", { jobId, path, language, theme, format }),
+  listThemes: () => invoke<string[]>("list_highlight_themes"),
+};
+
+// Notebook commands
+export const notebooks = {
+  read: (path: string) => invoke<Notebook>("read_notebook", { path }),
+  /** 写入全部单元格，保留文件原有的元数据 */
+  write: (path: string, cells: NotebookCell[]) =>
+    invoke<Notebook>("write_notebook", { path, cells }),
+};
+
+// PDF commands
+export const pdf = {
+  /** pageRange 形如 "1-3,5,8-"，为空时提取全部页 */
+  extractText: (path: string, pageRange?: string) =>
+    invoke<PdfText>("extract_pdf_text", { path, pageRange }),
+};
+
+// Diff review session commands
+export const reviews = {
+  create: (session: NewReview) => invoke<Review>("create_review", { session }),
+  list: (projectDir?: string) => invoke<ReviewSummary[]>("list_reviews", { projectDir }),
+  get: (id: string) => invoke<Review>("get_review", { id }),
+  /** hunkIndices 为空时作用于整个文件 */
+  decide: (id: string, path: string, decision: ReviewDecision, hunkIndices?: number[]) =>
+    invoke<Review>("set_review_decision", { id, path, hunkIndices, decision }),
+  /** 所有 hunk 都决定后才能定稿；文件在审查期间被修改时不写入任何文件 */
+  finalize: (id: string) => invoke<FinalizeReviewResult>("finalize_review", { id }),
+  delete: (id: string) => invoke<boolean>("delete_review", { id }),
+};
+
+// Project template commands
+export const projectTemplates = {
+  list: () => invoke<ProjectTemplate[]>("list_project_templates"),
+  delete: (templateId: string) => invoke<boolean>("delete_project_template", { templateId }),
+  /** 目标目录必须不存在或为空 */
+  create: (
+    templateId: string,
+    destDir: string,
+    variables?: Record<string, string>,
+    initGit?: boolean,
+  ) =>
+    invoke<CreatedProject>("create_project_from_template", {
+      templateId,
+      destDir,
+      variables,
+      initGit,
+    }),
+};
+
+// Idle and power commands
+export const power = {
+  getState: () => invoke<PowerState>("get_power_state"),
+  getSettings: () => invoke<PowerSettings>("get_power_settings"),
+  setSettings: (power: PowerSettings) => invoke<PowerState>("set_power_settings", { power }),
+};
+
+// System notification commands
+export const notifications = {
+  notify: (title: string, body: string, kind?: NotificationKind) =>
+    invoke("notify", { title, body, kind }),
+  getSettings: () => invoke<NotificationSettings>("get_notification_settings"),
+  setSettings: (notifications: NotificationSettings) =>
+    invoke("set_notification_settings", { notifications }),
+};
+
+// Webhook notification commands
+export const webhooks = {
+  get: () => invoke<WebhookConfig[]>("get_webhooks"),
+  set: (webhooks: WebhookConfig[]) => invoke("set_webhooks", { webhooks }),
+  /** 发送测试消息，返回 HTTP 状态码 */
+  test: (id: string) => invoke<number>("test_webhook", { id }),
+  reportWorkflowFinished: (
+    workflowId: string,
+    workflowName: string,
+    success: boolean,
+    summary?: string,
+  ) =>
+    invoke("report_workflow_finished", { workflowId, workflowName, success, summary }),
 };
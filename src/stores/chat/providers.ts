@@ -6,6 +6,7 @@
 
 import { useCallback } from "react";
 import type { OpencodeClient } from "@/services/opencode/types";
+import { modelFilters } from "@/services/tauri";
 import { 
   MODEL_STORAGE_KEY, 
   VARIANT_STORAGE_KEY, 
@@ -55,19 +56,29 @@ export function useRefreshProviders(
         // 例如: { "coder": "anthropic/claude-3-5-sonnet", "task": "openai/gpt-4" }
         const defaultModels = providerData.default as Record<string, string> | undefined;
         
+        // 按设置中的模型过滤规则隐藏被禁止的模型
+        const allModelKeys = all
+          .filter((p) => connected.includes(p.id))
+          .flatMap((p) => Object.keys(p.models).map((modelId) => `${p.id}/${modelId}`));
+        const allowedModels = new Set(
+          await modelFilters.filterAllowed(allModelKeys).catch(() => allModelKeys)
+        );
+
         // 只显示已连接的 providers
         const connectedProviders = all
           .filter((p) => connected.includes(p.id))
           .map((p) => ({
             id: p.id,
             name: p.name,
-            models: Object.entries(p.models).map(([modelId, model]) => ({
-              id: modelId,
-              name: model.name,
-              provider: p.id,
-              // 解析模型的 variants 配置
-              variants: model.variants,
-            })),
+            models: Object.entries(p.models)
+              .filter(([modelId]) => allowedModels.has(`${p.id}/${modelId}`))
+              .map(([modelId, model]) => ({
+                id: modelId,
+                name: model.name,
+                provider: p.id,
+                // 解析模型的 variants 配置
+                variants: model.variants,
+              })),
           }));
         
         setProviders(connectedProviders);
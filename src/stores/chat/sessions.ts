@@ -8,6 +8,7 @@ import { useCallback } from "react";
 import type { Session as ApiSession } from "@opencode-ai/sdk/v2";
 import type { OpencodeClient } from "@/services/opencode/types";
 import type { Session, Message } from "@/types/chat";
+import { getErrorMessage } from "@/types/error";
 import { mapApiSession } from "./utils";
 
 // ============== 类型定义 ==============
@@ -130,7 +131,7 @@ export function useCreateNewSession(deps: CreateSessionDeps) {
       }
     } catch (e) {
       console.error("创建会话失败:", e);
-      const detail = e instanceof Error ? e.message : "";
+      const detail = getErrorMessage(e, "");
       setError(detail ? t("errors.sendMessageFailedWithDetail", { detail }) : t("errors.createSessionFailed"));
     }
   }, [client, t, defaultDirectory, setSessions, setActiveSessionId, setMessages, setError]);
@@ -204,7 +205,7 @@ export function useRefreshSessions(
       }
     } catch (e) {
       console.error("刷新会话列表失败:", e);
-      const detail = e instanceof Error ? e.message : "";
+      const detail = getErrorMessage(e, "");
       setError(detail ? t("errors.sendMessageFailedWithDetail", { detail }) : t("errors.loadSessionsFailed"));
     }
   }, [client, activeSessionId, setSessions, setActiveSessionId, setError, loadMessages, checkAndRestoreSessionStatus, createNewSession, t]);
@@ -301,7 +302,7 @@ export function useDeleteSession(
       }
     } catch (e) {
       console.error("删除会话失败:", e);
-      const detail = e instanceof Error ? e.message : "";
+      const detail = getErrorMessage(e, "");
       setError(detail ? t("errors.sendMessageFailedWithDetail", { detail }) : t("errors.deleteSessionFailed"));
     }
   }, [client, activeSessionId, setSessions, setActiveSessionId, setError, loadMessages, createNewSession, t]);
@@ -365,7 +366,7 @@ export function useClearAllSessions(
       await createNewSession(directory);
     } catch (e) {
       console.error("清除所有会话失败:", e);
-      const detail = e instanceof Error ? e.message : "";
+      const detail = getErrorMessage(e, "");
       setError(detail ? t("errors.sendMessageFailedWithDetail", { detail }) : t("errors.deleteSessionFailed"));
     }
   }, [client, sessions, setSessions, setMessages, setError, createNewSession, t]);
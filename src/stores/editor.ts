@@ -10,7 +10,7 @@
  */
 
 import { create } from "zustand";
-import type { OpenedTab } from "./layout";
+import { touchRecentFile, type OpenedTab } from "./layout";
 
 // 打开的文件标签
 export interface EditorTab {
@@ -177,6 +177,7 @@ export const useEditor = create<EditorStore>()((set, get) => ({
   openFile: (path: string, name: string) => {
     const { tabs } = get();
     const existingTab = tabs.find((t) => t.path === path);
+    touchRecentFile(path);
 
     if (existingTab) {
       set({ activeTabPath: path, isVisible: true });
@@ -5,6 +5,7 @@
  * - 面板宽度比例
  * - 打开的文件标签
  * - 编辑器可见性
+ * - 窗口位置和大小（由后端读写，切换项目时保存 / 恢复）
  * - 与 Rust 后端同步持久化
  * 
  * 设计原则：
@@ -15,6 +16,7 @@
 
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
+import { getErrorMessage } from "@/types/error";
 
 // ============== 类型定义 ==============
 
@@ -28,6 +30,23 @@ export interface OpenedTab {
   language: string;
 }
 
+/** 窗口位置和大小（物理像素，与 Rust 后端对应） */
+export interface WindowGeometry {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  /** 是否最大化 */
+  maximized: boolean;
+}
+
+/** 最近打开的文件（与 Rust 后端对应） */
+export interface RecentFile {
+  path: string;
+  /** 最后打开时间（Unix 时间戳毫秒） */
+  opened_at: number;
+}
+
 /** 工作区布局配置（与 Rust 后端对应） */
 export interface WorkspaceLayout {
   /** 项目目录（用于标识） */
@@ -44,6 +63,10 @@ export interface WorkspaceLayout {
   active_tab_path: string | null;
   /** 编辑器面板是否可见 */
   editor_visible: boolean;
+  /** 窗口位置和大小（只读，保存布局时后端会保留已有值） */
+  window_geometry?: WindowGeometry | null;
+  /** 最近打开的文件（只读，由 touchRecentFile 维护） */
+  recent_files?: RecentFile[];
   /** 最后更新时间（Unix 时间戳毫秒） */
   updated_at: number;
 }
@@ -180,6 +203,34 @@ function deferredSave(getLayout: () => WorkspaceLayout | null): void {
   }, SAVE_DELAY);
 }
 
+/** 保存当前窗口位置到项目布局 */
+function saveWindowState(projectDirectory: string): Promise<void> {
+  return invoke<void>("save_project_window_state", { projectDirectory }).catch((e) => {
+    console.warn("[Layout] 保存窗口状态失败:", e);
+  });
+}
+
+/** 恢复项目保存的窗口位置 */
+function restoreWindowState(projectDirectory: string): void {
+  invoke<boolean>("restore_project_window_state", { projectDirectory }).catch((e) => {
+    console.warn("[Layout] 恢复窗口状态失败:", e);
+  });
+}
+
+/** 记录最近打开的文件（当前项目） */
+export function touchRecentFile(path: string): void {
+  const projectDirectory = useLayout.getState().currentProjectDirectory;
+  if (!projectDirectory) return;
+  invoke<void>("touch_recent_file", { path, projectDirectory }).catch((e) => {
+    console.warn("[Layout] 记录最近文件失败:", e);
+  });
+}
+
+/** 项目最近打开的文件（最近的在前） */
+export function listRecentFiles(projectDirectory: string, limit?: number): Promise<RecentFile[]> {
+  return invoke<RecentFile[]>("list_recent_files", { projectDirectory, limit });
+}
+
 // ============== Store 实现 ==============
 
 export const useLayout = create<LayoutStore>((set, get) => ({
@@ -199,6 +250,14 @@ export const useLayout = create<LayoutStore>((set, get) => ({
       return;
     }
 
+    // 切换项目时先记录上一个项目的窗口位置，再恢复新项目的
+    const previousDirectory = state.currentProjectDirectory;
+    if (previousDirectory) {
+      saveWindowState(previousDirectory).then(() => restoreWindowState(projectDirectory));
+    } else {
+      restoreWindowState(projectDirectory);
+    }
+
     // 1. 先同步从缓存加载（立即可用，无闪烁）
     const cachedLayout = loadFromCache(projectDirectory);
     
@@ -262,7 +321,7 @@ export const useLayout = create<LayoutStore>((set, get) => ({
       console.log("[Layout] 布局已保存");
     } catch (e) {
       console.error("[Layout] 保存布局失败:", e);
-      set({ error: e instanceof Error ? e.message : "保存布局失败" });
+      set({ error: getErrorMessage(e, "保存布局失败") });
     }
   },
 
@@ -376,6 +435,7 @@ if (typeof window !== "undefined") {
       saveToCache(layout);
       // 异步保存到 Rust（尽力而为）
       invoke("save_workspace_layout", { layout }).catch(() => {});
+      saveWindowState(layout.project_directory);
     }
   });
 }
@@ -29,6 +29,7 @@ import {
   saveOrchestration,
   deleteOrchestration as deleteOrchestrationFromFile,
 } from "@/services/orchestration";
+import { getErrorMessage } from "@/types/error";
 
 // ============================================================================
 // 类型定义
@@ -145,7 +146,7 @@ export const useOrchestrationStoreV2 = create<OrchestrationStore>()((set, get) =
         isLoading: false,
       });
     } catch (error) {
-      const message = error instanceof Error ? error.message : String(error);
+      const message = getErrorMessage(error);
       set({
         isLoading: false,
         error: message,
@@ -18,6 +18,7 @@ import {
   deleteAgent as deleteAgentFromFile,
   type AgentSummary,
 } from "@/services/agent";
+import { getErrorMessage } from "@/types/error";
 
 interface CanvasSelection {
   type: "primary" | "subagent" | "edge" | null;
@@ -104,7 +105,7 @@ export const useOrchestrationStore = create<AgentStore>()((set, get) => ({
         isLoadingAgents: false,
       });
     } catch (error) {
-      const message = error instanceof Error ? error.message : String(error);
+      const message = getErrorMessage(error);
       set({
         isLoadingAgents: false,
         agentsError: message,
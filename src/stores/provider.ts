@@ -9,8 +9,12 @@ import type {
   CustomConfig,
   ProviderAuthMethod,
   OAuthAuthorization,
+  ConnectionTestResult,
+  OAuthClientConfig,
+  OAuthSession,
 } from "@/types/provider";
 import type { OpencodeClient } from "@/services/opencode/types";
+import { getErrorMessage } from "@/types/error";
 
 interface ProviderStore {
   userProviders: UserProviderConfig[];
@@ -34,6 +38,9 @@ interface ProviderStore {
   // OAuth 相关方法
   startOAuthAuthorize: (client: OpencodeClient, providerID: string, method: number) => Promise<OAuthAuthorization | null>;
   completeOAuthCallback: (client: OpencodeClient, providerID: string, method: number, code?: string) => Promise<boolean>;
+  // 由 Axon 后端执行的 OAuth 流程（进度见 oauth:progress 事件）
+  startProviderOAuth: (providerID: string, config?: OAuthClientConfig) => Promise<OAuthSession | null>;
+  cancelProviderOAuth: (sessionId: string) => Promise<boolean>;
   
   addProvider: (config: {
     registryId: string;
@@ -376,13 +383,13 @@ export const useProviderStore = create<ProviderStore>()(
 
       testConnection: async (id) => {
         try {
-          const result = await invoke<boolean>("test_provider_connection", { id });
-          if (result) {
-            toast.success("连接测试成功");
+          const result = await invoke<ConnectionTestResult>("test_provider_connection", { id });
+          if (result.success) {
+            toast.success(`连接测试成功（${result.latencyMs}ms）`);
           } else {
-            toast.error("连接测试失败");
+            toast.error(`连接测试失败：${result.message}`);
           }
-          return result;
+          return result.success;
         } catch (error) {
           console.error("测试连接失败:", error);
           toast.error("测试连接失败");
@@ -425,7 +432,7 @@ export const useProviderStore = create<ProviderStore>()(
           console.error(`启动 OAuth 授权失败 (${providerID}):`, error);
           
           // 解析错误信息
-          const errorStr = String(error);
+          const errorStr = getErrorMessage(error);
           if (errorStr.includes("port") && errorStr.includes("in use")) {
             toast.error("OAuth 服务端口 1455 被占用，请关闭占用该端口的程序后重试");
           } else {
@@ -463,6 +470,25 @@ export const useProviderStore = create<ProviderStore>()(
           return false;
         }
       },
+
+      startProviderOAuth: async (providerID, config) => {
+        try {
+          return await invoke<OAuthSession>("start_provider_oauth", { providerId: providerID, config });
+        } catch (error) {
+          console.error(`启动 OAuth 授权失败 (${providerID}):`, error);
+          toast.error(`启动 OAuth 授权失败: ${getErrorMessage(error)}`);
+          return null;
+        }
+      },
+
+      cancelProviderOAuth: async (sessionId) => {
+        try {
+          return await invoke<boolean>("cancel_provider_oauth", { sessionId });
+        } catch (error) {
+          console.error(`取消 OAuth 授权失败 (${sessionId}):`, error);
+          return false;
+        }
+      },
     }),
     {
       name: "axon-provider-store",
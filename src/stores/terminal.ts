@@ -8,9 +8,12 @@
 
 import { create } from "zustand";
 import { persist, createJSONStorage } from "zustand/middleware";
+import { invoke } from "@tauri-apps/api/core";
 import type { OpencodeClient } from "@/services/opencode";
 import type {
+  ResolvedShell,
   TerminalConfig,
+  TerminalSessionSnapshot,
   TerminalStatus,
   QuickCommand,
 } from "@/types/terminal";
@@ -18,6 +21,7 @@ import {
   DEFAULT_QUICK_COMMANDS,
   DEFAULT_TERMINAL_CONFIG,
 } from "@/types/terminal";
+import { getErrorMessage } from "@/types/error";
 
 // 终端标签页（兼容 opencode PTY）
 export interface TerminalTab {
@@ -27,6 +31,7 @@ export interface TerminalTab {
   status: TerminalStatus;
   cwd: string;
   pid?: number;
+  profileId?: string;        // 使用的 Shell 配置 ID
   createdAt: number;
   // 状态恢复相关
   buffer?: string;           // 序列化的终端内容
@@ -62,13 +67,17 @@ interface TerminalActions {
   toggleVisible: () => void;
 
   // 标签页管理
-  createTab: (cwd?: string) => Promise<string>;
+  createTab: (cwd?: string, profileId?: string) => Promise<string>;
   closeTab: (id: string) => Promise<void>;
   clearAllTabs: () => void;
   selectTab: (id: string) => void;
   updateTab: (id: string, updates: Partial<TerminalTab>) => void;
   renameTab: (id: string, title: string) => void;
 
+  // 会话持久化
+  persistSessions: () => Promise<void>;
+  restoreTabs: () => Promise<number>;
+
   // 配置
   updateConfig: (config: Partial<TerminalConfig>) => void;
   resetConfig: () => void;
@@ -121,7 +130,7 @@ export const useTerminal = create<TerminalStore>()(
       setVisible: (visible: boolean) => set({ isVisible: visible }),
       toggleVisible: () => set((state) => ({ isVisible: !state.isVisible })),
 
-      createTab: async (cwd?: string): Promise<string> => {
+      createTab: async (cwd?: string, profileId?: string): Promise<string> => {
         const { _client, _directory, tabs } = get();
 
         if (!_client) {
@@ -140,11 +149,27 @@ export const useTerminal = create<TerminalStore>()(
             nextNumber++;
           }
 
+          // 解析 Shell 配置（失败时交给 opencode 使用默认 Shell）
+          let shell: ResolvedShell | null = null;
+          try {
+            shell = await invoke<ResolvedShell>("resolve_shell_profile", {
+              profileId: profileId ?? null,
+              cwd: cwd || _directory || null,
+            });
+          } catch (e) {
+            console.warn("[Terminal] 解析 Shell 配置失败:", e);
+          }
+
           // 调用 opencode PTY API 创建会话
           const response = await _client.pty.create({
             directory: _directory,
             title: `Terminal ${nextNumber}`,
-            cwd: cwd || _directory,
+            cwd: shell?.cwd || cwd || _directory,
+            ...(shell && {
+              command: shell.command,
+              args: shell.args,
+              env: shell.env,
+            }),
           });
 
           const ptyInfo = response.data;
@@ -159,6 +184,7 @@ export const useTerminal = create<TerminalStore>()(
             status: "connected",
             cwd: ptyInfo.cwd || cwd || _directory,
             pid: ptyInfo.pid,
+            profileId: shell?.profileId,
             createdAt: Date.now(),
           };
 
@@ -171,7 +197,7 @@ export const useTerminal = create<TerminalStore>()(
 
           return newTab.id;
         } catch (e) {
-          const error = e instanceof Error ? e.message : "创建终端失败";
+          const error = getErrorMessage(e, "创建终端失败");
           set({ error, isLoading: false });
           throw e;
         }
@@ -211,6 +237,52 @@ export const useTerminal = create<TerminalStore>()(
         set({ tabs: [], activeTabId: null, isVisible: false });
       },
 
+      persistSessions: async () => {
+        const sessions: TerminalSessionSnapshot[] = get().tabs.map((tab) => ({
+          title: tab.title,
+          profileId: tab.profileId ?? null,
+          cwd: tab.cwd,
+          scrollback: tab.buffer ?? "",
+          rows: tab.rows ?? null,
+          cols: tab.cols ?? null,
+        }));
+        try {
+          await invoke("save_terminal_sessions", { sessions });
+        } catch (e) {
+          console.warn("[Terminal] 保存终端会话失败:", e);
+        }
+      },
+
+      restoreTabs: async (): Promise<number> => {
+        let sessions: TerminalSessionSnapshot[] = [];
+        try {
+          sessions = await invoke<TerminalSessionSnapshot[]>("restore_terminals");
+        } catch (e) {
+          console.warn("[Terminal] 读取终端会话失败:", e);
+          return 0;
+        }
+
+        let restored = 0;
+        for (const session of sessions) {
+          try {
+            const id = await get().createTab(session.cwd, session.profileId ?? undefined);
+            const savedAt = session.savedAt
+              ? new Date(session.savedAt).toLocaleString()
+              : "";
+            // 回放上次的输出，并用暗色分隔线标记恢复点
+            const header = `\x1b[2m── 已恢复会话 ${savedAt} ──\x1b[0m\r\n`;
+            get().updateTab(id, {
+              title: session.title,
+              buffer: session.scrollback ? `${session.scrollback}\r\n${header}` : undefined,
+            });
+            restored++;
+          } catch (e) {
+            console.warn("[Terminal] 恢复终端失败:", session.cwd, e);
+          }
+        }
+        return restored;
+      },
+
       selectTab: (id: string) => set({ activeTabId: id }),
 
       updateTab: (id: string, updates: Partial<TerminalTab>) => {
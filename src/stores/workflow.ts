@@ -5,6 +5,7 @@
  * - 工作流列表的加载和管理
  * - 当前编辑中的工作流
  * - 工作流的 CRUD 操作
+ * - 从内置模板创建工作流
  * - 画布状态（节点选中、视口等）
  */
 
@@ -14,6 +15,11 @@ import { persist } from "zustand/middleware";
 import {
   type WorkflowDefinition,
   type WorkflowSummary,
+  type WorkflowTemplate,
+  type WorkflowBundleExport,
+  type WorkflowBundleImport,
+  type BundleConflictResolutions,
+  type ExecutionPlan,
   type SubagentConfig,
   type DelegationRule,
   createDefaultWorkflow,
@@ -21,6 +27,7 @@ import {
   createDefaultDelegationRule,
   workflowToSummary,
 } from "@/types/workflow";
+import { getErrorMessage } from "@/types/error";
 
 // ============================================================================
 // 类型定义
@@ -39,6 +46,9 @@ interface WorkflowState {
   /** 工作流列表摘要 */
   workflows: WorkflowSummary[];
   
+  /** 内置工作流模板 */
+  templates: WorkflowTemplate[];
+  
   /** 当前编辑中的工作流 */
   currentWorkflow: WorkflowDefinition | null;
   
@@ -71,6 +81,28 @@ interface WorkflowActions {
   /** 复制工作流 */
   duplicateWorkflow: (id: string) => Promise<WorkflowDefinition | null>;
   
+  /** 加载内置模板列表 */
+  loadTemplates: () => Promise<void>;
+  
+  /** 用模板创建工作流（未提供的参数使用默认值） */
+  createWorkflowFromTemplate: (
+    templateId: string,
+    params: Record<string, string>,
+    name?: string
+  ) => Promise<WorkflowSummary | null>;
+  
+  /** 试运行：不调用模型，返回执行计划和估算费用 */
+  planWorkflowExecution: (id: string, input: string) => Promise<ExecutionPlan | null>;
+  
+  /** 导出工作流包（含引用的 Agent 和自定义工具） */
+  exportWorkflowBundle: (id: string, destPath: string) => Promise<WorkflowBundleExport | null>;
+  
+  /** 导入工作流包，有冲突时返回冲突列表，选择处理方式后再次调用 */
+  importWorkflowBundle: (
+    path: string,
+    resolutions?: BundleConflictResolutions
+  ) => Promise<WorkflowBundleImport | null>;
+  
   // ========== 当前工作流操作 ==========
   
   /** 加载工作流到编辑器 */
@@ -82,6 +114,9 @@ interface WorkflowActions {
   /** 保存当前工作流 */
   saveCurrentWorkflow: () => Promise<void>;
   
+  /** 自动布局当前工作流的画布节点（先保存未保存的修改） */
+  autoLayoutCurrentWorkflow: () => Promise<void>;
+  
   /** 关闭当前工作流 */
   closeCurrentWorkflow: () => void;
   
@@ -158,6 +193,7 @@ type WorkflowStore = WorkflowState & WorkflowActions;
 
 const initialState: WorkflowState = {
   workflows: [],
+  templates: [],
   currentWorkflow: null,
   hasUnsavedChanges: false,
   selection: { type: null, id: null },
@@ -183,7 +219,7 @@ export const useWorkflowStore = create<WorkflowStore>()(
           const summaries = await invoke<WorkflowSummary[]>("list_workflows");
           set({ workflows: summaries, isLoading: false });
         } catch (error) {
-          const message = error instanceof Error ? error.message : String(error);
+          const message = getErrorMessage(error);
           set({ error: message, isLoading: false });
           console.error("加载工作流列表失败:", message);
         }
@@ -249,6 +285,83 @@ export const useWorkflowStore = create<WorkflowStore>()(
         }
       },
 
+      loadTemplates: async () => {
+        try {
+          const templates = await invoke<WorkflowTemplate[]>("list_workflow_templates");
+          set({ templates });
+        } catch (error) {
+          console.error("加载工作流模板失败:", getErrorMessage(error));
+        }
+      },
+
+      createWorkflowFromTemplate: async (templateId, params, name) => {
+        try {
+          const summary = await invoke<WorkflowSummary>("instantiate_workflow_template", {
+            templateId,
+            params,
+            name: name ?? null,
+          });
+          set((state) => ({
+            workflows: [summary, ...state.workflows],
+          }));
+          return summary;
+        } catch (error) {
+          const message = getErrorMessage(error);
+          set({ error: message });
+          console.error("从模板创建工作流失败:", message);
+          return null;
+        }
+      },
+
+      planWorkflowExecution: async (id, input) => {
+        try {
+          return await invoke<ExecutionPlan>("plan_workflow_execution", {
+            workflowId: id,
+            input,
+          });
+        } catch (error) {
+          const message = getErrorMessage(error);
+          set({ error: message });
+          console.error("生成执行计划失败:", message);
+          return null;
+        }
+      },
+
+      exportWorkflowBundle: async (id, destPath) => {
+        try {
+          return await invoke<WorkflowBundleExport>("export_workflow_bundle", {
+            workflowId: id,
+            destPath,
+          });
+        } catch (error) {
+          const message = getErrorMessage(error);
+          set({ error: message });
+          console.error("导出工作流包失败:", message);
+          return null;
+        }
+      },
+
+      importWorkflowBundle: async (path, resolutions) => {
+        try {
+          const result = await invoke<WorkflowBundleImport>("import_workflow_bundle", {
+            path,
+            resolutions: resolutions ?? null,
+          });
+          const imported = result.workflow;
+          if (imported) {
+            set((state) => ({
+              workflows: [imported, ...state.workflows],
+            }));
+          }
+          return result;
+        } catch (error) {
+          const message = getErrorMessage(error);
+          set({ error: message });
+          console.error("导入工作流包失败:", message);
+          return null;
+        }
+      },
+
       // ========== 当前工作流操作 ==========
 
       loadWorkflow: async (id) => {
@@ -265,7 +378,7 @@ export const useWorkflowStore = create<WorkflowStore>()(
             isLoading: false,
           });
         } catch (error) {
-          const message = error instanceof Error ? error.message : String(error);
+          const message = getErrorMessage(error);
           set({ error: message, isLoading: false });
           console.error("加载工作流失败:", message);
         }
@@ -312,6 +425,23 @@ export const useWorkflowStore = create<WorkflowStore>()(
         }
       },
 
+      autoLayoutCurrentWorkflow: async () => {
+        const { currentWorkflow, hasUnsavedChanges } = get();
+        if (!currentWorkflow) return;
+        
+        try {
+          if (hasUnsavedChanges) {
+            await get().saveCurrentWorkflow();
+          }
+          await invoke("auto_layout_workflow", { workflowId: currentWorkflow.id });
+          await get().loadWorkflow(currentWorkflow.id);
+        } catch (error) {
+          const message = getErrorMessage(error);
+          set({ error: message });
+          console.error("自动布局工作流失败:", message);
+        }
+      },
+
       closeCurrentWorkflow: () => {
         set({
           currentWorkflow: null,
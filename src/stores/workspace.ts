@@ -9,6 +9,7 @@
 import { useState, useCallback, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { appDataDir } from "@tauri-apps/api/path";
+import { getErrorMessage } from "@/types/error";
 
 // ============== 常量 ==============
 
@@ -170,7 +171,7 @@ export function useWorkspace(): UseWorkspaceReturn {
       setState((prev) => ({
         ...prev,
         isLoading: false,
-        error: e instanceof Error ? e.message : "初始化工作区失败",
+        error: getErrorMessage(e, "初始化工作区失败"),
       }));
     }
   }, [state.isInitialized]);
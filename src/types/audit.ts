@@ -0,0 +1,37 @@
+/**
+ * 审计日志类型定义
+ *
+ * 与 Rust 端 audit 模块保持一致
+ */
+
+export type AuditOutcome = "success" | "failure";
+
+// 单条审计记录
+export interface AuditEntry {
+  /** 调用时间（毫秒时间戳） */
+  timestamp: number;
+  command: string;
+  /** 参数摘要 */
+  args: Record<string, unknown>;
+  outcome: AuditOutcome;
+  /** 失败时的错误类别 */
+  errorKind?: string;
+  /** 失败时的错误信息 */
+  error?: string;
+  durationMs: number;
+}
+
+// 查询条件（均为可选）
+export interface AuditLogFilter {
+  /** 命令名称（包含匹配） */
+  command?: string;
+  outcome?: AuditOutcome;
+  /** 起始时间（毫秒时间戳，包含） */
+  since?: number;
+  /** 结束时间（毫秒时间戳，包含） */
+  until?: number;
+  /** 在参数摘要和错误信息中搜索的关键字 */
+  query?: string;
+  /** 返回条数上限（默认 200） */
+  limit?: number;
+}
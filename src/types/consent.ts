@@ -0,0 +1,48 @@
+/**
+ * 破坏性操作确认类型定义
+ *
+ * 与 Rust 端 consent 模块保持一致
+ */
+
+/** 新的待确认请求事件 */
+export const EVENT_CONSENT_REQUESTED = "consent:requested";
+
+/** 请求结束事件（用户回复或超时） */
+export const EVENT_CONSENT_RESOLVED = "consent:resolved";
+
+export type ConsentAction = "file_delete" | "command_exec";
+
+export type ConsentDecision = "allow_once" | "always_allow" | "deny";
+
+export type ConsentOutcome = "allowed" | "denied" | "timed_out";
+
+// 待确认请求
+export interface ConsentRequest {
+  id: string;
+  action: ConsentAction;
+  /** 发起请求的工具名（如 bash） */
+  tool: string;
+  /** 操作摘要（如完整命令行） */
+  summary: string;
+  /** 项目目录（为空时无法记住"始终允许"） */
+  project?: string;
+  sessionId?: string;
+  /** 创建时间（毫秒时间戳） */
+  createdAt: number;
+  /** 超时时间（毫秒时间戳），超时后自动拒绝 */
+  expiresAt: number;
+}
+
+// 请求结束事件负载
+export interface ConsentResolved {
+  id: string;
+  outcome: ConsentOutcome;
+}
+
+// "始终允许"规则
+export interface ConsentRule {
+  project: string;
+  action: ConsentAction;
+  /** 创建时间（毫秒时间戳） */
+  createdAt: number;
+}
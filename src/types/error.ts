@@ -0,0 +1,62 @@
+/**
+ * 后端命令错误类型（与 Rust AxonError 一致）
+ */
+
+export type AxonErrorKind =
+  | "not_found"
+  | "permission_denied"
+  | "invalid_input"
+  | "already_exists"
+  | "timeout"
+  | "network"
+  | "io"
+  | "invalid_data"
+  | "cancelled"
+  | "unavailable"
+  | "unsupported"
+  | "external"
+  | "internal";
+
+export interface AxonError {
+  kind: AxonErrorKind;
+  /** 可读的错误信息 */
+  message: string;
+  /** 附加信息（如路径、状态码） */
+  details?: unknown;
+  /** 是否可以直接重试 */
+  retryable: boolean;
+  /** 消息码（本地化消息），`message` 已按后端当前语言渲染 */
+  code?: string;
+  /** 消息模板参数 */
+  params?: Record<string, unknown>;
+}
+
+/** 判断 invoke 抛出的错误是否为后端结构化错误 */
+export function isAxonError(error: unknown): error is AxonError {
+  return (
+    typeof error === "object" &&
+    error !== null &&
+    typeof (error as AxonError).kind === "string" &&
+    typeof (error as AxonError).message === "string"
+  );
+}
+
+/** 判断错误是否属于指定类别 */
+export function isErrorKind(error: unknown, kind: AxonErrorKind): boolean {
+  return isAxonError(error) && error.kind === kind;
+}
+
+/**
+ * 提取错误信息
+ *
+ * 兼容后端结构化错误、Error 实例和字符串，无法识别时返回 fallback
+ */
+export function getErrorMessage(error: unknown, fallback?: string): string {
+  if (isAxonError(error) || error instanceof Error) {
+    return error.message;
+  }
+  if (typeof error === "string") {
+    return error;
+  }
+  return fallback ?? String(error);
+}
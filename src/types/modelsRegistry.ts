@@ -21,6 +21,49 @@ export interface ModelDefaults {
   defaultMaxTokens: number | null;
   costInput: number;
   costOutput: number;
+  /** 是否为用户自定义模型 */
+  custom: boolean;
+}
+
+/** 用户自定义模型（models.dev 未收录的模型，如自托管 vLLM） */
+export interface UserModelEntry {
+  providerId: string;
+  /** 新 provider 的显示名称 */
+  providerName?: string | null;
+  /** 新 provider 的 API 端点 */
+  baseUrl?: string | null;
+  /** 模型 ID（不含 provider 前缀） */
+  modelId: string;
+  name: string;
+  family?: string | null;
+  contextWindow: number;
+  maxOutputTokens: number;
+  costInput: number;
+  costOutput: number;
+  supportsReasoning: boolean;
+  supportsToolCall: boolean;
+  supportsStructuredOutput: boolean;
+  supportsAttachment: boolean;
+  supportsTemperature: boolean;
+  defaultTemperature?: number | null;
+  defaultTopP?: number | null;
+  defaultMaxTokens?: number | null;
+  createdAt?: string;
+  updatedAt?: string;
+}
+
+export interface ModelsRegistryImportResult {
+  providerCount: number;
+  modelCount: number;
+}
+
+/** models-registry:updated 事件负载 */
+export interface RegistryUpdatedEvent {
+  hash: string;
+  previousHash: string | null;
+  providerCount: number;
+  modelCount: number;
+  source: "remote" | "import";
 }
 
 export interface ModelsRegistryCacheInfo {
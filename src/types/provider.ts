@@ -164,3 +164,69 @@ export interface ProviderCard {
 }
 
 
+
+/** 连接测试状态 */
+export type ConnectionTestStatus =
+  | "ok"
+  | "invalid_key"
+  | "forbidden"
+  | "quota_exceeded"
+  | "rate_limited"
+  | "endpoint_not_found"
+  | "server_error"
+  | "network_error"
+  | "timeout"
+  | "not_configured"
+  | "unknown";
+
+/** 连接测试结果 */
+export interface ConnectionTestResult {
+  success: boolean;
+  status: ConnectionTestStatus;
+  /** HTTP 状态码（未收到响应时为空） */
+  httpStatus: number | null;
+  /** 面向用户的说明 */
+  message: string;
+  /** 实际请求的端点 */
+  endpoint: string | null;
+  latencyMs: number;
+}
+
+/** OAuth 客户端配置（授权码 + PKCE 流程） */
+export interface OAuthClientConfig {
+  authUrl: string;
+  tokenUrl: string;
+  clientId: string;
+  clientSecret?: string;
+  scopes?: string[];
+  extraParams?: Record<string, string>;
+  /** 固定的回调端口（默认随机端口） */
+  redirectPort?: number;
+}
+
+/** 已启动的 OAuth 会话 */
+export interface OAuthSession {
+  sessionId: string;
+  providerId: string;
+  /** 授权页地址（浏览器未能自动打开时可展示给用户） */
+  authUrl: string;
+  redirectUri: string;
+}
+
+/** OAuth 流程阶段 */
+export type OAuthStage =
+  | "waiting_for_callback"
+  | "exchanging_code"
+  | "completed"
+  | "refreshed"
+  | "failed"
+  | "cancelled";
+
+/** oauth:progress 事件负载 */
+export interface OAuthProgress {
+  /** 自动刷新时为空 */
+  sessionId: string | null;
+  providerId: string;
+  stage: OAuthStage;
+  message: string | null;
+}
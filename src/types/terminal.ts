@@ -164,6 +164,43 @@ export interface QuickCommand {
   cwd?: string;
 }
 
+// Shell 启动目录策略
+export type ShellStartupDirectory =
+  | { type: "project" }
+  | { type: "home" }
+  | { type: "custom"; path: string };
+
+// Shell 配置（由后端持久化在应用设置中）
+export interface ShellProfile {
+  id: string;
+  name: string;
+  executable: string;
+  args: string[];
+  env: Record<string, string>;
+  startupDirectory: ShellStartupDirectory;
+  builtin: boolean;
+}
+
+// 解析后的 Shell 启动参数（可直接传给 PTY 创建接口）
+export interface ResolvedShell {
+  profileId: string;
+  command: string;
+  args: string[];
+  env: Record<string, string>;
+  cwd: string;
+}
+
+// 终端会话快照（用于跨重启恢复）
+export interface TerminalSessionSnapshot {
+  title: string;
+  profileId?: string | null;
+  cwd: string;
+  scrollback: string;
+  rows?: number | null;
+  cols?: number | null;
+  savedAt?: number;
+}
+
 // 检测是否为 Windows 平台
 function isWindows(): boolean {
   return navigator.platform.toLowerCase().includes("win");
@@ -0,0 +1,92 @@
+/**
+ * 用量统计类型定义
+ *
+ * 与 Rust 端 usage 模块保持一致
+ */
+
+// 用量累计值
+export interface UsageTotals {
+  /** 请求次数（已完成的助手消息数） */
+  requests: number;
+  inputTokens: number;
+  outputTokens: number;
+  reasoningTokens: number;
+  cacheReadTokens: number;
+  cacheWriteTokens: number;
+  /** 费用（美元） */
+  cost: number;
+}
+
+// 查询范围（自定义范围的日期格式为 YYYY-MM-DD，包含两端）
+export type UsageRange =
+  | { type: "today" }
+  | { type: "last7Days" }
+  | { type: "last30Days" }
+  | { type: "all" }
+  | { type: "custom"; start: string; end: string };
+
+export interface ModelUsage {
+  modelId: string;
+  totals: UsageTotals;
+}
+
+export interface ProviderUsage {
+  providerId: string;
+  totals: UsageTotals;
+  models: ModelUsage[];
+}
+
+export interface DailyUsage {
+  date: string;
+  totals: UsageTotals;
+}
+
+// 用量汇总
+export interface UsageSummary {
+  start: string | null;
+  end: string | null;
+  totals: UsageTotals;
+  providers: ProviderUsage[];
+  daily: DailyUsage[];
+}
+
+// 单项指标的统计
+export interface MetricStats {
+  /** 有该指标的样本数 */
+  count: number;
+  mean: number;
+  p50: number;
+  p90: number;
+}
+
+// 性能统计（没有样本的指标为 null）
+export interface PerformanceStats {
+  requests: number;
+  /** 排队耗时：用户消息创建 → 助手消息创建（毫秒） */
+  queueMs: MetricStats | null;
+  /** 首 token 延迟（毫秒） */
+  firstTokenMs: MetricStats | null;
+  /** 输出速度（token / 秒） */
+  tokensPerSecond: MetricStats | null;
+}
+
+export interface ProviderPerformance {
+  providerId: string;
+  stats: PerformanceStats;
+}
+
+export interface DailyPerformance {
+  date: string;
+  stats: PerformanceStats;
+}
+
+// 模型性能汇总
+export interface ModelPerformance {
+  /** 查询的模型（"provider/model" 或不带服务商的模型 ID） */
+  modelId: string;
+  start: string | null;
+  end: string | null;
+  stats: PerformanceStats;
+  providers: ProviderPerformance[];
+  daily: DailyPerformance[];
+}
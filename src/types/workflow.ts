@@ -187,6 +187,9 @@ export interface WorkflowDefinition {
   
   /** 版本号 */
   version: number;
+
+  /** 创建时使用的模板 ID */
+  templateId?: string;
 }
 
 // ============================================================================
@@ -202,6 +205,111 @@ export interface WorkflowSummary {
   status: WorkflowStatus;
   subagentCount: number;
   updatedAt: number;
+  /** 来源，团队配置仓库中的定义只读 */
+  scope?: "local" | "team";
+}
+
+// ============================================================================
+// 工作流模板
+// ============================================================================
+
+/** 模板参数类型（决定使用的选择器） */
+export type WorkflowTemplateParamKind = "model" | "agent" | "text";
+
+export interface WorkflowTemplateParam {
+  name: string;
+  description?: string;
+  kind: WorkflowTemplateParamKind;
+  default?: string;
+  /** 为 true 且没有默认值时必须提供 */
+  required: boolean;
+}
+
+/** 内置工作流模板 */
+export interface WorkflowTemplate {
+  id: string;
+  name: string;
+  description: string;
+  icon?: string;
+  color?: string;
+  params: WorkflowTemplateParam[];
+}
+
+// ============================================================================
+// 执行计划（试运行）
+// ============================================================================
+
+export type PlanStepKind = "receive" | "delegate" | "synthesize";
+
+/** certain: 必然执行；possible: 取决于主 Agent 的判断 */
+export type PlanCertainty = "certain" | "possible";
+
+export interface PlanStep {
+  /** 画布节点 ID（主 Agent 为 primary） */
+  nodeId: string;
+  kind: PlanStepKind;
+  certainty: PlanCertainty;
+  /** 执行原因（命中的触发器、规则等） */
+  reason: string;
+  agentId: string | null;
+  agentName: string;
+  /** 为空时使用 OpenCode 默认模型 */
+  modelId: string | null;
+  toolMode: string;
+  tools: string[];
+  runInBackground: boolean;
+  inputTokens: number;
+  outputTokens: number;
+  /** 估算费用（美元），模型没有价格信息时为空 */
+  cost: number | null;
+}
+
+export interface ExecutionPlan {
+  steps: PlanStep[];
+  /** 不会执行的子 Agent（节点 ID） */
+  skipped: string[];
+  inputTokens: number;
+  outputTokens: number;
+  cost: number;
+  /** 有步骤缺少价格信息，cost 偏低 */
+  costIncomplete: boolean;
+  warnings: string[];
+}
+
+// ============================================================================
+// 工作流包（导入导出）
+// ============================================================================
+
+export interface WorkflowBundleExport {
+  path: string;
+  size: number;
+  agents: string[];
+  tools: string[];
+  /** 引用了但本地找不到定义的 Agent，未打包 */
+  missingAgents: string[];
+}
+
+/** 导入冲突：本地已有同 ID 但内容不同的定义 */
+export interface WorkflowBundleConflict {
+  kind: "agent" | "tool";
+  id: string;
+  name: string;
+}
+
+/** 冲突处理方式：覆盖本地、保留本地、以新 ID 导入 */
+export type BundleConflictResolution = "replace" | "keep" | "rename";
+
+export interface BundleConflictResolutions {
+  agents?: Record<string, BundleConflictResolution>;
+  tools?: Record<string, BundleConflictResolution>;
+}
+
+export interface WorkflowBundleImport {
+  /** 需要用户选择处理方式的冲突，非空时没有写入任何内容 */
+  conflicts: WorkflowBundleConflict[];
+  workflow: WorkflowSummary | null;
+  agents: string[];
+  tools: string[];
 }
 
 // ============================================================================